@@ -0,0 +1,294 @@
+// Command-line options for the `aruu` binary
+//
+// Hand-rolled to keep dependencies down; names are matched case-insensitively and
+// ignore `-`/`_`, so `--shader parametric-wave` and `--safety UltraSafe` both work.
+
+use anyhow::{anyhow, bail, Result};
+use std::path::PathBuf;
+
+use crate::control::SafetyLevel;
+use crate::rendering::{parse_backend, QualityLevel, ShaderType};
+
+pub const USAGE: &str = "\
+Usage: aruu [OPTIONS] [AUDIO_FILE]
+
+Options:
+  --file <path>        Audio file to play (same as the positional AUDIO_FILE)
+  --replay <path>      Replay a recorded session (record one with F9)
+  --input-device <dev> Audio input to listen to without a file: a number from --list-devices
+                       or part of the device name (default: the system default input)
+  --list-devices       List audio input devices and exit
+  --shader <name>      Starting shader; turns off automatic shader selection
+                       (classic, parametric-wave, plasma, kaleidoscope, tunnel, particle,
+                        fractal, spectralizer, oscilloscope, spectrogram, mixed)
+  --safety <level>     Epilepsy safety level (ultra-safe, safe, moderate, standard, disabled)
+  --quality <level>    Fixed render quality (potato, low, medium, high, ultra) or auto
+  --fps <n>            Frame rate cap and adaptive quality target
+  --fullscreen         Start fullscreen
+  --title <text>       Window title prefix, ahead of the live shader/BPM/track info
+  --backend <name>     Only try this GPU backend (vulkan, metal, dx12, gl); also ARUU_BACKEND.
+                       By default they're tried in that order until one works
+  --benchmark          Render each shader offscreen (only --shader if given) and print frame times
+  --benchmark-frames <n>  Frames measured per shader (default 300)
+  --json               Print benchmark results as JSON instead of a table
+  --export <path>      Render AUDIO_FILE offscreen at --fps (default 30) and write raw RGBA
+                       frames to a file or named pipe (- for stdout), for ffmpeg to encode
+  --export-size <WxH>  Export resolution (default 1280x720)
+  --validate-shaders   Compile every shader on a headless device, report errors and exit
+  --resume             Restore the shader, weights, palette, safety, quality and volume
+                       from the last checkpoint
+  --checkpoint <secs>  Save that state to aruu-state.toml every <secs> seconds and on exit
+                       (off by default; 0 keeps it off)
+  --no-warning         Skip the photosensitivity warning screen
+  -h, --help           Show this message
+
+Without a file, the visualizer listens to the default audio input (or --input-device).";
+
+/// Options parsed from the command line; anything not given keeps the builder default
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LaunchOptions {
+    pub file: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub input_device: Option<String>, // Index or name substring, see `match_input_device`
+    pub list_devices: bool,
+    pub shader: Option<ShaderType>,
+    pub safety: Option<SafetyLevel>,
+    pub quality: Option<QualityLevel>, // None is adaptive quality
+    pub fps: Option<u32>,
+    pub fullscreen: bool,
+    pub skip_warning: bool,
+    pub title: Option<String>,
+    pub backend: Option<wgpu::Backends>, // None tries each backend in priority order
+    pub benchmark: bool,
+    pub benchmark_frames: Option<u32>,
+    pub json: bool,
+    pub export: Option<PathBuf>, // "-" is stdout
+    pub export_size: Option<(u32, u32)>,
+    pub validate_shaders: bool,
+    pub resume: bool,
+    pub checkpoint_secs: Option<u64>, // 0 turns checkpoints off
+    pub help: bool,
+}
+
+impl LaunchOptions {
+    /// Parse arguments after the program name
+    pub fn parse<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut options = Self::default();
+        let mut args = args.into_iter().map(Into::into);
+
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| args.next().ok_or_else(|| anyhow!("{} needs a value", flag));
+            match arg.as_str() {
+                "--file" => options.set_file(value("--file")?)?,
+                "--replay" => options.replay = Some(PathBuf::from(value("--replay")?)),
+                "--input-device" => options.input_device = Some(value("--input-device")?),
+                "--list-devices" => options.list_devices = true,
+                "--shader" => options.shader = Some(parse_shader(&value("--shader")?)?),
+                "--safety" => options.safety = Some(parse_safety(&value("--safety")?)?),
+                "--quality" => options.quality = parse_quality(&value("--quality")?)?,
+                "--fps" => options.fps = Some(parse_fps(&value("--fps")?)?),
+                "--fullscreen" => options.fullscreen = true,
+                "--title" => options.title = Some(value("--title")?),
+                "--backend" => options.backend = Some(parse_backend(&value("--backend")?)?),
+                "--no-warning" => options.skip_warning = true,
+                "--benchmark" => options.benchmark = true,
+                "--benchmark-frames" => options.benchmark_frames = Some(parse_frames(&value("--benchmark-frames")?)?),
+                "--json" => options.json = true,
+                "--export" => options.export = Some(PathBuf::from(value("--export")?)),
+                "--export-size" => options.export_size = Some(parse_size(&value("--export-size")?)?),
+                "--validate-shaders" => options.validate_shaders = true,
+                "--resume" => options.resume = true,
+                "--checkpoint" => options.checkpoint_secs = Some(parse_seconds(&value("--checkpoint")?)?),
+                "-h" | "--help" => options.help = true,
+                flag if flag.starts_with('-') => bail!("Unknown option '{}'", flag),
+                _ => options.set_file(arg)?, // Positional file path, as before flags existed
+            }
+        }
+
+        if options.file.is_some() && options.replay.is_some() {
+            bail!("--replay can't be combined with an audio file");
+        }
+        if options.benchmark && (options.file.is_some() || options.replay.is_some()) {
+            bail!("--benchmark uses synthetic audio and can't take a file or replay");
+        }
+        if options.export.is_some() && (options.file.is_none() || options.benchmark) {
+            bail!("--export renders an audio file and needs one (not --replay or --benchmark)");
+        }
+        Ok(options)
+    }
+
+    fn set_file(&mut self, path: String) -> Result<()> {
+        if self.file.is_some() {
+            bail!("Only one audio file can be given (extra '{}')", path);
+        }
+        self.file = Some(PathBuf::from(path));
+        Ok(())
+    }
+}
+
+/// Lowercase with `-`, `_` and spaces removed
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn parse_shader(name: &str) -> Result<ShaderType> {
+    let wanted = normalize(name);
+    ShaderType::all()
+        .iter()
+        .copied()
+        .find(|shader| normalize(shader.name()) == wanted)
+        .ok_or_else(|| anyhow!("Unknown shader '{}'", name))
+}
+
+fn parse_safety(name: &str) -> Result<SafetyLevel> {
+    match normalize(name).as_str() {
+        "ultrasafe" => Ok(SafetyLevel::UltraSafe),
+        "safe" => Ok(SafetyLevel::Safe),
+        "moderate" => Ok(SafetyLevel::Moderate),
+        "standard" => Ok(SafetyLevel::Standard),
+        "disabled" => Ok(SafetyLevel::Disabled),
+        _ => Err(anyhow!("Unknown safety level '{}'", name)),
+    }
+}
+
+fn parse_quality(name: &str) -> Result<Option<QualityLevel>> {
+    match normalize(name).as_str() {
+        "auto" => Ok(None),
+        "potato" => Ok(Some(QualityLevel::Potato)),
+        "low" => Ok(Some(QualityLevel::Low)),
+        "medium" => Ok(Some(QualityLevel::Medium)),
+        "high" => Ok(Some(QualityLevel::High)),
+        "ultra" => Ok(Some(QualityLevel::Ultra)),
+        _ => Err(anyhow!("Unknown quality '{}'", name)),
+    }
+}
+
+fn parse_fps(value: &str) -> Result<u32> {
+    match value.parse::<u32>() {
+        Ok(fps) if fps > 0 => Ok(fps),
+        _ => Err(anyhow!("Invalid frame rate '{}'", value)),
+    }
+}
+
+fn parse_frames(value: &str) -> Result<u32> {
+    match value.parse::<u32>() {
+        Ok(frames) if frames > 0 => Ok(frames),
+        _ => Err(anyhow!("Invalid frame count '{}'", value)),
+    }
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32)> {
+    let parsed = value
+        .split_once(['x', 'X'])
+        .and_then(|(width, height)| Some((width.trim().parse::<u32>().ok()?, height.trim().parse::<u32>().ok()?)));
+    match parsed {
+        Some((width, height)) if width > 0 && height > 0 => Ok((width, height)),
+        _ => Err(anyhow!("Invalid size '{}', expected WIDTHxHEIGHT", value)),
+    }
+}
+
+fn parse_seconds(value: &str) -> Result<u64> {
+    value.parse::<u64>().map_err(|_| anyhow!("Invalid number of seconds '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shader_and_safety_flags() {
+        let options = LaunchOptions::parse(["--shader", "plasma", "--safety", "moderate"]).unwrap();
+        assert_eq!(options.shader, Some(ShaderType::Plasma));
+        assert_eq!(options.safety, Some(SafetyLevel::Moderate));
+        assert_eq!(options.file, None);
+
+        let options = LaunchOptions::parse(["--shader", "Parametric-Wave", "--safety", "ultra_safe"]).unwrap();
+        assert_eq!(options.shader, Some(ShaderType::ParametricWave));
+        assert_eq!(options.safety, Some(SafetyLevel::UltraSafe));
+    }
+
+    #[test]
+    fn test_invalid_values_error() {
+        assert!(LaunchOptions::parse(["--shader", "lava-lamp"]).is_err());
+        assert!(LaunchOptions::parse(["--safety", "reckless"]).is_err());
+        assert!(LaunchOptions::parse(["--fps", "0"]).is_err());
+        assert!(LaunchOptions::parse(["--shader"]).is_err()); // Missing value
+        assert!(LaunchOptions::parse(["--bogus"]).is_err());
+        assert!(LaunchOptions::parse(["--backend", "glide"]).is_err());
+    }
+
+    #[test]
+    fn test_positional_file_still_works() {
+        let options = LaunchOptions::parse(["song.wav", "--fullscreen", "--no-warning", "--fps", "144"]).unwrap();
+        assert_eq!(options.file, Some(PathBuf::from("song.wav")));
+        assert!(options.fullscreen && options.skip_warning);
+        assert_eq!(options.fps, Some(144));
+        assert_eq!(LaunchOptions::parse(["--title", "Live Set"]).unwrap().title.as_deref(), Some("Live Set"));
+        assert_eq!(LaunchOptions::parse(["--backend", "GL"]).unwrap().backend, Some(wgpu::Backends::GL));
+
+        assert_eq!(LaunchOptions::parse(["--file", "a.wav"]).unwrap().file, Some(PathBuf::from("a.wav")));
+        assert!(LaunchOptions::parse(["a.wav", "b.wav"]).is_err());
+        assert_eq!(LaunchOptions::parse(Vec::<String>::new()).unwrap(), LaunchOptions::default());
+    }
+
+    #[test]
+    fn test_quality_levels() {
+        assert_eq!(LaunchOptions::parse(["--quality", "low"]).unwrap().quality, Some(QualityLevel::Low));
+        assert_eq!(LaunchOptions::parse(["--quality", "auto"]).unwrap().quality, None);
+    }
+
+    #[test]
+    fn test_benchmark_flags() {
+        let options = LaunchOptions::parse(["--benchmark", "--benchmark-frames", "60", "--json", "--shader", "tunnel"]).unwrap();
+        assert!(options.benchmark && options.json);
+        assert_eq!(options.benchmark_frames, Some(60));
+        assert_eq!(options.shader, Some(ShaderType::Tunnel));
+
+        assert!(LaunchOptions::parse(["--benchmark-frames", "0"]).is_err());
+        assert!(LaunchOptions::parse(["--benchmark", "song.wav"]).is_err());
+    }
+
+    #[test]
+    fn test_input_device_flags() {
+        let options = LaunchOptions::parse(["--input-device", "Focusrite"]).unwrap();
+        assert_eq!(options.input_device.as_deref(), Some("Focusrite"));
+        assert_eq!(LaunchOptions::parse(["--input-device", "2"]).unwrap().input_device.as_deref(), Some("2"));
+        assert!(LaunchOptions::parse(["--list-devices"]).unwrap().list_devices);
+        assert!(LaunchOptions::parse(["--input-device"]).is_err());
+    }
+
+    #[test]
+    fn test_export_flags() {
+        let options = LaunchOptions::parse(["song.wav", "--export", "-", "--export-size", "1920x1080", "--fps", "24"]).unwrap();
+        assert_eq!(options.export, Some(PathBuf::from("-")));
+        assert_eq!(options.export_size, Some((1920, 1080)));
+        assert_eq!(options.fps, Some(24));
+
+        assert!(LaunchOptions::parse(["--export", "frames.rgba"]).is_err()); // No audio file
+        assert!(LaunchOptions::parse(["song.wav", "--export-size", "1920"]).is_err());
+        assert!(LaunchOptions::parse(["song.wav", "--export-size", "0x720"]).is_err());
+    }
+
+    #[test]
+    fn test_validate_shaders_flag() {
+        assert!(LaunchOptions::parse(["--validate-shaders"]).unwrap().validate_shaders);
+        assert!(!LaunchOptions::parse(Vec::<String>::new()).unwrap().validate_shaders);
+    }
+
+    #[test]
+    fn test_resume_and_checkpoint_flags() {
+        let options = LaunchOptions::parse(["--resume", "--checkpoint", "0"]).unwrap();
+        assert!(options.resume);
+        assert_eq!(options.checkpoint_secs, Some(0));
+
+        assert_eq!(LaunchOptions::parse(["--checkpoint", "45"]).unwrap().checkpoint_secs, Some(45));
+        assert!(LaunchOptions::parse(["--checkpoint", "soon"]).is_err());
+    }
+}
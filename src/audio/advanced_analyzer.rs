@@ -1,5 +1,60 @@
-use super::AudioFeatures;
+use super::{chroma_from_bins, detect_key, AudioFeatures, Band, EnvelopeFollower, HpssSeparator, MusicalKey, Weighting, DEFAULT_ROLLOFF_PERCENTILE};
 use std::collections::VecDeque;
+use std::time::Duration;
+
+const DEFAULT_FRAME_RATE: f32 = 60.0;
+const DEFAULT_DYNAMIC_RANGE_WINDOW: Duration = Duration::from_millis(1667); // ~100 frames at 60fps
+const CHROMA_SMOOTHING: f32 = 0.9; // Keys change over bars, not frames
+
+/// Signal level below which a frame counts as silent
+pub const DEFAULT_SILENCE_FLOOR_DB: f32 = -50.0;
+
+/// Continuous silence needed before `is_silent` reports true
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time for automatic gain control to catch up with a louder passage
+pub const DEFAULT_AGC_ATTACK: Duration = Duration::from_millis(50);
+
+/// Time for automatic gain control to recover after the music gets quieter
+pub const DEFAULT_AGC_RELEASE: Duration = Duration::from_secs(3);
+
+/// Most automatic gain control will boost a quiet spectrum (40 dB), so noise isn't blown up
+pub const MAX_AGC_GAIN: f32 = 100.0;
+
+/// Default band envelope attack and release: zero, so band energies follow each frame exactly
+pub const DEFAULT_BAND_ENVELOPE: Duration = Duration::ZERO;
+
+/// Running spectral peak that band energies are normalized by
+#[derive(Debug, Clone, PartialEq)]
+struct AutoGain {
+    attack: Duration,
+    release: Duration,
+    envelope: f32, // Smoothed peak bin magnitude; 0.0 until the first frame
+}
+
+impl AutoGain {
+    fn new(attack: Duration, release: Duration) -> Self {
+        Self { attack, release, envelope: 0.0 }
+    }
+
+    /// Follow this frame's peak and return the gain that brings the running peak to 1.0
+    fn update(&mut self, bins: &[f32], frame_rate: f32) -> f32 {
+        let peak = bins.iter().fold(0.0f32, |acc, &x| acc.max(x.abs()));
+        if self.envelope <= 0.0 {
+            self.envelope = peak; // Start at the first level heard instead of ramping up from nothing
+        } else {
+            let time = if peak > self.envelope { self.attack } else { self.release };
+            let frames = time.as_secs_f32() * frame_rate;
+            let coefficient = if frames > 0.0 { (-1.0 / frames).exp() } else { 0.0 };
+            self.envelope = peak + (self.envelope - peak) * coefficient;
+        }
+        (1.0 / self.envelope.max(1.0 / MAX_AGC_GAIN)).min(MAX_AGC_GAIN)
+    }
+
+    fn reset(&mut self) {
+        self.envelope = 0.0;
+    }
+}
 
 /// Advanced audio analyzer that maintains state between frames for temporal analysis
 pub struct AdvancedAudioAnalyzer {
@@ -8,6 +63,19 @@ pub struct AdvancedAudioAnalyzer {
     sample_rate: f32,
     frame_count: u64,
     history_size: usize,
+    frame_rate: f32,                    // Analysis frames per second, used to size history windows
+    dynamic_range_window: Duration,
+    rolloff_percentile: f32, // Energy fraction used for spectral rolloff
+    chroma: [f32; 12],       // Smoothed pitch-class profile for key detection
+    loudness_weighting: Weighting,
+    weighting_curve: Vec<f32>, // Per-bin gains for `loudness_weighting`, rebuilt when the bin count changes
+    silence_floor_db: f32,
+    idle_timeout: Duration,
+    silent_frames: u64, // Consecutive frames below `silence_floor_db`
+    hpss: Option<HpssSeparator>, // Harmonic/percussive split, off by default (two medians per bin)
+    agc_enabled: bool,
+    auto_gain: AutoGain,
+    band_envelopes: [EnvelopeFollower; 5], // Indexed by `Band::index`
 }
 
 impl AdvancedAudioAnalyzer {
@@ -18,7 +86,152 @@ impl AdvancedAudioAnalyzer {
             sample_rate,
             frame_count: 0,
             history_size: 100,
+            frame_rate: DEFAULT_FRAME_RATE,
+            dynamic_range_window: DEFAULT_DYNAMIC_RANGE_WINDOW,
+            rolloff_percentile: DEFAULT_ROLLOFF_PERCENTILE,
+            chroma: [0.0; 12],
+            loudness_weighting: Weighting::None,
+            weighting_curve: Vec::new(),
+            silence_floor_db: DEFAULT_SILENCE_FLOOR_DB,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            silent_frames: 0,
+            hpss: None,
+            agc_enabled: false,
+            auto_gain: AutoGain::new(DEFAULT_AGC_ATTACK, DEFAULT_AGC_RELEASE),
+            band_envelopes: std::array::from_fn(|_| EnvelopeFollower::new(DEFAULT_BAND_ENVELOPE, DEFAULT_BAND_ENVELOPE)),
+        }
+    }
+
+    /// Set how much RMS history feeds the dynamic range measure
+    pub fn set_dynamic_range_window(&mut self, window: Duration) {
+        self.dynamic_range_window = window;
+        self.resize_history();
+    }
+
+    pub fn dynamic_range_window(&self) -> Duration {
+        self.dynamic_range_window
+    }
+
+    /// Set the rate `analyze_with_context` is called at, so windows keep their duration
+    pub fn set_frame_rate(&mut self, frame_rate: f32) {
+        self.frame_rate = frame_rate.max(1.0);
+        self.resize_history();
+    }
+
+    pub fn frame_rate(&self) -> f32 {
+        self.frame_rate
+    }
+
+    /// Number of frames currently retained for dynamic range
+    pub fn history_size(&self) -> usize {
+        self.history_size
+    }
+
+    fn resize_history(&mut self) {
+        self.history_size = Self::frames_for(self.dynamic_range_window, self.frame_rate);
+        while self.rms_history.len() > self.history_size {
+            self.rms_history.pop_front();
+        }
+    }
+
+    fn frames_for(window: Duration, frame_rate: f32) -> usize {
+        ((window.as_secs_f32() * frame_rate).round() as usize).max(10) // Dynamic range needs 10 frames
+    }
+
+    /// Set the energy fraction used for spectral rolloff (clamped to 0.5-0.99)
+    pub fn set_rolloff_percentile(&mut self, percentile: f32) {
+        self.rolloff_percentile = percentile.clamp(0.5, 0.99);
+    }
+
+    pub fn rolloff_percentile(&self) -> f32 {
+        self.rolloff_percentile
+    }
+
+    /// Set the frequency weighting used for `overall_volume` and `signal_level_db`
+    pub fn set_loudness_weighting(&mut self, weighting: Weighting) {
+        self.loudness_weighting = weighting;
+        self.weighting_curve.clear();
+    }
+
+    pub fn loudness_weighting(&self) -> Weighting {
+        self.loudness_weighting
+    }
+
+    /// Set the signal level (dB) below which frames count toward silence
+    pub fn set_silence_floor_db(&mut self, floor_db: f32) {
+        self.silence_floor_db = floor_db;
+    }
+
+    pub fn silence_floor_db(&self) -> f32 {
+        self.silence_floor_db
+    }
+
+    /// Set how long the signal must stay below the floor before `is_silent` reports true
+    pub fn set_idle_timeout(&mut self, timeout: Duration) {
+        self.idle_timeout = timeout;
+    }
+
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    /// Whether the signal has stayed below the silence floor for the idle timeout
+    /// (measured in analysis frames at `frame_rate`)
+    pub fn is_silent(&self) -> bool {
+        self.silent_frames >= self.idle_frames()
+    }
+
+    fn idle_frames(&self) -> u64 {
+        ((self.idle_timeout.as_secs_f32() * self.frame_rate).round() as u64).max(1)
+    }
+
+    /// Enable harmonic/percussive separation, filling `harmonic_energy` and `percussive_energy`
+    pub fn set_hpss_enabled(&mut self, enabled: bool) {
+        if enabled != self.hpss.is_some() {
+            self.hpss = enabled.then(HpssSeparator::default);
+        }
+    }
+
+    pub fn hpss_enabled(&self) -> bool {
+        self.hpss.is_some()
+    }
+
+    /// Normalize band energies and `overall_volume` by a running spectral peak, so the spectrum's
+    /// shape rather than its level drives visuals; `signal_level_db` and `peak_level_db` stay raw
+    ///
+    /// `attack` is how fast the gain drops when the music gets louder, `release` how fast it
+    /// rises again when it gets quieter.
+    pub fn set_agc(&mut self, enabled: bool, attack: Duration, release: Duration) {
+        if enabled != self.agc_enabled {
+            self.auto_gain.reset();
         }
+        self.agc_enabled = enabled;
+        self.auto_gain.attack = attack;
+        self.auto_gain.release = release;
+    }
+
+    pub fn agc_enabled(&self) -> bool {
+        self.agc_enabled
+    }
+
+    pub fn agc_attack(&self) -> Duration {
+        self.auto_gain.attack
+    }
+
+    pub fn agc_release(&self) -> Duration {
+        self.auto_gain.release
+    }
+
+    /// Smooth one band's energy with its own rise and fall times, e.g. a punchy attack and slow
+    /// release on bass; zero for both (the default) leaves the band unsmoothed
+    pub fn set_band_envelope(&mut self, band: Band, attack: Duration, release: Duration) {
+        self.band_envelopes[band.index()].set_times(attack, release);
+    }
+
+    /// Attack and release applied to a band
+    pub fn band_envelope(&self, band: Band) -> (Duration, Duration) {
+        let follower = &self.band_envelopes[band.index()];
+        (follower.attack(), follower.release())
     }
 
     /// Analyze frequency bins with full temporal context
@@ -26,7 +239,44 @@ impl AdvancedAudioAnalyzer {
         self.frame_count += 1;
 
         // Start with basic analysis from frequency bins
-        let mut features = AudioFeatures::from_frequency_bins(bins, self.sample_rate);
+        let mut features = AudioFeatures::from_frequency_bins_with_rolloff(bins, self.sample_rate, self.rolloff_percentile);
+
+        // Perceptual loudness, before dynamic range reads the level back
+        if self.loudness_weighting != Weighting::None {
+            if self.weighting_curve.len() != bins.len() {
+                self.weighting_curve = self.loudness_weighting.curve(bins.len(), self.sample_rate);
+            }
+            features.apply_loudness_weighting(bins, &self.weighting_curve);
+        }
+
+        // Normalize what visuals read by the running peak; bands are linear in the bins, so scaling
+        // them matches extracting bands from normalized bins. Levels in dB stay raw for safety.
+        if self.agc_enabled {
+            let gain = self.auto_gain.update(bins, self.frame_rate);
+            for band in [
+                &mut features.sub_bass,
+                &mut features.bass,
+                &mut features.mid,
+                &mut features.treble,
+                &mut features.presence,
+                &mut features.overall_volume,
+            ] {
+                *band *= gain;
+            }
+        }
+
+        // Per-band envelopes shape what visuals see after normalization
+        for band in Band::ALL {
+            let energy = features.band_mut(band);
+            *energy = self.band_envelopes[band.index()].process(*energy, self.frame_rate);
+        }
+
+        // Track sustained silence on the (possibly weighted) level
+        if features.signal_level_db < self.silence_floor_db {
+            self.silent_frames += 1;
+        } else {
+            self.silent_frames = 0;
+        }
 
         // Calculate spectral flux (frame-to-frame spectral difference)
         features.spectral_flux = self.calculate_spectral_flux(bins);
@@ -39,6 +289,19 @@ impl AdvancedAudioAnalyzer {
             features.zero_crossing_rate = Self::calculate_zero_crossing_rate(samples);
         }
 
+        // Detect musical key from the smoothed chroma profile
+        if let Some((key, confidence)) = self.update_chroma(bins) {
+            features.detected_key = Some(key);
+            features.key_confidence = confidence;
+        }
+
+        // Split sustained and transient energy (unweighted, so the two sum to the raw mean magnitude)
+        if let Some(hpss) = &mut self.hpss {
+            let energy = hpss.separate(bins);
+            features.harmonic_energy = energy.harmonic;
+            features.percussive_energy = energy.percussive;
+        }
+
         // Update state for next frame
         self.update_state(bins, &features);
 
@@ -109,6 +372,19 @@ impl AdvancedAudioAnalyzer {
         (rate * 10.0).min(1.0) // Scale to reasonable range
     }
 
+    fn update_chroma(&mut self, bins: &[f32]) -> Option<(MusicalKey, f32)> {
+        let frame_chroma = chroma_from_bins(bins, self.sample_rate);
+        for (smoothed, current) in self.chroma.iter_mut().zip(frame_chroma.iter()) {
+            *smoothed = *smoothed * CHROMA_SMOOTHING + current * (1.0 - CHROMA_SMOOTHING);
+        }
+        detect_key(&self.chroma)
+    }
+
+    /// Smoothed 12-bin pitch-class profile (index 0 = C)
+    pub fn chroma(&self) -> &[f32; 12] {
+        &self.chroma
+    }
+
     fn update_state(&mut self, current_spectrum: &[f32], _features: &AudioFeatures) {
         // Store current spectrum for next frame's flux calculation
         self.previous_spectrum.clear();
@@ -119,7 +395,16 @@ impl AdvancedAudioAnalyzer {
     pub fn reset(&mut self) {
         self.previous_spectrum.clear();
         self.rms_history.clear();
+        self.chroma = [0.0; 12];
         self.frame_count = 0;
+        self.silent_frames = 0;
+        self.auto_gain.reset();
+        for follower in &mut self.band_envelopes {
+            follower.reset();
+        }
+        if let Some(hpss) = &mut self.hpss {
+            hpss.reset();
+        }
     }
 
     pub fn frame_count(&self) -> u64 {
@@ -179,4 +464,264 @@ mod tests {
             assert!(features.dynamic_range <= 1.0);
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_sustained_quiet_becomes_silent() {
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        analyzer.set_frame_rate(60.0);
+        analyzer.set_idle_timeout(Duration::from_secs(1));
+        let quiet = vec![0.001; 512]; // -60 dB
+        let loud = vec![0.5; 512];
+
+        for _ in 0..59 {
+            assert!(analyzer.analyze_with_context(&quiet, None).signal_level_db < DEFAULT_SILENCE_FLOOR_DB);
+        }
+        assert!(!analyzer.is_silent());
+        analyzer.analyze_with_context(&quiet, None);
+        assert!(analyzer.is_silent());
+
+        // A single loud frame restarts the countdown
+        analyzer.analyze_with_context(&loud, None);
+        assert!(!analyzer.is_silent());
+        for _ in 0..30 {
+            analyzer.analyze_with_context(&quiet, None);
+        }
+        assert!(!analyzer.is_silent());
+
+        // Raising the floor counts the loud signal as silence too
+        analyzer.set_silence_floor_db(0.0);
+        for _ in 0..60 {
+            analyzer.analyze_with_context(&loud, None);
+        }
+        assert!(analyzer.is_silent());
+        analyzer.reset();
+        assert!(!analyzer.is_silent());
+    }
+
+    #[test]
+    fn test_higher_rolloff_percentile_lands_higher() {
+        // Decaying spectrum so energy is spread across many bins
+        let bins: Vec<f32> = (0..512).map(|i| 1.0 / (1.0 + i as f32 * 0.05)).collect();
+
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        let rolloff_85 = analyzer.analyze_with_context(&bins, None).spectral_rolloff;
+
+        analyzer.set_rolloff_percentile(0.95);
+        let rolloff_95 = analyzer.analyze_with_context(&bins, None).spectral_rolloff;
+
+        assert!(rolloff_95 > rolloff_85, "95% rolloff {} should exceed 85% rolloff {}", rolloff_95, rolloff_85);
+    }
+
+    #[test]
+    fn test_rolloff_percentile_is_clamped() {
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        analyzer.set_rolloff_percentile(1.5);
+        assert_eq!(analyzer.rolloff_percentile(), 0.99);
+        analyzer.set_rolloff_percentile(0.1);
+        assert_eq!(analyzer.rolloff_percentile(), 0.5);
+    }
+
+    #[test]
+    fn test_spectral_crest_single_tone_vs_flat() {
+        let mut tone = vec![0.0; 512];
+        tone[40] = 1.0;
+        let flat = vec![0.3; 512];
+
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        let tone_crest = analyzer.analyze_with_context(&tone, None).spectral_crest;
+        let flat_crest = analyzer.analyze_with_context(&flat, None).spectral_crest;
+
+        assert!(tone_crest > 100.0, "Single tone crest should be high, got {}", tone_crest);
+        assert!((flat_crest - 1.0).abs() < 1e-4);
+        assert_eq!(AudioFeatures::calculate_spectral_crest(&[0.0; 16]), 0.0);
+    }
+
+    #[test]
+    fn test_longer_dynamic_range_window_retains_more_history() {
+        let mut short = AdvancedAudioAnalyzer::new(44100.0);
+        short.set_dynamic_range_window(Duration::from_secs(1));
+        let mut long = AdvancedAudioAnalyzer::new(44100.0);
+        long.set_dynamic_range_window(Duration::from_secs(4));
+
+        assert_eq!(short.history_size(), 60);
+        assert_eq!(long.history_size(), 240);
+
+        let bins = vec![0.1; 64];
+        for _ in 0..200 {
+            short.analyze_with_context(&bins, None);
+            long.analyze_with_context(&bins, None);
+        }
+
+        assert_eq!(short.rms_history.len(), 60);
+        assert_eq!(long.rms_history.len(), 200);
+    }
+
+    #[test]
+    fn test_window_follows_frame_rate() {
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        assert_eq!(analyzer.history_size(), 100);
+
+        analyzer.set_dynamic_range_window(Duration::from_secs(2));
+        analyzer.set_frame_rate(30.0);
+        assert_eq!(analyzer.history_size(), 60);
+        assert_eq!(analyzer.dynamic_range_window(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_key_detected_from_sustained_triad() {
+        use crate::audio::{KeyMode, PitchClass};
+
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        let bin_width = 44100.0 / 2.0 / 1024.0;
+        let mut bins = vec![0.0; 1024];
+        for freq in [261.63f32, 329.63, 392.0, 523.25, 659.26, 783.99] {
+            bins[(freq / bin_width).round() as usize] = 1.0;
+        }
+
+        let silent = analyzer.analyze_with_context(&vec![0.0; 1024], None);
+        assert_eq!(silent.detected_key, None);
+
+        let mut features = AudioFeatures::new();
+        for _ in 0..30 {
+            features = analyzer.analyze_with_context(&bins, None);
+        }
+        assert_eq!(features.detected_key, Some(MusicalKey::new(PitchClass::C, KeyMode::Major)));
+        assert!(features.key_confidence > 0.6);
+
+        analyzer.reset();
+        assert_eq!(analyzer.chroma(), &[0.0; 12]);
+    }
+
+    #[test]
+    fn test_loudness_weighting_applies_to_volume() {
+        let mut bins = vec![0.0; 512];
+        bins[1] = 1.0; // ~43 Hz
+
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        let raw = analyzer.analyze_with_context(&bins, None);
+
+        analyzer.set_loudness_weighting(Weighting::A);
+        assert_eq!(analyzer.loudness_weighting(), Weighting::A);
+        let weighted = analyzer.analyze_with_context(&bins, None);
+        assert!(weighted.overall_volume < raw.overall_volume * 0.1);
+        assert!(weighted.signal_level_db < raw.signal_level_db);
+    }
+
+    /// Total harmonic and percussive energy over `frames` FFT windows of `samples`
+    fn hpss_totals(samples: &mut impl Iterator<Item = f32>, frames: usize) -> (f32, f32) {
+        use crate::audio::{FftAnalyzer, WindowFunction};
+
+        let mut fft = FftAnalyzer::new(1024, WindowFunction::default());
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        analyzer.set_hpss_enabled(true);
+        let (mut harmonic, mut percussive) = (0.0, 0.0);
+        for _ in 0..frames {
+            let window: Vec<f32> = samples.by_ref().take(1024).collect();
+            let features = analyzer.analyze_with_context(fft.process_audio(&window), None);
+            harmonic += features.harmonic_energy;
+            percussive += features.percussive_energy;
+        }
+        (harmonic, percussive)
+    }
+
+    #[test]
+    fn test_hpss_disabled_by_default() {
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        assert!(!analyzer.hpss_enabled());
+        let features = analyzer.analyze_with_context(&vec![0.5; 512], None);
+        assert_eq!((features.harmonic_energy, features.percussive_energy), (0.0, 0.0));
+
+        analyzer.set_hpss_enabled(true);
+        let features = analyzer.analyze_with_context(&vec![0.5; 512], None);
+        assert!(features.harmonic_energy + features.percussive_energy > 0.0);
+    }
+
+    #[test]
+    fn test_hpss_steady_tone_is_harmonic() {
+        use crate::audio::{SignalGenerator, SignalSpec};
+
+        let (harmonic, percussive) = hpss_totals(&mut SignalGenerator::new(SignalSpec::sine(440.0, 0.8)), 40);
+        assert!(harmonic > percussive * 4.0, "tone harmonic {} vs percussive {}", harmonic, percussive);
+    }
+
+    #[test]
+    fn test_hpss_impulse_train_is_percussive() {
+        // One click in the middle of every eighth window
+        let mut clicks = (0..).map(|i: usize| if i % (8 * 1024) == 512 { 1.0 } else { 0.0 });
+        let (harmonic, percussive) = hpss_totals(&mut clicks, 40);
+        assert!(percussive > harmonic * 4.0, "clicks harmonic {} vs percussive {}", harmonic, percussive);
+    }
+
+    fn band_values(features: &AudioFeatures) -> [f32; 5] {
+        [features.sub_bass, features.bass, features.mid, features.treble, features.presence]
+    }
+
+    #[test]
+    fn test_agc_normalizes_level_but_not_db() {
+        let shape: Vec<f32> = (0..512).map(|i| 0.05 / (1.0 + i as f32 * 0.1)).collect();
+        let loud: Vec<f32> = shape.iter().map(|x| x * 10.0).collect();
+
+        let mut quiet_analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        let mut loud_analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        for analyzer in [&mut quiet_analyzer, &mut loud_analyzer] {
+            analyzer.set_agc(true, DEFAULT_AGC_ATTACK, DEFAULT_AGC_RELEASE);
+        }
+
+        let (mut quiet, mut louder) = (AudioFeatures::new(), AudioFeatures::new());
+        for _ in 0..120 {
+            quiet = quiet_analyzer.analyze_with_context(&shape, None);
+            louder = loud_analyzer.analyze_with_context(&loud, None);
+        }
+
+        for (a, b) in band_values(&quiet).iter().zip(band_values(&louder)) {
+            assert!((a - b).abs() <= 1e-3 * a.max(b), "bands differ: {} vs {}", a, b);
+        }
+        assert!((quiet.overall_volume - louder.overall_volume).abs() < 1e-4);
+
+        // Raw levels still show the 20 dB difference
+        assert!((louder.signal_level_db - quiet.signal_level_db - 20.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_agc_recovers_after_level_drop() {
+        let loud: Vec<f32> = vec![0.5; 256];
+        let quiet: Vec<f32> = vec![0.05; 256];
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        assert!(!analyzer.agc_enabled());
+        analyzer.set_agc(true, DEFAULT_AGC_ATTACK, Duration::from_millis(500));
+
+        let settled = (0..60).map(|_| analyzer.analyze_with_context(&loud, None)).last().unwrap();
+
+        // Right after the drop the gain is still set for the loud passage
+        let dropped = analyzer.analyze_with_context(&quiet, None);
+        assert!(dropped.mid < settled.mid * 0.2);
+
+        // Ten release times later it is back to the same normalized level
+        let recovered = (0..300).map(|_| analyzer.analyze_with_context(&quiet, None)).last().unwrap();
+        assert!((recovered.mid - settled.mid).abs() < 0.01 * settled.mid);
+    }
+
+    #[test]
+    fn test_band_envelope_punchy_attack_slow_release() {
+        let loud = vec![0.5; 256];
+        let quiet = vec![0.0; 256];
+        let mut analyzer = AdvancedAudioAnalyzer::new(44100.0);
+        assert_eq!(analyzer.band_envelope(Band::Bass), (DEFAULT_BAND_ENVELOPE, DEFAULT_BAND_ENVELOPE));
+        analyzer.set_band_envelope(Band::Bass, Duration::from_millis(5), Duration::from_millis(400));
+
+        analyzer.analyze_with_context(&quiet, None);
+        let hit = analyzer.analyze_with_context(&loud, None);
+        let raw = AudioFeatures::from_frequency_bins(&loud, 44100.0);
+        assert!(hit.bass > raw.bass * 0.95, "attack reached {} of {}", hit.bass, raw.bass);
+
+        // Bass falls away gradually while the unsmoothed mid drops at once
+        let after = analyzer.analyze_with_context(&quiet, None);
+        assert!(after.bass > hit.bass * 0.9);
+        assert_eq!(after.mid, 0.0);
+        let later = (0..20).map(|_| analyzer.analyze_with_context(&quiet, None)).last().unwrap();
+        assert!(later.bass < after.bass * 0.5 && later.bass > 0.0);
+
+        analyzer.reset();
+        assert_eq!(analyzer.analyze_with_context(&quiet, None).bass, 0.0);
+    }
+}
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+/// One of the five fixed analysis bands carried by `AudioFeatures`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Band {
+    SubBass,
+    Bass,
+    Mid,
+    Treble,
+    Presence,
+}
+
+impl Band {
+    pub const ALL: [Band; 5] = [
+        Band::SubBass,
+        Band::Bass,
+        Band::Mid,
+        Band::Treble,
+        Band::Presence,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Band::SubBass => "Sub Bass",
+            Band::Bass => "Bass",
+            Band::Mid => "Mid",
+            Band::Treble => "Treble",
+            Band::Presence => "Presence",
+        }
+    }
+
+    /// Position in `ALL`
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// One-pole envelope follower with separate rise (attack) and fall (release) times
+///
+/// Each time is how long the output takes to cover ~63% of a step. A zero time
+/// follows the input exactly in that direction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvelopeFollower {
+    attack: Duration,
+    release: Duration,
+    value: Option<f32>, // None until the first input
+}
+
+impl EnvelopeFollower {
+    pub fn new(attack: Duration, release: Duration) -> Self {
+        Self { attack, release, value: None }
+    }
+
+    pub fn set_times(&mut self, attack: Duration, release: Duration) {
+        self.attack = attack;
+        self.release = release;
+    }
+
+    pub fn attack(&self) -> Duration {
+        self.attack
+    }
+
+    pub fn release(&self) -> Duration {
+        self.release
+    }
+
+    /// Follow one input value, called `frame_rate` times per second
+    pub fn process(&mut self, input: f32, frame_rate: f32) -> f32 {
+        let value = match self.value {
+            None => input, // Start at the first level instead of ramping up from nothing
+            Some(previous) => {
+                let time = if input > previous { self.attack } else { self.release };
+                let frames = time.as_secs_f32() * frame_rate;
+                let coefficient = if frames > 0.0 { (-1.0 / frames).exp() } else { 0.0 };
+                input + (previous - input) * coefficient
+            }
+        };
+        self.value = Some(value);
+        value
+    }
+
+    /// Forget the current level; the next input is passed straight through
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fast_attack_slow_release() {
+        let mut follower = EnvelopeFollower::new(Duration::from_millis(5), Duration::from_millis(500));
+        follower.process(0.0, 60.0);
+
+        // A 5 ms attack is well under one 60 fps frame, so a step up lands almost at once
+        let risen = follower.process(1.0, 60.0);
+        assert!(risen > 0.95, "step up reached only {}", risen);
+
+        // A step down falls gradually and keeps falling
+        let mut previous = follower.process(0.0, 60.0);
+        assert!(previous > 0.9, "step down dropped to {} in one frame", previous);
+        for _ in 0..29 {
+            let value = follower.process(0.0, 60.0);
+            assert!(value < previous);
+            previous = value;
+        }
+        // Half a second is one release time: about 1/e of the step remains
+        assert!((previous - (-1.0f32).exp()).abs() < 0.02, "after 500 ms: {}", previous);
+    }
+
+    #[test]
+    fn test_zero_times_pass_through() {
+        let mut follower = EnvelopeFollower::new(Duration::ZERO, Duration::ZERO);
+        for input in [0.2, 0.9, 0.1, 0.5] {
+            assert_eq!(follower.process(input, 60.0), input);
+        }
+
+        follower.set_times(Duration::from_secs(1), Duration::from_secs(1));
+        assert!(follower.process(0.0, 60.0) > 0.4);
+        follower.reset();
+        assert_eq!(follower.process(0.0, 60.0), 0.0);
+    }
+
+    #[test]
+    fn test_band_indices_match_all() {
+        for (index, band) in Band::ALL.iter().enumerate() {
+            assert_eq!(band.index(), index);
+        }
+    }
+}
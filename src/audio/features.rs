@@ -1,4 +1,135 @@
-#[derive(Debug, Clone)]
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+
+use super::{Band, MusicalKey};
+
+/// Default fraction of spectral energy used for the rolloff frequency
+pub const DEFAULT_ROLLOFF_PERCENTILE: f32 = 0.85;
+
+/// Lower edges (Hz) of the default sub-bass, bass, mid, treble and presence bands
+pub const DEFAULT_BAND_EDGES_HZ: [f32; 5] = [0.0, 60.0, 200.0, 2000.0, 8000.0];
+
+/// Frequency split for band energies: each edge is a band's lower bound in Hz, and each band
+/// runs up to the next edge (the last one up to Nyquist)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequencyBands {
+    edges: Vec<f32>,
+}
+
+impl FrequencyBands {
+    /// Bands from ascending lower edges; at least one edge, all finite and non-negative
+    pub fn new(edges: Vec<f32>) -> Result<Self> {
+        if edges.is_empty() {
+            return Err(anyhow!("Frequency bands need at least one edge"));
+        }
+        if edges.iter().any(|edge| !edge.is_finite() || *edge < 0.0) {
+            return Err(anyhow!("Band edges must be finite and non-negative: {:?}", edges));
+        }
+        if edges.windows(2).any(|pair| pair[1] <= pair[0]) {
+            return Err(anyhow!("Band edges must be strictly ascending: {:?}", edges));
+        }
+        Ok(Self { edges })
+    }
+
+    pub fn edges(&self) -> &[f32] {
+        &self.edges
+    }
+
+    pub fn band_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Check every band starts below Nyquist for this sample rate
+    pub fn validate(&self, sample_rate: f32) -> Result<()> {
+        let nyquist = sample_rate / 2.0;
+        match self.edges.iter().find(|&&edge| edge >= nyquist) {
+            Some(edge) => Err(anyhow!("Band edge {} Hz is not below Nyquist ({} Hz)", edge, nyquist)),
+            None => Ok(()),
+        }
+    }
+
+    /// Bin range covered by each band for a spectrum of `bin_count` bins spanning 0 to Nyquist
+    pub fn bin_ranges(&self, bin_count: usize, sample_rate: f32) -> Vec<Range<usize>> {
+        let nyquist = sample_rate / 2.0;
+        let to_bin = |hz: f32| ((hz / nyquist * bin_count as f32) as usize).min(bin_count);
+        (0..self.edges.len())
+            .map(|band| {
+                let start = to_bin(self.edges[band]);
+                let end = self.edges.get(band + 1).map_or(bin_count, |&edge| to_bin(edge));
+                start..end.max(start)
+            })
+            .collect()
+    }
+
+    /// Mean bin magnitude of each band (0.0 for bands narrower than one bin)
+    pub fn energies(&self, bins: &[f32], sample_rate: f32) -> Vec<f32> {
+        self.bin_ranges(bins.len(), sample_rate)
+            .into_iter()
+            .map(|range| {
+                if range.is_empty() {
+                    0.0
+                } else {
+                    let width = range.len() as f32;
+                    bins[range].iter().sum::<f32>() / width
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for FrequencyBands {
+    fn default() -> Self {
+        Self {
+            edges: DEFAULT_BAND_EDGES_HZ.to_vec(),
+        }
+    }
+}
+
+/// Frequency weighting applied to bins before measuring loudness
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Weighting {
+    #[default]
+    None, // Raw bin magnitudes
+    A,    // IEC 61672 A-weighting: roughly how loud each frequency sounds at moderate levels
+}
+
+impl Weighting {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weighting::None => "None",
+            Weighting::A => "A",
+        }
+    }
+
+    /// Linear gain per bin for a spectrum of `bin_count` bins spanning 0 to Nyquist
+    pub fn curve(&self, bin_count: usize, sample_rate: f32) -> Vec<f32> {
+        match self {
+            Weighting::None => vec![1.0; bin_count],
+            Weighting::A => {
+                let reference = a_weighting_response(1000.0);
+                (0..bin_count)
+                    .map(|i| {
+                        let frequency = i as f32 * sample_rate / (2.0 * bin_count as f32);
+                        a_weighting_response(frequency) / reference
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Unnormalized A-weighting magnitude response
+fn a_weighting_response(frequency: f32) -> f32 {
+    let f2 = frequency * frequency;
+    let numerator = 12194.0f32.powi(2) * f2 * f2;
+    let denominator = (f2 + 20.6f32.powi(2))
+        * ((f2 + 107.7f32.powi(2)) * (f2 + 737.9f32.powi(2))).sqrt()
+        * (f2 + 12194.0f32.powi(2));
+    numerator / denominator
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct AudioFeatures {
     // 5-band frequency analysis
     pub sub_bass: f32,        // 20-60 Hz - deep low-end content
@@ -15,15 +146,26 @@ pub struct AudioFeatures {
 
     // Spectral characteristics
     pub spectral_centroid: f32,   // Brightness measure
-    pub spectral_rolloff: f32,    // Frequency below which 85% of energy is contained
+    pub spectral_rolloff: f32,    // Frequency below which the rolloff percentile of energy is contained
     pub spectral_flux: f32,       // Frame-to-frame spectral difference
+    pub spectral_crest: f32,      // Peak-to-mean magnitude ratio (1.0 = flat, higher = peakier)
 
     // Harmonic and pitch analysis
     pub pitch_confidence: f32,    // Harmonic content confidence (0-1)
     pub zero_crossing_rate: f32,  // Rate of sign changes in time domain
+    pub detected_key: Option<MusicalKey>, // Best-matching key from the chroma profile
+    pub key_confidence: f32,      // Correlation with the detected key's profile (0-1)
 
     // Transient detection
     pub onset_strength: f32,      // Strength of transient events
+
+    // Stereo image (both 0.0 for mono sources)
+    pub stereo_balance: f32,      // -1.0 full left to 1.0 full right
+    pub stereo_width: f32,        // Side relative to mid energy (0.0 mono, 1.0 wide)
+
+    // Source separation (both 0.0 unless HPSS is enabled)
+    pub harmonic_energy: f32,     // Mean magnitude of sustained, tonal content
+    pub percussive_energy: f32,   // Mean magnitude of transient, broadband content
 }
 
 impl AudioFeatures {
@@ -46,70 +188,48 @@ impl AudioFeatures {
             spectral_centroid: 0.0,
             spectral_rolloff: 0.0,
             spectral_flux: 0.0,
+            spectral_crest: 0.0,
 
             // Harmonic and pitch analysis
             pitch_confidence: 0.0,
             zero_crossing_rate: 0.0,
+            detected_key: None,
+            key_confidence: 0.0,
 
             // Transient detection
             onset_strength: 0.0,
+
+            // Stereo image
+            stereo_balance: 0.0,
+            stereo_width: 0.0,
+
+            // Source separation
+            harmonic_energy: 0.0,
+            percussive_energy: 0.0,
         }
     }
 
     pub fn from_frequency_bins(bins: &[f32], sample_rate: f32) -> Self {
-        let total_bins = bins.len();
-        let nyquist = sample_rate / 2.0;
-
-        // 5-band frequency analysis with precise frequency ranges
-        let sub_bass_limit = (60.0 / nyquist * total_bins as f32) as usize;
-        let bass_limit = (200.0 / nyquist * total_bins as f32) as usize;
-        let mid_limit = (2000.0 / nyquist * total_bins as f32) as usize;
-        let treble_limit = (8000.0 / nyquist * total_bins as f32) as usize;
-
-        // Calculate frequency band energies
-        let sub_bass = if sub_bass_limit > 0 {
-            bins[0..sub_bass_limit.min(total_bins)].iter().sum::<f32>() / sub_bass_limit as f32
-        } else {
-            0.0
-        };
-
-        let bass = if bass_limit > sub_bass_limit {
-            bins[sub_bass_limit..bass_limit.min(total_bins)].iter().sum::<f32>()
-                / (bass_limit - sub_bass_limit) as f32
-        } else {
-            0.0
-        };
-
-        let mid = if mid_limit > bass_limit {
-            bins[bass_limit..mid_limit.min(total_bins)].iter().sum::<f32>()
-                / (mid_limit - bass_limit) as f32
-        } else {
-            0.0
-        };
-
-        let treble = if treble_limit > mid_limit {
-            bins[mid_limit..treble_limit.min(total_bins)].iter().sum::<f32>()
-                / (treble_limit - mid_limit) as f32
-        } else {
-            0.0
-        };
+        Self::from_frequency_bins_with_rolloff(bins, sample_rate, DEFAULT_ROLLOFF_PERCENTILE)
+    }
 
-        let presence = if total_bins > treble_limit {
-            bins[treble_limit..total_bins].iter().sum::<f32>()
-                / (total_bins - treble_limit) as f32
-        } else {
-            0.0
-        };
+    /// Analyze with a custom band split, returning the usual features (named bands from the
+    /// default split) and the mean magnitude of each custom band
+    pub fn from_frequency_bins_with_bands(bins: &[f32], sample_rate: f32, bands: &FrequencyBands) -> Result<(Self, Vec<f32>)> {
+        bands.validate(sample_rate)?;
+        let features = Self::from_frequency_bins(bins, sample_rate);
+        Ok((features, bands.energies(bins, sample_rate)))
+    }
 
-        let overall_volume = bins.iter().sum::<f32>() / total_bins as f32;
+    /// Analyze frequency bins using a custom rolloff percentile (0.0-1.0)
+    pub fn from_frequency_bins_with_rolloff(bins: &[f32], sample_rate: f32, rolloff_percentile: f32) -> Self {
+        // 5-band frequency analysis over the default split
+        let [sub_bass, bass, mid, treble, presence]: [f32; 5] = FrequencyBands::default()
+            .energies(bins, sample_rate)
+            .try_into()
+            .unwrap_or([0.0; 5]);
 
-        // Calculate signal levels in dB
-        let rms = (bins.iter().map(|x| x * x).sum::<f32>() / total_bins as f32).sqrt();
-        let signal_level_db = if rms > 0.0 {
-            20.0 * rms.log10()
-        } else {
-            -60.0
-        };
+        let (overall_volume, signal_level_db) = Self::loudness(bins.iter().copied());
 
         let peak = bins.iter().fold(0.0f32, |acc, &x| acc.max(x.abs()));
         let peak_level_db = if peak > 0.0 {
@@ -120,7 +240,8 @@ impl AudioFeatures {
 
         // Advanced spectral analysis
         let spectral_centroid = Self::calculate_spectral_centroid(bins, sample_rate);
-        let spectral_rolloff = Self::calculate_spectral_rolloff(bins, sample_rate);
+        let spectral_rolloff = Self::calculate_spectral_rolloff(bins, sample_rate, rolloff_percentile);
+        let spectral_crest = Self::calculate_spectral_crest(bins);
         let pitch_confidence = Self::calculate_pitch_confidence(bins);
         let onset_strength = Self::calculate_onset_strength(bins);
 
@@ -142,14 +263,73 @@ impl AudioFeatures {
             spectral_centroid,
             spectral_rolloff,
             spectral_flux: 0.0, // Overridden by AdvancedAnalyzer in production (validated by test)
+            spectral_crest,
 
             // Harmonic and pitch analysis
             pitch_confidence,
             zero_crossing_rate: 0.0, // Overridden by AdvancedAnalyzer in production (validated by test)
+            detected_key: None,      // Needs smoothed chroma history, filled by AdvancedAnalyzer
+            key_confidence: 0.0,
 
             // Transient detection
             onset_strength,
+
+            // Stereo image needs separate channels, filled by AudioProcessor
+            stereo_balance: 0.0,
+            stereo_width: 0.0,
+
+            // Needs spectrum history, filled by AdvancedAnalyzer when HPSS is enabled
+            harmonic_energy: 0.0,
+            percussive_energy: 0.0,
+        }
+    }
+
+    /// Energy of one of the five fixed bands
+    pub fn band(&self, band: Band) -> f32 {
+        match band {
+            Band::SubBass => self.sub_bass,
+            Band::Bass => self.bass,
+            Band::Mid => self.mid,
+            Band::Treble => self.treble,
+            Band::Presence => self.presence,
+        }
+    }
+
+    pub fn band_mut(&mut self, band: Band) -> &mut f32 {
+        match band {
+            Band::SubBass => &mut self.sub_bass,
+            Band::Bass => &mut self.bass,
+            Band::Mid => &mut self.mid,
+            Band::Treble => &mut self.treble,
+            Band::Presence => &mut self.presence,
+        }
+    }
+
+    /// Recompute `overall_volume` and `signal_level_db` from bins scaled by a weighting curve
+    /// (see `Weighting::curve`); the curve must match the bin count
+    pub fn apply_loudness_weighting(&mut self, bins: &[f32], curve: &[f32]) {
+        if bins.len() != curve.len() {
+            return;
         }
+        let (overall_volume, signal_level_db) = Self::loudness(bins.iter().zip(curve).map(|(bin, gain)| bin * gain));
+        self.overall_volume = overall_volume;
+        self.signal_level_db = signal_level_db;
+    }
+
+    /// Mean magnitude and RMS level in dB
+    fn loudness(bins: impl ExactSizeIterator<Item = f32>) -> (f32, f32) {
+        let total_bins = bins.len() as f32;
+        let (sum, square_sum) = bins.fold((0.0f32, 0.0f32), |(sum, squares), x| (sum + x, squares + x * x));
+        let overall_volume = sum / total_bins;
+
+        // Calculate signal levels in dB
+        let rms = (square_sum / total_bins).sqrt();
+        let signal_level_db = if rms > 0.0 {
+            20.0 * rms.log10()
+        } else {
+            -60.0
+        };
+        (overall_volume, signal_level_db)
     }
 
     fn calculate_spectral_centroid(bins: &[f32], sample_rate: f32) -> f32 {
@@ -169,9 +349,9 @@ impl AudioFeatures {
         }
     }
 
-    fn calculate_spectral_rolloff(bins: &[f32], sample_rate: f32) -> f32 {
+    pub fn calculate_spectral_rolloff(bins: &[f32], sample_rate: f32, rolloff_percentile: f32) -> f32 {
         let total_energy: f32 = bins.iter().sum();
-        let rolloff_threshold = 0.85 * total_energy;
+        let rolloff_threshold = rolloff_percentile.clamp(0.0, 1.0) * total_energy;
         let mut cumulative_energy = 0.0;
 
         for (i, &magnitude) in bins.iter().enumerate() {
@@ -184,6 +364,22 @@ impl AudioFeatures {
         sample_rate / 2.0
     }
 
+    /// Peak magnitude divided by mean magnitude, independent of overall loudness
+    pub fn calculate_spectral_crest(bins: &[f32]) -> f32 {
+        if bins.is_empty() {
+            return 0.0;
+        }
+
+        let mean = bins.iter().map(|x| x.abs()).sum::<f32>() / bins.len() as f32;
+        let peak = bins.iter().fold(0.0f32, |acc, &x| acc.max(x.abs()));
+
+        if mean > 0.0 {
+            peak / mean
+        } else {
+            0.0
+        }
+    }
+
     fn calculate_pitch_confidence(bins: &[f32]) -> f32 {
         // Calculate pitch confidence based on harmonic structure
         // Higher values indicate more harmonic/tonal content
@@ -249,4 +445,83 @@ impl AudioFeatures {
         // Combine high-frequency ratio and spectral complexity
         ((hf_ratio * 2.0 + normalized_variation) / 3.0).min(1.0)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_three_band_split_accounts_for_all_energy() {
+        let bins: Vec<f32> = (0..512).map(|i| 1.0 + (i % 7) as f32 * 0.25).collect();
+        let bands = FrequencyBands::new(vec![0.0, 500.0, 4000.0]).unwrap();
+
+        let (features, energies) = AudioFeatures::from_frequency_bins_with_bands(&bins, 44100.0, &bands).unwrap();
+        assert_eq!(energies.len(), 3);
+
+        // Ranges tile the spectrum, and mean energy times width sums back to the total
+        let ranges = bands.bin_ranges(bins.len(), 44100.0);
+        assert_eq!(ranges.first().unwrap().start, 0);
+        assert_eq!(ranges.last().unwrap().end, bins.len());
+        assert!(ranges.windows(2).all(|pair| pair[0].end == pair[1].start));
+        let reconstructed: f32 = energies.iter().zip(&ranges).map(|(energy, range)| energy * range.len() as f32).sum();
+        let total: f32 = bins.iter().sum();
+        assert!((reconstructed - total).abs() < total * 1e-5);
+
+        // Named fields still follow the default five-band split
+        let default_energies = FrequencyBands::default().energies(&bins, 44100.0);
+        assert_eq!(features.bass, default_energies[1]);
+        assert_eq!(features.presence, default_energies[4]);
+    }
+
+    #[test]
+    fn test_band_edges_validated() {
+        assert!(FrequencyBands::new(Vec::new()).is_err());
+        assert!(FrequencyBands::new(vec![0.0, 200.0, 100.0]).is_err());
+        assert!(FrequencyBands::new(vec![0.0, 100.0, 100.0]).is_err());
+        assert!(FrequencyBands::new(vec![-10.0, 100.0]).is_err());
+
+        // 30 kHz is above Nyquist at 44.1 kHz
+        let bands = FrequencyBands::new(vec![0.0, 1000.0, 30000.0]).unwrap();
+        assert!(bands.validate(44100.0).is_err());
+        assert!(AudioFeatures::from_frequency_bins_with_bands(&[0.0; 64], 44100.0, &bands).is_err());
+        assert!(bands.validate(96000.0).is_ok());
+    }
+
+    #[test]
+    fn test_a_weighting_favors_midrange_over_bass() {
+        let sample_rate = 44100.0;
+        let bin_count = 512;
+        let bin_for = |hz: f32| (hz / (sample_rate / 2.0) * bin_count as f32).round() as usize;
+        let curve = Weighting::A.curve(bin_count, sample_rate);
+
+        let mut at_1k = vec![0.0; bin_count];
+        at_1k[bin_for(1000.0)] = 1.0;
+        let mut at_50 = vec![0.0; bin_count];
+        at_50[bin_for(50.0)] = 1.0;
+
+        let mut mid = AudioFeatures::from_frequency_bins(&at_1k, sample_rate);
+        let mut low = AudioFeatures::from_frequency_bins(&at_50, sample_rate);
+        assert_eq!(mid.overall_volume, low.overall_volume);
+
+        mid.apply_loudness_weighting(&at_1k, &curve);
+        low.apply_loudness_weighting(&at_50, &curve);
+        assert!(mid.overall_volume > low.overall_volume * 10.0);
+        assert!(mid.signal_level_db > low.signal_level_db + 20.0);
+
+        // Normalized to unity at 1 kHz, silent at DC
+        assert!((curve[bin_for(1000.0)] - 1.0).abs() < 0.05);
+        assert_eq!(curve[0], 0.0);
+    }
+
+    #[test]
+    fn test_no_weighting_preserves_levels() {
+        let bins: Vec<f32> = (0..64).map(|i| (i % 7) as f32 * 0.1).collect();
+        let mut features = AudioFeatures::from_frequency_bins(&bins, 44100.0);
+        let (volume, level) = (features.overall_volume, features.signal_level_db);
+
+        features.apply_loudness_weighting(&bins, &Weighting::None.curve(bins.len(), 44100.0));
+        assert!((features.overall_volume - volume).abs() < 1e-6);
+        assert!((features.signal_level_db - level).abs() < 1e-4);
+        assert_eq!(Weighting::default(), Weighting::None);
+    }
+}
@@ -1,32 +1,177 @@
 use rustfft::{FftPlanner, num_complex::Complex};
 use std::sync::Arc;
 
+/// Taper applied to each analysis block before the transform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WindowFunction {
+    Rectangular,    // No taper: sharpest peak for bin-centered tones, heavy leakage otherwise
+    #[default]
+    Hann,           // General purpose default
+    Hamming,        // Lower first sidelobe than Hann, slower sidelobe falloff
+    Blackman,       // Low leakage, wider main lobe
+    BlackmanHarris, // Lowest leakage; best for sustained tonal material
+}
+
+impl WindowFunction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WindowFunction::Rectangular => "Rectangular",
+            WindowFunction::Hann => "Hann",
+            WindowFunction::Hamming => "Hamming",
+            WindowFunction::Blackman => "Blackman",
+            WindowFunction::BlackmanHarris => "Blackman-Harris",
+        }
+    }
+
+    /// Symmetric window coefficients for a block of `size` samples
+    pub fn coefficients(&self, size: usize) -> Vec<f32> {
+        if size < 2 {
+            return vec![1.0; size];
+        }
+
+        let (a0, a1, a2, a3) = match self {
+            WindowFunction::Rectangular => (1.0, 0.0, 0.0, 0.0),
+            WindowFunction::Hann => (0.5, 0.5, 0.0, 0.0),
+            WindowFunction::Hamming => (0.54, 0.46, 0.0, 0.0),
+            WindowFunction::Blackman => (0.42, 0.5, 0.08, 0.0),
+            WindowFunction::BlackmanHarris => (0.35875, 0.48829, 0.14128, 0.01168),
+        };
+
+        (0..size)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * i as f32 / (size - 1) as f32;
+                a0 - a1 * phase.cos() + a2 * (2.0 * phase).cos() - a3 * (3.0 * phase).cos()
+            })
+            .collect()
+    }
+}
+
+/// Largest spectral smoothing coefficient; higher values would freeze the spectrum
+pub const MAX_SPECTRAL_SMOOTHING: f32 = 0.95;
+
+/// Per-bin exponential moving average over successive spectra
+///
+/// Each output bin is `alpha * previous + (1 - alpha) * current`, so a higher
+/// `alpha` trades responsiveness for steadier bins. An alpha of 0 passes spectra through.
+#[derive(Debug, Clone, Default)]
+pub struct SpectralSmoother {
+    alpha: f32,
+    previous: Vec<f32>,
+}
+
+impl SpectralSmoother {
+    pub fn new(alpha: f32) -> Self {
+        let mut smoother = Self::default();
+        smoother.set_alpha(alpha);
+        smoother
+    }
+
+    /// Set the smoothing coefficient (clamped to 0.0-`MAX_SPECTRAL_SMOOTHING`; 0.0 disables)
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.alpha = if alpha.is_finite() { alpha.clamp(0.0, MAX_SPECTRAL_SMOOTHING) } else { 0.0 };
+        if self.alpha == 0.0 {
+            self.previous.clear();
+        }
+    }
+
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    /// Smooth `bins` in place against the previous spectrum (the first spectrum, or one
+    /// with a different bin count, starts the average)
+    pub fn apply(&mut self, bins: &mut [f32]) {
+        if self.alpha == 0.0 {
+            return;
+        }
+        if self.previous.len() != bins.len() {
+            self.previous.clear();
+            self.previous.extend_from_slice(bins);
+            return;
+        }
+
+        for (bin, previous) in bins.iter_mut().zip(self.previous.iter_mut()) {
+            *previous = self.alpha * *previous + (1.0 - self.alpha) * *bin;
+            *bin = *previous;
+        }
+    }
+
+    /// Forget the running average, e.g. when the source changes
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+}
+
 pub struct FftAnalyzer {
     fft: Arc<dyn rustfft::Fft<f32>>,
     buffer: Vec<Complex<f32>>,
+    window_function: WindowFunction,
     window: Vec<f32>,
+    taper: Vec<f32>, // Window for short (zero-padded) input, rebuilt only when its length changes
     scratch: Vec<Complex<f32>>,
     output_buffer: Vec<f32>,
+    smoother: SpectralSmoother, // Temporal smoothing of the magnitudes (off by default)
 }
 
 impl FftAnalyzer {
-    pub fn new(size: usize) -> Self {
+    pub fn new(size: usize, window_function: WindowFunction) -> Self {
         let mut planner = FftPlanner::new();
         let fft = planner.plan_fft_forward(size);
         let scratch_len = fft.get_inplace_scratch_len();
 
         let buffer = vec![Complex::new(0.0, 0.0); size];
-        let window = Self::hann_window(size);
+        let window = window_function.coefficients(size);
         let scratch = vec![Complex::new(0.0, 0.0); scratch_len];
         let output_buffer = vec![0.0; size / 2];
 
         Self {
             fft,
             buffer,
+            window_function,
             window,
+            taper: Vec::new(),
             scratch,
             output_buffer,
+            smoother: SpectralSmoother::default(),
+        }
+    }
+
+    /// Switch the window function; coefficients are recomputed once here, not per frame
+    pub fn set_window(&mut self, window_function: WindowFunction) {
+        if window_function == self.window_function {
+            return;
+        }
+        self.window_function = window_function;
+        self.window = window_function.coefficients(self.buffer.len());
+        self.taper.clear();
+    }
+
+    pub fn window_function(&self) -> WindowFunction {
+        self.window_function
+    }
+
+    /// Average each bin over time to steady noisy high bands (0.0 disables; see `SpectralSmoother`)
+    ///
+    /// Runs before anything reads the spectrum, so every derived feature sees the smoothed bins.
+    pub fn set_spectral_smoothing(&mut self, alpha: f32) {
+        self.smoother.set_alpha(alpha);
+    }
+
+    pub fn spectral_smoothing(&self) -> f32 {
+        self.smoother.alpha()
+    }
+
+    /// Drop the smoothing history so the next spectrum stands alone
+    pub fn reset_smoothing(&mut self) {
+        self.smoother.reset();
+    }
+
+    fn store_magnitudes(&mut self) {
+        let half = self.buffer.len() / 2;
+        for (output, complex) in self.output_buffer.iter_mut().zip(self.buffer.iter().take(half)) {
+            *output = complex.norm();
         }
+        self.smoother.apply(&mut self.output_buffer);
     }
 
     pub fn process_audio(&mut self, samples: &[f32]) -> &[f32] {
@@ -41,21 +186,86 @@ impl FftAnalyzer {
         }
 
         self.fft.process_with_scratch(&mut self.buffer, &mut self.scratch);
+        self.store_magnitudes();
+
+        &self.output_buffer
+    }
+
+    /// Analyze a buffer that may be shorter than the FFT window
+    ///
+    /// Short input gets its own taper from the current window function and is zero-padded to the window size, with
+    /// gain compensation so band energies stay comparable to a full window.
+    pub fn process_audio_padded(&mut self, samples: &[f32]) -> &[f32] {
+        let size = self.buffer.len();
+
+        if samples.len() >= size {
+            return self.process_audio(samples);
+        }
 
-        for (i, complex) in self.buffer.iter().take(size / 2).enumerate() {
-            self.output_buffer[i] = complex.norm();
+        let available = samples.len();
+        if available < 2 {
+            return &[];
+        }
+
+        if self.taper.len() != available {
+            self.taper = self.window_function.coefficients(available);
+        }
+        let gain = size as f32 / available as f32;
+
+        let tapered = samples.iter().zip(&self.taper).map(|(&sample, &taper)| sample * taper * gain);
+        for (slot, value) in self.buffer.iter_mut().zip(tapered.chain(std::iter::repeat(0.0))) {
+            *slot = Complex::new(value, 0.0);
         }
 
+        self.fft.process_with_scratch(&mut self.buffer, &mut self.scratch);
+        self.store_magnitudes();
+
         &self.output_buffer
     }
 
-    fn hann_window(size: usize) -> Vec<f32> {
-        (0..size)
-            .map(|i| {
-                let phase = 2.0 * std::f32::consts::PI * i as f32 / (size - 1) as f32;
-                0.5 * (1.0 - phase.cos())
-            })
-            .collect()
+    /// Analyze left and right channels separately, e.g. for the split-screen spectralizer
+    ///
+    /// Each channel is windowed like `process_audio_padded`, using its newest window of
+    /// samples. The temporal smoothing follows the mono spectrum, so it isn't applied here.
+    /// An empty `right` (a mono source) gets a copy of the left spectrum.
+    pub fn process_stereo(&self, left: &[f32], right: &[f32]) -> (Vec<f32>, Vec<f32>) {
+        let left_spectrum = self.channel_spectrum(left);
+        let right_spectrum = if right.is_empty() { left_spectrum.clone() } else { self.channel_spectrum(right) };
+        (left_spectrum, right_spectrum)
+    }
+
+    /// Magnitudes of one channel without touching the mono analysis buffers
+    fn channel_spectrum(&self, samples: &[f32]) -> Vec<f32> {
+        let size = self.buffer.len();
+        let newest = &samples[samples.len().saturating_sub(size)..];
+        if newest.len() < 2 {
+            return vec![0.0; size / 2];
+        }
+
+        let short_taper;
+        let (window, gain) = if newest.len() == size {
+            (&self.window[..], 1.0)
+        } else {
+            short_taper = self.window_function.coefficients(newest.len());
+            (&short_taper[..], size as f32 / newest.len() as f32)
+        };
+
+        let mut buffer: Vec<Complex<f32>> = (0..size)
+            .map(|i| Complex::new(newest.get(i).map_or(0.0, |&sample| sample * window[i] * gain), 0.0))
+            .collect();
+        let mut scratch = vec![Complex::new(0.0, 0.0); self.scratch.len()];
+        self.fft.process_with_scratch(&mut buffer, &mut scratch);
+
+        buffer.iter().take(size / 2).map(|complex| complex.norm()).collect()
+    }
+
+    /// Magnitudes from the most recent analysis
+    pub fn spectrum(&self) -> &[f32] {
+        &self.output_buffer
+    }
+
+    pub fn window_size(&self) -> usize {
+        self.buffer.len()
     }
 
     pub fn get_frequency_bin(&self, bin: usize, sample_rate: f32) -> f32 {
@@ -70,7 +280,7 @@ mod tests {
 
     #[test]
     fn test_fft_processing() {
-        let mut analyzer = FftAnalyzer::new(1024);
+        let mut analyzer = FftAnalyzer::new(1024, WindowFunction::Hann);
 
         let sample_rate = 44100.0;
         let frequency = 1000.0;
@@ -96,11 +306,153 @@ mod tests {
         assert_abs_diff_eq!(peak_bin as f32, expected_bin as f32, epsilon = 2.0);
     }
 
+    #[test]
+    fn test_padded_processing_of_short_input() {
+        let mut analyzer = FftAnalyzer::new(1024, WindowFunction::Hann);
+        let samples: Vec<f32> = (0..512)
+            .map(|i| (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 44100.0).sin())
+            .collect();
+
+        assert!(analyzer.process_audio(&samples).is_empty());
+
+        let result = analyzer.process_audio_padded(&samples);
+        assert_eq!(result.len(), 512);
+
+        let peak_bin = result
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|(i, _)| i)
+            .unwrap();
+        let expected_bin = 1000.0 / 44100.0 * 1024.0;
+        assert_abs_diff_eq!(peak_bin as f32, expected_bin, epsilon = 3.0);
+    }
+
+    #[test]
+    fn test_spectral_smoothing_reduces_flicker() {
+        let mut analyzer = FftAnalyzer::new(256, WindowFunction::Hann);
+        let tone = |amplitude: f32| -> Vec<f32> {
+            (0..256).map(|i| amplitude * (2.0 * std::f32::consts::PI * 20.0 * i as f32 / 256.0).sin()).collect()
+        };
+        let variance = |values: &[f32]| {
+            let mean = values.iter().sum::<f32>() / values.len() as f32;
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32
+        };
+
+        // A bin whose magnitude alternates loud/quiet every frame
+        let run = |analyzer: &mut FftAnalyzer| -> Vec<f32> {
+            (0..40).map(|frame| analyzer.process_audio(&tone(if frame % 2 == 0 { 1.0 } else { 0.2 }))[20]).collect()
+        };
+
+        let raw = run(&mut analyzer);
+        analyzer.set_spectral_smoothing(0.8);
+        assert_eq!(analyzer.spectral_smoothing(), 0.8);
+        let smoothed = run(&mut analyzer);
+
+        assert!(variance(&smoothed[10..]) < variance(&raw[10..]) * 0.1,
+                "smoothed variance {} should be well below raw {}", variance(&smoothed[10..]), variance(&raw[10..]));
+
+        // Disabling passes the raw bins straight through again
+        analyzer.set_spectral_smoothing(0.0);
+        assert_abs_diff_eq!(analyzer.process_audio(&tone(1.0))[20], raw[0], epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_smoother_clamps_and_restarts_on_new_length() {
+        let mut smoother = SpectralSmoother::new(2.0);
+        assert_eq!(smoother.alpha(), MAX_SPECTRAL_SMOOTHING);
+        smoother.set_alpha(f32::NAN);
+        assert_eq!(smoother.alpha(), 0.0);
+
+        smoother.set_alpha(0.5);
+        let mut first = [1.0, 1.0];
+        smoother.apply(&mut first);
+        assert_eq!(first, [1.0, 1.0]);
+        let mut second = [0.0, 2.0];
+        smoother.apply(&mut second);
+        assert_eq!(second, [0.5, 1.5]);
+
+        let mut resized = [4.0, 4.0, 4.0];
+        smoother.apply(&mut resized);
+        assert_eq!(resized, [4.0, 4.0, 4.0]);
+    }
+
+    #[test]
+    fn test_stereo_spectra_keep_channels_apart() {
+        let analyzer = FftAnalyzer::new(1024, WindowFunction::Hann);
+        let tone: Vec<f32> = (0..1024)
+            .map(|i| (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 44100.0).sin())
+            .collect();
+        let silence = vec![0.0; 1024];
+
+        // A tone only in the left channel shows up on the left alone
+        let (left, right) = analyzer.process_stereo(&tone, &silence);
+        assert_eq!((left.len(), right.len()), (512, 512));
+        let left_energy: f32 = left.iter().sum();
+        let right_energy: f32 = right.iter().sum();
+        assert!(left_energy > 100.0, "left energy {}", left_energy);
+        assert!(right_energy < 1e-3, "right energy {}", right_energy);
+
+        // Matches the mono path for the same window
+        let mut mono = FftAnalyzer::new(1024, WindowFunction::Hann);
+        assert_abs_diff_eq!(left[23], mono.process_audio(&tone)[23], epsilon = 1e-3);
+
+        // A mono source duplicates its spectrum into both halves
+        let (left, right) = analyzer.process_stereo(&tone, &[]);
+        assert_eq!(left, right);
+    }
+
     #[test]
     fn test_hann_window() {
-        let window = FftAnalyzer::hann_window(8);
+        let window = WindowFunction::Hann.coefficients(8);
         assert_abs_diff_eq!(window[0], 0.0, epsilon = 1e-6);
         assert_abs_diff_eq!(window[7], 0.0, epsilon = 1e-6);
         assert!(window[4] > 0.9);
     }
+
+    #[test]
+    fn test_window_coefficients() {
+        assert!(WindowFunction::Rectangular.coefficients(16).iter().all(|&w| w == 1.0));
+        assert_abs_diff_eq!(WindowFunction::Hamming.coefficients(16)[0], 0.08, epsilon = 1e-6);
+        assert_abs_diff_eq!(WindowFunction::Blackman.coefficients(16)[0], 0.0, epsilon = 1e-6);
+        assert_abs_diff_eq!(WindowFunction::BlackmanHarris.coefficients(16)[0], 0.00006, epsilon = 1e-5);
+
+        // Symmetric with unity peak at the center
+        for window in [WindowFunction::Hann, WindowFunction::Hamming, WindowFunction::Blackman, WindowFunction::BlackmanHarris] {
+            let coefficients = window.coefficients(65);
+            assert_abs_diff_eq!(coefficients[32], 1.0, epsilon = 1e-5);
+            assert_abs_diff_eq!(coefficients[10], coefficients[54], epsilon = 1e-5);
+        }
+        assert_eq!(WindowFunction::default(), WindowFunction::Hann);
+    }
+
+    #[test]
+    fn test_blackman_harris_concentrates_tone_energy() {
+        // A tone halfway between bins leaks across the spectrum without a taper
+        let samples: Vec<f32> = (0..1024)
+            .map(|i| (2.0 * std::f32::consts::PI * 1055.0 * i as f32 / 44100.0).sin())
+            .collect();
+
+        // Width of the peak measured at -40 dB
+        let peak_width = |analyzer: &mut FftAnalyzer| {
+            let spectrum = analyzer.process_audio(&samples);
+            let peak = spectrum.iter().cloned().fold(0.0, f32::max);
+            spectrum.iter().filter(|&&m| m > peak * 0.01).count()
+        };
+
+        let mut analyzer = FftAnalyzer::new(1024, WindowFunction::Rectangular);
+        let rectangular_width = peak_width(&mut analyzer);
+
+        analyzer.set_window(WindowFunction::BlackmanHarris);
+        assert_eq!(analyzer.window_function(), WindowFunction::BlackmanHarris);
+        let blackman_harris_width = peak_width(&mut analyzer);
+
+        assert!(
+            blackman_harris_width < rectangular_width,
+            "Blackman-Harris {} bins vs rectangular {} bins",
+            blackman_harris_width,
+            rectangular_width
+        );
+        assert!(blackman_harris_width <= 8);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,138 @@
+// Harmonic/percussive source separation by median filtering (Fitzgerald, 2010):
+// sustained partials are smooth along time, transients are smooth along frequency
+
+use std::collections::VecDeque;
+
+/// Frames in the time-direction median; notes must sustain about half this long to count as harmonic
+pub const DEFAULT_HARMONIC_WINDOW: usize = 17;
+
+/// Bins in the frequency-direction median; wider windows ignore more of a tone's spread
+pub const DEFAULT_PERCUSSIVE_WINDOW: usize = 17;
+
+/// Mean magnitude assigned to each component; the two sum to the frame's mean magnitude
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HpssEnergy {
+    pub harmonic: f32,
+    pub percussive: f32,
+}
+
+/// Splits each spectrum frame into harmonic and percussive parts with soft (Wiener) masks
+///
+/// The time median only looks back, so a new note reads as percussive until it has
+/// lasted half the harmonic window.
+pub struct HpssSeparator {
+    history: VecDeque<Vec<f32>>, // Recent spectra, newest at the back
+    harmonic_window: usize,
+    percussive_window: usize,
+    scratch: Vec<f32>,
+}
+
+impl HpssSeparator {
+    pub fn new(harmonic_window: usize, percussive_window: usize) -> Self {
+        let harmonic_window = harmonic_window.max(1);
+        Self {
+            history: VecDeque::with_capacity(harmonic_window),
+            harmonic_window,
+            percussive_window: percussive_window.max(1),
+            scratch: Vec::new(),
+        }
+    }
+
+    pub fn harmonic_window(&self) -> usize {
+        self.harmonic_window
+    }
+
+    pub fn percussive_window(&self) -> usize {
+        self.percussive_window
+    }
+
+    /// Add a spectrum frame and split its energy between the two components
+    pub fn separate(&mut self, bins: &[f32]) -> HpssEnergy {
+        if bins.is_empty() {
+            return HpssEnergy::default();
+        }
+        if self.history.front().is_some_and(|frame| frame.len() != bins.len()) {
+            self.history.clear(); // FFT size changed
+        }
+
+        // Reuse the oldest frame's allocation once the window is full
+        let mut frame = if self.history.len() >= self.harmonic_window {
+            self.history.pop_front().unwrap_or_default()
+        } else {
+            Vec::with_capacity(bins.len())
+        };
+        frame.clear();
+        frame.extend_from_slice(bins);
+        self.history.push_back(frame);
+
+        let half_width = self.percussive_window / 2;
+        let mut energy = HpssEnergy::default();
+        for (k, &magnitude) in bins.iter().enumerate() {
+            if magnitude <= 0.0 {
+                continue;
+            }
+
+            self.scratch.clear();
+            self.scratch.extend(self.history.iter().map(|frame| frame[k]));
+            let harmonic = median(&mut self.scratch);
+
+            self.scratch.clear();
+            self.scratch.extend_from_slice(&bins[k.saturating_sub(half_width)..(k + half_width + 1).min(bins.len())]);
+            let percussive = median(&mut self.scratch);
+
+            let (harmonic_power, percussive_power) = (harmonic * harmonic, percussive * percussive);
+            let total_power = harmonic_power + percussive_power;
+            let harmonic_mask = if total_power > 0.0 { harmonic_power / total_power } else { 0.5 };
+            energy.harmonic += magnitude * harmonic_mask;
+            energy.percussive += magnitude * (1.0 - harmonic_mask);
+        }
+
+        let bin_count = bins.len() as f32;
+        energy.harmonic /= bin_count;
+        energy.percussive /= bin_count;
+        energy
+    }
+
+    /// Forget past frames (useful when switching audio sources)
+    pub fn reset(&mut self) {
+        self.history.clear();
+    }
+}
+
+impl Default for HpssSeparator {
+    fn default() -> Self {
+        Self::new(DEFAULT_HARMONIC_WINDOW, DEFAULT_PERCUSSIVE_WINDOW)
+    }
+}
+
+/// Middle value (upper middle for even counts); reorders `values`
+fn median(values: &mut [f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let middle = values.len() / 2;
+    *values.select_nth_unstable_by(middle, |a, b| a.total_cmp(b)).1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&mut [3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&mut [4.0, 1.0, 3.0, 2.0]), 3.0);
+        assert_eq!(median(&mut []), 0.0);
+    }
+
+    #[test]
+    fn test_energy_split_preserves_mean_magnitude() {
+        let mut separator = HpssSeparator::default();
+        let bins: Vec<f32> = (0..256).map(|i| ((i * 37) % 11) as f32 * 0.05).collect();
+        let mean = bins.iter().sum::<f32>() / bins.len() as f32;
+        for _ in 0..5 {
+            let energy = separator.separate(&bins);
+            assert!((energy.harmonic + energy.percussive - mean).abs() < 1e-4);
+        }
+    }
+}
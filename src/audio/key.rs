@@ -0,0 +1,230 @@
+/// Lowest frequency folded into the chroma vector; below this FFT bins span several semitones
+const CHROMA_MIN_FREQ: f32 = 100.0;
+
+/// Highest frequency folded into the chroma vector; above this harmonics dominate
+const CHROMA_MAX_FREQ: f32 = 5000.0;
+
+/// Krumhansl-Schmuckler major key profile, starting at the tonic
+const MAJOR_PROFILE: [f32; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+
+/// Krumhansl-Schmuckler minor key profile, starting at the tonic
+const MINOR_PROFILE: [f32; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+/// One of the twelve pitch classes, C = 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PitchClass {
+    C,
+    CSharp,
+    D,
+    DSharp,
+    E,
+    F,
+    FSharp,
+    G,
+    GSharp,
+    A,
+    ASharp,
+    B,
+}
+
+impl PitchClass {
+    pub fn all() -> [PitchClass; 12] {
+        [
+            PitchClass::C,
+            PitchClass::CSharp,
+            PitchClass::D,
+            PitchClass::DSharp,
+            PitchClass::E,
+            PitchClass::F,
+            PitchClass::FSharp,
+            PitchClass::G,
+            PitchClass::GSharp,
+            PitchClass::A,
+            PitchClass::ASharp,
+            PitchClass::B,
+        ]
+    }
+
+    /// Pitch class for a semitone index (wraps modulo 12)
+    pub fn from_index(index: usize) -> Self {
+        Self::all()[index % 12]
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PitchClass::C => "C",
+            PitchClass::CSharp => "C#",
+            PitchClass::D => "D",
+            PitchClass::DSharp => "D#",
+            PitchClass::E => "E",
+            PitchClass::F => "F",
+            PitchClass::FSharp => "F#",
+            PitchClass::G => "G",
+            PitchClass::GSharp => "G#",
+            PitchClass::A => "A",
+            PitchClass::ASharp => "A#",
+            PitchClass::B => "B",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyMode {
+    Major,
+    Minor,
+}
+
+impl KeyMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyMode::Major => "major",
+            KeyMode::Minor => "minor",
+        }
+    }
+
+    fn profile(&self) -> &'static [f32; 12] {
+        match self {
+            KeyMode::Major => &MAJOR_PROFILE,
+            KeyMode::Minor => &MINOR_PROFILE,
+        }
+    }
+}
+
+/// A musical key: root note plus major/minor mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MusicalKey {
+    pub root: PitchClass,
+    pub mode: KeyMode,
+}
+
+impl MusicalKey {
+    pub fn new(root: PitchClass, mode: KeyMode) -> Self {
+        Self { root, mode }
+    }
+
+    /// Human-readable name, e.g. "C major"
+    pub fn name(&self) -> String {
+        format!("{} {}", self.root.name(), self.mode.name())
+    }
+}
+
+/// Fold FFT magnitude bins into a 12-bin pitch-class profile (index 0 = C)
+pub fn chroma_from_bins(bins: &[f32], sample_rate: f32) -> [f32; 12] {
+    let mut chroma = [0.0; 12];
+    if bins.is_empty() {
+        return chroma;
+    }
+
+    let bin_width = sample_rate / 2.0 / bins.len() as f32;
+    for (i, &magnitude) in bins.iter().enumerate().skip(1) {
+        let freq = i as f32 * bin_width;
+        if !(CHROMA_MIN_FREQ..=CHROMA_MAX_FREQ).contains(&freq) {
+            continue;
+        }
+
+        // MIDI note 69 is A4 = 440 Hz; MIDI 60 is C4, so note mod 12 is the pitch class
+        let note = (69.0 + 12.0 * (freq / 440.0).log2()).round() as i32;
+        chroma[note.rem_euclid(12) as usize] += magnitude;
+    }
+
+    chroma
+}
+
+/// Correlate a chroma vector against all 24 major/minor key profiles and return the best
+/// match with its correlation (0-1) as confidence; `None` for silent or featureless input
+pub fn detect_key(chroma: &[f32; 12]) -> Option<(MusicalKey, f32)> {
+    let mut best: Option<(MusicalKey, f32)> = None;
+
+    for mode in [KeyMode::Major, KeyMode::Minor] {
+        let profile = mode.profile();
+        for root in PitchClass::all() {
+            // Rotate the profile so its tonic lines up with this root
+            let rotated: [f32; 12] = std::array::from_fn(|i| profile[(i + 12 - root.index()) % 12]);
+            let Some(correlation) = pearson_correlation(chroma, &rotated) else {
+                return None; // Flat chroma correlates with nothing
+            };
+            if !matches!(best, Some((_, score)) if score >= correlation) {
+                best = Some((MusicalKey::new(root, mode), correlation));
+            }
+        }
+    }
+
+    best.map(|(key, correlation)| (key, correlation.clamp(0.0, 1.0)))
+}
+
+fn pearson_correlation(a: &[f32; 12], b: &[f32; 12]) -> Option<f32> {
+    let mean_a = a.iter().sum::<f32>() / 12.0;
+    let mean_b = b.iter().sum::<f32>() / 12.0;
+
+    let mut covariance = 0.0;
+    let mut variance_a = 0.0;
+    let mut variance_b = 0.0;
+    for (x, y) in a.iter().zip(b.iter()) {
+        let dx = x - mean_a;
+        let dy = y - mean_b;
+        covariance += dx * dy;
+        variance_a += dx * dx;
+        variance_b += dy * dy;
+    }
+
+    let denominator = (variance_a * variance_b).sqrt();
+    if denominator <= f32::EPSILON {
+        return None;
+    }
+    Some(covariance / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 44100.0;
+    const BIN_COUNT: usize = 2048;
+
+    /// Spectrum with equal peaks at the bins nearest each frequency
+    fn spectrum_with_peaks(freqs: &[f32]) -> Vec<f32> {
+        let bin_width = SAMPLE_RATE / 2.0 / BIN_COUNT as f32;
+        let mut bins = vec![0.0; BIN_COUNT];
+        for &freq in freqs {
+            bins[(freq / bin_width).round() as usize] = 1.0;
+        }
+        bins
+    }
+
+    #[test]
+    fn test_chroma_folds_octaves() {
+        // A3, A4 and A5 all land on pitch class A
+        let chroma = chroma_from_bins(&spectrum_with_peaks(&[220.0, 440.0, 880.0]), SAMPLE_RATE);
+        assert_eq!(chroma[PitchClass::A.index()], 3.0);
+        assert_eq!(chroma.iter().sum::<f32>(), 3.0);
+    }
+
+    #[test]
+    fn test_c_major_triad_detected() {
+        // C, E and G across two octaves
+        let bins = spectrum_with_peaks(&[261.63, 329.63, 392.0, 523.25, 659.26, 783.99]);
+        let chroma = chroma_from_bins(&bins, SAMPLE_RATE);
+
+        let (key, confidence) = detect_key(&chroma).expect("triad should yield a key");
+        assert_eq!(key, MusicalKey::new(PitchClass::C, KeyMode::Major));
+        assert_eq!(key.name(), "C major");
+        assert!(confidence > 0.6, "confidence {} too low", confidence);
+    }
+
+    #[test]
+    fn test_a_minor_triad_detected() {
+        let bins = spectrum_with_peaks(&[220.0, 261.63, 329.63, 440.0]);
+        let (key, _) = detect_key(&chroma_from_bins(&bins, SAMPLE_RATE)).unwrap();
+        assert_eq!(key, MusicalKey::new(PitchClass::A, KeyMode::Minor));
+    }
+
+    #[test]
+    fn test_silence_has_no_key() {
+        assert!(detect_key(&[0.0; 12]).is_none());
+        assert!(detect_key(&[1.0; 12]).is_none());
+    }
+}
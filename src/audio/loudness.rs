@@ -0,0 +1,227 @@
+// Integrated loudness per ITU-R BS.1770 / EBU R128: K-weighted mean square over gated
+// 400 ms blocks, used to level file playback toward a common target (ReplayGain-style)
+
+/// Loudness files are leveled toward, as used by most streaming services
+pub const TARGET_LOUDNESS_LUFS: f32 = -14.0;
+
+/// Limits on the correction, so near-silent tracks aren't boosted into noise
+pub const REPLAY_GAIN_RANGE_DB: (f32, f32) = (-24.0, 12.0);
+
+const BLOCK_STEPS: usize = 4; // 400 ms gating blocks built from 100 ms steps (75% overlap)
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+
+/// Second-order IIR section (direct form I)
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2], // a1, a2 (a0 normalized to 1)
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self { b, a, x: [0.0; 2], y: [0.0; 2] }
+    }
+
+    fn process(&mut self, input: f64) -> f64 {
+        let output = self.b[0] * input + self.b[1] * self.x[0] + self.b[2] * self.x[1] - self.a[0] * self.y[0] - self.a[1] * self.y[1];
+        self.x = [input, self.x[0]];
+        self.y = [output, self.y[0]];
+        output
+    }
+}
+
+/// K-weighting filter pair (head-related high shelf, then RLB high-pass) for any sample rate
+fn k_weighting(sample_rate: f64) -> [Biquad; 2] {
+    let f0 = 1681.974450955533;
+    let gain_db = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (std::f64::consts::PI * f0 / sample_rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad::new(
+        [(vh + vb * k / q + k * k) / a0, 2.0 * (k * k - vh) / a0, (vh - vb * k / q + k * k) / a0],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (std::f64::consts::PI * f0 / sample_rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad::new([1.0, -2.0, 1.0], [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0]);
+
+    [shelf, high_pass]
+}
+
+/// Streaming integrated-loudness measurement over interleaved samples
+///
+/// All channels are weighted equally, which matches BS.1770 for mono and stereo.
+pub struct LoudnessMeter {
+    filters: Vec<[Biquad; 2]>, // One K-weighting chain per channel
+    step_frames: usize,        // Frames per 100 ms step
+    channel: usize,            // Channel of the next pushed sample
+    frames_in_step: usize,
+    step_energy: f64,          // Channel-summed squares in the current step
+    steps: Vec<f64>,           // Channel-summed mean square of each finished step
+    peak: f32,                 // Largest absolute sample, unweighted
+}
+
+impl LoudnessMeter {
+    pub fn new(channels: u16, sample_rate: f32) -> Self {
+        Self {
+            filters: vec![k_weighting(sample_rate as f64); channels.max(1) as usize],
+            step_frames: ((sample_rate / 10.0).round() as usize).max(1),
+            channel: 0,
+            frames_in_step: 0,
+            step_energy: 0.0,
+            steps: Vec::new(),
+            peak: 0.0,
+        }
+    }
+
+    /// Add interleaved samples
+    pub fn push(&mut self, samples: impl IntoIterator<Item = f32>) {
+        for sample in samples {
+            self.peak = self.peak.max(sample.abs());
+            let [shelf, high_pass] = &mut self.filters[self.channel];
+            let weighted = high_pass.process(shelf.process(sample as f64));
+            self.step_energy += weighted * weighted;
+
+            self.channel += 1;
+            if self.channel == self.filters.len() {
+                self.channel = 0;
+                self.frames_in_step += 1;
+                if self.frames_in_step == self.step_frames {
+                    self.steps.push(self.step_energy / self.step_frames as f64);
+                    self.frames_in_step = 0;
+                    self.step_energy = 0.0;
+                }
+            }
+        }
+    }
+
+    /// Largest absolute sample pushed so far (1.0 = full scale)
+    pub fn sample_peak(&self) -> f32 {
+        self.peak
+    }
+
+    /// Gated loudness of everything pushed so far, or None for silence or under 400 ms of audio
+    pub fn integrated_lufs(&self) -> Option<f32> {
+        let blocks: Vec<f64> = self.steps.windows(BLOCK_STEPS).map(|steps| steps.iter().sum::<f64>() / BLOCK_STEPS as f64).collect();
+
+        let gated_mean = |threshold: f64| {
+            let gated: Vec<f64> = blocks.iter().copied().filter(|&block| block_loudness(block) > threshold).collect();
+            (!gated.is_empty()).then(|| gated.iter().sum::<f64>() / gated.len() as f64)
+        };
+
+        let absolute = gated_mean(ABSOLUTE_GATE_LUFS)?;
+        let relative = gated_mean(block_loudness(absolute) + RELATIVE_GATE_LU)?;
+        Some(block_loudness(relative) as f32)
+    }
+}
+
+fn block_loudness(mean_square: f64) -> f64 {
+    if mean_square > 0.0 {
+        -0.691 + 10.0 * mean_square.log10()
+    } else {
+        f64::NEG_INFINITY
+    }
+}
+
+/// Correction in dB that brings `loudness_lufs` to the target, within `REPLAY_GAIN_RANGE_DB`
+/// and never lifting `peak` past full scale
+pub fn replay_gain_db(loudness_lufs: f32, peak: f32) -> f32 {
+    let (min, max) = REPLAY_GAIN_RANGE_DB;
+    let gain_db = (TARGET_LOUDNESS_LUFS - loudness_lufs).clamp(min, max);
+    if peak > 0.0 {
+        gain_db.min(-20.0 * peak.log10())
+    } else {
+        gain_db
+    }
+}
+
+/// Linear amplitude factor for a gain in dB
+pub fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32, frequency: f32, sample_rate: f32, seconds: f32) -> impl Iterator<Item = f32> {
+        let frames = (sample_rate * seconds) as usize;
+        (0..frames).map(move |i| amplitude * (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate).sin())
+    }
+
+    #[test]
+    fn test_full_scale_1khz_sine_reads_minus_3_lufs() {
+        // BS.1770's reference point: a 0 dBFS 1 kHz tone on one channel is about -3.0 LUFS
+        for sample_rate in [44100.0, 48000.0] {
+            let mut meter = LoudnessMeter::new(1, sample_rate);
+            meter.push(sine(1.0, 1000.0, sample_rate, 3.0));
+            let loudness = meter.integrated_lufs().unwrap();
+            assert!((loudness + 3.0).abs() < 0.2, "{} Hz: {} LUFS", sample_rate, loudness);
+        }
+    }
+
+    #[test]
+    fn test_level_change_shifts_loudness_and_gain() {
+        let measure = |amplitude| {
+            let mut meter = LoudnessMeter::new(2, 44100.0);
+            meter.push(sine(amplitude, 440.0, 44100.0, 2.0).flat_map(|s| [s, s]));
+            meter.integrated_lufs().unwrap()
+        };
+        let (quiet, loud) = (measure(0.05), measure(0.5));
+        assert!((loud - quiet - 20.0).abs() < 0.1);
+        assert!(replay_gain_db(quiet, 0.05) > 0.0);
+        assert!(replay_gain_db(loud, 0.5) < 0.0);
+        assert!((db_to_gain(-6.0206) - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_boost_stops_at_full_scale_peak() {
+        // A quiet bed under a full-scale transient would clip if boosted to the target
+        let mut meter = LoudnessMeter::new(1, 44100.0);
+        meter.push(sine(0.05, 440.0, 44100.0, 2.0));
+        meter.push([1.0]);
+        assert_eq!(meter.sample_peak(), 1.0);
+
+        let loudness = meter.integrated_lufs().unwrap();
+        assert!(loudness < TARGET_LOUDNESS_LUFS);
+        assert_eq!(replay_gain_db(loudness, 1.0), 0.0);
+
+        // Headroom below full scale is still usable, and silence leaves the range alone
+        assert!((replay_gain_db(loudness, 0.5) - 6.0206).abs() < 1e-3);
+        assert_eq!(replay_gain_db(-40.0, 0.0), REPLAY_GAIN_RANGE_DB.1);
+    }
+
+    #[test]
+    fn test_silence_and_short_input_have_no_loudness() {
+        let mut meter = LoudnessMeter::new(1, 44100.0);
+        meter.push(std::iter::repeat_n(0.0, 44100));
+        assert_eq!(meter.integrated_lufs(), None);
+
+        let mut short = LoudnessMeter::new(1, 44100.0);
+        short.push(sine(0.5, 440.0, 44100.0, 0.3));
+        assert_eq!(short.integrated_lufs(), None);
+    }
+
+    #[test]
+    fn test_silent_gaps_are_gated_out() {
+        let mut continuous = LoudnessMeter::new(1, 44100.0);
+        continuous.push(sine(0.3, 440.0, 44100.0, 2.0));
+
+        let mut with_gap = LoudnessMeter::new(1, 44100.0);
+        with_gap.push(sine(0.3, 440.0, 44100.0, 1.0));
+        with_gap.push(std::iter::repeat_n(0.0, 88200));
+        with_gap.push(sine(0.3, 440.0, 44100.0, 1.0));
+
+        // Ungated, the silence would halve the mean square (-3 LU); only blocks straddling the edges remain
+        let difference = continuous.integrated_lufs().unwrap() - with_gap.integrated_lufs().unwrap();
+        assert!(difference.abs() < 1.0, "gap changed loudness by {} LU", difference);
+    }
+}
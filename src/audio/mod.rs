@@ -3,9 +3,25 @@ pub mod fft;
 pub mod features;
 pub mod rhythm;
 pub mod advanced_analyzer;
+pub mod key;
+pub mod playlist;
+pub mod recording;
+pub mod signal;
+pub mod hpss;
+pub mod loudness;
+pub mod envelope;
+pub mod noise_gate;
 
 pub use processor::*;
 pub use fft::*;
 pub use features::*;
 pub use rhythm::*;
-pub use advanced_analyzer::*;
\ No newline at end of file
+pub use advanced_analyzer::*;
+pub use key::*;
+pub use playlist::*;
+pub use recording::*;
+pub use signal::*;
+pub use hpss::*;
+pub use loudness::*;
+pub use envelope::*;
+pub use noise_gate::*;
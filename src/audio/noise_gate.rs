@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use super::AudioFeatures;
+
+/// Drop below the open threshold by this much before the gate starts closing, so a level
+/// hovering at the threshold doesn't chatter open and shut
+pub const NOISE_GATE_HYSTERESIS_DB: f32 = 6.0;
+
+/// Per-frame gate that blanks feature output while the input is only background noise
+///
+/// Opens as soon as `signal_level_db` reaches the threshold, and closes once the level has
+/// stayed under the threshold minus `NOISE_GATE_HYSTERESIS_DB` for the hold time. Unlike
+/// silence detection it never switches modes; gated frames simply carry no energy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoiseGate {
+    threshold_db: Option<f32>, // None = gate off
+    hold: Duration,
+    open: bool,
+    below_for: Duration, // Time spent under the close threshold while open
+}
+
+impl NoiseGate {
+    pub fn new() -> Self {
+        Self {
+            threshold_db: None,
+            hold: Duration::ZERO,
+            open: true,
+            below_for: Duration::ZERO,
+        }
+    }
+
+    /// Gate frames quieter than `threshold_db` (None turns the gate off) after `hold` below it
+    pub fn set(&mut self, threshold_db: Option<f32>, hold: Duration) {
+        self.threshold_db = threshold_db.filter(|db| db.is_finite());
+        self.hold = hold;
+        self.reset();
+    }
+
+    pub fn threshold_db(&self) -> Option<f32> {
+        self.threshold_db
+    }
+
+    pub fn hold(&self) -> Duration {
+        self.hold
+    }
+
+    /// Whether frames currently pass through
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Gate one frame in place, `frame_time` after the previous one; returns whether it passed
+    pub fn process(&mut self, features: &mut AudioFeatures, frame_time: Duration) -> bool {
+        let Some(threshold_db) = self.threshold_db else {
+            return true;
+        };
+
+        let level_db = features.signal_level_db;
+        if level_db >= threshold_db {
+            self.open = true;
+            self.below_for = Duration::ZERO;
+        } else if self.open {
+            if level_db < threshold_db - NOISE_GATE_HYSTERESIS_DB {
+                self.below_for += frame_time;
+                if self.below_for >= self.hold {
+                    self.open = false;
+                }
+            } else {
+                self.below_for = Duration::ZERO; // Inside the hysteresis band
+            }
+        }
+
+        if !self.open {
+            // Keep the raw levels so meters still show what the gate is hearing
+            *features = AudioFeatures {
+                signal_level_db: features.signal_level_db,
+                peak_level_db: features.peak_level_db,
+                ..AudioFeatures::new()
+            };
+        }
+        self.open
+    }
+
+    /// Start open again, e.g. for a new source
+    pub fn reset(&mut self) {
+        self.open = true;
+        self.below_for = Duration::ZERO;
+    }
+}
+
+impl Default for NoiseGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(10);
+
+    fn frame(level_db: f32) -> AudioFeatures {
+        AudioFeatures {
+            sub_bass: 0.3,
+            bass: 0.4,
+            mid: 0.2,
+            overall_volume: 0.25,
+            onset_strength: 0.1,
+            signal_level_db: level_db,
+            ..AudioFeatures::new()
+        }
+    }
+
+    #[test]
+    fn test_quiet_frames_are_suppressed_after_hold() {
+        let mut gate = NoiseGate::new();
+        gate.set(Some(-40.0), Duration::from_millis(50));
+
+        // Hum below the threshold still passes until the hold runs out
+        for _ in 0..4 {
+            let mut features = frame(-55.0);
+            assert!(gate.process(&mut features, FRAME));
+            assert_eq!(features.bass, 0.4);
+        }
+        let mut features = frame(-55.0);
+        assert!(!gate.process(&mut features, FRAME));
+        assert_eq!([features.sub_bass, features.bass, features.mid, features.overall_volume, features.onset_strength], [0.0; 5]);
+        assert_eq!(features.signal_level_db, -55.0);
+
+        // A frame at the threshold passes straight away
+        let mut features = frame(-40.0);
+        assert!(gate.process(&mut features, FRAME));
+        assert_eq!(features, frame(-40.0));
+    }
+
+    #[test]
+    fn test_hysteresis_prevents_chatter() {
+        let mut gate = NoiseGate::new();
+        gate.set(Some(-40.0), Duration::ZERO);
+
+        // Wobbling just under the threshold never closes an open gate
+        for level_db in [-41.0, -39.0, -43.0, -44.0, -41.0, -45.5] {
+            assert!(gate.process(&mut frame(level_db), FRAME));
+        }
+
+        // Once closed, it takes the full threshold to reopen
+        assert!(!gate.process(&mut frame(-50.0), FRAME));
+        for level_db in [-44.0, -41.0, -40.5] {
+            assert!(!gate.process(&mut frame(level_db), FRAME));
+        }
+        assert!(gate.process(&mut frame(-38.0), FRAME));
+    }
+
+    #[test]
+    fn test_disabled_gate_passes_everything() {
+        let mut gate = NoiseGate::new();
+        let mut features = frame(-90.0);
+        assert!(gate.process(&mut features, FRAME));
+        assert_eq!(features, frame(-90.0));
+
+        gate.set(Some(f32::NAN), Duration::ZERO);
+        assert_eq!(gate.threshold_db(), None);
+    }
+}
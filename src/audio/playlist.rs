@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+use anyhow::{Result, anyhow};
+
+/// Playback moved to another playlist entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackChanged {
+    pub index: usize,
+    pub path: PathBuf,
+}
+
+/// Called whenever the playing playlist entry changes
+pub type TrackChangeCallback = Box<dyn FnMut(&TrackChanged) + Send>;
+
+/// Ordered audio files played back to back, with a cursor on the current entry
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Playlist {
+    tracks: Vec<PathBuf>,
+    current: usize,
+}
+
+impl Playlist {
+    pub fn new(tracks: Vec<PathBuf>) -> Self {
+        Self { tracks, current: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    pub fn tracks(&self) -> &[PathBuf] {
+        &self.tracks
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Path of the current entry (None for an empty playlist)
+    pub fn current(&self) -> Option<&Path> {
+        self.tracks.get(self.current).map(PathBuf::as_path)
+    }
+
+    /// Move to the next entry; returns the new index, or None if already on the last one
+    pub fn advance(&mut self) -> Option<usize> {
+        if self.current + 1 >= self.tracks.len() {
+            return None;
+        }
+        self.current += 1;
+        Some(self.current)
+    }
+
+    /// Move to the previous entry; returns the new index, or None if already on the first one
+    pub fn previous(&mut self) -> Option<usize> {
+        if self.current == 0 {
+            return None;
+        }
+        self.current -= 1;
+        Some(self.current)
+    }
+
+    /// Jump straight to an entry
+    pub fn select(&mut self, index: usize) -> Result<()> {
+        if index >= self.tracks.len() {
+            return Err(anyhow!("Track {} is out of range for a playlist of {}", index, self.tracks.len()));
+        }
+        self.current = index;
+        Ok(())
+    }
+
+    /// Event describing the current entry
+    pub fn track_changed(&self) -> Option<TrackChanged> {
+        self.current().map(|path| TrackChanged {
+            index: self.current,
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl FromIterator<PathBuf> for Playlist {
+    fn from_iter<I: IntoIterator<Item = PathBuf>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_entry_playlist_advances() {
+        let mut playlist: Playlist = ["one.wav", "two.wav"].into_iter().map(PathBuf::from).collect();
+        assert_eq!(playlist.len(), 2);
+        assert_eq!(playlist.current_index(), 0);
+        assert_eq!(playlist.current(), Some(Path::new("one.wav")));
+
+        assert_eq!(playlist.advance(), Some(1));
+        assert_eq!(playlist.current(), Some(Path::new("two.wav")));
+        assert_eq!(playlist.advance(), None);
+        assert_eq!(playlist.current_index(), 1);
+
+        assert_eq!(playlist.previous(), Some(0));
+        assert_eq!(playlist.previous(), None);
+
+        let event = playlist.track_changed().unwrap();
+        assert_eq!(event.index, 0);
+        assert_eq!(event.path, PathBuf::from("one.wav"));
+    }
+
+    #[test]
+    fn test_select_rejects_out_of_range() {
+        let mut playlist = Playlist::new(vec![PathBuf::from("one.wav"), PathBuf::from("two.wav")]);
+        assert!(playlist.select(1).is_ok());
+        assert_eq!(playlist.current_index(), 1);
+        assert!(playlist.select(2).is_err());
+        assert_eq!(playlist.current_index(), 1);
+
+        let empty = Playlist::default();
+        assert!(empty.is_empty());
+        assert!(empty.current().is_none());
+        assert!(empty.track_changed().is_none());
+    }
+}
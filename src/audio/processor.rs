@@ -1,148 +1,940 @@
 use cpal::{Device, Stream, SampleFormat, StreamConfig, traits::*};
-use rodio::{Decoder, OutputStream, Sink};
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use std::cell::Cell;
 use std::sync::{Arc, Mutex};
-use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use anyhow::{Result, anyhow};
 
-use super::{FftAnalyzer, AudioFeatures, AdvancedAudioAnalyzer};
+use super::{db_to_gain, replay_gain_db, FftAnalyzer, AudioFeatures, AdvancedAudioAnalyzer, Band, BeatClick, LoudnessMeter, NoiseGate, Playlist, SignalGenerator, SignalSpec, TrackChangeCallback, TrackChanged, Weighting, WindowFunction};
+use crate::rendering::GpuFft;
 
 const BUFFER_SIZE: usize = 1024;
 const SAMPLE_RATE: u32 = 44100;
+const TAP_BATCH_SIZE: usize = 256; // Mono samples collected before locking the analysis buffer
+const MIN_ANALYSIS_SAMPLES: usize = BUFFER_SIZE / 8; // Shortest buffer worth zero-padding
+const SILENCE_THRESHOLD: f32 = 1e-4; // Peak sample level treated as digital silence
+const ALL_INPUT_CHANNELS: usize = usize::MAX; // Input channel selection meaning "no channel extraction"
+const CLICK_LENGTH: Duration = Duration::from_millis(25);
+const CLICK_FREQ: f32 = 1000.0;
+const DOWNBEAT_CLICK_FREQ: f32 = 1500.0; // Higher pitch marks the start of the bar
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(500);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(8);
+const LOOP_END_MARGIN: Duration = Duration::from_millis(50); // Loops ending at the end of a file wrap this early, before the sink runs dry
+
+/// Largest audio/visual latency correction in either direction
+pub const MAX_LATENCY_OFFSET_MS: i32 = 500;
+
+/// Where playback at `position` should jump while looping `region` (start, end): back to the
+/// start once the end has been reached, otherwise nowhere
+pub fn loop_seek_target(region: (Duration, Duration), position: Duration) -> Option<Duration> {
+    let (start, end) = region;
+    (end > start && position >= end).then_some(start)
+}
+
+/// Analysis picked up at a new playback position after a seek or loop wrap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Seeked {
+    pub position: Duration,
+}
+
+/// Called on the first analysis frame after each seek or loop wrap
+pub type SeekCallback = Box<dyn FnMut(&Seeked) + Send>;
+
+/// Pending `measure_loudness` result from a background scan
+type LoudnessScan = Receiver<Result<(Option<f32>, f32)>>;
+
+/// Left/right frames kept alongside the mono downmix for stereo image analysis
+type StereoBuffer = Arc<Mutex<VecDeque<[f32; 2]>>>;
+
+/// Where the spectrum FFT runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FftBackend {
+    #[default]
+    Cpu, // rustfft via `FftAnalyzer`
+    Gpu, // Compute-shader Stockham FFT via `GpuFft`; full windows only
+}
+
+impl FftBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FftBackend::Cpu => "CPU",
+            FftBackend::Gpu => "GPU",
+        }
+    }
+}
+
+/// Exponential backoff between attempts to reopen a lost input device
+#[derive(Debug, Clone)]
+pub struct ReconnectBackoff {
+    delay: Duration,
+    next_attempt: Option<Instant>, // None = try right away
+}
+
+impl ReconnectBackoff {
+    pub fn new() -> Self {
+        Self {
+            delay: RECONNECT_INITIAL_DELAY,
+            next_attempt: None,
+        }
+    }
+
+    /// Whether another attempt is due at `now`
+    pub fn ready(&self, now: Instant) -> bool {
+        self.next_attempt.is_none_or(|next| now >= next)
+    }
+
+    /// Record a failed attempt; returns the wait before the next one, which doubles up to `RECONNECT_MAX_DELAY`
+    pub fn failed(&mut self, now: Instant) -> Duration {
+        let wait = self.delay;
+        self.next_attempt = Some(now + wait);
+        self.delay = (self.delay * 2).min(RECONNECT_MAX_DELAY);
+        wait
+    }
+
+    /// Start over after a successful reconnect
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Why the last analyzed frame did or did not produce features
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisState {
+    WaitingForSamples, // Not enough audio buffered yet (startup or after a flush)
+    Silent,            // Enough samples, but the signal is silent
+    Partial,           // Short buffer analyzed with zero-padding
+    Active,            // Full analysis window available
+}
+
+/// An audio input device as the host reports it
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceInfo {
+    pub index: usize, // Position in `AudioProcessor::list_input_devices`, for picking by number
+    pub name: String,
+    pub is_default: bool,
+    pub channels: Option<u16>,    // From the device's default input config, when it has one
+    pub sample_rate: Option<u32>,
+}
+
+/// Index of the device `selector` names: a list index, or else a case-insensitive substring
+/// of the device name (the first match wins)
+pub fn match_input_device(devices: &[DeviceInfo], selector: &str) -> Option<usize> {
+    let selector = selector.trim();
+    if let Ok(index) = selector.parse::<usize>() {
+        return devices.iter().position(|device| device.index == index);
+    }
+    let needle = selector.to_lowercase();
+    devices.iter().position(|device| device.name.to_lowercase().contains(&needle))
+}
 
 pub struct AudioProcessor {
-    _stream: Option<Stream>,
+    input_stream: Option<Stream>,
     _output_stream: Option<OutputStream>,
     sink: Option<Sink>,
+    output_handle: Option<OutputStreamHandle>, // Mixes metronome clicks alongside the sink's queue
     audio_buffer: Arc<Mutex<VecDeque<f32>>>,
+    stereo_buffer: StereoBuffer, // Empty for mono sources and single-channel extraction
     fft_analyzer: FftAnalyzer,
     advanced_analyzer: AdvancedAudioAnalyzer,
-    #[allow(dead_code)] // Used in tests
     sample_rate: f32,
+    channels: u16, // Channel count of the current source (analysis always sees a mono downmix)
     volume: f32, // Volume level (0.0 to 1.0)
+    replay_gain_enabled: bool,
+    replay_gains: HashMap<PathBuf, f32>, // Scanned correction in dB per file, so reloading skips the scan
+    current_file: Option<PathBuf>,       // File given to `play_from_file`; readers have no path
+    replay_gain_scan: Option<(PathBuf, LoudnessScan)>, // Background loudness scan of `current_file`
+    track_gain_db: f32, // Correction applied on top of `volume` for the current file
+    analysis_state: AnalysisState,
+    current_duration: Option<Duration>, // Length of the loaded file, when the decoder knows it
+    input_channels: u16, // Channel count of the live input stream
+    input_channel: Arc<AtomicUsize>, // Input channel fed to analysis (ALL_INPUT_CHANNELS = as delivered)
+    device_healthy: Arc<AtomicBool>, // Cleared by the stream error callback when the input device disappears
+    latency_offset_ms: i32, // Positive delays visuals behind playback, negative runs analysis ahead of it
+    feature_delay: FeatureDelay,
+    lookahead_samples: Arc<AtomicUsize>, // Interleaved samples the file tap reads ahead of playback
+    gpu_fft: Option<GpuFft>, // Present when the GPU backend is selected and available
+    spectrum_on_gpu: bool,   // Whether the last frame's spectrum came from `gpu_fft`
+    playlist: Option<Playlist>,
+    queued_tracks: Vec<QueuedTrack>, // Layout of each playlist entry on the sink, starting at `queue_start`
+    queue_start: usize,
+    track_change_callback: Option<TrackChangeCallback>,
+    signal_generator: Option<SignalGenerator>, // Synthetic source standing in for live input
+    offline_decoder: Option<OfflineDecoder>,   // File decoded frame by frame instead of played
+    noise_gate: NoiseGate,
+    input_device_selector: Option<String>, // Device chosen with `new_with_device`, reused on reconnect
+    loop_region: Option<(Duration, Duration)>, // Playback wraps from the end back to the start
+    loop_in: Option<Duration>,                 // Start marked with `mark_loop_in`, before an end is set
+    seek_anchor: Cell<Option<(Duration, Duration)>>, // Last seek target and the stale position the sink reported then
+    seek_callback: Option<SeekCallback>,
+    pending_seek: Option<Seeked>, // Reported once samples from the new position reach the analysis window
+}
+
+/// A file decoded on demand rather than played, so analysis follows the decode position
+struct OfflineDecoder {
+    samples: Box<dyn Iterator<Item = f32> + Send>, // Interleaved, `channels` wide
+    channels: u16,
+    analysis_frames: u64, // `process_frame` calls served so far
+    frames_decoded: u64,  // Sample frames (one sample per channel) pulled from the file
+    finished: bool,
+}
+
+/// Stream layout of a playlist entry, applied to analysis when the sink reaches it
+#[derive(Debug, Clone, Copy)]
+struct QueuedTrack {
+    channels: u16,
+    sample_rate: f32,
+    duration: Option<Duration>,
+}
+
+/// Holds analyzed frames back so visuals trail playback by a whole number of frames
+pub struct FeatureDelay {
+    frames: VecDeque<AudioFeatures>,
+    delay_frames: usize,
+}
+
+impl FeatureDelay {
+    pub fn new() -> Self {
+        Self {
+            frames: VecDeque::new(),
+            delay_frames: 0,
+        }
+    }
+
+    pub fn set_delay_frames(&mut self, frames: usize) {
+        self.delay_frames = frames;
+    }
+
+    pub fn delay_frames(&self) -> usize {
+        self.delay_frames
+    }
+
+    /// Queue this frame's features and return the ones from `delay_frames` frames ago
+    /// (the oldest available while the delay is still filling)
+    pub fn push(&mut self, features: AudioFeatures) -> AudioFeatures {
+        self.frames.push_back(features);
+        while self.frames.len() > self.delay_frames + 1 {
+            self.frames.pop_front();
+        }
+        self.frames.front().cloned().unwrap_or_else(AudioFeatures::new)
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+impl Default for FeatureDelay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Sample format conversions, normalizing each format to roughly -1.0..1.0
+fn f64_to_f32(s: f64) -> f32 {
+    s as f32
+}
+
+fn i8_to_f32(s: i8) -> f32 {
+    s as f32 / i8::MAX as f32
+}
+
+fn i16_to_f32(s: i16) -> f32 {
+    s as f32 / i16::MAX as f32
+}
+
+fn i32_to_f32(s: i32) -> f32 {
+    (s as f64 / i32::MAX as f64) as f32
+}
+
+fn i64_to_f32(s: i64) -> f32 {
+    (s as f64 / i64::MAX as f64) as f32
+}
+
+fn u8_to_f32(s: u8) -> f32 {
+    (s as f32 - u8::MAX as f32 / 2.0) / (u8::MAX as f32 / 2.0)
+}
+
+fn u16_to_f32(s: u16) -> f32 {
+    (s as f32 - u16::MAX as f32 / 2.0) / (u16::MAX as f32 / 2.0)
+}
+
+fn u32_to_f32(s: u32) -> f32 {
+    ((s as f64 - u32::MAX as f64 / 2.0) / (u32::MAX as f64 / 2.0)) as f32
+}
+
+fn u64_to_f32(s: u64) -> f32 {
+    ((s as f64 - u64::MAX as f64 / 2.0) / (u64::MAX as f64 / 2.0)) as f32
+}
+
+/// Passes file samples through to playback while feeding a mono downmix to the analysis buffer
+/// and, for stereo files, the left/right frames to the stereo buffer
+pub struct AnalysisTap<S>
+where
+    S: Source<Item = f32>,
+{
+    input: S,
+    buffer: Arc<Mutex<VecDeque<f32>>>,
+    stereo_buffer: StereoBuffer,
+    frame_channels: u16,
+    frame_position: u16,
+    frame_sum: f32,
+    frame_stereo: [f32; 2],
+    pending: Vec<f32>,
+    pending_stereo: Vec<[f32; 2]>,
+    lookahead: Arc<AtomicUsize>,
+    delay_line: VecDeque<f32>, // Samples already analyzed but not yet handed to playback
+}
+
+impl<S> AnalysisTap<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(input: S, buffer: Arc<Mutex<VecDeque<f32>>>, stereo_buffer: StereoBuffer) -> Self {
+        Self::with_lookahead(input, buffer, stereo_buffer, Arc::new(AtomicUsize::new(0)))
+    }
+
+    /// Tap that analyzes `lookahead` interleaved samples before they reach playback
+    pub fn with_lookahead(
+        input: S,
+        buffer: Arc<Mutex<VecDeque<f32>>>,
+        stereo_buffer: StereoBuffer,
+        lookahead: Arc<AtomicUsize>,
+    ) -> Self {
+        Self {
+            frame_channels: input.channels().max(1),
+            input,
+            buffer,
+            stereo_buffer,
+            frame_position: 0,
+            frame_sum: 0.0,
+            frame_stereo: [0.0; 2],
+            pending: Vec::with_capacity(TAP_BATCH_SIZE),
+            pending_stereo: Vec::with_capacity(TAP_BATCH_SIZE),
+            lookahead,
+            delay_line: VecDeque::new(),
+        }
+    }
+
+    fn flush(&mut self) {
+        AudioProcessor::write_input_data(&self.pending, &self.buffer, None, 1, ALL_INPUT_CHANNELS);
+        AudioProcessor::write_stereo_frames(self.pending_stereo.iter().copied(), &self.stereo_buffer);
+        self.pending.clear();
+        self.pending_stereo.clear();
+    }
+
+    /// Pull one sample from the decoder and feed it to analysis
+    fn read_input(&mut self) -> Option<f32> {
+        if self.frame_position == 0 {
+            // Channel layout can change between frames of some formats
+            self.frame_channels = self.input.channels().max(1);
+        }
+
+        let sample = match self.input.next() {
+            Some(sample) => sample,
+            None => {
+                self.flush();
+                return None;
+            }
+        };
+
+        if self.frame_position < 2 {
+            self.frame_stereo[self.frame_position as usize] = sample;
+        }
+        self.frame_sum += sample;
+        self.frame_position += 1;
+
+        if self.frame_position >= self.frame_channels {
+            self.pending.push(self.frame_sum / self.frame_channels as f32);
+            if self.frame_channels >= 2 {
+                self.pending_stereo.push(self.frame_stereo);
+            }
+            self.frame_sum = 0.0;
+            self.frame_position = 0;
+
+            if self.pending.len() >= TAP_BATCH_SIZE {
+                self.flush();
+            }
+        }
+
+        Some(sample)
+    }
+}
+
+impl<S> Iterator for AnalysisTap<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        // Read ahead so analysis sees samples before they play; a shrinking lookahead drains
+        // the delay line instead of skipping audio
+        let lookahead = self.lookahead.load(Ordering::Relaxed);
+        while self.delay_line.len() <= lookahead {
+            match self.read_input() {
+                Some(sample) => self.delay_line.push_back(sample),
+                None => break,
+            }
+        }
+        self.delay_line.pop_front()
+    }
+}
+
+impl<S> Source for AnalysisTap<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len().map(|len| len + self.delay_line.len())
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), rodio::source::SeekError> {
+        self.frame_position = 0;
+        self.frame_sum = 0.0;
+        self.pending.clear();
+        self.pending_stereo.clear();
+        self.delay_line.clear();
+        self.input.try_seek(pos)
+    }
 }
 
 impl AudioProcessor {
+    /// Live input from the default input device
     pub fn new() -> Result<Self> {
-        let host = cpal::default_host();
-        let device = host
+        let device = cpal::default_host()
             .default_input_device()
             .ok_or_else(|| anyhow!("No input device available"))?;
+        Self::with_input_device(device)
+    }
+
+    /// Live input from the device `device_name` selects: an index from `list_input_devices` or
+    /// part of a device name. Falls back to the default input, with a warning, when nothing matches.
+    pub fn new_with_device(device_name: &str) -> Result<Self> {
+        let device = Self::find_input_device(device_name)?;
+        let mut processor = Self::with_input_device(device)?;
+        processor.input_device_selector = Some(device_name.to_string());
+        Ok(processor)
+    }
+
+    /// Input devices on the default host; empty when there are none or they can't be enumerated
+    pub fn list_input_devices() -> Vec<DeviceInfo> {
+        Self::input_devices()
+            .into_iter()
+            .enumerate()
+            .map(|(index, (device, is_default))| {
+                let config = device.default_input_config().ok();
+                DeviceInfo {
+                    index,
+                    name: device.name().unwrap_or_else(|_| "unknown device".to_string()),
+                    is_default,
+                    channels: config.as_ref().map(|config| config.channels()),
+                    sample_rate: config.as_ref().map(|config| config.sample_rate().0),
+                }
+            })
+            .collect()
+    }
 
+    /// Input devices paired with whether each is the host's default
+    fn input_devices() -> Vec<(Device, bool)> {
+        let host = cpal::default_host();
+        let default_name = host.default_input_device().and_then(|device| device.name().ok());
+        match host.input_devices() {
+            Ok(devices) => devices
+                .map(|device| {
+                    let is_default = default_name.is_some() && device.name().ok() == default_name;
+                    (device, is_default)
+                })
+                .collect(),
+            Err(e) => {
+                eprintln!("Failed to enumerate audio input devices: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// The device `selector` names, or the default input device when none matches
+    fn find_input_device(selector: &str) -> Result<Device> {
+        let devices = Self::input_devices();
+        let infos: Vec<DeviceInfo> = devices
+            .iter()
+            .enumerate()
+            .map(|(index, (device, is_default))| DeviceInfo {
+                index,
+                name: device.name().unwrap_or_default(),
+                is_default: *is_default,
+                channels: None,
+                sample_rate: None,
+            })
+            .collect();
+
+        if let Some((device, _)) = match_input_device(&infos, selector).and_then(|position| devices.into_iter().nth(position)) {
+            println!("🎤 Audio input device: {}", device.name().unwrap_or_else(|_| "unknown device".to_string()));
+            return Ok(device);
+        }
+
+        println!("⚠️  Audio input device '{}' not found - using the default input", selector);
+        cpal::default_host()
+            .default_input_device()
+            .ok_or_else(|| anyhow!("No input device available"))
+    }
+
+    fn with_input_device(device: Device) -> Result<Self> {
         let config = device.default_input_config()?;
         let sample_rate = config.sample_rate().0 as f32;
+        let channels = config.channels();
 
         let audio_buffer = Arc::new(Mutex::new(VecDeque::with_capacity(BUFFER_SIZE * 4)));
         let buffer_clone = Arc::clone(&audio_buffer);
+        let stereo_buffer = Arc::new(Mutex::new(VecDeque::with_capacity(BUFFER_SIZE * 4)));
+        let input_channel = Arc::new(AtomicUsize::new(ALL_INPUT_CHANNELS));
+        let device_healthy = Arc::new(AtomicBool::new(true));
 
-        let stream = Self::build_input_stream(&device, config, buffer_clone)?;
+        let stream = Self::build_input_stream(
+            &device,
+            config,
+            buffer_clone,
+            Arc::clone(&stereo_buffer),
+            Arc::clone(&input_channel),
+            Arc::clone(&device_healthy),
+        )?;
 
         let (_output_stream, stream_handle) = OutputStream::try_default()?;
         let sink = Sink::try_new(&stream_handle)?;
 
         Ok(Self {
-            _stream: Some(stream),
+            input_stream: Some(stream),
             _output_stream: Some(_output_stream),
             sink: Some(sink),
+            output_handle: Some(stream_handle),
             audio_buffer,
-            fft_analyzer: FftAnalyzer::new(BUFFER_SIZE),
+            stereo_buffer,
+            fft_analyzer: FftAnalyzer::new(BUFFER_SIZE, WindowFunction::default()),
             advanced_analyzer: AdvancedAudioAnalyzer::new(sample_rate),
             sample_rate,
+            channels,
             volume: 0.1, // Default volume at 10%
+            replay_gain_enabled: true,
+            replay_gains: HashMap::new(),
+            current_file: None,
+            replay_gain_scan: None,
+            track_gain_db: 0.0,
+            analysis_state: AnalysisState::WaitingForSamples,
+            current_duration: None,
+            input_channels: channels,
+            input_channel,
+            device_healthy,
+            latency_offset_ms: 0,
+            feature_delay: FeatureDelay::new(),
+            lookahead_samples: Arc::new(AtomicUsize::new(0)),
+            gpu_fft: None,
+            spectrum_on_gpu: false,
+            playlist: None,
+            queued_tracks: Vec::new(),
+            queue_start: 0,
+            track_change_callback: None,
+            signal_generator: None,
+            offline_decoder: None,
+            noise_gate: NoiseGate::new(),
+            input_device_selector: None,
+            loop_region: None,
+            loop_in: None,
+            seek_anchor: Cell::new(None),
+            seek_callback: None,
+            pending_seek: None,
         })
     }
 
     pub fn new_default() -> Self {
         Self {
-            _stream: None,
+            input_stream: None,
             _output_stream: None,
             sink: None,
+            output_handle: None,
             audio_buffer: Arc::new(Mutex::new(VecDeque::new())),
-            fft_analyzer: FftAnalyzer::new(BUFFER_SIZE),
+            stereo_buffer: Arc::new(Mutex::new(VecDeque::new())),
+            fft_analyzer: FftAnalyzer::new(BUFFER_SIZE, WindowFunction::default()),
             advanced_analyzer: AdvancedAudioAnalyzer::new(SAMPLE_RATE as f32),
             sample_rate: SAMPLE_RATE as f32,
+            channels: 1,
             volume: 0.1, // Default volume at 10%
+            replay_gain_enabled: true,
+            replay_gains: HashMap::new(),
+            current_file: None,
+            replay_gain_scan: None,
+            track_gain_db: 0.0,
+            analysis_state: AnalysisState::WaitingForSamples,
+            current_duration: None,
+            input_channels: 1,
+            input_channel: Arc::new(AtomicUsize::new(ALL_INPUT_CHANNELS)),
+            device_healthy: Arc::new(AtomicBool::new(true)),
+            latency_offset_ms: 0,
+            feature_delay: FeatureDelay::new(),
+            lookahead_samples: Arc::new(AtomicUsize::new(0)),
+            gpu_fft: None,
+            spectrum_on_gpu: false,
+            playlist: None,
+            queued_tracks: Vec::new(),
+            queue_start: 0,
+            track_change_callback: None,
+            signal_generator: None,
+            offline_decoder: None,
+            noise_gate: NoiseGate::new(),
+            input_device_selector: None,
+            loop_region: None,
+            loop_in: None,
+            seek_anchor: Cell::new(None),
+            seek_callback: None,
+            pending_seek: None,
         }
     }
 
+    /// Analyze a synthetic test signal instead of an input device. Each `process_frame`
+    /// generates one frame's worth of samples (sample rate / analysis frame rate), so runs
+    /// are reproducible regardless of wall-clock timing.
+    pub fn new_test_signal(spec: SignalSpec) -> Self {
+        let mut processor = Self::new_default();
+        processor.configure_for_source(1, spec.sample_rate as f32);
+        processor.signal_generator = Some(SignalGenerator::new(spec));
+        println!("🧪 Test signal: {} at {:.0} Hz, amplitude {:.2}", spec.waveform.name(), spec.frequency, spec.amplitude);
+        processor
+    }
+
+    /// Analyze a file without playing it. Each `process_frame` decodes exactly the samples
+    /// that fall within the next analysis frame (sample rate / analysis frame rate, without
+    /// rounding drift), so features stay locked to the decode position rather than the wall
+    /// clock however fast frames are rendered.
+    pub fn new_offline<R>(reader: R) -> Result<Self>
+    where
+        R: Read + Seek + Send + Sync + 'static,
+    {
+        let decoder = Decoder::new(reader)?;
+        let channels = decoder.channels().max(1);
+        let sample_rate = decoder.sample_rate() as f32;
+
+        let mut processor = Self::new_default();
+        processor.configure_for_source(channels, sample_rate);
+        processor.current_duration = decoder.total_duration();
+        processor.offline_decoder = Some(OfflineDecoder {
+            samples: Box::new(decoder.convert_samples::<f32>()),
+            channels,
+            analysis_frames: 0,
+            frames_decoded: 0,
+            finished: false,
+        });
+        Ok(processor)
+    }
+
+    /// How far into the file offline decoding has reached
+    pub fn offline_position(&self) -> Option<Duration> {
+        self.offline_decoder
+            .as_ref()
+            .map(|decoder| Duration::from_secs_f64(decoder.frames_decoded as f64 / self.sample_rate as f64))
+    }
+
+    /// Whether an offline file has been decoded to its end
+    pub fn offline_finished(&self) -> bool {
+        self.offline_decoder.as_ref().is_some_and(|decoder| decoder.finished)
+    }
+
+    /// Spec of the synthetic source, when one replaces live input
+    pub fn test_signal(&self) -> Option<&SignalSpec> {
+        self.signal_generator.as_ref().map(SignalGenerator::spec)
+    }
+
     fn build_input_stream(
         device: &Device,
         config: cpal::SupportedStreamConfig,
         audio_buffer: Arc<Mutex<VecDeque<f32>>>,
+        stereo_buffer: StereoBuffer,
+        input_channel: Arc<AtomicUsize>,
+        device_healthy: Arc<AtomicBool>,
     ) -> Result<Stream> {
         let sample_format = config.sample_format();
         let config: StreamConfig = config.into();
 
         let stream = match sample_format {
-            SampleFormat::F32 => device.build_input_stream(
-                &config,
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    Self::write_input_data(data, &audio_buffer);
-                },
-                |err| eprintln!("Error in audio stream: {}", err),
-                None,
-            )?,
-            SampleFormat::I16 => device.build_input_stream(
-                &config,
-                move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                    let float_data: Vec<f32> = data.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
-                    Self::write_input_data(&float_data, &audio_buffer);
-                },
-                |err| eprintln!("Error in audio stream: {}", err),
-                None,
-            )?,
-            SampleFormat::U16 => device.build_input_stream(
-                &config,
-                move |data: &[u16], _: &cpal::InputCallbackInfo| {
-                    let float_data: Vec<f32> = data.iter()
-                        .map(|&s| (s as f32 - u16::MAX as f32 / 2.0) / (u16::MAX as f32 / 2.0))
-                        .collect();
-                    Self::write_input_data(&float_data, &audio_buffer);
-                },
-                |err| eprintln!("Error in audio stream: {}", err),
-                None,
-            )?,
+            SampleFormat::F32 => Self::build_converting_stream(device, &config, audio_buffer, stereo_buffer, input_channel, device_healthy, |s: f32| s)?,
+            SampleFormat::F64 => Self::build_converting_stream(device, &config, audio_buffer, stereo_buffer, input_channel, device_healthy, f64_to_f32)?,
+            SampleFormat::I8 => Self::build_converting_stream(device, &config, audio_buffer, stereo_buffer, input_channel, device_healthy, i8_to_f32)?,
+            SampleFormat::I16 => Self::build_converting_stream(device, &config, audio_buffer, stereo_buffer, input_channel, device_healthy, i16_to_f32)?,
+            SampleFormat::I32 => Self::build_converting_stream(device, &config, audio_buffer, stereo_buffer, input_channel, device_healthy, i32_to_f32)?,
+            SampleFormat::I64 => Self::build_converting_stream(device, &config, audio_buffer, stereo_buffer, input_channel, device_healthy, i64_to_f32)?,
+            SampleFormat::U8 => Self::build_converting_stream(device, &config, audio_buffer, stereo_buffer, input_channel, device_healthy, u8_to_f32)?,
+            SampleFormat::U16 => Self::build_converting_stream(device, &config, audio_buffer, stereo_buffer, input_channel, device_healthy, u16_to_f32)?,
+            SampleFormat::U32 => Self::build_converting_stream(device, &config, audio_buffer, stereo_buffer, input_channel, device_healthy, u32_to_f32)?,
+            SampleFormat::U64 => Self::build_converting_stream(device, &config, audio_buffer, stereo_buffer, input_channel, device_healthy, u64_to_f32)?,
             _ => return Err(anyhow!("Unsupported sample format: {:?}", sample_format)),
         };
 
+        println!("🎤 Input sample format: {:?}", sample_format);
+
         stream.play()?;
         Ok(stream)
     }
 
-    fn write_input_data(input: &[f32], buffer: &Arc<Mutex<VecDeque<f32>>>) {
+    /// Build an input stream that normalizes samples of type `T` to f32
+    fn build_converting_stream<T, F>(
+        device: &Device,
+        config: &StreamConfig,
+        audio_buffer: Arc<Mutex<VecDeque<f32>>>,
+        stereo_buffer: StereoBuffer,
+        input_channel: Arc<AtomicUsize>,
+        device_healthy: Arc<AtomicBool>,
+        convert: F,
+    ) -> Result<Stream>
+    where
+        T: cpal::SizedSample,
+        F: Fn(T) -> f32 + Send + 'static,
+    {
+        let channels = config.channels.max(1) as usize;
+        let mut float_data: Vec<f32> = Vec::new();
+        let stream = device.build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                float_data.clear();
+                float_data.extend(data.iter().map(|&s| convert(s)));
+                let channel = input_channel.load(Ordering::Relaxed);
+                Self::write_input_data(&float_data, &audio_buffer, Some(&stereo_buffer), channels, channel);
+            },
+            move |err| {
+                // A vanished device never recovers on its own; `reconnect` opens a new stream
+                if let cpal::StreamError::DeviceNotAvailable = err {
+                    device_healthy.store(false, Ordering::Relaxed);
+                }
+                eprintln!("Error in audio stream: {}", err);
+            },
+            None,
+        )?;
+        Ok(stream)
+    }
+
+    /// Append an interleaved `channels`-wide block to the analysis buffers: the selected
+    /// `channel` alone when valid, otherwise a per-frame mono downmix plus the first two
+    /// channels as left/right frames for stereo analysis
+    fn write_input_data(
+        input: &[f32],
+        buffer: &Arc<Mutex<VecDeque<f32>>>,
+        stereo_buffer: Option<&StereoBuffer>,
+        channels: usize,
+        channel: usize,
+    ) {
+        let channels = channels.max(1);
         if let Ok(mut buffer) = buffer.lock() {
-            for &sample in input {
+            for frame in input.chunks_exact(channels) {
+                let sample = if channel < channels {
+                    frame[channel]
+                } else {
+                    frame.iter().sum::<f32>() / channels as f32
+                };
                 if buffer.len() >= BUFFER_SIZE * 4 {
                     buffer.pop_front();
                 }
                 buffer.push_back(sample);
             }
         }
+
+        if let Some(stereo_buffer) = stereo_buffer {
+            if channels >= 2 && channel >= channels {
+                // Straight into the deque: this runs on the audio callback, so no allocation
+                Self::write_stereo_frames(input.chunks_exact(channels).map(|frame| [frame[0], frame[1]]), stereo_buffer);
+            }
+        }
+    }
+
+    fn write_stereo_frames(frames: impl ExactSizeIterator<Item = [f32; 2]>, buffer: &StereoBuffer) {
+        if frames.len() == 0 {
+            return;
+        }
+        if let Ok(mut buffer) = buffer.lock() {
+            for frame in frames {
+                if buffer.len() >= BUFFER_SIZE * 4 {
+                    buffer.pop_front();
+                }
+                buffer.push_back(frame);
+            }
+        }
     }
 
     pub fn process_frame(&mut self) -> Result<AudioFeatures> {
+        self.poll_playlist();
+        self.poll_replay_gain_scan();
+        self.apply_loop_region();
+        self.generate_test_signal();
+        self.decode_offline_frame();
         let samples = self.get_audio_samples();
 
-        if samples.len() < BUFFER_SIZE {
+        if samples.len() < MIN_ANALYSIS_SAMPLES {
+            self.analysis_state = AnalysisState::WaitingForSamples;
             return Ok(AudioFeatures::new());
         }
+        if let Some(event) = self.pending_seek.take() {
+            if let Some(callback) = &mut self.seek_callback {
+                callback(&event);
+            }
+        }
 
-        let frequency_bins = self.fft_analyzer.process_audio(&samples);
-
-        // Use advanced analyzer for full temporal analysis including spectral flux and dynamic range
-        let time_domain_samples = if samples.len() >= BUFFER_SIZE {
-            Some(&samples[..BUFFER_SIZE])
+        let window = &samples[..samples.len().min(BUFFER_SIZE)];
+        let peak = window.iter().fold(0.0f32, |acc, &x| acc.max(x.abs()));
+        self.analysis_state = if peak < SILENCE_THRESHOLD {
+            AnalysisState::Silent
+        } else if window.len() < BUFFER_SIZE {
+            AnalysisState::Partial
         } else {
-            None
+            AnalysisState::Active
+        };
+
+        // Full windows go to the GPU when selected; a failing GPU drops back to the CPU for good
+        self.spectrum_on_gpu = false;
+        if window.len() == BUFFER_SIZE {
+            if let Some(gpu_fft) = self.gpu_fft.as_mut() {
+                match gpu_fft.process(window).map(|_| ()) {
+                    Ok(()) => self.spectrum_on_gpu = true,
+                    Err(e) => {
+                        eprintln!("⚠️  GPU FFT failed, using CPU FFT: {}", e);
+                        self.gpu_fft = None;
+                    }
+                }
+            }
+        }
+
+        // Short buffers are tapered and zero-padded rather than discarded
+        let frequency_bins = match &self.gpu_fft {
+            Some(gpu_fft) if self.spectrum_on_gpu => gpu_fft.spectrum(),
+            _ => self.fft_analyzer.process_audio_padded(window),
         };
 
-        let features = self.advanced_analyzer.analyze_with_context(
+        // Use advanced analyzer for full temporal analysis including spectral flux and dynamic range
+        let mut features = self.advanced_analyzer.analyze_with_context(
             frequency_bins,
-            time_domain_samples
+            Some(window)
         );
 
+        // Stereo image over the same span of frames (mono sources leave both at 0.0)
+        let (stereo_balance, stereo_width) = self.stereo_image();
+        features.stereo_balance = stereo_balance;
+        features.stereo_width = stereo_width;
+
+        let frame_time = Duration::from_secs_f32(1.0 / self.advanced_analyzer.frame_rate());
+        self.noise_gate.process(&mut features, frame_time);
+
+        // Latency compensation only applies to file playback, where we control timing
+        if self.playback_position().is_some() {
+            return Ok(self.feature_delay.push(features));
+        }
         Ok(features)
     }
 
+    /// FFT magnitudes from the most recent `process_frame` (empty while waiting for samples)
+    pub fn spectrum(&self) -> &[f32] {
+        match self.analysis_state {
+            AnalysisState::WaitingForSamples => &[],
+            _ => match &self.gpu_fft {
+                Some(gpu_fft) if self.spectrum_on_gpu => gpu_fft.spectrum(),
+                _ => self.fft_analyzer.spectrum(),
+            },
+        }
+    }
+
+    /// State of the most recent `process_frame` call
+    pub fn analysis_state(&self) -> AnalysisState {
+        self.analysis_state
+    }
+
+    /// Balance (-1.0 left to 1.0 right) and mid/side width (0.0 mono to 1.0 wide) of the
+    /// newest analysis window of left/right frames
+    fn stereo_image(&self) -> (f32, f32) {
+        let Ok(buffer) = self.stereo_buffer.lock() else {
+            return (0.0, 0.0);
+        };
+        let skip = buffer.len().saturating_sub(BUFFER_SIZE);
+        let frames: Vec<[f32; 2]> = buffer.iter().skip(skip).copied().collect();
+        measure_stereo_image(&frames)
+    }
+
+    /// Separate left and right FFT magnitudes over the newest analysis window
+    ///
+    /// Mono sources (and single-channel extraction) have no left/right frames, so both
+    /// halves are the mono spectrum.
+    pub fn stereo_spectra(&self) -> (Vec<f32>, Vec<f32>) {
+        let (left, right): (Vec<f32>, Vec<f32>) = match self.stereo_buffer.lock() {
+            Ok(buffer) => {
+                let skip = buffer.len().saturating_sub(BUFFER_SIZE);
+                buffer.iter().skip(skip).map(|&[left, right]| (left, right)).unzip()
+            }
+            Err(_) => (Vec::new(), Vec::new()),
+        };
+
+        if left.is_empty() {
+            let mono = self.spectrum().to_vec();
+            return (mono.clone(), mono);
+        }
+        self.fft_analyzer.process_stereo(&left, &right)
+    }
+
+    /// Newest `count` mono samples, oldest first (fewer while the buffer fills)
+    pub fn recent_samples(&self, count: usize) -> Vec<f32> {
+        let Ok(buffer) = self.audio_buffer.lock() else {
+            return Vec::new();
+        };
+        let skip = buffer.len().saturating_sub(count);
+        buffer.iter().skip(skip).copied().collect()
+    }
+
+    /// Feed the next frame of the test signal through the same path as live input
+    fn generate_test_signal(&mut self) {
+        let frame_samples = (self.sample_rate / self.advanced_analyzer.frame_rate()).round().max(1.0) as usize;
+        if let Some(generator) = self.signal_generator.as_mut() {
+            let samples = generator.take_samples(frame_samples);
+            Self::write_input_data(&samples, &self.audio_buffer, None, 1, ALL_INPUT_CHANNELS);
+        }
+    }
+
+    /// Decode one analysis frame of the offline file into the analysis buffers
+    fn decode_offline_frame(&mut self) {
+        let frame_rate = self.advanced_analyzer.frame_rate() as f64;
+        let sample_rate = self.sample_rate as f64;
+        let Some(decoder) = self.offline_decoder.as_mut() else {
+            return;
+        };
+
+        // Frame boundaries come from the frame count, so fractional samples per frame don't accumulate
+        decoder.analysis_frames += 1;
+        let target = (decoder.analysis_frames as f64 * sample_rate / frame_rate).round() as u64;
+        let wanted = target.saturating_sub(decoder.frames_decoded) as usize * decoder.channels as usize;
+
+        let samples: Vec<f32> = decoder.samples.by_ref().take(wanted).collect();
+        if samples.len() < wanted {
+            decoder.finished = true;
+        }
+        decoder.frames_decoded += (samples.len() / decoder.channels as usize) as u64;
+
+        let channels = decoder.channels as usize;
+        Self::write_input_data(&samples, &self.audio_buffer, Some(&self.stereo_buffer), channels, ALL_INPUT_CHANNELS);
+    }
+
     fn get_audio_samples(&self) -> Vec<f32> {
         if let Ok(buffer) = self.audio_buffer.lock() {
             buffer.iter().copied().collect()
@@ -151,72 +943,956 @@ impl AudioProcessor {
         }
     }
 
+    /// Decode and play an audio file
+    ///
+    /// With replay gain enabled (the default) the file is leveled to `TARGET_LOUDNESS_LUFS`.
+    /// The first play starts at unity gain while a background scan measures it; the result is
+    /// applied once `process_frame` picks it up and is cached per path for later plays.
     pub fn play_from_file(&mut self, file_path: &str) -> Result<()> {
-        if let Some(ref sink) = self.sink {
-            let file = std::fs::File::open(file_path)?;
-            let decoder = Decoder::new(file)?;
-            sink.append(decoder);
-
-            // Apply current volume setting
-            sink.set_volume(self.volume);
+        if self.sink.is_none() {
+            return Err(anyhow!("No audio output available"));
+        }
+        let file = std::fs::File::open(file_path)?;
+        let path = PathBuf::from(file_path);
+        let cached_gain_db = self.replay_gains.get(&path).copied();
+        let gain_db = if self.replay_gain_enabled { cached_gain_db.unwrap_or(0.0) } else { 0.0 };
+        self.play_reader_with_gain(file, gain_db)?;
 
-            Ok(())
-        } else {
-            Err(anyhow!("No audio output available"))
+        if self.replay_gain_enabled && cached_gain_db.is_none() {
+            let (sender, receiver) = channel();
+            let scan_path = path.clone();
+            std::thread::spawn(move || {
+                let _ = sender.send(Self::measure_loudness(&scan_path));
+            });
+            self.replay_gain_scan = Some((path.clone(), receiver));
         }
+        self.current_file = Some(path);
+        Ok(())
     }
 
-    pub fn is_playing(&self) -> bool {
-        self.sink.as_ref().map_or(false, |sink| !sink.empty())
+    /// Decode and play audio from any seekable reader, e.g. a buffer received over the network
+    ///
+    /// Streams can't be scanned ahead, so they play without replay gain.
+    pub fn play_from_reader<R>(&mut self, reader: R) -> Result<()>
+    where
+        R: Read + Seek + Send + Sync + 'static,
+    {
+        self.play_reader_with_gain(reader, 0.0)
     }
 
-    pub fn stop(&self) {
-        if let Some(ref sink) = self.sink {
-            sink.stop();
+    fn play_reader_with_gain<R>(&mut self, reader: R, gain_db: f32) -> Result<()>
+    where
+        R: Read + Seek + Send + Sync + 'static,
+    {
+        if self.sink.is_none() {
+            return Err(anyhow!("No audio output available"));
         }
-    }
 
-    pub fn pause(&self) {
+        let source = self.open_reader_source(reader)?;
+        self.track_gain_db = gain_db;
+
         if let Some(ref sink) = self.sink {
-            sink.pause();
+            sink.append(source);
+
+            // Apply current volume setting
+            sink.set_volume(self.output_volume());
         }
+
+        Ok(())
     }
 
-    /// Set volume level (0.0 = silent, 1.0 = full volume)
-    pub fn set_volume(&mut self, volume: f32) {
-        // Clamp volume to valid range
-        self.volume = volume.clamp(0.0, 1.0);
+    /// Cache a finished loudness scan and, if its file is still playing, apply the replay
+    /// gain (0 dB if it couldn't be measured)
+    fn poll_replay_gain_scan(&mut self) {
+        let Some((path, receiver)) = &self.replay_gain_scan else {
+            return;
+        };
+        let scan = match receiver.try_recv() {
+            Ok(scan) => scan,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.replay_gain_scan = None;
+                return;
+            }
+        };
+        let gain_db = match scan {
+            Ok((Some(loudness), peak)) => {
+                let gain_db = replay_gain_db(loudness, peak);
+                println!("🎚️  Loudness {:.1} LUFS, replay gain {:+.1} dB", loudness, gain_db);
+                gain_db
+            }
+            Ok((None, _)) => 0.0, // Silent or too short to measure
+            Err(e) => {
+                println!("⚠️  Loudness scan failed ({}), playing at unity gain", e);
+                0.0
+            }
+        };
+        self.replay_gains.insert(path.clone(), gain_db);
+        self.replay_gain_scan = None;
 
-        // Apply volume to sink if available
-        if let Some(ref sink) = self.sink {
-            sink.set_volume(self.volume);
+        if self.replay_gain_enabled {
+            self.track_gain_db = gain_db;
+            if let Some(ref sink) = self.sink {
+                sink.set_volume(self.output_volume());
+            }
         }
-
-        println!("🔊 Volume set to: {:.0}%", self.volume * 100.0);
     }
 
-    /// Get current volume level
-    pub fn get_volume(&self) -> f32 {
-        self.volume
+    /// Integrated loudness of a whole file in LUFS (None if it can't be measured) and its
+    /// sample peak, decoding it separately from playback
+    pub fn measure_loudness(path: &Path) -> Result<(Option<f32>, f32)> {
+        let decoder = Decoder::new(std::fs::File::open(path)?)?;
+        let mut meter = LoudnessMeter::new(decoder.channels(), decoder.sample_rate() as f32);
+        meter.push(decoder.convert_samples::<f32>());
+        Ok((meter.integrated_lufs(), meter.sample_peak()))
     }
 
-    pub fn resume(&self) {
-        if let Some(ref sink) = self.sink {
-            sink.play();
+    /// Level files toward a common loudness on load (on by default); turning it off
+    /// returns the current track to unity gain
+    pub fn set_replay_gain_enabled(&mut self, enabled: bool) {
+        self.replay_gain_enabled = enabled;
+        if !enabled {
+            self.track_gain_db = 0.0;
+            if let Some(ref sink) = self.sink {
+                sink.set_volume(self.output_volume());
+            }
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    pub fn replay_gain_enabled(&self) -> bool {
+        self.replay_gain_enabled
+    }
 
-    #[test]
-    fn test_audio_processor_creation() {
+    /// Correction applied to the current file in dB (0.0 for streams, live input and playlists)
+    pub fn track_gain_db(&self) -> f32 {
+        self.track_gain_db
+    }
+
+    /// Sink volume: the user volume with the current track's replay gain
+    fn output_volume(&self) -> f32 {
+        self.volume * db_to_gain(self.track_gain_db)
+    }
+
+    /// Open a file for playback and reconfigure analysis for its channel count and sample rate
+    pub fn open_file_source(&mut self, file_path: &str) -> Result<AnalysisTap<impl Source<Item = f32> + Send + 'static>> {
+        let file = std::fs::File::open(file_path)?;
+        self.open_reader_source(file)
+    }
+
+    /// Open a seekable reader for playback and reconfigure analysis for its layout
+    pub fn open_reader_source<R>(&mut self, reader: R) -> Result<AnalysisTap<impl Source<Item = f32> + Send + 'static>>
+    where
+        R: Read + Seek + Send + Sync + 'static,
+    {
+        let decoder = Decoder::new(reader)?;
+
+        // A single file replaces any playlist in progress
+        self.playlist = None;
+        self.queued_tracks.clear();
+        self.current_file = None;
+        self.replay_gain_scan = None;
+
+        let channels = decoder.channels().max(1);
+        let sample_rate = decoder.sample_rate() as f32;
+        self.configure_for_source(channels, sample_rate);
+        self.current_duration = decoder.total_duration();
+        self.pause_input();
+
+        println!("🎼 File source: {} channel(s) @ {} Hz", channels, sample_rate);
+        Ok(AnalysisTap::with_lookahead(
+            decoder.convert_samples::<f32>(),
+            Arc::clone(&self.audio_buffer),
+            Arc::clone(&self.stereo_buffer),
+            Arc::clone(&self.lookahead_samples),
+        ))
+    }
+
+    /// Queue every playlist entry from its current one onward for gapless playback
+    pub fn play_playlist(&mut self, playlist: Playlist) -> Result<()> {
+        if playlist.is_empty() {
+            return Err(anyhow!("Playlist is empty"));
+        }
+        let start = playlist.current_index();
+        self.playlist = Some(playlist);
+        self.queue_playlist_from(start)
+    }
+
+    /// Path of the file playing now: the current playlist entry, or the file from `play_from_file`
+    pub fn current_track(&self) -> Option<&Path> {
+        match &self.playlist {
+            Some(playlist) => playlist.current(),
+            None => self.current_file.as_deref(),
+        }
+    }
+
+    /// The playlist being played, with its cursor on the current entry
+    pub fn playlist(&self) -> Option<&Playlist> {
+        self.playlist.as_ref()
+    }
+
+    /// Skip to the next playlist entry
+    pub fn next_track(&mut self) -> Result<()> {
+        let playlist = self.playlist.as_ref().ok_or_else(|| anyhow!("No playlist loaded"))?;
+        let next = playlist.current_index() + 1;
+        if next >= playlist.len() {
+            return Err(anyhow!("Already on the last track"));
+        }
+        self.queue_playlist_from(next)
+    }
+
+    /// Go back one playlist entry; on the first entry, restart it
+    pub fn previous_track(&mut self) -> Result<()> {
+        let playlist = self.playlist.as_ref().ok_or_else(|| anyhow!("No playlist loaded"))?;
+        self.queue_playlist_from(playlist.current_index().saturating_sub(1))
+    }
+
+    /// Install or remove the track-change callback
+    pub fn set_track_change_callback(&mut self, callback: Option<TrackChangeCallback>) {
+        self.track_change_callback = callback;
+    }
+
+    /// Replace the callback with a channel; events are dropped once the receiver goes away
+    pub fn track_changes(&mut self) -> Receiver<TrackChanged> {
+        let (sender, receiver) = channel();
+        self.track_change_callback = Some(Box::new(move |event: &TrackChanged| {
+            let _ = sender.send(event.clone());
+        }));
+        receiver
+    }
+
+    /// Install or remove the seek callback
+    pub fn set_seek_callback(&mut self, callback: Option<SeekCallback>) {
+        self.seek_callback = callback;
+    }
+
+    /// Replace the seek callback with a channel; events are dropped once the receiver goes away
+    pub fn seeks(&mut self) -> Receiver<Seeked> {
+        let (sender, receiver) = channel();
+        self.seek_callback = Some(Box::new(move |event: &Seeked| {
+            let _ = sender.send(*event);
+        }));
+        receiver
+    }
+
+    /// Replace the sink's queue with playlist entries `index..`
+    fn queue_playlist_from(&mut self, index: usize) -> Result<()> {
+        let sink = self.sink.as_ref().ok_or_else(|| anyhow!("No audio output available"))?;
+        let playlist = self.playlist.as_mut().ok_or_else(|| anyhow!("No playlist loaded"))?;
+        playlist.select(index)?;
+
+        // Open every remaining entry up front so a bad file fails now rather than mid-album
+        let mut decoders = Vec::new();
+        let mut layouts = Vec::new();
+        for path in &playlist.tracks()[index..] {
+            let file = std::fs::File::open(path)
+                .map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?;
+            let decoder = Decoder::new(file)
+                .map_err(|e| anyhow!("Failed to decode {}: {}", path.display(), e))?;
+            layouts.push(QueuedTrack {
+                channels: decoder.channels().max(1),
+                sample_rate: decoder.sample_rate() as f32,
+                duration: decoder.total_duration(),
+            });
+            decoders.push(decoder);
+        }
+
+        // Sources appended back to back play without gaps
+        if !sink.empty() {
+            sink.clear();
+        }
+        for decoder in decoders {
+            sink.append(AnalysisTap::with_lookahead(
+                decoder.convert_samples::<f32>(),
+                Arc::clone(&self.audio_buffer),
+                Arc::clone(&self.stereo_buffer),
+                Arc::clone(&self.lookahead_samples),
+            ));
+        }
+        sink.set_volume(self.volume); // One sink volume covers every entry, so no replay gain
+        sink.play();
+
+        self.track_gain_db = 0.0;
+        self.replay_gain_scan = None;
+        self.queue_start = index;
+        self.queued_tracks = layouts;
+        self.pause_input();
+        self.enter_track(index);
+        Ok(())
+    }
+
+    /// Notice when the sink has moved on to the next queued entry
+    fn poll_playlist(&mut self) {
+        let (Some(sink), Some(playlist)) = (&self.sink, &self.playlist) else {
+            return;
+        };
+        let remaining = sink.len();
+        if remaining == 0 {
+            return; // Finished or stopped; the cursor stays on the last entry played
+        }
+        let playing = self.queue_start + self.queued_tracks.len().saturating_sub(remaining);
+        if playing != playlist.current_index() {
+            self.enter_track(playing);
+        }
+    }
+
+    /// Reset analysis for the playlist entry now playing and report the change
+    fn enter_track(&mut self, index: usize) {
+        let Some(track) = index.checked_sub(self.queue_start).and_then(|i| self.queued_tracks.get(i)).copied() else {
+            return;
+        };
+        // Temporal state from the previous track would smear into this one
+        self.configure_for_source(track.channels, track.sample_rate);
+        self.current_duration = track.duration;
+
+        let Some(playlist) = self.playlist.as_mut() else {
+            return;
+        };
+        if playlist.select(index).is_err() {
+            return;
+        }
+        if let Some(event) = playlist.track_changed() {
+            println!("🎶 Track {}/{}: {}", index + 1, playlist.len(), event.path.display());
+            if let Some(callback) = &mut self.track_change_callback {
+                callback(&event);
+            }
+        }
+    }
+
+    /// False once the live input device has been lost (unplugged or disabled)
+    pub fn is_device_healthy(&self) -> bool {
+        self.device_healthy.load(Ordering::Relaxed)
+    }
+
+    /// Rebuild the live input stream on the selected input device (or the current default),
+    /// e.g. after `is_device_healthy` reports a disconnect
+    ///
+    /// Buffered samples and analyzer history carry over unless the new device runs at a
+    /// different sample rate; file playback stays the analysis source if a track is playing.
+    pub fn reconnect(&mut self) -> Result<()> {
+        // Release the dead stream before opening the replacement
+        self.input_stream = None;
+
+        let device = match &self.input_device_selector {
+            Some(selector) => Self::find_input_device(selector)?,
+            None => cpal::default_host()
+                .default_input_device()
+                .ok_or_else(|| anyhow!("No input device available"))?,
+        };
+        let config = device.default_input_config()?;
+        let sample_rate = config.sample_rate().0 as f32;
+        let channels = config.channels();
+
+        self.device_healthy.store(true, Ordering::Relaxed);
+        let stream = Self::build_input_stream(
+            &device,
+            config,
+            Arc::clone(&self.audio_buffer),
+            Arc::clone(&self.stereo_buffer),
+            Arc::clone(&self.input_channel),
+            Arc::clone(&self.device_healthy),
+        )
+        .inspect_err(|_| self.device_healthy.store(false, Ordering::Relaxed))?;
+        self.input_stream = Some(stream);
+
+        // A selected channel the new device lacks falls back to the full downmix
+        self.input_channels = channels;
+        if self.input_channel.load(Ordering::Relaxed) >= channels as usize {
+            self.input_channel.store(ALL_INPUT_CHANNELS, Ordering::Relaxed);
+        }
+
+        if self.is_playing() {
+            self.pause_input();
+        } else if sample_rate != self.sample_rate {
+            self.configure_for_source(channels, sample_rate);
+        } else {
+            self.channels = channels;
+        }
+
+        println!("🎤 Audio input reconnected: {}", device.name().unwrap_or_else(|_| "unknown device".to_string()));
+        Ok(())
+    }
+
+    /// File playback replaces live input as the analysis source
+    fn pause_input(&self) {
+        if let Some(ref stream) = self.input_stream {
+            if let Err(e) = stream.pause() {
+                eprintln!("Failed to pause audio input: {}", e);
+            }
+        }
+    }
+
+    /// Reset analyzers for a new source layout; stale samples (and any test signal) are dropped
+    fn configure_for_source(&mut self, channels: u16, sample_rate: f32) {
+        self.signal_generator = None;
+        self.offline_decoder = None;
+        self.loop_region = None;
+        self.loop_in = None;
+        self.seek_anchor.set(None);
+        self.pending_seek = None;
+        self.channels = channels;
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            let mut analyzer = AdvancedAudioAnalyzer::new(sample_rate);
+            analyzer.set_frame_rate(self.advanced_analyzer.frame_rate());
+            analyzer.set_dynamic_range_window(self.advanced_analyzer.dynamic_range_window());
+            analyzer.set_rolloff_percentile(self.advanced_analyzer.rolloff_percentile());
+            analyzer.set_loudness_weighting(self.advanced_analyzer.loudness_weighting());
+            analyzer.set_silence_floor_db(self.advanced_analyzer.silence_floor_db());
+            analyzer.set_idle_timeout(self.advanced_analyzer.idle_timeout());
+            analyzer.set_hpss_enabled(self.advanced_analyzer.hpss_enabled());
+            analyzer.set_agc(
+                self.advanced_analyzer.agc_enabled(),
+                self.advanced_analyzer.agc_attack(),
+                self.advanced_analyzer.agc_release(),
+            );
+            for band in Band::ALL {
+                let (attack, release) = self.advanced_analyzer.band_envelope(band);
+                analyzer.set_band_envelope(band, attack, release);
+            }
+            self.advanced_analyzer = analyzer;
+        } else {
+            self.advanced_analyzer.reset();
+        }
+        self.noise_gate.reset();
+
+        self.clear_buffers();
+        self.apply_latency_offset();
+    }
+
+    /// Drop buffered samples so a new source or position starts with a clean window
+    fn clear_buffers(&mut self) {
+        self.feature_delay.clear();
+        if let Ok(mut buffer) = self.audio_buffer.lock() {
+            buffer.clear();
+        }
+        if let Ok(mut buffer) = self.stereo_buffer.lock() {
+            buffer.clear();
+        }
+        self.fft_analyzer.reset_smoothing();
+        if let Some(gpu_fft) = self.gpu_fft.as_mut() {
+            gpu_fft.reset_smoothing();
+        }
+    }
+
+    /// Tell the analyzer how often `process_frame` runs so history windows keep their duration
+    pub fn set_analysis_frame_rate(&mut self, frame_rate: f32) {
+        self.advanced_analyzer.set_frame_rate(frame_rate);
+        self.apply_latency_offset();
+    }
+
+    /// Shift file-playback visuals relative to the audio, clamped to ±`MAX_LATENCY_OFFSET_MS`.
+    /// Positive values delay analyzed features by whole frames; negative values make the file
+    /// tap analyze samples that far ahead of playback. Live input is never shifted.
+    pub fn set_latency_offset(&mut self, ms: i32) {
+        self.latency_offset_ms = ms.clamp(-MAX_LATENCY_OFFSET_MS, MAX_LATENCY_OFFSET_MS);
+        self.apply_latency_offset();
+    }
+
+    pub fn latency_offset_ms(&self) -> i32 {
+        self.latency_offset_ms
+    }
+
+    /// Convert the offset into a feature delay (frames) or a tap lookahead (whole sample frames)
+    fn apply_latency_offset(&mut self) {
+        let seconds = self.latency_offset_ms.unsigned_abs() as f32 / 1000.0;
+        if self.latency_offset_ms >= 0 {
+            let frames = (seconds * self.advanced_analyzer.frame_rate()).round() as usize;
+            self.feature_delay.set_delay_frames(frames);
+            self.lookahead_samples.store(0, Ordering::Relaxed);
+        } else {
+            let sample_frames = (seconds * self.sample_rate).round() as usize;
+            self.feature_delay.set_delay_frames(0);
+            self.lookahead_samples.store(sample_frames * self.channels.max(1) as usize, Ordering::Relaxed);
+        }
+    }
+
+    pub fn set_dynamic_range_window(&mut self, window: Duration) {
+        self.advanced_analyzer.set_dynamic_range_window(window);
+    }
+
+    /// Frequency weighting for `overall_volume` and `signal_level_db` (default `Weighting::None`)
+    pub fn set_loudness_weighting(&mut self, weighting: Weighting) {
+        self.advanced_analyzer.set_loudness_weighting(weighting);
+    }
+
+    pub fn loudness_weighting(&self) -> Weighting {
+        self.advanced_analyzer.loudness_weighting()
+    }
+
+    /// Silence needed before `is_silent` reports true (see `AdvancedAudioAnalyzer::set_idle_timeout`)
+    pub fn set_idle_timeout(&mut self, timeout: Duration) {
+        self.advanced_analyzer.set_idle_timeout(timeout);
+    }
+
+    /// Signal level (dB) below which frames count as silent
+    pub fn set_silence_floor_db(&mut self, floor_db: f32) {
+        self.advanced_analyzer.set_silence_floor_db(floor_db);
+    }
+
+    /// Harmonic/percussive separation for `harmonic_energy` and `percussive_energy` (off by default)
+    pub fn set_hpss_enabled(&mut self, enabled: bool) {
+        self.advanced_analyzer.set_hpss_enabled(enabled);
+    }
+
+    pub fn hpss_enabled(&self) -> bool {
+        self.advanced_analyzer.hpss_enabled()
+    }
+
+    /// Automatic gain control on band energies (off by default; see `AdvancedAudioAnalyzer::set_agc`)
+    pub fn set_agc(&mut self, enabled: bool, attack: Duration, release: Duration) {
+        self.advanced_analyzer.set_agc(enabled, attack, release);
+    }
+
+    pub fn agc_enabled(&self) -> bool {
+        self.advanced_analyzer.agc_enabled()
+    }
+
+    /// Attack/release smoothing on one band's energy (see `AdvancedAudioAnalyzer::set_band_envelope`)
+    pub fn set_band_envelope(&mut self, band: Band, attack: Duration, release: Duration) {
+        self.advanced_analyzer.set_band_envelope(band, attack, release);
+    }
+
+    /// Blank feature output while the level stays under `threshold_db` (None turns the gate off)
+    /// for `hold_ms`, so room noise and mains hum don't move the visuals; see `NoiseGate`
+    pub fn set_noise_gate(&mut self, threshold_db: Option<f32>, hold_ms: u32) {
+        self.noise_gate.set(threshold_db, Duration::from_millis(hold_ms as u64));
+    }
+
+    pub fn noise_gate_threshold_db(&self) -> Option<f32> {
+        self.noise_gate.threshold_db()
+    }
+
+    /// Whether the noise gate is currently blanking frames
+    pub fn is_noise_gated(&self) -> bool {
+        !self.noise_gate.is_open()
+    }
+
+    /// Whether the analyzed signal has been below the silence floor for the idle timeout
+    pub fn is_silent(&self) -> bool {
+        self.advanced_analyzer.is_silent()
+    }
+
+    /// Window function applied before the spectrum FFT (Hann by default)
+    pub fn set_fft_window(&mut self, window: WindowFunction) {
+        self.fft_analyzer.set_window(window);
+        if let Some(gpu_fft) = self.gpu_fft.as_mut() {
+            gpu_fft.set_window(window);
+        }
+    }
+
+    /// Per-bin EMA over successive spectra, ahead of feature extraction (0.0 disables, the default)
+    pub fn set_spectral_smoothing(&mut self, alpha: f32) {
+        self.fft_analyzer.set_spectral_smoothing(alpha);
+        if let Some(gpu_fft) = self.gpu_fft.as_mut() {
+            gpu_fft.set_spectral_smoothing(alpha);
+        }
+    }
+
+    pub fn spectral_smoothing(&self) -> f32 {
+        self.fft_analyzer.spectral_smoothing()
+    }
+
+    /// Choose where the spectrum FFT runs; returns the backend actually in use, which stays
+    /// CPU when no adapter with compute shader support is available
+    pub fn set_fft_backend(&mut self, backend: FftBackend) -> FftBackend {
+        match backend {
+            FftBackend::Cpu => self.gpu_fft = None,
+            FftBackend::Gpu if self.gpu_fft.is_none() => {
+                match GpuFft::new(BUFFER_SIZE, self.fft_analyzer.window_function()) {
+                    Ok(mut gpu_fft) => {
+                        gpu_fft.set_spectral_smoothing(self.fft_analyzer.spectral_smoothing());
+                        println!("⚡ GPU FFT enabled ({} points)", gpu_fft.size());
+                        self.gpu_fft = Some(gpu_fft);
+                    }
+                    Err(e) => println!("⚠️  {} - using CPU FFT", e),
+                }
+            }
+            FftBackend::Gpu => {}
+        }
+        self.fft_backend()
+    }
+
+    pub fn fft_backend(&self) -> FftBackend {
+        if self.gpu_fft.is_some() {
+            FftBackend::Gpu
+        } else {
+            FftBackend::Cpu
+        }
+    }
+
+    /// Sample rate the analyzers are configured for
+    ///
+    /// Audio is analyzed at the source's native rate rather than resampled to 44.1kHz: the
+    /// analyzers convert bins to Hz with this rate, and `RhythmDetector` should be given it too.
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// Channel count of the current source
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Feed only one channel of a multi-channel input to the analyzers (None = use the stream as delivered)
+    pub fn set_input_channel(&mut self, index: Option<usize>) -> Result<()> {
+        match index {
+            Some(index) if index >= self.input_channels as usize => Err(anyhow!(
+                "Input channel {} out of range: the input stream has {} channel(s)",
+                index,
+                self.input_channels
+            )),
+            Some(index) => {
+                self.input_channel.store(index, Ordering::Relaxed);
+                if let Ok(mut buffer) = self.stereo_buffer.lock() {
+                    buffer.clear(); // A single extracted channel has no stereo image
+                }
+                println!("🎚️  Analyzing input channel {} of {}", index + 1, self.input_channels);
+                Ok(())
+            }
+            None => {
+                self.input_channel.store(ALL_INPUT_CHANNELS, Ordering::Relaxed);
+                Ok(())
+            }
+        }
+    }
+
+    /// Selected input channel, if one is being extracted
+    pub fn input_channel(&self) -> Option<usize> {
+        let channel = self.input_channel.load(Ordering::Relaxed);
+        (channel != ALL_INPUT_CHANNELS).then_some(channel)
+    }
+
+    /// Play a short metronome tick at the current volume, returning whether it was played
+    ///
+    /// Clicks go straight to the output device rather than through the sink or an
+    /// `AnalysisTap`, so they never reach file analysis or delay the queued track.
+    /// With live input, use headphones to keep speakers from feeding the microphone.
+    pub fn play_click(&self, click: &BeatClick) -> bool {
+        let Some(ref handle) = self.output_handle else {
+            return false;
+        };
+
+        let freq = if click.downbeat { DOWNBEAT_CLICK_FREQ } else { CLICK_FREQ };
+        let tick = rodio::source::SineWave::new(freq)
+            .take_duration(CLICK_LENGTH)
+            .amplify(click.gain() * self.volume);
+        match handle.play_raw(tick) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Failed to play metronome click: {}", e);
+                false
+            }
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.sink.as_ref().map_or(false, |sink| !sink.empty())
+    }
+
+    pub fn stop(&self) {
+        if let Some(ref sink) = self.sink {
+            sink.stop();
+        }
+
+        // Fall back to live input for analysis
+        if let Some(ref stream) = self.input_stream {
+            if let Err(e) = stream.play() {
+                eprintln!("Failed to resume audio input: {}", e);
+            }
+        }
+    }
+
+    /// Stop playback and release the input stream and output device
+    ///
+    /// Safe to call more than once; returns whether anything was still open.
+    pub fn shutdown(&mut self) -> bool {
+        let had_resources = self.sink.is_some() || self.input_stream.is_some() || self._output_stream.is_some();
+
+        if let Some(sink) = self.sink.take() {
+            sink.stop();
+        }
+        if let Some(stream) = self.input_stream.take() {
+            if let Err(e) = stream.pause() {
+                eprintln!("Failed to stop audio input: {}", e);
+            }
+        }
+        self.output_handle = None;
+        self._output_stream = None;
+        self.current_duration = None;
+
+        had_resources
+    }
+
+    pub fn pause(&self) {
+        if let Some(ref sink) = self.sink {
+            sink.pause();
+        }
+    }
+
+    /// Set volume level (0.0 = silent, 1.0 = full volume)
+    pub fn set_volume(&mut self, volume: f32) {
+        // Clamp volume to valid range
+        self.volume = volume.clamp(0.0, 1.0);
+
+        // Apply volume to sink if available
+        if let Some(ref sink) = self.sink {
+            sink.set_volume(self.output_volume());
+        }
+
+        println!("🔊 Volume set to: {:.0}%", self.volume * 100.0);
+    }
+
+    /// Get current volume level
+    pub fn get_volume(&self) -> f32 {
+        self.volume
+    }
+
+    pub fn resume(&self) {
+        if let Some(ref sink) = self.sink {
+            sink.play();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.sink.as_ref().is_some_and(|sink| sink.is_paused())
+    }
+
+    /// Length of the loaded file, if known
+    pub fn duration(&self) -> Option<Duration> {
+        self.current_duration
+    }
+
+    /// Current playback position within the loaded file
+    pub fn playback_position(&self) -> Option<Duration> {
+        let reported = self.sink.as_ref()
+            .filter(|sink| !sink.empty())
+            .map(|sink| sink.get_pos())?;
+
+        // The sink only refreshes its position from the playback thread every few ms, so
+        // right after a seek it still reports where it was; use the target until that moves
+        match self.seek_anchor.get() {
+            Some((target, stale)) if reported == stale => Some(target),
+            Some(_) => {
+                self.seek_anchor.set(None);
+                Some(reported)
+            }
+            None => Some(reported),
+        }
+    }
+
+    /// Playback position as a fraction of the file length (0.0 to 1.0)
+    pub fn playback_fraction(&self) -> Option<f32> {
+        let duration = self.current_duration.filter(|d| !d.is_zero())?;
+        let position = self.playback_position()?;
+        Some((position.as_secs_f32() / duration.as_secs_f32()).clamp(0.0, 1.0))
+    }
+
+    /// Jump to a position in the loaded file, clamped to its length when known
+    pub fn seek(&mut self, position: Duration) -> Result<()> {
+        let target = self.seek_silently(position)?;
+        println!("⏩ Seeked to {:.1}s", target.as_secs_f32());
+        Ok(())
+    }
+
+    /// `seek` without the log line, for jumps that repeat (loop wraps); returns the target
+    fn seek_silently(&mut self, position: Duration) -> Result<Duration> {
+        let sink = self.sink.as_ref()
+            .filter(|sink| !sink.empty())
+            .ok_or_else(|| anyhow!("Nothing is playing"))?;
+        let target = self.current_duration.map_or(position, |duration| position.min(duration));
+        sink.try_seek(target).map_err(|e| anyhow!("Seek failed: {}", e))?;
+        // The playback thread stores its position just before applying a seek, so this
+        // is the pre-seek value it keeps reporting until its next refresh
+        self.seek_anchor.set(Some((target, sink.get_pos())));
+
+        // Samples from before the jump would smear into the next analysis frames, and onset
+        // history spans it; listeners reset that once the new position is analyzed
+        self.clear_buffers();
+        self.advanced_analyzer.reset();
+        self.pending_seek = Some(Seeked { position: target });
+        Ok(target)
+    }
+
+    /// Loop playback between two positions of the loaded file (None plays straight through).
+    /// The end is kept just short of the end of the file so the loop can't run out of audio.
+    pub fn set_loop_region(&mut self, region: Option<(Duration, Duration)>) -> Result<()> {
+        let Some((start, end)) = region else {
+            self.loop_region = None;
+            self.loop_in = None;
+            println!("🔁 Loop off");
+            return Ok(());
+        };
+
+        let end = self.current_duration.map_or(end, |duration| end.min(duration.saturating_sub(LOOP_END_MARGIN)));
+        if end <= start {
+            return Err(anyhow!("Loop end ({:.1}s) must come after its start ({:.1}s)", end.as_secs_f32(), start.as_secs_f32()));
+        }
+        self.loop_region = Some((start, end));
+        println!("🔁 Looping {:.1}s - {:.1}s", start.as_secs_f32(), end.as_secs_f32());
+        Ok(())
+    }
+
+    pub fn loop_region(&self) -> Option<(Duration, Duration)> {
+        self.loop_region
+    }
+
+    /// Mark the current position as the loop start; an active loop keeps its end when that's
+    /// still after the new start
+    pub fn mark_loop_in(&mut self) -> Result<Duration> {
+        let position = self.playback_position().ok_or_else(|| anyhow!("Nothing is playing"))?;
+        self.loop_in = Some(position);
+        self.loop_region = self.loop_region.filter(|&(_, end)| end > position).map(|(_, end)| (position, end));
+        println!("🔁 Loop in at {:.1}s", position.as_secs_f32());
+        Ok(position)
+    }
+
+    /// Loop from the marked start (or the start of the file) to the current position
+    pub fn mark_loop_out(&mut self) -> Result<()> {
+        let position = self.playback_position().ok_or_else(|| anyhow!("Nothing is playing"))?;
+        let start = self.loop_in.unwrap_or(Duration::ZERO);
+        self.set_loop_region(Some((start, position)))?;
+        self.loop_in = Some(start);
+        Ok(())
+    }
+
+    /// Jump back to the loop start once playback passes the loop end. Analysis restarts
+    /// clean there, and the wrap is reported like a seek so onset history can be reset.
+    fn apply_loop_region(&mut self) {
+        let (Some(region), Some(position)) = (self.loop_region, self.playback_position()) else {
+            return;
+        };
+        if let Some(start) = loop_seek_target(region, position) {
+            if let Err(e) = self.seek_silently(start) {
+                eprintln!("⚠️  Loop wrap failed, looping off: {}", e);
+                self.loop_region = None;
+            }
+        }
+    }
+
+    /// Seek to a fraction (0.0 to 1.0) of the loaded file
+    pub fn seek_fraction(&mut self, fraction: f32) -> Result<()> {
+        let duration = self.current_duration
+            .ok_or_else(|| anyhow!("Seeking requires a source with known duration"))?;
+        self.seek(duration.mul_f32(fraction.clamp(0.0, 1.0)))
+    }
+
+    /// Skip forward (positive) or rewind (negative) by a number of seconds, stopping at the start
+    pub fn seek_by(&mut self, offset_secs: f32) -> Result<()> {
+        let position = self.playback_position()
+            .ok_or_else(|| anyhow!("Nothing is playing"))?;
+        let target = (position.as_secs_f32() + offset_secs).max(0.0);
+        self.seek(Duration::from_secs_f32(target))
+    }
+}
+
+/// Stereo balance and width of left/right frames; silence reads as centered and mono
+fn measure_stereo_image(frames: &[[f32; 2]]) -> (f32, f32) {
+    if frames.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let mut left_energy = 0.0;
+    let mut right_energy = 0.0;
+    let mut mid_energy = 0.0;
+    let mut side_energy = 0.0;
+    for &[left, right] in frames {
+        left_energy += left * left;
+        right_energy += right * right;
+        let mid = (left + right) * 0.5;
+        let side = (left - right) * 0.5;
+        mid_energy += mid * mid;
+        side_energy += side * side;
+    }
+
+    let count = frames.len() as f32;
+    let left_rms = (left_energy / count).sqrt();
+    let right_rms = (right_energy / count).sqrt();
+    if left_rms + right_rms < SILENCE_THRESHOLD {
+        return (0.0, 0.0);
+    }
+
+    let balance = (right_rms - left_rms) / (right_rms + left_rms);
+
+    // Side as strong as mid (decorrelated or hard-panned) counts as fully wide
+    let mid_rms = (mid_energy / count).sqrt();
+    let side_rms = (side_energy / count).sqrt();
+    let width = (2.0 * side_rms / (mid_rms + side_rms)).min(1.0);
+
+    (balance, width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_processor_creation() {
         let processor = AudioProcessor::new_default();
         assert_eq!(processor.sample_rate, SAMPLE_RATE as f32);
     }
 
+    #[test]
+    fn test_list_input_devices_without_hardware() {
+        // Headless machines have no inputs; the list is simply empty
+        let devices = AudioProcessor::list_input_devices();
+        for (position, device) in devices.iter().enumerate() {
+            assert_eq!(device.index, position);
+        }
+    }
+
+    #[test]
+    fn test_match_input_device_by_index_or_name() {
+        let device = |index: usize, name: &str| DeviceInfo {
+            index,
+            name: name.to_string(),
+            is_default: index == 0,
+            channels: Some(2),
+            sample_rate: Some(48000),
+        };
+        let devices = vec![device(0, "HD Webcam Microphone"), device(1, "Focusrite USB Audio"), device(2, "USB Audio Loopback")];
+
+        assert_eq!(match_input_device(&devices, "1"), Some(1));
+        assert_eq!(match_input_device(&devices, "focusrite"), Some(1));
+        assert_eq!(match_input_device(&devices, "USB AUDIO"), Some(1)); // First match wins
+        assert_eq!(match_input_device(&devices, "7"), None);
+        assert_eq!(match_input_device(&devices, "Scarlett"), None);
+        assert_eq!(match_input_device(&[], "0"), None);
+    }
+
+    #[test]
+    fn test_loop_seeks_back_at_region_end() {
+        let region = (Duration::from_secs(80), Duration::from_secs(95));
+
+        // 1:20-1:35 plays through untouched, then wraps to 1:20 at or past 1:35
+        assert_eq!(loop_seek_target(region, Duration::from_secs(80)), None);
+        assert_eq!(loop_seek_target(region, Duration::from_millis(94_990)), None);
+        assert_eq!(loop_seek_target(region, Duration::from_secs(95)), Some(Duration::from_secs(80)));
+        assert_eq!(loop_seek_target(region, Duration::from_millis(95_016)), Some(Duration::from_secs(80)));
+
+        // Before the region, playback runs into it; an empty region never loops
+        assert_eq!(loop_seek_target(region, Duration::from_secs(10)), None);
+        assert_eq!(loop_seek_target((Duration::from_secs(5), Duration::from_secs(5)), Duration::from_secs(6)), None);
+    }
+
+    #[test]
+    fn test_loop_region_validation() {
+        let mut processor = AudioProcessor::new_default();
+        processor.current_duration = Some(Duration::from_secs(120));
+
+        processor.set_loop_region(Some((Duration::from_secs(80), Duration::from_secs(95)))).unwrap();
+        assert_eq!(processor.loop_region(), Some((Duration::from_secs(80), Duration::from_secs(95))));
+
+        // An end past the file is pulled in so the sink never runs dry
+        processor.set_loop_region(Some((Duration::from_secs(100), Duration::from_secs(200)))).unwrap();
+        assert_eq!(processor.loop_region(), Some((Duration::from_secs(100), Duration::from_secs(120) - LOOP_END_MARGIN)));
+
+        assert!(processor.set_loop_region(Some((Duration::from_secs(95), Duration::from_secs(80)))).is_err());
+        assert!(processor.mark_loop_out().is_err()); // Nothing is playing
+
+        processor.set_loop_region(None).unwrap();
+        assert_eq!(processor.loop_region(), None);
+    }
+
     #[test]
     fn test_process_frame_empty() {
         let mut processor = AudioProcessor::new_default();
@@ -227,6 +1903,710 @@ mod tests {
         assert_eq!(features.overall_volume, 0.0);
     }
 
+    #[test]
+    fn test_half_window_sine_is_zero_padded() {
+        let mut processor = AudioProcessor::new_default();
+        {
+            let mut buffer = processor.audio_buffer.lock().unwrap();
+            for i in 0..BUFFER_SIZE / 2 {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                buffer.push_back(0.8 * (2.0 * std::f32::consts::PI * 440.0 * t).sin());
+            }
+        }
+
+        let features = processor.process_frame().unwrap();
+        assert_eq!(processor.analysis_state(), AnalysisState::Partial);
+        assert!(features.mid > 0.0, "Half-window sine should still produce band energy");
+        assert!(features.overall_volume > 0.0);
+    }
+
+    #[test]
+    fn test_device_disconnect_reports_unhealthy() {
+        let processor = AudioProcessor::new_default();
+        assert!(processor.is_device_healthy());
+
+        // What the stream error callback does on `StreamError::DeviceNotAvailable`
+        processor.device_healthy.store(false, Ordering::Relaxed);
+        assert!(!processor.is_device_healthy());
+    }
+
+    #[test]
+    fn test_reconnect_backoff_doubles_until_capped() {
+        let start = Instant::now();
+        let mut backoff = ReconnectBackoff::new();
+        assert!(backoff.ready(start));
+
+        assert_eq!(backoff.failed(start), RECONNECT_INITIAL_DELAY);
+        assert!(!backoff.ready(start));
+        assert!(backoff.ready(start + RECONNECT_INITIAL_DELAY));
+
+        assert_eq!(backoff.failed(start), RECONNECT_INITIAL_DELAY * 2);
+        for _ in 0..10 {
+            backoff.failed(start);
+        }
+        assert_eq!(backoff.failed(start), RECONNECT_MAX_DELAY);
+
+        backoff.reset();
+        assert!(backoff.ready(start));
+    }
+
+    #[test]
+    fn test_waiting_vs_silent_states() {
+        let mut processor = AudioProcessor::new_default();
+        processor.process_frame().unwrap();
+        assert_eq!(processor.analysis_state(), AnalysisState::WaitingForSamples);
+
+        {
+            let mut buffer = processor.audio_buffer.lock().unwrap();
+            buffer.extend(std::iter::repeat_n(0.0, BUFFER_SIZE));
+        }
+        processor.process_frame().unwrap();
+        assert_eq!(processor.analysis_state(), AnalysisState::Silent);
+    }
+
+    /// Write a short 16-bit PCM WAV file of silence
+    fn write_test_wav(path: &std::path::Path, channels: u16, sample_rate: u32, frames: u32) {
+        std::fs::write(path, test_wav_bytes(channels, sample_rate, frames)).unwrap();
+    }
+
+    /// A short 16-bit PCM WAV of silence, in memory
+    fn test_wav_bytes(channels: u16, sample_rate: u32, frames: u32) -> Vec<u8> {
+        let bytes_per_frame = channels as u32 * 2;
+        let data_len = frames * bytes_per_frame;
+        let mut wav = Vec::with_capacity(44 + data_len as usize);
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&channels.to_le_bytes());
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * bytes_per_frame).to_le_bytes());
+        wav.extend_from_slice(&(bytes_per_frame as u16).to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        wav.resize(44 + data_len as usize, 0);
+        wav
+    }
+
+    /// A mono 16-bit PCM WAV of a 440 Hz tone at `amplitude`, in memory
+    fn tone_wav_bytes(amplitude: f32, sample_rate: u32, frames: u32) -> Vec<u8> {
+        let mut wav = test_wav_bytes(1, sample_rate, frames);
+        for (i, sample) in wav[44..].chunks_exact_mut(2).enumerate() {
+            let phase = 2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32;
+            let value = (amplitude * phase.sin() * i16::MAX as f32) as i16;
+            sample.copy_from_slice(&value.to_le_bytes());
+        }
+        wav
+    }
+
+    #[test]
+    fn test_replay_gain_levels_quiet_and_loud_files() {
+        let quiet_path = std::env::temp_dir().join("aruu_test_quiet_tone.wav");
+        let loud_path = std::env::temp_dir().join("aruu_test_loud_tone.wav");
+        let peaky_path = std::env::temp_dir().join("aruu_test_peaky_tone.wav");
+        std::fs::write(&quiet_path, tone_wav_bytes(0.02, 44100, 88200)).unwrap();
+        std::fs::write(&loud_path, tone_wav_bytes(0.9, 44100, 88200)).unwrap();
+
+        let mut processor = AudioProcessor::new_default();
+        let (sink, _output) = Sink::new_idle();
+        processor.sink = Some(sink);
+        processor.set_volume(0.5);
+        assert!(processor.replay_gain_enabled());
+
+        // The scan runs in the background; playback starts at unity gain until it reports
+        let finish_scan = |processor: &mut AudioProcessor| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while processor.replay_gain_scan.is_some() && Instant::now() < deadline {
+                processor.poll_replay_gain_scan();
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        };
+
+        // Quieter than the target: boosted above the user volume
+        processor.play_from_file(quiet_path.to_str().unwrap()).unwrap();
+        assert_eq!(processor.track_gain_db(), 0.0);
+        finish_scan(&mut processor);
+        assert!(processor.track_gain_db() > 0.0);
+        assert!(processor.sink.as_ref().unwrap().volume() > 0.5);
+
+        // Louder than the target: turned down
+        processor.play_from_file(loud_path.to_str().unwrap()).unwrap();
+        finish_scan(&mut processor);
+        assert!(processor.track_gain_db() < 0.0);
+        assert!(processor.sink.as_ref().unwrap().volume() < 0.5);
+        assert!(processor.replay_gains.contains_key(&quiet_path));
+
+        // Replaying a scanned file applies the cached gain right away
+        processor.play_from_file(quiet_path.to_str().unwrap()).unwrap();
+        assert!(processor.replay_gain_scan.is_none());
+        assert!(processor.track_gain_db() > 0.0);
+
+        // A full-scale transient caps the boost so even full user volume can't clip it
+        let mut peaky = tone_wav_bytes(0.02, 44100, 88200);
+        peaky[44..46].copy_from_slice(&i16::MIN.to_le_bytes());
+        std::fs::write(&peaky_path, peaky).unwrap();
+        processor.set_volume(1.0);
+        processor.play_from_file(peaky_path.to_str().unwrap()).unwrap();
+        finish_scan(&mut processor);
+        assert!(processor.sink.as_ref().unwrap().volume() <= 1.0);
+        processor.set_volume(0.5);
+
+        // Streams can't be scanned and play at the user volume
+        processor.play_from_reader(std::io::Cursor::new(tone_wav_bytes(0.02, 44100, 4410))).unwrap();
+        assert_eq!(processor.track_gain_db(), 0.0);
+        assert!((processor.sink.as_ref().unwrap().volume() - 0.5).abs() < 1e-6);
+
+        let _ = std::fs::remove_file(quiet_path);
+        let _ = std::fs::remove_file(loud_path);
+        let _ = std::fs::remove_file(peaky_path);
+    }
+
+    #[test]
+    fn test_seek_updates_playback_position() {
+        let mut processor = AudioProcessor::new_default();
+        let (sink, mut output) = Sink::new_idle();
+        processor.sink = Some(sink);
+        processor.play_from_reader(std::io::Cursor::new(tone_wav_bytes(0.5, 8000, 8000 * 30))).unwrap();
+        assert_eq!(processor.duration(), Some(Duration::from_secs(30)));
+
+        // The sink applies seeks while its output is pulled; drain at about 8x real time
+        let stop = Arc::new(AtomicBool::new(false));
+        let drain = {
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    output.by_ref().take(64).for_each(drop);
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            })
+        };
+
+        let seconds = |processor: &AudioProcessor| processor.playback_position().unwrap().as_secs_f32();
+        processor.seek(Duration::from_secs(10)).unwrap();
+        let after_seek = seconds(&processor);
+        assert!((10.0..11.0).contains(&after_seek), "position {}s after seeking to 10s", after_seek);
+
+        processor.seek_by(5.0).unwrap();
+        let forward = seconds(&processor);
+        assert!(forward >= after_seek + 5.0 && forward < after_seek + 6.0, "{}s -> {}s", after_seek, forward);
+
+        // Back-to-back skips build on each other rather than on a position the sink hasn't refreshed
+        processor.seek_by(-5.0).unwrap();
+        processor.seek_by(-5.0).unwrap();
+        let rewound = seconds(&processor);
+        assert!(rewound >= forward - 10.0 && rewound < forward - 9.0, "{}s -> {}s", forward, rewound);
+
+        // Once the sink has caught up the anchor is dropped, so a later repeat of the stale
+        // position isn't mistaken for the seek target
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(seconds(&processor) >= rewound);
+        assert_eq!(processor.seek_anchor.get(), None);
+
+        // Rewinding past the start stops at 0
+        processor.seek_by(-60.0).unwrap();
+        assert!(seconds(&processor) < 1.0);
+        processor.seek_fraction(0.5).unwrap();
+        assert!((15.0..16.0).contains(&seconds(&processor)));
+
+        stop.store(true, Ordering::Relaxed);
+        drain.join().unwrap();
+    }
+
+    #[test]
+    fn test_loop_wrap_does_not_register_onset() {
+        use crate::audio::RhythmDetector;
+
+        let mut processor = AudioProcessor::new_default();
+        let (sink, mut output) = Sink::new_idle();
+        processor.sink = Some(sink);
+        processor.play_from_reader(std::io::Cursor::new(tone_wav_bytes(0.5, 8000, 8000 * 30))).unwrap();
+        processor.set_loop_region(Some((Duration::from_secs(1), Duration::from_millis(1500)))).unwrap();
+        let seeks = processor.seeks();
+        let mut detector = RhythmDetector::new(processor.sample_rate());
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let drain = {
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    output.by_ref().take(64).for_each(drop);
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            })
+        };
+
+        // A steady tone has no onsets after it starts; each wrap must not add one either
+        let mut wraps = 0;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while wraps < 3 && Instant::now() < deadline {
+            let features = processor.process_frame().unwrap();
+            if seeks.try_iter().count() > 0 {
+                wraps += 1;
+                detector.reset_onset_history();
+            }
+            let rhythm = detector.process_frame(&[features.bass, features.mid, features.treble, features.overall_volume]);
+            assert!(wraps == 0 || !rhythm.onset_detected, "onset after loop wrap {}", wraps);
+            std::thread::sleep(Duration::from_millis(2));
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        drain.join().unwrap();
+        assert_eq!(wraps, 3, "loop should have wrapped three times");
+    }
+
+    #[test]
+    fn test_offline_decode_follows_frame_count() {
+        // 30 analysis frames per second of a 44.1 kHz tone is exactly 1470 samples per frame
+        let wav = std::io::Cursor::new(tone_wav_bytes(0.5, 44100, 44100 / 2));
+        let mut processor = AudioProcessor::new_offline(wav).expect("In-memory WAV should decode");
+        processor.set_analysis_frame_rate(30.0);
+        assert_eq!(processor.offline_position(), Some(Duration::ZERO));
+
+        for _ in 0..10 {
+            processor.process_frame().unwrap();
+        }
+        assert_eq!(processor.offline_position(), Some(Duration::from_secs_f64(14700.0 / 44100.0)));
+        assert!(!processor.offline_finished());
+        assert!(!processor.spectrum().is_empty());
+
+        // Half a second is 15 frames; the 16th runs out of samples
+        for _ in 0..6 {
+            processor.process_frame().unwrap();
+        }
+        assert!(processor.offline_finished());
+        assert_eq!(processor.offline_position(), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_play_from_in_memory_reader() {
+        let mut processor = AudioProcessor::new_default();
+        let wav = std::io::Cursor::new(test_wav_bytes(2, 44100, 44100));
+
+        // No output device: nothing to play through
+        assert!(processor.play_from_reader(wav.clone()).is_err());
+
+        // Volume chosen before playback is applied to the new source
+        processor.set_volume(0.25);
+        let (sink, _output) = Sink::new_idle();
+        processor.sink = Some(sink);
+        processor.play_from_reader(wav).expect("In-memory WAV should decode");
+
+        assert!(processor.is_playing());
+        assert_eq!(processor.channels(), 2);
+        assert!((processor.sink.as_ref().unwrap().volume() - 0.25).abs() < 1e-6);
+        assert!(processor.duration().is_some());
+
+        // Bytes that aren't audio are rejected rather than queued
+        let mut processor = AudioProcessor::new_default();
+        let (sink, _output) = Sink::new_idle();
+        processor.sink = Some(sink);
+        assert!(processor.play_from_reader(std::io::Cursor::new(vec![0u8; 64])).is_err());
+        assert!(!processor.is_playing());
+    }
+
+    #[test]
+    fn test_stereo_file_sets_analyzer_sample_rate() {
+        let path = std::env::temp_dir().join("aruu_test_stereo_48k.wav");
+        write_test_wav(&path, 2, 48000, 4800);
+
+        let mut processor = AudioProcessor::new_default();
+        let source = processor.open_file_source(path.to_str().unwrap()).expect("WAV should decode");
+
+        assert_eq!(processor.sample_rate(), 48000.0);
+        assert_eq!(processor.channels(), 2);
+        assert_eq!(source.channels(), 2);
+
+        // Draining the tap feeds one mono sample per stereo frame into analysis
+        let played = source.count();
+        assert_eq!(played, 4800 * 2);
+        assert_eq!(processor.get_audio_samples().len(), BUFFER_SIZE * 4); // Capped ring buffer
+        assert_eq!(processor.stereo_buffer.lock().unwrap().len(), BUFFER_SIZE * 4);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_fft_backend_selection() {
+        let mut processor = AudioProcessor::new_default();
+        assert_eq!(processor.fft_backend(), FftBackend::Cpu);
+        assert_eq!(processor.set_fft_backend(FftBackend::Cpu), FftBackend::Cpu);
+
+        // Without compute support the GPU request falls back; either way the spectrum matches the CPU
+        let backend = processor.set_fft_backend(FftBackend::Gpu);
+        {
+            let mut buffer = processor.audio_buffer.lock().unwrap();
+            for i in 0..BUFFER_SIZE {
+                buffer.push_back(0.5 * (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 44100.0).sin());
+            }
+        }
+        processor.process_frame().unwrap();
+        assert_eq!(processor.spectrum_on_gpu, backend == FftBackend::Gpu);
+
+        let samples = processor.get_audio_samples();
+        let mut cpu = FftAnalyzer::new(BUFFER_SIZE, WindowFunction::default());
+        let expected = cpu.process_audio(&samples).to_vec();
+        let peak = expected.iter().fold(0.0f32, |acc, &m| acc.max(m));
+        for (actual, expected) in processor.spectrum().iter().zip(&expected) {
+            assert!((actual - expected).abs() <= peak * 1e-4 + 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_spectral_smoothing_setting() {
+        let mut processor = AudioProcessor::new_default();
+        assert_eq!(processor.spectral_smoothing(), 0.0);
+        processor.set_spectral_smoothing(0.6);
+        assert_eq!(processor.spectral_smoothing(), 0.6);
+        processor.set_spectral_smoothing(5.0);
+        assert_eq!(processor.spectral_smoothing(), crate::audio::MAX_SPECTRAL_SMOOTHING);
+    }
+
+    #[test]
+    fn test_sine_test_signal_centroid_near_its_frequency() {
+        let mut processor = AudioProcessor::new_test_signal(SignalSpec::sine(440.0, 0.8));
+        assert_eq!(processor.test_signal().map(|spec| spec.frequency), Some(440.0));
+
+        let features = (0..3).map(|_| processor.process_frame().unwrap()).last().unwrap();
+        assert_eq!(processor.analysis_state(), AnalysisState::Active);
+        assert!(
+            (features.spectral_centroid - 440.0).abs() < 30.0,
+            "440 Hz sine centroid was {} Hz",
+            features.spectral_centroid
+        );
+    }
+
+    #[test]
+    fn test_noise_gate_suppresses_hum_and_passes_music() {
+        let run = |amplitude: f32, gate_db: Option<f32>| {
+            let mut processor = AudioProcessor::new_test_signal(SignalSpec::sine(60.0, amplitude));
+            processor.set_noise_gate(gate_db, 50);
+            let features = (0..20).map(|_| processor.process_frame().unwrap()).last().unwrap();
+            (features, processor.is_noise_gated())
+        };
+
+        let (hum, _) = run(0.005, None);
+        let (loud, _) = run(0.8, None);
+        assert!(hum.bass + hum.sub_bass > 0.0, "ungated hum should register");
+        let threshold_db = (hum.signal_level_db + loud.signal_level_db) / 2.0;
+
+        // Hum under the threshold is blanked, keeping its level for meters
+        let (gated, closed) = run(0.005, Some(threshold_db));
+        assert!(closed);
+        assert_eq!([gated.sub_bass, gated.bass, gated.mid, gated.treble, gated.presence, gated.overall_volume], [0.0; 6]);
+        assert_eq!(gated.signal_level_db, hum.signal_level_db);
+
+        // Louder material passes untouched
+        let (passed, closed) = run(0.8, Some(threshold_db));
+        assert!(!closed);
+        assert_eq!(passed, loud);
+    }
+
+    /// Tempo estimated from 120 BPM bass thumps at `sample_rate`, using the visualizer's
+    /// features-to-rhythm path
+    fn tempo_at_sample_rate(sample_rate: u32) -> f32 {
+        use crate::audio::RhythmDetector;
+        use std::f32::consts::TAU;
+
+        let mut processor = AudioProcessor::new_default();
+        processor.configure_for_source(1, sample_rate as f32);
+        let mut rhythm_detector = RhythmDetector::new(processor.sample_rate());
+
+        let frame_samples = sample_rate as usize / 60;
+        let beat_samples = sample_rate as usize / 2;
+        let thump_samples = sample_rate as usize / 20;
+        let mut estimated_bpm = 0.0;
+        for frame in 0..60 * 10 {
+            let samples: Vec<f32> = (frame * frame_samples..(frame + 1) * frame_samples)
+                .map(|i| if i % beat_samples < thump_samples { 0.8 * (TAU * 80.0 * i as f32 / sample_rate as f32).sin() } else { 0.0 })
+                .collect();
+            AudioProcessor::write_input_data(&samples, &processor.audio_buffer, None, 1, ALL_INPUT_CHANNELS);
+
+            let features = processor.process_frame().unwrap();
+            let bins = [features.bass, features.mid, features.treble, features.overall_volume];
+            estimated_bpm = rhythm_detector.process_frame(&bins).estimated_bpm;
+        }
+        estimated_bpm
+    }
+
+    #[test]
+    fn test_tempo_matches_across_sample_rates() {
+        let at_44k = tempo_at_sample_rate(44100);
+        let at_48k = tempo_at_sample_rate(48000);
+        assert!((at_44k - 120.0).abs() < 3.0, "44.1kHz tempo was {} BPM", at_44k);
+        assert!((at_48k - at_44k).abs() < 3.0, "48kHz tempo {} BPM vs 44.1kHz {} BPM", at_48k, at_44k);
+    }
+
+    #[test]
+    fn test_white_noise_test_signal_has_flat_spectrum() {
+        let mut processor = AudioProcessor::new_test_signal(SignalSpec::white_noise(0.5));
+        let mut total = vec![0.0; BUFFER_SIZE / 2];
+        for _ in 0..60 {
+            processor.process_frame().unwrap();
+            for (sum, bin) in total.iter_mut().zip(processor.spectrum()) {
+                *sum += bin;
+            }
+        }
+
+        // Every quarter of the spectrum (DC aside) carries about the same energy
+        let mean = total[1..].iter().sum::<f32>() / (total.len() - 1) as f32;
+        for quarter in total[1..].chunks(total.len() / 4) {
+            let level = quarter.iter().sum::<f32>() / quarter.len() as f32;
+            assert!((level / mean - 1.0).abs() < 0.2, "quarter level {} vs mean {}", level, mean);
+        }
+    }
+
+    #[test]
+    fn test_feature_delay_returns_delayed_frame() {
+        let frame = |index: usize| {
+            let mut features = AudioFeatures::new();
+            features.overall_volume = index as f32;
+            features
+        };
+
+        let mut delay = FeatureDelay::new();
+        delay.set_delay_frames(3);
+        let delayed: Vec<f32> = (0..8).map(|i| delay.push(frame(i)).overall_volume).collect();
+        // Holds the first frame while filling, then trails by exactly three frames
+        assert_eq!(delayed, vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        // No delay passes frames straight through
+        delay.set_delay_frames(0);
+        assert_eq!(delay.push(frame(8)).overall_volume, 8.0);
+    }
+
+    #[test]
+    fn test_latency_offset_converts_to_frames_and_lookahead() {
+        let mut processor = AudioProcessor::new_default();
+        processor.set_analysis_frame_rate(60.0);
+
+        processor.set_latency_offset(100);
+        assert_eq!(processor.feature_delay.delay_frames(), 6);
+        assert_eq!(processor.lookahead_samples.load(Ordering::Relaxed), 0);
+
+        processor.set_latency_offset(-10_000);
+        assert_eq!(processor.latency_offset_ms(), -MAX_LATENCY_OFFSET_MS);
+        assert_eq!(processor.feature_delay.delay_frames(), 0);
+        assert_eq!(processor.lookahead_samples.load(Ordering::Relaxed), 22050);
+    }
+
+    #[test]
+    fn test_negative_latency_analyzes_ahead_of_playback() {
+        let mut processor = AudioProcessor::new_default();
+        processor.set_latency_offset(-50);
+        let mut source = processor
+            .open_reader_source(std::io::Cursor::new(test_wav_bytes(1, 44100, 4410)))
+            .expect("In-memory WAV should decode");
+        assert_eq!(processor.lookahead_samples.load(Ordering::Relaxed), 2205);
+
+        // The first sample played has 50 ms of audio already analyzed behind it
+        assert!(source.next().is_some());
+        assert!(processor.get_audio_samples().len() >= 2048);
+
+        // Nothing is skipped: playback still receives every sample
+        assert_eq!(source.count(), 4410 - 1);
+    }
+
+    #[test]
+    fn test_input_channel_extracts_interleaved_samples() {
+        let buffer = Arc::new(Mutex::new(VecDeque::new()));
+        let interleaved: Vec<f32> = (0..16).map(|i| i as f32).collect(); // 4 frames of 4 channels
+
+        let stereo = Arc::new(Mutex::new(VecDeque::new()));
+
+        AudioProcessor::write_input_data(&interleaved, &buffer, Some(&stereo), 4, 2);
+        let buffered: Vec<f32> = buffer.lock().unwrap().iter().copied().collect();
+        assert_eq!(buffered, vec![2.0, 6.0, 10.0, 14.0]);
+        assert!(stereo.lock().unwrap().is_empty());
+
+        // Without a selection each frame is downmixed, with the first two channels kept as stereo
+        buffer.lock().unwrap().clear();
+        AudioProcessor::write_input_data(&interleaved, &buffer, Some(&stereo), 4, ALL_INPUT_CHANNELS);
+        let buffered: Vec<f32> = buffer.lock().unwrap().iter().copied().collect();
+        assert_eq!(buffered, vec![1.5, 5.5, 9.5, 13.5]);
+        let frames: Vec<[f32; 2]> = stereo.lock().unwrap().iter().copied().collect();
+        assert_eq!(frames, vec![[0.0, 1.0], [4.0, 5.0], [8.0, 9.0], [12.0, 13.0]]);
+    }
+
+    /// Interleaved stereo sine with independent channel gains
+    fn stereo_sine(left_gain: f32, right_gain: f32) -> Vec<f32> {
+        (0..BUFFER_SIZE)
+            .flat_map(|i| {
+                let s = (2.0 * std::f32::consts::PI * 440.0 * i as f32 / SAMPLE_RATE as f32).sin();
+                [s * left_gain, s * right_gain]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hard_panned_input_reports_balance() {
+        for (left_gain, right_gain, expected) in [(0.8, 0.0, -1.0), (0.0, 0.8, 1.0)] {
+            let mut processor = AudioProcessor::new_default();
+            let stereo = stereo_sine(left_gain, right_gain);
+            AudioProcessor::write_input_data(&stereo, &processor.audio_buffer, Some(&processor.stereo_buffer), 2, ALL_INPUT_CHANNELS);
+
+            let features = processor.process_frame().unwrap();
+            assert!((features.stereo_balance - expected).abs() < 1e-3, "balance {}", features.stereo_balance);
+            assert!(features.stereo_width > 0.9);
+        }
+
+        // Identical channels are centered and mono
+        let mut processor = AudioProcessor::new_default();
+        let centered = stereo_sine(0.5, 0.5);
+        AudioProcessor::write_input_data(&centered, &processor.audio_buffer, Some(&processor.stereo_buffer), 2, ALL_INPUT_CHANNELS);
+        let features = processor.process_frame().unwrap();
+        assert!(features.stereo_balance.abs() < 1e-3);
+        assert!(features.stereo_width < 1e-3);
+    }
+
+    #[test]
+    fn test_mono_input_has_no_stereo_image() {
+        let mut processor = AudioProcessor::new_default();
+        {
+            let mut buffer = processor.audio_buffer.lock().unwrap();
+            for i in 0..BUFFER_SIZE {
+                buffer.push_back((i as f32 * 0.1).sin());
+            }
+        }
+
+        let features = processor.process_frame().unwrap();
+        assert!(features.overall_volume > 0.0);
+        assert_eq!(features.stereo_balance, 0.0);
+        assert_eq!(features.stereo_width, 0.0);
+
+        let (left, right) = processor.stereo_spectra();
+        assert_eq!(left, processor.spectrum());
+        assert_eq!(left, right);
+    }
+
+    #[test]
+    fn test_left_only_input_splits_spectra() {
+        let mut processor = AudioProcessor::new_default();
+        let stereo = stereo_sine(0.8, 0.0);
+        AudioProcessor::write_input_data(&stereo, &processor.audio_buffer, Some(&processor.stereo_buffer), 2, ALL_INPUT_CHANNELS);
+        processor.process_frame().unwrap();
+
+        let (left, right) = processor.stereo_spectra();
+        assert_eq!(left.len(), right.len());
+        assert!(left.iter().sum::<f32>() > 10.0);
+        assert!(right.iter().sum::<f32>() < 1e-3);
+    }
+
+    #[test]
+    fn test_input_channel_validated_against_stream() {
+        let mut processor = AudioProcessor::new_default();
+        assert!(processor.set_input_channel(Some(0)).is_ok());
+        assert_eq!(processor.input_channel(), Some(0));
+        assert!(processor.set_input_channel(Some(3)).is_err());
+        assert_eq!(processor.input_channel(), Some(0));
+        assert!(processor.set_input_channel(None).is_ok());
+        assert_eq!(processor.input_channel(), None);
+    }
+
+    #[test]
+    fn test_signed_sample_conversions() {
+        assert!((i8_to_f32(i8::MAX) - 1.0).abs() < 1e-4);
+        assert!((i16_to_f32(i16::MAX) - 1.0).abs() < 1e-4);
+        assert!((i32_to_f32(i32::MAX) - 1.0).abs() < 1e-4);
+        assert!((i64_to_f32(i64::MAX) - 1.0).abs() < 1e-4);
+        assert_eq!(i32_to_f32(0), 0.0);
+        assert!((i8_to_f32(i8::MIN) + 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_unsigned_sample_conversions() {
+        assert!((u8_to_f32(u8::MAX) - 1.0).abs() < 1e-4);
+        assert!((u16_to_f32(u16::MAX) - 1.0).abs() < 1e-4);
+        assert!((u32_to_f32(u32::MAX) - 1.0).abs() < 1e-4);
+        assert!((u64_to_f32(u64::MAX) - 1.0).abs() < 1e-4);
+        assert!((u8_to_f32(0) + 1.0).abs() < 1e-4);
+        assert!(u32_to_f32(u32::MAX / 2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_float_sample_conversion() {
+        assert_eq!(f64_to_f32(1.0), 1.0);
+        assert_eq!(f64_to_f32(-0.5), -0.5);
+    }
+
+    #[test]
+    fn test_seek_without_playback_fails() {
+        let mut processor = AudioProcessor::new_default();
+        assert!(processor.seek(Duration::from_secs(1)).is_err());
+        assert!(processor.seek_fraction(0.5).is_err());
+        assert!(processor.seek_by(5.0).is_err());
+        assert!(processor.playback_fraction().is_none());
+        assert!(!processor.is_paused());
+    }
+
+    #[test]
+    fn test_shutdown_stops_playback_and_is_idempotent() {
+        let mut processor = AudioProcessor::new_default();
+        let (sink, _output) = Sink::new_idle();
+        sink.append(rodio::source::SineWave::new(440.0));
+        processor.sink = Some(sink);
+        assert!(processor.is_playing());
+
+        assert!(processor.shutdown());
+        assert!(!processor.is_playing());
+        assert!(processor.play_from_file("anything.wav").is_err());
+
+        // A second shutdown has nothing left to release
+        assert!(!processor.shutdown());
+        assert!(!processor.is_playing());
+    }
+
+    #[test]
+    fn test_file_source_reports_duration() {
+        let path = std::env::temp_dir().join("aruu_test_duration.wav");
+        write_test_wav(&path, 1, 44100, 22050);
+
+        let mut processor = AudioProcessor::new_default();
+        let _source = processor.open_file_source(path.to_str().unwrap()).unwrap();
+        let duration = processor.duration().expect("WAV length should be known");
+        assert!((duration.as_secs_f32() - 0.5).abs() < 0.01);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_playlist_reports_track_changes() {
+        let paths: Vec<_> = (0..2)
+            .map(|i| std::env::temp_dir().join(format!("aruu_test_playlist_{}.wav", i)))
+            .collect();
+        for path in &paths {
+            write_test_wav(path, 1, 44100, 2205);
+        }
+
+        let mut processor = AudioProcessor::new_default();
+        assert!(processor.next_track().is_err());
+        assert!(processor.play_playlist(Playlist::default()).is_err());
+
+        let (sink, mut output) = Sink::new_idle();
+        processor.sink = Some(sink);
+        let changes = processor.track_changes();
+
+        processor.play_playlist(paths.iter().cloned().collect()).unwrap();
+        assert_eq!(processor.playlist().unwrap().len(), 2);
+        assert_eq!(processor.playlist().unwrap().current_index(), 0);
+        assert_eq!(changes.try_recv().unwrap().index, 0);
+
+        // Play through the first entry and into the second
+        output.by_ref().take(2205 + 64).for_each(drop);
+        processor.process_frame().unwrap();
+
+        assert_eq!(processor.playlist().unwrap().current_index(), 1);
+        let event = changes.try_recv().unwrap();
+        assert_eq!(event.index, 1);
+        assert_eq!(event.path, paths[1]);
+        assert!(processor.next_track().is_err());
+
+        for path in &paths {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
     #[test]
     fn test_advanced_analyzer_overrides_hardcoded_values() {
         // This test validates the ASSUMPTION that AdvancedAnalyzer properly calculates
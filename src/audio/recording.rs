@@ -0,0 +1,477 @@
+// Feature timelines: per-frame analysis results written as NDJSON (one JSON object per line)
+// and read back for deterministic replays without audio hardware
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+
+use super::{AudioFeatures, KeyMode, MusicalKey, PitchClass, RhythmFeatures};
+use crate::clock::{system_clock, SharedClock};
+
+/// Supplies the analysis results for each rendered frame
+pub trait FeatureSource {
+    /// Features for the next frame; None once the source is exhausted
+    fn next_features(&mut self) -> Option<(AudioFeatures, RhythmFeatures)>;
+}
+
+/// One frame of a feature timeline
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedFrame {
+    pub frame_index: u64,
+    pub timestamp: Duration, // Since recording started (stored as whole microseconds)
+    pub audio: AudioFeatures,
+    pub rhythm: RhythmFeatures,
+}
+
+/// Writes one NDJSON line per analyzed frame while recording
+pub struct FeatureRecorder {
+    clock: SharedClock,
+    started: Instant,
+    writer: Option<BufWriter<File>>,
+    path: Option<PathBuf>,
+    frames_written: u64,
+}
+
+impl FeatureRecorder {
+    pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a recorder stamping frames from a custom time source
+    pub fn with_clock(clock: SharedClock) -> Self {
+        let started = clock.now();
+        Self {
+            clock,
+            started,
+            writer: None,
+            path: None,
+            frames_written: 0,
+        }
+    }
+
+    /// Start a new timeline at `path`, replacing the file; a recording in progress is stopped first
+    pub fn start<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        if self.is_recording() {
+            self.stop()?;
+        }
+
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create feature timeline {}", path.display()))?;
+        self.writer = Some(BufWriter::new(file));
+        self.path = Some(path.to_path_buf());
+        self.started = self.clock.now();
+        self.frames_written = 0;
+        println!("⏺️  Recording features to {}", path.display());
+        Ok(())
+    }
+
+    /// Append one frame to the timeline
+    pub fn record(&mut self, audio: &AudioFeatures, rhythm: &RhythmFeatures, frame_index: u64) -> Result<()> {
+        let writer = self.writer.as_mut().ok_or_else(|| anyhow!("Feature recorder is not recording"))?;
+        let frame = RecordedFrame {
+            frame_index,
+            timestamp: self.clock.now().duration_since(self.started),
+            audio: audio.clone(),
+            rhythm: rhythm.clone(),
+        };
+        writeln!(writer, "{}", format_frame(&frame))?;
+        self.frames_written += 1;
+        Ok(())
+    }
+
+    /// Flush and close the timeline; returns the number of frames written
+    pub fn stop(&mut self) -> Result<u64> {
+        let mut writer = self.writer.take().ok_or_else(|| anyhow!("Feature recorder is not recording"))?;
+        writer.flush()?;
+        if let Some(path) = self.path.take() {
+            println!("💾 Feature timeline saved to {} ({} frames)", path.display(), self.frames_written);
+        }
+        Ok(self.frames_written)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// Frames written since the last `start`
+    pub fn frames_written(&self) -> u64 {
+        self.frames_written
+    }
+}
+
+impl Default for FeatureRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for FeatureRecorder {
+    fn drop(&mut self) {
+        if let Some(writer) = &mut self.writer {
+            let _ = writer.flush();
+        }
+    }
+}
+
+/// Replays a recorded feature timeline one frame at a time
+#[derive(Debug, Clone, Default)]
+pub struct FeaturePlayback {
+    frames: VecDeque<RecordedFrame>,
+}
+
+impl FeaturePlayback {
+    /// Load a timeline written by `FeatureRecorder`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read feature timeline {}", path.display()))?;
+        Self::parse(&text)
+    }
+
+    /// Parse NDJSON timeline text; blank lines are skipped
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut frames = VecDeque::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let frame = parse_frame(line).with_context(|| format!("Feature timeline line {}", index + 1))?;
+            frames.push_back(frame);
+        }
+        Ok(Self { frames })
+    }
+
+    /// Next recorded frame, with its index and timestamp
+    pub fn next_frame(&mut self) -> Option<RecordedFrame> {
+        self.frames.pop_front()
+    }
+
+    /// Frames left to replay
+    pub fn remaining(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+impl FeatureSource for FeaturePlayback {
+    fn next_features(&mut self) -> Option<(AudioFeatures, RhythmFeatures)> {
+        self.next_frame().map(|frame| (frame.audio, frame.rhythm))
+    }
+}
+
+/// Numeric fields in file order
+const AUDIO_FIELDS: [&str; 21] = [
+    "sub_bass",
+    "bass",
+    "mid",
+    "treble",
+    "presence",
+    "overall_volume",
+    "signal_level_db",
+    "peak_level_db",
+    "dynamic_range",
+    "spectral_centroid",
+    "spectral_rolloff",
+    "spectral_flux",
+    "spectral_crest",
+    "pitch_confidence",
+    "zero_crossing_rate",
+    "key_confidence",
+    "onset_strength",
+    "stereo_balance",
+    "stereo_width",
+    "harmonic_energy",
+    "percussive_energy",
+];
+
+fn audio_values(audio: &AudioFeatures) -> [f32; 21] {
+    [
+        audio.sub_bass,
+        audio.bass,
+        audio.mid,
+        audio.treble,
+        audio.presence,
+        audio.overall_volume,
+        audio.signal_level_db,
+        audio.peak_level_db,
+        audio.dynamic_range,
+        audio.spectral_centroid,
+        audio.spectral_rolloff,
+        audio.spectral_flux,
+        audio.spectral_crest,
+        audio.pitch_confidence,
+        audio.zero_crossing_rate,
+        audio.key_confidence,
+        audio.onset_strength,
+        audio.stereo_balance,
+        audio.stereo_width,
+        audio.harmonic_energy,
+        audio.percussive_energy,
+    ]
+}
+
+fn audio_field_mut<'a>(audio: &'a mut AudioFeatures, name: &str) -> Option<&'a mut f32> {
+    Some(match name {
+        "sub_bass" => &mut audio.sub_bass,
+        "bass" => &mut audio.bass,
+        "mid" => &mut audio.mid,
+        "treble" => &mut audio.treble,
+        "presence" => &mut audio.presence,
+        "overall_volume" => &mut audio.overall_volume,
+        "signal_level_db" => &mut audio.signal_level_db,
+        "peak_level_db" => &mut audio.peak_level_db,
+        "dynamic_range" => &mut audio.dynamic_range,
+        "spectral_centroid" => &mut audio.spectral_centroid,
+        "spectral_rolloff" => &mut audio.spectral_rolloff,
+        "spectral_flux" => &mut audio.spectral_flux,
+        "spectral_crest" => &mut audio.spectral_crest,
+        "pitch_confidence" => &mut audio.pitch_confidence,
+        "zero_crossing_rate" => &mut audio.zero_crossing_rate,
+        "key_confidence" => &mut audio.key_confidence,
+        "onset_strength" => &mut audio.onset_strength,
+        "stereo_balance" => &mut audio.stereo_balance,
+        "stereo_width" => &mut audio.stereo_width,
+        "harmonic_energy" => &mut audio.harmonic_energy,
+        "percussive_energy" => &mut audio.percussive_energy,
+        _ => return None,
+    })
+}
+
+/// JSON number for an f32 (`Display` round-trips exactly); JSON has no NaN or infinity, so those become null
+pub(crate) fn json_number(value: f32) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn format_frame(frame: &RecordedFrame) -> String {
+    let mut fields = vec![
+        format!("\"frame\":{}", frame.frame_index),
+        format!("\"timestamp_us\":{}", frame.timestamp.as_micros()),
+    ];
+    for (name, value) in AUDIO_FIELDS.iter().zip(audio_values(&frame.audio)) {
+        fields.push(format!("\"{}\":{}", name, json_number(value)));
+    }
+    fields.push(match frame.audio.detected_key {
+        Some(key) => format!("\"detected_key\":\"{}\"", key.name()),
+        None => "\"detected_key\":null".to_string(),
+    });
+
+    let rhythm = &frame.rhythm;
+    fields.push(format!("\"beat_strength\":{}", json_number(rhythm.beat_strength)));
+    fields.push(format!("\"tempo_bpm\":{}", json_number(rhythm.tempo_bpm)));
+    fields.push(format!("\"estimated_bpm\":{}", json_number(rhythm.estimated_bpm)));
+    fields.push(format!("\"tempo_confidence\":{}", json_number(rhythm.tempo_confidence)));
+    fields.push(format!("\"onset_detected\":{}", rhythm.onset_detected));
+    fields.push(format!("\"rhythm_stability\":{}", json_number(rhythm.rhythm_stability)));
+    fields.push(format!("\"downbeat_detected\":{}", rhythm.downbeat_detected));
+    fields.push(format!("\"beat_position\":{}", rhythm.beat_position));
+
+    format!("{{{}}}", fields.join(","))
+}
+
+/// A value in the flat objects this format writes
+#[derive(Debug, Clone, PartialEq)]
+enum JsonValue {
+    Number(f64),
+    Bool(bool),
+    Text(String),
+    Null,
+}
+
+/// Split a flat JSON object (no nesting, no escapes in strings) into key/value pairs
+fn parse_flat_object(line: &str) -> Result<Vec<(String, JsonValue)>> {
+    let body = line
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or_else(|| anyhow!("Expected a JSON object"))?;
+
+    let mut pairs = Vec::new();
+    let mut rest = body.trim();
+    while !rest.is_empty() {
+        let after_quote = rest.strip_prefix('"').ok_or_else(|| anyhow!("Expected a quoted key at '{}'", rest))?;
+        let key_end = after_quote.find('"').ok_or_else(|| anyhow!("Unterminated key"))?;
+        let key = &after_quote[..key_end];
+        let after_key = after_quote[key_end + 1..].trim_start();
+        let after_colon = after_key.strip_prefix(':').ok_or_else(|| anyhow!("Expected ':' after \"{}\"", key))?.trim_start();
+
+        let (value, remainder) = if let Some(text) = after_colon.strip_prefix('"') {
+            let end = text.find('"').ok_or_else(|| anyhow!("Unterminated string for \"{}\"", key))?;
+            (JsonValue::Text(text[..end].to_string()), &text[end + 1..])
+        } else {
+            let end = after_colon.find(',').unwrap_or(after_colon.len());
+            let token = after_colon[..end].trim();
+            let value = match token {
+                "true" => JsonValue::Bool(true),
+                "false" => JsonValue::Bool(false),
+                "null" => JsonValue::Null,
+                number => JsonValue::Number(number.parse().map_err(|_| anyhow!("Bad value '{}' for \"{}\"", number, key))?),
+            };
+            (value, &after_colon[end..])
+        };
+        pairs.push((key.to_string(), value));
+
+        let remainder = remainder.trim_start();
+        rest = match remainder.strip_prefix(',') {
+            Some(next) => next.trim_start(),
+            None if remainder.is_empty() => remainder,
+            None => return Err(anyhow!("Expected ',' after \"{}\"", key)),
+        };
+    }
+    Ok(pairs)
+}
+
+/// Key from its `MusicalKey::name`, e.g. "F# minor"
+fn parse_key(name: &str) -> Result<MusicalKey> {
+    let (root, mode) = name.split_once(' ').ok_or_else(|| anyhow!("Bad key '{}'", name))?;
+    let root = PitchClass::all()
+        .into_iter()
+        .find(|pitch| pitch.name() == root)
+        .ok_or_else(|| anyhow!("Unknown key root '{}'", root))?;
+    let mode = [KeyMode::Major, KeyMode::Minor]
+        .into_iter()
+        .find(|candidate| candidate.name() == mode)
+        .ok_or_else(|| anyhow!("Unknown key mode '{}'", mode))?;
+    Ok(MusicalKey::new(root, mode))
+}
+
+fn parse_frame(line: &str) -> Result<RecordedFrame> {
+    let mut frame = RecordedFrame {
+        frame_index: 0,
+        timestamp: Duration::ZERO,
+        audio: AudioFeatures::new(),
+        rhythm: RhythmFeatures::new(),
+    };
+
+    for (key, value) in parse_flat_object(line)? {
+        // null stands in for non-finite numbers
+        let number = match &value {
+            JsonValue::Number(n) => Some(*n),
+            JsonValue::Null => Some(f64::NAN),
+            _ => None,
+        };
+        let flag = match &value {
+            JsonValue::Bool(b) => Some(*b),
+            _ => None,
+        };
+        let expect_number = || number.ok_or_else(|| anyhow!("\"{}\" should be a number", key));
+        let expect_flag = || flag.ok_or_else(|| anyhow!("\"{}\" should be true or false", key));
+
+        match key.as_str() {
+            "frame" => frame.frame_index = expect_number()? as u64,
+            "timestamp_us" => frame.timestamp = Duration::from_micros(expect_number()? as u64),
+            "detected_key" => {
+                frame.audio.detected_key = match &value {
+                    JsonValue::Text(name) => Some(parse_key(name)?),
+                    _ => None,
+                }
+            }
+            "beat_strength" => frame.rhythm.beat_strength = expect_number()? as f32,
+            "tempo_bpm" => frame.rhythm.tempo_bpm = expect_number()? as f32,
+            "estimated_bpm" => frame.rhythm.estimated_bpm = expect_number()? as f32,
+            "tempo_confidence" => frame.rhythm.tempo_confidence = expect_number()? as f32,
+            "onset_detected" => frame.rhythm.onset_detected = expect_flag()?,
+            "rhythm_stability" => frame.rhythm.rhythm_stability = expect_number()? as f32,
+            "downbeat_detected" => frame.rhythm.downbeat_detected = expect_flag()?,
+            "beat_position" => frame.rhythm.beat_position = expect_number()? as u8,
+            // Unknown fields are ignored so newer timelines still load
+            name => {
+                if let Some(field) = audio_field_mut(&mut frame.audio, name) {
+                    *field = expect_number()? as f32;
+                }
+            }
+        }
+    }
+
+    Ok(frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn synthetic_frame(index: usize) -> (AudioFeatures, RhythmFeatures) {
+        let mut audio = AudioFeatures::new();
+        let t = index as f32;
+        audio.sub_bass = 0.1 * t;
+        audio.bass = (t * 0.7).sin().abs();
+        audio.mid = 1.0 / (t + 3.0);
+        audio.overall_volume = 0.333_333_34 * t;
+        audio.signal_level_db = -60.0 + t * 1.25;
+        audio.spectral_centroid = 1234.567 + t;
+        audio.stereo_balance = -0.25;
+        audio.percussive_energy = 0.05 * t;
+        audio.detected_key = index.is_multiple_of(3).then(|| MusicalKey::new(PitchClass::from_index(index), KeyMode::Minor));
+        audio.key_confidence = 0.5;
+
+        let mut rhythm = RhythmFeatures::new();
+        rhythm.beat_strength = 0.9 - t * 0.05;
+        rhythm.estimated_bpm = 128.0 + t * 0.1;
+        rhythm.onset_detected = index.is_multiple_of(2);
+        rhythm.downbeat_detected = index.is_multiple_of(4);
+        rhythm.beat_position = (index % 4) as u8;
+        (audio, rhythm)
+    }
+
+    #[test]
+    fn test_round_trip_replays_equal_frames() {
+        let clock = MockClock::new();
+        let mut recorder = FeatureRecorder::with_clock(clock.shared());
+        let path = std::env::temp_dir().join(format!("aruu_features_test_{}.ndjson", std::process::id()));
+
+        assert!(recorder.record(&AudioFeatures::new(), &RhythmFeatures::new(), 0).is_err());
+        recorder.start(&path).unwrap();
+        for index in 0..10 {
+            let (audio, rhythm) = synthetic_frame(index);
+            recorder.record(&audio, &rhythm, index as u64).unwrap();
+            clock.advance(Duration::from_millis(16));
+        }
+        assert_eq!(recorder.stop().unwrap(), 10);
+        assert!(!recorder.is_recording());
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().count(), 10);
+        let mut playback = FeaturePlayback::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(playback.remaining(), 10);
+
+        for index in 0..10 {
+            let frame = playback.next_frame().unwrap();
+            assert_eq!(frame.frame_index, index as u64);
+            assert_eq!(frame.timestamp, Duration::from_millis(16 * index as u64));
+
+            let (audio, rhythm) = synthetic_frame(index);
+            assert_eq!(frame.audio, audio);
+            assert_eq!(frame.rhythm, rhythm);
+        }
+        assert!(playback.is_finished());
+        assert!(playback.next_features().is_none());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_lines() {
+        assert!(FeaturePlayback::parse("not json").is_err());
+        assert!(FeaturePlayback::parse("{\"bass\":loud}").is_err());
+        assert!(FeaturePlayback::parse("{\"onset_detected\":1}").is_err());
+        assert!(FeaturePlayback::parse("{\"detected_key\":\"H major\"}").is_err());
+
+        // Missing fields keep their defaults, unknown ones are skipped, non-finite values are null
+        let mut playback = FeaturePlayback::parse("\n{\"frame\":7,\"bass\":0.5,\"future_field\":1,\"mid\":null}\n").unwrap();
+        let (audio, rhythm) = playback.next_features().unwrap();
+        assert_eq!(audio.bass, 0.5);
+        assert!(audio.mid.is_nan());
+        assert_eq!(rhythm.estimated_bpm, 120.0);
+    }
+}
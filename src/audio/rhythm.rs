@@ -1,11 +1,30 @@
 use std::collections::VecDeque;
+use std::time::Duration;
+use anyhow::{Result, anyhow};
 
 const ONSET_THRESHOLD: f32 = 0.1;
+const ONSET_THRESHOLD_DB: f32 = 3.0;     // Rise over the recent average needed in the dB domain
+const ONSET_ENERGY_RATIO: f32 = 1.2;     // Rise over the previous frame needed for an onset
+const ONSET_SENSITIVITY_RANGE: (f32, f32) = (0.1, 10.0);
+const ONSET_ENERGY_FLOOR: f32 = 1e-6;    // Keeps silent frames finite in dB
+const ONSET_FLUX_WINDOW: f32 = 0.5;      // Seconds of flux history behind the adaptive threshold
+const ONSET_FLUX_DEVIATIONS: f32 = 1.5;  // Standard deviations above the recent mean flux needed for an onset
+const ONSET_FLUX_MIN_RISE: f32 = 0.05;   // Smallest rise over the mean that counts, so steady flux never triggers
+const MIN_ONSET_INTERVAL: f32 = 0.05;    // Seconds; a transient straddling two frames counts once
+const ONSET_BAND_MAX_HZ: f32 = 5512.5;   // Top of the onset energy band (the lowest quarter of bins at 44.1kHz)
 const TEMPO_WINDOW_SIZE: usize = 100;
+const DEFAULT_FRAME_RATE: f32 = 60.0;
 const MIN_BPM: f32 = 60.0;
 const MAX_BPM: f32 = 200.0;
-
-#[derive(Debug, Clone)]
+const BPM_RANGE_LIMITS: (f32, f32) = (30.0, 300.0); // Widest search range set_bpm_range accepts
+const CLICK_BEAT_GAIN: f32 = 0.5;
+const CLICK_DOWNBEAT_GAIN: f32 = 1.0;
+const DEFAULT_TEMPO_LOCK_THRESHOLD: f32 = 0.7; // Tempo confidence needed to commit to a BPM
+const TEMPO_OCTAVE_TOLERANCE: f32 = 0.08;      // How close to 2x or 0.5x counts as an octave error
+const TEMPO_LOCK_RELEASE_SECONDS: f32 = 4.0;   // Sustained octave estimates this long are a real tempo change
+const AUTOCORRELATION_TIE_RATIO: f32 = 0.98;   // Periodicity scores this close to the best count as ties
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct RhythmFeatures {
     pub beat_strength: f32,
     pub tempo_bpm: f32,
@@ -15,6 +34,7 @@ pub struct RhythmFeatures {
     pub rhythm_stability: f32,
     pub downbeat_detected: bool,
     pub beat_position: u8, // 0-3 for quarter notes in 4/4 time
+    pub tempo_lock_changed: bool, // The detector committed to a tempo, or moved its lock, this frame
 }
 
 impl RhythmFeatures {
@@ -28,29 +48,76 @@ impl RhythmFeatures {
             rhythm_stability: 0.0,
             downbeat_detected: false,
             beat_position: 0,
+            tempo_lock_changed: false,
+        }
+    }
+}
+
+/// Metronome tick emitted when the detected beat position advances
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeatClick {
+    pub beat_position: u8,
+    pub downbeat: bool,
+}
+
+impl BeatClick {
+    /// Relative loudness; downbeats are accented
+    pub fn gain(&self) -> f32 {
+        if self.downbeat { CLICK_DOWNBEAT_GAIN } else { CLICK_BEAT_GAIN }
+    }
+}
+
+/// How onset detection measures the rise in low-band energy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnsetFlux {
+    #[default]
+    Linear,  // Absolute energy difference; quiet passages need bigger relative jumps
+    Decibel, // Level difference in dB; the same relative jump counts at any loudness
+}
+
+impl OnsetFlux {
+    pub fn name(&self) -> &'static str {
+        match self {
+            OnsetFlux::Linear => "Linear",
+            OnsetFlux::Decibel => "Decibel",
         }
     }
 }
 
 pub struct RhythmDetector {
     energy_history: VecDeque<f32>,
+    flux_history: VecDeque<f32>,    // Recent spectral flux, for the adaptive onset threshold
     onset_times: VecDeque<f32>,
     last_energy: f32,
     frame_count: u64,
-    #[allow(dead_code)] // Used in tests
-    sample_rate: f32,
+    sample_rate: f32,               // Rate of the audio behind the bins, so bands stay in Hz
     beat_counter: u8,
     last_beat_time: f32,
     tempo_stable: bool,
     tempo_history: VecDeque<f32>,   // Track tempo estimates over time
     last_estimated_bpm: f32,
     tempo_confidence: f32,
+    frame_rate: f32,                // Frames per second process_frame is called at
+    tempo_window: Duration,
+    tempo_window_frames: usize,     // Energy history length derived from tempo_window
+    min_bpm: f32,                   // Tempo search range
+    max_bpm: f32,
+    click_enabled: bool,            // Emit BeatClicks for audible beat debugging
+    pending_click: Option<BeatClick>,
+    tempo_lock: Option<f32>,        // Committed BPM that half/double-tempo estimates are folded back onto
+    lock_threshold: f32,            // Confidence above which the current estimate becomes the lock
+    octave_frames: usize,           // Consecutive frames whose estimate sat an octave from the lock
+    onset_sensitivity: f32,         // Divides the onset threshold and ratio margin (1.0 = default)
+    onset_flux: OnsetFlux,
 }
 
 impl RhythmDetector {
+    /// Create a detector for spectra of audio at `sample_rate`; pass the source's real rate
+    /// (e.g. `AudioProcessor::sample_rate`) rather than assuming 44.1kHz
     pub fn new(sample_rate: f32) -> Self {
         Self {
             energy_history: VecDeque::with_capacity(TEMPO_WINDOW_SIZE),
+            flux_history: VecDeque::new(),
             onset_times: VecDeque::with_capacity(50),
             last_energy: 0.0,
             frame_count: 0,
@@ -61,15 +128,206 @@ impl RhythmDetector {
             tempo_history: VecDeque::with_capacity(20),
             last_estimated_bpm: 120.0,
             tempo_confidence: 0.0,
+            frame_rate: DEFAULT_FRAME_RATE,
+            tempo_window: Duration::from_secs_f32(TEMPO_WINDOW_SIZE as f32 / DEFAULT_FRAME_RATE),
+            tempo_window_frames: TEMPO_WINDOW_SIZE,
+            min_bpm: MIN_BPM,
+            max_bpm: MAX_BPM,
+            click_enabled: false,
+            pending_click: None,
+            tempo_lock: None,
+            lock_threshold: DEFAULT_TEMPO_LOCK_THRESHOLD,
+            octave_frames: 0,
+            onset_sensitivity: 1.0,
+            onset_flux: OnsetFlux::Linear,
+        }
+    }
+
+    /// Follow a source whose sample rate changed; tempo history is kept since timing is per frame
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate.max(1.0);
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+
+    /// BPM the detector has committed to, if any
+    pub fn tempo_lock(&self) -> Option<f32> {
+        self.tempo_lock
+    }
+
+    /// Tempo confidence (0-1) at which the detector commits to its estimate
+    pub fn set_lock_threshold(&mut self, threshold: f32) {
+        self.lock_threshold = threshold.clamp(0.0, 1.0);
+    }
+
+    pub fn lock_threshold(&self) -> f32 {
+        self.lock_threshold
+    }
+
+    /// Forget the committed tempo and its history, e.g. when the track changes
+    pub fn reset_tempo_lock(&mut self) {
+        self.tempo_lock = None;
+        self.octave_frames = 0;
+        self.tempo_history.clear();
+        self.tempo_confidence = 0.0;
+    }
+
+    /// Forget the energy and flux history onsets are measured against, e.g. after a seek, so
+    /// the jump in the audio doesn't read as a hit. Tempo tracking carries on.
+    pub fn reset_onset_history(&mut self) {
+        self.energy_history.clear();
+        self.flux_history.clear();
+        self.last_energy = 0.0;
+    }
+
+    /// Whether `bpm` sits at half or double `reference`
+    fn is_octave_of(bpm: f32, reference: f32) -> bool {
+        let ratio = bpm / reference;
+        (ratio - 2.0).abs() < 2.0 * TEMPO_OCTAVE_TOLERANCE || (ratio - 0.5).abs() < 0.5 * TEMPO_OCTAVE_TOLERANCE
+    }
+
+    /// Fold a half/double-tempo estimate back onto the locked tempo's octave
+    fn fold_to_lock(&self, bpm: f32) -> f32 {
+        match self.tempo_lock {
+            Some(lock) if Self::is_octave_of(bpm, lock) => {
+                if bpm > lock { bpm / 2.0 } else { bpm * 2.0 }
+            }
+            _ => bpm,
+        }
+    }
+
+    /// Octave correction with hysteresis: octave jumps are folded back until they persist
+    /// for `TEMPO_LOCK_RELEASE_SECONDS`, after which the new tempo is accepted
+    fn apply_tempo_lock(&mut self, bpm: f32) -> f32 {
+        let Some(lock) = self.tempo_lock else {
+            return bpm;
+        };
+        if !Self::is_octave_of(bpm, lock) {
+            self.octave_frames = 0;
+            return bpm;
+        }
+
+        self.octave_frames += 1;
+        if self.octave_frames as f32 >= TEMPO_LOCK_RELEASE_SECONDS * self.frame_rate {
+            self.tempo_lock = Some(bpm);
+            self.octave_frames = 0;
+            self.tempo_history.clear();
+            return bpm;
+        }
+        self.fold_to_lock(bpm)
+    }
+
+    /// Set the tempo search range, e.g. 40-240 for half-time trap or slow ambient pulses
+    pub fn set_bpm_range(&mut self, min_bpm: f32, max_bpm: f32) -> Result<()> {
+        let (lower, upper) = BPM_RANGE_LIMITS;
+        if min_bpm.is_nan() || max_bpm.is_nan() || min_bpm >= max_bpm {
+            return Err(anyhow!("Invalid BPM range {}-{}: minimum must be below maximum", min_bpm, max_bpm));
+        }
+        if min_bpm < lower || max_bpm > upper {
+            return Err(anyhow!("BPM range {}-{} outside supported {}-{}", min_bpm, max_bpm, lower, upper));
+        }
+
+        self.min_bpm = min_bpm;
+        self.max_bpm = max_bpm;
+        Ok(())
+    }
+
+    pub fn bpm_range(&self) -> (f32, f32) {
+        (self.min_bpm, self.max_bpm)
+    }
+
+    /// Scale onset detection: above 1.0 catches softer onsets, below 1.0 ignores more of them
+    /// (clamped to 0.1-10.0)
+    pub fn set_onset_sensitivity(&mut self, sensitivity: f32) {
+        self.onset_sensitivity = sensitivity.clamp(ONSET_SENSITIVITY_RANGE.0, ONSET_SENSITIVITY_RANGE.1);
+    }
+
+    pub fn onset_sensitivity(&self) -> f32 {
+        self.onset_sensitivity
+    }
+
+    /// Measure onset energy rises linearly (default) or in dB
+    pub fn set_onset_flux(&mut self, flux: OnsetFlux) {
+        self.onset_flux = flux;
+    }
+
+    pub fn onset_flux(&self) -> OnsetFlux {
+        self.onset_flux
+    }
+
+    /// Emit a `BeatClick` on every detected beat, to hear whether detection lines up with the music
+    pub fn set_click_enabled(&mut self, enabled: bool) {
+        self.click_enabled = enabled;
+        if !enabled {
+            self.pending_click = None;
+        }
+    }
+
+    pub fn is_click_enabled(&self) -> bool {
+        self.click_enabled
+    }
+
+    /// Consume the click for the most recent beat, if one is waiting
+    pub fn take_click(&mut self) -> Option<BeatClick> {
+        self.pending_click.take()
+    }
+
+    /// Set how much energy history tempo and stability estimates look at
+    pub fn set_tempo_window(&mut self, window: Duration) {
+        self.tempo_window = window;
+        self.resize_tempo_window();
+    }
+
+    pub fn tempo_window(&self) -> Duration {
+        self.tempo_window
+    }
+
+    /// Set the real rate frames arrive at, so timing and windows stay in seconds
+    pub fn set_frame_rate(&mut self, frame_rate: f32) {
+        self.frame_rate = frame_rate.max(1.0);
+        self.resize_tempo_window();
+    }
+
+    /// Number of frames currently retained for tempo analysis
+    pub fn tempo_window_frames(&self) -> usize {
+        self.tempo_window_frames
+    }
+
+    fn resize_tempo_window(&mut self) {
+        self.tempo_window_frames = ((self.tempo_window.as_secs_f32() * self.frame_rate).round() as usize).max(2);
+        while self.energy_history.len() > self.tempo_window_frames {
+            self.energy_history.pop_front();
         }
     }
 
     pub fn process_frame(&mut self, frequency_bins: &[f32]) -> RhythmFeatures {
+        self.process(frequency_bins, None)
+    }
+
+    /// Like `process_frame`, but onsets come from `spectral_flux` (the analyzer's per-bin
+    /// positive spectral difference) against an adaptive threshold instead of low-band
+    /// energy, so pitched onsets with little energy change still register. Tempo, beat
+    /// strength and stability are still tracked from `frequency_bins`.
+    pub fn process_frame_with_flux(&mut self, frequency_bins: &[f32], spectral_flux: f32) -> RhythmFeatures {
+        self.process(frequency_bins, Some(spectral_flux))
+    }
+
+    fn process(&mut self, frequency_bins: &[f32], spectral_flux: Option<f32>) -> RhythmFeatures {
         self.frame_count += 1;
-        let current_time = self.frame_count as f32 / 60.0;
+        let current_time = self.frame_count as f32 / self.frame_rate;
 
         let current_energy = self.calculate_energy(frequency_bins);
-        let onset_detected = self.detect_onset(current_energy);
+        let onset_candidate = match spectral_flux {
+            Some(flux) => self.detect_flux_onset(flux),
+            None => self.detect_onset(current_energy),
+        };
+        if let Some(flux) = spectral_flux {
+            self.push_flux(flux);
+        }
+        let onset_detected = onset_candidate
+            && self.onset_times.back().is_none_or(|&last| current_time - last >= MIN_ONSET_INTERVAL);
 
         let mut downbeat_detected = false;
         let mut beat_position = self.beat_counter;
@@ -82,7 +340,7 @@ impl RhythmDetector {
             }
 
             // Check if this is a strong beat (potential downbeat or beat)
-            let tempo_bpm = self.estimate_tempo();
+            let tempo_bpm = self.fold_to_lock(self.estimate_tempo());
             let expected_beat_interval = 60.0 / tempo_bpm;
             let current_beat_strength = self.calculate_beat_strength(current_energy);
 
@@ -96,22 +354,34 @@ impl RhythmDetector {
                 if self.beat_counter == 0 && current_beat_strength > 0.7 {
                     downbeat_detected = true;
                 }
+
+                if self.click_enabled {
+                    self.pending_click = Some(BeatClick { beat_position, downbeat: downbeat_detected });
+                }
             }
         }
 
         self.energy_history.push_back(current_energy);
-        if self.energy_history.len() > TEMPO_WINDOW_SIZE {
+        if self.energy_history.len() > self.tempo_window_frames {
             self.energy_history.pop_front();
         }
 
-        let tempo_bpm = self.estimate_tempo();
         let beat_strength = self.calculate_beat_strength(current_energy);
         let rhythm_stability = self.calculate_rhythm_stability();
 
-        // Enhanced BPM estimation and confidence tracking
-        let estimated_bpm = self.estimate_tempo();
+        // Enhanced BPM estimation with octave correction and confidence tracking
+        let lock_before = self.tempo_lock;
+        let estimated_bpm = self.apply_tempo_lock(self.estimate_tempo());
+        let mut tempo_lock_changed = lock_before.is_some() && self.tempo_lock != lock_before;
+        let tempo_bpm = estimated_bpm;
         self.update_tempo_confidence(estimated_bpm);
 
+        // Commit once confident; the lock then follows gradual drift
+        if self.tempo_confidence >= self.lock_threshold {
+            tempo_lock_changed |= self.tempo_lock.is_none();
+            self.tempo_lock = Some(self.last_estimated_bpm);
+        }
+
         // Mark tempo as stable if rhythm stability is high
         if rhythm_stability > 0.6 {
             self.tempo_stable = true;
@@ -128,12 +398,16 @@ impl RhythmDetector {
             rhythm_stability,
             downbeat_detected,
             beat_position,
+            tempo_lock_changed,
         }
     }
 
     fn calculate_energy(&self, frequency_bins: &[f32]) -> f32 {
+        // Bins span 0 Hz to Nyquist, so the band's bin count depends on the sample rate
+        let nyquist = self.sample_rate / 2.0;
+        let band_bins = (frequency_bins.len() as f32 * (ONSET_BAND_MAX_HZ / nyquist).min(1.0)).round() as usize;
         frequency_bins.iter()
-            .take(frequency_bins.len() / 4)
+            .take(band_bins)
             .map(|&x| x * x)
             .sum::<f32>()
             .sqrt()
@@ -149,8 +423,41 @@ impl RhythmDetector {
             .take(10)
             .sum::<f32>() / 10.0;
 
-        let energy_increase = current_energy - recent_avg;
-        energy_increase > ONSET_THRESHOLD && current_energy > self.last_energy * 1.2
+        // Sensitivity shrinks both the rise over the average and the margin over the last frame
+        let required_ratio = 1.0 + (ONSET_ENERGY_RATIO - 1.0) / self.onset_sensitivity;
+        let exceeds_average = match self.onset_flux {
+            OnsetFlux::Linear => current_energy - recent_avg > ONSET_THRESHOLD / self.onset_sensitivity,
+            OnsetFlux::Decibel => {
+                let to_db = |energy: f32| 20.0 * energy.max(ONSET_ENERGY_FLOOR).log10();
+                to_db(current_energy) - to_db(recent_avg) > ONSET_THRESHOLD_DB / self.onset_sensitivity
+            }
+        };
+        exceeds_average && current_energy > self.last_energy * required_ratio
+    }
+
+    /// Flux onset: a rise above the previous frame that clears the recent mean by
+    /// `ONSET_FLUX_DEVIATIONS` standard deviations (at least `ONSET_FLUX_MIN_RISE`), so busy
+    /// passages need bigger spikes than quiet ones
+    fn detect_flux_onset(&self, flux: f32) -> bool {
+        if self.flux_history.len() < 10 {
+            return false;
+        }
+
+        let count = self.flux_history.len() as f32;
+        let mean = self.flux_history.iter().sum::<f32>() / count;
+        let variance = self.flux_history.iter().map(|&f| (f - mean) * (f - mean)).sum::<f32>() / count;
+        let margin = (ONSET_FLUX_DEVIATIONS * variance.sqrt()).max(ONSET_FLUX_MIN_RISE) / self.onset_sensitivity;
+
+        let previous = self.flux_history.back().copied().unwrap_or(0.0);
+        flux > mean + margin && flux > previous
+    }
+
+    fn push_flux(&mut self, flux: f32) {
+        let window_frames = ((ONSET_FLUX_WINDOW * self.frame_rate).round() as usize).max(10);
+        self.flux_history.push_back(flux);
+        while self.flux_history.len() > window_frames {
+            self.flux_history.pop_front();
+        }
     }
 
     fn estimate_tempo(&self) -> f32 {
@@ -190,7 +497,7 @@ impl RhythmDetector {
             histogram_tempo * 0.7 + autocorr_tempo * 0.3
         };
 
-        final_tempo.clamp(MIN_BPM, MAX_BPM)
+        final_tempo.clamp(self.min_bpm, self.max_bpm)
     }
 
     fn find_tempo_candidates(&self, intervals: &[f32]) -> Vec<f32> {
@@ -199,7 +506,7 @@ impl RhythmDetector {
 
         for &interval in intervals {
             let bpm = (60.0 / interval) as u32;
-            if bpm >= MIN_BPM as u32 && bpm <= MAX_BPM as u32 {
+            if bpm >= self.min_bpm as u32 && bpm <= self.max_bpm as u32 {
                 *bpm_counts.entry(bpm).or_insert(0) += 1;
                 // Also count nearby BPM values to handle slight variations
                 *bpm_counts.entry(bpm.saturating_sub(1)).or_insert(0) += 1;
@@ -228,12 +535,13 @@ impl RhythmDetector {
             return 120.0;
         }
 
-        // Test different period lengths for periodicity
-        let mut best_score = 0.0;
-        let mut best_period = 0.5; // 120 BPM default
+        // Score different period lengths for periodicity
+        let mut scores = Vec::new();
 
-        // Test periods from 0.3s (200 BPM) to 1.0s (60 BPM)
-        for test_period in (30..=100).map(|x| x as f32 / 100.0) {
+        // Test periods across the BPM range in 10ms steps (0.3s-1.0s for the default 60-200 BPM)
+        let shortest_period = ((6000.0 / self.max_bpm) - 1e-3).ceil() as u32;
+        let longest_period = ((6000.0 / self.min_bpm) + 1e-3).floor() as u32;
+        for test_period in (shortest_period..=longest_period).map(|x| x as f32 / 100.0) {
             let mut score = 0.0;
             let mut count = 0;
 
@@ -255,14 +563,23 @@ impl RhythmDetector {
             }
 
             if count > 0 {
-                score /= count as f32;
-                if score > best_score {
-                    best_score = score;
-                    best_period = test_period;
-                }
+                scores.push((test_period, score / count as f32));
             }
         }
 
+        // Multiples of the beat period score almost as well as the beat itself; among near-ties
+        // prefer the period closest to the locked tempo, otherwise the shortest
+        let best_score = scores.iter().fold(0.0f32, |acc, &(_, score)| acc.max(score));
+        let target_period = self.tempo_lock.map(|bpm| 60.0 / bpm);
+        let best_period = scores.iter()
+            .filter(|&&(_, score)| best_score > 0.0 && score >= best_score * AUTOCORRELATION_TIE_RATIO)
+            .map(|&(period, _)| period)
+            .min_by(|a, b| match target_period {
+                Some(target) => (a - target).abs().total_cmp(&(b - target).abs()),
+                None => a.total_cmp(b),
+            })
+            .unwrap_or(0.5); // 120 BPM default
+
         60.0 / best_period
     }
 
@@ -376,6 +693,33 @@ mod tests {
         assert_abs_diff_eq!(tempo, 120.0, epsilon = 5.0);
     }
 
+    #[test]
+    fn test_wider_bpm_range_detects_slow_tempo() {
+        let mut detector = RhythmDetector::new(44100.0);
+        for i in 0..10 {
+            detector.onset_times.push_back(i as f32 * 1.25); // 48 BPM click train
+        }
+
+        // The default 60-200 range can't report it
+        assert!(detector.estimate_tempo() >= MIN_BPM);
+
+        detector.set_bpm_range(40.0, 240.0).unwrap();
+        assert_abs_diff_eq!(detector.estimate_tempo(), 48.0, epsilon = 2.0);
+    }
+
+    #[test]
+    fn test_bpm_range_validation() {
+        let mut detector = RhythmDetector::new(44100.0);
+        assert!(detector.set_bpm_range(120.0, 90.0).is_err());
+        assert!(detector.set_bpm_range(100.0, 100.0).is_err());
+        assert!(detector.set_bpm_range(5.0, 200.0).is_err());
+        assert!(detector.set_bpm_range(60.0, 1000.0).is_err());
+        assert_eq!(detector.bpm_range(), (MIN_BPM, MAX_BPM));
+
+        assert!(detector.set_bpm_range(40.0, 240.0).is_ok());
+        assert_eq!(detector.bpm_range(), (40.0, 240.0));
+    }
+
     #[test]
     fn test_rhythm_features_default() {
         let features = RhythmFeatures::new();
@@ -386,4 +730,215 @@ mod tests {
         assert_eq!(features.downbeat_detected, false);
         assert_eq!(features.beat_position, 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_longer_tempo_window_retains_more_history() {
+        let mut short = RhythmDetector::new(44100.0);
+        short.set_tempo_window(Duration::from_secs(1));
+        let mut long = RhythmDetector::new(44100.0);
+        long.set_tempo_window(Duration::from_secs(3));
+
+        let bins = vec![0.2; 64];
+        for _ in 0..150 {
+            short.process_frame(&bins);
+            long.process_frame(&bins);
+        }
+
+        assert_eq!(short.energy_history.len(), 60);
+        assert_eq!(long.energy_history.len(), 150);
+
+        long.set_frame_rate(30.0);
+        assert_eq!(long.tempo_window_frames(), 90);
+        assert_eq!(long.energy_history.len(), 90);
+    }
+
+    /// Feed `seconds` of frames at 60fps: a steady bed with a single-frame energy pulse at `bpm`
+    fn drive_pulses(detector: &mut RhythmDetector, bpm: f32, seconds: f32, clicks: &mut Vec<BeatClick>) {
+        let frames_per_beat = (60.0 * 60.0 / bpm).round() as usize;
+        for frame in 0..(seconds * 60.0) as usize {
+            let bins = if frame % frames_per_beat == 0 { vec![1.0; 8] } else { vec![0.3; 8] };
+            detector.process_frame(&bins);
+            clicks.extend(detector.take_click());
+        }
+    }
+
+    #[test]
+    fn test_clicks_follow_detected_beats() {
+        let mut detector = RhythmDetector::new(44100.0);
+        detector.set_click_enabled(true);
+
+        // Let tempo lock in, then count clicks over a fixed eight-second window
+        let mut clicks = Vec::new();
+        drive_pulses(&mut detector, 120.0, 6.0, &mut clicks);
+        clicks.clear();
+        drive_pulses(&mut detector, 120.0, 8.0, &mut clicks);
+
+        assert!((15..=17).contains(&clicks.len()), "expected ~16 clicks at 120 BPM, got {}", clicks.len());
+        let downbeats = clicks.iter().filter(|click| click.downbeat).count();
+        assert!((3..=5).contains(&downbeats), "expected ~4 downbeats, got {}", downbeats);
+        assert!(clicks.iter().filter(|click| click.downbeat).all(|click| click.beat_position == 0));
+        const { assert!(CLICK_DOWNBEAT_GAIN > CLICK_BEAT_GAIN) };
+
+        // Disabled detectors stay silent
+        detector.set_click_enabled(false);
+        clicks.clear();
+        drive_pulses(&mut detector, 120.0, 4.0, &mut clicks);
+        assert!(clicks.is_empty());
+    }
+
+    #[test]
+    fn test_tempo_lock_resists_octave_errors() {
+        let mut detector = RhythmDetector::new(44100.0);
+        detector.set_bpm_range(60.0, 260.0).unwrap();
+        let mut clicks = Vec::new();
+
+        drive_pulses(&mut detector, 120.0, 10.0, &mut clicks);
+        let locked = detector.tempo_lock().expect("steady pulses should lock the tempo");
+        assert!((locked - 120.0).abs() < 2.0, "expected lock near 120 BPM, got {}", locked);
+
+        // A brief double-time passage is folded back onto the locked tempo
+        drive_pulses(&mut detector, 240.0, 2.0, &mut clicks);
+        let locked = detector.tempo_lock().unwrap();
+        assert!((locked - 120.0).abs() < 2.0, "lock drifted to {} during double-time burst", locked);
+        assert!((detector.last_estimated_bpm - 120.0).abs() < 2.0);
+
+        detector.reset_tempo_lock();
+        assert!(detector.tempo_lock().is_none());
+        assert_eq!(detector.tempo_confidence, 0.0);
+    }
+
+    #[test]
+    fn test_tempo_lock_change_is_reported_once() {
+        let mut detector = RhythmDetector::new(44100.0);
+        let mut changes = 0;
+        for frame in 0..600 {
+            let bins = if frame % 30 == 0 { vec![1.0; 8] } else { vec![0.3; 8] }; // 120 BPM at 60 FPS
+            if detector.process_frame(&bins).tempo_lock_changed {
+                changes += 1;
+            }
+        }
+
+        // Taking the lock is reported; following gradual drift afterwards is not
+        assert!(detector.tempo_lock().is_some());
+        assert_eq!(changes, 1);
+    }
+
+    #[test]
+    fn test_tempo_lock_threshold() {
+        let mut detector = RhythmDetector::new(44100.0);
+        assert_eq!(detector.lock_threshold(), DEFAULT_TEMPO_LOCK_THRESHOLD);
+        detector.set_lock_threshold(1.5);
+        assert_eq!(detector.lock_threshold(), 1.0);
+
+        detector.set_lock_threshold(-0.5);
+        assert_eq!(detector.lock_threshold(), 0.0);
+
+        // No onsets yet, so nothing to lock onto
+        assert!(detector.tempo_lock().is_none());
+
+        assert!(RhythmDetector::is_octave_of(240.0, 120.0));
+        assert!(RhythmDetector::is_octave_of(61.0, 120.0));
+        assert!(!RhythmDetector::is_octave_of(180.0, 120.0));
+    }
+
+    /// Onsets from a steady bed with a single-frame bump every half second
+    fn count_onsets(detector: &mut RhythmDetector, bed: f32, bump: f32) -> usize {
+        (0..240)
+            .filter(|frame| {
+                let level = if frame % 30 == 29 { bump } else { bed };
+                detector.process_frame(&[level; 8]).onset_detected
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_onset_sensitivity_catches_small_bumps() {
+        // Low-band energy rises by ~0.05: under the default threshold of 0.1
+        let mut default = RhythmDetector::new(44100.0);
+        assert_eq!(default.onset_sensitivity(), 1.0);
+        assert_eq!(count_onsets(&mut default, 0.3, 0.335), 0);
+
+        let mut low = RhythmDetector::new(44100.0);
+        low.set_onset_sensitivity(0.5);
+        assert_eq!(count_onsets(&mut low, 0.3, 0.335), 0);
+
+        let mut high = RhythmDetector::new(44100.0);
+        high.set_onset_sensitivity(3.0);
+        assert_eq!(count_onsets(&mut high, 0.3, 0.335), 8);
+
+        high.set_onset_sensitivity(100.0);
+        assert_eq!(high.onset_sensitivity(), 10.0);
+    }
+
+    #[test]
+    fn test_decibel_flux_is_level_independent() {
+        // The same relative bump, one loud and one quiet: linear flux only hears the loud one
+        let mut linear = RhythmDetector::new(44100.0);
+        assert_eq!(linear.onset_flux(), OnsetFlux::Linear);
+        assert_eq!(count_onsets(&mut linear, 0.3, 0.6), 8);
+        let mut linear = RhythmDetector::new(44100.0);
+        assert_eq!(count_onsets(&mut linear, 0.01, 0.02), 0);
+
+        let mut decibel = RhythmDetector::new(44100.0);
+        decibel.set_onset_flux(OnsetFlux::Decibel);
+        assert_eq!(count_onsets(&mut decibel, 0.01, 0.02), 8);
+
+        // A 0.8 dB bump needs more than default sensitivity in dB too
+        let mut decibel = RhythmDetector::new(44100.0);
+        decibel.set_onset_flux(OnsetFlux::Decibel);
+        assert_eq!(count_onsets(&mut decibel, 0.3, 0.33), 0);
+        decibel.set_onset_sensitivity(5.0);
+        assert_eq!(count_onsets(&mut decibel, 0.3, 0.33), 8);
+    }
+
+    #[test]
+    fn test_flux_spike_triggers_onset_without_energy_change() {
+        // A pitch change at constant level: flat low-band energy, one spike in spectral flux
+        let bins = [0.4; 8];
+        let mut energy = RhythmDetector::new(44100.0);
+        let mut flux = RhythmDetector::new(44100.0);
+
+        for frame in 0..30 {
+            assert!(!energy.process_frame(&bins).onset_detected);
+            let quiet_flux = if frame % 2 == 0 { 0.02 } else { 0.03 };
+            assert!(!flux.process_frame_with_flux(&bins, quiet_flux).onset_detected, "frame {}", frame);
+        }
+
+        assert!(!energy.process_frame(&bins).onset_detected);
+        assert!(flux.process_frame_with_flux(&bins, 0.55).onset_detected);
+
+        // The old energy path is unaffected by flux frames
+        assert!(!flux.process_frame(&bins).onset_detected);
+    }
+
+    #[test]
+    fn test_flux_threshold_adapts_to_busy_passages() {
+        let bins = [0.4; 8];
+        let mut detector = RhythmDetector::new(44100.0);
+
+        // Flux swinging between 0.5 and 0.1 puts the threshold near 0.6
+        for frame in 0..30 {
+            let busy_flux = if frame % 2 == 0 { 0.5 } else { 0.1 };
+            detector.process_frame_with_flux(&bins, busy_flux);
+        }
+        assert!(!detector.process_frame_with_flux(&bins, 0.55).onset_detected);
+
+        // Once things calm down, the same spike stands out
+        for _ in 0..30 {
+            detector.process_frame_with_flux(&bins, 0.02);
+        }
+        assert!(detector.process_frame_with_flux(&bins, 0.55).onset_detected);
+    }
+
+    #[test]
+    fn test_energy_band_follows_sample_rate() {
+        // The 0-5.5kHz onset band is the lowest 256 of 1024 bins at 44.1kHz, but only 235 at 48kHz
+        let bins = vec![1.0; 1024];
+        assert_abs_diff_eq!(RhythmDetector::new(44100.0).calculate_energy(&bins), 16.0, epsilon = 1e-3);
+
+        let mut detector = RhythmDetector::new(44100.0);
+        detector.set_sample_rate(48000.0);
+        assert_eq!(detector.sample_rate(), 48000.0);
+        assert_abs_diff_eq!(detector.calculate_energy(&bins), 235.0f32.sqrt(), epsilon = 1e-3);
+    }
+}
@@ -0,0 +1,255 @@
+// Synthetic test signals: deterministic stand-ins for live input so bug reports and
+// golden-frame tests can reproduce the exact audio that drove the visuals
+
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+/// Sample rate used when a spec does not ask for another one
+pub const DEFAULT_SIGNAL_SAMPLE_RATE: u32 = 44100;
+
+/// Seed for the noise generators; any fixed value keeps runs reproducible
+const DEFAULT_NOISE_SEED: u64 = 0x5EED_A5A5_0B5E_55ED;
+
+/// Scale keeping the summed pink noise filter output near the white noise range
+const PINK_NOISE_GAIN: f32 = 0.2;
+
+/// Shape of a generated test signal
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Waveform {
+    Sine,
+    Square,
+    WhiteNoise, // Equal energy per Hz
+    PinkNoise,  // Equal energy per octave (-3 dB/octave)
+    Sweep { end_frequency: f32, duration: Duration }, // Linear chirp from `frequency`, restarting after `duration`
+}
+
+impl Waveform {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Waveform::Sine => "Sine",
+            Waveform::Square => "Square",
+            Waveform::WhiteNoise => "White noise",
+            Waveform::PinkNoise => "Pink noise",
+            Waveform::Sweep { .. } => "Sweep",
+        }
+    }
+}
+
+/// Everything needed to regenerate a test signal sample for sample
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalSpec {
+    pub waveform: Waveform,
+    pub frequency: f32, // Hz; start frequency for sweeps, ignored by noise
+    pub amplitude: f32, // Peak level (0.0 to 1.0)
+    pub sample_rate: u32,
+    pub seed: u64, // Noise generator seed
+}
+
+impl SignalSpec {
+    pub fn new(waveform: Waveform, frequency: f32, amplitude: f32) -> Self {
+        Self {
+            waveform,
+            frequency,
+            amplitude: amplitude.clamp(0.0, 1.0),
+            sample_rate: DEFAULT_SIGNAL_SAMPLE_RATE,
+            seed: DEFAULT_NOISE_SEED,
+        }
+    }
+
+    pub fn sine(frequency: f32, amplitude: f32) -> Self {
+        Self::new(Waveform::Sine, frequency, amplitude)
+    }
+
+    pub fn square(frequency: f32, amplitude: f32) -> Self {
+        Self::new(Waveform::Square, frequency, amplitude)
+    }
+
+    pub fn white_noise(amplitude: f32) -> Self {
+        Self::new(Waveform::WhiteNoise, 0.0, amplitude)
+    }
+
+    pub fn pink_noise(amplitude: f32) -> Self {
+        Self::new(Waveform::PinkNoise, 0.0, amplitude)
+    }
+
+    pub fn sweep(start_frequency: f32, end_frequency: f32, duration: Duration, amplitude: f32) -> Self {
+        Self::new(Waveform::Sweep { end_frequency, duration }, start_frequency, amplitude)
+    }
+
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = sample_rate.max(1);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Endless mono sample stream for a `SignalSpec`; the same spec always yields the same samples
+#[derive(Debug, Clone)]
+pub struct SignalGenerator {
+    spec: SignalSpec,
+    phase: f32,       // Oscillator phase in cycles (0.0 to 1.0)
+    sample_index: u64, // Samples since the start of the current sweep
+    rng_state: u64,
+    pink_state: [f32; 3], // Paul Kellet's economy pink noise filter
+}
+
+impl SignalGenerator {
+    pub fn new(spec: SignalSpec) -> Self {
+        Self {
+            spec,
+            phase: 0.0,
+            sample_index: 0,
+            rng_state: spec.seed.max(1), // xorshift never leaves zero
+            pink_state: [0.0; 3],
+        }
+    }
+
+    pub fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+
+    /// Restart from the first sample
+    pub fn reset(&mut self) {
+        *self = Self::new(self.spec);
+    }
+
+    /// Generate the next `count` samples
+    pub fn take_samples(&mut self, count: usize) -> Vec<f32> {
+        self.by_ref().take(count).collect()
+    }
+
+    fn next_sample(&mut self) -> f32 {
+        let sample_rate = self.spec.sample_rate as f32;
+        let value = match self.spec.waveform {
+            Waveform::Sine => self.advance_phase(self.spec.frequency / sample_rate, |phase| (TAU * phase).sin()),
+            Waveform::Square => self.advance_phase(self.spec.frequency / sample_rate, |phase| if phase < 0.5 { 1.0 } else { -1.0 }),
+            Waveform::WhiteNoise => self.white(),
+            Waveform::PinkNoise => self.pink(),
+            Waveform::Sweep { end_frequency, duration } => {
+                let length = (duration.as_secs_f32() * sample_rate).round().max(1.0) as u64;
+                if self.sample_index >= length {
+                    self.sample_index = 0;
+                    self.phase = 0.0;
+                }
+                let progress = self.sample_index as f32 / length as f32;
+                let frequency = self.spec.frequency + (end_frequency - self.spec.frequency) * progress;
+                self.advance_phase(frequency / sample_rate, |phase| (TAU * phase).sin())
+            }
+        };
+        self.sample_index += 1;
+        value * self.spec.amplitude
+    }
+
+    /// Value at the current phase, then step the phase by `increment` cycles
+    fn advance_phase(&mut self, increment: f32, shape: impl Fn(f32) -> f32) -> f32 {
+        let value = shape(self.phase);
+        self.phase = (self.phase + increment).rem_euclid(1.0);
+        value
+    }
+
+    /// Uniform sample in -1.0..1.0 from a xorshift64 generator
+    fn white(&mut self) -> f32 {
+        let mut x = self.rng_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.rng_state = x;
+        (x >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    fn pink(&mut self) -> f32 {
+        let white = self.white();
+        let [b0, b1, b2] = &mut self.pink_state;
+        *b0 = 0.99765 * *b0 + white * 0.0990460;
+        *b1 = 0.96300 * *b1 + white * 0.2965164;
+        *b2 = 0.57000 * *b2 + white * 1.0526913;
+        ((*b0 + *b1 + *b2 + white * 0.1848) * PINK_NOISE_GAIN).clamp(-1.0, 1.0)
+    }
+}
+
+impl Iterator for SignalGenerator {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        Some(self.next_sample())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{FftAnalyzer, WindowFunction};
+
+    const WINDOW: usize = 1024;
+
+    /// Mean magnitude spectrum over `frames` consecutive windows
+    fn average_spectrum(spec: SignalSpec, frames: usize) -> Vec<f32> {
+        let mut generator = SignalGenerator::new(spec);
+        let mut fft = FftAnalyzer::new(WINDOW, WindowFunction::default());
+        let mut total = vec![0.0; WINDOW / 2];
+        for _ in 0..frames {
+            let bins = fft.process_audio(&generator.take_samples(WINDOW));
+            for (sum, bin) in total.iter_mut().zip(bins) {
+                *sum += bin / frames as f32;
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn test_same_spec_reproduces_samples() {
+        let spec = SignalSpec::white_noise(0.5);
+        let first = SignalGenerator::new(spec).take_samples(512);
+        assert_eq!(first, SignalGenerator::new(spec).take_samples(512));
+        assert!(first.iter().all(|s| s.abs() <= 0.5));
+        assert_ne!(first, SignalGenerator::new(spec.seed(7)).take_samples(512));
+
+        let mut generator = SignalGenerator::new(spec);
+        generator.take_samples(100);
+        generator.reset();
+        assert_eq!(generator.take_samples(512), first);
+    }
+
+    #[test]
+    fn test_periodic_waveforms() {
+        // 441 Hz at 44.1 kHz repeats every 100 samples
+        let sine = SignalGenerator::new(SignalSpec::sine(441.0, 0.8)).take_samples(200);
+        assert!(sine[0].abs() < 1e-6);
+        assert!((sine[25] - 0.8).abs() < 1e-3);
+        assert!((sine[125] - 0.8).abs() < 1e-3);
+
+        let square = SignalGenerator::new(SignalSpec::square(441.0, 0.5)).take_samples(100);
+        assert!(square[..50].iter().all(|&s| s == 0.5));
+        assert!(square[51..].iter().all(|&s| s == -0.5));
+    }
+
+    #[test]
+    fn test_sweep_moves_energy_up_and_restarts() {
+        let spec = SignalSpec::sweep(200.0, 8000.0, Duration::from_secs(1), 0.8);
+        let mut generator = SignalGenerator::new(spec);
+        let mut fft = FftAnalyzer::new(WINDOW, WindowFunction::default());
+        let peak_bin = |bins: &[f32]| (0..bins.len()).max_by(|&a, &b| bins[a].total_cmp(&bins[b])).unwrap();
+
+        let start = peak_bin(fft.process_audio(&generator.take_samples(WINDOW)));
+        generator.take_samples(DEFAULT_SIGNAL_SAMPLE_RATE as usize / 2);
+        let middle = peak_bin(fft.process_audio(&generator.take_samples(WINDOW)));
+        assert!(middle > start * 10, "sweep should climb: {} -> {}", start, middle);
+
+        // Past the duration it starts again from the low end
+        generator.take_samples(DEFAULT_SIGNAL_SAMPLE_RATE as usize / 2 - 2 * WINDOW);
+        let restarted = peak_bin(fft.process_audio(&generator.take_samples(WINDOW)));
+        assert!(restarted < middle / 4);
+    }
+
+    #[test]
+    fn test_pink_noise_tilts_toward_bass() {
+        let pink = average_spectrum(SignalSpec::pink_noise(1.0), 32);
+        let low: f32 = pink[4..40].iter().sum::<f32>() / 36.0;
+        let high: f32 = pink[400..436].iter().sum::<f32>() / 36.0;
+        assert!(low > high * 3.0, "pink noise low {} vs high {}", low, high);
+    }
+}
@@ -0,0 +1,197 @@
+use std::time::{Duration, Instant};
+
+use crate::audio::{AudioFeatures, RhythmFeatures};
+use crate::clock::{system_clock, SharedClock};
+
+/// Live volume above this counts as real audio and ends attract mode
+pub const ATTRACT_WAKE_LEVEL: f32 = 0.05;
+
+/// How long each shader shows while attracting
+pub const ATTRACT_SHADER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Scale of the demo signal relative to full-level music
+pub const ATTRACT_INTENSITY: f32 = 0.3;
+
+/// Tempo of the demo signal's gentle pulse
+const DEMO_BPM: f32 = 90.0;
+
+/// Idle screensaver for installations: after a stretch of silence, show a low-intensity
+/// demo signal and cycle slowly through shaders until real audio returns
+pub struct AttractMode {
+    clock: SharedClock,
+    enabled: bool,
+    idle_after: Duration,
+    active: bool,
+    last_audio: Instant,
+    started: Instant,
+    last_switch: Instant,
+}
+
+impl AttractMode {
+    pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create an attract-mode tracker driven by a custom time source (tests use `MockClock`)
+    pub fn with_clock(clock: SharedClock) -> Self {
+        let now = clock.now();
+        Self {
+            clock,
+            enabled: false,
+            idle_after: Duration::from_secs(60),
+            active: false,
+            last_audio: now,
+            started: now,
+            last_switch: now,
+        }
+    }
+
+    /// Enable or disable attract mode and set the silence needed before it starts
+    pub fn set_enabled(&mut self, enabled: bool, idle_after: Duration) {
+        self.enabled = enabled;
+        self.idle_after = idle_after;
+        self.last_audio = self.clock.now();
+        if !enabled {
+            self.active = false;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn idle_after(&self) -> Duration {
+        self.idle_after
+    }
+
+    /// Whether the demo signal is currently standing in for live audio
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Feed this frame's live features and return whether attract mode is active
+    pub fn update(&mut self, live: &AudioFeatures) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let now = self.clock.now();
+        if live.overall_volume > ATTRACT_WAKE_LEVEL {
+            self.last_audio = now;
+            if self.active {
+                self.active = false;
+                println!("🎤 Audio detected - leaving attract mode");
+            }
+        } else if !self.active && now.duration_since(self.last_audio) >= self.idle_after {
+            self.active = true;
+            self.started = now;
+            self.last_switch = now;
+            println!("🌙 No audio for {:.0}s - entering attract mode", self.idle_after.as_secs_f32());
+        }
+
+        self.active
+    }
+
+    /// Returns true once per shader interval while active, when it's time to move on
+    pub fn take_shader_switch(&mut self) -> bool {
+        if !self.active {
+            return false;
+        }
+
+        let now = self.clock.now();
+        if now.duration_since(self.last_switch) >= ATTRACT_SHADER_INTERVAL {
+            self.last_switch = now;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Synthetic low-intensity features: a slow pulse with drifting spectral balance
+    pub fn demo_frame(&self) -> (AudioFeatures, RhythmFeatures) {
+        let t = self.clock.now().duration_since(self.started).as_secs_f32();
+        let beats = t * DEMO_BPM / 60.0;
+        let phase = beats.fract();
+        let pulse = (-phase * 6.0).exp();
+        let drift = 0.5 + 0.5 * (t * 0.1).sin();
+
+        let mut audio = AudioFeatures::new();
+        audio.sub_bass = (0.3 + 0.7 * pulse) * ATTRACT_INTENSITY;
+        audio.bass = (0.4 + 0.6 * pulse) * ATTRACT_INTENSITY;
+        audio.mid = (0.3 + 0.4 * drift) * ATTRACT_INTENSITY;
+        audio.treble = (0.2 + 0.4 * (1.0 - drift)) * ATTRACT_INTENSITY;
+        audio.presence = 0.2 * ATTRACT_INTENSITY;
+        audio.overall_volume = (0.4 + 0.3 * pulse) * ATTRACT_INTENSITY;
+        audio.spectral_centroid = 0.3 + 0.3 * drift;
+        audio.pitch_confidence = 0.5;
+        audio.onset_strength = pulse * ATTRACT_INTENSITY;
+
+        let mut rhythm = RhythmFeatures::new();
+        rhythm.beat_strength = pulse * ATTRACT_INTENSITY;
+        rhythm.tempo_bpm = DEMO_BPM;
+        rhythm.estimated_bpm = DEMO_BPM;
+        rhythm.tempo_confidence = 1.0;
+        rhythm.rhythm_stability = 1.0;
+        rhythm.beat_position = (beats as u32 % 4) as u8;
+
+        (audio, rhythm)
+    }
+}
+
+impl Default for AttractMode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn test_attract_enters_after_idle_and_exits_on_audio() {
+        let clock = MockClock::new();
+        let mut attract = AttractMode::with_clock(clock.shared());
+        let silence = AudioFeatures::new();
+
+        // Disabled by default
+        clock.advance_secs(120.0);
+        assert!(!attract.update(&silence));
+
+        attract.set_enabled(true, Duration::from_secs(10));
+        clock.advance_secs(9.0);
+        assert!(!attract.update(&silence));
+        clock.advance_secs(1.0);
+        assert!(attract.update(&silence));
+
+        // The demo signal stays gentle
+        let (demo, rhythm) = attract.demo_frame();
+        assert!(demo.overall_volume > 0.0 && demo.overall_volume <= ATTRACT_INTENSITY);
+        assert_eq!(rhythm.estimated_bpm, DEMO_BPM);
+
+        // One loud frame ends it immediately
+        let mut loud = AudioFeatures::new();
+        loud.overall_volume = 0.6;
+        clock.advance_secs(0.016);
+        assert!(!attract.update(&loud));
+        assert!(!attract.is_active());
+
+        // The idle timer restarts from the last audio
+        clock.advance_secs(5.0);
+        assert!(!attract.update(&silence));
+    }
+
+    #[test]
+    fn test_attract_switches_shaders_slowly() {
+        let clock = MockClock::new();
+        let mut attract = AttractMode::with_clock(clock.shared());
+        attract.set_enabled(true, Duration::ZERO);
+        assert!(attract.update(&AudioFeatures::new()));
+
+        assert!(!attract.take_shader_switch());
+        clock.advance(ATTRACT_SHADER_INTERVAL);
+        assert!(attract.take_shader_switch());
+        assert!(!attract.take_shader_switch());
+    }
+}
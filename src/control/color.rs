@@ -0,0 +1,385 @@
+// CPU-side color math shared by palettes, the safety engine and color modes.
+// RGB channels are 0.0 to 1.0, hues are normalized turns (0.0 to 1.0, matching
+// `ColorPalette::base_hue` and the shaders), and Oklab/Oklch operate on linear RGB.
+
+/// Simple 3D vector for RGB color operations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vector3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vector3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl Vector3<f32> {
+    /// Multiply vector by scalar
+    pub fn mul_scalar(self, scalar: f32) -> Self {
+        Self {
+            x: self.x * scalar,
+            y: self.y * scalar,
+            z: self.z * scalar,
+        }
+    }
+}
+
+impl std::ops::Mul<f32> for Vector3<f32> {
+    type Output = Vector3<f32>;
+
+    fn mul(self, scalar: f32) -> Self::Output {
+        self.mul_scalar(scalar)
+    }
+}
+
+/// Decode an sRGB-encoded channel value to linear light
+pub fn srgb_to_linear(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a linear channel value with the sRGB transfer function
+pub fn linear_to_srgb(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Relative luminance of linear RGB (ITU-R BT.709 weights)
+pub fn relative_luminance(rgb: Vector3<f32>) -> f32 {
+    0.2126 * rgb.x + 0.7152 * rgb.y + 0.0722 * rgb.z
+}
+
+/// Supported white-balance color temperatures in Kelvin (min, max)
+pub const WHITE_BALANCE_RANGE_KELVIN: (f32, f32) = (2000.0, 12000.0);
+
+/// Color temperature that leaves output untouched (D65, the sRGB white point)
+pub const NEUTRAL_WHITE_BALANCE_KELVIN: f32 = 6500.0;
+
+/// Color temperature on a -1.0 (cool, the top of `WHITE_BALANCE_RANGE_KELVIN`) to +1.0
+/// (warm, the bottom) scale, 0.0 neutral. Each half is linear in mireds (1e6 / Kelvin), so
+/// equal steps look like equal shifts, as with photographic filters.
+pub fn color_temperature_to_kelvin(temperature: f32) -> f32 {
+    let (min, max) = WHITE_BALANCE_RANGE_KELVIN;
+    let t = if temperature.is_finite() { temperature.clamp(-1.0, 1.0) } else { 0.0 };
+    let neutral = 1.0e6 / NEUTRAL_WHITE_BALANCE_KELVIN;
+    let extreme = if t >= 0.0 { 1.0e6 / min } else { 1.0e6 / max };
+    1.0e6 / (neutral + (extreme - neutral) * t.abs())
+}
+
+/// Inverse of `color_temperature_to_kelvin`, clamping to the supported range
+pub fn kelvin_to_color_temperature(kelvin: f32) -> f32 {
+    let (min, max) = WHITE_BALANCE_RANGE_KELVIN;
+    let kelvin = if kelvin.is_finite() { kelvin.clamp(min, max) } else { NEUTRAL_WHITE_BALANCE_KELVIN };
+    let neutral = 1.0e6 / NEUTRAL_WHITE_BALANCE_KELVIN;
+    let mired = 1.0e6 / kelvin;
+    if mired >= neutral {
+        (mired - neutral) / (1.0e6 / min - neutral)
+    } else {
+        -(neutral - mired) / (neutral - 1.0e6 / max)
+    }
+}
+
+/// Approximate sRGB color of a blackbody at the given temperature (Tanner Helland's fit)
+pub fn kelvin_to_srgb(kelvin: f32) -> Vector3<f32> {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.69873 * (t - 60.0).powf(-0.13320476)
+    };
+    let g = if t <= 66.0 {
+        99.4708 * t.ln() - 161.11957
+    } else {
+        288.12216 * (t - 60.0).powf(-0.075514846)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.51773 * (t - 10.0).ln() - 305.0448
+    };
+
+    Vector3::new(
+        (r / 255.0).clamp(0.0, 1.0),
+        (g / 255.0).clamp(0.0, 1.0),
+        (b / 255.0).clamp(0.0, 1.0),
+    )
+}
+
+/// Linear RGB multiplier that tints neutral output toward a color temperature
+///
+/// The temperature is clamped to `WHITE_BALANCE_RANGE_KELVIN` and the result is relative to
+/// `NEUTRAL_WHITE_BALANCE_KELVIN`, normalized so no channel exceeds 1.0 (tinting never brightens).
+pub fn white_balance_multiplier(kelvin: f32) -> Vector3<f32> {
+    let (min, max) = WHITE_BALANCE_RANGE_KELVIN;
+    let kelvin = if kelvin.is_finite() { kelvin.clamp(min, max) } else { NEUTRAL_WHITE_BALANCE_KELVIN };
+
+    let linear = |c: Vector3<f32>| Vector3::new(srgb_to_linear(c.x), srgb_to_linear(c.y), srgb_to_linear(c.z));
+    let target = linear(kelvin_to_srgb(kelvin));
+    let neutral = linear(kelvin_to_srgb(NEUTRAL_WHITE_BALANCE_KELVIN));
+
+    let ratio = Vector3::new(
+        target.x / neutral.x.max(1e-6),
+        target.y / neutral.y.max(1e-6),
+        target.z / neutral.z.max(1e-6),
+    );
+    let peak = ratio.x.max(ratio.y).max(ratio.z).max(1e-6);
+    ratio * (1.0 / peak)
+}
+
+/// Hue (turns), saturation and value, all 0.0 to 1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+impl Hsv {
+    pub fn new(h: f32, s: f32, v: f32) -> Self {
+        Self { h, s, v }
+    }
+}
+
+/// Fully saturated RGB for a hue in turns (same formula as the shaders' `hue_to_rgb`)
+pub fn hue_to_rgb(hue: f32) -> Vector3<f32> {
+    let h = hue.rem_euclid(1.0);
+    Vector3::new(
+        ((h * 6.0 - 3.0).abs() - 1.0).clamp(0.0, 1.0),
+        (2.0 - (h * 6.0 - 2.0).abs()).clamp(0.0, 1.0),
+        (2.0 - (h * 6.0 - 4.0).abs()).clamp(0.0, 1.0),
+    )
+}
+
+pub fn hsv_to_rgb(hsv: Hsv) -> Vector3<f32> {
+    let rgb = hue_to_rgb(hsv.h);
+    let s = hsv.s.clamp(0.0, 1.0);
+    let v = hsv.v.clamp(0.0, 1.0);
+    Vector3::new(
+        ((rgb.x - 1.0) * s + 1.0) * v,
+        ((rgb.y - 1.0) * s + 1.0) * v,
+        ((rgb.z - 1.0) * s + 1.0) * v,
+    )
+}
+
+pub fn rgb_to_hsv(rgb: Vector3<f32>) -> Hsv {
+    let max = rgb.x.max(rgb.y).max(rgb.z);
+    let min = rgb.x.min(rgb.y).min(rgb.z);
+    let delta = max - min;
+
+    let h = if delta <= f32::EPSILON {
+        0.0 // Gray has no hue
+    } else if max == rgb.x {
+        ((rgb.y - rgb.z) / delta).rem_euclid(6.0) / 6.0
+    } else if max == rgb.y {
+        ((rgb.z - rgb.x) / delta + 2.0) / 6.0
+    } else {
+        ((rgb.x - rgb.y) / delta + 4.0) / 6.0
+    };
+    let s = if max > 0.0 { delta / max } else { 0.0 };
+
+    Hsv::new(h, s, max)
+}
+
+/// Perceptual lightness and opponent axes (Björn Ottosson's Oklab)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// Polar Oklab: lightness, chroma and hue (turns)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+impl Oklab {
+    pub fn new(l: f32, a: f32, b: f32) -> Self {
+        Self { l, a, b }
+    }
+
+    pub fn to_oklch(self) -> Oklch {
+        let c = (self.a * self.a + self.b * self.b).sqrt();
+        let h = (self.b.atan2(self.a) / std::f32::consts::TAU).rem_euclid(1.0);
+        Oklch { l: self.l, c, h }
+    }
+}
+
+impl Oklch {
+    pub fn new(l: f32, c: f32, h: f32) -> Self {
+        Self { l, c, h }
+    }
+
+    pub fn to_oklab(self) -> Oklab {
+        let angle = self.h * std::f32::consts::TAU;
+        Oklab::new(self.l, self.c * angle.cos(), self.c * angle.sin())
+    }
+}
+
+pub fn linear_srgb_to_oklab(rgb: Vector3<f32>) -> Oklab {
+    let l = 0.41222147 * rgb.x + 0.53633254 * rgb.y + 0.051445993 * rgb.z;
+    let m = 0.2119035 * rgb.x + 0.6806995 * rgb.y + 0.10739696 * rgb.z;
+    let s = 0.08830246 * rgb.x + 0.28171884 * rgb.y + 0.6299787 * rgb.z;
+
+    let (l, m, s) = (l.cbrt(), m.cbrt(), s.cbrt());
+
+    Oklab::new(
+        0.21045426 * l + 0.7936178 * m - 0.004072047 * s,
+        1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+        0.025904037 * l + 0.78277177 * m - 0.80867577 * s,
+    )
+}
+
+/// Convert back to linear RGB (may fall outside 0.0 to 1.0 for out-of-gamut colors)
+pub fn oklab_to_linear_srgb(lab: Oklab) -> Vector3<f32> {
+    let l = lab.l + 0.39633778 * lab.a + 0.21580376 * lab.b;
+    let m = lab.l - 0.105561346 * lab.a - 0.06385417 * lab.b;
+    let s = lab.l - 0.08948418 * lab.a - 1.2914855 * lab.b;
+
+    let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+
+    Vector3::new(
+        4.0767417 * l - 3.3077116 * m + 0.23096993 * s,
+        -1.268438 * l + 2.6097574 * m - 0.3413194 * s,
+        -0.0041960863 * l - 0.7034186 * m + 1.7076147 * s,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_rgb_close(actual: Vector3<f32>, expected: Vector3<f32>, epsilon: f32) {
+        assert!((actual.x - expected.x).abs() < epsilon, "{:?} != {:?}", actual, expected);
+        assert!((actual.y - expected.y).abs() < epsilon, "{:?} != {:?}", actual, expected);
+        assert!((actual.z - expected.z).abs() < epsilon, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn test_hsv_reference_conversions() {
+        assert_rgb_close(hsv_to_rgb(Hsv::new(0.0, 1.0, 1.0)), Vector3::new(1.0, 0.0, 0.0), 1e-6);
+        assert_rgb_close(hsv_to_rgb(Hsv::new(1.0 / 3.0, 1.0, 1.0)), Vector3::new(0.0, 1.0, 0.0), 1e-5);
+        assert_rgb_close(hsv_to_rgb(Hsv::new(2.0 / 3.0, 1.0, 0.5)), Vector3::new(0.0, 0.0, 0.5), 1e-5);
+        assert_rgb_close(hsv_to_rgb(Hsv::new(0.25, 0.0, 0.7)), Vector3::new(0.7, 0.7, 0.7), 1e-6);
+
+        let orange = rgb_to_hsv(Vector3::new(1.0, 0.5, 0.0));
+        assert!((orange.h - 30.0 / 360.0).abs() < 1e-5);
+        assert_eq!(orange.s, 1.0);
+        assert_eq!(orange.v, 1.0);
+    }
+
+    #[test]
+    fn test_hsv_round_trip() {
+        for i in 0..12 {
+            let hsv = Hsv::new(i as f32 / 12.0, 0.8, 0.6);
+            let back = rgb_to_hsv(hsv_to_rgb(hsv));
+            assert!((back.h - hsv.h).abs() < 1e-4);
+            assert!((back.s - hsv.s).abs() < 1e-4);
+            assert!((back.v - hsv.v).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_oklab_reference_and_round_trip() {
+        // Reference values from the Oklab definition
+        let white = linear_srgb_to_oklab(Vector3::new(1.0, 1.0, 1.0));
+        assert!((white.l - 1.0).abs() < 1e-3 && white.a.abs() < 1e-3 && white.b.abs() < 1e-3);
+
+        let red = linear_srgb_to_oklab(Vector3::new(1.0, 0.0, 0.0));
+        assert!((red.l - 0.6280).abs() < 1e-3);
+        assert!((red.a - 0.2249).abs() < 1e-3);
+        assert!((red.b - 0.1258).abs() < 1e-3);
+
+        for rgb in [
+            Vector3::new(0.2, 0.5, 0.8),
+            Vector3::new(0.9, 0.1, 0.3),
+            Vector3::new(0.05, 0.05, 0.05),
+        ] {
+            assert_rgb_close(oklab_to_linear_srgb(linear_srgb_to_oklab(rgb)), rgb, 1e-4);
+
+            let lab = linear_srgb_to_oklab(rgb);
+            let back = lab.to_oklch().to_oklab();
+            assert!((back.a - lab.a).abs() < 1e-5 && (back.b - lab.b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_color_temperature_scale() {
+        let (min, max) = WHITE_BALANCE_RANGE_KELVIN;
+        assert!((color_temperature_to_kelvin(0.0) - NEUTRAL_WHITE_BALANCE_KELVIN).abs() < 1e-2);
+        assert!((color_temperature_to_kelvin(1.0) - min).abs() < 1e-2);
+        assert!((color_temperature_to_kelvin(-1.0) - max).abs() < 1e-1);
+        assert_eq!(color_temperature_to_kelvin(5.0), color_temperature_to_kelvin(1.0));
+
+        for t in [-1.0, -0.5, -0.1, 0.0, 0.25, 0.75, 1.0] {
+            let back = kelvin_to_color_temperature(color_temperature_to_kelvin(t));
+            assert!((back - t).abs() < 1e-4, "{} -> {}", t, back);
+        }
+        assert_eq!(kelvin_to_color_temperature(f32::NAN), 0.0);
+    }
+
+    #[test]
+    fn test_white_balance_multiplier() {
+        let neutral = white_balance_multiplier(NEUTRAL_WHITE_BALANCE_KELVIN);
+        assert_rgb_close(neutral, Vector3::new(1.0, 1.0, 1.0), 1e-3);
+
+        // Below 5000K is warmer: red dominates blue
+        for kelvin in [2000.0, 3200.0, 4500.0] {
+            let warm = white_balance_multiplier(kelvin);
+            assert_eq!(warm.x, 1.0);
+            assert!(warm.z < warm.y && warm.y < warm.x, "{}K: {:?}", kelvin, warm);
+        }
+
+        // Well above neutral is cooler: blue dominates red
+        for kelvin in [8000.0, 10000.0, 12000.0] {
+            let cool = white_balance_multiplier(kelvin);
+            assert_eq!(cool.z, 1.0);
+            assert!(cool.x < cool.z, "{}K: {:?}", kelvin, cool);
+        }
+
+        // Out-of-range requests clamp instead of going to extremes
+        assert_eq!(white_balance_multiplier(500.0), white_balance_multiplier(WHITE_BALANCE_RANGE_KELVIN.0));
+        assert_eq!(white_balance_multiplier(f32::NAN), neutral);
+    }
+
+    #[test]
+    fn test_srgb_transfer_round_trip() {
+        for i in 0..=10 {
+            let value = i as f32 / 10.0;
+            assert!((linear_to_srgb(srgb_to_linear(value)) - value).abs() < 1e-4);
+        }
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_vector3_operations() {
+        let vec = Vector3::new(0.5, 0.3, 0.2);
+        let scalar = 0.8;
+
+        let result = vec * scalar;
+        assert!((result.x - 0.4).abs() < 0.001);
+        assert!((result.y - 0.24).abs() < 0.001);
+        assert!((result.z - 0.16).abs() < 0.001);
+
+        let result2 = vec.mul_scalar(scalar);
+        assert_eq!(result, result2);
+    }
+}
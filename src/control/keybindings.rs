@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use winit::keyboard::KeyCode;
+
+use crate::rendering::{QualityLevel, ShaderType};
+
+/// Something a key press can do
+///
+/// ESC, the emergency-stop key, X (resume) and the exit key are dispatched before
+/// bindings are consulted, so a binding can add safety keys but never shadow them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    SetShader(ShaderType),
+    CycleNext,
+    CyclePrevious,
+    ToggleAuto,
+    SetQuality(Option<QualityLevel>), // None = automatic quality
+    TogglePerformanceOverlay,
+    ToggleHelp,
+    CycleSafetyLevel,
+    ToggleSafetyStatus,
+    ToggleDebugOverlay,
+    ToggleControlPanel,
+    ToggleBeatGrid,
+    ToggleTrails,
+    ToggleFullscreen,
+    ToggleSessionRecording,
+    Screenshot,
+    CycleEffectWeight,
+    RaiseEffectWeight,
+    LowerEffectWeight,
+    SavePreset(u8),   // Also triggered by Shift + a RecallPreset key
+    RecallPreset(u8),
+    SeekForward,
+    SeekBackward,
+    SetLoopIn,  // Loop start at the playback position
+    SetLoopOut, // Loop end at the playback position; looping starts
+    ClearLoop,
+    EmergencyStop,
+    Resume,
+}
+
+impl Action {
+    /// Every action that takes no argument, for parsing binding files
+    const SIMPLE: [Action; 24] = [
+        Action::CycleNext,
+        Action::CyclePrevious,
+        Action::ToggleAuto,
+        Action::TogglePerformanceOverlay,
+        Action::ToggleHelp,
+        Action::CycleSafetyLevel,
+        Action::ToggleSafetyStatus,
+        Action::ToggleDebugOverlay,
+        Action::ToggleControlPanel,
+        Action::ToggleBeatGrid,
+        Action::ToggleTrails,
+        Action::ToggleFullscreen,
+        Action::ToggleSessionRecording,
+        Action::Screenshot,
+        Action::CycleEffectWeight,
+        Action::RaiseEffectWeight,
+        Action::LowerEffectWeight,
+        Action::SeekForward,
+        Action::SeekBackward,
+        Action::SetLoopIn,
+        Action::SetLoopOut,
+        Action::ClearLoop,
+        Action::EmergencyStop,
+        Action::Resume,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::SetShader(_) => "SetShader",
+            Action::CycleNext => "CycleNext",
+            Action::CyclePrevious => "CyclePrevious",
+            Action::ToggleAuto => "ToggleAuto",
+            Action::SetQuality(_) => "SetQuality",
+            Action::TogglePerformanceOverlay => "TogglePerformanceOverlay",
+            Action::ToggleHelp => "ToggleHelp",
+            Action::CycleSafetyLevel => "CycleSafetyLevel",
+            Action::ToggleSafetyStatus => "ToggleSafetyStatus",
+            Action::ToggleDebugOverlay => "ToggleDebugOverlay",
+            Action::ToggleControlPanel => "ToggleControlPanel",
+            Action::ToggleBeatGrid => "ToggleBeatGrid",
+            Action::ToggleTrails => "ToggleTrails",
+            Action::ToggleFullscreen => "ToggleFullscreen",
+            Action::ToggleSessionRecording => "ToggleSessionRecording",
+            Action::Screenshot => "Screenshot",
+            Action::CycleEffectWeight => "CycleEffectWeight",
+            Action::RaiseEffectWeight => "RaiseEffectWeight",
+            Action::LowerEffectWeight => "LowerEffectWeight",
+            Action::SavePreset(_) => "SavePreset",
+            Action::RecallPreset(_) => "RecallPreset",
+            Action::SeekForward => "SeekForward",
+            Action::SeekBackward => "SeekBackward",
+            Action::SetLoopIn => "SetLoopIn",
+            Action::SetLoopOut => "SetLoopOut",
+            Action::ClearLoop => "ClearLoop",
+            Action::EmergencyStop => "EmergencyStop",
+            Action::Resume => "Resume",
+        }
+    }
+
+    /// Parse an action as written in a bindings file, e.g. "CycleNext", "SetShader Plasma"
+    /// "SetQuality Auto" or "RecallPreset 2"
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut tokens = text.split_whitespace();
+        let name = tokens.next().ok_or_else(|| anyhow!("Missing action"))?;
+        let argument = tokens.next();
+        if tokens.next().is_some() {
+            return Err(anyhow!("Too many arguments in '{}'", text));
+        }
+
+        match (name, argument) {
+            ("SetShader", Some(shader)) => Ok(Action::SetShader(parse_shader(shader)?)),
+            ("SetQuality", Some(quality)) => Ok(Action::SetQuality(parse_quality(quality)?)),
+            ("SavePreset", Some(slot)) => Ok(Action::SavePreset(parse_preset_slot(slot)?)),
+            ("RecallPreset", Some(slot)) => Ok(Action::RecallPreset(parse_preset_slot(slot)?)),
+            ("SetShader" | "SetQuality" | "SavePreset" | "RecallPreset", None) => Err(anyhow!("{} needs an argument", name)),
+            (name, None) => Action::SIMPLE
+                .into_iter()
+                .find(|action| action.name() == name)
+                .ok_or_else(|| anyhow!("Unknown action '{}'", name)),
+            (name, Some(_)) => Err(anyhow!("{} takes no argument", name)),
+        }
+    }
+}
+
+fn parse_shader(token: &str) -> Result<ShaderType> {
+    ShaderType::all()
+        .iter()
+        .copied()
+        .find(|shader| format!("{:?}", shader) == token)
+        .ok_or_else(|| anyhow!("Unknown shader '{}'", token))
+}
+
+pub(crate) fn parse_quality(token: &str) -> Result<Option<QualityLevel>> {
+    match token {
+        "Auto" => Ok(None),
+        "Potato" => Ok(Some(QualityLevel::Potato)),
+        "Low" => Ok(Some(QualityLevel::Low)),
+        "Medium" => Ok(Some(QualityLevel::Medium)),
+        "High" => Ok(Some(QualityLevel::High)),
+        "Ultra" => Ok(Some(QualityLevel::Ultra)),
+        _ => Err(anyhow!("Unknown quality '{}'", token)),
+    }
+}
+
+fn parse_preset_slot(token: &str) -> Result<u8> {
+    token.parse().map_err(|_| anyhow!("Invalid preset slot '{}'", token))
+}
+
+/// Keys that can be named in a bindings file (by their `KeyCode` variant name)
+const BINDABLE_KEYS: [KeyCode; 78] = [
+    KeyCode::KeyA, KeyCode::KeyB, KeyCode::KeyC, KeyCode::KeyD, KeyCode::KeyE, KeyCode::KeyF,
+    KeyCode::KeyG, KeyCode::KeyH, KeyCode::KeyI, KeyCode::KeyJ, KeyCode::KeyK, KeyCode::KeyL,
+    KeyCode::KeyM, KeyCode::KeyN, KeyCode::KeyO, KeyCode::KeyP, KeyCode::KeyQ, KeyCode::KeyR,
+    KeyCode::KeyS, KeyCode::KeyT, KeyCode::KeyU, KeyCode::KeyV, KeyCode::KeyW, KeyCode::KeyX,
+    KeyCode::KeyY, KeyCode::KeyZ,
+    KeyCode::Digit0, KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4,
+    KeyCode::Digit5, KeyCode::Digit6, KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+    KeyCode::Numpad0, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::Numpad4,
+    KeyCode::Numpad5, KeyCode::Numpad6, KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Space, KeyCode::Tab, KeyCode::Enter, KeyCode::Backspace,
+    KeyCode::ArrowUp, KeyCode::ArrowDown, KeyCode::ArrowLeft, KeyCode::ArrowRight,
+    KeyCode::Minus, KeyCode::Equal, KeyCode::BracketLeft, KeyCode::BracketRight,
+    KeyCode::Semicolon, KeyCode::Quote, KeyCode::Comma, KeyCode::Period, KeyCode::Slash,
+    KeyCode::Backslash, KeyCode::Backquote, KeyCode::Pause,
+];
+
+fn parse_key(token: &str) -> Result<KeyCode> {
+    BINDABLE_KEYS
+        .into_iter()
+        .find(|key| format!("{:?}", key) == token)
+        .ok_or_else(|| anyhow!("Unknown or unbindable key '{}'", token))
+}
+
+/// Key-to-action map consulted by `UserInterface::handle_keyboard_input`
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyBindings {
+    bindings: HashMap<KeyCode, Action>,
+}
+
+impl KeyBindings {
+    /// No keys bound (safety keys still work)
+    pub fn empty() -> Self {
+        Self { bindings: HashMap::new() }
+    }
+
+    /// Load a bindings file on top of the default layout
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read key bindings {}", path.display()))?;
+        Self::parse(&text)
+    }
+
+    /// Parse bindings text, one `Key = Action` per line on top of the default layout.
+    /// `Key = Unbound` removes a binding, a `clear` line drops every binding above it,
+    /// and `#` starts a comment:
+    ///
+    /// ```text
+    /// clear
+    /// KeyJ = CycleNext
+    /// KeyK = SetShader Plasma
+    /// KeyL = SetQuality Auto
+    /// ```
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut bindings = Self::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line == "clear" {
+                bindings.bindings.clear();
+                continue;
+            }
+
+            let parsed = line
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Expected 'Key = Action', got '{}'", line))
+                .and_then(|(key, action)| {
+                    let key = parse_key(key.trim())?;
+                    let action = match action.trim() {
+                        "Unbound" => None,
+                        action => Some(Action::parse(action)?),
+                    };
+                    Ok((key, action))
+                })
+                .with_context(|| format!("Key bindings line {}", index + 1))?;
+
+            match parsed {
+                (key, Some(action)) => bindings.bind(key, action),
+                (key, None) => bindings.unbind(key),
+            }
+        }
+        Ok(bindings)
+    }
+
+    pub fn bind(&mut self, key: KeyCode, action: Action) {
+        self.bindings.insert(key, action);
+    }
+
+    pub fn unbind(&mut self, key: KeyCode) {
+        self.bindings.remove(&key);
+    }
+
+    /// Action bound to a key, if any
+    pub fn action_for(&self, key: KeyCode) -> Option<Action> {
+        self.bindings.get(&key).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+}
+
+impl Default for KeyBindings {
+    /// The built-in layout shown by the help screen
+    fn default() -> Self {
+        let mut bindings = Self::empty();
+        let shader_keys = [
+            (KeyCode::Digit1, ShaderType::Classic),
+            (KeyCode::Digit2, ShaderType::ParametricWave),
+            (KeyCode::Digit3, ShaderType::Plasma),
+            (KeyCode::Digit4, ShaderType::Kaleidoscope),
+            (KeyCode::Digit5, ShaderType::Tunnel),
+            (KeyCode::Digit6, ShaderType::Particle),
+            (KeyCode::Digit7, ShaderType::Fractal),
+            (KeyCode::Digit8, ShaderType::Spectralizer),
+            (KeyCode::Digit9, ShaderType::Oscilloscope),
+            (KeyCode::Digit0, ShaderType::Spectrogram),
+        ];
+        for (key, shader) in shader_keys {
+            bindings.bind(key, Action::SetShader(shader));
+        }
+
+        bindings.bind(KeyCode::Space, Action::CycleNext);
+        bindings.bind(KeyCode::Tab, Action::CyclePrevious);
+        bindings.bind(KeyCode::KeyA, Action::ToggleAuto);
+
+        bindings.bind(KeyCode::KeyQ, Action::SetQuality(Some(QualityLevel::Potato)));
+        bindings.bind(KeyCode::KeyW, Action::SetQuality(Some(QualityLevel::Low)));
+        bindings.bind(KeyCode::KeyE, Action::SetQuality(Some(QualityLevel::Medium)));
+        bindings.bind(KeyCode::KeyR, Action::SetQuality(Some(QualityLevel::High)));
+        bindings.bind(KeyCode::KeyT, Action::SetQuality(Some(QualityLevel::Ultra)));
+        bindings.bind(KeyCode::KeyY, Action::SetQuality(None));
+
+        bindings.bind(KeyCode::KeyP, Action::TogglePerformanceOverlay);
+        bindings.bind(KeyCode::KeyH, Action::ToggleHelp);
+        bindings.bind(KeyCode::F1, Action::ToggleHelp);
+        bindings.bind(KeyCode::KeyS, Action::CycleSafetyLevel);
+        bindings.bind(KeyCode::KeyZ, Action::ToggleSafetyStatus);
+        bindings.bind(KeyCode::KeyD, Action::ToggleDebugOverlay);
+        bindings.bind(KeyCode::KeyC, Action::ToggleControlPanel);
+        bindings.bind(KeyCode::KeyG, Action::ToggleBeatGrid);
+        bindings.bind(KeyCode::KeyM, Action::ToggleTrails);
+        bindings.bind(KeyCode::KeyF, Action::ToggleFullscreen);
+        bindings.bind(KeyCode::Backslash, Action::CycleEffectWeight);
+        bindings.bind(KeyCode::BracketRight, Action::RaiseEffectWeight);
+        bindings.bind(KeyCode::BracketLeft, Action::LowerEffectWeight);
+        for (slot, key) in [KeyCode::F5, KeyCode::F6, KeyCode::F7, KeyCode::F8].into_iter().enumerate() {
+            bindings.bind(key, Action::RecallPreset(slot as u8 + 1));
+        }
+        bindings.bind(KeyCode::ArrowRight, Action::SeekForward);
+        bindings.bind(KeyCode::ArrowLeft, Action::SeekBackward);
+        bindings.bind(KeyCode::KeyI, Action::SetLoopIn);
+        bindings.bind(KeyCode::KeyO, Action::SetLoopOut);
+        bindings.bind(KeyCode::KeyL, Action::ClearLoop);
+        bindings.bind(KeyCode::F9, Action::ToggleSessionRecording);
+        bindings.bind(KeyCode::F12, Action::Screenshot);
+        bindings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_layout_matches_help() {
+        let bindings = KeyBindings::default();
+        assert_eq!(bindings.action_for(KeyCode::Digit3), Some(Action::SetShader(ShaderType::Plasma)));
+        assert_eq!(bindings.action_for(KeyCode::Space), Some(Action::CycleNext));
+        assert_eq!(bindings.action_for(KeyCode::KeyY), Some(Action::SetQuality(None)));
+        assert_eq!(bindings.action_for(KeyCode::F1), Some(Action::ToggleHelp));
+        assert_eq!(bindings.action_for(KeyCode::F6), Some(Action::RecallPreset(2)));
+        assert_eq!(bindings.action_for(KeyCode::BracketRight), Some(Action::RaiseEffectWeight));
+        assert_eq!(bindings.action_for(KeyCode::ArrowLeft), Some(Action::SeekBackward));
+        assert_eq!(bindings.action_for(KeyCode::KeyG), Some(Action::ToggleBeatGrid));
+        assert_eq!(bindings.action_for(KeyCode::KeyO), Some(Action::SetLoopOut));
+        assert_eq!(bindings.action_for(KeyCode::KeyJ), None);
+    }
+
+    #[test]
+    fn test_custom_binding_resolves_action() {
+        let bindings = KeyBindings::parse(
+            "# VJ layout\n\
+             KeyJ = CycleNext\n\
+             KeyK = SetShader Plasma  # trailing comment\n\
+             KeyL = SetQuality Auto\n\
+             KeyU = SavePreset 3\n\
+             Space = Unbound\n",
+        )
+        .unwrap();
+
+        assert_eq!(bindings.action_for(KeyCode::KeyJ), Some(Action::CycleNext));
+        assert_eq!(bindings.action_for(KeyCode::KeyK), Some(Action::SetShader(ShaderType::Plasma)));
+        assert_eq!(bindings.action_for(KeyCode::KeyL), Some(Action::SetQuality(None)));
+        assert_eq!(bindings.action_for(KeyCode::KeyU), Some(Action::SavePreset(3)));
+        assert_eq!(bindings.action_for(KeyCode::Space), None);
+        // Everything else keeps the default layout
+        assert_eq!(bindings.action_for(KeyCode::KeyA), Some(Action::ToggleAuto));
+
+        let only_custom = KeyBindings::parse("clear\nKeyJ = CycleNext").unwrap();
+        assert_eq!(only_custom.len(), 1);
+        assert_eq!(only_custom.action_for(KeyCode::KeyJ), Some(Action::CycleNext));
+    }
+
+    #[test]
+    fn test_rejects_bad_bindings() {
+        assert!(KeyBindings::parse("KeyJ CycleNext").is_err());
+        assert!(KeyBindings::parse("Hyper = CycleNext").is_err());
+        assert!(KeyBindings::parse("KeyJ = Explode").is_err());
+        assert!(KeyBindings::parse("KeyJ = SetShader").is_err());
+        assert!(KeyBindings::parse("KeyJ = SetShader Nonexistent").is_err());
+        assert!(KeyBindings::parse("KeyJ = CycleNext Plasma").is_err());
+        assert!(KeyBindings::parse("KeyJ = RecallPreset").is_err());
+        assert!(KeyBindings::parse("KeyJ = SavePreset first").is_err());
+    }
+}
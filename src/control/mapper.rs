@@ -1,10 +1,66 @@
 use super::{ShaderParameters, Smoother, SmoothingType, Smoothable, PaletteManager};
-use crate::audio::{AudioFeatures, RhythmFeatures};
+use crate::audio::{db_to_gain, AudioFeatures, RhythmFeatures};
+
+/// Quietest level the compressor works with; energies below this pass through
+const COMPRESSOR_FLOOR_DB: f32 = -120.0;
+
+/// Soft-knee compressor for feature values in 0.0..=1.0 (0 dB = 1.0)
+///
+/// Shapes how the volume and band energies drive the visuals so quiet, heavily mastered
+/// tracks still span the parameter range. Unlike AGC this doesn't track the input level;
+/// it's a fixed response curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompressorSettings {
+    pub threshold_db: f32,   // Level where compression starts
+    pub ratio: f32,          // Input dB over threshold per output dB (1.0 = no compression)
+    pub knee_db: f32,        // Width of the soft knee centered on the threshold (0.0 = hard knee)
+    pub makeup_gain_db: f32, // Gain applied after compression
+}
+
+impl Default for CompressorSettings {
+    fn default() -> Self {
+        Self {
+            threshold_db: -18.0,
+            ratio: 3.0,
+            knee_db: 6.0,
+            makeup_gain_db: 6.0,
+        }
+    }
+}
+
+impl CompressorSettings {
+    /// Output level in dB for an input level in dB, before makeup gain
+    pub fn gain_curve_db(&self, input_db: f32) -> f32 {
+        let ratio = self.ratio.max(1.0);
+        let knee = self.knee_db.max(0.0);
+        let over = input_db - self.threshold_db;
+
+        if 2.0 * over < -knee {
+            input_db
+        } else if 2.0 * over <= knee {
+            // Quadratic blend between the two slopes across the knee
+            input_db + (1.0 / ratio - 1.0) * (over + knee / 2.0).powi(2) / (2.0 * knee)
+        } else {
+            self.threshold_db + over / ratio
+        }
+    }
+
+    /// Compress a 0.0..=1.0 feature value, clamped back into range after makeup gain
+    pub fn apply(&self, value: f32) -> f32 {
+        let value = value.clamp(0.0, 1.0);
+        let input_db = 20.0 * value.max(db_to_gain(COMPRESSOR_FLOOR_DB)).log10();
+        if input_db <= COMPRESSOR_FLOOR_DB {
+            return value;
+        }
+        db_to_gain(self.gain_curve_db(input_db) + self.makeup_gain_db).clamp(0.0, 1.0)
+    }
+}
 
 pub struct FeatureMapper {
     smoother: Smoother,
     palette_manager: PaletteManager,
     frame_time: f32,
+    compressor: Option<CompressorSettings>,
 }
 
 impl FeatureMapper {
@@ -16,22 +72,57 @@ impl FeatureMapper {
             ("color_intensity", SmoothingType::adaptive(0.05, 0.3, 3.0)),
             ("frequency_scale", SmoothingType::exponential(2.0)),
             ("time_factor", SmoothingType::linear(0.15)),
-            ("bass_response", SmoothingType::adaptive(0.1, 0.6, 4.0)), // Fast response for bass
+            ("bass_response", SmoothingType::spring(600.0, 0.6)), // Punchy, with a little bounce on hits
             ("mid_response", SmoothingType::adaptive(0.08, 0.4, 2.5)),
             ("treble_response", SmoothingType::adaptive(0.05, 0.5, 5.0)), // Very responsive for treble
             ("overall_brightness", SmoothingType::exponential(3.0)),
             ("spectral_shift", SmoothingType::exponential(1.5)),
             ("saturation", SmoothingType::exponential(4.0)), // Fast response for volume changes
         ]);
+        smoother.configure_range("bass_response", 0.0, 1.0);
 
         Self {
             smoother,
             palette_manager: PaletteManager::new(),
             frame_time: 0.0,
+            compressor: None,
         }
     }
 
+    /// Compress overall volume and the band energies before they're mapped
+    pub fn set_compressor(&mut self, settings: CompressorSettings) {
+        self.compressor = Some(settings);
+    }
+
+    pub fn clear_compressor(&mut self) {
+        self.compressor = None;
+    }
+
+    pub fn compressor(&self) -> Option<CompressorSettings> {
+        self.compressor
+    }
+
+    /// Features with the compressor applied, or unchanged while it's off
+    fn compressed(&self, features: &AudioFeatures) -> AudioFeatures {
+        let mut features = features.clone();
+        if let Some(compressor) = self.compressor {
+            for value in [
+                &mut features.overall_volume,
+                &mut features.sub_bass,
+                &mut features.bass,
+                &mut features.mid,
+                &mut features.treble,
+                &mut features.presence,
+            ] {
+                *value = compressor.apply(*value);
+            }
+        }
+        features
+    }
+
     pub fn map_features_to_parameters(&mut self, features: &AudioFeatures) -> ShaderParameters {
+        let features = &self.compressed(features);
+
         // Update frame time for palette management
         self.frame_time += 1.0 / 60.0; // Assuming 60 FPS
 
@@ -80,6 +171,8 @@ impl FeatureMapper {
     }
 
     pub fn map_features_with_rhythm(&mut self, features: &AudioFeatures, rhythm: &RhythmFeatures) -> ShaderParameters {
+        let features = &self.compressed(features);
+
         // Update frame time for palette management
         self.frame_time += 1.0 / 60.0; // Assuming 60 FPS
 
@@ -198,13 +291,24 @@ mod tests {
             spectral_centroid: 2000.0,
             spectral_rolloff: 8000.0,
             spectral_flux: 0.2,
+            spectral_crest: 1.0,
 
             // Harmonic and pitch analysis
             pitch_confidence: 0.5,
             zero_crossing_rate: 0.1,
+            detected_key: None,
+            key_confidence: 0.0,
 
             // Transient detection
             onset_strength: 0.3,
+
+            // Stereo image
+            stereo_balance: 0.0,
+            stereo_width: 0.0,
+
+            // Source separation
+            harmonic_energy: 0.0,
+            percussive_energy: 0.0,
         };
 
         let params = mapper.map_features_to_parameters(&features);
@@ -245,13 +349,24 @@ mod tests {
             spectral_centroid: 1000.0,
             spectral_rolloff: 5000.0,
             spectral_flux: 0.4,
+            spectral_crest: 1.0,
 
             // Harmonic and pitch analysis
             pitch_confidence: 0.7,
             zero_crossing_rate: 0.1,
+            detected_key: None,
+            key_confidence: 0.0,
 
             // Transient detection
             onset_strength: 0.6,
+
+            // Stereo image
+            stereo_balance: 0.0,
+            stereo_width: 0.0,
+
+            // Source separation
+            harmonic_energy: 0.0,
+            percussive_energy: 0.0,
         };
 
         let _params1 = mapper.map_features_to_parameters(&features1);
@@ -274,13 +389,24 @@ mod tests {
             spectral_centroid: 2000.0,
             spectral_rolloff: 10000.0,
             spectral_flux: 0.1,
+            spectral_crest: 1.0,
 
             // Harmonic and pitch analysis
             pitch_confidence: 0.2,
             zero_crossing_rate: 0.2,
+            detected_key: None,
+            key_confidence: 0.0,
 
             // Transient detection
             onset_strength: 0.1,
+
+            // Stereo image
+            stereo_balance: 0.0,
+            stereo_width: 0.0,
+
+            // Source separation
+            harmonic_energy: 0.0,
+            percussive_energy: 0.0,
         };
 
         let params2 = mapper.map_features_to_parameters(&features2);
@@ -290,4 +416,42 @@ mod tests {
         assert!(params2.mid_response > 0.0 && params2.mid_response < 1.0);
         assert!(params2.treble_response > 0.0 && params2.treble_response < 1.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_compressor_reduces_level_above_threshold_by_ratio() {
+        let compressor = CompressorSettings { threshold_db: -20.0, ratio: 4.0, knee_db: 6.0, makeup_gain_db: 0.0 };
+
+        // 16 dB over the threshold (well past the knee) comes out 4 dB over
+        assert!((compressor.gain_curve_db(-4.0) - -16.0).abs() < 1e-4);
+        let output_db = 20.0 * compressor.apply(db_to_gain(-4.0)).log10();
+        assert!((output_db - -16.0).abs() < 1e-3);
+
+        // The knee is continuous with both slopes
+        assert!((compressor.gain_curve_db(-23.0) - -23.0).abs() < 1e-4);
+        assert!((compressor.gain_curve_db(-17.0) - -19.25).abs() < 1e-4);
+
+        // Makeup gain lifts the result, and the output stays in range
+        let louder = CompressorSettings { makeup_gain_db: 6.0, ..compressor };
+        assert!((20.0 * louder.apply(db_to_gain(-4.0)).log10() - -10.0).abs() < 1e-3);
+        assert_eq!(CompressorSettings { makeup_gain_db: 40.0, ..compressor }.apply(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_compressor_passes_quiet_input_unchanged() {
+        let compressor = CompressorSettings { threshold_db: -20.0, ratio: 4.0, knee_db: 6.0, makeup_gain_db: 0.0 };
+        for value in [0.0, 0.001, 0.01, db_to_gain(-24.0)] {
+            assert!((compressor.apply(value) - value).abs() < 1e-6, "{} should pass through", value);
+        }
+
+        // Off by default; once set, the mapper compresses loud bands
+        let mut mapper = FeatureMapper::new();
+        assert_eq!(mapper.compressor(), None);
+        let features = AudioFeatures { bass: 1.0, overall_volume: 0.05, ..AudioFeatures::new() };
+        assert_eq!(mapper.compressed(&features), features);
+
+        mapper.set_compressor(compressor);
+        let compressed = mapper.compressed(&features);
+        assert!((compressed.bass - db_to_gain(-15.0)).abs() < 1e-4);
+        assert!((compressed.overall_volume - 0.05).abs() < 1e-6);
+    }
+}
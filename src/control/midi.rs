@@ -0,0 +1,349 @@
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::sync::mpsc::Receiver;
+
+use crate::audio::{AudioFeatures, RhythmFeatures};
+
+/// MIDI clock resolution: pulses per quarter note
+pub const MIDI_CLOCK_PPQN: u32 = 24;
+
+/// Clock intervals averaged for the tempo estimate (two beats)
+const CLOCK_WINDOW: usize = 2 * MIDI_CLOCK_PPQN as usize;
+
+/// A gap this long between clock pulses means the clock stopped; older pulses are dropped
+const CLOCK_TIMEOUT_MICROS: u64 = 1_000_000;
+
+const STATUS_NOTE_ON: u8 = 0x90;
+const STATUS_CLOCK: u8 = 0xF8;
+const STATUS_START: u8 = 0xFA;
+const STATUS_CONTINUE: u8 = 0xFB;
+const STATUS_STOP: u8 = 0xFC;
+
+/// Something the render loop can act on, decoded from raw MIDI messages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MidiEvent {
+    /// Tempo from the incoming clock, updated once per beat
+    Tempo(f32),
+    /// A quarter note boundary of the incoming clock
+    Beat,
+    /// Note-on with velocity scaled to 0-1
+    NoteOn { channel: u8, note: u8, velocity: f32 },
+    Start,
+    Stop,
+}
+
+/// Turns timestamped MIDI messages into `MidiEvent`s, estimating tempo from clock pulses
+pub struct MidiDecoder {
+    clock_times: VecDeque<u64>, // Microsecond timestamps of recent clock pulses
+    pulses: u32,                // Pulses since start, for beat boundaries
+}
+
+impl MidiDecoder {
+    pub fn new() -> Self {
+        Self {
+            clock_times: VecDeque::with_capacity(CLOCK_WINDOW + 1),
+            pulses: 0,
+        }
+    }
+
+    /// Decode one message received at `timestamp_micros`; unknown messages yield nothing
+    pub fn decode(&mut self, timestamp_micros: u64, message: &[u8]) -> Vec<MidiEvent> {
+        let Some(&status) = message.first() else {
+            return Vec::new();
+        };
+
+        match status {
+            STATUS_CLOCK => self.clock_pulse(timestamp_micros),
+            STATUS_START => {
+                self.pulses = 0;
+                self.clock_times.clear();
+                vec![MidiEvent::Start]
+            }
+            STATUS_CONTINUE => vec![MidiEvent::Start],
+            STATUS_STOP => vec![MidiEvent::Stop],
+            _ if status & 0xF0 == STATUS_NOTE_ON && message.len() >= 3 => {
+                // Velocity 0 is a note-off by convention
+                if message[2] == 0 {
+                    return Vec::new();
+                }
+                vec![MidiEvent::NoteOn {
+                    channel: status & 0x0F,
+                    note: message[1] & 0x7F,
+                    velocity: (message[2] & 0x7F) as f32 / 127.0,
+                }]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Tempo implied by the buffered clock pulses, once a full beat has arrived
+    pub fn bpm(&self) -> Option<f32> {
+        if self.clock_times.len() <= MIDI_CLOCK_PPQN as usize {
+            return None;
+        }
+        let first = *self.clock_times.front()?;
+        let last = *self.clock_times.back()?;
+        let intervals = (self.clock_times.len() - 1) as f32;
+        let pulse_micros = (last - first) as f32 / intervals;
+        if pulse_micros <= 0.0 {
+            return None;
+        }
+        Some(60_000_000.0 / (pulse_micros * MIDI_CLOCK_PPQN as f32))
+    }
+
+    fn clock_pulse(&mut self, timestamp_micros: u64) -> Vec<MidiEvent> {
+        if let Some(&previous) = self.clock_times.back() {
+            if timestamp_micros.saturating_sub(previous) > CLOCK_TIMEOUT_MICROS || timestamp_micros < previous {
+                self.clock_times.clear();
+            }
+        }
+        if self.clock_times.len() > CLOCK_WINDOW {
+            self.clock_times.pop_front();
+        }
+        self.clock_times.push_back(timestamp_micros);
+
+        let on_beat = self.pulses.is_multiple_of(MIDI_CLOCK_PPQN);
+        self.pulses = self.pulses.wrapping_add(1);
+        if !on_beat {
+            return Vec::new();
+        }
+
+        let mut events = vec![MidiEvent::Beat];
+        if let Some(bpm) = self.bpm() {
+            events.push(MidiEvent::Tempo(bpm));
+        }
+        events
+    }
+}
+
+impl Default for MidiDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Applies MIDI tempo and note-ons on top of the analyzed rhythm, each optionally
+pub struct MidiSync {
+    override_tempo: bool,
+    override_onsets: bool,
+    bpm: Option<f32>,
+    note_velocity: Option<f32>, // Loudest note-on this frame
+    notes_seen: bool,           // Onsets only come from MIDI once notes have arrived
+}
+
+impl MidiSync {
+    pub fn new() -> Self {
+        Self {
+            override_tempo: true,
+            override_onsets: true,
+            bpm: None,
+            note_velocity: None,
+            notes_seen: false,
+        }
+    }
+
+    /// Replace the detected BPM with the MIDI clock tempo
+    pub fn set_override_tempo(&mut self, enabled: bool) {
+        self.override_tempo = enabled;
+    }
+
+    /// Replace detected onsets with MIDI note-ons
+    pub fn set_override_onsets(&mut self, enabled: bool) {
+        self.override_onsets = enabled;
+    }
+
+    /// Latest clock tempo, if a clock is running
+    pub fn bpm(&self) -> Option<f32> {
+        self.bpm
+    }
+
+    /// Take in this frame's events (call once per frame, even with none)
+    pub fn update(&mut self, events: &[MidiEvent]) {
+        self.note_velocity = None;
+        for event in events {
+            match *event {
+                MidiEvent::Tempo(bpm) => self.bpm = Some(bpm),
+                MidiEvent::Stop => self.bpm = None,
+                MidiEvent::NoteOn { velocity, .. } => {
+                    self.notes_seen = true;
+                    self.note_velocity = Some(self.note_velocity.unwrap_or(0.0).max(velocity));
+                }
+                MidiEvent::Beat | MidiEvent::Start => {}
+            }
+        }
+    }
+
+    /// Override the analyzed features with MIDI where enabled and available
+    pub fn apply(&self, audio: &mut AudioFeatures, rhythm: &mut RhythmFeatures) {
+        if self.override_tempo {
+            if let Some(bpm) = self.bpm {
+                rhythm.estimated_bpm = bpm;
+                rhythm.tempo_bpm = bpm;
+                rhythm.tempo_confidence = 1.0;
+            }
+        }
+
+        if self.override_onsets && self.notes_seen {
+            rhythm.onset_detected = self.note_velocity.is_some();
+            if let Some(velocity) = self.note_velocity {
+                audio.onset_strength = audio.onset_strength.max(velocity);
+            }
+        }
+    }
+}
+
+impl Default for MidiSync {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A MIDI input port feeding timestamped messages to a decoder; needs the `midi` feature
+pub struct MidiSource {
+    receiver: Receiver<(u64, Vec<u8>)>,
+    decoder: MidiDecoder,
+    port_name: String,
+    #[cfg(feature = "midi")]
+    _connection: midir::MidiInputConnection<()>,
+}
+
+impl MidiSource {
+    /// Open the first input port whose name contains `port_filter` (empty = first port)
+    #[cfg(feature = "midi")]
+    pub fn open(port_filter: &str) -> Result<Self> {
+        use anyhow::anyhow;
+
+        let mut input = midir::MidiInput::new("aruu")?;
+        input.ignore(midir::Ignore::None); // Keep clock messages
+
+        let ports = input.ports();
+        let port = ports
+            .iter()
+            .find(|port| input.port_name(port).is_ok_and(|name| name.contains(port_filter)))
+            .ok_or_else(|| anyhow!("No MIDI input port matching '{}'", port_filter))?
+            .clone();
+        let port_name = input.port_name(&port)?;
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let connection = input
+            .connect(&port, "aruu-input", move |timestamp, message, _| {
+                let _ = sender.send((timestamp, message.to_vec()));
+            }, ())
+            .map_err(|e| anyhow!("Failed to connect to MIDI port {}: {}", port_name, e))?;
+
+        Ok(Self {
+            receiver,
+            decoder: MidiDecoder::new(),
+            port_name,
+            _connection: connection,
+        })
+    }
+
+    /// Without the `midi` feature there are no ports to open
+    #[cfg(not(feature = "midi"))]
+    pub fn open(_port_filter: &str) -> Result<Self> {
+        Err(anyhow::anyhow!("MIDI input is not available: built without the `midi` feature"))
+    }
+
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+
+    /// Decode everything received since the last poll; never blocks
+    pub fn poll(&mut self) -> Vec<MidiEvent> {
+        let mut events = Vec::new();
+        while let Ok((timestamp, message)) = self.receiver.try_recv() {
+            events.extend(self.decoder.decode(timestamp, &message));
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Clock pulses at `bpm` starting at `start_micros`
+    fn clock_stream(decoder: &mut MidiDecoder, bpm: f64, pulses: usize, start_micros: u64) -> Vec<MidiEvent> {
+        let pulse_micros = 60_000_000.0 / (bpm * MIDI_CLOCK_PPQN as f64);
+        (0..pulses)
+            .flat_map(|i| decoder.decode(start_micros + (i as f64 * pulse_micros) as u64, &[STATUS_CLOCK]))
+            .collect()
+    }
+
+    fn tempos(events: &[MidiEvent]) -> Vec<f32> {
+        events.iter().filter_map(|e| match e { MidiEvent::Tempo(bpm) => Some(*bpm), _ => None }).collect()
+    }
+
+    #[test]
+    fn test_clock_decodes_to_bpm() {
+        let mut decoder = MidiDecoder::new();
+        assert_eq!(decoder.decode(0, &[STATUS_START]), vec![MidiEvent::Start]);
+
+        // Three beats of 120 BPM clock: tempo is known from the second beat on
+        let events = clock_stream(&mut decoder, 120.0, 3 * 24 + 1, 0);
+        assert_eq!(events.iter().filter(|e| **e == MidiEvent::Beat).count(), 4);
+        let tempos = tempos(&events);
+        assert_eq!(tempos.len(), 3);
+        for bpm in tempos {
+            assert!((bpm - 120.0).abs() < 0.1, "decoded {} BPM", bpm);
+        }
+    }
+
+    #[test]
+    fn test_tempo_follows_clock_changes() {
+        let mut decoder = MidiDecoder::new();
+        clock_stream(&mut decoder, 100.0, 48, 0);
+        assert!((decoder.bpm().unwrap() - 100.0).abs() < 0.1);
+
+        // After a pause longer than the timeout the old pulses no longer count
+        let events = clock_stream(&mut decoder, 140.0, 3 * 24, 5_000_000);
+        let last = *tempos(&events).last().unwrap();
+        assert!((last - 140.0).abs() < 0.1, "decoded {} BPM", last);
+    }
+
+    #[test]
+    fn test_note_on_velocity() {
+        let mut decoder = MidiDecoder::new();
+        assert_eq!(
+            decoder.decode(0, &[0x93, 60, 127]),
+            vec![MidiEvent::NoteOn { channel: 3, note: 60, velocity: 1.0 }]
+        );
+        // Velocity zero is a note-off; other messages are ignored
+        assert!(decoder.decode(0, &[0x90, 60, 0]).is_empty());
+        assert!(decoder.decode(0, &[0x80, 60, 64]).is_empty());
+        assert!(decoder.decode(0, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_sync_overrides_rhythm() {
+        let mut sync = MidiSync::new();
+        let mut audio = AudioFeatures::new();
+        let mut rhythm = RhythmFeatures::new();
+        rhythm.estimated_bpm = 95.0;
+        rhythm.onset_detected = true;
+
+        // Nothing received yet: analysis passes through untouched
+        sync.update(&[]);
+        sync.apply(&mut audio, &mut rhythm);
+        assert_eq!(rhythm.estimated_bpm, 95.0);
+        assert!(rhythm.onset_detected);
+
+        sync.update(&[MidiEvent::Tempo(128.0), MidiEvent::NoteOn { channel: 0, note: 36, velocity: 0.8 }]);
+        sync.apply(&mut audio, &mut rhythm);
+        assert_eq!(rhythm.estimated_bpm, 128.0);
+        assert_eq!(rhythm.tempo_confidence, 1.0);
+        assert!(rhythm.onset_detected);
+        assert_eq!(audio.onset_strength, 0.8);
+
+        // Once notes drive onsets, a frame without one has no onset
+        sync.update(&[]);
+        sync.apply(&mut audio, &mut rhythm);
+        assert!(!rhythm.onset_detected);
+
+        sync.set_override_tempo(false);
+        rhythm.estimated_bpm = 95.0;
+        sync.apply(&mut audio, &mut rhythm);
+        assert_eq!(rhythm.estimated_bpm, 95.0);
+    }
+}
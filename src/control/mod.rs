@@ -1,13 +1,25 @@
+pub mod attract;
+pub mod color;
+pub mod keybindings;
 pub mod mapper;
+pub mod midi;
+pub mod osc;
 pub mod parameters;
+pub mod presets;
 pub mod smoothing;
 pub mod palettes;
 pub mod user_interface;
 pub mod safety;
 pub mod warning;
 
+pub use attract::*;
+pub use color::*;
+pub use keybindings::*;
 pub use mapper::*;
+pub use midi::*;
+pub use osc::*;
 pub use parameters::*;
+pub use presets::*;
 pub use smoothing::*;
 pub use palettes::*;
 pub use user_interface::*;
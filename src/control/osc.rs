@@ -0,0 +1,321 @@
+use anyhow::{anyhow, Result};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+use crate::control::SafetyLevel;
+use crate::rendering::{QualityLevel, ShaderType};
+
+/// UDP port the OSC endpoint listens on unless configured otherwise
+pub const DEFAULT_OSC_PORT: u16 = 9000;
+
+/// Largest datagram read in one go; OSC control messages are far smaller
+const MAX_PACKET_SIZE: usize = 1536;
+
+const BUNDLE_TAG: &[u8] = b"#bundle\0";
+
+/// One OSC argument; only the types control surfaces commonly send are understood
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Str(String),
+    Bool(bool),
+    Nil,
+}
+
+impl OscArg {
+    /// Integer value, accepting whole-number floats from controllers that only send floats
+    pub fn as_int(&self) -> Option<i32> {
+        match *self {
+            OscArg::Int(value) => Some(value),
+            OscArg::Float(value) if value.fract() == 0.0 && value.abs() <= i32::MAX as f32 => Some(value as i32),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+/// Parse an OSC packet (a message or a bundle, possibly nested) into its messages
+pub fn parse_packet(packet: &[u8]) -> Result<Vec<OscMessage>> {
+    let mut messages = Vec::new();
+    parse_into(packet, &mut messages)?;
+    Ok(messages)
+}
+
+fn parse_into(packet: &[u8], messages: &mut Vec<OscMessage>) -> Result<()> {
+    if packet.starts_with(BUNDLE_TAG) {
+        // Tag, 8-byte time tag (ignored: everything applies on the next frame), then sized elements
+        let mut cursor = BUNDLE_TAG.len() + 8;
+        while cursor < packet.len() {
+            let size = read_i32(packet, cursor)? as usize;
+            cursor += 4;
+            let end = cursor.checked_add(size).filter(|&end| end <= packet.len())
+                .ok_or_else(|| anyhow!("Truncated OSC bundle element"))?;
+            parse_into(&packet[cursor..end], messages)?;
+            cursor = end;
+        }
+        return Ok(());
+    }
+
+    let (address, mut cursor) = read_string(packet, 0)?;
+    if !address.starts_with('/') {
+        return Err(anyhow!("Not an OSC message: address '{}'", address));
+    }
+
+    // A missing type tag string is allowed by older senders and means no arguments
+    let mut args = Vec::new();
+    if cursor < packet.len() {
+        let (tags, next) = read_string(packet, cursor)?;
+        cursor = next;
+        let tags = tags.strip_prefix(',').ok_or_else(|| anyhow!("OSC type tags must start with ','"))?;
+        for tag in tags.chars() {
+            let arg = match tag {
+                'i' => {
+                    let value = read_i32(packet, cursor)?;
+                    cursor += 4;
+                    OscArg::Int(value)
+                }
+                'f' => {
+                    let value = f32::from_bits(read_i32(packet, cursor)? as u32);
+                    cursor += 4;
+                    OscArg::Float(value)
+                }
+                's' => {
+                    let (value, next) = read_string(packet, cursor)?;
+                    cursor = next;
+                    OscArg::Str(value)
+                }
+                'T' => OscArg::Bool(true),
+                'F' => OscArg::Bool(false),
+                'N' | 'I' => OscArg::Nil,
+                other => return Err(anyhow!("Unsupported OSC argument type '{}'", other)),
+            };
+            args.push(arg);
+        }
+    }
+
+    messages.push(OscMessage { address, args });
+    Ok(())
+}
+
+/// Read a null-terminated string padded to 4 bytes; returns it and the offset after the padding
+fn read_string(packet: &[u8], offset: usize) -> Result<(String, usize)> {
+    let rest = packet.get(offset..).ok_or_else(|| anyhow!("Truncated OSC packet"))?;
+    let len = rest.iter().position(|&b| b == 0).ok_or_else(|| anyhow!("Unterminated OSC string"))?;
+    let value = std::str::from_utf8(&rest[..len]).map_err(|_| anyhow!("OSC string is not UTF-8"))?;
+    Ok((value.to_string(), offset + (len + 4) / 4 * 4))
+}
+
+fn read_i32(packet: &[u8], offset: usize) -> Result<i32> {
+    let bytes = packet.get(offset..offset + 4).ok_or_else(|| anyhow!("Truncated OSC argument"))?;
+    Ok(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// What a remote controller asked for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OscCommand {
+    /// `/aruu/shader i` - index into `ShaderType::all()`
+    SetShader(ShaderType),
+    /// `/aruu/safety i` - 0 ultra safe to 3 standard; protection can't be disabled remotely
+    SetSafetyLevel(SafetyLevel),
+    /// `/aruu/quality i` - 0 potato to 4 ultra, -1 for automatic
+    SetQuality(Option<QualityLevel>),
+    /// `/aruu/emergency_stop` - any arguments are ignored
+    EmergencyStop,
+}
+
+impl OscCommand {
+    /// Map a message onto a command; unknown addresses and bad values are errors
+    pub fn from_message(message: &OscMessage) -> Result<Self> {
+        let int_arg = || {
+            message.args.first().and_then(OscArg::as_int)
+                .ok_or_else(|| anyhow!("{} expects an int argument", message.address))
+        };
+
+        match message.address.as_str() {
+            "/aruu/shader" => {
+                let index = int_arg()?;
+                let shaders = ShaderType::all();
+                usize::try_from(index).ok().and_then(|i| shaders.get(i)).copied()
+                    .map(OscCommand::SetShader)
+                    .ok_or_else(|| anyhow!("Shader index {} out of range 0-{}", index, shaders.len() - 1))
+            }
+            "/aruu/safety" => {
+                let level = match int_arg()? {
+                    0 => SafetyLevel::UltraSafe,
+                    1 => SafetyLevel::Safe,
+                    2 => SafetyLevel::Moderate,
+                    3 => SafetyLevel::Standard,
+                    other => return Err(anyhow!("Safety level {} out of range 0-3", other)),
+                };
+                Ok(OscCommand::SetSafetyLevel(level))
+            }
+            "/aruu/quality" => {
+                let quality = match int_arg()? {
+                    -1 => None,
+                    0 => Some(QualityLevel::Potato),
+                    1 => Some(QualityLevel::Low),
+                    2 => Some(QualityLevel::Medium),
+                    3 => Some(QualityLevel::High),
+                    4 => Some(QualityLevel::Ultra),
+                    other => return Err(anyhow!("Quality level {} out of range -1-4", other)),
+                };
+                Ok(OscCommand::SetQuality(quality))
+            }
+            "/aruu/emergency_stop" => Ok(OscCommand::EmergencyStop),
+            other => Err(anyhow!("Unknown OSC address {}", other)),
+        }
+    }
+}
+
+/// Non-blocking UDP endpoint for remote control over OSC
+pub struct OscServer {
+    socket: UdpSocket,
+    buffer: Vec<u8>,
+}
+
+impl OscServer {
+    /// Bind a UDP socket, e.g. `("0.0.0.0", DEFAULT_OSC_PORT)`
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            buffer: vec![0; MAX_PACKET_SIZE],
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Drain every datagram waiting on the socket; invalid packets and messages are logged and skipped
+    pub fn poll(&mut self) -> Vec<OscCommand> {
+        let mut commands = Vec::new();
+        while let Ok((len, sender)) = self.socket.recv_from(&mut self.buffer) {
+            let messages = match parse_packet(&self.buffer[..len]) {
+                Ok(messages) => messages,
+                Err(e) => {
+                    println!("⚠️  Ignoring OSC packet from {}: {}", sender, e);
+                    continue;
+                }
+            };
+            for message in messages {
+                match OscCommand::from_message(&message) {
+                    Ok(command) => commands.push(command),
+                    Err(e) => println!("⚠️  Ignoring OSC message from {}: {}", sender, e),
+                }
+            }
+        }
+        commands
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pad(bytes: &mut Vec<u8>, text: &str) {
+        bytes.extend_from_slice(text.as_bytes());
+        bytes.push(0);
+        while !bytes.len().is_multiple_of(4) {
+            bytes.push(0);
+        }
+    }
+
+    /// Encode a message with int arguments
+    fn int_message(address: &str, values: &[i32]) -> Vec<u8> {
+        let mut packet = Vec::new();
+        pad(&mut packet, address);
+        pad(&mut packet, &format!(",{}", "i".repeat(values.len())));
+        for value in values {
+            packet.extend_from_slice(&value.to_be_bytes());
+        }
+        packet
+    }
+
+    #[test]
+    fn test_parse_known_packet() {
+        // "/aruu/shader" is 12 bytes, so its terminator pads out to 16
+        let packet = int_message("/aruu/shader", &[3]);
+        assert_eq!(packet.len(), 16 + 4 + 4);
+
+        let messages = parse_packet(&packet).unwrap();
+        assert_eq!(messages, vec![OscMessage { address: "/aruu/shader".to_string(), args: vec![OscArg::Int(3)] }]);
+        assert_eq!(OscCommand::from_message(&messages[0]).unwrap(), OscCommand::SetShader(ShaderType::Kaleidoscope));
+    }
+
+    #[test]
+    fn test_commands_from_messages() {
+        let command = |packet: Vec<u8>| OscCommand::from_message(&parse_packet(&packet).unwrap()[0]);
+
+        assert_eq!(command(int_message("/aruu/safety", &[0])).unwrap(), OscCommand::SetSafetyLevel(SafetyLevel::UltraSafe));
+        assert_eq!(command(int_message("/aruu/quality", &[4])).unwrap(), OscCommand::SetQuality(Some(QualityLevel::Ultra)));
+        assert_eq!(command(int_message("/aruu/quality", &[-1])).unwrap(), OscCommand::SetQuality(None));
+        assert_eq!(command(int_message("/aruu/emergency_stop", &[])).unwrap(), OscCommand::EmergencyStop);
+
+        // Whole-number floats count as ints
+        let mut float_packet = Vec::new();
+        pad(&mut float_packet, "/aruu/safety");
+        pad(&mut float_packet, ",f");
+        float_packet.extend_from_slice(&2.0f32.to_bits().to_be_bytes());
+        assert_eq!(command(float_packet).unwrap(), OscCommand::SetSafetyLevel(SafetyLevel::Moderate));
+    }
+
+    #[test]
+    fn test_invalid_messages_rejected() {
+        let command = |packet: Vec<u8>| OscCommand::from_message(&parse_packet(&packet).unwrap()[0]);
+
+        assert!(command(int_message("/aruu/shader", &[ShaderType::all().len() as i32])).is_err());
+        assert!(command(int_message("/aruu/shader", &[-1])).is_err());
+        assert!(command(int_message("/aruu/safety", &[4])).is_err()); // No remote disable
+        assert!(command(int_message("/aruu/shader", &[])).is_err());
+        assert!(command(int_message("/aruu/volume", &[1])).is_err());
+
+        assert!(parse_packet(b"not osc").is_err());
+        assert!(parse_packet(&int_message("/aruu/shader", &[3])[..18]).is_err());
+    }
+
+    #[test]
+    fn test_bundle_flattened() {
+        let first = int_message("/aruu/shader", &[0]);
+        let second = int_message("/aruu/emergency_stop", &[]);
+
+        let mut bundle = BUNDLE_TAG.to_vec();
+        bundle.extend_from_slice(&1u64.to_be_bytes()); // "Immediately"
+        for element in [&first, &second] {
+            bundle.extend_from_slice(&(element.len() as i32).to_be_bytes());
+            bundle.extend_from_slice(element);
+        }
+
+        let messages = parse_packet(&bundle).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].address, "/aruu/emergency_stop");
+    }
+
+    #[test]
+    fn test_server_drains_datagrams() {
+        let mut server = OscServer::bind("127.0.0.1:0").unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let target = server.local_addr().unwrap();
+        sender.send_to(&int_message("/aruu/quality", &[2]), target).unwrap();
+        sender.send_to(&int_message("/aruu/nonsense", &[]), target).unwrap();
+        sender.send_to(&int_message("/aruu/emergency_stop", &[]), target).unwrap();
+
+        // Loopback delivery is quick but not synchronous
+        let mut commands = Vec::new();
+        for _ in 0..100 {
+            commands.extend(server.poll());
+            if commands.len() >= 2 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(commands, vec![OscCommand::SetQuality(Some(QualityLevel::Medium)), OscCommand::EmergencyStop]);
+        assert!(server.poll().is_empty());
+    }
+}
@@ -1,3 +1,22 @@
+use super::color::{hsv_to_rgb, linear_srgb_to_oklab, oklab_to_linear_srgb, rgb_to_hsv, Hsv, Oklab, Vector3};
+use crate::audio::PitchClass;
+use anyhow::{anyhow, Result};
+
+/// Most stops a custom palette can hold; matches the shaders' `palette_stops` array length
+pub const MAX_CUSTOM_PALETTE_STOPS: usize = 8;
+
+/// Key confidence needed before the key-synced hue retargets to a newly detected key
+pub const KEY_SYNC_MIN_CONFIDENCE: f32 = 0.5;
+
+/// Fastest key-synced hue movement (turns per second) at a safety color change rate of 1.0
+const KEY_SYNC_HUE_SPEED: f32 = 1.0 / 6.0;
+
+/// Hue a key-synced palette shows for a pitch class: C is red and each step around the
+/// circle of fifths (C, G, D, ...) moves 30°, so related keys get neighbouring colors
+pub fn pitch_class_hue(pitch_class: PitchClass) -> f32 {
+    ((pitch_class.index() * 7) % 12) as f32 / 12.0
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ColorPalette {
     Rainbow = 0,
@@ -8,10 +27,12 @@ pub enum ColorPalette {
     Blue = 5,
     Indigo = 6,
     Violet = 7,
+    Custom = 8, // User-defined RGB stops held by `PaletteManager`; not part of downbeat cycling
+    KeySynced = 9, // Hue follows the detected key, held by `PaletteManager`; not part of downbeat cycling
 }
 
 impl ColorPalette {
-    pub const COUNT: usize = 8;
+    pub const COUNT: usize = 8; // Built-in palettes; Custom and KeySynced are excluded
 
     pub fn all_palettes() -> [ColorPalette; Self::COUNT] {
         [
@@ -27,6 +48,9 @@ impl ColorPalette {
     }
 
     pub fn next(&self) -> ColorPalette {
+        if matches!(self, ColorPalette::Custom | ColorPalette::KeySynced) {
+            return ColorPalette::Rainbow;
+        }
         let palettes = Self::all_palettes();
         let current_index = *self as usize;
         palettes[(current_index + 1) % Self::COUNT]
@@ -42,6 +66,8 @@ impl ColorPalette {
             ColorPalette::Blue => "Blue",
             ColorPalette::Indigo => "Indigo",
             ColorPalette::Violet => "Violet",
+            ColorPalette::Custom => "Custom",
+            ColorPalette::KeySynced => "Key Synced",
         }
     }
 
@@ -55,6 +81,8 @@ impl ColorPalette {
             ColorPalette::Blue => 0.667,     // 240°
             ColorPalette::Indigo => 0.75,    // 270°
             ColorPalette::Violet => 0.833,   // 300°
+            ColorPalette::Custom => 0.0,     // Derived from the stops by `PaletteManager::hue_params`
+            ColorPalette::KeySynced => 0.0,  // Follows the detected key in `PaletteManager::hue_params`
         }
     }
 
@@ -68,12 +96,30 @@ impl ColorPalette {
             ColorPalette::Blue => 0.167,     // ±60° around blue (more variation)
             ColorPalette::Indigo => 0.083,   // ±30° around indigo
             ColorPalette::Violet => 0.083,   // ±30° around violet
+            ColorPalette::Custom => 1.0,     // Full spectrum until the stops are known
+            ColorPalette::KeySynced => 0.083, // ±30°, one step around the circle of fifths
         }
     }
 
     pub fn as_index(&self) -> f32 {
         *self as usize as f32
     }
+
+    /// Palette color at a position (0.0 to 1.0) across its hue range, as full-value RGB.
+    /// Custom has no stops here and falls back to a black-to-white ramp; use `PaletteManager::color_at`
+    pub fn color_at(&self, position: f32) -> Vector3<f32> {
+        if *self == ColorPalette::Custom {
+            let gray = position.clamp(0.0, 1.0);
+            return Vector3::new(gray, gray, gray);
+        }
+
+        let offset = if *self == ColorPalette::Rainbow {
+            position
+        } else {
+            (position.clamp(0.0, 1.0) - 0.5) * 2.0 * self.hue_range()
+        };
+        hsv_to_rgb(Hsv::new(self.base_hue() + offset, 1.0, 1.0))
+    }
 }
 
 pub struct PaletteManager {
@@ -83,6 +129,12 @@ pub struct PaletteManager {
     last_switch_time: f32,
     transition_duration: f32,
     in_transition: bool,
+    palette_locked: bool, // User-chosen palette; ignores downbeat cycling and shader defaults
+    custom_stops: Vec<(f32, [f32; 3])>, // (position 0-1, RGB), sorted by position
+    continuous_position: Option<f32>, // Palette position (0-8) when driven by a continuous control
+    key_hue: f32,                     // Hue shown by KeySynced; eases toward key_target_hue
+    key_target_hue: Option<f32>,      // Hue of the last confidently detected key
+    last_key_update: Option<f32>,     // Time of the previous `update_key_sync`
 }
 
 impl PaletteManager {
@@ -94,6 +146,12 @@ impl PaletteManager {
             last_switch_time: 0.0,
             transition_duration: 1.0, // 1 second cross-fade
             in_transition: false,
+            palette_locked: false,
+            custom_stops: vec![(0.0, [0.0; 3]), (1.0, [1.0; 3])],
+            continuous_position: None,
+            key_hue: 0.0,
+            key_target_hue: None,
+            last_key_update: None,
         }
     }
 
@@ -102,6 +160,10 @@ impl PaletteManager {
     }
 
     pub fn try_switch_palette(&mut self, current_time: f32, downbeat_detected: bool) -> bool {
+        if self.palette_locked {
+            return false;
+        }
+
         if downbeat_detected && (current_time - self.last_switch_time) >= self.switch_cooldown {
             self.previous_palette = self.current_palette;
             self.current_palette = self.current_palette.next();
@@ -115,6 +177,9 @@ impl PaletteManager {
     }
 
     pub fn get_transition_blend(&self, current_time: f32) -> f32 {
+        if let Some(blend) = self.continuous_blend() {
+            return blend;
+        }
         if !self.in_transition {
             return 1.0; // No transition, fully showing current palette
         }
@@ -140,6 +205,155 @@ impl PaletteManager {
         self.previous_palette
     }
 
+    /// Blend smoothly across the built-in palettes from a continuous control: 0.0 is Rainbow,
+    /// 1.0 Red and so on, with 7.0-8.0 fading Violet back into Rainbow. Values outside 0-8 wrap.
+    /// The palette stays pinned to this position until `lock_palette` or `unlock_palette`
+    pub fn set_continuous_position(&mut self, position: f32) {
+        if !position.is_finite() {
+            return;
+        }
+        let position = position.rem_euclid(ColorPalette::COUNT as f32);
+        let palettes = ColorPalette::all_palettes();
+        let lower = (position.floor() as usize).min(ColorPalette::COUNT - 1);
+
+        self.previous_palette = palettes[lower];
+        self.current_palette = palettes[(lower + 1) % ColorPalette::COUNT];
+        self.continuous_position = Some(position);
+        self.in_transition = false;
+        self.palette_locked = true;
+    }
+
+    pub fn continuous_position(&self) -> Option<f32> {
+        self.continuous_position
+    }
+
+    /// Weight of the current palette against the previous one while on a continuous position
+    pub fn continuous_blend(&self) -> Option<f32> {
+        self.continuous_position.map(|position| position.fract())
+    }
+
+    /// Replace the custom palette's stops: up to `MAX_CUSTOM_PALETTE_STOPS` (position 0-1, RGB 0-1) pairs.
+    /// Stops are sorted by position and clamped; positions outside the first/last stop hold their color
+    pub fn set_custom_palette(&mut self, stops: Vec<(f32, [f32; 3])>) -> Result<()> {
+        if stops.is_empty() || stops.len() > MAX_CUSTOM_PALETTE_STOPS {
+            return Err(anyhow!(
+                "Custom palette needs 1 to {} stops, got {}",
+                MAX_CUSTOM_PALETTE_STOPS,
+                stops.len()
+            ));
+        }
+        if stops.iter().any(|(position, rgb)| !position.is_finite() || rgb.iter().any(|c| !c.is_finite())) {
+            return Err(anyhow!("Custom palette stops must be finite"));
+        }
+
+        let mut stops: Vec<(f32, [f32; 3])> = stops
+            .into_iter()
+            .map(|(position, rgb)| (position.clamp(0.0, 1.0), rgb.map(|c| c.clamp(0.0, 1.0))))
+            .collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.custom_stops = stops;
+        println!("🎨 Custom palette set with {} stops", self.custom_stops.len());
+        Ok(())
+    }
+
+    pub fn custom_stops(&self) -> &[(f32, [f32; 3])] {
+        &self.custom_stops
+    }
+
+    /// Custom palette color at a position, linearly interpolated between the surrounding stops
+    pub fn custom_color_at(&self, position: f32) -> Vector3<f32> {
+        let position = position.clamp(0.0, 1.0);
+        let to_vector = |rgb: [f32; 3]| Vector3::new(rgb[0], rgb[1], rgb[2]);
+
+        let next = self.custom_stops.iter().position(|(p, _)| *p >= position);
+        match next {
+            Some(0) => to_vector(self.custom_stops[0].1),
+            Some(index) => {
+                let (p0, c0) = self.custom_stops[index - 1];
+                let (p1, c1) = self.custom_stops[index];
+                let t = if p1 > p0 { (position - p0) / (p1 - p0) } else { 1.0 };
+                Vector3::new(
+                    c0[0] + (c1[0] - c0[0]) * t,
+                    c0[1] + (c1[1] - c0[1]) * t,
+                    c0[2] + (c1[2] - c0[2]) * t,
+                )
+            }
+            None => to_vector(self.custom_stops[self.custom_stops.len() - 1].1),
+        }
+    }
+
+    /// Stops packed for the shaders' `palette_stops` storage buffer: (r, g, b, position), unused slots zeroed
+    pub fn custom_stops_packed(&self) -> [[f32; 4]; MAX_CUSTOM_PALETTE_STOPS] {
+        let mut packed = [[0.0; 4]; MAX_CUSTOM_PALETTE_STOPS];
+        for (slot, (position, rgb)) in packed.iter_mut().zip(&self.custom_stops) {
+            *slot = [rgb[0], rgb[1], rgb[2], *position];
+        }
+        packed
+    }
+
+    /// Base hue and hue range for a palette; Custom derives them from its stops so
+    /// hue-only shaders still land near the custom colors
+    pub fn hue_params(&self, palette: ColorPalette) -> (f32, f32) {
+        if palette == ColorPalette::KeySynced {
+            return (self.key_hue, palette.hue_range());
+        }
+        if palette != ColorPalette::Custom {
+            return (palette.base_hue(), palette.hue_range());
+        }
+
+        // Saturation-weighted circular mean of the stop hues; spread is the widest deviation from it
+        let hsvs: Vec<Hsv> = self.custom_stops.iter().map(|(_, rgb)| rgb_to_hsv(Vector3::new(rgb[0], rgb[1], rgb[2]))).collect();
+        let (sin_sum, cos_sum) = hsvs.iter().fold((0.0f32, 0.0f32), |(s, c), hsv| {
+            let angle = hsv.h * std::f32::consts::TAU;
+            (s + angle.sin() * hsv.s, c + angle.cos() * hsv.s)
+        });
+        if sin_sum.abs() < 1e-6 && cos_sum.abs() < 1e-6 {
+            return (0.0, 0.0); // Grayscale stops: no hue to vary
+        }
+
+        let base_hue = (sin_sum.atan2(cos_sum) / std::f32::consts::TAU).rem_euclid(1.0);
+        let spread = hsvs
+            .iter()
+            .filter(|hsv| hsv.s > 1e-3)
+            .map(|hsv| {
+                let delta = (hsv.h - base_hue).rem_euclid(1.0);
+                delta.min(1.0 - delta)
+            })
+            .fold(0.0f32, f32::max);
+        (base_hue, (spread * 2.0).min(1.0))
+    }
+
+    /// Palette color at a position, using the custom stops for `ColorPalette::Custom`
+    /// and the key hue for `ColorPalette::KeySynced`
+    pub fn palette_color_at(&self, palette: ColorPalette, position: f32) -> Vector3<f32> {
+        match palette {
+            ColorPalette::Custom => self.custom_color_at(position),
+            ColorPalette::KeySynced => {
+                let (base_hue, hue_range) = self.hue_params(palette);
+                let offset = (position.clamp(0.0, 1.0) - 0.5) * 2.0 * hue_range;
+                hsv_to_rgb(Hsv::new(base_hue + offset, 1.0, 1.0))
+            }
+            _ => palette.color_at(position),
+        }
+    }
+
+    /// Palette color at a position, cross-faded in Oklab while a transition is running
+    pub fn color_at(&self, position: f32, current_time: f32) -> Vector3<f32> {
+        let blend = self.get_transition_blend(current_time);
+        let to = self.palette_color_at(self.current_palette, position);
+        if blend >= 1.0 {
+            return to;
+        }
+
+        let from = linear_srgb_to_oklab(self.palette_color_at(self.previous_palette, position));
+        let to = linear_srgb_to_oklab(to);
+        oklab_to_linear_srgb(Oklab::new(
+            from.l + (to.l - from.l) * blend,
+            from.a + (to.a - from.a) * blend,
+            from.b + (to.b - from.b) * blend,
+        ))
+    }
+
     pub fn set_cooldown(&mut self, seconds: f32) {
         self.switch_cooldown = seconds.max(0.1);
     }
@@ -149,6 +363,88 @@ impl PaletteManager {
         self.last_switch_time = current_time;
         println!("🎨 Palette forced to: {}", palette.name());
     }
+
+    /// Apply a shader's preferred palette with a cross-fade, unless the user has locked one
+    pub fn apply_shader_default(&mut self, palette: ColorPalette, current_time: f32) -> bool {
+        if self.palette_locked || palette == self.current_palette {
+            return false;
+        }
+
+        self.previous_palette = self.current_palette;
+        self.current_palette = palette;
+        self.last_switch_time = current_time;
+        self.in_transition = true;
+        println!("🎨 Palette set by shader default: {}", palette.name());
+        true
+    }
+
+    /// Pin a palette so automatic switching leaves it alone
+    pub fn lock_palette(&mut self, palette: ColorPalette, current_time: f32) {
+        self.continuous_position = None;
+        self.force_switch_palette(palette, current_time);
+        self.palette_locked = true;
+    }
+
+    pub fn unlock_palette(&mut self) {
+        self.continuous_position = None;
+        self.palette_locked = false;
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.palette_locked
+    }
+
+    /// Lock the key-synced palette (true), or release it back to automatic switching (false).
+    /// The key hue starts from the hue already on screen, so enabling it doesn't jump
+    pub fn set_key_sync(&mut self, enabled: bool) {
+        if enabled == self.is_key_synced() {
+            return;
+        }
+
+        if enabled {
+            if self.current_palette != ColorPalette::Rainbow {
+                self.key_hue = self.hue_params(self.current_palette).0;
+            }
+            self.previous_palette = self.current_palette;
+            self.current_palette = ColorPalette::KeySynced;
+            self.continuous_position = None;
+            self.in_transition = false;
+            self.palette_locked = true;
+            println!("🎼 Palette following the detected key");
+        } else {
+            self.unlock_palette();
+            println!("🎼 Palette no longer following the key");
+        }
+    }
+
+    /// Key-synced and locked; once released, the key hue holds until the next automatic switch
+    pub fn is_key_synced(&self) -> bool {
+        self.palette_locked && self.current_palette == ColorPalette::KeySynced
+    }
+
+    /// Track the detected key. Keys below `KEY_SYNC_MIN_CONFIDENCE` are ignored, and while
+    /// key-synced the hue moves toward the key's hue along the shorter way around the color
+    /// wheel, no faster than `KEY_SYNC_HUE_SPEED` scaled by the safety `color_change_rate`
+    pub fn update_key_sync(&mut self, key_root: Option<PitchClass>, confidence: f32, current_time: f32, color_change_rate: f32) {
+        let elapsed = self.last_key_update.map_or(0.0, |last| (current_time - last).max(0.0));
+        self.last_key_update = Some(current_time);
+
+        if let Some(root) = key_root.filter(|_| confidence >= KEY_SYNC_MIN_CONFIDENCE) {
+            self.key_target_hue = Some(pitch_class_hue(root));
+        }
+        let Some(target) = self.key_target_hue.filter(|_| self.is_key_synced()) else {
+            return;
+        };
+
+        let delta = (target - self.key_hue + 0.5).rem_euclid(1.0) - 0.5; // Shortest signed arc
+        let max_step = KEY_SYNC_HUE_SPEED * color_change_rate.max(0.0) * elapsed;
+        self.key_hue = (self.key_hue + delta.clamp(-max_step, max_step)).rem_euclid(1.0);
+    }
+
+    /// Hue the key-synced palette is showing right now
+    pub fn key_hue(&self) -> f32 {
+        self.key_hue
+    }
 }
 
 #[cfg(test)]
@@ -182,6 +478,24 @@ mod tests {
         assert_eq!(manager.current_palette(), ColorPalette::Red);
     }
 
+    #[test]
+    fn test_shader_default_respects_lock() {
+        let mut manager = PaletteManager::new();
+
+        assert!(manager.apply_shader_default(ColorPalette::Violet, 1.0));
+        assert_eq!(manager.current_palette(), ColorPalette::Violet);
+        assert_eq!(manager.previous_palette(), ColorPalette::Rainbow);
+
+        manager.lock_palette(ColorPalette::Blue, 2.0);
+        assert!(!manager.apply_shader_default(ColorPalette::Rainbow, 3.0));
+        assert!(!manager.try_switch_palette(10.0, true));
+        assert_eq!(manager.current_palette(), ColorPalette::Blue);
+
+        manager.unlock_palette();
+        assert!(manager.apply_shader_default(ColorPalette::Rainbow, 11.0));
+        assert_eq!(manager.current_palette(), ColorPalette::Rainbow);
+    }
+
     #[test]
     fn test_palette_properties() {
         assert_eq!(ColorPalette::Rainbow.name(), "Rainbow");
@@ -190,4 +504,141 @@ mod tests {
         assert_eq!(ColorPalette::Rainbow.hue_range(), 1.0);
         assert_eq!(ColorPalette::Red.hue_range(), 0.083);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_palette_colors() {
+        let red = ColorPalette::Red.color_at(0.5);
+        assert_eq!(red, Vector3::new(1.0, 0.0, 0.0));
+
+        // Blend starts at the previous palette and ends at the current one
+        let mut manager = PaletteManager::new();
+        manager.apply_shader_default(ColorPalette::Blue, 10.0);
+        manager.previous_palette = ColorPalette::Red;
+        let start = manager.color_at(0.5, 10.0);
+        assert!((start.x - 1.0).abs() < 1e-3 && start.z.abs() < 1e-3);
+        let end = manager.color_at(0.5, 100.0);
+        assert!(end.x.abs() < 1e-2 && (end.z - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_custom_palette_interpolates() {
+        let mut manager = PaletteManager::new();
+        manager.set_custom_palette(vec![(1.0, [1.0, 1.0, 1.0]), (0.0, [0.0, 0.0, 0.0])]).unwrap();
+
+        // Stops are sorted, so black-to-white gives mid gray halfway
+        assert_eq!(manager.custom_color_at(0.5), Vector3::new(0.5, 0.5, 0.5));
+        assert_eq!(manager.custom_color_at(-1.0), Vector3::new(0.0, 0.0, 0.0));
+        assert_eq!(manager.custom_color_at(2.0), Vector3::new(1.0, 1.0, 1.0));
+
+        manager.lock_palette(ColorPalette::Custom, 0.0);
+        assert_eq!(manager.color_at(0.5, 10.0), Vector3::new(0.5, 0.5, 0.5));
+        assert_eq!(manager.hue_params(ColorPalette::Custom), (0.0, 0.0));
+
+        let packed = manager.custom_stops_packed();
+        assert_eq!(packed[1], [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(packed[2], [0.0; 4]);
+
+        // Stop count is bounded by the shader array
+        assert!(manager.set_custom_palette(Vec::new()).is_err());
+        assert!(manager.set_custom_palette(vec![(0.0, [0.0; 3]); MAX_CUSTOM_PALETTE_STOPS + 1]).is_err());
+        assert_eq!(manager.custom_stops().len(), 2);
+
+        // Custom never enters downbeat cycling
+        assert_eq!(ColorPalette::Custom.next(), ColorPalette::Rainbow);
+        assert!(!ColorPalette::all_palettes().contains(&ColorPalette::Custom));
+    }
+
+    #[test]
+    fn test_custom_palette_hue_params() {
+        let mut manager = PaletteManager::new();
+        manager.set_custom_palette(vec![(0.0, [1.0, 0.0, 0.0]), (1.0, [1.0, 1.0, 0.0])]).unwrap();
+
+        // Red to yellow centers on orange and spans 60°
+        let (base_hue, hue_range) = manager.hue_params(ColorPalette::Custom);
+        assert!((base_hue - 1.0 / 12.0).abs() < 1e-3);
+        assert!((hue_range - 1.0 / 6.0).abs() < 1e-3);
+        assert_eq!(manager.hue_params(ColorPalette::Blue), (0.667, 0.167));
+    }
+
+    #[test]
+    fn test_continuous_position_blends_adjacent_palettes() {
+        let mut manager = PaletteManager::new();
+        manager.set_continuous_position(1.5);
+        assert_eq!(manager.current_palette(), ColorPalette::Orange);
+        assert_eq!(manager.previous_palette(), ColorPalette::Red);
+        assert!((manager.get_transition_blend(0.0) - 0.5).abs() < 1e-6);
+        assert!(manager.is_locked());
+        assert!(!manager.try_switch_palette(100.0, true));
+
+        // Violet fades back into Rainbow, and the position wraps past 8
+        manager.set_continuous_position(7.25);
+        assert_eq!(manager.previous_palette(), ColorPalette::Violet);
+        assert_eq!(manager.current_palette(), ColorPalette::Rainbow);
+        assert!((manager.continuous_blend().unwrap() - 0.25).abs() < 1e-6);
+        manager.set_continuous_position(8.0);
+        assert_eq!(manager.previous_palette(), ColorPalette::Rainbow);
+        assert_eq!(manager.continuous_blend(), Some(0.0));
+        manager.set_continuous_position(-0.5);
+        assert_eq!(manager.previous_palette(), ColorPalette::Violet);
+
+        // Halfway between Red and Orange sits between their hues
+        manager.set_continuous_position(1.5);
+        let color = manager.color_at(0.5, 0.0);
+        assert!(color.x > 0.9 && color.y > 0.1 && color.y < 0.5);
+
+        manager.unlock_palette();
+        assert!(manager.continuous_position().is_none());
+        assert_eq!(manager.get_transition_blend(0.0), 1.0);
+    }
+
+    #[test]
+    fn test_pitch_classes_map_to_even_hues() {
+        let mut hues: Vec<f32> = PitchClass::all().iter().map(|&pitch_class| pitch_class_hue(pitch_class)).collect();
+        assert_eq!(pitch_class_hue(PitchClass::C), 0.0); // C is red
+        assert!((pitch_class_hue(PitchClass::G) - 1.0 / 12.0).abs() < 1e-6); // A fifth up is the next hue
+
+        hues.sort_by(f32::total_cmp);
+        for (step, hue) in hues.iter().enumerate() {
+            assert!((hue - step as f32 / 12.0).abs() < 1e-6, "hue {} is {}", step, hue);
+        }
+    }
+
+    #[test]
+    fn test_key_sync_hue_wraps_continuously() {
+        const FRAME: f32 = 1.0 / 60.0;
+        let circular_distance = |a: f32, b: f32| {
+            let delta = (a - b).rem_euclid(1.0);
+            delta.min(1.0 - delta)
+        };
+
+        let mut manager = PaletteManager::new();
+        manager.set_key_sync(true);
+        assert!(manager.is_key_synced() && manager.is_locked());
+        assert_eq!(manager.current_palette().next(), ColorPalette::Rainbow);
+
+        // Settle on B, then change key to C and to F and back to C: every step stays small,
+        // including across the wrap from the end of the color wheel back to red
+        for root in [PitchClass::B, PitchClass::C, PitchClass::F, PitchClass::C] {
+            let mut time = manager.last_key_update.unwrap_or(0.0);
+            for _ in 0..600 {
+                time += FRAME;
+                let before = manager.key_hue();
+                manager.update_key_sync(Some(root), 0.9, time, 1.0);
+                assert!(circular_distance(manager.key_hue(), before) <= KEY_SYNC_HUE_SPEED * FRAME + 1e-5);
+            }
+            assert!(circular_distance(manager.key_hue(), pitch_class_hue(root)) < 1e-4, "{:?}", root);
+        }
+        assert!(manager.key_hue() < 1e-4 || manager.key_hue() > 1.0 - 1e-4);
+
+        // Weak detections and a zero safety rate leave the hue alone
+        let hue = manager.key_hue();
+        manager.update_key_sync(Some(PitchClass::FSharp), 0.1, 20.0, 1.0);
+        assert_eq!(manager.key_hue(), hue);
+        manager.update_key_sync(Some(PitchClass::FSharp), 0.9, 30.0, 0.0);
+        assert_eq!(manager.key_hue(), hue);
+
+        manager.set_key_sync(false);
+        assert!(!manager.is_locked() && !manager.is_key_synced());
+        assert_eq!(manager.hue_params(ColorPalette::KeySynced).0, hue);
+    }
+}
@@ -0,0 +1,244 @@
+//! Named visual presets that can be saved and recalled
+//!
+//! A preset captures the active shader, the locked palette (if any), the
+//! safety level and the multi-mode effect weights. Presets live in a
+//! plain-text file with one `[name]` section per preset.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::control::{ColorPalette, SafetyLevel};
+use crate::rendering::{EffectWeights, ShaderType};
+use crate::session::{parse_safety_level, parse_shader_type, safety_level_token};
+
+/// First line of every preset file
+const PRESET_HEADER: &str = "aruu-presets 1";
+
+/// Palette token meaning "follow the shader's default palette"
+const AUTO_PALETTE: &str = "auto";
+
+/// Snapshot of the visual settings worth recalling
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    pub shader: ShaderType,
+    pub palette: Option<ColorPalette>, // None leaves palette selection to the shader
+    pub safety_level: SafetyLevel,
+    pub effect_weights: EffectWeights,
+}
+
+impl Default for Preset {
+    fn default() -> Self {
+        Self {
+            shader: ShaderType::Classic,
+            palette: None,
+            safety_level: SafetyLevel::default(),
+            effect_weights: EffectWeights::default(),
+        }
+    }
+}
+
+/// Named presets, kept sorted by name
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PresetBank {
+    presets: BTreeMap<String, Preset>,
+}
+
+impl PresetBank {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a preset file; a missing file yields an empty bank
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read preset file {}", path.display()))?;
+        Self::parse(&text)
+    }
+
+    /// Parse the plain-text preset format
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(PRESET_HEADER) {
+            return Err(anyhow!("Not an Aruu preset file (missing '{}' header)", PRESET_HEADER));
+        }
+
+        let mut bank = Self::new();
+        let mut current: Option<(String, Preset)> = None;
+        for (index, line) in lines.enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line_number = index + 2;
+
+            if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(anyhow!("Preset line {}: empty preset name", line_number));
+                }
+                if let Some((name, preset)) = current.take() {
+                    bank.insert(name, preset);
+                }
+                current = Some((name.to_string(), Preset::default()));
+                continue;
+            }
+
+            let (_, preset) = current
+                .as_mut()
+                .ok_or_else(|| anyhow!("Preset line {}: setting outside a [preset] section", line_number))?;
+            parse_setting(preset, line).with_context(|| format!("Preset line {}", line_number))?;
+        }
+        if let Some((name, preset)) = current {
+            bank.insert(name, preset);
+        }
+
+        Ok(bank)
+    }
+
+    /// Serialize to the plain-text preset format
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(PRESET_HEADER);
+        text.push('\n');
+        for (name, preset) in &self.presets {
+            let palette = preset.palette.map_or(AUTO_PALETTE, |palette| palette.name());
+            let weights: Vec<String> = preset.effect_weights.to_array().iter().map(|w| w.to_string()).collect();
+            text.push_str(&format!("\n[{}]\n", name));
+            text.push_str(&format!("shader = {:?}\n", preset.shader));
+            text.push_str(&format!("palette = {}\n", palette));
+            text.push_str(&format!("safety = {}\n", safety_level_token(preset.safety_level)));
+            text.push_str(&format!("weights = {}\n", weights.join(" ")));
+        }
+        text
+    }
+
+    /// Write every preset to disk
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_text())
+            .with_context(|| format!("Failed to write preset file {}", path.display()))
+    }
+
+    /// Store a preset, replacing any existing one with the same name
+    pub fn insert(&mut self, name: impl Into<String>, preset: Preset) {
+        self.presets.insert(name.into(), preset);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Preset> {
+        self.presets.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Preset> {
+        self.presets.remove(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.presets.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.presets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.presets.is_empty()
+    }
+}
+
+/// Bank name for a numbered key-binding slot
+pub fn slot_name(slot: u8) -> String {
+    format!("Slot {}", slot)
+}
+
+fn parse_palette(token: &str) -> Result<Option<ColorPalette>> {
+    if token.eq_ignore_ascii_case(AUTO_PALETTE) {
+        return Ok(None);
+    }
+    ColorPalette::all_palettes()
+        .into_iter()
+        .find(|palette| palette.name() == token)
+        .map(Some)
+        .ok_or_else(|| anyhow!("Unknown palette '{}'", token))
+}
+
+fn parse_setting(preset: &mut Preset, line: &str) -> Result<()> {
+    let (key, value) = line
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Expected 'key = value', got '{}'", line))?;
+    let value = value.trim();
+
+    match key.trim() {
+        "shader" => preset.shader = parse_shader_type(value)?,
+        "palette" => preset.palette = parse_palette(value)?,
+        "safety" => preset.safety_level = parse_safety_level(value)?,
+        "weights" => {
+            let values = value.split_whitespace().map(|v| v.parse::<f32>()).collect::<Result<Vec<_>, _>>()?;
+            let weights: [f32; EffectWeights::COUNT] = values
+                .try_into()
+                .map_err(|values: Vec<f32>| anyhow!("Expected {} weights, got {}", EffectWeights::COUNT, values.len()))?;
+            preset.effect_weights = EffectWeights::from_array(weights).clamped();
+        }
+        other => return Err(anyhow!("Unknown preset setting '{}'", other)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_bank() -> PresetBank {
+        let mut bank = PresetBank::new();
+        bank.insert(slot_name(1), Preset {
+            shader: ShaderType::Plasma,
+            palette: Some(ColorPalette::Blue),
+            safety_level: SafetyLevel::UltraSafe,
+            effect_weights: EffectWeights::from_array([0.8, 0.1, 0.0, 0.25, 0.5, 1.0]),
+        });
+        bank.insert("Late night", Preset {
+            shader: ShaderType::Spectralizer,
+            ..Preset::default()
+        });
+        bank
+    }
+
+    #[test]
+    fn test_preset_bank_round_trips_through_text_and_file() {
+        let bank = sample_bank();
+        let parsed = PresetBank::parse(&bank.to_text()).unwrap();
+        assert_eq!(parsed, bank);
+        assert_eq!(parsed.get("Slot 1").unwrap().palette, Some(ColorPalette::Blue));
+        assert_eq!(parsed.get("Late night").unwrap().palette, None);
+        assert_eq!(parsed.names().collect::<Vec<_>>(), vec!["Late night", "Slot 1"]);
+
+        let path = std::env::temp_dir().join(format!("aruu-presets-test-{}.txt", std::process::id()));
+        bank.save(&path).unwrap();
+        let loaded = PresetBank::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded, bank);
+
+        assert!(PresetBank::load(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_presets() {
+        assert!(PresetBank::parse("[Slot 1]\nshader = Plasma\n").is_err());
+        assert!(PresetBank::parse("aruu-presets 1\nshader = Plasma\n").is_err());
+        assert!(PresetBank::parse("aruu-presets 1\n[Slot 1]\nshader = Nope\n").is_err());
+        assert!(PresetBank::parse("aruu-presets 1\n[Slot 1]\npalette = Custom\n").is_err());
+        assert!(PresetBank::parse("aruu-presets 1\n[Slot 1]\nweights = 0.1 0.2\n").is_err());
+        assert!(PresetBank::parse("aruu-presets 1\n[Slot 1]\nbrightness = 2\n").is_err());
+
+        // Omitted settings fall back to defaults; comments are ignored
+        let bank = PresetBank::parse("aruu-presets 1\n# mine\n[Calm]\nsafety = Safe\n").unwrap();
+        let calm = bank.get("Calm").unwrap();
+        assert_eq!(calm.safety_level, SafetyLevel::Safe);
+        assert_eq!(calm.shader, ShaderType::Classic);
+        assert_eq!(calm.effect_weights, EffectWeights::default());
+    }
+}
@@ -10,40 +10,11 @@
 /// - Preserve musical reactivity while ensuring user safety
 /// - Intelligent dampening rather than blanket restrictions
 
-use std::time::Instant;
-
-/// Simple 3D vector for RGB color operations
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Vector3<T> {
-    pub x: T,
-    pub y: T,
-    pub z: T,
-}
-
-impl<T> Vector3<T> {
-    pub fn new(x: T, y: T, z: T) -> Self {
-        Self { x, y, z }
-    }
-}
-
-impl Vector3<f32> {
-    /// Multiply vector by scalar
-    pub fn mul_scalar(self, scalar: f32) -> Self {
-        Self {
-            x: self.x * scalar,
-            y: self.y * scalar,
-            z: self.z * scalar,
-        }
-    }
-}
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
 
-impl std::ops::Mul<f32> for Vector3<f32> {
-    type Output = Vector3<f32>;
-
-    fn mul(self, scalar: f32) -> Self::Output {
-        self.mul_scalar(scalar)
-    }
-}
+use crate::clock::{system_clock, SharedClock};
+use super::color::{linear_to_srgb, relative_luminance, srgb_to_linear, Vector3};
 
 /// Core safety limits based on international standards
 pub const FLASH_RATE_LIMIT_HZ: f32 = 3.0;  // Maximum 3 flashes per second
@@ -52,7 +23,7 @@ pub const RED_FLASH_LIMIT_HZ: f32 = 3.0;     // Red flashes most dangerous
 pub const SAFETY_COOLDOWN_SECONDS: f32 = 1.0 / FLASH_RATE_LIMIT_HZ; // 333ms between major changes
 
 /// Safety levels for user control
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum SafetyLevel {
     /// Ultra-conservative for maximum safety
     UltraSafe,
@@ -79,23 +50,30 @@ pub struct FlashTracker {
     last_red_flash: Instant,
     recent_changes: Vec<(Instant, f32)>, // (time, intensity) pairs
     change_accumulator: f32,
+    clock: SharedClock,
 }
 
 impl FlashTracker {
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a tracker driven by a custom time source
+    pub fn with_clock(clock: SharedClock) -> Self {
         // Initialize with past timestamps to allow first flash
-        let past_time = Instant::now() - std::time::Duration::from_secs(1);
+        let past_time = clock.now() - std::time::Duration::from_secs(1);
         Self {
             last_major_change: past_time,
             last_red_flash: past_time,
             recent_changes: Vec::new(),
             change_accumulator: 0.0,
+            clock,
         }
     }
 
     /// Check if a visual change is safe to allow
     pub fn can_allow_change(&mut self, intensity: f32, is_red_dominant: bool) -> bool {
-        let now = Instant::now();
+        let now = self.clock.now();
 
         // Clean old changes (only keep last second)
         self.recent_changes.retain(|(time, _)| now.duration_since(*time).as_secs_f32() < 1.0);
@@ -130,7 +108,7 @@ impl FlashTracker {
 
     /// Record a visual change for tracking
     pub fn record_change(&mut self, intensity: f32, is_red_dominant: bool) {
-        let now = Instant::now();
+        let now = self.clock.now();
 
         if intensity > 0.3 {
             self.last_major_change = now;
@@ -150,24 +128,48 @@ impl FlashTracker {
 pub struct LuminanceLimiter {
     previous_luminance: f32,
     luminance_history: Vec<(Instant, f32)>,
+    clock: SharedClock,
 }
 
 impl LuminanceLimiter {
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a limiter driven by a custom time source
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
-            previous_luminance: 0.5, // Start with medium brightness
+            previous_luminance: 0.214, // Start at sRGB mid-gray, measured in linear light
             luminance_history: Vec::new(),
+            clock,
         }
     }
 
-    /// Calculate relative luminance from RGB values (ITU-R BT.709 standard)
+    /// Calculate relative luminance of sRGB-encoded values (ITU-R BT.709 weights on linear light)
+    ///
+    /// The WCAG flash threshold is defined on relative luminance, so shader output is
+    /// decoded to linear light first; sRGB mid-gray (0.5) measures ~0.214, not 0.5.
     pub fn calculate_luminance(rgb: Vector3<f32>) -> f32 {
-        0.2126 * rgb.x + 0.7152 * rgb.y + 0.0722 * rgb.z
+        Self::calculate_luminance_linear(Self::to_linear(rgb))
+    }
+
+    /// Calculate relative luminance of values that are already linear light
+    pub fn calculate_luminance_linear(rgb: Vector3<f32>) -> f32 {
+        relative_luminance(rgb)
     }
 
-    /// Limit luminance change to safe levels
+    fn to_linear(rgb: Vector3<f32>) -> Vector3<f32> {
+        Vector3::new(srgb_to_linear(rgb.x), srgb_to_linear(rgb.y), srgb_to_linear(rgb.z))
+    }
+
+    fn to_srgb(rgb: Vector3<f32>) -> Vector3<f32> {
+        Vector3::new(linear_to_srgb(rgb.x), linear_to_srgb(rgb.y), linear_to_srgb(rgb.z))
+    }
+
+    /// Limit luminance change to safe levels; takes and returns sRGB, limits in linear light
     pub fn limit_luminance_change(&mut self, new_rgb: Vector3<f32>) -> Vector3<f32> {
-        let new_luminance = Self::calculate_luminance(new_rgb);
+        let linear_rgb = Self::to_linear(new_rgb);
+        let new_luminance = Self::calculate_luminance_linear(linear_rgb);
         let luminance_delta = (new_luminance - self.previous_luminance).abs();
 
         if luminance_delta > LUMINANCE_CHANGE_LIMIT {
@@ -178,19 +180,19 @@ impl LuminanceLimiter {
                 self.previous_luminance - LUMINANCE_CHANGE_LIMIT
             };
 
-            // Scale RGB to achieve safe luminance
+            // Scale linear RGB to achieve safe luminance, then re-encode
             let luminance_ratio = safe_luminance / new_luminance.max(0.001);
-            let safe_rgb = new_rgb * luminance_ratio;
+            let safe_rgb = Self::to_srgb(linear_rgb * luminance_ratio);
 
             self.previous_luminance = safe_luminance;
 
             // Record this change
-            self.luminance_history.push((Instant::now(), safe_luminance));
+            self.luminance_history.push((self.clock.now(), safe_luminance));
 
             safe_rgb
         } else {
             self.previous_luminance = new_luminance;
-            self.luminance_history.push((Instant::now(), new_luminance));
+            self.luminance_history.push((self.clock.now(), new_luminance));
             new_rgb
         }
     }
@@ -201,7 +203,7 @@ impl LuminanceLimiter {
             return 0.0;
         }
 
-        let now = Instant::now();
+        let now = self.clock.now();
         let recent_changes: Vec<_> = self.luminance_history.iter()
             .filter(|(time, _)| now.duration_since(*time).as_secs_f32() < 1.0)
             .collect();
@@ -226,16 +228,27 @@ pub struct SafetyEngine {
     safety_level: SafetyLevel,
     emergency_stop: bool,
     safety_warnings: Vec<String>,
+    clock: SharedClock,
+    auto_resume: Option<Duration>,     // None = manual resume only
+    emergency_stopped_at: Option<Instant>,
 }
 
 impl SafetyEngine {
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create an engine whose flash and luminance tracking share a custom time source
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
-            flash_tracker: FlashTracker::new(),
-            luminance_limiter: LuminanceLimiter::new(),
+            flash_tracker: FlashTracker::with_clock(clock.clone()),
+            luminance_limiter: LuminanceLimiter::with_clock(clock.clone()),
             safety_level: SafetyLevel::default(),
             emergency_stop: false,
             safety_warnings: Vec::new(),
+            clock,
+            auto_resume: None,
+            emergency_stopped_at: None,
         }
     }
 
@@ -252,15 +265,42 @@ impl SafetyEngine {
     /// Emergency stop all visual effects
     pub fn emergency_stop(&mut self) {
         self.emergency_stop = true;
+        self.emergency_stopped_at = Some(self.clock.now());
         self.safety_warnings.push("Emergency stop activated".to_string());
     }
 
     /// Resume from emergency stop
     pub fn resume(&mut self) {
         self.emergency_stop = false;
+        self.emergency_stopped_at = None;
         self.safety_warnings.clear();
     }
 
+    /// Opt-in automatic resume (at UltraSafe) after an emergency stop; None keeps manual-only
+    pub fn set_auto_resume(&mut self, interval: Option<Duration>) {
+        self.auto_resume = interval;
+    }
+
+    /// Configured auto-resume interval, if any
+    pub fn auto_resume(&self) -> Option<Duration> {
+        self.auto_resume
+    }
+
+    /// Advance time-based safety state; returns true when an auto-resume just happened
+    pub fn update(&mut self) -> bool {
+        let (Some(interval), Some(stopped_at)) = (self.auto_resume, self.emergency_stopped_at) else {
+            return false;
+        };
+        if !self.emergency_stop || self.clock.now().duration_since(stopped_at) < interval {
+            return false;
+        }
+
+        self.resume();
+        self.safety_level = SafetyLevel::UltraSafe;
+        println!("🛡️  Auto-resumed after {:.0}s emergency stop - Ultra Safe mode", interval.as_secs_f32());
+        true
+    }
+
     /// Check if emergency stop is active
     pub fn is_emergency_stopped(&self) -> bool {
         self.emergency_stop
@@ -357,6 +397,7 @@ pub struct SafetyMultipliers {
     pub color_change_rate: f32,
     pub brightness_range: f32,
     pub pattern_complexity: f32,
+    pub screen_shake: f32,
 }
 
 impl SafetyMultipliers {
@@ -367,6 +408,7 @@ impl SafetyMultipliers {
             color_change_rate: 0.0,
             brightness_range: 0.1,
             pattern_complexity: 0.0,
+            screen_shake: 0.0,
         }
     }
 
@@ -377,6 +419,7 @@ impl SafetyMultipliers {
             color_change_rate: 0.2,
             brightness_range: 0.3,
             pattern_complexity: 0.3,
+            screen_shake: 0.0,   // Never shake the screen in Ultra Safe
         }
     }
 
@@ -387,6 +430,7 @@ impl SafetyMultipliers {
             color_change_rate: 0.4,
             brightness_range: 0.5,
             pattern_complexity: 0.5,
+            screen_shake: 0.3,
         }
     }
 
@@ -397,6 +441,7 @@ impl SafetyMultipliers {
             color_change_rate: 0.7,
             brightness_range: 0.7,
             pattern_complexity: 0.7,
+            screen_shake: 0.6,
         }
     }
 
@@ -407,6 +452,7 @@ impl SafetyMultipliers {
             color_change_rate: 0.9,
             brightness_range: 0.9,
             pattern_complexity: 0.9,
+            screen_shake: 0.8,
         }
     }
 
@@ -417,6 +463,7 @@ impl SafetyMultipliers {
             color_change_rate: 1.0,
             brightness_range: 1.0,
             pattern_complexity: 1.0,
+            screen_shake: 1.0,
         }
     }
 }
@@ -463,6 +510,7 @@ impl SafetyStatus {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
 
     #[test]
     fn test_flash_rate_limiting() {
@@ -476,6 +524,22 @@ mod tests {
         assert!(!tracker.can_allow_change(0.5, false));
     }
 
+    #[test]
+    fn test_flash_cooldown_with_mock_clock() {
+        let clock = MockClock::new();
+        let mut tracker = FlashTracker::with_clock(clock.shared());
+
+        tracker.record_change(0.5, false);
+
+        // Just short of the cooldown window is still blocked
+        clock.advance_secs(SAFETY_COOLDOWN_SECONDS - 0.01);
+        assert!(!tracker.can_allow_change(0.5, false));
+
+        // Once the cooldown has passed the next flash is allowed
+        clock.advance_secs(0.02);
+        assert!(tracker.can_allow_change(0.5, false));
+    }
+
     #[test]
     fn test_luminance_limiting() {
         let mut limiter = LuminanceLimiter::new();
@@ -483,8 +547,24 @@ mod tests {
         let bright_color = Vector3::new(1.0, 1.0, 1.0);
         let limited = limiter.limit_luminance_change(bright_color);
 
-        // Should be limited from previous luminance (0.5)
-        assert!(LuminanceLimiter::calculate_luminance(limited) <= 0.6);
+        // Should be limited from previous luminance (mid-gray, 0.214 linear)
+        assert!(LuminanceLimiter::calculate_luminance(limited) <= 0.214 + LUMINANCE_CHANGE_LIMIT + 0.001);
+    }
+
+    #[test]
+    fn test_luminance_limiting_in_linear_light() {
+        let mut limiter = LuminanceLimiter::new();
+
+        // Mid-gray sRGB matches the starting luminance, so it passes untouched
+        let gray = Vector3::new(0.5, 0.5, 0.5);
+        assert_eq!(limiter.limit_luminance_change(gray), gray);
+
+        // A jump to white is capped at +10% linear luminance, and the result stays sRGB-encoded
+        let limited = limiter.limit_luminance_change(Vector3::new(1.0, 1.0, 1.0));
+        let luminance = LuminanceLimiter::calculate_luminance(limited);
+        assert!((luminance - (0.214 + LUMINANCE_CHANGE_LIMIT)).abs() < 0.002);
+        assert!((limited.x - linear_to_srgb(0.314)).abs() < 0.002);
+        assert!(limited.x > 0.314); // Encoded value, not the linear one
     }
 
     #[test]
@@ -508,6 +588,35 @@ mod tests {
         assert!(safe_color.x < 0.2); // Should be very dim in emergency
     }
 
+    #[test]
+    fn test_emergency_stop_is_manual_by_default() {
+        let clock = MockClock::new();
+        let mut engine = SafetyEngine::with_clock(clock.shared());
+
+        engine.emergency_stop();
+        clock.advance_secs(3600.0);
+        assert!(!engine.update());
+        assert!(engine.is_emergency_stopped());
+    }
+
+    #[test]
+    fn test_auto_resume_after_interval() {
+        let clock = MockClock::new();
+        let mut engine = SafetyEngine::with_clock(clock.shared());
+        engine.set_safety_level(SafetyLevel::Standard);
+        engine.set_auto_resume(Some(Duration::from_secs(5)));
+
+        engine.emergency_stop();
+        clock.advance_secs(4.9);
+        assert!(!engine.update());
+        assert!(engine.is_emergency_stopped());
+
+        clock.advance_secs(0.2);
+        assert!(engine.update());
+        assert!(!engine.is_emergency_stopped());
+        assert_eq!(engine.get_safety_level(), SafetyLevel::UltraSafe);
+    }
+
     // ===== NEW COMPREHENSIVE SAFETY PIPELINE TESTS =====
 
     #[test]
@@ -689,6 +798,23 @@ mod tests {
         assert!((blue_luminance - 0.0722).abs() < 0.001);
     }
 
+    #[test]
+    fn test_luminance_on_linearized_values() {
+        // sRGB mid-gray (0.5) is ~21.4% linear light, not 50%
+        let gray = Vector3::new(0.5, 0.5, 0.5);
+        assert!((LuminanceLimiter::calculate_luminance(gray) - 0.2140).abs() < 0.001);
+        assert!((LuminanceLimiter::calculate_luminance_linear(gray) - 0.5).abs() < 0.001);
+
+        let linear = Vector3::new(srgb_to_linear(0.5), srgb_to_linear(0.25), srgb_to_linear(0.1));
+        assert!((linear.x - 0.2140).abs() < 0.001);
+        assert!((linear.y - 0.0509).abs() < 0.001);
+        assert!((linear.z - 0.0100).abs() < 0.001);
+
+        let expected = 0.2126 * 0.2140 + 0.7152 * 0.0509 + 0.0722 * 0.0100;
+        assert!((LuminanceLimiter::calculate_luminance_linear(linear) - expected).abs() < 0.001);
+        assert!((LuminanceLimiter::calculate_luminance(Vector3::new(0.5, 0.25, 0.1)) - expected).abs() < 0.001);
+    }
+
     #[test]
     fn test_safety_integration_with_different_audio_intensities() {
         let mut engine = SafetyEngine::new();
@@ -711,21 +837,4 @@ mod tests {
             assert!(!engine.can_allow_effect(high_intensity, Vector3::new(0.5, 0.5, 0.5)));
         }
     }
-
-    #[test]
-    fn test_vector3_operations() {
-        // Test Vector3 math operations used in safety calculations
-        let vec = Vector3::new(0.5, 0.3, 0.2);
-        let scalar = 0.8;
-
-        let result = vec * scalar;
-        assert!((result.x - 0.4).abs() < 0.001);
-        assert!((result.y - 0.24).abs() < 0.001);
-        assert!((result.z - 0.16).abs() < 0.001);
-
-        let result2 = vec.mul_scalar(scalar);
-        assert_eq!(result.x, result2.x);
-        assert_eq!(result.y, result2.y);
-        assert_eq!(result.z, result2.z);
-    }
-}
\ No newline at end of file
+}
@@ -1,10 +1,21 @@
 use std::collections::HashMap;
 
+/// Time step per `smooth` call; the mapper runs once per frame at 60 FPS
+const FRAME_DT: f32 = 1.0 / 60.0;
+
+/// Largest angular frequency times time step per spring integration substep, well inside
+/// the semi-implicit Euler stability limit of 2
+const MAX_SPRING_STEP: f32 = 0.25;
+
+/// Stiffest spring accepted (angular frequency 100 rad/s, about 16 Hz)
+const MAX_SPRING_STIFFNESS: f32 = 10_000.0;
+
 #[derive(Debug, Clone)]
 pub enum SmoothingType {
     Linear(f32),      // factor: 0.0 = no smoothing, 1.0 = instant change
     Exponential(f32), // decay: higher = faster response
     Adaptive { min_factor: f32, max_factor: f32, sensitivity: f32 },
+    Spring { stiffness: f32, damping: f32 }, // stiffness: pull per unit distance; damping: ratio, 1.0 = critical
 }
 
 impl SmoothingType {
@@ -23,12 +34,23 @@ impl SmoothingType {
             sensitivity: sensitivity.max(0.1),
         }
     }
+
+    /// Damped spring pulled toward each new value. Damping at or above 1.0 settles without
+    /// overshoot; below it the value swings past the target and rings down.
+    pub fn spring(stiffness: f32, damping: f32) -> Self {
+        Self::Spring {
+            stiffness: stiffness.clamp(1.0, MAX_SPRING_STIFFNESS),
+            damping: damping.max(0.0),
+        }
+    }
 }
 
 pub struct Smoother {
     smoothing_configs: HashMap<String, SmoothingType>,
     previous_values: HashMap<String, f32>,
     change_rates: HashMap<String, f32>,
+    velocities: HashMap<String, f32>,   // Spring state, per parameter
+    ranges: HashMap<String, (f32, f32)>, // Valid output range, per parameter
 }
 
 impl Smoother {
@@ -37,6 +59,8 @@ impl Smoother {
             smoothing_configs: HashMap::new(),
             previous_values: HashMap::new(),
             change_rates: HashMap::new(),
+            velocities: HashMap::new(),
+            ranges: HashMap::new(),
         }
     }
 
@@ -44,6 +68,11 @@ impl Smoother {
         self.smoothing_configs.insert(param_name.to_string(), smoothing_type);
     }
 
+    /// Keep a parameter's smoothed output within `min..=max` (springs overshoot their target)
+    pub fn configure_range(&mut self, param_name: &str, min: f32, max: f32) {
+        self.ranges.insert(param_name.to_string(), (min.min(max), min.max(max)));
+    }
+
     pub fn configure_multiple(&mut self, configs: &[(&str, SmoothingType)]) {
         for (name, smoothing_type) in configs {
             self.configure(name, smoothing_type.clone());
@@ -52,13 +81,25 @@ impl Smoother {
 
     pub fn smooth(&mut self, param_name: &str, new_value: f32) -> f32 {
         let previous = self.previous_values.get(param_name).copied().unwrap_or(new_value);
+        let mut velocity = self.velocities.get(param_name).copied().unwrap_or(0.0);
 
-        let smoothed_value = if let Some(smoothing_type) = self.smoothing_configs.get(param_name) {
-            self.apply_smoothing(smoothing_type, previous, new_value, param_name)
+        let mut smoothed_value = if let Some(smoothing_type) = self.smoothing_configs.get(param_name) {
+            self.apply_smoothing(smoothing_type, previous, new_value, param_name, &mut velocity)
         } else {
             new_value
         };
 
+        if let Some(&(min, max)) = self.ranges.get(param_name) {
+            // Stop at the bound rather than pressing against it on later frames
+            if smoothed_value < min || smoothed_value > max {
+                smoothed_value = smoothed_value.clamp(min, max);
+                velocity = 0.0;
+            }
+        }
+        if matches!(self.smoothing_configs.get(param_name), Some(SmoothingType::Spring { .. })) {
+            self.velocities.insert(param_name.to_string(), velocity);
+        }
+
         let change_rate = (new_value - previous).abs();
         self.change_rates.insert(param_name.to_string(), change_rate);
         self.previous_values.insert(param_name.to_string(), smoothed_value);
@@ -66,14 +107,13 @@ impl Smoother {
         smoothed_value
     }
 
-    fn apply_smoothing(&self, smoothing_type: &SmoothingType, previous: f32, new_value: f32, param_name: &str) -> f32 {
+    fn apply_smoothing(&self, smoothing_type: &SmoothingType, previous: f32, new_value: f32, param_name: &str, velocity: &mut f32) -> f32 {
         match smoothing_type {
             SmoothingType::Linear(factor) => {
                 lerp(previous, new_value, *factor)
             }
             SmoothingType::Exponential(decay) => {
-                let dt = 1.0 / 60.0;
-                let alpha = 1.0 - (-decay * dt).exp();
+                let alpha = 1.0 - (-decay * FRAME_DT).exp();
                 lerp(previous, new_value, alpha)
             }
             SmoothingType::Adaptive { min_factor, max_factor, sensitivity } => {
@@ -82,6 +122,9 @@ impl Smoother {
                 let adaptive_factor = lerp(*min_factor, *max_factor, normalized_change);
                 lerp(previous, new_value, adaptive_factor)
             }
+            SmoothingType::Spring { stiffness, damping } => {
+                spring_step(previous, velocity, new_value, *stiffness, *damping, FRAME_DT)
+            }
         }
     }
 
@@ -98,11 +141,13 @@ impl Smoother {
     pub fn reset(&mut self, param_name: &str) {
         self.previous_values.remove(param_name);
         self.change_rates.remove(param_name);
+        self.velocities.remove(param_name);
     }
 
     pub fn reset_all(&mut self) {
         self.previous_values.clear();
         self.change_rates.clear();
+        self.velocities.clear();
     }
 }
 
@@ -110,6 +155,22 @@ fn lerp(a: f32, b: f32, t: f32) -> f32 {
     a + (b - a) * t
 }
 
+/// Advance a damped spring from `position` toward `target` over `dt` seconds with
+/// semi-implicit Euler, substepping so stiff springs stay stable at any frame rate
+fn spring_step(position: f32, velocity: &mut f32, target: f32, stiffness: f32, damping: f32, dt: f32) -> f32 {
+    let omega = stiffness.sqrt();
+    let substeps = (omega * dt / MAX_SPRING_STEP).ceil().max(1.0) as u32;
+    let step = dt / substeps as f32;
+
+    let mut position = position;
+    for _ in 0..substeps {
+        let acceleration = stiffness * (target - position) - 2.0 * damping * omega * *velocity;
+        *velocity += acceleration * step;
+        position += *velocity * step;
+    }
+    position
+}
+
 pub trait Smoothable {
     fn apply_smoothing(&mut self, smoother: &mut Smoother);
 }
@@ -157,6 +218,55 @@ mod tests {
         assert!(large_change != small_change);
     }
 
+    /// Smooth a 0 -> 1 step for two seconds of frames, returning every output
+    fn spring_step_response(damping: f32) -> Vec<f32> {
+        let mut smoother = Smoother::new();
+        smoother.configure("bass", SmoothingType::spring(400.0, damping));
+        smoother.smooth("bass", 0.0);
+        (0..120).map(|_| smoother.smooth("bass", 1.0)).collect()
+    }
+
+    #[test]
+    fn test_overdamped_spring_converges_without_overshoot() {
+        let values = spring_step_response(1.5);
+
+        assert!(values.iter().all(|&v| v <= 1.0), "Heavily damped spring overshot");
+        assert!(values.windows(2).all(|pair| pair[1] >= pair[0]), "Heavily damped spring should rise monotonically");
+        assert_abs_diff_eq!(*values.last().unwrap(), 1.0, epsilon = 0.001);
+    }
+
+    #[test]
+    fn test_underdamped_spring_overshoots_once_then_settles() {
+        let values = spring_step_response(0.6);
+
+        // Count separate excursions noticeably past the target
+        let mut overshoots = 0;
+        let mut above = false;
+        for &v in &values {
+            if v > 1.01 && !above {
+                overshoots += 1;
+            }
+            above = v > 1.01;
+        }
+        assert_eq!(overshoots, 1);
+        assert!(values.iter().cloned().fold(0.0, f32::max) > 1.05, "Lightly damped spring should swing past the target");
+        assert_abs_diff_eq!(*values.last().unwrap(), 1.0, epsilon = 0.001);
+    }
+
+    #[test]
+    fn test_stiff_spring_is_stable_and_range_clamped() {
+        let mut smoother = Smoother::new();
+        smoother.configure("bass", SmoothingType::spring(1.0e6, 0.3)); // Clamped to the stiffest spring
+        smoother.configure_range("bass", 0.0, 1.0);
+        smoother.smooth("bass", 0.0);
+
+        for frame in 0..240 {
+            let target = if (frame / 30) % 2 == 0 { 1.0 } else { 0.0 };
+            let value = smoother.smooth("bass", target);
+            assert!(value.is_finite() && (0.0..=1.0).contains(&value), "frame {}: {}", frame, value);
+        }
+    }
+
     #[test]
     fn test_multiple_smoothing() {
         let mut smoother = Smoother::new();
@@ -1,9 +1,33 @@
 use anyhow::Result;
 use winit::event::{ElementState, KeyEvent};
-use winit::keyboard::{KeyCode, PhysicalKey};
-
-use crate::rendering::{EnhancedFrameComposer, ShaderType, QualityLevel};
-use crate::control::{SafetyEngine, SafetyLevel, EpilepsyWarning};
+use winit::keyboard::{KeyCode, ModifiersState, PhysicalKey};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::rendering::{EffectWeights, EnhancedFrameComposer, ShaderType, QualityLevel};
+use crate::session_state::SessionState;
+use crate::control::{
+    slot_name, Action, ColorPalette, KeyBindings, Preset, PresetBank, SafetyEngine, SafetyLevel, EpilepsyWarning,
+    OscCommand,
+};
+
+/// Default dedicated emergency-stop key, alongside ESC
+pub const DEFAULT_EMERGENCY_STOP_KEY: KeyCode = KeyCode::Pause;
+/// Default exit key, deliberately far from ESC so stopping never quits
+pub const DEFAULT_EXIT_KEY: KeyCode = KeyCode::F10;
+/// How far one raise/lower key press moves the selected effect weight
+pub const EFFECT_WEIGHT_STEP: f32 = 0.05;
+
+/// Seconds skipped by the seek forward/backward keys
+pub const SEEK_STEP_SECS: f32 = 5.0;
+
+/// Loop point change requested from the keyboard, applied to playback by the visualizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopEdit {
+    In,    // Loop start at the playback position
+    Out,   // Loop end at the playback position
+    Clear,
+}
 
 /// User interface controls for real-time interaction
 pub struct UserInterface {
@@ -27,12 +51,30 @@ pub struct UserInterface {
     current_safety_level: SafetyLevel,
     /// Show safety status in overlay
     pub show_safety_status: bool,
-    /// ESC key press tracking for double-press exit
-    esc_press_count: u32,
-    /// Time of last ESC press for double-press detection
-    last_esc_time: std::time::Instant,
+    /// Dedicated emergency-stop key (ESC always stops as well)
+    emergency_stop_key: KeyCode,
+    /// Key that exits the application (None = close the window to exit)
+    exit_key: Option<KeyCode>,
     /// Flag to signal application should exit
     should_exit: bool,
+    /// Session recording start/stop requested (F9), consumed by the visualizer
+    session_toggle_requested: bool,
+    /// Fullscreen toggle requested (F or Alt+Enter), consumed by the visualizer
+    fullscreen_toggle_requested: bool,
+    /// Relative seek in seconds requested by the arrow keys, consumed by the visualizer
+    seek_requested: Option<f32>,
+    /// Loop in/out/clear requested (I, O, L), consumed by the visualizer
+    loop_edit_requested: Option<LoopEdit>,
+    /// Modifier keys currently held, for chorded shortcuts
+    modifiers: ModifiersState,
+    /// Key-to-action map (safety keys are handled before it)
+    key_bindings: KeyBindings,
+    /// Effect weight adjusted by the raise/lower keys (index into `EffectWeights::NAMES`)
+    selected_effect_weight: usize,
+    /// Saved presets, recalled by slot
+    preset_bank: PresetBank,
+    /// File the preset bank is written to on every save (None = keep presets in memory)
+    preset_path: Option<PathBuf>,
 }
 
 impl UserInterface {
@@ -51,15 +93,27 @@ impl UserInterface {
                 ShaderType::Particle,
                 ShaderType::Fractal,
                 ShaderType::Spectralizer,
+                ShaderType::Oscilloscope,
+                ShaderType::Spectrogram,
+                ShaderType::Mixed,
             ],
             show_help: false,
             safety_engine: SafetyEngine::new(),
             epilepsy_warning: EpilepsyWarning::new(),
             current_safety_level: SafetyLevel::Safe, // Default to safe
             show_safety_status: true, // Show safety status by default
-            esc_press_count: 0,
-            last_esc_time: std::time::Instant::now(),
+            emergency_stop_key: DEFAULT_EMERGENCY_STOP_KEY,
+            exit_key: Some(DEFAULT_EXIT_KEY),
             should_exit: false,
+            session_toggle_requested: false,
+            fullscreen_toggle_requested: false,
+            seek_requested: None,
+            loop_edit_requested: None,
+            modifiers: ModifiersState::empty(),
+            key_bindings: KeyBindings::default(),
+            selected_effect_weight: 0,
+            preset_bank: PresetBank::new(),
+            preset_path: None,
         }
     }
 
@@ -77,152 +131,159 @@ impl UserInterface {
         let mut handled = false;
 
         if let PhysicalKey::Code(keycode) = &event.physical_key {
-            match keycode {
-                // Shader selection (1-8 keys)
-                KeyCode::Digit1 => {
-                    self.set_shader(ShaderType::Classic, composer, context)?;
-                    handled = true;
-                }
-                KeyCode::Digit2 => {
-                    self.set_shader(ShaderType::ParametricWave, composer, context)?;
-                    handled = true;
-                }
-                KeyCode::Digit3 => {
-                    self.set_shader(ShaderType::Plasma, composer, context)?;
-                    handled = true;
-                }
-                KeyCode::Digit4 => {
-                    self.set_shader(ShaderType::Kaleidoscope, composer, context)?;
-                    handled = true;
-                }
-                KeyCode::Digit5 => {
-                    self.set_shader(ShaderType::Tunnel, composer, context)?;
-                    handled = true;
-                }
-                KeyCode::Digit6 => {
-                    self.set_shader(ShaderType::Particle, composer, context)?;
-                    handled = true;
-                }
-                KeyCode::Digit7 => {
-                    self.set_shader(ShaderType::Fractal, composer, context)?;
-                    handled = true;
-                }
-                KeyCode::Digit8 => {
-                    self.set_shader(ShaderType::Spectralizer, composer, context)?;
-                    handled = true;
-                }
+            // Safety keys take precedence over every other binding
+            if self.dispatch_safety_key(*keycode) {
+                return Ok(true);
+            }
 
-                // Shader cycling
-                KeyCode::Space => {
-                    self.cycle_next_shader(composer, context)?;
-                    handled = true;
-                }
-                KeyCode::Tab => {
-                    self.cycle_previous_shader(composer, context)?;
-                    handled = true;
-                }
+            // Alt+Enter is the conventional fullscreen chord, whatever Enter is bound to
+            if self.is_fullscreen_chord(*keycode) {
+                self.fullscreen_toggle_requested = true;
+                return Ok(true);
+            }
 
-                // Auto shader mode toggle
-                KeyCode::KeyA => {
-                    self.toggle_auto_shader();
-                    handled = true;
-                }
+            if let Some(action) = self.key_bindings.action_for(*keycode) {
+                let action = self.apply_modifiers(action);
+                self.perform_action(action, composer, context)?;
+                handled = true;
+            }
+        }
 
-                // Quality level controls
-                KeyCode::KeyQ => {
-                    self.set_quality_override(Some(QualityLevel::Potato), composer);
-                    handled = true;
-                }
-                KeyCode::KeyW => {
-                    self.set_quality_override(Some(QualityLevel::Low), composer);
-                    handled = true;
-                }
-                KeyCode::KeyE => {
-                    self.set_quality_override(Some(QualityLevel::Medium), composer);
-                    handled = true;
-                }
-                KeyCode::KeyR => {
-                    self.set_quality_override(Some(QualityLevel::High), composer);
-                    handled = true;
-                }
-                KeyCode::KeyT => {
-                    self.set_quality_override(Some(QualityLevel::Ultra), composer);
-                    handled = true;
-                }
-                KeyCode::KeyY => {
-                    self.set_quality_override(None, composer); // Auto quality
-                    handled = true;
-                }
+        Ok(handled)
+    }
 
-                // Performance overlay toggle
-                KeyCode::KeyP => {
-                    self.toggle_performance_overlay();
-                    handled = true;
+    /// Carry out a bound action
+    fn perform_action(
+        &mut self,
+        action: Action,
+        composer: &mut EnhancedFrameComposer,
+        context: &crate::rendering::WgpuContext,
+    ) -> Result<()> {
+        match action {
+            Action::SetShader(shader_type) => self.set_shader(shader_type, composer, context)?,
+            Action::CycleNext => self.cycle_next_shader(composer, context)?,
+            Action::CyclePrevious => self.cycle_previous_shader(composer, context)?,
+            Action::ToggleAuto => self.toggle_auto_shader(),
+            Action::SetQuality(quality) => self.set_quality_override(quality, composer),
+            Action::TogglePerformanceOverlay => self.toggle_performance_overlay(),
+            Action::ToggleHelp => self.toggle_help(),
+            Action::CycleSafetyLevel => self.cycle_safety_level(),
+            Action::ToggleSafetyStatus => self.toggle_safety_status(),
+            Action::ToggleDebugOverlay => composer.toggle_debug_overlay(),
+            Action::ToggleControlPanel => composer.toggle_control_panel(),
+            Action::ToggleBeatGrid => composer.toggle_beat_grid(),
+            Action::ToggleTrails => composer.toggle_trails(),
+            Action::ToggleFullscreen => self.fullscreen_toggle_requested = true,
+            Action::ToggleSessionRecording => self.session_toggle_requested = true,
+            Action::Screenshot => {
+                let path = Self::screenshot_file_name();
+                match composer.capture_screenshot(context, std::path::Path::new(&path)) {
+                    Ok(()) => println!("📸 Capturing screenshot to {}", path),
+                    Err(e) => eprintln!("Screenshot unavailable: {}", e),
                 }
+            }
+            Action::CycleEffectWeight => self.cycle_effect_weight(),
+            Action::RaiseEffectWeight => self.nudge_effect_weight(EFFECT_WEIGHT_STEP, composer),
+            Action::LowerEffectWeight => self.nudge_effect_weight(-EFFECT_WEIGHT_STEP, composer),
+            Action::SavePreset(slot) => self.save_preset(slot, composer),
+            Action::RecallPreset(slot) => self.recall_preset(slot, composer, context)?,
+            Action::SeekForward => self.request_seek(SEEK_STEP_SECS),
+            Action::SeekBackward => self.request_seek(-SEEK_STEP_SECS),
+            Action::SetLoopIn => self.loop_edit_requested = Some(LoopEdit::In),
+            Action::SetLoopOut => self.loop_edit_requested = Some(LoopEdit::Out),
+            Action::ClearLoop => self.loop_edit_requested = Some(LoopEdit::Clear),
+            Action::EmergencyStop => self.emergency_stop(),
+            Action::Resume => self.resume_from_emergency(),
+        }
+        Ok(())
+    }
 
-                // Help display toggle
-                KeyCode::KeyH | KeyCode::F1 => {
-                    self.toggle_help();
-                    handled = true;
-                }
+    /// Shift turns a preset recall key into a save to the same slot
+    fn apply_modifiers(&self, action: Action) -> Action {
+        match action {
+            Action::RecallPreset(slot) if self.modifiers.shift_key() => Action::SavePreset(slot),
+            action => action,
+        }
+    }
 
-                // Emergency stop (ESC key) - Critical safety feature with double-press exit
-                KeyCode::Escape => {
-                    let now = std::time::Instant::now();
-                    let time_since_last_esc = now.duration_since(self.last_esc_time).as_secs_f32();
-
-                    if time_since_last_esc <= 2.0 {
-                        // Second ESC within 2 seconds - signal exit
-                        self.esc_press_count += 1;
-                        if self.esc_press_count >= 2 {
-                            self.should_exit = true;
-                            println!("🚪 Exiting Aruu Audio Visualizer...");
-                        }
-                    } else {
-                        // First ESC or too much time passed - reset and do emergency stop
-                        self.esc_press_count = 1;
-                        self.emergency_stop();
-                    }
-
-                    self.last_esc_time = now;
-                    handled = true;
-                }
+    /// Replace the key-to-action map (ESC, the emergency-stop key, X and the exit key always keep their roles)
+    pub fn set_key_bindings(&mut self, bindings: KeyBindings) {
+        self.key_bindings = bindings;
+    }
 
-                // Safety level controls
-                KeyCode::KeyS => {
-                    self.cycle_safety_level();
-                    handled = true;
-                }
+    pub fn key_bindings(&self) -> &KeyBindings {
+        &self.key_bindings
+    }
 
-                // Safety status toggle
-                KeyCode::KeyZ => {
-                    self.toggle_safety_status();
-                    handled = true;
-                }
+    /// Track held modifier keys (from `WindowEvent::ModifiersChanged`)
+    pub fn set_modifiers(&mut self, modifiers: ModifiersState) {
+        self.modifiers = modifiers;
+    }
 
-                // Resume from emergency stop
-                KeyCode::KeyX => {
-                    self.resume_from_emergency();
-                    handled = true;
-                }
+    /// Whether this key completes Alt+Enter with the current modifiers
+    pub fn is_fullscreen_chord(&self, keycode: KeyCode) -> bool {
+        self.modifiers.alt_key() && matches!(keycode, KeyCode::Enter | KeyCode::NumpadEnter)
+    }
 
-                // Toggle debug overlay (D key)
-                KeyCode::KeyD => {
-                    composer.toggle_debug_overlay();
-                    handled = true;
-                }
+    /// Handle emergency stop, resume and exit keys; returns true if the key was consumed
+    ///
+    /// Emergency stop and exit are separate keys so a user trying to stop flashing
+    /// can never quit by accident.
+    pub fn dispatch_safety_key(&mut self, keycode: KeyCode) -> bool {
+        if keycode == KeyCode::Escape || keycode == self.emergency_stop_key {
+            self.emergency_stop();
+            return true;
+        }
 
-                // Toggle control panel (C key)
-                KeyCode::KeyC => {
-                    composer.toggle_control_panel();
-                    handled = true;
-                }
+        if keycode == KeyCode::KeyX {
+            self.resume_from_emergency();
+            return true;
+        }
 
-                _ => {}
-            }
+        if Some(keycode) == self.exit_key {
+            self.should_exit = true;
+            println!("🚪 Exiting Aruu Audio Visualizer...");
+            return true;
         }
 
-        Ok(handled)
+        false
+    }
+
+    /// Choose the dedicated emergency-stop key (ESC keeps working too)
+    pub fn set_emergency_stop_key(&mut self, keycode: KeyCode) {
+        self.emergency_stop_key = keycode;
+    }
+
+    pub fn emergency_stop_key(&self) -> KeyCode {
+        self.emergency_stop_key
+    }
+
+    /// Choose the exit key, or `None` to only exit by closing the window
+    pub fn set_exit_key(&mut self, keycode: Option<KeyCode>) {
+        self.exit_key = keycode;
+    }
+
+    pub fn exit_key(&self) -> Option<KeyCode> {
+        self.exit_key
+    }
+
+    /// Apply a command from a remote controller, as if the matching key had been pressed
+    pub fn apply_remote_command(
+        &mut self,
+        command: OscCommand,
+        composer: &mut EnhancedFrameComposer,
+        context: &crate::rendering::WgpuContext,
+    ) -> Result<()> {
+        match command {
+            OscCommand::SetShader(shader_type) => self.set_shader(shader_type, composer, context)?,
+            OscCommand::SetSafetyLevel(level) => {
+                self.set_safety_level(level);
+                println!("🛡️  Safety Level: {:?} (remote)", level);
+            }
+            OscCommand::SetQuality(quality) => self.set_quality_override(quality, composer),
+            OscCommand::EmergencyStop => self.emergency_stop(),
+        }
+        Ok(())
     }
 
     /// Set specific shader and disable auto mode
@@ -236,9 +297,7 @@ impl UserInterface {
         composer.set_shader_immediately(shader_type, context)?;
 
         // Update cycle index to match current shader
-        if let Some(index) = self.available_shaders.iter().position(|&s| s == shader_type) {
-            self.shader_cycle_index = index;
-        }
+        self.sync_shader_index(shader_type);
 
         println!("🎨 Manual shader: {} (auto mode disabled)", shader_type.name());
         Ok(())
@@ -288,9 +347,9 @@ impl UserInterface {
     /// Set quality level override
     fn set_quality_override(&mut self, quality: Option<QualityLevel>, composer: &mut EnhancedFrameComposer) {
         self.quality_override = quality;
+        composer.set_quality_override(quality);
 
         if let Some(q) = quality {
-            composer.set_quality(q);
             println!("🔧 Quality override: {:?}", q);
         } else {
             println!("🔧 Quality override: Auto");
@@ -319,7 +378,7 @@ impl UserInterface {
         println!("\n🎵 ARUU - Audio Visualizer Controls 🎵");
         println!("========================================");
         println!("SHADER SELECTION:");
-        println!("  1-8     Direct shader selection");
+        println!("  1-9, 0  Direct shader selection");
         println!("  Space   Next shader");
         println!("  Tab     Previous shader");
         println!("  A       Toggle auto shader mode");
@@ -333,14 +392,35 @@ impl UserInterface {
         println!("  Y       Auto quality");
         println!();
         println!("🛡️  SAFETY CONTROLS:");
-        println!("  ESC     Emergency stop (critical safety)");
+        println!("  ESC     Emergency stop (critical safety, never exits)");
+        println!("  {:<7} Emergency stop", format!("{:?}", self.emergency_stop_key));
         println!("  S       Cycle safety level");
         println!("  X       Resume from emergency stop");
         println!("  Z       Toggle safety status display");
         println!();
+        if self.key_bindings != KeyBindings::default() {
+            println!("  (Custom key bindings loaded; shader and display keys may differ)");
+            println!();
+        }
+        println!("PLAYBACK:");
+        println!("  ←/→     Rewind/skip {} seconds", SEEK_STEP_SECS);
+        println!("  I / O   Set loop start / end at the current position");
+        println!("  L       Stop looping");
+        println!();
         println!("DISPLAY:");
         println!("  P       Toggle performance overlay");
+        println!("  M       Toggle motion trails");
+        println!("  G       Toggle beat grid overlay");
+        println!("  F       Toggle fullscreen (also Alt+Enter)");
+        println!("  F5-F8   Recall preset 1-4 (Shift+key saves)");
+        println!("  \\       Select effect weight for the Mixed shader");
+        println!("  [ / ]   Lower/raise the selected effect weight");
         println!("  H/F1    Toggle this help");
+        println!("  F9      Start/stop session recording (for bug reports)");
+        println!("  F12     Save a PNG screenshot");
+        if let Some(exit_key) = self.exit_key {
+            println!("  {:<7} Exit application", format!("{:?}", exit_key));
+        }
         println!();
         println!("SHADERS:");
         println!("  1. Classic      - Original wave patterns");
@@ -351,6 +431,9 @@ impl UserInterface {
         println!("  6. Particle     - Dynamic particle systems");
         println!("  7. Fractal      - Mandelbrot/Julia sets");
         println!("  8. Spectralizer - Direct frequency visualization");
+        println!("  9. Oscilloscope - Raw waveform trace");
+        println!("  0. Spectrogram  - Scrolling frequency history");
+        println!("     Mixed        - Weighted blend of six effects (Space/Tab)");
         println!();
         println!("🛡️  SAFETY LEVELS:");
         println!("  🛡️ Ultra Safe   - Maximum epilepsy protection");
@@ -411,7 +494,6 @@ impl UserInterface {
         self.safety_engine.emergency_stop();
         println!("⛔ EMERGENCY STOP ACTIVATED - All visual effects halted");
         println!("   Press X to resume or adjust safety levels");
-        println!("   ESC again to exit application");
     }
 
     /// Resume from emergency stop
@@ -423,6 +505,18 @@ impl UserInterface {
         }
     }
 
+    /// Opt-in auto-resume for unattended installations (None = resume with X only)
+    pub fn set_auto_resume(&mut self, interval: Option<Duration>) {
+        self.safety_engine.set_auto_resume(interval);
+    }
+
+    /// Per-frame safety housekeeping (auto-resume drops back to UltraSafe)
+    pub fn update_safety(&mut self) {
+        if self.safety_engine.update() {
+            self.current_safety_level = SafetyLevel::UltraSafe;
+        }
+    }
+
     /// Cycle through safety levels
     pub fn cycle_safety_level(&mut self) {
         self.current_safety_level = match self.current_safety_level {
@@ -446,6 +540,19 @@ impl UserInterface {
         println!("🛡️  Safety Level: {}", level_description);
     }
 
+    /// Set a specific safety level
+    pub fn set_safety_level(&mut self, level: SafetyLevel) {
+        self.current_safety_level = level;
+        self.safety_engine.set_safety_level(level);
+    }
+
+    /// Align the shader cycling position with a shader chosen elsewhere
+    pub fn sync_shader_index(&mut self, shader_type: ShaderType) {
+        if let Some(index) = self.available_shaders.iter().position(|&s| s == shader_type) {
+            self.shader_cycle_index = index;
+        }
+    }
+
     /// Toggle safety status display
     pub fn toggle_safety_status(&mut self) {
         self.show_safety_status = !self.show_safety_status;
@@ -473,7 +580,157 @@ impl UserInterface {
         self.safety_engine.is_emergency_stopped()
     }
 
-    /// Check if application should exit (double ESC press detected)
+    /// Consume a pending session recording toggle
+    pub fn take_session_toggle(&mut self) -> bool {
+        std::mem::take(&mut self.session_toggle_requested)
+    }
+
+    /// Select the next effect weight for the raise/lower keys
+    pub fn cycle_effect_weight(&mut self) {
+        self.selected_effect_weight = (self.selected_effect_weight + 1) % EffectWeights::COUNT;
+        println!("🎚️  Adjusting {} weight", EffectWeights::NAMES[self.selected_effect_weight]);
+    }
+
+    pub fn selected_effect_weight(&self) -> usize {
+        self.selected_effect_weight
+    }
+
+    /// Move the selected effect weight; the others rescale so the mix still sums to 1.0
+    fn nudge_effect_weight(&mut self, delta: f32, composer: &mut EnhancedFrameComposer) {
+        let weights = composer.effect_weights().nudged(self.selected_effect_weight, delta);
+        composer.set_effect_weights(weights);
+
+        let percent = weights.to_array()[self.selected_effect_weight] * 100.0;
+        println!("🎚️  {} weight: {:.0}%", EffectWeights::NAMES[self.selected_effect_weight], percent);
+    }
+
+    /// Load presets from a file (missing is fine) and write future saves back to it
+    pub fn set_preset_file(&mut self, path: PathBuf) -> Result<()> {
+        self.preset_bank = PresetBank::load(&path)?;
+        self.preset_path = Some(path);
+        Ok(())
+    }
+
+    pub fn preset_bank(&self) -> &PresetBank {
+        &self.preset_bank
+    }
+
+    /// Store a preset in a slot, writing the bank to disk if a preset file is set
+    pub fn store_preset(&mut self, slot: u8, preset: Preset) -> Result<()> {
+        self.preset_bank.insert(slot_name(slot), preset);
+        match &self.preset_path {
+            Some(path) => self.preset_bank.save(path),
+            None => Ok(()),
+        }
+    }
+
+    /// Capture the current look into a preset slot
+    fn save_preset(&mut self, slot: u8, composer: &EnhancedFrameComposer) {
+        // Custom stops and the key hue aren't stored, so those palettes save as "follow the shader"
+        let palette = Some(composer.current_palette()).filter(|&palette| {
+            composer.is_palette_locked() && !matches!(palette, ColorPalette::Custom | ColorPalette::KeySynced)
+        });
+        let preset = Preset {
+            shader: composer.current_shader(),
+            palette,
+            safety_level: self.current_safety_level,
+            effect_weights: composer.effect_weights(),
+        };
+
+        match self.store_preset(slot, preset) {
+            Ok(()) => println!("💾 Saved preset {}", slot),
+            Err(e) => eprintln!("Failed to save preset {}: {}", slot, e),
+        }
+    }
+
+    /// Apply a saved preset slot (disables auto shader mode)
+    fn recall_preset(
+        &mut self,
+        slot: u8,
+        composer: &mut EnhancedFrameComposer,
+        context: &crate::rendering::WgpuContext,
+    ) -> Result<()> {
+        let Some(preset) = self.preset_bank.get(&slot_name(slot)).cloned() else {
+            println!("💾 Preset {} is empty (Shift+key saves it)", slot);
+            return Ok(());
+        };
+
+        self.set_shader(preset.shader, composer, context)?;
+        // Presets never switch protection off, matching `cycle_safety_level`
+        if preset.safety_level != SafetyLevel::Disabled {
+            self.set_safety_level(preset.safety_level);
+        }
+        composer.set_effect_weights(preset.effect_weights);
+        match preset.palette {
+            Some(palette) => composer.lock_palette(palette),
+            None => composer.unlock_palette(),
+        }
+
+        println!("💾 Recalled preset {}", slot);
+        Ok(())
+    }
+
+    /// Capture the tuned visual setup for a checkpoint; the caller fills in the volume
+    pub fn session_state(&self, composer: &EnhancedFrameComposer) -> SessionState {
+        SessionState {
+            shader: composer.current_shader(),
+            effect_weights: composer.effect_weights(),
+            palette_position: composer.palette_position(),
+            safety_level: self.current_safety_level,
+            quality_override: self.quality_override,
+            ..SessionState::default()
+        }
+    }
+
+    /// Restore a checkpointed setup, like recalling a preset
+    pub fn restore_session_state(
+        &mut self,
+        state: &SessionState,
+        composer: &mut EnhancedFrameComposer,
+        context: &crate::rendering::WgpuContext,
+    ) -> Result<()> {
+        self.set_shader(state.shader, composer, context)?;
+        // A resumed session never switches protection off either
+        if state.safety_level != SafetyLevel::Disabled {
+            self.set_safety_level(state.safety_level);
+        }
+        composer.set_effect_weights(state.effect_weights);
+        if let Some(position) = state.palette_position {
+            composer.set_palette_position(position);
+        }
+        self.set_quality_override(state.quality_override, composer);
+        Ok(())
+    }
+
+    /// Consume a pending fullscreen toggle
+    pub fn take_fullscreen_toggle(&mut self) -> bool {
+        std::mem::take(&mut self.fullscreen_toggle_requested)
+    }
+
+    /// Queue a relative seek; presses before the visualizer catches up add together
+    pub fn request_seek(&mut self, offset_secs: f32) {
+        self.seek_requested = Some(self.seek_requested.unwrap_or(0.0) + offset_secs);
+    }
+
+    /// Consume the pending relative seek in seconds
+    pub fn take_seek_request(&mut self) -> Option<f32> {
+        self.seek_requested.take()
+    }
+
+    /// Consume the pending loop point change
+    pub fn take_loop_edit(&mut self) -> Option<LoopEdit> {
+        self.loop_edit_requested.take()
+    }
+
+    /// Timestamped file name for a screenshot in the working directory
+    fn screenshot_file_name() -> String {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        format!("aruu-screenshot-{}.png", timestamp)
+    }
+
+    /// Check if application should exit (exit key pressed)
     pub fn should_exit(&self) -> bool {
         self.should_exit
     }
@@ -556,7 +813,7 @@ mod tests {
         assert!(ui.auto_shader_enabled);
         assert!(ui.quality_override.is_none());
         assert!(!ui.show_performance_overlay);
-        assert_eq!(ui.available_shaders.len(), 8);
+        assert_eq!(ui.available_shaders.len(), 11);
     }
 
     #[test]
@@ -604,4 +861,119 @@ mod tests {
         ui.toggle_performance_overlay();
         assert!(!ui.show_performance_overlay);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_emergency_stop_and_exit_are_independent() {
+        let mut ui = UserInterface::new();
+
+        // Repeated ESC only ever stops
+        assert!(ui.dispatch_safety_key(KeyCode::Escape));
+        assert!(ui.dispatch_safety_key(KeyCode::Escape));
+        assert!(ui.is_emergency_stopped());
+        assert!(!ui.should_exit());
+
+        // Exit works without any emergency stop
+        let mut ui = UserInterface::new();
+        assert!(ui.dispatch_safety_key(DEFAULT_EXIT_KEY));
+        assert!(ui.should_exit());
+        assert!(!ui.is_emergency_stopped());
+    }
+
+    #[test]
+    fn test_configurable_emergency_stop_key() {
+        let mut ui = UserInterface::new();
+        assert!(ui.dispatch_safety_key(DEFAULT_EMERGENCY_STOP_KEY));
+        assert!(ui.is_emergency_stopped());
+        assert!(ui.dispatch_safety_key(KeyCode::KeyX));
+        assert!(!ui.is_emergency_stopped());
+
+        ui.set_emergency_stop_key(KeyCode::KeyB);
+        assert!(ui.dispatch_safety_key(KeyCode::KeyB));
+        assert!(ui.is_emergency_stopped());
+        assert!(!ui.should_exit());
+
+        // Without an exit key, nothing on the keyboard quits
+        ui.set_exit_key(None);
+        assert!(!ui.dispatch_safety_key(DEFAULT_EXIT_KEY));
+        assert!(!ui.should_exit());
+    }
+
+    #[test]
+    fn test_custom_key_bindings_replace_defaults() {
+        let mut ui = UserInterface::new();
+        assert_eq!(ui.key_bindings().action_for(KeyCode::Space), Some(Action::CycleNext));
+        assert_eq!(ui.key_bindings().action_for(KeyCode::KeyJ), None);
+
+        let mut bindings = KeyBindings::empty();
+        bindings.bind(KeyCode::KeyJ, Action::CycleNext);
+        ui.set_key_bindings(bindings);
+        assert_eq!(ui.key_bindings().action_for(KeyCode::KeyJ), Some(Action::CycleNext));
+        assert_eq!(ui.key_bindings().action_for(KeyCode::Space), None);
+
+        // Safety keys never go through the bindings
+        assert!(ui.dispatch_safety_key(KeyCode::Escape));
+        assert!(ui.is_emergency_stopped());
+    }
+
+    #[test]
+    fn test_fullscreen_shortcuts() {
+        let mut ui = UserInterface::new();
+        assert_eq!(ui.key_bindings().action_for(KeyCode::KeyF), Some(Action::ToggleFullscreen));
+        assert!(!ui.take_fullscreen_toggle());
+
+        assert!(!ui.is_fullscreen_chord(KeyCode::Enter));
+        ui.set_modifiers(ModifiersState::ALT);
+        assert!(ui.is_fullscreen_chord(KeyCode::Enter));
+        assert!(ui.is_fullscreen_chord(KeyCode::NumpadEnter));
+        assert!(!ui.is_fullscreen_chord(KeyCode::KeyF));
+        ui.set_modifiers(ModifiersState::empty());
+        assert!(!ui.is_fullscreen_chord(KeyCode::Enter));
+    }
+
+    #[test]
+    fn test_seek_requests_accumulate() {
+        let mut ui = UserInterface::new();
+        assert_eq!(ui.key_bindings().action_for(KeyCode::ArrowRight), Some(Action::SeekForward));
+        assert_eq!(ui.take_seek_request(), None);
+
+        ui.request_seek(SEEK_STEP_SECS);
+        ui.request_seek(SEEK_STEP_SECS);
+        ui.request_seek(-SEEK_STEP_SECS);
+        assert_eq!(ui.take_seek_request(), Some(SEEK_STEP_SECS));
+        assert_eq!(ui.take_seek_request(), None);
+    }
+
+    #[test]
+    fn test_effect_weight_selection_wraps() {
+        let mut ui = UserInterface::new();
+        assert_eq!(ui.selected_effect_weight(), 0);
+        assert_eq!(ui.key_bindings().action_for(KeyCode::Backslash), Some(Action::CycleEffectWeight));
+        for _ in 0..EffectWeights::COUNT - 1 {
+            ui.cycle_effect_weight();
+        }
+        assert_eq!(ui.selected_effect_weight(), EffectWeights::COUNT - 1);
+        ui.cycle_effect_weight();
+        assert_eq!(ui.selected_effect_weight(), 0);
+    }
+
+    #[test]
+    fn test_preset_slots_save_with_shift() {
+        let mut ui = UserInterface::new();
+        let recall = ui.key_bindings().action_for(KeyCode::F5).unwrap();
+        assert_eq!(ui.apply_modifiers(recall), Action::RecallPreset(1));
+        ui.set_modifiers(ModifiersState::SHIFT);
+        assert_eq!(ui.apply_modifiers(recall), Action::SavePreset(1));
+        assert_eq!(ui.apply_modifiers(Action::CycleNext), Action::CycleNext);
+
+        let path = std::env::temp_dir().join(format!("aruu-ui-presets-{}.txt", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        ui.set_preset_file(path.clone()).unwrap();
+        assert!(ui.preset_bank().is_empty());
+
+        let preset = Preset { shader: ShaderType::Tunnel, ..Preset::default() };
+        ui.store_preset(2, preset.clone()).unwrap();
+        let reloaded = PresetBank::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(reloaded.get(&slot_name(2)), Some(&preset));
+    }
+}
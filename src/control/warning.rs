@@ -6,6 +6,8 @@
 use winit::event::{ElementState, KeyEvent};
 use std::time::Instant;
 
+use crate::clock::{system_clock, SharedClock};
+
 /// Warning screen state and user interaction
 #[derive(Debug, Clone, PartialEq)]
 pub enum WarningState {
@@ -26,17 +28,29 @@ pub struct EpilepsyWarning {
     state: WarningState,
     start_time: Instant,
     selected_option: usize, // 0=Continue, 1=Safety Mode, 2=Exit
+    clock: SharedClock,
 }
 
 impl EpilepsyWarning {
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a warning screen timed by a custom time source
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
             state: WarningState::ShowingWarning,
-            start_time: Instant::now(),
+            start_time: clock.now(),
             selected_option: 1, // Default to Safety Mode
+            clock,
         }
     }
 
+    /// Time since the warning was first shown
+    fn elapsed(&self) -> std::time::Duration {
+        self.clock.now().duration_since(self.start_time)
+    }
+
     /// Handle keyboard input for warning screen
     pub fn handle_input(&mut self, key_event: &KeyEvent) -> bool {
         if self.state != WarningState::ShowingWarning {
@@ -137,7 +151,7 @@ impl EpilepsyWarning {
 
     /// Get warning text to display
     pub fn get_warning_text(&self) -> String {
-        let elapsed = self.start_time.elapsed().as_secs();
+        let elapsed = self.elapsed().as_secs();
         let selection_arrows = match self.selected_option {
             0 => "→ [1] Continue  [ ] Safety Mode  [ ] Exit",
             1 => "[ ] Continue  → [2] Safety Mode  [ ] Exit",
@@ -176,7 +190,7 @@ Time displayed: {}s (minimum 5s required)
 
     /// Check if minimum display time has elapsed
     pub fn minimum_time_elapsed(&self) -> bool {
-        self.start_time.elapsed().as_secs() >= 5 // Require 5 seconds minimum
+        self.elapsed().as_secs() >= 5 // Require 5 seconds minimum
     }
 }
 
@@ -189,6 +203,7 @@ impl Default for EpilepsyWarning {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
 
     #[test]
     fn test_warning_state_transitions() {
@@ -205,8 +220,15 @@ mod tests {
 
     #[test]
     fn test_minimum_display_time() {
-        let warning = EpilepsyWarning::new();
+        let clock = MockClock::new();
+        let warning = EpilepsyWarning::with_clock(clock.shared());
         assert!(!warning.minimum_time_elapsed()); // Should be false immediately
+
+        clock.advance_secs(4.9);
+        assert!(!warning.minimum_time_elapsed());
+
+        clock.advance_secs(0.2);
+        assert!(warning.minimum_time_elapsed());
     }
 
     #[test]
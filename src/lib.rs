@@ -1,9 +1,17 @@
+pub mod args;
 pub mod audio;
+pub mod clock;
 pub mod rendering;
 pub mod control;
+pub mod session;
+pub mod session_state;
 pub mod visualizer;
 
+pub use args::*;
 pub use audio::*;
+pub use clock::*;
 pub use rendering::*;
 pub use control::*;
+pub use session::*;
+pub use session_state::*;
 pub use visualizer::*;
\ No newline at end of file
@@ -1,32 +1,148 @@
-use aruu::AudioVisualizer;
+use aruu::{
+    request_headless_device, run_benchmark, AudioProcessor, AudioVisualizer, BenchmarkOptions, FrameExporter, HeadlessRenderer, LaunchOptions,
+    ShaderRegistry, ShaderType, DEFAULT_BENCHMARK_RESOLUTION, DEFAULT_EXPORT_FPS, DEFAULT_EXPORT_RESOLUTION, EXPORT_HEADER_LEN, USAGE,
+};
 use std::env;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    println!("🎵 Aruu Audio Visualizer - Phase 2 Demo");
+    let options = match LaunchOptions::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("❌ {}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    if options.list_devices {
+        list_devices();
+        return Ok(());
+    }
+    if options.validate_shaders {
+        return validate_shaders();
+    }
+    if options.benchmark {
+        return benchmark(&options);
+    }
+    if let Some(ref path) = options.export {
+        return export(&options, path);
+    }
 
-    let (mut visualizer, event_loop) = AudioVisualizer::new().await?;
+    println!("🎵 Aruu Audio Visualizer - Phase 2 Demo");
 
-    let args: Vec<String> = env::args().collect();
-    if args.len() > 1 {
-        let audio_file = &args[1];
-        println!("🎶 Loading audio file: {}", audio_file);
-        match visualizer.load_audio_file(audio_file) {
-            Ok(_) => println!("✅ Successfully loaded audio file"),
-            Err(e) => println!("❌ Failed to load audio file: {}", e),
-        }
-    } else {
-        println!("💡 Usage: cargo run [audio_file]");
+    if options.file.is_none() && options.replay.is_none() {
+        println!("💡 Usage: cargo run [audio_file] (or cargo run -- --help for all options)");
+        println!("   Or: cargo run -- --replay <session_file> (record one with F9)");
         println!("   Testing files: sample_gentle.wav, sample_rock.m4a");
         println!("   Or run without arguments for real-time microphone input");
     }
 
+    let (visualizer, event_loop) = AudioVisualizer::new(&options).await?;
+
     println!("🎨 Starting real-time audio visualization...");
     println!("   Close the window or press Ctrl+C to exit");
 
     visualizer.run(event_loop)
 }
 
+/// Print the audio input devices `--input-device` can pick from
+fn list_devices() {
+    let devices = AudioProcessor::list_input_devices();
+    if devices.is_empty() {
+        println!("No audio input devices found");
+        return;
+    }
+    for device in devices {
+        let format = match (device.channels, device.sample_rate) {
+            (Some(channels), Some(rate)) => format!(" ({} ch, {} Hz)", channels, rate),
+            _ => String::new(),
+        };
+        println!("{:>3}  {}{}{}", device.index, device.name, format, if device.is_default { "  [default]" } else { "" });
+    }
+}
+
+/// Render every shader (or just `--shader`) offscreen and print frame time statistics
+fn benchmark(options: &LaunchOptions) -> anyhow::Result<()> {
+    let mut benchmark_options = BenchmarkOptions::default();
+    if let Some(frames) = options.benchmark_frames {
+        benchmark_options.frames = frames;
+    }
+    if let Some(fps) = options.fps {
+        benchmark_options.target_fps = fps as f32;
+    }
+    let shaders = match options.shader {
+        Some(shader) => vec![shader],
+        None => ShaderType::all().to_vec(),
+    };
+
+    let (width, height) = DEFAULT_BENCHMARK_RESOLUTION;
+    let mut renderer = HeadlessRenderer::new(width, height)?;
+    let report = run_benchmark(&mut renderer, &shaders, &benchmark_options)?;
+
+    if options.json {
+        println!("{}", report.to_json());
+    } else {
+        println!("{}", report.table());
+    }
+    Ok(())
+}
+
+/// Compile every registered shader and report each result; exits non-zero if any fail
+fn validate_shaders() -> anyhow::Result<()> {
+    let (device, _queue) = request_headless_device()?;
+    let results = ShaderRegistry::new().validate_all(&device);
+
+    let mut failures = 0;
+    for (shader_type, result) in &results {
+        match result {
+            Ok(()) => println!("✅ {}", shader_type.name()),
+            Err(e) => {
+                failures += 1;
+                println!("❌ {}", e);
+            }
+        }
+    }
+    if failures > 0 {
+        anyhow::bail!("{} of {} shaders failed validation", failures, results.len());
+    }
+    println!("All {} shaders compiled", results.len());
+    Ok(())
+}
+
+/// Render the audio file offscreen and stream raw RGBA frames to `path` ("-" for stdout).
+/// Progress goes to stderr so stdout carries nothing but frames.
+fn export(options: &LaunchOptions, path: &Path) -> anyhow::Result<()> {
+    let file = options.file.as_deref().expect("--export is only accepted with an audio file");
+    let (width, height) = options.export_size.unwrap_or(DEFAULT_EXPORT_RESOLUTION);
+    let fps = options.fps.unwrap_or(DEFAULT_EXPORT_FPS);
+
+    let mut renderer = HeadlessRenderer::new(width, height)?;
+    if let Some(shader) = options.shader {
+        renderer.set_shader(shader)?;
+    }
+    let mut exporter = FrameExporter::from_file(renderer, file, fps)?;
+
+    eprintln!(
+        "🎬 Exporting {}x{} @ {} FPS; encode with: ffmpeg -skip_initial_bytes {} -f rawvideo -pix_fmt rgba -s {}x{} -r {} -i <frames> -i {} -shortest out.mp4",
+        width, height, fps, EXPORT_HEADER_LEN, width, height, fps, file.display()
+    );
+    let mut out: Box<dyn Write> = if path == Path::new("-") {
+        Box::new(BufWriter::new(std::io::stdout().lock()))
+    } else {
+        // Opening a named pipe blocks until the reader (ffmpeg) connects
+        Box::new(BufWriter::new(std::fs::File::create(path)?))
+    };
+    let frames = exporter.export(&mut out, None)?;
+
+    eprintln!("✅ Exported {} frames ({:.1}s of audio)", frames, exporter.position().as_secs_f32());
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use aruu::{AudioProcessor, FeatureMapper, AudioFeatures};
@@ -0,0 +1,278 @@
+// Headless shader benchmark: renders each shader offscreen over the same synthetic
+// audio and reports frame time statistics, so regressions and low-end hardware
+// limits show up without eyeballing the live FPS counter
+
+use anyhow::{bail, Result};
+use std::time::{Duration, Instant};
+
+use crate::audio::recording::json_number;
+use crate::audio::{AudioFeatures, AudioProcessor, RhythmDetector, RhythmFeatures, SignalSpec};
+use crate::clock::MockClock;
+use super::{HeadlessRenderer, PerformanceManager, PerformanceMetrics, QualityLevel, ShaderType};
+
+/// Frames measured per shader when `--benchmark-frames` is not given
+pub const DEFAULT_BENCHMARK_FRAMES: u32 = 300;
+
+/// Offscreen resolution for benchmark runs
+pub const DEFAULT_BENCHMARK_RESOLUTION: (u32, u32) = (1280, 720);
+
+/// Unmeasured frames rendered first, so one-off pipeline and driver setup isn't counted
+const WARMUP_FRAMES: u32 = 5;
+
+/// What to render and for how long
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkOptions {
+    pub frames: u32,     // Measured frames per shader
+    pub target_fps: f32, // Frame budget the adaptive quality aims for
+    pub signal: SignalSpec,
+}
+
+impl Default for BenchmarkOptions {
+    fn default() -> Self {
+        Self {
+            frames: DEFAULT_BENCHMARK_FRAMES,
+            target_fps: 60.0,
+            // A repeating sweep moves energy through every band, like music does
+            signal: SignalSpec::sweep(40.0, 8000.0, Duration::from_secs(4), 0.6),
+        }
+    }
+}
+
+/// Frame time statistics for one shader
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShaderBenchmark {
+    pub shader: ShaderType,
+    pub frames: u32,
+    pub average: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub settled_quality: QualityLevel, // Where adaptive quality ended up after the run
+}
+
+impl ShaderBenchmark {
+    /// Summarize measured frame times (in any order)
+    pub fn from_frame_times(shader: ShaderType, frame_times: &[Duration], settled_quality: QualityLevel) -> Self {
+        let mut sorted = frame_times.to_vec();
+        sorted.sort();
+        let total: Duration = sorted.iter().sum();
+
+        Self {
+            shader,
+            frames: sorted.len() as u32,
+            average: if sorted.is_empty() { Duration::ZERO } else { total / sorted.len() as u32 },
+            p50: percentile(&sorted, 0.50),
+            p95: percentile(&sorted, 0.95),
+            p99: percentile(&sorted, 0.99),
+            settled_quality,
+        }
+    }
+
+    /// Frame rate the average frame time would sustain
+    pub fn average_fps(&self) -> f32 {
+        let seconds = self.average.as_secs_f32();
+        if seconds > 0.0 { 1.0 / seconds } else { 0.0 }
+    }
+}
+
+/// Value at `fraction` through sorted samples, indexed like `percentile_99_frame_time`
+fn percentile(sorted: &[Duration], fraction: f32) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let index = (sorted.len() as f32 * fraction) as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
+/// Results of a full benchmark run
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport {
+    pub width: u32,
+    pub height: u32,
+    pub target_fps: f32,
+    pub results: Vec<ShaderBenchmark>,
+}
+
+impl BenchmarkReport {
+    /// Fixed-width table for the terminal
+    pub fn table(&self) -> String {
+        let mut lines = vec![
+            format!("Shader benchmark at {}x{}, target {:.0} FPS", self.width, self.height, self.target_fps),
+            format!(
+                "{:<16} {:>7} {:>9} {:>9} {:>9} {:>9} {:>8}  {}",
+                "Shader", "Frames", "Avg ms", "P50 ms", "P95 ms", "P99 ms", "Avg FPS", "Quality"
+            ),
+        ];
+        for result in &self.results {
+            lines.push(format!(
+                "{:<16} {:>7} {:>9.2} {:>9.2} {:>9.2} {:>9.2} {:>8.1}  {:?}",
+                result.shader.name(),
+                result.frames,
+                millis(result.average),
+                millis(result.p50),
+                millis(result.p95),
+                millis(result.p99),
+                result.average_fps(),
+                result.settled_quality,
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// Single JSON object with one entry per shader, for scripts and CI
+    pub fn to_json(&self) -> String {
+        let results: Vec<String> = self
+            .results
+            .iter()
+            .map(|result| {
+                format!(
+                    "{{\"shader\":\"{}\",\"frames\":{},\"average_ms\":{},\"p50_ms\":{},\"p95_ms\":{},\"p99_ms\":{},\"average_fps\":{},\"settled_quality\":\"{:?}\"}}",
+                    result.shader.name(),
+                    result.frames,
+                    json_number(millis(result.average)),
+                    json_number(millis(result.p50)),
+                    json_number(millis(result.p95)),
+                    json_number(millis(result.p99)),
+                    json_number(result.average_fps()),
+                    result.settled_quality,
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"width\":{},\"height\":{},\"target_fps\":{},\"results\":[{}]}}",
+            self.width,
+            self.height,
+            json_number(self.target_fps),
+            results.join(",")
+        )
+    }
+}
+
+fn millis(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
+
+/// Analyze `frames` frames of the test signal through the normal audio and rhythm pipeline
+pub fn synthetic_audio(signal: SignalSpec, frames: u32, frame_rate: f32) -> Result<Vec<(AudioFeatures, RhythmFeatures)>> {
+    let mut processor = AudioProcessor::new_test_signal(signal);
+    let mut rhythm_detector = RhythmDetector::new(processor.sample_rate());
+    rhythm_detector.set_frame_rate(frame_rate);
+
+    (0..frames)
+        .map(|_| {
+            let audio = processor.process_frame()?;
+            let rhythm = rhythm_detector.process_frame(processor.spectrum());
+            Ok((audio, rhythm))
+        })
+        .collect()
+}
+
+/// Render `options.frames` frames of each shader and time them
+///
+/// Every shader sees the same analyzed audio, precomputed so analysis cost stays out of
+/// the numbers. Each shader gets a fresh `PerformanceManager` whose clock advances by
+/// what a display capped at the target rate would show, so quality settles in
+/// simulated time however fast the GPU renders.
+pub fn run_benchmark(renderer: &mut HeadlessRenderer, shaders: &[ShaderType], options: &BenchmarkOptions) -> Result<BenchmarkReport> {
+    if options.frames == 0 {
+        bail!("A benchmark needs at least one frame per shader");
+    }
+
+    let audio = synthetic_audio(options.signal, options.frames, options.target_fps)?;
+    let (width, height) = renderer.dimensions();
+    let mut results = Vec::with_capacity(shaders.len());
+
+    for &shader in shaders {
+        renderer.set_shader(shader)?;
+        let (warmup_audio, warmup_rhythm) = &audio[0];
+        for _ in 0..WARMUP_FRAMES {
+            renderer.render_at_quality(warmup_audio, warmup_rhythm, QualityLevel::High)?;
+        }
+
+        let clock = MockClock::new();
+        let mut performance_manager = PerformanceManager::with_clock(options.target_fps, clock.shared());
+        let frame_budget = performance_manager.target_frame_time();
+        let mut frame_times = Vec::with_capacity(audio.len());
+
+        for (features, rhythm) in &audio {
+            let start = Instant::now();
+            renderer.render_at_quality(features, rhythm, performance_manager.current_quality())?;
+            let frame_time = start.elapsed();
+
+            frame_times.push(frame_time);
+            clock.advance(frame_time.max(frame_budget));
+            performance_manager.update(PerformanceMetrics {
+                frame_time,
+                cpu_time: Duration::ZERO,
+                gpu_time: frame_time, // The render waits on the GPU, so the frame is all GPU time
+                fps: 1.0 / frame_time.as_secs_f32().max(f32::EPSILON),
+                dropped_frames: 0,
+                memory_usage_mb: 0.0,
+            });
+        }
+
+        let result = ShaderBenchmark::from_frame_times(shader, &frame_times, performance_manager.current_quality());
+        println!("⏱️  {}: {:.2} ms average over {} frames", shader.name(), millis(result.average), result.frames);
+        results.push(result);
+    }
+
+    Ok(BenchmarkReport { width, height, target_fps: options.target_fps, results })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None).await.ok()
+        })
+    }
+
+    #[test]
+    fn test_frame_time_statistics() {
+        let frame_times: Vec<Duration> = (1..=100).rev().map(Duration::from_millis).collect();
+        let result = ShaderBenchmark::from_frame_times(ShaderType::Plasma, &frame_times, QualityLevel::Medium);
+
+        assert_eq!(result.frames, 100);
+        assert_eq!(result.average, Duration::from_micros(50_500));
+        assert_eq!(result.p50, Duration::from_millis(51));
+        assert_eq!(result.p95, Duration::from_millis(96));
+        assert_eq!(result.p99, Duration::from_millis(100));
+        assert!(result.p50 <= result.p95 && result.p95 <= result.p99);
+
+        let report = BenchmarkReport { width: 320, height: 180, target_fps: 60.0, results: vec![result] };
+        let table = report.table();
+        assert!(table.contains("Plasma") && table.contains("Medium"));
+        assert_eq!(
+            report.to_json(),
+            "{\"width\":320,\"height\":180,\"target_fps\":60,\"results\":[{\"shader\":\"Plasma\",\"frames\":100,\
+             \"average_ms\":50.5,\"p50_ms\":51,\"p95_ms\":96,\"p99_ms\":100,\"average_fps\":19.80198,\"settled_quality\":\"Medium\"}]}"
+        );
+    }
+
+    #[test]
+    fn test_tiny_benchmark_run() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping benchmark test: no GPU adapter available");
+            return;
+        };
+
+        let mut renderer = HeadlessRenderer::with_device(device, queue, 64, 36).expect("Headless renderer should build");
+        let options = BenchmarkOptions { frames: 4, ..BenchmarkOptions::default() };
+        let report = run_benchmark(&mut renderer, &[ShaderType::Classic], &options).unwrap();
+
+        assert_eq!(report.results.len(), 1);
+        let result = &report.results[0];
+        assert_eq!(result.shader, ShaderType::Classic);
+        assert_eq!(result.frames, 4);
+        for time in [result.average, result.p50, result.p95, result.p99] {
+            assert!(time.as_secs_f32().is_finite() && time > Duration::ZERO);
+        }
+        assert!(result.average_fps().is_finite());
+        assert_eq!(renderer.frames_rendered(), (WARMUP_FRAMES + 4) as u64);
+    }
+}
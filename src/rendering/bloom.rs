@@ -0,0 +1,401 @@
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use super::trails::FrameTarget;
+use super::FrameEncoder;
+
+/// Glow strength used until `set_intensity` is called
+pub const DEFAULT_BLOOM_INTENSITY: f32 = 0.6;
+
+/// Upper bound for bloom intensity; beyond this highlights wash out the whole frame
+pub const MAX_BLOOM_INTENSITY: f32 = 2.0;
+
+/// Scene luminance below which nothing blooms
+const BLOOM_THRESHOLD: f32 = 0.6;
+
+/// Share of the intensity applied between beats; the rest follows beat strength
+const RESTING_BLOOM: f32 = 0.25;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct BloomUniforms {
+    texel_step: [f32; 2],
+    intensity: f32,
+    threshold: f32,
+}
+
+impl BloomUniforms {
+    fn blur(texel_step: [f32; 2], threshold: f32) -> Self {
+        Self { texel_step, intensity: 0.0, threshold }
+    }
+
+    fn combine(intensity: f32) -> Self {
+        Self { texel_step: [0.0; 2], intensity, threshold: 0.0 }
+    }
+}
+
+/// Beat-driven bloom post-process
+///
+/// When enabled, shaders render into an offscreen scene texture. Its bright parts are
+/// blurred horizontally then vertically at half resolution, and the combine pass adds
+/// the glow back over the scene while writing to the output view.
+pub struct BloomSystem {
+    enabled: bool,
+    intensity: f32,
+    format: wgpu::TextureFormat,
+    size: (u32, u32),
+    scene: FrameTarget,
+    blur: [FrameTarget; 2], // Horizontal then vertical blur results
+    sampler: wgpu::Sampler,
+    bind_group_layout: wgpu::BindGroupLayout,
+    blur_pipeline: wgpu::RenderPipeline,
+    combine_pipeline: wgpu::RenderPipeline,
+    horizontal_uniforms: wgpu::Buffer,
+    vertical_uniforms: wgpu::Buffer,
+    combine_uniforms: wgpu::Buffer,
+    bind_groups: [wgpu::BindGroup; 3], // Horizontal blur, vertical blur, combine
+}
+
+impl BloomSystem {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, width: u32, height: u32) -> Self {
+        let size = (width.max(1), height.max(1));
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("bloom_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/bloom.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("bloom_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                Self::texture_layout_entry(1),
+                Self::texture_layout_entry(2),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("bloom_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let blur_pipeline = Self::create_pipeline(device, &pipeline_layout, &shader, format, "fs_blur", "bloom_blur_pipeline");
+        let combine_pipeline = Self::create_pipeline(device, &pipeline_layout, &shader, format, "fs_combine", "bloom_combine_pipeline");
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("bloom_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        // One buffer per pass, since all three are recorded into the same submit
+        let uniform_buffer = |label: &str| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: bytemuck::cast_slice(&[BloomUniforms::combine(0.0)]),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            })
+        };
+        let horizontal_uniforms = uniform_buffer("bloom_horizontal_uniforms");
+        let vertical_uniforms = uniform_buffer("bloom_vertical_uniforms");
+        let combine_uniforms = uniform_buffer("bloom_combine_uniforms");
+
+        let scene = FrameTarget::new(device, format, size, "bloom_scene_texture");
+        let blur = Self::create_blur_targets(device, format, size);
+        let bind_groups = Self::create_bind_groups(
+            device, &bind_group_layout, &sampler,
+            [&horizontal_uniforms, &vertical_uniforms, &combine_uniforms], &scene, &blur,
+        );
+
+        Self {
+            enabled: false,
+            intensity: DEFAULT_BLOOM_INTENSITY,
+            format,
+            size,
+            scene,
+            blur,
+            sampler,
+            bind_group_layout,
+            blur_pipeline,
+            combine_pipeline,
+            horizontal_uniforms,
+            vertical_uniforms,
+            combine_uniforms,
+            bind_groups,
+        }
+    }
+
+    fn texture_layout_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        }
+    }
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        format: wgpu::TextureFormat,
+        entry_point: &str,
+        label: &str,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point,
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Blur targets run at half the output resolution
+    fn blur_size(size: (u32, u32)) -> (u32, u32) {
+        ((size.0 / 2).max(1), (size.1 / 2).max(1))
+    }
+
+    fn create_blur_targets(device: &wgpu::Device, format: wgpu::TextureFormat, size: (u32, u32)) -> [FrameTarget; 2] {
+        let blur_size = Self::blur_size(size);
+        [
+            FrameTarget::new(device, format, blur_size, "bloom_blur_texture_0"),
+            FrameTarget::new(device, format, blur_size, "bloom_blur_texture_1"),
+        ]
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        uniforms: &wgpu::Buffer,
+        scene: &wgpu::TextureView,
+        bloom: &wgpu::TextureView,
+        label: &str,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(label),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniforms.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(scene) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(bloom) },
+                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::Sampler(sampler) },
+            ],
+        })
+    }
+
+    /// Bind groups for the horizontal blur, vertical blur and combine passes, in order
+    fn create_bind_groups(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        uniforms: [&wgpu::Buffer; 3],
+        scene: &FrameTarget,
+        blur: &[FrameTarget; 2],
+    ) -> [wgpu::BindGroup; 3] {
+        // Blur passes read only their source; it fills both texture slots
+        [
+            Self::create_bind_group(device, layout, sampler, uniforms[0],
+                                    &scene.view, &scene.view, "bloom_horizontal_bind_group"),
+            Self::create_bind_group(device, layout, sampler, uniforms[1],
+                                    &blur[0].view, &blur[0].view, "bloom_vertical_bind_group"),
+            Self::create_bind_group(device, layout, sampler, uniforms[2],
+                                    &scene.view, &blur[1].view, "bloom_combine_bind_group"),
+        ]
+    }
+
+    /// Clamp a requested intensity to the supported range
+    pub fn clamp_intensity(intensity: f32) -> f32 {
+        if intensity.is_finite() { intensity.clamp(0.0, MAX_BLOOM_INTENSITY) } else { DEFAULT_BLOOM_INTENSITY }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Set the glow strength on a full-strength beat (0.0 to `MAX_BLOOM_INTENSITY`)
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = Self::clamp_intensity(intensity);
+    }
+
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// Glow strength for this frame: a faint resting level that swells with the beat
+    /// (`safety_beat_intensity` scales the beat-driven part, as for other beat effects)
+    pub fn beat_intensity(&self, beat_strength: f32, safety_beat_intensity: f32) -> f32 {
+        let beat = if beat_strength.is_finite() { beat_strength.clamp(0.0, 1.0) } else { 0.0 };
+        self.intensity * (RESTING_BLOOM + (1.0 - RESTING_BLOOM) * beat * safety_beat_intensity.clamp(0.0, 1.0))
+    }
+
+    /// Size of the offscreen scene texture
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// Recreate offscreen targets if the output size changed
+    pub fn ensure_size(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        let size = (width.max(1), height.max(1));
+        if size == self.size {
+            return;
+        }
+
+        self.size = size;
+        self.scene = FrameTarget::new(device, self.format, size, "bloom_scene_texture");
+        self.blur = Self::create_blur_targets(device, self.format, size);
+        self.bind_groups = Self::create_bind_groups(
+            device, &self.bind_group_layout, &self.sampler,
+            [&self.horizontal_uniforms, &self.vertical_uniforms, &self.combine_uniforms], &self.scene, &self.blur,
+        );
+    }
+
+    /// View that shaders should render into while bloom is active
+    pub fn scene_view(&self) -> &wgpu::TextureView {
+        &self.scene.view
+    }
+
+    /// Record the blur and combine passes, writing the scene plus `intensity` of glow to `target`
+    pub fn apply(&self, queue: &wgpu::Queue, frame: &mut FrameEncoder, target: &wgpu::TextureView, intensity: f32) {
+        let (_, blur_height) = Self::blur_size(self.size);
+        queue.write_buffer(&self.horizontal_uniforms, 0,
+                           bytemuck::cast_slice(&[BloomUniforms::blur([1.0 / self.size.0 as f32, 0.0], BLOOM_THRESHOLD)]));
+        queue.write_buffer(&self.vertical_uniforms, 0,
+                           bytemuck::cast_slice(&[BloomUniforms::blur([0.0, 1.0 / blur_height as f32], 0.0)]));
+        queue.write_buffer(&self.combine_uniforms, 0, bytemuck::cast_slice(&[BloomUniforms::combine(intensity)]));
+
+        Self::draw_pass(frame, &self.blur[0].view, &self.blur_pipeline, &self.bind_groups[0], "bloom_horizontal_pass");
+        Self::draw_pass(frame, &self.blur[1].view, &self.blur_pipeline, &self.bind_groups[1], "bloom_vertical_pass");
+        Self::draw_pass(frame, target, &self.combine_pipeline, &self.bind_groups[2], "bloom_combine_pass");
+    }
+
+    fn draw_pass(
+        frame: &mut FrameEncoder,
+        view: &wgpu::TextureView,
+        pipeline: &wgpu::RenderPipeline,
+        bind_group: &wgpu::BindGroup,
+        label: &str,
+    ) {
+        let mut render_pass = frame.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some(label),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None).await.ok()
+        })
+    }
+
+    #[test]
+    fn test_intensity_is_clamped() {
+        assert_eq!(BloomSystem::clamp_intensity(-1.0), 0.0);
+        assert_eq!(BloomSystem::clamp_intensity(0.5), 0.5);
+        assert_eq!(BloomSystem::clamp_intensity(10.0), MAX_BLOOM_INTENSITY);
+        assert_eq!(BloomSystem::clamp_intensity(f32::NAN), DEFAULT_BLOOM_INTENSITY);
+    }
+
+    #[test]
+    fn test_toggle_and_resize_track_output() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping bloom test: no GPU adapter available");
+            return;
+        };
+
+        let mut bloom = BloomSystem::new(&device, FORMAT, 64, 32);
+        assert!(!bloom.is_enabled());
+        bloom.set_enabled(true);
+        assert!(bloom.is_enabled());
+
+        // Beats swell the glow; the safety multiplier can take it back to the resting level
+        let resting = bloom.beat_intensity(0.0, 1.0);
+        assert!(bloom.beat_intensity(1.0, 1.0) > resting);
+        assert_eq!(bloom.beat_intensity(1.0, 0.0), resting);
+        assert!((bloom.beat_intensity(1.0, 1.0) - DEFAULT_BLOOM_INTENSITY).abs() < 1e-6);
+
+        assert_eq!(bloom.size(), (64, 32));
+        bloom.ensure_size(&device, 128, 48);
+        assert_eq!(bloom.size(), (128, 48));
+        assert_eq!(bloom.blur[0].texture.width(), 64);
+        assert_eq!(bloom.blur[1].texture.height(), 24);
+
+        // Passes still record and submit after the resize
+        let output = FrameTarget::new(&device, FORMAT, (128, 48), "bloom_test_output");
+        let mut frame = FrameEncoder::new(&device);
+        bloom.apply(&queue, &mut frame, &output.view, bloom.beat_intensity(0.8, 1.0));
+        frame.submit(&queue);
+
+        bloom.set_enabled(false);
+        assert!(!bloom.is_enabled());
+    }
+}
@@ -1,7 +1,7 @@
 use wgpu::util::DeviceExt;
 use bytemuck::{Pod, Zeroable};
 use crate::control::ShaderParameters;
-use super::{WgpuContext, VERTEX_SHADER, FRAGMENT_SHADER};
+use super::{WgpuContext, render_format, VERTEX_SHADER, FRAGMENT_SHADER};
 use anyhow::Result;
 
 #[repr(C)]
@@ -119,7 +119,7 @@ impl FrameComposer {
                     module: &fragment_shader,
                     entry_point: "fs_main",
                     targets: &[Some(wgpu::ColorTargetState {
-                        format: context.config.format,
+                        format: render_format(&context.config),
                         blend: Some(wgpu::BlendState::REPLACE),
                         write_mask: wgpu::ColorWrites::ALL,
                     })],
@@ -214,9 +214,7 @@ impl FrameComposer {
         parameters: &ShaderParameters,
     ) -> Result<()> {
         let output = context.get_current_texture()?;
-        let view = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        let view = context.create_output_view(&output);
 
         let time = self.start_time.elapsed().as_secs_f32();
         let uniform_data = UniformData {
@@ -1,11 +1,183 @@
 use wgpu::{Device, Queue, Surface, SurfaceConfiguration};
 use winit::{
     event_loop::EventLoop,
-    window::Window,
+    window::{Fullscreen, Icon, Window},
 };
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use std::future::Future;
 use std::sync::Arc;
 
+use super::GpuCapabilities;
+
+/// Window title until the visualizer sets a live one
+pub const DEFAULT_WINDOW_TITLE: &str = "Aruu Audio Visualizer";
+
+/// Application icon, embedded so the binary runs from anywhere
+pub const DEFAULT_WINDOW_ICON_PNG: &[u8] = include_bytes!("../../assets/icon.png");
+
+/// Decode a PNG into RGBA8 pixels and its size, as `Icon::from_rgba` takes them
+pub fn decode_icon_png(png: &[u8]) -> Result<(Vec<u8>, u32, u32)> {
+    let image = image::load_from_memory_with_format(png, image::ImageFormat::Png)
+        .map_err(|e| anyhow!("Failed to decode window icon: {}", e))?
+        .into_rgba8();
+    let (width, height) = image.dimensions();
+    Ok((image.into_raw(), width, height))
+}
+
+/// How the window covers the screen when fullscreen is toggled on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FullscreenMode {
+    #[default]
+    Borderless, // Desktop-sized borderless window; instant to toggle, keeps the desktop video mode
+    Exclusive,  // Takes over the monitor at its best video mode
+}
+
+impl FullscreenMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FullscreenMode::Borderless => "Borderless",
+            FullscreenMode::Exclusive => "Exclusive",
+        }
+    }
+}
+
+/// Present mode actually used for `requested`: the request when the surface supports it,
+/// otherwise the closest alternative (the other tear-free/uncapped mode, then V-sync)
+pub fn choose_present_mode(requested: wgpu::PresentMode, supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+    use wgpu::PresentMode::{Fifo, FifoRelaxed, Immediate, Mailbox};
+    let preferences: &[wgpu::PresentMode] = match requested {
+        Mailbox => &[Mailbox, Immediate, Fifo],
+        Immediate => &[Immediate, Mailbox, Fifo],
+        Fifo => &[Fifo, FifoRelaxed],
+        other => &[other, Fifo],
+    };
+    preferences
+        .iter()
+        .copied()
+        .find(|mode| supported.contains(mode))
+        .or_else(|| supported.first().copied())
+        .unwrap_or(Fifo)
+}
+
+/// Human-readable present mode for logs and status text
+pub fn present_mode_name(mode: wgpu::PresentMode) -> &'static str {
+    match mode {
+        wgpu::PresentMode::Fifo => "Fifo (V-sync)",
+        wgpu::PresentMode::FifoRelaxed => "FifoRelaxed (Adaptive V-sync)",
+        wgpu::PresentMode::Immediate => "Immediate (Unlimited FPS)",
+        wgpu::PresentMode::Mailbox => "Mailbox (Triple buffering)",
+        wgpu::PresentMode::AutoVsync => "AutoVsync",
+        wgpu::PresentMode::AutoNoVsync => "AutoNoVsync",
+    }
+}
+
+/// Environment variable that forces one GPU backend, like `--backend`
+pub const BACKEND_ENV_VAR: &str = "ARUU_BACKEND";
+
+/// Backends tried in order until one yields an adapter and device; GL last, since it's the
+/// slowest but the most likely to work where Vulkan drivers are broken
+pub const BACKEND_PRIORITY: [wgpu::Backends; 4] = [
+    wgpu::Backends::VULKAN,
+    wgpu::Backends::METAL,
+    wgpu::Backends::DX12,
+    wgpu::Backends::GL,
+];
+
+const BACKEND_NAMES: [(wgpu::Backends, &str); 4] = [
+    (wgpu::Backends::VULKAN, "Vulkan"),
+    (wgpu::Backends::METAL, "Metal"),
+    (wgpu::Backends::DX12, "DX12"),
+    (wgpu::Backends::GL, "GL"),
+];
+
+/// Human-readable backend for logs
+pub fn backend_name(backends: wgpu::Backends) -> &'static str {
+    BACKEND_NAMES
+        .iter()
+        .find(|(backend, _)| *backend == backends)
+        .map_or("Unknown", |(_, name)| name)
+}
+
+/// Parse a backend name (vulkan, metal, dx12 or gl, case-insensitive)
+pub fn parse_backend(name: &str) -> Result<wgpu::Backends> {
+    match name.trim().to_lowercase().as_str() {
+        "vulkan" | "vk" => Ok(wgpu::Backends::VULKAN),
+        "metal" => Ok(wgpu::Backends::METAL),
+        "dx12" | "d3d12" => Ok(wgpu::Backends::DX12),
+        "gl" | "opengl" | "gles" => Ok(wgpu::Backends::GL),
+        _ => Err(anyhow!("Unknown GPU backend '{}' (expected vulkan, metal, dx12 or gl)", name)),
+    }
+}
+
+/// Backend forced through `ARUU_BACKEND`, if it's set
+pub fn backend_from_env() -> Result<Option<wgpu::Backends>> {
+    match std::env::var(BACKEND_ENV_VAR) {
+        Ok(name) if !name.trim().is_empty() => parse_backend(&name).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Backends to try: only the forced one, or all of `BACKEND_PRIORITY`
+pub fn backend_candidates(forced: Option<wgpu::Backends>) -> Vec<wgpu::Backends> {
+    forced.map_or_else(|| BACKEND_PRIORITY.to_vec(), |backend| vec![backend])
+}
+
+/// Run `init` for each candidate in order and keep the first that succeeds, logging the
+/// ones that fail. Errors only when every candidate fails.
+pub async fn first_working_backend<T, F, Fut>(candidates: &[wgpu::Backends], mut init: F) -> Result<(wgpu::Backends, T)>
+where
+    F: FnMut(wgpu::Backends) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut failures = Vec::new();
+    for &backend in candidates {
+        match init(backend).await {
+            Ok(value) => return Ok((backend, value)),
+            Err(e) => {
+                println!("⚠️  {} backend unavailable: {}", backend_name(backend), e);
+                failures.push(format!("{}: {}", backend_name(backend), e));
+            }
+        }
+    }
+    bail!("No GPU backend could be initialized ({})", failures.join("; "))
+}
+
+/// Surface, adapter and device from one backend
+async fn init_backend(backends: wgpu::Backends, window: Arc<Window>) -> Result<(Surface<'static>, wgpu::Adapter, Device, Queue)> {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends,
+        ..Default::default()
+    });
+
+    let surface = instance.create_surface(window)?;
+
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            compatible_surface: Some(&surface),
+            force_fallback_adapter: false,
+        })
+        .await
+        .ok_or_else(|| anyhow!("no adapter can present to the window"))?;
+
+    // Timestamp queries let the performance manager see real GPU time when the adapter has them
+    let required_features = adapter.features() & wgpu::Features::TIMESTAMP_QUERY;
+
+    let (device, queue) = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                required_features,
+                required_limits: wgpu::Limits::default(),
+                label: None,
+                memory_hints: wgpu::MemoryHints::MemoryUsage,
+            },
+            None,
+        )
+        .await?;
+
+    Ok((surface, adapter, device, queue))
+}
+
 pub struct WgpuContext {
     pub surface: Surface<'static>,
     pub device: Device,
@@ -13,45 +185,42 @@ pub struct WgpuContext {
     pub config: SurfaceConfiguration,
     pub size: winit::dpi::PhysicalSize<u32>,
     pub window: Arc<Window>,
+    pub capabilities: GpuCapabilities,
+    pub fullscreen_mode: FullscreenMode,
+    present_modes: Vec<wgpu::PresentMode>, // Modes the surface supports
+    title: String,                         // Last title handed to the window
 }
 
 impl WgpuContext {
+    /// Window and surface presenting with V-sync
     pub async fn new() -> Result<(Self, EventLoop<()>)> {
+        Self::with_present_mode(wgpu::PresentMode::Fifo).await
+    }
+
+    /// Window and surface presenting with `present_mode` (or the closest supported mode), on
+    /// the backend named by `ARUU_BACKEND` or the first of `BACKEND_PRIORITY` that works
+    pub async fn with_present_mode(present_mode: wgpu::PresentMode) -> Result<(Self, EventLoop<()>)> {
+        Self::with_backend(present_mode, backend_from_env()?).await
+    }
+
+    /// Like `with_present_mode`, but only tries `backend` when one is given
+    pub async fn with_backend(present_mode: wgpu::PresentMode, backend: Option<wgpu::Backends>) -> Result<(Self, EventLoop<()>)> {
         let event_loop = EventLoop::new()?;
         let window = Arc::new(event_loop
             .create_window(winit::window::WindowAttributes::default() // ASSUMPTION: Keeping deprecated API for simplicity - requires major refactoring to fix
-                .with_title("Aruu Audio Visualizer")
+                .with_title(DEFAULT_WINDOW_TITLE)
                 .with_inner_size(winit::dpi::LogicalSize::new(800, 600)))?);
 
         let size = window.inner_size();
 
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        });
-
-        let surface = instance.create_surface(Arc::clone(&window))?;
-
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .ok_or_else(|| anyhow::anyhow!("Failed to find an appropriate adapter"))?;
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::default(),
-                    label: None,
-                    memory_hints: wgpu::MemoryHints::MemoryUsage,
-                },
-                None,
-            )
-            .await?;
+        let (backend, (surface, adapter, device, queue)) =
+            first_working_backend(&backend_candidates(backend), |backends| init_backend(backends, Arc::clone(&window))).await?;
+
+        let capabilities = GpuCapabilities::from_adapter(&adapter.limits(), &adapter.get_info());
+        println!("🖥️  Adapter: {} ({:?}) on {}", capabilities.adapter_name, capabilities.device_type, backend_name(backend));
+        if let Some(warning) = capabilities.warning_message() {
+            println!("⚠️  {}", warning);
+        }
 
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
@@ -61,45 +230,35 @@ impl WgpuContext {
             .copied()
             .unwrap_or(surface_caps.formats[0]);
 
-        // Select present mode with preference for V-sync (60 FPS cap)
-        let present_mode = surface_caps
-            .present_modes
-            .iter()
-            .copied()
-            .find(|&mode| mode == wgpu::PresentMode::Fifo)
-            .or_else(|| {
-                // Fallback hierarchy for V-sync-like behavior
-                surface_caps
-                    .present_modes
-                    .iter()
-                    .copied()
-                    .find(|&mode| mode == wgpu::PresentMode::FifoRelaxed)
-            })
-            .unwrap_or(surface_caps.present_modes[0]);
+        // Shaders output linear color; render through an sRGB view so encoding happens once at output
+        let render_format = surface_format.add_srgb_suffix();
+        let view_formats = if render_format != surface_format {
+            vec![render_format]
+        } else {
+            vec![]
+        };
+
+        let present_mode = choose_present_mode(present_mode, &surface_caps.present_modes);
+
+        // Copy-source frames allow screenshots where the platform supports it
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+            | (surface_caps.usages & wgpu::TextureUsages::COPY_SRC);
 
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage,
             format: surface_format,
             width: size.width,
             height: size.height,
             present_mode,
             alpha_mode: surface_caps.alpha_modes[0],
-            view_formats: vec![],
+            view_formats,
             desired_maximum_frame_latency: 2,
         };
 
         surface.configure(&device, &config);
 
-        // Log the selected present mode for verification
-        let mode_name = match present_mode {
-            wgpu::PresentMode::Fifo => "Fifo (V-sync, 60 FPS)",
-            wgpu::PresentMode::FifoRelaxed => "FifoRelaxed (Adaptive V-sync)",
-            wgpu::PresentMode::Immediate => "Immediate (Unlimited FPS)",
-            wgpu::PresentMode::Mailbox => "Mailbox (Triple buffering)",
-            wgpu::PresentMode::AutoVsync => "AutoVsync",
-            wgpu::PresentMode::AutoNoVsync => "AutoNoVsync",
-        };
-        println!("🖥️  Present mode: {}", mode_name);
+        println!("🖥️  Present mode: {}", present_mode_name(present_mode));
+        println!("🎨 Surface format: {:?} (rendering as {:?})", surface_format, render_format);
 
         let context = Self {
             surface,
@@ -108,23 +267,230 @@ impl WgpuContext {
             config,
             size,
             window,
+            capabilities,
+            fullscreen_mode: FullscreenMode::default(),
+            present_modes: surface_caps.present_modes,
+            title: DEFAULT_WINDOW_TITLE.to_string(),
         };
 
         Ok((context, event_loop))
     }
 
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
-        if new_size.width > 0 && new_size.height > 0 {
+        if resize_config(&mut self.config, new_size) {
             self.size = new_size;
-            self.config.width = new_size.width;
-            self.config.height = new_size.height;
             self.surface.configure(&self.device, &self.config);
         }
     }
 
+    pub fn present_mode(&self) -> wgpu::PresentMode {
+        self.config.present_mode
+    }
+
+    /// Switch present mode at runtime; returns the mode in use after falling back if needed
+    pub fn set_present_mode(&mut self, requested: wgpu::PresentMode) -> wgpu::PresentMode {
+        let present_mode = choose_present_mode(requested, &self.present_modes);
+        if present_mode != self.config.present_mode {
+            self.config.present_mode = present_mode;
+            self.surface.configure(&self.device, &self.config);
+            println!("🖥️  Present mode: {}", present_mode_name(present_mode));
+        }
+        present_mode
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.window.fullscreen().is_some()
+    }
+
+    /// Enter or leave fullscreen in `fullscreen_mode`; returns whether the window is now fullscreen
+    ///
+    /// The surface is resized right away; the `Resized` event that follows confirms the final size.
+    pub fn set_fullscreen(&mut self, enabled: bool) -> bool {
+        let fullscreen = enabled.then(|| self.fullscreen_target());
+        self.window.set_fullscreen(fullscreen);
+        self.resize(self.window.inner_size());
+        self.is_fullscreen()
+    }
+
+    pub fn toggle_fullscreen(&mut self) -> bool {
+        self.set_fullscreen(!self.is_fullscreen())
+    }
+
+    /// Change the window title; an unchanged title skips the window system call
+    pub fn set_title(&mut self, title: &str) {
+        if title != self.title {
+            self.window.set_title(title);
+            self.title = title.to_string();
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Set the window (and taskbar) icon from PNG bytes, e.g. `DEFAULT_WINDOW_ICON_PNG`
+    pub fn set_icon_png(&self, png: &[u8]) -> Result<()> {
+        let (rgba, width, height) = decode_icon_png(png)?;
+        let icon = Icon::from_rgba(rgba, width, height).map_err(|e| anyhow!("Invalid window icon: {}", e))?;
+        self.window.set_window_icon(Some(icon));
+        Ok(())
+    }
+
+    /// Exclusive mode uses the current monitor's largest, fastest video mode, falling back to
+    /// borderless when none is reported
+    fn fullscreen_target(&self) -> Fullscreen {
+        if self.fullscreen_mode == FullscreenMode::Exclusive {
+            let video_mode = self.window.current_monitor().and_then(|monitor| {
+                monitor.video_modes().max_by_key(|mode| {
+                    let size = mode.size();
+                    (size.width * size.height, mode.refresh_rate_millihertz())
+                })
+            });
+            if let Some(video_mode) = video_mode {
+                return Fullscreen::Exclusive(video_mode);
+            }
+        }
+        Fullscreen::Borderless(None)
+    }
+
+    /// View of the surface texture in the linear-to-sRGB render format
+    pub fn create_output_view(&self, output: &wgpu::SurfaceTexture) -> wgpu::TextureView {
+        output.texture.create_view(&wgpu::TextureViewDescriptor {
+            format: Some(render_format(&self.config)),
+            ..Default::default()
+        })
+    }
+
     pub fn get_current_texture(&self) -> Result<wgpu::SurfaceTexture> {
         self.surface
             .get_current_texture()
             .map_err(|e| anyhow::anyhow!("Failed to acquire next swap chain texture: {}", e))
     }
-}
\ No newline at end of file
+}
+
+/// Apply a new window size to the surface configuration; zero sizes (minimized windows)
+/// are ignored. Returns whether the size was applied.
+pub fn resize_config(config: &mut SurfaceConfiguration, new_size: winit::dpi::PhysicalSize<u32>) -> bool {
+    if new_size.width == 0 || new_size.height == 0 {
+        return false;
+    }
+    config.width = new_size.width;
+    config.height = new_size.height;
+    true
+}
+
+/// Format pipelines render into: the sRGB view of the surface when one was registered
+pub fn render_format(config: &SurfaceConfiguration) -> wgpu::TextureFormat {
+    config.view_formats.first().copied().unwrap_or(config.format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(format: wgpu::TextureFormat, view_formats: Vec<wgpu::TextureFormat>) -> SurfaceConfiguration {
+        SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width: 800,
+            height: 600,
+            present_mode: wgpu::PresentMode::Fifo,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats,
+            desired_maximum_frame_latency: 2,
+        }
+    }
+
+    #[test]
+    fn test_embedded_icon_decodes() {
+        let (rgba, width, height) = decode_icon_png(DEFAULT_WINDOW_ICON_PNG).unwrap();
+        assert_eq!((width, height), (64, 64));
+        assert_eq!(rgba.len(), (width * height * 4) as usize);
+        assert!(Icon::from_rgba(rgba, width, height).is_ok());
+
+        assert!(decode_icon_png(b"not a png").is_err());
+    }
+
+    #[test]
+    fn test_render_format_prefers_srgb_view() {
+        let config = test_config(
+            wgpu::TextureFormat::Bgra8Unorm,
+            vec![wgpu::TextureFormat::Bgra8UnormSrgb],
+        );
+        assert_eq!(render_format(&config), wgpu::TextureFormat::Bgra8UnormSrgb);
+
+        let config = test_config(wgpu::TextureFormat::Rgba8UnormSrgb, vec![]);
+        assert_eq!(render_format(&config), wgpu::TextureFormat::Rgba8UnormSrgb);
+    }
+
+    #[test]
+    fn test_present_mode_falls_back_to_supported() {
+        use wgpu::PresentMode::{Fifo, FifoRelaxed, Immediate, Mailbox};
+
+        let all = [Fifo, FifoRelaxed, Immediate, Mailbox];
+        for mode in [Fifo, Immediate, Mailbox] {
+            assert_eq!(choose_present_mode(mode, &all), mode);
+        }
+
+        // Uncapped requests prefer the other uncapped mode before V-sync
+        assert_eq!(choose_present_mode(Mailbox, &[Fifo, Immediate]), Immediate);
+        assert_eq!(choose_present_mode(Immediate, &[Fifo, Mailbox]), Mailbox);
+        assert_eq!(choose_present_mode(Immediate, &[Fifo]), Fifo);
+        assert_eq!(choose_present_mode(Fifo, &[FifoRelaxed, Immediate]), FifoRelaxed);
+        assert_eq!(choose_present_mode(Fifo, &[]), Fifo);
+    }
+
+    #[test]
+    fn test_resize_updates_resolution_uniforms() {
+        use crate::audio::{AudioFeatures, RhythmFeatures};
+        use crate::rendering::UniformManager;
+
+        let mut config = test_config(wgpu::TextureFormat::Bgra8UnormSrgb, vec![]);
+        let mut manager = UniformManager::new();
+        let mut uniforms_for = |config: &SurfaceConfiguration| {
+            manager.map_audio_data(&AudioFeatures::new(), &RhythmFeatures::new(), (config.width, config.height), None, 1.0)
+        };
+
+        // Going fullscreen on a 1080p monitor
+        assert!(resize_config(&mut config, winit::dpi::PhysicalSize::new(1920, 1080)));
+        let uniforms = uniforms_for(&config);
+        assert_eq!((uniforms.resolution_x, uniforms.resolution_y), (1920.0, 1080.0));
+        assert!((uniforms.resolution_x / uniforms.resolution_y - 16.0 / 9.0).abs() < 1e-6);
+        assert!((uniforms.aa_width - 2.0 / 1080.0).abs() < 1e-9);
+
+        // Minimizing reports a zero size, which must not reach the shaders
+        assert!(!resize_config(&mut config, winit::dpi::PhysicalSize::new(0, 0)));
+        assert_eq!(uniforms_for(&config).resolution_y, 1080.0);
+
+        // Back to a window on a HiDPI display (800x600 logical at 2x)
+        assert!(resize_config(&mut config, winit::dpi::LogicalSize::new(800, 600).to_physical(2.0)));
+        assert_eq!(uniforms_for(&config).resolution_x, 1600.0);
+    }
+
+    #[test]
+    fn test_backend_fallback_picks_first_available() {
+        // Mocked availability: no GPU needed, Vulkan and Metal fail to initialize
+        let available = [wgpu::Backends::DX12, wgpu::Backends::GL];
+        let init = |backend: wgpu::Backends| async move {
+            if available.contains(&backend) {
+                Ok(backend_name(backend))
+            } else {
+                Err(anyhow!("not available"))
+            }
+        };
+
+        let (backend, name) = pollster::block_on(first_working_backend(&backend_candidates(None), init)).unwrap();
+        assert_eq!(backend, wgpu::Backends::DX12);
+        assert_eq!(name, "DX12");
+
+        // A forced backend is the only one tried
+        let (backend, _) = pollster::block_on(first_working_backend(&backend_candidates(Some(wgpu::Backends::GL)), init)).unwrap();
+        assert_eq!(backend, wgpu::Backends::GL);
+        let error = pollster::block_on(first_working_backend(&backend_candidates(Some(wgpu::Backends::VULKAN)), init)).unwrap_err();
+        assert!(error.to_string().contains("Vulkan: not available"));
+
+        assert_eq!(parse_backend("Vulkan").unwrap(), wgpu::Backends::VULKAN);
+        assert_eq!(parse_backend("opengl").unwrap(), wgpu::Backends::GL);
+        assert!(parse_backend("glide").is_err());
+    }
+}
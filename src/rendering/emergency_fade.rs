@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use crate::control::{LUMINANCE_CHANGE_LIMIT, SAFETY_COOLDOWN_SECONDS};
+
+/// Time to fade to black when emergency stop engages, and back up on resume
+pub const DEFAULT_EMERGENCY_FADE_DURATION: Duration = Duration::from_millis(500);
+
+/// Rough relative luminance of the scene, for the emergency fade to start from
+///
+/// There is no per-frame readback, so this leans on the shaders scaling their output
+/// by the overall volume and the safety brightness allowance.
+pub fn estimated_scene_luminance(overall_volume: f32, brightness_range: f32) -> f32 {
+    (overall_volume * brightness_range).clamp(0.0, 1.0)
+}
+
+/// Brightness envelope for emergency stop: down to black while stopped, back up after
+///
+/// A fade down and back up is one flash, so the duration is never shorter than the
+/// safety cooldown; that keeps even a rapid stop/resume cycle under `FLASH_RATE_LIMIT_HZ`.
+/// Each frame's step is also capped at `LUMINANCE_CHANGE_LIMIT`, like `IdleFade`.
+#[derive(Debug, Clone)]
+pub struct EmergencyFade {
+    stopped: bool,
+    level: f32,           // Scene brightness share (1.0 = normal, 0.0 = black)
+    start_luminance: f32, // Scene luminance when the stop engaged
+    duration: Duration,
+}
+
+impl EmergencyFade {
+    pub fn new() -> Self {
+        Self {
+            stopped: false,
+            level: 1.0,
+            start_luminance: 0.0,
+            duration: DEFAULT_EMERGENCY_FADE_DURATION,
+        }
+    }
+
+    /// Full fade length, raised to the safety cooldown if shorter
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration.max(Duration::from_secs_f32(SAFETY_COOLDOWN_SECONDS));
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Fade toward black (true) or back to the scene (false); `scene_luminance` is where a new stop starts from
+    pub fn set_stopped(&mut self, stopped: bool, scene_luminance: f32) {
+        if stopped && !self.stopped {
+            self.start_luminance = scene_luminance.clamp(0.0, 1.0);
+        }
+        self.stopped = stopped;
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Advance the fade by one frame and return the new level
+    pub fn update(&mut self, frame_time: Duration) -> f32 {
+        let step = (frame_time.as_secs_f32() / self.duration.as_secs_f32()).min(LUMINANCE_CHANGE_LIMIT);
+        let target = if self.stopped { 0.0 } else { 1.0 };
+        self.level = if self.level < target {
+            (self.level + step).min(target)
+        } else {
+            (self.level - step).max(target)
+        };
+        self.level
+    }
+
+    /// Current scene brightness share (1.0 = fully visible)
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Gray level (linear light) to clear to while stopped
+    pub fn clear_luminance(&self) -> f32 {
+        self.start_luminance * self.level
+    }
+}
+
+impl Default for EmergencyFade {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    #[test]
+    fn test_fade_down_is_monotonic_and_reaches_black_in_time() {
+        let mut fade = EmergencyFade::new();
+        fade.set_stopped(true, 0.6);
+
+        let frames = (DEFAULT_EMERGENCY_FADE_DURATION.as_secs_f32() / FRAME.as_secs_f32()).ceil() as usize;
+        let mut previous = fade.clear_luminance();
+        assert!((previous - 0.6).abs() < 1e-6);
+        for _ in 0..frames {
+            fade.update(FRAME);
+            let luminance = fade.clear_luminance();
+            assert!(luminance <= previous);
+            assert!(previous - luminance <= 0.6 * LUMINANCE_CHANGE_LIMIT + 1e-6);
+            previous = luminance;
+        }
+        assert_eq!(fade.level(), 0.0);
+        assert_eq!(fade.clear_luminance(), 0.0);
+    }
+
+    #[test]
+    fn test_resume_ramps_back_up() {
+        let mut fade = EmergencyFade::new();
+        fade.set_stopped(true, 0.5);
+        for _ in 0..40 {
+            fade.update(FRAME);
+        }
+
+        fade.set_stopped(false, 0.0);
+        let mut previous = fade.level();
+        for _ in 0..40 {
+            let level = fade.update(FRAME);
+            assert!(level >= previous);
+            previous = level;
+        }
+        assert_eq!(fade.level(), 1.0);
+
+        // A stalled frame can't jump the whole way
+        fade.set_stopped(true, 0.5);
+        assert!(fade.update(Duration::from_secs(1)) >= 1.0 - LUMINANCE_CHANGE_LIMIT);
+    }
+
+    #[test]
+    fn test_duration_respects_flash_rate_limit() {
+        let mut fade = EmergencyFade::new();
+        fade.set_duration(Duration::from_millis(50));
+        assert!(fade.duration().as_secs_f32() >= SAFETY_COOLDOWN_SECONDS);
+        fade.set_duration(Duration::from_secs(2));
+        assert_eq!(fade.duration(), Duration::from_secs(2));
+    }
+}
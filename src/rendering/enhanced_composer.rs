@@ -1,10 +1,13 @@
 use wgpu::util::DeviceExt;
 use bytemuck::{Pod, Zeroable};
 use anyhow::Result;
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use crate::audio::{AudioFeatures, RhythmFeatures};
-use super::{WgpuContext, ShaderSystem, ShaderType, PerformanceManager, PerformanceMetrics, QualityLevel, OverlaySystem};
+use crate::control::ColorPalette;
+use super::{AdaptiveThresholds, WgpuContext, render_format, ShaderSystem, ShaderType, EffectWeights, EasingCurve, FractalMode, PerformanceManager, PerformanceMetrics, QualityLevel, QualityChangeEvent, QualityTransition, OverlaySystem, debug_overlay_lines, TrailSystem, BloomSystem, IdleFade, IdleScreen, IdleUniforms, EmergencyFade, estimated_scene_luminance, VuMeter, ScreenShake, FrameNotifier, FrameCallback, FrameInfo, FrameEncoder, ScreenshotReadback, ShaderSelectionConfig, GpuTimer, gpu_time_or_estimate, DEFAULT_AUTO_SHADER_COOLDOWN, DownbeatQuantizer, check_screenshot_support, DEFAULT_TRAIL_DECAY};
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
@@ -36,63 +39,130 @@ const VERTICES: &[Vertex] = &[
 
 const INDICES: &[u16] = &[0, 1, 2, 2, 3, 0];
 
+/// Full-screen quad every shader pipeline draws with
+pub(super) struct QuadBuffers {
+    pub(super) vertex_buffer: wgpu::Buffer,
+    pub(super) index_buffer: wgpu::Buffer, // `Uint16` indices
+    pub(super) index_count: u32,
+}
+
+/// Upload the full-screen quad's vertices and indices
+pub(super) fn create_quad_buffers(device: &wgpu::Device) -> QuadBuffers {
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Enhanced Vertex Buffer"),
+        contents: bytemuck::cast_slice(VERTICES),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+
+    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Enhanced Index Buffer"),
+        contents: bytemuck::cast_slice(INDICES),
+        usage: wgpu::BufferUsages::INDEX,
+    });
+
+    QuadBuffers {
+        vertex_buffer,
+        index_buffer,
+        index_count: INDICES.len() as u32,
+    }
+}
+
 /// Enhanced frame composer using the new shader system architecture
 pub struct EnhancedFrameComposer {
     shader_system: ShaderSystem,
     overlay_system: OverlaySystem,
-    vertex_buffer: wgpu::Buffer,
-    index_buffer: wgpu::Buffer,
+    trail_system: TrailSystem,
+    bloom_system: BloomSystem,
+    idle_screen: IdleScreen,
+    idle_fade: IdleFade, // Crossfade to the idle pattern while the input is silent
+    emergency_fade: EmergencyFade, // Fade to black on emergency stop, and back on resume
+    scene_luminance: f32, // Estimated luminance of the last rendered frame
+    created_at: Instant, // Drives the idle pattern, which ignores audio
     performance_manager: PerformanceManager,
+    quality_transition: QualityTransition, // Smooths quality-derived scalars after level changes
     frame_start_time: Option<Instant>,
     last_auto_shader_switch: Instant,
     auto_shader_cooldown: std::time::Duration,
+    selection_config: ShaderSelectionConfig, // Feature thresholds for auto shader selection
+    transition_on_downbeat: bool, // Hold auto shader switches for the next downbeat
+    downbeat_quantizer: DownbeatQuantizer,
     // Overlay state
     show_debug_overlay: bool,
     show_control_panel: bool,
+    show_beat_grid: bool,
     mouse_position: (f32, f32),
     mouse_pressed: bool,
+    transport_playing: bool,
+    transport_position: Option<f32>, // Fraction of the loaded track, if seekable
+    vu_meter: VuMeter,
+    screen_shake: ScreenShake,
+    frame_notifier: FrameNotifier, // Frame-ready hook for external compositors
+    screenshot_request: Cell<Option<PathBuf>>, // PNG path for the next presented frame
 }
 
 impl EnhancedFrameComposer {
     pub fn new(context: &WgpuContext) -> Result<Self> {
         // Initialize shader system
-        let shader_system = ShaderSystem::new(&context.device, &context.config)?;
+        let mut shader_system = ShaderSystem::new(&context.device, &context.config)?;
 
         // Initialize overlay system
         let overlay_system = OverlaySystem::new(context)?;
 
-        // Create vertex buffer
-        let vertex_buffer = context
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Enhanced Vertex Buffer"),
-                contents: bytemuck::cast_slice(VERTICES),
-                usage: wgpu::BufferUsages::VERTEX,
-            });
+        // Frame feedback for motion trails (disabled until a decay is set)
+        let trail_system = TrailSystem::new(&context.device, render_format(&context.config), context.config.width, context.config.height);
 
-        // Create index buffer
-        let index_buffer = context
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Enhanced Index Buffer"),
-                contents: bytemuck::cast_slice(INDICES),
-                usage: wgpu::BufferUsages::INDEX,
-            });
+        // Beat-driven glow over every shader (disabled until requested)
+        let bloom_system = BloomSystem::new(&context.device, render_format(&context.config), context.config.width, context.config.height);
+
+        // Gentle pattern shown while nothing is playing
+        let idle_screen = IdleScreen::new(&context.device, render_format(&context.config));
+
+        let mut performance_manager = PerformanceManager::new(60.0); // Until `set_target_fps`
+        if context.capabilities.software_rendering {
+            // Software adapters can't sustain anything heavier
+            performance_manager.set_quality(context.capabilities.recommended_quality);
+        }
+        let quality_transition = QualityTransition::new(performance_manager.current_quality());
+
+        // Real GPU timings where supported; otherwise gpu_time stays a frame-time estimate
+        let gpu_timer = GpuTimer::new(&context.device, &context.queue);
+        if gpu_timer.is_some() {
+            println!("⏱️  GPU timestamp queries enabled");
+        } else {
+            println!("⏱️  GPU timestamp queries unavailable, estimating GPU time");
+        }
+        shader_system.set_gpu_timer(gpu_timer);
 
         Ok(Self {
             shader_system,
             overlay_system,
-            vertex_buffer,
-            index_buffer,
-            performance_manager: PerformanceManager::new(60.0), // Target 60 FPS
+            trail_system,
+            bloom_system,
+            idle_screen,
+            idle_fade: IdleFade::new(),
+            emergency_fade: EmergencyFade::new(),
+            scene_luminance: 0.0,
+            created_at: Instant::now(),
+            performance_manager,
+            quality_transition,
             frame_start_time: None,
             last_auto_shader_switch: Instant::now(),
-            auto_shader_cooldown: std::time::Duration::from_millis(2500), // 2.5 seconds between switches
+            auto_shader_cooldown: DEFAULT_AUTO_SHADER_COOLDOWN,
+            selection_config: ShaderSelectionConfig::default(),
+            transition_on_downbeat: false,
+            downbeat_quantizer: DownbeatQuantizer::default(),
             // Overlay state defaults
             show_debug_overlay: true,  // Show debug overlay by default
             show_control_panel: true,  // Show control panel by default
+            show_beat_grid: false,     // Beat grid is opt-in, for beatmatching
             mouse_position: (0.0, 0.0),
             mouse_pressed: false,
+            transport_playing: true,
+            transport_position: None,
+            vu_meter: VuMeter::new(),
+            screen_shake: ScreenShake::new(),
+            frame_notifier: FrameNotifier::new(),
+            screenshot_request: Cell::new(None),
         })
     }
 
@@ -105,41 +175,91 @@ impl EnhancedFrameComposer {
         safety_multipliers: Option<crate::control::safety::SafetyMultipliers>,
         volume: f32,
     ) -> Result<()> {
-        // Check for emergency stop - if active, render black screen instead of shaders
-        if let Some(ref multipliers) = safety_multipliers {
-            if multipliers.beat_intensity == 0.0 && multipliers.brightness_range <= 0.1 {
-                // Emergency stop is active - render solid black screen
-                return self.render_emergency_blackout(context);
-            }
-        }
-
         // Start frame timing
         let frame_start = Instant::now();
+        let frame_interval = self.frame_start_time.map_or(Duration::ZERO, |previous| frame_start - previous);
         self.frame_start_time = Some(frame_start);
 
+        // Emergency stop freezes the scene and fades a flat gray from its last luminance to black;
+        // an instant cut would itself be a flash
+        let emergency_stopped = safety_multipliers
+            .is_some_and(|multipliers| multipliers.beat_intensity == 0.0 && multipliers.brightness_range <= 0.1);
+        self.emergency_fade.set_stopped(emergency_stopped, self.scene_luminance);
+        let emergency_level = self.emergency_fade.update(frame_interval);
+        if emergency_stopped {
+            return self.render_emergency_blackout(context, self.emergency_fade.clear_luminance());
+        }
+
+        // After a resume the scene ramps back up through the safety brightness allowance
+        let safety_multipliers = safety_multipliers.map(|mut multipliers| {
+            multipliers.brightness_range *= emergency_level;
+            multipliers
+        });
+        let idle_opacity = self.idle_fade.update(frame_interval);
+
         // Update shader system (handles transitions, etc.)
         self.shader_system.update(&context.device, &context.config)?;
 
         // Get surface texture
         let output = context.get_current_texture()?;
-        let view = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        let view = context.create_output_view(&output);
+
+        // Shader, trail and overlay passes are all recorded here and submitted once
+        let mut frame = FrameEncoder::new(&context.device);
+
+        // With trails enabled the shader renders offscreen and is composited afterwards
+        let trails_enabled = self.trail_system.is_enabled();
+        if trails_enabled {
+            self.trail_system.ensure_size(&context.device, context.config.width, context.config.height);
+        }
+        let composite_target = if trails_enabled { self.trail_system.scene_view() } else { &view };
+
+        // Bloom adds another offscreen stage in front of trails; skipped when quality rules out advanced effects
+        let bloom_active = self.bloom_system.is_enabled() && self.performance_manager.current_quality().enable_advanced_effects();
+        if bloom_active {
+            self.bloom_system.ensure_size(&context.device, context.config.width, context.config.height);
+        }
+        let shader_target = if bloom_active { self.bloom_system.scene_view() } else { composite_target };
+
+        // Bass hits nudge the whole image (scaled down or off by the safety level)
+        let shake = self.screen_shake.update(audio_features, safety_multipliers.as_ref());
+        self.shader_system.set_screen_shake(shake);
+        self.shader_system.set_safety_multipliers(safety_multipliers);
 
         // Render using shader system with performance awareness
-        let current_quality = self.performance_manager.current_quality();
+        self.quality_transition.set_target(self.performance_manager.current_quality());
+        let quality_uniforms = self.quality_transition.current();
         self.shader_system.render_with_quality(
-            &context.device,
             &context.queue,
-            &view,
-            &self.vertex_buffer,
-            &self.index_buffer,
-            INDICES.len() as u32,
+            &mut frame,
+            shader_target,
             audio_features,
             rhythm_features,
-            current_quality,
-            safety_multipliers,
+            &quality_uniforms,
         )?;
+        if let Some(gpu_timer) = self.shader_system.gpu_timer_mut() {
+            gpu_timer.resolve(&mut frame);
+        }
+
+        // Glow swells with the beat, within the safety level's beat allowance
+        if bloom_active {
+            let safety_beat = safety_multipliers.map_or(1.0, |s| s.beat_intensity);
+            let intensity = self.bloom_system.beat_intensity(rhythm_features.beat_strength, safety_beat);
+            self.bloom_system.apply(&context.queue, &mut frame, composite_target, intensity);
+        }
+
+        // Silence fades in the idle pattern, dimmed like everything else by the safety level
+        if idle_opacity > 0.0 {
+            let brightness = safety_multipliers.map_or(1.0, |s| s.brightness_range);
+            let time = frame_start.duration_since(self.created_at).as_secs_f32();
+            let uniforms = IdleUniforms::new(time, idle_opacity, brightness, (context.config.width, context.config.height));
+            self.idle_screen.draw(&context.queue, &mut frame, composite_target, uniforms);
+        }
+
+        // Blend in the decayed previous frame before overlays are drawn
+        if trails_enabled {
+            self.trail_system.composite(&context.queue, &mut frame, &view);
+        }
 
         // Update overlay system state
         self.overlay_system.update(
@@ -147,9 +267,11 @@ impl EnhancedFrameComposer {
             self.mouse_pressed,
             self.show_debug_overlay,
             self.show_control_panel,
+            self.show_beat_grid,
         );
 
         // Create overlay uniforms with current state
+        self.vu_meter.update(audio_features.overall_volume);
         let overlay_uniforms = self.create_overlay_uniforms(
             audio_features,
             rhythm_features,
@@ -158,45 +280,117 @@ impl EnhancedFrameComposer {
             volume,
         );
 
+        // Debug readouts as text, formatted only while the panel is showing
+        if self.show_debug_overlay {
+            let lines = debug_overlay_lines(&overlay_uniforms, self.current_shader().name());
+            self.overlay_system.set_debug_lines(lines);
+        }
+
         // Render overlay shaders on top of main visualization
-        if let Err(e) = self.overlay_system.render(context, &view, &overlay_uniforms) {
+        if let Err(e) = self.overlay_system.render(&context.queue, &mut frame, &view, &overlay_uniforms) {
             eprintln!("Overlay rendering error: {}", e);
             // Continue without overlays rather than crash
         }
 
+        // Copy the finished frame for a requested screenshot before it's presented
+        let screenshot = self.screenshot_request.take().and_then(|path| {
+            match ScreenshotReadback::record(&context.device, &mut frame, &output.texture) {
+                Ok(readback) => Some((path, readback)),
+                Err(e) => {
+                    eprintln!("Screenshot failed: {}", e);
+                    None
+                }
+            }
+        });
+
+        frame.submit(&context.queue);
         output.present();
+        if let Some(gpu_timer) = self.shader_system.gpu_timer_mut() {
+            gpu_timer.after_submit();
+        }
+
+        // Waits only for this frame's GPU work, so the loop stalls by at most one frame
+        if let Some((path, readback)) = screenshot {
+            match readback.save_png(&context.device, &path) {
+                Ok(()) => println!("📸 Screenshot saved: {}", path.display()),
+                Err(e) => eprintln!("Screenshot failed: {}", e),
+            }
+        }
 
         // Update performance metrics
         let frame_time = frame_start.elapsed();
+        let measured_gpu_time = self.shader_system.gpu_timer_mut().and_then(|timer| timer.collect(&context.device));
         let metrics = PerformanceMetrics {
             frame_time,
             cpu_time: frame_time, // Simplified - in real app would measure separately
-            gpu_time: Duration::from_secs_f32(frame_time.as_secs_f32() * 0.7), // Estimate GPU portion
+            gpu_time: gpu_time_or_estimate(measured_gpu_time, frame_time), // Shader pass timestamps when supported
             fps: 1.0 / frame_time.as_secs_f32(),
             dropped_frames: if frame_time.as_millis() > 20 { 1 } else { 0 },
             memory_usage_mb: 150.0, // Estimate
         };
 
-        let quality_changed = self.performance_manager.update(metrics);
+        let quality_changed = self.performance_manager.update(metrics.clone());
 
         // Log performance adjustments
         if quality_changed {
             println!("📊 {}", self.performance_manager.performance_report());
         }
 
+        self.frame_notifier.notify(self.current_shader(), &metrics);
+
+        let brightness = safety_multipliers.map_or(1.0, |s| s.brightness_range);
+        self.scene_luminance = estimated_scene_luminance(audio_features.overall_volume, brightness);
+
+        Ok(())
+    }
+
+    /// Save the next rendered frame (with overlays) as a PNG at `path`
+    ///
+    /// The copy is recorded into that frame and written right after it's presented, so
+    /// errors from the write itself are logged rather than returned.
+    pub fn capture_screenshot(&self, context: &WgpuContext, path: &Path) -> Result<()> {
+        check_screenshot_support(&context.config)?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if !parent.is_dir() {
+                return Err(anyhow::anyhow!("Screenshot directory {} does not exist", parent.display()));
+            }
+        }
+
+        self.screenshot_request.set(Some(path.to_path_buf()));
         Ok(())
     }
 
     /// Switch to a different shader mode
     pub fn set_shader(&mut self, shader_type: ShaderType, context: &WgpuContext) -> Result<()> {
+        self.downbeat_quantizer.cancel(); // An explicit choice replaces any queued auto switch
         self.shader_system.set_shader(shader_type, &context.device, &context.config)
     }
 
     /// Set shader immediately without transition animation (for manual user input)
     pub fn set_shader_immediately(&mut self, shader_type: ShaderType, context: &WgpuContext) -> Result<()> {
+        self.downbeat_quantizer.cancel();
         self.shader_system.set_shader_immediately(shader_type, &context.device, &context.config)
     }
 
+    /// Easing curve for shader crossfades (linear by default)
+    pub fn set_transition_easing(&mut self, easing: EasingCurve) {
+        self.shader_system.set_transition_easing(easing);
+    }
+
+    /// Show a user-defined palette of (position, RGB) stops, at most `MAX_CUSTOM_PALETTE_STOPS`
+    pub fn set_custom_palette(&mut self, stops: Vec<(f32, [f32; 3])>, context: &WgpuContext) -> Result<()> {
+        self.shader_system.set_custom_palette(&context.queue, stops)
+    }
+
+    /// Blend continuously across the built-in palettes, e.g. from spectral centroid (0.0-8.0, wrapping)
+    pub fn set_palette_position(&mut self, position: f32) {
+        self.shader_system.set_palette_position(position);
+    }
+
+    pub fn palette_position(&self) -> Option<f32> {
+        self.shader_system.palette_position()
+    }
+
     /// Get the currently active shader
     pub fn current_shader(&self) -> ShaderType {
         self.shader_system.current_shader()
@@ -234,36 +428,258 @@ impl EnhancedFrameComposer {
                              audio_features: &AudioFeatures,
                              rhythm_features: &RhythmFeatures) -> Result<()> {
         let current = self.current_shader();
+        let now = Instant::now();
+
+        // A switch queued for the downbeat starts now, or once the wait times out
+        if let Some(queued_shader) = self.downbeat_quantizer.update(now, rhythm_features.downbeat_detected) {
+            println!("🥁 Auto-selecting shader on downbeat: {}", queued_shader.name());
+            self.set_shader(queued_shader, context)?;
+            self.last_auto_shader_switch = now;
+            return Ok(());
+        }
 
         // Intelligent shader selection based on audio characteristics
         let recommended_shader = self.analyze_audio_for_shader(audio_features, rhythm_features);
 
         if recommended_shader != current {
             // Check cooldown to prevent rapid switching and console spam
-            let now = Instant::now();
             let time_since_last_switch = now.duration_since(self.last_auto_shader_switch);
 
             if time_since_last_switch >= self.auto_shader_cooldown {
-                println!("🤖 Auto-selecting shader: {} (based on audio analysis)", recommended_shader.name());
-                self.set_shader(recommended_shader, context)?;
-                self.last_auto_shader_switch = now;
+                if self.transition_on_downbeat {
+                    self.downbeat_quantizer.request(recommended_shader, now);
+                } else {
+                    println!("🤖 Auto-selecting shader: {} (based on audio analysis)", recommended_shader.name());
+                    self.set_shader(recommended_shader, context)?;
+                    self.last_auto_shader_switch = now;
+                }
             }
             // If within cooldown, silently continue with current shader
+        } else {
+            // The audio came back around to the current shader before the downbeat
+            self.downbeat_quantizer.cancel();
         }
 
         Ok(())
     }
 
+    /// Call `callback` after every rendered visualization frame (None removes it; blackout frames are not reported)
+    /// Replace the thresholds auto shader selection uses (e.g. gentler for ambient, quicker for EDM)
+    pub fn set_selection_config(&mut self, config: ShaderSelectionConfig) {
+        self.selection_config = config;
+    }
+
+    pub fn selection_config(&self) -> &ShaderSelectionConfig {
+        &self.selection_config
+    }
+
+    /// Minimum time between automatic shader switches
+    pub fn set_auto_shader_cooldown(&mut self, cooldown: Duration) {
+        self.auto_shader_cooldown = cooldown;
+    }
+
+    pub fn auto_shader_cooldown(&self) -> Duration {
+        self.auto_shader_cooldown
+    }
+
+    /// Start auto shader switches on the next detected downbeat instead of mid-phrase
+    pub fn set_transition_on_downbeat(&mut self, enabled: bool) {
+        self.transition_on_downbeat = enabled;
+        if !enabled {
+            self.downbeat_quantizer.cancel();
+        }
+    }
+
+    pub fn transition_on_downbeat(&self) -> bool {
+        self.transition_on_downbeat
+    }
+
+    /// Longest a downbeat-quantized switch waits before starting anyway
+    pub fn set_downbeat_timeout(&mut self, timeout: Duration) {
+        self.downbeat_quantizer.set_timeout(timeout);
+    }
+
+    /// Auto switch waiting for the next downbeat, if any
+    pub fn pending_shader(&self) -> Option<ShaderType> {
+        self.downbeat_quantizer.pending()
+    }
+
+    pub fn set_frame_callback(&mut self, callback: Option<FrameCallback>) {
+        self.frame_notifier.set_callback(callback);
+    }
+
+    /// Visualization frames rendered so far (blackout frames excluded)
+    pub fn frames_rendered(&self) -> u64 {
+        self.frame_notifier.frame_count()
+    }
+
+    /// Receive frame-ready notifications over a channel instead of a callback
+    pub fn frame_channel(&mut self) -> std::sync::mpsc::Receiver<FrameInfo> {
+        self.frame_notifier.channel()
+    }
+
+    /// Fix the kaleidoscope mirror count (None = follow the music)
+    pub fn set_kaleidoscope_segments(&mut self, segments: Option<u32>) {
+        self.shader_system.set_kaleidoscope_segments(segments);
+    }
+
+    /// Upload the newest time-domain samples for the oscilloscope shader
+    pub fn set_waveform(&self, context: &WgpuContext, samples: &[f32]) {
+        self.shader_system.set_waveform(&context.queue, samples);
+    }
+
+    /// Scroll one frame of FFT magnitudes into the spectrogram shader's history
+    pub fn push_spectrum(&mut self, context: &WgpuContext, bins: &[f32]) {
+        self.shader_system.push_spectrum(&context.queue, bins);
+    }
+
+    /// Change how many frames the spectrogram spans (default `DEFAULT_SPECTROGRAM_COLUMNS`)
+    pub fn set_spectrogram_columns(&mut self, columns: usize, context: &WgpuContext) -> Result<()> {
+        self.shader_system.set_spectrogram_columns(columns, &context.device, &context.config)
+    }
+
+    /// Tint the visualization toward a color temperature in Kelvin (overlays stay untinted)
+    pub fn set_white_balance(&mut self, kelvin: f32) {
+        self.shader_system.set_white_balance(kelvin);
+    }
+
+    pub fn white_balance(&self) -> f32 {
+        self.shader_system.white_balance()
+    }
+
+    /// Tint on a -1.0 (cool) to 1.0 (warm) scale, clamped; the same setting as `set_white_balance`.
+    /// Applied after the safety brightness cap, and it only ever dims channels.
+    pub fn set_color_temperature(&mut self, temperature: f32) {
+        self.shader_system.set_color_temperature(temperature);
+    }
+
+    pub fn color_temperature(&self) -> f32 {
+        self.shader_system.color_temperature()
+    }
+
+    /// Switch the Spectralizer between a logarithmic (default) and linear frequency axis
+    pub fn set_spectralizer_log_scale(&mut self, enabled: bool) {
+        self.shader_system.set_spectralizer_log_scale(enabled);
+    }
+
+    pub fn spectralizer_log_scale(&self) -> bool {
+        self.shader_system.spectralizer_log_scale()
+    }
+
+    /// Split the Spectralizer into the left channel spectrum (top) and the right (bottom)
+    pub fn set_spectralizer_stereo_split(&mut self, enabled: bool) {
+        self.shader_system.set_spectralizer_stereo_split(enabled);
+    }
+
+    pub fn spectralizer_stereo_split(&self) -> bool {
+        self.shader_system.spectralizer_stereo_split()
+    }
+
+    /// Upload left/right FFT magnitudes for the split Spectralizer
+    pub fn set_stereo_spectrum(&self, context: &WgpuContext, left: &[f32], right: &[f32]) {
+        self.shader_system.set_stereo_spectrum(&context.queue, left, right);
+    }
+
+    /// Draw the Fractal shader as the Mandelbrot blend (default) or the pitch-driven Julia set
+    pub fn set_fractal_mode(&mut self, mode: FractalMode) {
+        self.shader_system.set_fractal_mode(mode);
+    }
+
+    pub fn fractal_mode(&self) -> FractalMode {
+        self.shader_system.fractal_mode()
+    }
+
+    /// Set the multi-mode effect weights (normalized), e.g. from a recalled preset
+    pub fn set_effect_weights(&mut self, weights: EffectWeights) {
+        self.shader_system.set_effect_weights(weights);
+    }
+
+    pub fn effect_weights(&self) -> EffectWeights {
+        self.shader_system.effect_weights()
+    }
+
+    /// Apply each shader's recommended palette and effect weights when switching to it
+    pub fn set_shader_recommendations(&mut self, enabled: bool) {
+        self.shader_system.set_shader_recommendations(enabled);
+    }
+
+    pub fn shader_recommendations(&self) -> bool {
+        self.shader_system.shader_recommendations()
+    }
+
+    /// Pin a palette so shader defaults and downbeats leave it alone
+    pub fn lock_palette(&mut self, palette: ColorPalette) {
+        self.shader_system.lock_palette(palette);
+    }
+
+    /// Let shader defaults and downbeats choose the palette again
+    pub fn unlock_palette(&mut self) {
+        self.shader_system.unlock_palette();
+    }
+
+    pub fn current_palette(&self) -> ColorPalette {
+        self.shader_system.current_palette()
+    }
+
+    pub fn is_palette_locked(&self) -> bool {
+        self.shader_system.is_palette_locked()
+    }
+
+    /// Let the detected key drive the palette hue
+    pub fn set_key_sync(&mut self, enabled: bool) {
+        self.shader_system.set_key_sync(enabled);
+    }
+
+    pub fn is_key_synced(&self) -> bool {
+        self.shader_system.is_key_synced()
+    }
+
+    /// Fix the Classic shader's wave count and radial speed (None = follow the music)
+    pub fn set_classic_waves(&mut self, wave_count: Option<f32>, radial_speed: Option<f32>) {
+        self.shader_system.set_classic_waves(wave_count, radial_speed);
+    }
+
     /// Get current performance quality level
     pub fn current_quality(&self) -> QualityLevel {
         self.performance_manager.current_quality()
     }
 
-    /// Manually set performance quality level
+    /// Manually set performance quality level (adaptive logic may change it later)
     pub fn set_quality(&mut self, quality: QualityLevel) {
         self.performance_manager.set_quality(quality);
     }
 
+    /// Frame rate the adaptive quality aims for (matches the render loop's frame cap)
+    pub fn set_target_fps(&mut self, target_fps: f32) {
+        self.performance_manager.set_target_fps(target_fps);
+    }
+
+    /// Pin quality to a level, or return to adaptive quality with `None`
+    pub fn set_quality_override(&mut self, quality: Option<QualityLevel>) {
+        if let Some(q) = quality {
+            self.performance_manager.set_quality(q);
+        }
+        self.performance_manager.lock_quality(quality.is_some());
+    }
+
+    pub fn is_quality_locked(&self) -> bool {
+        self.performance_manager.is_quality_locked()
+    }
+
+    /// Freeze (false) or resume (true) automatic quality changes, e.g. for the length of a show
+    pub fn set_adaptive_quality(&mut self, adaptive: bool) {
+        self.performance_manager.set_adaptive(adaptive);
+    }
+
+    pub fn is_adaptive_quality(&self) -> bool {
+        self.performance_manager.is_adaptive()
+    }
+
+    /// Tune how eagerly adaptive quality steps down or up
+    pub fn set_adaptive_thresholds(&mut self, thresholds: AdaptiveThresholds) -> Result<()> {
+        self.performance_manager.set_thresholds(thresholds)
+    }
+
     /// Get performance metrics report
     pub fn performance_report(&self) -> String {
         self.performance_manager.performance_report()
@@ -274,11 +690,19 @@ impl EnhancedFrameComposer {
         self.performance_manager.average_fps()
     }
 
-    /// Render solid black screen for emergency stop
-    fn render_emergency_blackout(&mut self, context: &WgpuContext) -> Result<()> {
+    /// Timeline of adaptive quality decisions, oldest first
+    pub fn quality_history(&self) -> &std::collections::VecDeque<QualityChangeEvent> {
+        self.performance_manager.quality_history()
+    }
+
+    /// Render a flat gray screen (black once the fade completes) for emergency stop
+    fn render_emergency_blackout(&mut self, context: &WgpuContext, luminance: f32) -> Result<()> {
         // Get surface texture
         let output = context.get_current_texture()?;
-        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let view = context.create_output_view(&output);
+
+        // Clear values are linear light, so a gray at the luminance reproduces it on screen
+        let gray = luminance as f64;
 
         // Create command encoder for clear operation
         let mut encoder = context.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -293,7 +717,7 @@ impl EnhancedFrameComposer {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), // Solid black
+                        load: wgpu::LoadOp::Clear(wgpu::Color { r: gray, g: gray, b: gray, a: 1.0 }),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -301,7 +725,7 @@ impl EnhancedFrameComposer {
                 occlusion_query_set: None,
                 timestamp_writes: None,
             });
-            // Render pass automatically clears to black, no drawing needed
+            // Render pass clears to the fade's gray, no drawing needed
         }
 
         // Submit commands and present
@@ -311,40 +735,9 @@ impl EnhancedFrameComposer {
         Ok(())
     }
 
+    /// Recommend a shader using the current selection thresholds
     fn analyze_audio_for_shader(&self, audio: &AudioFeatures, rhythm: &RhythmFeatures) -> ShaderType {
-        // Analyze audio characteristics to recommend optimal shader
-
-        // High bass content -> Classic or Tunnel
-        if audio.bass + audio.sub_bass > 0.7 {
-            return if rhythm.tempo_confidence > 0.8 {
-                ShaderType::Tunnel // Strong rhythm + bass = tunnel effect
-            } else {
-                ShaderType::Classic // Just bass = classic waves
-            };
-        }
-
-        // High treble + onset activity -> Particle system
-        if audio.treble + audio.presence > 0.6 && audio.onset_strength > 0.5 {
-            return ShaderType::Particle;
-        }
-
-        // High pitch confidence + harmony -> Kaleidoscope
-        if audio.pitch_confidence > 0.7 && rhythm.rhythm_stability > 0.6 {
-            return ShaderType::Kaleidoscope;
-        }
-
-        // High spectral flux (dynamic changes) -> Parametric wave
-        if audio.spectral_flux > 0.4 {
-            return ShaderType::ParametricWave;
-        }
-
-        // High dynamic range -> Fractal
-        if audio.dynamic_range > 0.6 {
-            return ShaderType::Fractal;
-        }
-
-        // Default fallback
-        ShaderType::Classic
+        self.selection_config.analyze_audio_for_shader(audio, rhythm)
     }
 
     /// Create overlay uniforms with current state data
@@ -368,6 +761,9 @@ impl EnhancedFrameComposer {
             ShaderType::Particle => 5.0,
             ShaderType::Fractal => 6.0,
             ShaderType::Spectralizer => 7.0,
+            ShaderType::Oscilloscope => 8.0,
+            ShaderType::Spectrogram => 9.0,
+            ShaderType::Mixed => 10.0,
         };
 
         // Calculate current FPS and performance metrics from performance manager
@@ -403,6 +799,7 @@ impl EnhancedFrameComposer {
             tempo_confidence: rhythm_features.tempo_confidence,
             onset_detected: if rhythm_features.onset_detected { 1.0 } else { 0.0 },
             downbeat_detected: if rhythm_features.downbeat_detected { 1.0 } else { 0.0 },
+            beat_position: rhythm_features.beat_position as f32,
 
             // Copy spectral features
             spectral_centroid: audio_features.spectral_centroid,
@@ -422,7 +819,10 @@ impl EnhancedFrameComposer {
             show_debug_overlay: if self.show_debug_overlay { 1.0 } else { 0.0 },
             show_control_panel: if self.show_control_panel { 1.0 } else { 0.0 },
             ui_volume: volume, // Actual volume from audio processor
-            ui_is_playing: 1.0, // ASSUMPTION: Always playing for now
+            ui_is_playing: if self.transport_playing { 1.0 } else { 0.0 },
+            ui_playback_position: self.transport_position.unwrap_or(-1.0),
+            ui_meter_level: self.vu_meter.level(),
+            ui_meter_peak: self.vu_meter.peak(),
             ui_safety_level: safety_multipliers.map_or(1.0, |s| {
                 // Convert safety multipliers to level (0-4 scale)
                 if s.beat_intensity <= 0.1 { 0.0 } // UltraSafe
@@ -436,6 +836,9 @@ impl EnhancedFrameComposer {
             ui_current_shader_index: current_shader_index,
             ui_fps: current_fps,
             ui_frame_time: frame_time,
+            ui_quality_reason: self.performance_manager.last_quality_change().map_or(0.0, |e| e.reason.code()),
+            ui_software_renderer: if context.capabilities.software_rendering { 1.0 } else { 0.0 },
+            ui_quality_change_age: self.performance_manager.time_since_quality_change().map_or(0.0, |age| age.as_secs_f32()),
             screen_width: context.config.width as f32,
             screen_height: context.config.height as f32,
             text_scale: 1.0,
@@ -450,6 +853,12 @@ impl EnhancedFrameComposer {
         }
     }
 
+    /// Update playback state shown by the control panel transport row
+    pub fn set_transport_state(&mut self, playing: bool, position: Option<f32>) {
+        self.transport_playing = playing;
+        self.transport_position = position;
+    }
+
     /// Update mouse position for overlay interaction
     pub fn update_mouse_position(&mut self, x: f32, y: f32) {
         self.mouse_position = (x, y);
@@ -472,6 +881,12 @@ impl EnhancedFrameComposer {
         println!("🎛️ Control panel: {}", if self.show_control_panel { "ON" } else { "OFF" });
     }
 
+    /// Toggle beat grid visibility
+    pub fn toggle_beat_grid(&mut self) {
+        self.show_beat_grid = !self.show_beat_grid;
+        println!("🥁 Beat grid: {}", if self.show_beat_grid { "ON" } else { "OFF" });
+    }
+
     /// Set debug overlay visibility
     pub fn set_debug_overlay(&mut self, visible: bool) {
         self.show_debug_overlay = visible;
@@ -482,6 +897,69 @@ impl EnhancedFrameComposer {
         self.show_control_panel = visible;
     }
 
+    /// Set beat grid visibility
+    pub fn set_beat_grid(&mut self, visible: bool) {
+        self.show_beat_grid = visible;
+    }
+
+    /// Set motion trail decay (0.0 disables trails)
+    pub fn set_trail_decay(&mut self, decay: f32) {
+        self.trail_system.set_decay(decay);
+    }
+
+    /// Get current motion trail decay
+    pub fn trail_decay(&self) -> f32 {
+        self.trail_system.decay()
+    }
+
+    /// Toggle motion trails on/off
+    pub fn toggle_trails(&mut self) {
+        let decay = if self.trail_system.is_enabled() { 0.0 } else { DEFAULT_TRAIL_DECAY };
+        self.trail_system.set_decay(decay);
+        println!("✨ Motion trails: {}", if self.trail_system.is_enabled() { "ON" } else { "OFF" });
+    }
+
+    /// Fade to the idle pattern while the input is silent, and back once audio resumes
+    pub fn set_idle(&mut self, idle: bool) {
+        if idle != self.idle_fade.is_active() {
+            println!("{}", if idle { "🌙 Input silent - showing idle pattern" } else { "🎤 Audio resumed" });
+        }
+        self.idle_fade.set_active(idle);
+    }
+
+    /// Whether the idle pattern is on screen (including while fading out)
+    pub fn is_idle(&self) -> bool {
+        self.idle_fade.is_visible()
+    }
+
+    /// Time to fade to black on emergency stop and back on resume (never below the safety cooldown)
+    pub fn set_emergency_fade_duration(&mut self, duration: Duration) {
+        self.emergency_fade.set_duration(duration);
+    }
+
+    pub fn emergency_fade_duration(&self) -> Duration {
+        self.emergency_fade.duration()
+    }
+
+    /// Enable or disable the bloom post-process (only runs at quality levels with advanced effects)
+    pub fn set_bloom_enabled(&mut self, enabled: bool) {
+        self.bloom_system.set_enabled(enabled);
+        println!("🌟 Bloom: {}", if enabled { "ON" } else { "OFF" });
+    }
+
+    pub fn is_bloom_enabled(&self) -> bool {
+        self.bloom_system.is_enabled()
+    }
+
+    /// Set the bloom glow strength on a full-strength beat (0.0 to `MAX_BLOOM_INTENSITY`)
+    pub fn set_bloom_intensity(&mut self, intensity: f32) {
+        self.bloom_system.set_intensity(intensity);
+    }
+
+    pub fn bloom_intensity(&self) -> f32 {
+        self.bloom_system.intensity()
+    }
+
     /// Handle mouse click events and return overlay events
     pub fn handle_mouse_click(&self, x: f32, y: f32) -> Vec<super::OverlayEvent> {
         self.overlay_system.handle_mouse_click(x, y)
@@ -489,7 +967,7 @@ impl EnhancedFrameComposer {
 
     /// Check if overlay system is visible
     pub fn has_visible_overlays(&self) -> bool {
-        self.show_debug_overlay || self.show_control_panel
+        self.show_debug_overlay || self.show_control_panel || self.show_beat_grid
     }
 
     /// Get current mouse position
@@ -520,106 +998,4 @@ mod tests {
         assert_eq!(VERTICES[2].position, [1.0, 1.0, 0.0]);   // Top-right
         assert_eq!(VERTICES[3].position, [-1.0, 1.0, 0.0]);  // Top-left
     }
-
-    #[test]
-    fn test_audio_analysis_for_shader() {
-        use crate::audio::{AudioFeatures, RhythmFeatures};
-
-        // Create a mock composer struct to test the audio analysis method
-        struct MockComposer;
-
-        impl MockComposer {
-            fn analyze_audio_for_shader(&self, audio: &AudioFeatures, rhythm: &RhythmFeatures) -> ShaderType {
-                // High bass content -> Classic or Tunnel
-                if audio.bass + audio.sub_bass > 0.7 {
-                    return if rhythm.tempo_confidence > 0.8 {
-                        ShaderType::Tunnel
-                    } else {
-                        ShaderType::Classic
-                    };
-                }
-
-                // High treble + onset activity -> Particle system
-                if audio.treble + audio.presence > 0.6 && audio.onset_strength > 0.5 {
-                    return ShaderType::Particle;
-                }
-
-                // High pitch confidence + harmony -> Kaleidoscope
-                if audio.pitch_confidence > 0.7 && rhythm.rhythm_stability > 0.6 {
-                    return ShaderType::Kaleidoscope;
-                }
-
-                // High spectral flux -> Parametric wave
-                if audio.spectral_flux > 0.4 {
-                    return ShaderType::ParametricWave;
-                }
-
-                // High dynamic range -> Fractal
-                if audio.dynamic_range > 0.6 {
-                    return ShaderType::Fractal;
-                }
-
-                ShaderType::Classic
-            }
-        }
-
-        let composer = MockComposer;
-
-        // Test bass-heavy music
-        let bass_audio = AudioFeatures {
-            bass: 0.8,
-            sub_bass: 0.6,
-            ..AudioFeatures::new()
-        };
-        let high_tempo_rhythm = RhythmFeatures {
-            tempo_confidence: 0.9,
-            ..RhythmFeatures::new()
-        };
-        assert_eq!(composer.analyze_audio_for_shader(&bass_audio, &high_tempo_rhythm), ShaderType::Tunnel);
-
-        let low_tempo_rhythm = RhythmFeatures {
-            tempo_confidence: 0.5,
-            ..RhythmFeatures::new()
-        };
-        assert_eq!(composer.analyze_audio_for_shader(&bass_audio, &low_tempo_rhythm), ShaderType::Classic);
-
-        // Test treble-heavy with onsets
-        let treble_audio = AudioFeatures {
-            treble: 0.7,
-            presence: 0.5,
-            onset_strength: 0.6,
-            ..AudioFeatures::new()
-        };
-        assert_eq!(composer.analyze_audio_for_shader(&treble_audio, &high_tempo_rhythm), ShaderType::Particle);
-
-        // Test harmonic content
-        let harmonic_audio = AudioFeatures {
-            pitch_confidence: 0.8,
-            ..AudioFeatures::new()
-        };
-        let stable_rhythm = RhythmFeatures {
-            rhythm_stability: 0.7,
-            ..RhythmFeatures::new()
-        };
-        assert_eq!(composer.analyze_audio_for_shader(&harmonic_audio, &stable_rhythm), ShaderType::Kaleidoscope);
-
-        // Test high spectral flux
-        let dynamic_audio = AudioFeatures {
-            spectral_flux: 0.5,
-            ..AudioFeatures::new()
-        };
-        assert_eq!(composer.analyze_audio_for_shader(&dynamic_audio, &high_tempo_rhythm), ShaderType::ParametricWave);
-
-        // Test high dynamic range
-        let range_audio = AudioFeatures {
-            dynamic_range: 0.7,
-            ..AudioFeatures::new()
-        };
-        assert_eq!(composer.analyze_audio_for_shader(&range_audio, &high_tempo_rhythm), ShaderType::Fractal);
-
-        // Test default case
-        let default_audio = AudioFeatures::new();
-        let default_rhythm = RhythmFeatures::new();
-        assert_eq!(composer.analyze_audio_for_shader(&default_audio, &default_rhythm), ShaderType::Classic);
-    }
 }
\ No newline at end of file
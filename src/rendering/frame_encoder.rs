@@ -0,0 +1,66 @@
+/// Records every pass of one frame (shader, trails, overlays) into a single command
+/// encoder so the whole frame goes to the GPU in one queue submit
+pub struct FrameEncoder {
+    encoder: wgpu::CommandEncoder,
+    pass_count: u32,
+}
+
+impl FrameEncoder {
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self {
+            encoder: device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("frame_encoder"),
+            }),
+            pass_count: 0,
+        }
+    }
+
+    /// Begin a render pass recorded into this frame
+    pub fn begin_render_pass<'a>(&'a mut self, descriptor: &wgpu::RenderPassDescriptor<'_>) -> wgpu::RenderPass<'a> {
+        self.pass_count += 1;
+        self.encoder.begin_render_pass(descriptor)
+    }
+
+    /// Record a texture-to-buffer copy after this frame's passes (screenshot readback)
+    pub fn copy_texture_to_buffer(
+        &mut self,
+        source: wgpu::ImageCopyTexture<'_>,
+        destination: wgpu::ImageCopyBuffer<'_>,
+        size: wgpu::Extent3d,
+    ) {
+        self.encoder.copy_texture_to_buffer(source, destination, size);
+    }
+
+    /// Record resolving `queries` into `destination` (GPU timestamps)
+    pub fn resolve_query_set(
+        &mut self,
+        query_set: &wgpu::QuerySet,
+        queries: std::ops::Range<u32>,
+        destination: &wgpu::Buffer,
+        destination_offset: wgpu::BufferAddress,
+    ) {
+        self.encoder.resolve_query_set(query_set, queries, destination, destination_offset);
+    }
+
+    /// Record a buffer-to-buffer copy after this frame's passes
+    pub fn copy_buffer_to_buffer(
+        &mut self,
+        source: &wgpu::Buffer,
+        source_offset: wgpu::BufferAddress,
+        destination: &wgpu::Buffer,
+        destination_offset: wgpu::BufferAddress,
+        size: wgpu::BufferAddress,
+    ) {
+        self.encoder.copy_buffer_to_buffer(source, source_offset, destination, destination_offset, size);
+    }
+
+    /// Render passes recorded so far
+    pub fn pass_count(&self) -> u32 {
+        self.pass_count
+    }
+
+    /// Submit the frame; consuming the encoder guarantees one submit per frame
+    pub fn submit(self, queue: &wgpu::Queue) -> wgpu::SubmissionIndex {
+        queue.submit(std::iter::once(self.encoder.finish()))
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Instant;
+
+use crate::clock::{system_clock, SharedClock};
+use super::{PerformanceMetrics, ShaderType};
+
+/// Details of one presented frame, for compositors and other embedders
+#[derive(Debug, Clone)]
+pub struct FrameInfo {
+    pub frame_index: u64,
+    pub timestamp: Instant,
+    pub shader: ShaderType,
+    pub metrics: PerformanceMetrics,
+}
+
+/// Called once per presented frame
+pub type FrameCallback = Box<dyn FnMut(&FrameInfo) + Send>;
+
+/// Delivers frame-ready notifications to an optional callback
+pub struct FrameNotifier {
+    clock: SharedClock,
+    callback: Option<FrameCallback>,
+    frame_index: u64,
+}
+
+impl FrameNotifier {
+    pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a notifier that timestamps frames from a custom time source
+    pub fn with_clock(clock: SharedClock) -> Self {
+        Self {
+            clock,
+            callback: None,
+            frame_index: 0,
+        }
+    }
+
+    /// Install or remove the frame callback
+    pub fn set_callback(&mut self, callback: Option<FrameCallback>) {
+        self.callback = callback;
+    }
+
+    /// Replace the callback with a channel; frames are dropped once the receiver goes away
+    pub fn channel(&mut self) -> Receiver<FrameInfo> {
+        let (sender, receiver) = channel();
+        self.callback = Some(Box::new(move |info: &FrameInfo| {
+            let _ = sender.send(info.clone());
+        }));
+        receiver
+    }
+
+    pub fn has_callback(&self) -> bool {
+        self.callback.is_some()
+    }
+
+    /// Report a presented frame
+    pub fn notify(&mut self, shader: ShaderType, metrics: &PerformanceMetrics) {
+        self.frame_index += 1;
+        if let Some(callback) = &mut self.callback {
+            let info = FrameInfo {
+                frame_index: self.frame_index,
+                timestamp: self.clock.now(),
+                shader,
+                metrics: metrics.clone(),
+            };
+            callback(&info);
+        }
+    }
+
+    /// Frames reported so far
+    pub fn frame_count(&self) -> u64 {
+        self.frame_index
+    }
+}
+
+impl Default for FrameNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[test]
+    fn test_callback_invoked_once_per_frame_with_monotonic_timestamps() {
+        let clock = MockClock::new();
+        let mut notifier = FrameNotifier::with_clock(clock.shared());
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&frames);
+        notifier.set_callback(Some(Box::new(move |info: &FrameInfo| {
+            sink.lock().unwrap().push(info.clone());
+        })));
+
+        for _ in 0..5 {
+            clock.advance(Duration::from_millis(16));
+            notifier.notify(ShaderType::Plasma, &PerformanceMetrics::default());
+        }
+
+        let frames = frames.lock().unwrap();
+        assert_eq!(frames.len(), 5);
+        assert!(frames.windows(2).all(|w| w[1].timestamp > w[0].timestamp));
+        assert!(frames.windows(2).all(|w| w[1].frame_index == w[0].frame_index + 1));
+        assert!(frames.iter().all(|f| f.shader == ShaderType::Plasma));
+    }
+
+    #[test]
+    fn test_channel_receives_frames() {
+        let mut notifier = FrameNotifier::new();
+        let receiver = notifier.channel();
+
+        notifier.notify(ShaderType::Classic, &PerformanceMetrics::default());
+        notifier.notify(ShaderType::Fractal, &PerformanceMetrics::default());
+
+        let received: Vec<FrameInfo> = receiver.try_iter().collect();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[1].shader, ShaderType::Fractal);
+
+        // A dropped receiver doesn't break rendering
+        drop(receiver);
+        notifier.notify(ShaderType::Classic, &PerformanceMetrics::default());
+        assert_eq!(notifier.frame_count(), 3);
+    }
+}
@@ -0,0 +1,183 @@
+// Video export: decodes an audio file at a fixed frame rate, analyzes and renders each
+// frame offscreen, and streams raw RGBA frames to a writer (stdout or a named pipe)
+// for ffmpeg to encode, e.g.
+//
+//   aruu song.wav --export - | ffmpeg -skip_initial_bytes 32 -f rawvideo -pix_fmt rgba \
+//       -s 1280x720 -r 30 -i - -i song.wav -shortest out.mp4
+
+use anyhow::{bail, Result};
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::audio::{AudioProcessor, RhythmDetector};
+use super::HeadlessRenderer;
+
+/// Frame rate used when `--fps` is not given
+pub const DEFAULT_EXPORT_FPS: u32 = 30;
+
+/// Offscreen resolution used when `--export-size` is not given
+pub const DEFAULT_EXPORT_RESOLUTION: (u32, u32) = (1280, 720);
+
+/// Bytes in the stream header; ffmpeg skips it with `-skip_initial_bytes`
+pub const EXPORT_HEADER_LEN: usize = 32;
+
+/// Renders a file's visuals frame by frame, with the audio decode position driving time
+pub struct FrameExporter {
+    renderer: HeadlessRenderer,
+    processor: AudioProcessor,
+    rhythm_detector: RhythmDetector,
+    frame_rate: u32,
+    frames_written: u64,
+}
+
+impl FrameExporter {
+    /// Export `reader`'s audio at `frame_rate` frames per second
+    pub fn new<R>(renderer: HeadlessRenderer, reader: R, frame_rate: u32) -> Result<Self>
+    where
+        R: Read + Seek + Send + Sync + 'static,
+    {
+        if frame_rate == 0 {
+            bail!("Export frame rate must be non-zero");
+        }
+
+        let mut processor = AudioProcessor::new_offline(reader)?;
+        processor.set_analysis_frame_rate(frame_rate as f32);
+        let mut rhythm_detector = RhythmDetector::new(processor.sample_rate());
+        rhythm_detector.set_frame_rate(frame_rate as f32);
+
+        Ok(Self { renderer, processor, rhythm_detector, frame_rate, frames_written: 0 })
+    }
+
+    pub fn from_file(renderer: HeadlessRenderer, path: &Path, frame_rate: u32) -> Result<Self> {
+        Self::new(renderer, std::fs::File::open(path)?, frame_rate)
+    }
+
+    /// Fixed-length ASCII header, space padded and newline terminated:
+    /// `ARUU RGBA <width>x<height> <fps>`
+    pub fn header(&self) -> [u8; EXPORT_HEADER_LEN] {
+        let (width, height) = self.renderer.dimensions();
+        let text = format!("ARUU RGBA {}x{} {}", width, height, self.frame_rate);
+
+        let mut header = [b' '; EXPORT_HEADER_LEN];
+        let len = text.len().min(EXPORT_HEADER_LEN - 1);
+        header[..len].copy_from_slice(&text.as_bytes()[..len]);
+        header[EXPORT_HEADER_LEN - 1] = b'\n';
+        header
+    }
+
+    /// How far into the audio the exported frames have reached
+    pub fn position(&self) -> Duration {
+        self.processor.offline_position().unwrap_or(Duration::ZERO)
+    }
+
+    pub fn frames_written(&self) -> u64 {
+        self.frames_written
+    }
+
+    pub fn renderer_mut(&mut self) -> &mut HeadlessRenderer {
+        &mut self.renderer
+    }
+
+    /// Decode, analyze and render the next frame, writing its pixels. Returns false once
+    /// the audio has run out, without writing anything.
+    pub fn export_frame<W: Write>(&mut self, out: &mut W) -> Result<bool> {
+        let before = self.position();
+        let audio = self.processor.process_frame()?;
+        if self.processor.offline_finished() && self.position() == before {
+            return Ok(false); // Nothing new was decoded for this frame
+        }
+        let rhythm = self.rhythm_detector.process_frame(self.processor.spectrum());
+
+        let pixels = self.renderer.render_to_buffer(&audio, &rhythm)?;
+        out.write_all(&pixels)?;
+        self.frames_written += 1;
+        Ok(true)
+    }
+
+    /// Write the header, then frames until the audio ends or `max_frames` are written.
+    /// Returns the number of frames written.
+    pub fn export<W: Write>(&mut self, out: &mut W, max_frames: Option<u64>) -> Result<u64> {
+        out.write_all(&self.header())?;
+        while max_frames.is_none_or(|max| self.frames_written < max) {
+            if !self.export_frame(out)? {
+                break;
+            }
+        }
+        out.flush()?;
+        Ok(self.frames_written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None).await.ok()
+        })
+    }
+
+    /// One second of a mono 16-bit 440 Hz tone as an in-memory WAV
+    fn tone_wav(sample_rate: u32) -> Vec<u8> {
+        let data_len = sample_rate * 2;
+        let mut wav = Vec::with_capacity(44 + data_len as usize);
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // Mono
+        wav.extend_from_slice(&sample_rate.to_le_bytes());
+        wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_len.to_le_bytes());
+        for i in 0..sample_rate {
+            let phase = std::f32::consts::TAU * 440.0 * i as f32 / sample_rate as f32;
+            wav.extend_from_slice(&((0.5 * phase.sin() * i16::MAX as f32) as i16).to_le_bytes());
+        }
+        wav
+    }
+
+    #[test]
+    fn test_exports_header_and_raw_frames() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping export test: no GPU adapter available");
+            return;
+        };
+
+        let (width, height, frames) = (48, 27, 4);
+        let renderer = HeadlessRenderer::with_device(device, queue, width, height).expect("Headless renderer should build");
+        let mut exporter = FrameExporter::new(renderer, std::io::Cursor::new(tone_wav(44100)), 30).unwrap();
+
+        let mut output = Vec::new();
+        assert_eq!(exporter.export(&mut output, Some(frames)).unwrap(), frames);
+
+        assert_eq!(output.len(), (width * height * 4) as usize * frames as usize + EXPORT_HEADER_LEN);
+        assert!(output.starts_with(b"ARUU RGBA 48x27 30 "));
+        assert_eq!(output[EXPORT_HEADER_LEN - 1], b'\n');
+
+        // Visuals advance exactly one frame of audio per frame rendered
+        assert_eq!(exporter.position(), Duration::from_secs_f64(4.0 * 1470.0 / 44100.0));
+    }
+
+    #[test]
+    fn test_export_stops_when_audio_ends() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping export test: no GPU adapter available");
+            return;
+        };
+
+        let renderer = HeadlessRenderer::with_device(device, queue, 16, 16).expect("Headless renderer should build");
+        let mut exporter = FrameExporter::new(renderer, std::io::Cursor::new(tone_wav(8000)), 10).unwrap();
+
+        let mut output = Vec::new();
+        assert_eq!(exporter.export(&mut output, None).unwrap(), 10); // One second at 10 FPS
+        assert_eq!(output.len(), 16 * 16 * 4 * 10 + EXPORT_HEADER_LEN);
+    }
+}
@@ -0,0 +1,320 @@
+use anyhow::{anyhow, Result};
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use crate::audio::{SpectralSmoother, WindowFunction};
+use super::GpuCapabilities;
+
+/// Invocations per workgroup; matches `@workgroup_size` in fft.comp.wgsl
+const FFT_WORKGROUP_SIZE: u32 = 64;
+
+/// Per-pass parameters for fft.comp.wgsl
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+struct FftParams {
+    n: u32,
+    stride: u32,
+    _padding: [u32; 2],
+}
+
+/// Radix-2 Stockham FFT on a compute shader, producing the same magnitudes as `FftAnalyzer`.
+/// Worth it for large windows; small ones are faster on the CPU than a GPU round trip.
+pub struct GpuFft {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    size: usize,
+    window_function: WindowFunction,
+    window: Vec<f32>,
+    ping_pong: [wgpu::Buffer; 2], // Complex work buffers; [0] receives the windowed samples
+    _params_buffers: Vec<wgpu::Buffer>, // Kept alive for the bind groups
+    butterfly_pipeline: wgpu::ComputePipeline,
+    magnitude_pipeline: wgpu::ComputePipeline,
+    pass_bind_groups: Vec<wgpu::BindGroup>, // One per butterfly pass, alternating buffer direction
+    magnitude_bind_group: wgpu::BindGroup,
+    magnitude_buffer: wgpu::Buffer,
+    staging_buffer: wgpu::Buffer,
+    output_buffer: Vec<f32>,
+    smoother: SpectralSmoother, // Same temporal smoothing as `FftAnalyzer`, applied after readback
+}
+
+impl GpuFft {
+    /// Create a GPU FFT on its own device, so analysis never waits behind frame rendering.
+    /// Fails when no adapter is available or it lacks compute shader support.
+    pub fn new(size: usize, window_function: WindowFunction) -> Result<Self> {
+        let (device, queue) = pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: wgpu::PowerPreference::HighPerformance,
+                    compatible_surface: None,
+                    force_fallback_adapter: false,
+                })
+                .await
+                .ok_or_else(|| anyhow!("No GPU adapter available for GPU FFT"))?;
+
+            let capabilities = GpuCapabilities::from_adapter(&adapter.limits(), &adapter.get_info());
+            if !capabilities.supports_compute_shaders {
+                return Err(anyhow!("{} does not support compute shaders", capabilities.adapter_name));
+            }
+
+            adapter
+                .request_device(&wgpu::DeviceDescriptor::default(), None)
+                .await
+                .map_err(|e| anyhow!("Failed to create GPU FFT device: {}", e))
+        })?;
+
+        Self::with_device(device, queue, size, window_function)
+    }
+
+    /// Create a GPU FFT on an existing device with compute support
+    pub fn with_device(device: wgpu::Device, queue: wgpu::Queue, size: usize, window_function: WindowFunction) -> Result<Self> {
+        if size < 2 || !size.is_power_of_two() {
+            return Err(anyhow!("GPU FFT size must be a power of two, got {}", size));
+        }
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("fft_compute_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/fft.comp.wgsl").into()),
+        });
+
+        let storage_entry = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("fft_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage_entry(1, true),
+                storage_entry(2, false),
+                storage_entry(3, false),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("fft_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let create_pipeline = |entry_point: &str| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(entry_point),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point,
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        };
+        let butterfly_pipeline = create_pipeline("butterfly");
+        let magnitude_pipeline = create_pipeline("magnitude");
+
+        let complex_bytes = (size * 2 * std::mem::size_of::<f32>()) as u64;
+        let ping_pong = [0, 1].map(|index| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(if index == 0 { "fft_ping_buffer" } else { "fft_pong_buffer" }),
+                size: complex_bytes,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+        let magnitude_bytes = (size / 2 * std::mem::size_of::<f32>()) as u64;
+        let magnitude_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("fft_magnitude_buffer"),
+            size: magnitude_bytes,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("fft_staging_buffer"),
+            size: magnitude_bytes,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = |params: &wgpu::Buffer, input: &wgpu::Buffer, output: &wgpu::Buffer| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("fft_bind_group"),
+                layout: &bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: params.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 1, resource: input.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 2, resource: output.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 3, resource: magnitude_buffer.as_entire_binding() },
+                ],
+            })
+        };
+        let params_buffer = |stride: usize| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("fft_params_buffer"),
+                contents: bytemuck::bytes_of(&FftParams { n: size as u32, stride: stride as u32, _padding: [0; 2] }),
+                usage: wgpu::BufferUsages::UNIFORM,
+            })
+        };
+
+        // Pass p combines sub-transforms of length 2^p, reading ping_pong[p % 2]
+        let passes = size.trailing_zeros() as usize;
+        let params_buffers: Vec<wgpu::Buffer> = (0..passes).map(|pass| params_buffer(1 << pass)).collect();
+        let pass_bind_groups = params_buffers.iter()
+            .enumerate()
+            .map(|(pass, params)| bind_group(params, &ping_pong[pass % 2], &ping_pong[(pass + 1) % 2]))
+            .collect();
+        let magnitude_bind_group = bind_group(&params_buffers[0], &ping_pong[passes % 2], &ping_pong[(passes + 1) % 2]);
+
+        Ok(Self {
+            device,
+            queue,
+            size,
+            window_function,
+            window: window_function.coefficients(size),
+            ping_pong,
+            _params_buffers: params_buffers,
+            butterfly_pipeline,
+            magnitude_pipeline,
+            pass_bind_groups,
+            magnitude_bind_group,
+            magnitude_buffer,
+            staging_buffer,
+            output_buffer: vec![0.0; size / 2],
+            smoother: SpectralSmoother::default(),
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Switch the window function applied before upload
+    pub fn set_window(&mut self, window_function: WindowFunction) {
+        if window_function != self.window_function {
+            self.window_function = window_function;
+            self.window = window_function.coefficients(self.size);
+        }
+    }
+
+    /// Per-bin temporal smoothing of the magnitudes (see `FftAnalyzer::set_spectral_smoothing`)
+    pub fn set_spectral_smoothing(&mut self, alpha: f32) {
+        self.smoother.set_alpha(alpha);
+    }
+
+    pub fn reset_smoothing(&mut self) {
+        self.smoother.reset();
+    }
+
+    /// Transform the first `size` samples and return `size / 2` magnitudes (blocks on the readback).
+    /// Shorter input returns an empty spectrum, like `FftAnalyzer::process_audio`.
+    pub fn process(&mut self, samples: &[f32]) -> Result<&[f32]> {
+        if samples.len() < self.size {
+            return Ok(&[]);
+        }
+
+        let complex: Vec<[f32; 2]> = samples.iter()
+            .zip(&self.window)
+            .map(|(&sample, &weight)| [sample * weight, 0.0])
+            .collect();
+        self.queue.write_buffer(&self.ping_pong[0], 0, bytemuck::cast_slice(&complex));
+
+        let workgroups = ((self.size / 2) as u32).div_ceil(FFT_WORKGROUP_SIZE);
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("fft_encoder"),
+        });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("fft_pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.butterfly_pipeline);
+            for bind_group in &self.pass_bind_groups {
+                pass.set_bind_group(0, bind_group, &[]);
+                pass.dispatch_workgroups(workgroups, 1, 1);
+            }
+            pass.set_pipeline(&self.magnitude_pipeline);
+            pass.set_bind_group(0, &self.magnitude_bind_group, &[]);
+            pass.dispatch_workgroups(workgroups, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&self.magnitude_buffer, 0, &self.staging_buffer, 0, self.staging_buffer.size());
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = self.staging_buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|_| anyhow!("GPU FFT readback was dropped"))?
+            .map_err(|e| anyhow!("Failed to map GPU FFT buffer: {}", e))?;
+
+        {
+            let data = slice.get_mapped_range();
+            self.output_buffer.copy_from_slice(bytemuck::cast_slice(&data));
+        }
+        self.staging_buffer.unmap();
+        self.smoother.apply(&mut self.output_buffer);
+        Ok(&self.output_buffer)
+    }
+
+    /// Magnitudes from the most recent `process` call
+    pub fn spectrum(&self) -> &[f32] {
+        &self.output_buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::FftAnalyzer;
+
+    #[test]
+    fn test_gpu_spectrum_matches_cpu() {
+        let size = 1024;
+        let mut gpu = match GpuFft::new(size, WindowFunction::Hann) {
+            Ok(gpu) => gpu,
+            Err(e) => {
+                println!("Skipping GPU FFT test: {}", e);
+                return;
+            }
+        };
+        let mut cpu = FftAnalyzer::new(size, WindowFunction::Hann);
+
+        // Off-bin tone so leakage exercises the whole spectrum, not just one bin
+        let samples: Vec<f32> = (0..size)
+            .map(|i| 0.8 * (2.0 * std::f32::consts::PI * 37.3 * i as f32 / size as f32).sin())
+            .collect();
+
+        let expected = cpu.process_audio(&samples).to_vec();
+        let actual = gpu.process(&samples).expect("GPU FFT should run");
+        assert_eq!(actual.len(), expected.len());
+
+        let peak = expected.iter().fold(0.0f32, |acc, &m| acc.max(m));
+        for (bin, (gpu_value, cpu_value)) in actual.iter().zip(&expected).enumerate() {
+            assert!(
+                (gpu_value - cpu_value).abs() <= peak * 1e-4 + 1e-4,
+                "bin {}: GPU {} vs CPU {}",
+                bin, gpu_value, cpu_value
+            );
+        }
+        let peak_bin = actual.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).map(|(bin, _)| bin);
+        assert_eq!(peak_bin, Some(37));
+    }
+
+    #[test]
+    fn test_gpu_fft_rejects_non_power_of_two() {
+        assert!(GpuFft::new(1000, WindowFunction::Hann).is_err());
+    }
+}
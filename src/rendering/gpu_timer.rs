@@ -0,0 +1,270 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::FrameEncoder;
+
+/// Share of the frame time assumed to be GPU work when timestamps are unavailable
+const ESTIMATED_GPU_SHARE: f32 = 0.7;
+
+/// Longest believable pass duration; anything above comes from unwritten or wrapped queries
+const MAX_MEASURED_GPU_TIME: Duration = Duration::from_secs(1);
+
+/// Begin and end timestamps, 8 bytes each
+const TIMESTAMP_BYTES: wgpu::BufferAddress = 2 * std::mem::size_of::<u64>() as wgpu::BufferAddress;
+
+/// Guess at the GPU portion of a frame, used without timestamp query support
+pub fn estimated_gpu_time(frame_time: Duration) -> Duration {
+    frame_time.mul_f32(ESTIMATED_GPU_SHARE)
+}
+
+/// Measured GPU time when available, otherwise the frame-time estimate
+pub fn gpu_time_or_estimate(measured: Option<Duration>, frame_time: Duration) -> Duration {
+    measured.unwrap_or_else(|| estimated_gpu_time(frame_time))
+}
+
+/// Elapsed time between two raw timestamps; None for out-of-order or implausible pairs
+pub fn timestamp_duration(begin: u64, end: u64, period_ns: f32) -> Option<Duration> {
+    if end <= begin || !period_ns.is_finite() || period_ns <= 0.0 {
+        return None;
+    }
+    let nanos = (end - begin) as f64 * period_ns as f64;
+    Some(Duration::from_nanos(nanos as u64)).filter(|&elapsed| elapsed > Duration::ZERO && elapsed <= MAX_MEASURED_GPU_TIME)
+}
+
+/// Times the main shader pass with GPU timestamp queries
+///
+/// Results are read back without stalling: the readback buffer is mapped after submit and
+/// collected on a later frame, so `gpu_time` lags the frame it describes by a frame or two.
+/// While a readback is in flight, passes are not timed and the last measurement is kept.
+pub struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    readback_buffer: wgpu::Buffer,
+    period_ns: f32, // Nanoseconds per timestamp tick
+    recorded: bool, // This frame's queries were resolved into the readback buffer
+    in_flight: bool, // Readback mapping requested and not yet collected
+    map_result: Arc<Mutex<Option<bool>>>, // Set by the map callback: Some(true) once readable
+    latest: Option<Duration>,
+}
+
+impl GpuTimer {
+    /// Create a timer, or None when the device was created without `Features::TIMESTAMP_QUERY`
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("gpu_timer_queries"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gpu_timer_resolve"),
+            size: TIMESTAMP_BYTES,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gpu_timer_readback"),
+            size: TIMESTAMP_BYTES,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            readback_buffer,
+            period_ns: queue.get_timestamp_period(),
+            recorded: false,
+            in_flight: false,
+            map_result: Arc::new(Mutex::new(None)),
+            latest: None,
+        })
+    }
+
+    /// Timestamp writes for the pass to time, or None while the previous readback is pending
+    pub fn timestamp_writes(&self) -> Option<wgpu::RenderPassTimestampWrites<'_>> {
+        (!self.in_flight).then_some(wgpu::RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(0),
+            end_of_pass_write_index: Some(1),
+        })
+    }
+
+    /// Record copying this frame's timestamps to the readback buffer (after the timed pass)
+    pub fn resolve(&mut self, frame: &mut FrameEncoder) {
+        if self.in_flight {
+            return;
+        }
+        frame.resolve_query_set(&self.query_set, 0..2, &self.resolve_buffer, 0);
+        frame.copy_buffer_to_buffer(&self.resolve_buffer, 0, &self.readback_buffer, 0, TIMESTAMP_BYTES);
+        self.recorded = true;
+    }
+
+    /// Start reading back the resolved timestamps once the frame has been submitted
+    pub fn after_submit(&mut self) {
+        if !self.recorded {
+            return;
+        }
+        self.recorded = false;
+        self.in_flight = true;
+        let map_result = Arc::clone(&self.map_result);
+        self.readback_buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            if let Ok(mut map_result) = map_result.lock() {
+                *map_result = Some(result.is_ok());
+            }
+        });
+    }
+
+    /// Pick up a finished readback without blocking and return the newest measurement
+    pub fn collect(&mut self, device: &wgpu::Device) -> Option<Duration> {
+        self.collect_with(device, wgpu::Maintain::Poll)
+    }
+
+    /// Block until the pending readback finishes (tests and one-off measurements)
+    pub fn wait(&mut self, device: &wgpu::Device) -> Option<Duration> {
+        self.collect_with(device, wgpu::Maintain::Wait)
+    }
+
+    /// Most recent successful measurement
+    pub fn latest(&self) -> Option<Duration> {
+        self.latest
+    }
+
+    fn collect_with(&mut self, device: &wgpu::Device, maintain: wgpu::Maintain) -> Option<Duration> {
+        if !self.in_flight {
+            return self.latest;
+        }
+        device.poll(maintain);
+        let map_result = self.map_result.lock().ok().and_then(|mut result| result.take());
+        match map_result {
+            None => return self.latest,
+            Some(false) => {
+                // Failed mapping: give up on this measurement and time a later frame
+                self.in_flight = false;
+                return self.latest;
+            }
+            Some(true) => {}
+        }
+
+        let measured = {
+            let data = self.readback_buffer.slice(..).get_mapped_range();
+            let timestamps: &[u64] = bytemuck::cast_slice(&data);
+            timestamp_duration(timestamps[0], timestamps[1], self.period_ns)
+        };
+        self.readback_buffer.unmap();
+        self.in_flight = false;
+
+        // A pass that never ran leaves stale timestamps; keep the previous measurement then
+        if measured.is_some() {
+            self.latest = measured;
+        }
+        self.latest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Device with timestamp queries enabled, if the adapter has them
+    fn timestamp_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+            if !adapter.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+                return None;
+            }
+            let descriptor = wgpu::DeviceDescriptor {
+                required_features: wgpu::Features::TIMESTAMP_QUERY,
+                ..Default::default()
+            };
+            adapter.request_device(&descriptor, None).await.ok()
+        })
+    }
+
+    fn plain_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None).await.ok()
+        })
+    }
+
+    #[test]
+    fn test_timestamp_duration_conversion() {
+        assert_eq!(timestamp_duration(1_000, 3_000, 1.0), Some(Duration::from_micros(2)));
+        assert_eq!(timestamp_duration(10, 20, 100.0), Some(Duration::from_nanos(1_000)));
+        assert_eq!(timestamp_duration(5, 5, 1.0), None);
+        assert_eq!(timestamp_duration(9, 5, 1.0), None);
+        assert_eq!(timestamp_duration(0, 10, 0.0), None);
+        assert_eq!(timestamp_duration(0, 2_000_000_000, 1.0), None);
+    }
+
+    #[test]
+    fn test_estimate_used_without_measurement() {
+        let frame_time = Duration::from_millis(20);
+        let estimate = gpu_time_or_estimate(None, frame_time);
+        assert!(estimate.abs_diff(Duration::from_millis(14)) < Duration::from_micros(1));
+        assert_eq!(gpu_time_or_estimate(Some(Duration::from_millis(3)), frame_time), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn test_timer_unavailable_without_feature() {
+        let Some((device, queue)) = plain_device() else {
+            println!("Skipping GPU timer test: no GPU adapter available");
+            return;
+        };
+        assert!(GpuTimer::new(&device, &queue).is_none());
+    }
+
+    #[test]
+    fn test_timed_pass_reports_gpu_time() {
+        let Some((device, queue)) = timestamp_device() else {
+            println!("Skipping GPU timer test: timestamp queries unsupported");
+            return;
+        };
+        let mut timer = GpuTimer::new(&device, &queue).expect("Timestamp feature was requested");
+        assert_eq!(timer.latest(), None);
+
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("gpu_timer_test_target"),
+            size: wgpu::Extent3d { width: 512, height: 512, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut frame = FrameEncoder::new(&device);
+        frame.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("gpu_timer_test_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: timer.timestamp_writes(),
+        });
+        timer.resolve(&mut frame);
+        frame.submit(&queue);
+        timer.after_submit();
+
+        // Pending readback: no new timestamps until it's collected
+        assert!(timer.timestamp_writes().is_none());
+
+        let gpu_time = timer.wait(&device).expect("Timed pass should produce a GPU time");
+        assert!(gpu_time > Duration::ZERO && gpu_time.as_secs_f32().is_finite());
+        assert!(timer.timestamp_writes().is_some());
+    }
+}
@@ -0,0 +1,210 @@
+use anyhow::{anyhow, Result};
+
+use crate::audio::{AudioFeatures, RhythmFeatures};
+use crate::control::safety::SafetyMultipliers;
+use super::{FrameEncoder, PerformanceUniforms, QualityLevel, ScreenshotReadback, ShaderSystem, ShaderType};
+
+/// Offscreen target format; shaders output linear color and the sRGB format encodes it
+const HEADLESS_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Renders visualizations into an owned texture at a fixed resolution, without a window,
+/// for video export pipelines (e.g. RGBA frames piped into ffmpeg)
+pub struct HeadlessRenderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: wgpu::SurfaceConfiguration, // Describes the offscreen target to the shader system
+    target: wgpu::Texture,
+    target_view: wgpu::TextureView,
+    shader_system: ShaderSystem,
+    frames_rendered: u64,
+}
+
+/// Open a device on the default adapter, with no surface
+pub fn request_headless_device() -> Result<(wgpu::Device, wgpu::Queue)> {
+    pollster::block_on(async {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+            .ok_or_else(|| anyhow!("No GPU adapter available for headless rendering"))?;
+
+        let info = adapter.get_info();
+        eprintln!("🎞️  Headless rendering on {} ({:?})", info.name, info.backend);
+
+        adapter
+            .request_device(&wgpu::DeviceDescriptor::default(), None)
+            .await
+            .map_err(|e| anyhow!("Failed to create headless device: {}", e))
+    })
+}
+
+impl HeadlessRenderer {
+    /// Create a renderer on the default adapter, with no surface
+    pub fn new(width: u32, height: u32) -> Result<Self> {
+        let (device, queue) = request_headless_device()?;
+        Self::with_device(device, queue, width, height)
+    }
+
+    /// Create a renderer on an existing device
+    pub fn with_device(device: wgpu::Device, queue: wgpu::Queue, width: u32, height: u32) -> Result<Self> {
+        if width == 0 || height == 0 {
+            return Err(anyhow!("Headless resolution must be non-zero, got {}x{}", width, height));
+        }
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format: HEADLESS_FORMAT,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+        };
+
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("headless_target"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: HEADLESS_FORMAT,
+            usage: config.usage,
+            view_formats: &[],
+        });
+        let target_view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let shader_system = ShaderSystem::new(&device, &config)?;
+
+        Ok(Self {
+            device,
+            queue,
+            config,
+            target,
+            target_view,
+            shader_system,
+            frames_rendered: 0,
+        })
+    }
+
+    /// Output resolution in pixels
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.config.width, self.config.height)
+    }
+
+    /// Switch shaders without a transition, so every exported frame is fully one shader
+    pub fn set_shader(&mut self, shader_type: ShaderType) -> Result<()> {
+        self.shader_system.set_shader_immediately(shader_type, &self.device, &self.config)
+    }
+
+    pub fn current_shader(&self) -> ShaderType {
+        self.shader_system.current_shader()
+    }
+
+    /// Shader system for palette, white balance and other look settings
+    pub fn shader_system_mut(&mut self) -> &mut ShaderSystem {
+        &mut self.shader_system
+    }
+
+    /// Scale effects for a safety level on the following frames
+    pub fn set_safety_multipliers(&mut self, multipliers: Option<SafetyMultipliers>) {
+        self.shader_system.set_safety_multipliers(multipliers);
+    }
+
+    pub fn frames_rendered(&self) -> u64 {
+        self.frames_rendered
+    }
+
+    /// Render one frame and return its pixels as tightly packed RGBA8 rows, top row first
+    pub fn render_to_buffer(&mut self, audio: &AudioFeatures, rhythm: &RhythmFeatures) -> Result<Vec<u8>> {
+        self.shader_system.update(&self.device, &self.config)?;
+
+        let mut frame = FrameEncoder::new(&self.device);
+        self.shader_system.render(
+            &self.queue,
+            &mut frame,
+            &self.target_view,
+            audio,
+            rhythm,
+        )?;
+        let readback = ScreenshotReadback::record(&self.device, &mut frame, &self.target)?;
+        frame.submit(&self.queue);
+
+        let pixels = readback.read_rgba(&self.device)?;
+        self.frames_rendered += 1;
+        Ok(pixels)
+    }
+
+    /// Render one frame at a fixed quality level without reading it back, and wait for the
+    /// GPU to finish so the call's wall time is the frame's cost
+    pub fn render_at_quality(&mut self, audio: &AudioFeatures, rhythm: &RhythmFeatures, quality: QualityLevel) -> Result<()> {
+        self.shader_system.update(&self.device, &self.config)?;
+
+        let mut frame = FrameEncoder::new(&self.device);
+        self.shader_system.render_with_quality(
+            &self.queue,
+            &mut frame,
+            &self.target_view,
+            audio,
+            rhythm,
+            &PerformanceUniforms::from(quality),
+        )?;
+        frame.submit(&self.queue);
+        self.device.poll(wgpu::Maintain::Wait);
+
+        self.frames_rendered += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None).await.ok()
+        })
+    }
+
+    #[test]
+    fn test_renders_classic_frame_offscreen() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping headless test: no GPU adapter available");
+            return;
+        };
+
+        let (width, height) = (96, 54);
+        let mut renderer = HeadlessRenderer::with_device(device, queue, width, height).expect("Headless renderer should build");
+        renderer.set_shader(ShaderType::Classic).unwrap();
+        assert_eq!(renderer.current_shader(), ShaderType::Classic);
+
+        let audio = AudioFeatures {
+            bass: 0.7,
+            mid: 0.5,
+            treble: 0.4,
+            overall_volume: 0.6,
+            ..AudioFeatures::new()
+        };
+        let pixels = renderer.render_to_buffer(&audio, &RhythmFeatures::new()).unwrap();
+
+        assert_eq!(pixels.len(), (width * height * 4) as usize);
+        assert!(pixels.chunks_exact(4).all(|texel| texel[3] == 255), "Shader output should be opaque");
+        assert_eq!(renderer.frames_rendered(), 1);
+    }
+
+    #[test]
+    fn test_zero_resolution_rejected() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping headless test: no GPU adapter available");
+            return;
+        };
+        assert!(HeadlessRenderer::with_device(device, queue, 0, 720).is_err());
+    }
+}
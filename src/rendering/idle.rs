@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use crate::control::LUMINANCE_CHANGE_LIMIT;
+use super::FrameEncoder;
+
+/// Time for a full fade between the live scene and the idle pattern
+pub const IDLE_FADE_DURATION: Duration = Duration::from_secs(2);
+
+/// Per-frame parameters for the idle pattern
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct IdleUniforms {
+    pub time: f32,       // Seconds driving the animation
+    pub opacity: f32,    // Idle pattern share of the frame (0.0 to 1.0)
+    pub brightness: f32, // Safety brightness allowance (0.0 to 1.0)
+    pub aspect: f32,     // Output width / height
+}
+
+impl IdleUniforms {
+    pub fn new(time: f32, opacity: f32, brightness: f32, size: (u32, u32)) -> Self {
+        Self {
+            time,
+            opacity: opacity.clamp(0.0, 1.0),
+            brightness: brightness.clamp(0.0, 1.0),
+            aspect: size.0.max(1) as f32 / size.1.max(1) as f32,
+        }
+    }
+}
+
+/// Opacity of the idle pattern, eased in and out as silence starts and ends
+///
+/// The pattern and the scene both stay within 0.0-1.0 luminance, so limiting the
+/// opacity step per frame to `LUMINANCE_CHANGE_LIMIT` keeps the crossfade itself
+/// inside the safety limiter's per-change budget even on slow frames.
+#[derive(Debug, Clone, Default)]
+pub struct IdleFade {
+    active: bool,
+    level: f32,
+}
+
+impl IdleFade {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fade toward the idle pattern (true) or back to the live scene (false)
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Advance the fade by one frame and return the new opacity
+    pub fn update(&mut self, frame_time: Duration) -> f32 {
+        let step = (frame_time.as_secs_f32() / IDLE_FADE_DURATION.as_secs_f32()).min(LUMINANCE_CHANGE_LIMIT);
+        let target = if self.active { 1.0 } else { 0.0 };
+        self.level = if self.level < target {
+            (self.level + step).min(target)
+        } else {
+            (self.level - step).max(target)
+        };
+        self.level
+    }
+
+    /// Current idle pattern opacity (0.0 = live scene only)
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Whether any of the idle pattern is on screen
+    pub fn is_visible(&self) -> bool {
+        self.level > 0.0
+    }
+}
+
+/// Draws the idle pattern over the frame with the fade's opacity
+pub struct IdleScreen {
+    pipeline: wgpu::RenderPipeline,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl IdleScreen {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("idle_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/idle.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("idle_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("idle_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("idle_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::COLOR,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("idle_uniforms"),
+            contents: bytemuck::cast_slice(&[IdleUniforms::new(0.0, 0.0, 1.0, (1, 1))]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("idle_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() }],
+        });
+
+        Self { pipeline, uniform_buffer, bind_group }
+    }
+
+    /// Record a pass blending the idle pattern over whatever `target` already holds
+    pub fn draw(&self, queue: &wgpu::Queue, frame: &mut FrameEncoder, target: &wgpu::TextureView, uniforms: IdleUniforms) {
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+
+        let mut render_pass = frame.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("idle_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(16);
+
+    #[test]
+    fn test_fade_ramps_in_and_out() {
+        let mut fade = IdleFade::new();
+        assert_eq!(fade.update(FRAME), 0.0);
+        assert!(!fade.is_visible());
+
+        fade.set_active(true);
+        let first = fade.update(FRAME);
+        assert!(first > 0.0 && first < 0.05);
+
+        // About IDLE_FADE_DURATION of frames reaches full opacity
+        for _ in 0..130 {
+            fade.update(FRAME);
+        }
+        assert_eq!(fade.level(), 1.0);
+
+        fade.set_active(false);
+        assert!(fade.update(FRAME) < 1.0);
+        for _ in 0..130 {
+            fade.update(FRAME);
+        }
+        assert_eq!(fade.level(), 0.0);
+        assert!(!fade.is_visible());
+    }
+
+    #[test]
+    fn test_fade_step_respects_luminance_limit() {
+        // A stalled frame must not jump straight to the idle pattern
+        let mut fade = IdleFade::new();
+        fade.set_active(true);
+        assert_eq!(fade.update(Duration::from_secs(5)), LUMINANCE_CHANGE_LIMIT);
+        fade.set_active(false);
+        assert_eq!(fade.update(Duration::from_secs(5)), 0.0);
+    }
+}
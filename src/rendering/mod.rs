@@ -5,6 +5,23 @@ pub mod shader_system;
 pub mod enhanced_composer;
 pub mod performance;
 pub mod overlay_system;
+pub mod trails;
+pub mod bloom;
+pub mod idle;
+pub mod emergency_fade;
+pub mod vu_meter;
+pub mod screen_shake;
+pub mod frame_events;
+pub mod frame_encoder;
+pub mod screenshot;
+pub mod headless;
+pub mod frame_exporter;
+pub mod benchmark;
+pub mod shader_selection;
+pub mod spectrogram;
+pub mod gpu_fft;
+pub mod gpu_timer;
+pub mod text;
 
 pub use context::*;
 pub use shaders::*;
@@ -12,4 +29,21 @@ pub use composer::*;
 pub use shader_system::*;
 pub use enhanced_composer::*;
 pub use performance::*;
-pub use overlay_system::*;
\ No newline at end of file
+pub use overlay_system::*;
+pub use trails::*;
+pub use bloom::*;
+pub use idle::*;
+pub use emergency_fade::*;
+pub use vu_meter::*;
+pub use screen_shake::*;
+pub use frame_events::*;
+pub use frame_encoder::*;
+pub use screenshot::*;
+pub use headless::*;
+pub use frame_exporter::*;
+pub use benchmark::*;
+pub use shader_selection::*;
+pub use spectrogram::*;
+pub use gpu_fft::*;
+pub use gpu_timer::*;
+pub use text::*;
@@ -1,13 +1,14 @@
 use wgpu::util::DeviceExt;
 use anyhow::Result;
 
-use super::{WgpuContext, UniversalUniforms};
+use super::{FrameEncoder, WgpuContext, UniversalUniforms, TextRenderer, glyph_size_for_height, render_format};
 
 /// Types of overlay shaders available
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OverlayType {
     DebugOverlay,    // Right-side debug information with transparent white background
     ControlPanel,    // Top-left control panel with UI elements
+    BeatGrid,        // Bottom-center quarter-note ticks for beatmatching
 }
 
 impl OverlayType {
@@ -15,6 +16,7 @@ impl OverlayType {
         match self {
             OverlayType::DebugOverlay => "Debug Overlay",
             OverlayType::ControlPanel => "Control Panel",
+            OverlayType::BeatGrid => "Beat Grid",
         }
     }
 
@@ -22,6 +24,7 @@ impl OverlayType {
         match self {
             OverlayType::DebugOverlay => include_str!("shaders/overlay_debug.frag.wgsl"),
             OverlayType::ControlPanel => include_str!("shaders/overlay_control.frag.wgsl"),
+            OverlayType::BeatGrid => include_str!("shaders/overlay_beat_grid.frag.wgsl"),
         }
     }
 
@@ -32,6 +35,8 @@ impl OverlayType {
             OverlayType::DebugOverlay => (0.7, 0.0, 1.0, 1.0),
             // Control panel: top-left (x: 0.0-0.4, y: 0.0-0.3)
             OverlayType::ControlPanel => (0.0, 0.0, 0.4, 0.3),
+            // Beat grid: bottom-center strip (x: 0.35-0.65, y: 0.9-0.97)
+            OverlayType::BeatGrid => (0.35, 0.9, 0.65, 0.97),
         }
     }
 }
@@ -47,19 +52,42 @@ pub struct OverlayShader {
 pub struct OverlaySystem {
     overlays: Vec<OverlayShader>,
     uniform_buffer: wgpu::Buffer,
+    control_layout: ControlLayout,
+    control_layout_buffer: wgpu::Buffer, // `ControlLayoutUniform`, read by overlay_control.frag.wgsl
     bind_group_layout: wgpu::BindGroupLayout,
     bind_group: Option<wgpu::BindGroup>,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     mouse_position: (f32, f32),
     mouse_pressed: bool,
+    text_renderer: TextRenderer,
+    debug_lines: Vec<String>, // Drawn into the debug overlay's label rows
+}
+
+/// Debug overlay text rows (top of each label area in overlay_debug.frag.wgsl, panel-local y)
+const DEBUG_TEXT_ROWS: [f32; 4] = [0.065, 0.17, 0.47, 0.67];
+const DEBUG_TEXT_LEFT: f32 = 0.05; // Panel-local x
+const DEBUG_TEXT_HEIGHT: f32 = 0.025; // Glyph height as a fraction of the screen height, before `text_scale`
+const DEBUG_TEXT_COLOR: [f32; 4] = [0.1, 0.2, 0.4, 0.95];
+
+/// Readouts for the debug overlay rows: current shader, volume, tempo and frame rate
+pub fn debug_overlay_lines(uniforms: &UniversalUniforms, shader_name: &str) -> Vec<String> {
+    vec![
+        format!("SHADER: {}", shader_name),
+        format!("VOL {:.0}%  {:.0} DB", uniforms.ui_volume * 100.0, uniforms.signal_level_db),
+        format!("BPM {:.0} ({:.0}%)", uniforms.estimated_bpm, uniforms.tempo_confidence * 100.0),
+        format!("FPS {:.0}  {:.1} MS", uniforms.ui_fps, uniforms.ui_frame_time),
+    ]
 }
 
 impl OverlaySystem {
     /// Create a new overlay system
     pub fn new(wgpu_context: &WgpuContext) -> Result<Self> {
-        let device = &wgpu_context.device;
+        Self::with_device(&wgpu_context.device, &wgpu_context.queue, &wgpu_context.config)
+    }
 
+    /// Create an overlay system for any device and target configuration (no window required)
+    pub fn with_device(device: &wgpu::Device, queue: &wgpu::Queue, config: &wgpu::SurfaceConfiguration) -> Result<Self> {
         // Create uniform buffer for overlay-specific data
         let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Overlay Uniform Buffer"),
@@ -68,6 +96,14 @@ impl OverlaySystem {
             mapped_at_creation: false,
         });
 
+        // Control panel element rects, fixed for the lifetime of the overlay system
+        let control_layout = ControlLayout::default();
+        let control_layout_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Overlay Control Layout Buffer"),
+            contents: bytemuck::cast_slice(&[control_layout.uniform()]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
         // Create bind group layout for overlays
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Overlay Bind Group Layout"),
@@ -83,6 +119,17 @@ impl OverlaySystem {
                     },
                     count: None,
                 },
+                // Control panel layout binding
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -105,24 +152,26 @@ impl OverlaySystem {
         let mut overlay_system = Self {
             overlays: Vec::new(),
             uniform_buffer,
+            control_layout,
+            control_layout_buffer,
             bind_group_layout,
             bind_group: None,
             vertex_buffer,
             index_buffer,
             mouse_position: (0.0, 0.0),
             mouse_pressed: false,
+            text_renderer: TextRenderer::new(device, queue, render_format(config)),
+            debug_lines: Vec::new(),
         };
 
         // Initialize overlay shaders
-        overlay_system.initialize_overlays(wgpu_context)?;
+        overlay_system.initialize_overlays(device, config)?;
 
         Ok(overlay_system)
     }
 
     /// Initialize all overlay shaders
-    fn initialize_overlays(&mut self, wgpu_context: &WgpuContext) -> Result<()> {
-        let device = &wgpu_context.device;
-
+    fn initialize_overlays(&mut self, device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> Result<()> {
         // Create bind group
         self.bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Overlay Bind Group"),
@@ -132,6 +181,10 @@ impl OverlaySystem {
                     binding: 0,
                     resource: self.uniform_buffer.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.control_layout_buffer.as_entire_binding(),
+                },
             ],
         }));
 
@@ -139,10 +192,10 @@ impl OverlaySystem {
         let vertex_shader_source = include_str!("shaders/overlay.vert.wgsl");
 
         // Create overlay shaders
-        for overlay_type in [OverlayType::DebugOverlay, OverlayType::ControlPanel] {
+        for overlay_type in [OverlayType::DebugOverlay, OverlayType::ControlPanel, OverlayType::BeatGrid] {
             let overlay_shader = self.create_overlay_shader(
                 device,
-                &wgpu_context.config,
+                config,
                 overlay_type,
                 vertex_shader_source,
             )?;
@@ -189,7 +242,7 @@ impl OverlaySystem {
                 module: &fragment_shader,
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
+                    format: render_format(config),
                     blend: Some(wgpu::BlendState::ALPHA_BLENDING), // Enable alpha blending for overlays
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -226,7 +279,8 @@ impl OverlaySystem {
                   mouse_pos: (f32, f32),
                   mouse_pressed: bool,
                   show_debug: bool,
-                  show_control: bool) {
+                  show_control: bool,
+                  show_beat_grid: bool) {
         self.mouse_position = mouse_pos;
         self.mouse_pressed = mouse_pressed;
 
@@ -235,13 +289,37 @@ impl OverlaySystem {
             overlay.enabled = match overlay.overlay_type {
                 OverlayType::DebugOverlay => show_debug,
                 OverlayType::ControlPanel => show_control,
+                OverlayType::BeatGrid => show_beat_grid,
             };
         }
     }
 
-    /// Render all enabled overlays
-    pub fn render(&self,
-                  wgpu_context: &WgpuContext,
+    /// Text for the debug overlay rows (see `debug_overlay_lines`); extra lines are ignored
+    pub fn set_debug_lines(&mut self, lines: Vec<String>) {
+        self.debug_lines = lines;
+    }
+
+    /// Lay out the debug text for this frame, or nothing while the debug overlay is hidden
+    fn queue_debug_text(&mut self, uniforms: &UniversalUniforms) {
+        self.text_renderer.clear();
+        let debug_visible = self.overlays.iter().any(|o| o.enabled && o.overlay_type == OverlayType::DebugOverlay);
+        if !debug_visible {
+            return;
+        }
+
+        let (min_x, min_y, max_x, max_y) = OverlayType::DebugOverlay.screen_region();
+        let resolution = (uniforms.screen_width as u32, uniforms.screen_height as u32);
+        let glyph_size = glyph_size_for_height(DEBUG_TEXT_HEIGHT * uniforms.text_scale.max(0.1), resolution);
+        for (line, row) in self.debug_lines.iter().zip(DEBUG_TEXT_ROWS) {
+            let origin = [min_x + DEBUG_TEXT_LEFT * (max_x - min_x), min_y + row * (max_y - min_y)];
+            self.text_renderer.queue_text(line, origin, glyph_size, DEBUG_TEXT_COLOR);
+        }
+    }
+
+    /// Record all enabled overlays into `frame`, on top of what the frame already drew to `view`
+    pub fn render(&mut self,
+                  queue: &wgpu::Queue,
+                  frame: &mut FrameEncoder,
                   view: &wgpu::TextureView,
                   uniforms: &UniversalUniforms) -> Result<()> {
 
@@ -252,18 +330,17 @@ impl OverlaySystem {
         }
 
         // Update uniform buffer with current data
-        wgpu_context.queue.write_buffer(
+        queue.write_buffer(
             &self.uniform_buffer,
             0,
             bytemuck::cast_slice(&[*uniforms]),
         );
 
-        let mut encoder = wgpu_context.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("Overlay Render Encoder"),
-        });
+        self.queue_debug_text(uniforms);
+        self.text_renderer.prepare(queue);
 
         {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            let mut render_pass = frame.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Overlay Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view,
@@ -292,12 +369,19 @@ impl OverlaySystem {
                     render_pass.draw_indexed(0..6, 0, 0..1); // Draw quad (6 indices)
                 }
             }
+
+            // Real text on top of the debug panel
+            self.text_renderer.draw(&mut render_pass);
         }
 
-        wgpu_context.queue.submit(std::iter::once(encoder.finish()));
         Ok(())
     }
 
+    /// Layout shared by the control panel shader and click handling
+    pub fn control_layout(&self) -> &ControlLayout {
+        &self.control_layout
+    }
+
     /// Handle mouse click events and return any UI interactions
     pub fn handle_mouse_click(&self, x: f32, y: f32) -> Vec<OverlayEvent> {
         let mut events = Vec::new();
@@ -316,7 +400,7 @@ impl OverlaySystem {
                 let local_y = (y - min_y) / (max_y - min_y);
 
                 // Generate events based on overlay type and click position
-                events.extend(self.process_overlay_click(overlay.overlay_type, local_x, local_y));
+                events.extend(Self::process_overlay_click(&self.control_layout, overlay.overlay_type, local_x, local_y));
             }
         }
 
@@ -324,47 +408,146 @@ impl OverlaySystem {
     }
 
     /// Process clicks within a specific overlay
-    fn process_overlay_click(&self, overlay_type: OverlayType, local_x: f32, local_y: f32) -> Vec<OverlayEvent> {
+    fn process_overlay_click(layout: &ControlLayout, overlay_type: OverlayType, local_x: f32, local_y: f32) -> Vec<OverlayEvent> {
         match overlay_type {
-            OverlayType::DebugOverlay => {
-                // Debug overlay doesn't have interactive elements currently
+            OverlayType::DebugOverlay | OverlayType::BeatGrid => {
+                // Debug overlay and beat grid don't have interactive elements currently
                 vec![]
             },
-            OverlayType::ControlPanel => {
-                // ASSUMPTION: Simplified UI layout for control panel
-                // Top row: volume control (y: 0.2-0.4)
-                // Middle row: file controls (y: 0.4-0.6)
-                // Bottom row: safety controls (y: 0.6-0.8)
-
-                if local_y >= 0.2 && local_y <= 0.4 {
-                    // Volume control area
-                    if local_x >= 0.1 && local_x <= 0.9 {
-                        let volume = local_x; // Volume based on X position
-                        return vec![OverlayEvent::VolumeChanged(volume)];
-                    }
-                } else if local_y >= 0.4 && local_y <= 0.6 {
-                    // File control area
-                    if local_x >= 0.1 && local_x <= 0.3 {
-                        return vec![OverlayEvent::OpenFile];
-                    } else if local_x >= 0.4 && local_x <= 0.5 {
-                        return vec![OverlayEvent::PreviousTrack];
-                    } else if local_x >= 0.6 && local_x <= 0.7 {
-                        return vec![OverlayEvent::NextTrack];
-                    }
-                } else if local_y >= 0.6 && local_y <= 0.8 {
-                    // Safety control area
-                    if local_x >= 0.1 && local_x <= 0.9 {
-                        return vec![OverlayEvent::ToggleSafety];
-                    }
-                }
-                vec![]
-            }
+            OverlayType::ControlPanel => layout.event_at(local_x, local_y).into_iter().collect(),
+        }
+    }
+}
+
+/// Axis-aligned rectangle in panel-local coordinates (0.0 to 1.0, y down)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ControlRect {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+impl ControlRect {
+    pub const fn new(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Self {
+        Self { min_x, min_y, max_x, max_y }
+    }
+
+    /// Rect of the given half extents around a center point
+    pub const fn centered(center_x: f32, center_y: f32, half_width: f32, half_height: f32) -> Self {
+        Self::new(center_x - half_width, center_y - half_height, center_x + half_width, center_y + half_height)
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+    }
+
+    pub fn center(&self) -> (f32, f32) {
+        ((self.min_x + self.max_x) * 0.5, (self.min_y + self.max_y) * 0.5)
+    }
+
+    /// Horizontal position of `x` across the rect, clamped to 0.0-1.0
+    pub fn fraction_x(&self, x: f32) -> f32 {
+        ((x - self.min_x) / (self.max_x - self.min_x)).clamp(0.0, 1.0)
+    }
+}
+
+/// One interactive control: where it is drawn and what clicking it does
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlElement {
+    pub rect: ControlRect,
+    /// Event to emit; the values of `VolumeChanged` and `Seek` are replaced by the click's position along the rect
+    pub event: OverlayEvent,
+}
+
+impl ControlElement {
+    pub fn event_at(&self, x: f32) -> OverlayEvent {
+        match self.event {
+            OverlayEvent::VolumeChanged(_) => OverlayEvent::VolumeChanged(self.rect.fraction_x(x)),
+            OverlayEvent::Seek(_) => OverlayEvent::Seek(self.rect.fraction_x(x)),
+            ref event => event.clone(),
+        }
+    }
+}
+
+/// Slots in `ControlLayout` (must match the indices in overlay_control.frag.wgsl)
+pub const CONTROL_VOLUME_SLIDER: usize = 0;
+pub const CONTROL_PREVIOUS_TRACK: usize = 1;
+pub const CONTROL_OPEN_FILE: usize = 2;
+pub const CONTROL_NEXT_TRACK: usize = 3;
+pub const CONTROL_SAFETY_LEVEL: usize = 4;
+pub const CONTROL_EMERGENCY_STOP: usize = 5;
+pub const CONTROL_PLAY_PAUSE: usize = 6;
+pub const CONTROL_SEEK_BAR: usize = 7;
+pub const CONTROL_ELEMENT_COUNT: usize = 8;
+
+/// Interactive elements of the control panel, in draw order (later elements are drawn on top)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlLayout {
+    elements: Vec<ControlElement>,
+}
+
+impl ControlLayout {
+    pub fn new() -> Self {
+        let element = |rect, event| ControlElement { rect, event };
+        Self {
+            elements: vec![
+                element(ControlRect::new(0.1, 0.29, 0.9, 0.33), OverlayEvent::VolumeChanged(0.0)),
+                element(ControlRect::centered(0.2, 0.52, 0.045, 0.045), OverlayEvent::PreviousTrack),
+                element(ControlRect::centered(0.5, 0.52, 0.08, 0.05), OverlayEvent::OpenFile),
+                element(ControlRect::centered(0.8, 0.52, 0.045, 0.045), OverlayEvent::NextTrack),
+                element(ControlRect::new(0.1, 0.65, 0.9, 0.68), OverlayEvent::ToggleSafety),
+                element(ControlRect::centered(0.5, 0.72, 0.08, 0.08), OverlayEvent::EmergencyStop),
+                element(ControlRect::new(0.05, 0.9, 0.15, 0.98), OverlayEvent::PlayPause),
+                element(ControlRect::new(0.2, 0.9, 0.9, 0.98), OverlayEvent::Seek(0.0)),
+            ],
+        }
+    }
+
+    pub fn elements(&self) -> &[ControlElement] {
+        &self.elements
+    }
+
+    pub fn element(&self, slot: usize) -> Option<&ControlElement> {
+        self.elements.get(slot)
+    }
+
+    /// Topmost element under a panel-local point
+    pub fn hit_test(&self, x: f32, y: f32) -> Option<&ControlElement> {
+        self.elements.iter().rev().find(|element| element.rect.contains(x, y))
+    }
+
+    /// Event for a click at a panel-local point, if it lands on an element
+    pub fn event_at(&self, x: f32, y: f32) -> Option<OverlayEvent> {
+        self.hit_test(x, y).map(|element| element.event_at(x))
+    }
+
+    /// Element rects for the control panel shader, as (min_x, min_y, max_x, max_y)
+    pub fn uniform(&self) -> ControlLayoutUniform {
+        let mut uniform: ControlLayoutUniform = bytemuck::Zeroable::zeroed();
+        for (slot, element) in uniform.rects.iter_mut().zip(&self.elements) {
+            let rect = element.rect;
+            *slot = [rect.min_x, rect.min_y, rect.max_x, rect.max_y];
         }
+        uniform
+    }
+}
+
+impl Default for ControlLayout {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+/// GPU copy of `ControlLayout` (binding 1 of the overlay bind group)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ControlLayoutUniform {
+    pub rects: [[f32; 4]; CONTROL_ELEMENT_COUNT],
+}
+
 /// Events that can be generated by overlay interactions
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum OverlayEvent {
     VolumeChanged(f32),
     OpenFile,
@@ -372,6 +555,8 @@ pub enum OverlayEvent {
     NextTrack,
     ToggleSafety,
     EmergencyStop,
+    PlayPause,
+    Seek(f32), // Fraction of the track (0.0 to 1.0)
 }
 
 /// Vertex structure for overlay rendering
@@ -417,4 +602,189 @@ fn create_overlay_vertices() -> Vec<OverlayVertex> {
 /// Create indices for overlay quads
 fn create_overlay_indices() -> Vec<u16> {
     vec![0, 1, 2, 2, 3, 0]
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: u32 = 64;
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None).await.ok()
+        })
+    }
+
+    #[test]
+    fn test_frame_with_overlays_is_one_submit() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping overlay test: no GPU adapter available");
+            return;
+        };
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: FORMAT,
+            width: SIZE,
+            height: SIZE,
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+        };
+        let mut overlays = OverlaySystem::with_device(&device, &queue, &config).expect("Overlay pipelines should build");
+        overlays.update((0.5, 0.5), false, true, true, true);
+        overlays.set_debug_lines(debug_overlay_lines(&UniversalUniforms::default(), "Classic"));
+
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("overlay_test_target"),
+            size: wgpu::Extent3d { width: SIZE, height: SIZE, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // The visualization pass and every overlay share one encoder
+        let mut frame = FrameEncoder::new(&device);
+        {
+            let _scene_pass = frame.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("overlay_test_scene_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLUE), store: wgpu::StoreOp::Store },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+        }
+        let uniforms = UniversalUniforms { screen_width: SIZE as f32, screen_height: SIZE as f32, ..UniversalUniforms::default() };
+        overlays.render(&queue, &mut frame, &view, &uniforms).expect("Overlays should record");
+        assert_eq!(frame.pass_count(), 2);
+        assert!(overlays.text_renderer.glyph_count() > 0);
+
+        // Submitting consumes the frame, so it can only go to the queue once
+        frame.submit(&queue);
+        device.poll(wgpu::Maintain::Wait);
+    }
+
+    fn control_click(local_x: f32, local_y: f32) -> Vec<OverlayEvent> {
+        OverlaySystem::process_overlay_click(&ControlLayout::default(), OverlayType::ControlPanel, local_x, local_y)
+    }
+
+    #[test]
+    fn test_volume_slider_center_click() {
+        let layout = ControlLayout::default();
+        let slider = layout.element(CONTROL_VOLUME_SLIDER).unwrap().rect;
+        let (center_x, center_y) = slider.center();
+        match control_click(center_x, center_y)[..] {
+            [OverlayEvent::VolumeChanged(volume)] => assert!((volume - 0.5).abs() < 1e-5),
+            ref other => panic!("Expected volume event, got {:?}", other),
+        }
+
+        // Value follows the position along the drawn track, not the whole panel width
+        let quarter_x = slider.min_x + (slider.max_x - slider.min_x) * 0.25;
+        match control_click(quarter_x, center_y)[..] {
+            [OverlayEvent::VolumeChanged(volume)] => assert!((volume - 0.25).abs() < 1e-5),
+            ref other => panic!("Expected volume event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_seek_region_click_maps_to_fraction() {
+        let seek_bar = ControlLayout::default().element(CONTROL_SEEK_BAR).unwrap().rect;
+        let (middle_x, middle_y) = seek_bar.center();
+        let events = control_click(middle_x, middle_y);
+        assert_eq!(events.len(), 1);
+        match events[0] {
+            OverlayEvent::Seek(fraction) => assert!((fraction - 0.5).abs() < 1e-5),
+            ref other => panic!("Expected seek event, got {:?}", other),
+        }
+
+        let start = control_click(seek_bar.min_x, middle_y);
+        assert_eq!(start, vec![OverlayEvent::Seek(0.0)]);
+    }
+
+    #[test]
+    fn test_buttons_hit_where_drawn() {
+        assert_eq!(control_click(0.1, 0.94), vec![OverlayEvent::PlayPause]);
+        assert_eq!(control_click(0.2, 0.52), vec![OverlayEvent::PreviousTrack]);
+        assert_eq!(control_click(0.5, 0.52), vec![OverlayEvent::OpenFile]);
+        assert_eq!(control_click(0.8, 0.52), vec![OverlayEvent::NextTrack]);
+
+        // Gaps between elements do nothing
+        assert!(control_click(0.35, 0.52).is_empty());
+        assert!(control_click(0.5, 0.1).is_empty());
+    }
+
+    #[test]
+    fn test_hit_test_prefers_topmost_element() {
+        let layout = ControlLayout::default();
+        let emergency = layout.element(CONTROL_EMERGENCY_STOP).unwrap().rect;
+        let safety = layout.element(CONTROL_SAFETY_LEVEL).unwrap().rect;
+        assert!(safety.contains(0.5, 0.66) && emergency.contains(0.5, 0.66));
+
+        assert_eq!(layout.event_at(0.5, 0.66), Some(OverlayEvent::EmergencyStop));
+        assert_eq!(layout.event_at(0.2, 0.66), Some(OverlayEvent::ToggleSafety));
+    }
+
+    #[test]
+    fn test_layout_uniform_matches_slots() {
+        let layout = ControlLayout::default();
+        assert_eq!(layout.elements().len(), CONTROL_ELEMENT_COUNT);
+
+        let uniform = layout.uniform();
+        let slider = layout.element(CONTROL_VOLUME_SLIDER).unwrap().rect;
+        assert_eq!(uniform.rects[CONTROL_VOLUME_SLIDER], [slider.min_x, slider.min_y, slider.max_x, slider.max_y]);
+        assert_eq!(std::mem::size_of::<ControlLayoutUniform>() % 16, 0);
+    }
+
+    #[test]
+    fn test_debug_lines_format_overlay_values() {
+        let uniforms = UniversalUniforms {
+            ui_volume: 0.8,
+            signal_level_db: -23.4,
+            estimated_bpm: 127.6,
+            tempo_confidence: 0.9,
+            ui_fps: 59.7,
+            ui_frame_time: 16.74,
+            ..UniversalUniforms::default()
+        };
+        let lines = debug_overlay_lines(&uniforms, "Plasma");
+        assert_eq!(lines, vec![
+            "SHADER: Plasma".to_string(),
+            "VOL 80%  -23 DB".to_string(),
+            "BPM 128 (90%)".to_string(),
+            "FPS 60  16.7 MS".to_string(),
+        ]);
+        assert_eq!(lines.len(), DEBUG_TEXT_ROWS.len());
+    }
+
+    #[test]
+    fn test_beat_grid_overlay_source() {
+        assert_eq!(OverlayType::BeatGrid.name(), "Beat Grid");
+
+        let source = OverlayType::BeatGrid.shader_source();
+        assert!(!source.is_empty());
+        assert!(source.contains("UniversalUniforms"));
+        assert!(source.contains("fn fs_main"));
+        assert!(source.contains("uniforms.beat_position"));
+        assert!(source.contains("uniforms.downbeat_detected"));
+    }
+
+    #[test]
+    fn test_beat_grid_ignores_clicks() {
+        let (min_x, min_y, max_x, max_y) = OverlayType::BeatGrid.screen_region();
+        assert!(min_x < max_x && min_y < max_y && max_y <= 1.0);
+        assert!(OverlaySystem::process_overlay_click(&ControlLayout::default(), OverlayType::BeatGrid, 0.5, 0.5).is_empty());
+    }
+}
@@ -1,7 +1,12 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+use crate::clock::{system_clock, SharedClock};
+
 /// Performance quality levels for adaptive rendering
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum QualityLevel {
     /// Maximum quality with full effects
     Ultra,
@@ -106,6 +111,66 @@ impl Default for PerformanceMetrics {
     }
 }
 
+/// Maximum number of quality changes kept for diagnostics
+const MAX_QUALITY_HISTORY: usize = 32;
+
+/// Why the performance manager changed quality
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityChangeReason {
+    SustainedLowFps,
+    SustainedHeadroom,
+    ManualOverride,
+}
+
+impl QualityChangeReason {
+    pub fn name(&self) -> &'static str {
+        match self {
+            QualityChangeReason::SustainedLowFps => "sustained low FPS",
+            QualityChangeReason::SustainedHeadroom => "sustained FPS headroom",
+            QualityChangeReason::ManualOverride => "manual override",
+        }
+    }
+
+    /// Numeric code for shader uniforms (0 is reserved for "no change yet")
+    pub fn code(&self) -> f32 {
+        match self {
+            QualityChangeReason::SustainedLowFps => 1.0,
+            QualityChangeReason::SustainedHeadroom => 2.0,
+            QualityChangeReason::ManualOverride => 3.0,
+        }
+    }
+}
+
+/// A single entry in the quality decision timeline
+#[derive(Debug, Clone)]
+pub struct QualityChangeEvent {
+    pub timestamp: Instant,
+    pub quality: QualityLevel,
+    pub reason: QualityChangeReason,
+}
+
+/// When adaptive quality steps down or up, relative to the frame budget
+///
+/// Longer runs make adaptation lazier: fewer visible quality pops, slower recovery.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveThresholds {
+    pub poor_ratio: f32,  // Load over the budget by this factor is a poor frame
+    pub good_ratio: f32,  // Load under the budget by this factor is headroom
+    pub poor_frames: u32, // Consecutive poor frames before quality drops
+    pub good_frames: u32, // Consecutive headroom frames before quality rises
+}
+
+impl Default for AdaptiveThresholds {
+    fn default() -> Self {
+        Self {
+            poor_ratio: 1.2, // 20% over target
+            good_ratio: 0.8, // 20% under target
+            poor_frames: 5,
+            good_frames: 15,
+        }
+    }
+}
+
 /// Adaptive performance manager
 pub struct PerformanceManager {
     current_quality: QualityLevel,
@@ -115,21 +180,52 @@ pub struct PerformanceManager {
     adjustment_cooldown: Duration,
     consecutive_poor_frames: u32,
     consecutive_good_frames: u32,
+    quality_history: VecDeque<QualityChangeEvent>, // Bounded timeline of quality changes
+    quality_locked: bool,                           // When set, update() never changes quality
+    adaptive: bool,                                 // Cleared to freeze quality regardless of overrides
+    thresholds: AdaptiveThresholds,
+    clock: SharedClock,
 }
 
 impl PerformanceManager {
     pub fn new(target_fps: f32) -> Self {
+        Self::with_clock(target_fps, system_clock())
+    }
+
+    /// Create a manager whose adjustment cooldown runs on a custom time source
+    pub fn with_clock(target_fps: f32, clock: SharedClock) -> Self {
         Self {
             current_quality: QualityLevel::High, // Start optimistic
             target_fps,
             metrics_history: Vec::with_capacity(60), // Store 1 second of history
-            last_adjustment: Instant::now(),
+            last_adjustment: clock.now(),
             adjustment_cooldown: Duration::from_secs(2), // Don't adjust too frequently
             consecutive_poor_frames: 0,
             consecutive_good_frames: 0,
+            quality_history: VecDeque::with_capacity(MAX_QUALITY_HISTORY),
+            quality_locked: false,
+            adaptive: true,
+            thresholds: AdaptiveThresholds::default(),
+            clock,
         }
     }
 
+    /// Frame rate the quality adjustments aim for; ratios are measured against its frame time
+    pub fn set_target_fps(&mut self, target_fps: f32) {
+        self.target_fps = if target_fps.is_finite() { target_fps.max(1.0) } else { 60.0 };
+        self.consecutive_poor_frames = 0;
+        self.consecutive_good_frames = 0;
+    }
+
+    pub fn target_fps(&self) -> f32 {
+        self.target_fps
+    }
+
+    /// Frame budget at the target rate
+    pub fn target_frame_time(&self) -> Duration {
+        Duration::from_secs_f32(1.0 / self.target_fps)
+    }
+
     /// Update performance metrics and potentially adjust quality
     pub fn update(&mut self, metrics: PerformanceMetrics) -> bool {
         let mut quality_changed = false;
@@ -140,25 +236,32 @@ impl PerformanceManager {
             self.metrics_history.remove(0);
         }
 
-        // Check if we should consider adjusting quality
-        if self.last_adjustment.elapsed() >= self.adjustment_cooldown {
-            let target_frame_time = Duration::from_secs_f32(1.0 / self.target_fps);
-            let performance_ratio = metrics.frame_time.as_secs_f32() / target_frame_time.as_secs_f32();
+        // A locked or frozen quality level is never adjusted automatically
+        if self.quality_locked || !self.adaptive {
+            return false;
+        }
 
-            if performance_ratio > 1.2 {
-                // Frame time is 20% over target
+        // Check if we should consider adjusting quality
+        if self.clock.now().duration_since(self.last_adjustment) >= self.adjustment_cooldown {
+            // V-sync pins frame time at the target, so measured GPU time also counts as load
+            let target_frame_time = self.target_frame_time();
+            let load = metrics.frame_time.max(metrics.gpu_time);
+            let performance_ratio = load.as_secs_f32() / target_frame_time.as_secs_f32();
+
+            if performance_ratio > self.thresholds.poor_ratio {
+                // Frame time is over target
                 self.consecutive_poor_frames += 1;
                 self.consecutive_good_frames = 0;
 
-                if self.consecutive_poor_frames >= 5 {
+                if self.consecutive_poor_frames >= self.thresholds.poor_frames {
                     quality_changed = self.decrease_quality();
                 }
-            } else if performance_ratio < 0.8 {
-                // Frame time is 20% under target - we have headroom
+            } else if performance_ratio < self.thresholds.good_ratio {
+                // Frame time is under target - we have headroom
                 self.consecutive_good_frames += 1;
                 self.consecutive_poor_frames = 0;
 
-                if self.consecutive_good_frames >= 15 {
+                if self.consecutive_good_frames >= self.thresholds.good_frames {
                     quality_changed = self.increase_quality();
                 }
             } else {
@@ -185,7 +288,8 @@ impl PerformanceManager {
 
         if self.current_quality != old_quality {
             println!("🔻 Performance: Decreased quality to {:?}", self.current_quality);
-            self.last_adjustment = Instant::now();
+            self.record_quality_change(QualityChangeReason::SustainedLowFps);
+            self.last_adjustment = self.clock.now();
             self.consecutive_poor_frames = 0;
             true
         } else {
@@ -207,7 +311,8 @@ impl PerformanceManager {
 
         if self.current_quality != old_quality {
             println!("🔺 Performance: Increased quality to {:?}", self.current_quality);
-            self.last_adjustment = Instant::now();
+            self.record_quality_change(QualityChangeReason::SustainedHeadroom);
+            self.last_adjustment = self.clock.now();
             self.consecutive_good_frames = 0;
             true
         } else {
@@ -215,6 +320,81 @@ impl PerformanceManager {
         }
     }
 
+    /// Lock or unlock adaptive quality; unlike `set_quality` this persists across updates
+    pub fn lock_quality(&mut self, locked: bool) {
+        if self.quality_locked != locked {
+            println!("🔒 Performance: Quality {}", if locked { "locked" } else { "adaptive" });
+        }
+        self.quality_locked = locked;
+        self.consecutive_poor_frames = 0;
+        self.consecutive_good_frames = 0;
+    }
+
+    pub fn is_quality_locked(&self) -> bool {
+        self.quality_locked
+    }
+
+    /// Turn automatic quality changes on or off; metrics are still tracked while off.
+    /// Independent of `lock_quality`, so a quality override coming and going doesn't re-enable it
+    pub fn set_adaptive(&mut self, adaptive: bool) {
+        if self.adaptive != adaptive {
+            println!("🔒 Performance: Adaptive quality {}", if adaptive { "enabled" } else { "frozen" });
+        }
+        self.adaptive = adaptive;
+        self.consecutive_poor_frames = 0;
+        self.consecutive_good_frames = 0;
+    }
+
+    pub fn is_adaptive(&self) -> bool {
+        self.adaptive
+    }
+
+    /// Tune when quality steps down or up; rejects ratios that would overlap or never trigger
+    pub fn set_thresholds(&mut self, thresholds: AdaptiveThresholds) -> Result<()> {
+        let AdaptiveThresholds { poor_ratio, good_ratio, poor_frames, good_frames } = thresholds;
+        if !(poor_ratio.is_finite() && good_ratio.is_finite()) || good_ratio <= 0.0 || good_ratio >= poor_ratio {
+            bail!("Adaptive quality ratios need 0 < good ({}) < poor ({})", good_ratio, poor_ratio);
+        }
+        if poor_frames == 0 || good_frames == 0 {
+            bail!("Adaptive quality frame counts must be at least 1");
+        }
+        self.thresholds = thresholds;
+        self.consecutive_poor_frames = 0;
+        self.consecutive_good_frames = 0;
+        Ok(())
+    }
+
+    pub fn thresholds(&self) -> AdaptiveThresholds {
+        self.thresholds
+    }
+
+    fn record_quality_change(&mut self, reason: QualityChangeReason) {
+        if self.quality_history.len() >= MAX_QUALITY_HISTORY {
+            self.quality_history.pop_front();
+        }
+        self.quality_history.push_back(QualityChangeEvent {
+            timestamp: self.clock.now(),
+            quality: self.current_quality,
+            reason,
+        });
+    }
+
+    /// Timeline of quality changes, oldest first
+    pub fn quality_history(&self) -> &VecDeque<QualityChangeEvent> {
+        &self.quality_history
+    }
+
+    /// Most recent quality change, if any
+    pub fn last_quality_change(&self) -> Option<&QualityChangeEvent> {
+        self.quality_history.back()
+    }
+
+    /// Seconds since the most recent quality change
+    pub fn time_since_quality_change(&self) -> Option<Duration> {
+        self.last_quality_change()
+            .map(|event| self.clock.now().duration_since(event.timestamp))
+    }
+
     /// Get current quality level
     pub fn current_quality(&self) -> QualityLevel {
         self.current_quality
@@ -225,7 +405,8 @@ impl PerformanceManager {
         if self.current_quality != quality {
             println!("🎛️  Performance: Quality manually set to {:?}", quality);
             self.current_quality = quality;
-            self.last_adjustment = Instant::now();
+            self.record_quality_change(QualityChangeReason::ManualOverride);
+            self.last_adjustment = self.clock.now();
             self.consecutive_poor_frames = 0;
             self.consecutive_good_frames = 0;
         }
@@ -262,26 +443,68 @@ impl PerformanceManager {
 
     /// Get performance report for debugging
     pub fn performance_report(&self) -> String {
-        format!(
+        let mut report = format!(
             "Quality: {:?} | Avg FPS: {:.1} | P99 Frame Time: {:.1}ms | History: {} samples",
             self.current_quality,
             self.average_fps(),
             self.percentile_99_frame_time().as_secs_f32() * 1000.0,
             self.metrics_history.len()
-        )
+        );
+
+        if let (Some(event), Some(age)) = (self.last_quality_change(), self.time_since_quality_change()) {
+            report.push_str(&format!(" | Last change: {:?} ({}, {:.0}s ago)", event.quality, event.reason.name(), age.as_secs_f32()));
+        }
+
+        report
     }
 }
 
 /// GPU capability detection and shader compatibility
+#[derive(Debug, Clone)]
 pub struct GpuCapabilities {
     pub max_texture_size: u32,
     pub max_compute_workgroups: u32,
     pub supports_compute_shaders: bool,
     pub memory_gb: f32,
     pub recommended_quality: QualityLevel,
+    pub device_type: wgpu::DeviceType,
+    pub adapter_name: String,
+    pub software_rendering: bool, // CPU adapter (e.g. llvmpipe): expect very low FPS
 }
 
 impl GpuCapabilities {
+    /// Detect GPU capabilities from the adapter's limits and info
+    pub fn from_adapter(limits: &wgpu::Limits, info: &wgpu::AdapterInfo) -> Self {
+        let mut capabilities = Self::detect_with_device_type(limits, info.device_type);
+        capabilities.adapter_name = info.name.clone();
+        capabilities
+    }
+
+    /// Detect capabilities, forcing Potato quality on software (CPU) adapters
+    pub fn detect_with_device_type(limits: &wgpu::Limits, device_type: wgpu::DeviceType) -> Self {
+        let mut capabilities = Self::detect(limits);
+        capabilities.device_type = device_type;
+
+        if device_type == wgpu::DeviceType::Cpu {
+            capabilities.software_rendering = true;
+            capabilities.recommended_quality = QualityLevel::Potato;
+        }
+
+        capabilities
+    }
+
+    /// User-facing warning when rendering will be slow
+    pub fn warning_message(&self) -> Option<String> {
+        if self.software_rendering {
+            Some(format!(
+                "Software renderer detected ({}): rendering on the CPU will be very slow. Quality defaults to Potato; install GPU drivers for full speed.",
+                if self.adapter_name.is_empty() { "unknown adapter" } else { &self.adapter_name }
+            ))
+        } else {
+            None
+        }
+    }
+
     /// Detect GPU capabilities from WGPU limits
     pub fn detect(limits: &wgpu::Limits) -> Self {
         let max_texture_size = limits.max_texture_dimension_2d;
@@ -306,6 +529,9 @@ impl GpuCapabilities {
             supports_compute_shaders: max_compute_workgroups > 0,
             memory_gb: 2.0, // Conservative estimate
             recommended_quality,
+            device_type: wgpu::DeviceType::Other,
+            adapter_name: String::new(),
+            software_rendering: false,
         }
     }
 
@@ -358,9 +584,95 @@ impl From<QualityLevel> for PerformanceUniforms {
     }
 }
 
+impl PerformanceUniforms {
+    /// Linear blend between two sets of quality scalars
+    pub fn lerp(&self, other: &PerformanceUniforms, t: f32) -> PerformanceUniforms {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        PerformanceUniforms {
+            quality_level: mix(self.quality_level, other.quality_level),
+            complexity_multiplier: mix(self.complexity_multiplier, other.complexity_multiplier),
+            max_iterations: mix(self.max_iterations, other.max_iterations),
+            effect_intensity: mix(self.effect_intensity, other.effect_intensity),
+            resolution_scale: mix(self.resolution_scale, other.resolution_scale),
+            enable_advanced_effects: mix(self.enable_advanced_effects, other.enable_advanced_effects),
+            enable_particles: mix(self.enable_particles, other.enable_particles),
+            noise_octaves: mix(self.noise_octaves, other.noise_octaves),
+        }
+    }
+}
+
+/// Default time to blend quality scalars after a level change
+pub const QUALITY_TRANSITION_DURATION: Duration = Duration::from_millis(500);
+
+/// Interpolates quality-derived uniforms so level changes don't pop
+#[derive(Debug)]
+pub struct QualityTransition {
+    start: PerformanceUniforms,
+    target: PerformanceUniforms,
+    target_quality: QualityLevel,
+    started_at: Instant,
+    duration: Duration,
+    clock: SharedClock,
+}
+
+impl QualityTransition {
+    pub fn new(quality: QualityLevel) -> Self {
+        Self::with_clock(quality, system_clock())
+    }
+
+    /// Create a transition driven by a custom time source
+    pub fn with_clock(quality: QualityLevel, clock: SharedClock) -> Self {
+        let uniforms = PerformanceUniforms::from(quality);
+        Self {
+            start: uniforms,
+            target: uniforms,
+            target_quality: quality,
+            started_at: clock.now(),
+            duration: QUALITY_TRANSITION_DURATION,
+            clock,
+        }
+    }
+
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    /// Begin blending toward a new level; repeated calls with the same level are ignored
+    pub fn set_target(&mut self, quality: QualityLevel) {
+        if quality == self.target_quality {
+            return;
+        }
+
+        self.start = self.current();
+        self.target = PerformanceUniforms::from(quality);
+        self.target_quality = quality;
+        self.started_at = self.clock.now();
+    }
+
+    /// Blend progress from 0.0 (just changed) to 1.0 (settled)
+    pub fn progress(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let elapsed = self.clock.now().duration_since(self.started_at);
+        (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    /// Quality scalars for the current moment
+    pub fn current(&self) -> PerformanceUniforms {
+        self.start.lerp(&self.target, self.progress())
+    }
+
+    pub fn target_quality(&self) -> QualityLevel {
+        self.target_quality
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
 
     #[test]
     fn test_quality_level_properties() {
@@ -379,7 +691,8 @@ mod tests {
 
     #[test]
     fn test_performance_adjustment() {
-        let mut manager = PerformanceManager::new(60.0);
+        let clock = MockClock::new();
+        let mut manager = PerformanceManager::with_clock(60.0, clock.shared());
 
         // Simulate poor performance
         let poor_metrics = PerformanceMetrics {
@@ -391,8 +704,8 @@ mod tests {
         // Should not adjust immediately due to cooldown
         assert!(!manager.update(poor_metrics.clone()));
 
-        // Force cooldown to pass
-        manager.last_adjustment = Instant::now() - Duration::from_secs(3);
+        // Let the cooldown pass
+        clock.advance(Duration::from_secs(3));
 
         // Feed several poor frames
         for _ in 0..6 {
@@ -403,6 +716,206 @@ mod tests {
         assert_ne!(manager.current_quality(), QualityLevel::High);
     }
 
+    #[test]
+    fn test_target_frame_time_follows_target_fps() {
+        let manager = PerformanceManager::new(144.0);
+        assert_eq!(manager.target_fps(), 144.0);
+        assert!((manager.target_frame_time().as_secs_f64() - 1.0 / 144.0).abs() < 1e-6);
+
+        let mut manager = PerformanceManager::new(60.0);
+        manager.set_target_fps(0.0);
+        assert_eq!(manager.target_fps(), 1.0);
+    }
+
+    #[test]
+    fn test_ratios_scale_with_target_fps() {
+        // 10ms frames are headroom at 60 FPS but 44% over budget at 144 FPS
+        let frame = PerformanceMetrics {
+            frame_time: Duration::from_millis(10),
+            gpu_time: Duration::from_millis(5),
+            ..Default::default()
+        };
+
+        let clock = MockClock::new();
+        let mut fast = PerformanceManager::with_clock(144.0, clock.shared());
+        let mut standard = PerformanceManager::with_clock(60.0, clock.shared());
+        clock.advance(Duration::from_secs(3));
+        for _ in 0..5 {
+            fast.update(frame.clone());
+            standard.update(frame.clone());
+        }
+        assert_eq!(fast.current_quality(), QualityLevel::Medium);
+        assert_eq!(standard.current_quality(), QualityLevel::High);
+    }
+
+    #[test]
+    fn test_gpu_bound_frames_reduce_quality_under_vsync() {
+        let clock = MockClock::new();
+        let mut manager = PerformanceManager::with_clock(60.0, clock.shared());
+
+        // Frame pacing looks perfect, but the GPU needs more than the frame budget
+        let gpu_bound = PerformanceMetrics {
+            frame_time: Duration::from_micros(16_667),
+            gpu_time: Duration::from_millis(24),
+            ..Default::default()
+        };
+
+        clock.advance(Duration::from_secs(3));
+        for _ in 0..5 {
+            manager.update(gpu_bound.clone());
+        }
+        assert_eq!(manager.current_quality(), QualityLevel::Medium);
+    }
+
+    #[test]
+    fn test_quality_history_records_low_fps_decreases() {
+        let clock = MockClock::new();
+        let mut manager = PerformanceManager::with_clock(60.0, clock.shared());
+        assert!(manager.last_quality_change().is_none());
+
+        let poor_metrics = PerformanceMetrics {
+            frame_time: Duration::from_millis(30),
+            fps: 33.0,
+            ..Default::default()
+        };
+
+        // Two rounds of sustained poor frames, each after the cooldown
+        for _ in 0..2 {
+            clock.advance(Duration::from_secs(3));
+            for _ in 0..5 {
+                manager.update(poor_metrics.clone());
+            }
+        }
+
+        let history = manager.quality_history();
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|e| e.reason == QualityChangeReason::SustainedLowFps));
+        assert_eq!(history[0].quality, QualityLevel::Medium);
+        assert_eq!(history[1].quality, QualityLevel::Low);
+        assert_eq!(manager.last_quality_change().unwrap().reason.name(), "sustained low FPS");
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(manager.time_since_quality_change(), Some(Duration::from_secs(1)));
+
+        manager.set_quality(QualityLevel::Ultra);
+        assert_eq!(manager.last_quality_change().unwrap().reason, QualityChangeReason::ManualOverride);
+    }
+
+    #[test]
+    fn test_locked_quality_ignores_poor_frames() {
+        let clock = MockClock::new();
+        let mut manager = PerformanceManager::with_clock(60.0, clock.shared());
+        manager.set_quality(QualityLevel::Ultra);
+        manager.lock_quality(true);
+
+        let poor_metrics = PerformanceMetrics {
+            frame_time: Duration::from_millis(40),
+            fps: 25.0,
+            ..Default::default()
+        };
+
+        clock.advance(Duration::from_secs(3));
+        for _ in 0..20 {
+            assert!(!manager.update(poor_metrics.clone()));
+        }
+        assert_eq!(manager.current_quality(), QualityLevel::Ultra);
+
+        // Unlocking hands control back to the adaptive logic
+        manager.lock_quality(false);
+        for _ in 0..5 {
+            manager.update(poor_metrics.clone());
+        }
+        assert_eq!(manager.current_quality(), QualityLevel::High);
+    }
+
+    #[test]
+    fn test_frozen_adaptive_quality_never_changes() {
+        let clock = MockClock::new();
+        let mut manager = PerformanceManager::with_clock(60.0, clock.shared());
+        manager.set_adaptive(false);
+        assert!(!manager.is_adaptive());
+
+        let poor_metrics = PerformanceMetrics {
+            frame_time: Duration::from_millis(40),
+            fps: 25.0,
+            ..Default::default()
+        };
+
+        for _ in 0..10 {
+            clock.advance(Duration::from_secs(3));
+            for _ in 0..20 {
+                assert!(!manager.update(poor_metrics.clone()));
+            }
+        }
+        assert_eq!(manager.current_quality(), QualityLevel::High);
+        assert!(manager.quality_history().is_empty());
+
+        // Metrics are still tracked while frozen
+        assert!((manager.average_fps() - 25.0).abs() < 1e-3);
+
+        // Unlocking a quality override doesn't thaw it
+        manager.lock_quality(true);
+        manager.lock_quality(false);
+        for _ in 0..20 {
+            manager.update(poor_metrics.clone());
+        }
+        assert_eq!(manager.current_quality(), QualityLevel::High);
+    }
+
+    #[test]
+    fn test_custom_thresholds_set_trigger_counts() {
+        assert_eq!(
+            AdaptiveThresholds::default(),
+            AdaptiveThresholds { poor_ratio: 1.2, good_ratio: 0.8, poor_frames: 5, good_frames: 15 }
+        );
+
+        let clock = MockClock::new();
+        let mut manager = PerformanceManager::with_clock(60.0, clock.shared());
+        let lazy = AdaptiveThresholds { poor_ratio: 1.5, good_ratio: 0.5, poor_frames: 30, good_frames: 4 };
+        manager.set_thresholds(lazy).unwrap();
+        assert_eq!(manager.thresholds(), lazy);
+        clock.advance(Duration::from_secs(3));
+
+        // 30% over budget was poor by default, but sits inside the lazier band
+        let slightly_slow = PerformanceMetrics { frame_time: Duration::from_micros(21_667), gpu_time: Duration::ZERO, ..Default::default() };
+        for _ in 0..40 {
+            assert!(!manager.update(slightly_slow.clone()));
+        }
+
+        // Twice the budget drops quality on exactly the 30th frame
+        let slow = PerformanceMetrics { frame_time: Duration::from_millis(33), gpu_time: Duration::ZERO, ..Default::default() };
+        for _ in 0..29 {
+            assert!(!manager.update(slow.clone()));
+        }
+        assert!(manager.update(slow));
+        assert_eq!(manager.current_quality(), QualityLevel::Medium);
+
+        // Headroom raises it again on exactly the 4th frame after the cooldown
+        clock.advance(Duration::from_secs(3));
+        let fast = PerformanceMetrics { frame_time: Duration::from_millis(5), gpu_time: Duration::ZERO, ..Default::default() };
+        for _ in 0..3 {
+            assert!(!manager.update(fast.clone()));
+        }
+        assert!(manager.update(fast));
+        assert_eq!(manager.current_quality(), QualityLevel::High);
+
+        // Overlapping or empty thresholds are rejected and leave the current ones in place
+        assert!(manager.set_thresholds(AdaptiveThresholds { good_ratio: 1.3, poor_ratio: 1.2, ..lazy }).is_err());
+        assert!(manager.set_thresholds(AdaptiveThresholds { poor_frames: 0, ..lazy }).is_err());
+        assert!(manager.set_thresholds(AdaptiveThresholds { poor_ratio: f32::NAN, ..lazy }).is_err());
+        assert_eq!(manager.thresholds(), lazy);
+    }
+
+    #[test]
+    fn test_quality_history_is_bounded() {
+        let mut manager = PerformanceManager::new(60.0);
+        for i in 0..(MAX_QUALITY_HISTORY * 2) {
+            let quality = if i % 2 == 0 { QualityLevel::Low } else { QualityLevel::Medium };
+            manager.set_quality(quality);
+        }
+        assert_eq!(manager.quality_history().len(), MAX_QUALITY_HISTORY);
+    }
+
     #[test]
     fn test_gpu_capabilities_detection() {
         let limits = wgpu::Limits {
@@ -417,6 +930,63 @@ mod tests {
         assert!(!capabilities.supports_shader(10, QualityLevel::Medium));
     }
 
+    #[test]
+    fn test_quality_transition_reaches_target_over_duration() {
+        let clock = MockClock::new();
+        let mut transition = QualityTransition::with_clock(QualityLevel::High, clock.shared());
+        transition.set_target(QualityLevel::Medium);
+
+        let high = PerformanceUniforms::from(QualityLevel::High);
+        let medium = PerformanceUniforms::from(QualityLevel::Medium);
+
+        // No jump at the moment of change
+        assert_eq!(transition.current().resolution_scale, high.resolution_scale);
+
+        clock.advance(QUALITY_TRANSITION_DURATION / 2);
+        let halfway = transition.current();
+        assert!((halfway.resolution_scale - 0.9).abs() < 1e-4);
+        assert!((halfway.effect_intensity - (high.effect_intensity + medium.effect_intensity) / 2.0).abs() < 1e-4);
+
+        clock.advance(QUALITY_TRANSITION_DURATION / 2);
+        let settled = transition.current();
+        assert_eq!(transition.progress(), 1.0);
+        assert!((settled.resolution_scale - medium.resolution_scale).abs() < 1e-6);
+        assert!((settled.effect_intensity - medium.effect_intensity).abs() < 1e-6);
+        assert!((settled.complexity_multiplier - medium.complexity_multiplier).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_quality_transition_retarget_starts_from_current_blend() {
+        let clock = MockClock::new();
+        let mut transition = QualityTransition::with_clock(QualityLevel::High, clock.shared());
+        transition.set_target(QualityLevel::Potato);
+        clock.advance(QUALITY_TRANSITION_DURATION / 2);
+        let midway = transition.current();
+
+        // Same target again must not restart the blend
+        transition.set_target(QualityLevel::Potato);
+        assert_eq!(transition.current().resolution_scale, midway.resolution_scale);
+
+        transition.set_target(QualityLevel::Ultra);
+        assert_eq!(transition.current().resolution_scale, midway.resolution_scale);
+        assert_eq!(transition.target_quality(), QualityLevel::Ultra);
+    }
+
+    #[test]
+    fn test_cpu_adapter_forces_potato_quality() {
+        let limits = wgpu::Limits::default();
+
+        let gpu = GpuCapabilities::detect_with_device_type(&limits, wgpu::DeviceType::DiscreteGpu);
+        assert!(!gpu.software_rendering);
+        assert_ne!(gpu.recommended_quality, QualityLevel::Potato);
+        assert!(gpu.warning_message().is_none());
+
+        let cpu = GpuCapabilities::detect_with_device_type(&limits, wgpu::DeviceType::Cpu);
+        assert!(cpu.software_rendering);
+        assert_eq!(cpu.recommended_quality, QualityLevel::Potato);
+        assert!(cpu.warning_message().unwrap().contains("Potato"));
+    }
+
     #[test]
     fn test_performance_uniforms_conversion() {
         let uniforms = PerformanceUniforms::from(QualityLevel::Medium);
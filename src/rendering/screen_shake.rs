@@ -0,0 +1,242 @@
+use std::time::{Duration, Instant};
+
+use crate::audio::AudioFeatures;
+use crate::clock::{system_clock, SharedClock};
+use crate::control::safety::SafetyMultipliers;
+
+/// Largest displacement in texture-coordinate units (2% of the screen)
+pub const MAX_SCREEN_SHAKE: f32 = 0.02;
+
+/// Time for a shake to fall to ~37% of its strength
+pub const SCREEN_SHAKE_DECAY: Duration = Duration::from_millis(150);
+
+/// Bass-weighted onset energy needed before the screen moves at all
+const SHAKE_THRESHOLD: f32 = 0.05;
+
+/// Largest zoom-pulse enlargement (`pulse_scale` of 1.06 at full strength)
+pub const MAX_PULSE_SCALE: f32 = 0.06;
+
+/// Largest upward kick of the image on a pulse, in texture-coordinate units
+pub const MAX_PULSE_OFFSET: f32 = 0.01;
+
+/// Default time for the zoom pulse to swell after an onset
+pub const DEFAULT_PULSE_ATTACK: Duration = Duration::from_millis(40);
+
+/// Default time for the zoom pulse to settle back
+pub const DEFAULT_PULSE_DECAY: Duration = Duration::from_millis(250);
+
+/// Shortest allowed decay; faster release would let dense onsets flicker the zoom
+pub const MIN_PULSE_DECAY: Duration = Duration::from_millis(120);
+
+/// Envelope follower turning onset spikes into a smooth swell-and-release pulse
+///
+/// Each onset raises a target that decays over `decay`; the output chases the target over
+/// `attack` while rising and `decay` while falling, so a one-frame spike becomes a pulse
+/// that keeps growing for a few frames before easing out.
+#[derive(Debug, Clone)]
+pub struct PulseEnvelope {
+    attack: Duration,
+    decay: Duration,
+    target: f32,
+    level: f32,
+}
+
+impl PulseEnvelope {
+    pub fn new() -> Self {
+        Self {
+            attack: DEFAULT_PULSE_ATTACK,
+            decay: DEFAULT_PULSE_DECAY,
+            target: 0.0,
+            level: 0.0,
+        }
+    }
+
+    /// Rise time (up to the decay time) and release time (at least `MIN_PULSE_DECAY`)
+    pub fn set_times(&mut self, attack: Duration, decay: Duration) {
+        self.decay = decay.max(MIN_PULSE_DECAY);
+        self.attack = attack.min(self.decay);
+    }
+
+    pub fn attack(&self) -> Duration {
+        self.attack
+    }
+
+    pub fn decay(&self) -> Duration {
+        self.decay
+    }
+
+    /// Feed this frame's onset strength (0.0 to 1.0) after `dt` seconds and return the pulse level
+    pub fn update(&mut self, onset_strength: f32, dt: f32) -> f32 {
+        let input = if onset_strength.is_finite() { onset_strength.clamp(0.0, 1.0) } else { 0.0 };
+        let dt = if dt.is_finite() { dt.max(0.0) } else { 0.0 };
+        let follow = |time: Duration| {
+            let seconds = time.as_secs_f32();
+            if seconds > 0.0 { 1.0 - (-dt / seconds).exp() } else { 1.0 }
+        };
+
+        self.target = input.max(self.target * (1.0 - follow(self.decay)));
+        let rate = if self.target > self.level { follow(self.attack) } else { follow(self.decay) };
+        self.level += (self.target - self.level) * rate;
+        self.level
+    }
+
+    /// Current pulse level (0.0 to 1.0)
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+}
+
+impl Default for PulseEnvelope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `pulse_scale` and `pulse_offset` for a pulse level, gated by the safety pattern-complexity
+/// multiplier (Ultra Safe shrinks the pulse; emergency stop removes it)
+pub fn pulse_transform(level: f32, pattern_complexity: f32) -> (f32, f32) {
+    let gate = if pattern_complexity.is_finite() { pattern_complexity.clamp(0.0, 1.0) } else { 0.0 };
+    let strength = if level.is_finite() { level.clamp(0.0, 1.0) } else { 0.0 } * gate;
+    (1.0 + strength * MAX_PULSE_SCALE, strength * MAX_PULSE_OFFSET)
+}
+
+/// Decaying whole-screen shake driven by bass onsets
+pub struct ScreenShake {
+    clock: SharedClock,
+    energy: f32, // 0.0 to 1.0, before safety scaling
+    last_update: Instant,
+}
+
+impl ScreenShake {
+    pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a shake tracker driven by a custom time source (tests use `MockClock`)
+    pub fn with_clock(clock: SharedClock) -> Self {
+        let last_update = clock.now();
+        Self {
+            clock,
+            energy: 0.0,
+            last_update,
+        }
+    }
+
+    /// Feed this frame's features and return the shake amplitude for the `screen_shake` uniform
+    ///
+    /// The result is scaled by the safety screen-shake, pattern-complexity and brightness
+    /// multipliers, so Ultra Safe and emergency stop always return zero.
+    pub fn update(&mut self, audio: &AudioFeatures, safety: Option<&SafetyMultipliers>) -> f32 {
+        let now = self.clock.now();
+        let dt = now.duration_since(self.last_update).as_secs_f32();
+        self.last_update = now;
+
+        self.energy *= (-dt / SCREEN_SHAKE_DECAY.as_secs_f32()).exp();
+
+        let bass = audio.sub_bass * 0.6 + audio.bass * 0.4;
+        let impulse = (bass * audio.onset_strength).clamp(0.0, 1.0);
+        if impulse > SHAKE_THRESHOLD && impulse > self.energy {
+            self.energy = impulse;
+        }
+
+        let safety_scale = safety.map_or(1.0, |s| {
+            s.screen_shake.min(s.pattern_complexity).min(s.brightness_range).max(0.0)
+        });
+        self.energy * MAX_SCREEN_SHAKE * safety_scale
+    }
+}
+
+impl Default for ScreenShake {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn bass_hit() -> AudioFeatures {
+        let mut audio = AudioFeatures::new();
+        audio.sub_bass = 0.9;
+        audio.bass = 0.8;
+        audio.onset_strength = 0.8;
+        audio
+    }
+
+    #[test]
+    fn test_bass_onset_shake_decays() {
+        let clock = MockClock::new();
+        let mut shake = ScreenShake::with_clock(clock.shared());
+        let standard = SafetyMultipliers::standard();
+
+        let hit = shake.update(&bass_hit(), Some(&standard));
+        assert!(hit > 0.0);
+        assert!(hit <= MAX_SCREEN_SHAKE);
+
+        clock.advance(SCREEN_SHAKE_DECAY);
+        let decayed = shake.update(&AudioFeatures::new(), Some(&standard));
+        assert!(decayed > 0.0 && decayed < hit);
+        assert!((decayed / hit - (-1.0f32).exp()).abs() < 0.01);
+
+        clock.advance(SCREEN_SHAKE_DECAY * 10);
+        assert!(shake.update(&AudioFeatures::new(), Some(&standard)) < hit * 0.001);
+    }
+
+    #[test]
+    fn test_single_onset_pulse_rises_then_decays() {
+        let mut envelope = PulseEnvelope::new();
+        let frame = 1.0 / 60.0;
+
+        let mut levels = vec![envelope.update(1.0, frame)];
+        levels.extend((0..60).map(|_| envelope.update(0.0, frame)));
+
+        let peak = levels.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap().0;
+        assert!(peak > 0, "pulse should keep swelling after the spike: {:?}", &levels[..6]);
+        assert!(levels[..=peak].windows(2).all(|pair| pair[1] > pair[0]));
+        assert!(levels[peak..].windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(levels[peak] <= 1.0);
+        assert!(levels[60] < levels[peak] * 0.2);
+    }
+
+    #[test]
+    fn test_pulse_times_are_clamped() {
+        let mut envelope = PulseEnvelope::new();
+        envelope.set_times(Duration::from_secs(1), Duration::ZERO);
+        assert_eq!(envelope.decay(), MIN_PULSE_DECAY);
+        assert_eq!(envelope.attack(), MIN_PULSE_DECAY);
+
+        // Instant attack still releases no faster than the minimum decay
+        envelope.set_times(Duration::ZERO, Duration::ZERO);
+        assert_eq!(envelope.update(f32::NAN, 0.016), 0.0);
+        assert_eq!(envelope.update(5.0, 0.016), 1.0);
+        assert!(envelope.update(0.0, 0.016) > 0.8);
+    }
+
+    #[test]
+    fn test_pulse_transform_is_clamped_and_gated() {
+        assert_eq!(pulse_transform(0.0, 1.0), (1.0, 0.0));
+        assert_eq!(pulse_transform(3.0, 1.0), (1.0 + MAX_PULSE_SCALE, MAX_PULSE_OFFSET));
+        assert_eq!(pulse_transform(1.0, SafetyMultipliers::emergency_stop().pattern_complexity), (1.0, 0.0));
+
+        let (ultra_safe, _) = pulse_transform(1.0, SafetyMultipliers::ultra_safe().pattern_complexity);
+        let (standard, _) = pulse_transform(1.0, SafetyMultipliers::standard().pattern_complexity);
+        assert!(ultra_safe > 1.0 && ultra_safe < standard);
+    }
+
+    #[test]
+    fn test_shake_zeroed_under_ultra_safe() {
+        let clock = MockClock::new();
+        let mut shake = ScreenShake::with_clock(clock.shared());
+
+        assert_eq!(shake.update(&bass_hit(), Some(&SafetyMultipliers::ultra_safe())), 0.0);
+        assert_eq!(shake.update(&bass_hit(), Some(&SafetyMultipliers::emergency_stop())), 0.0);
+
+        // Sustained bass without a transient never shakes
+        let mut fresh = ScreenShake::with_clock(clock.shared());
+        let mut sustained_bass = bass_hit();
+        sustained_bass.onset_strength = 0.0;
+        assert_eq!(fresh.update(&sustained_bass, None), 0.0);
+    }
+}
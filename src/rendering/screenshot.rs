@@ -0,0 +1,208 @@
+use anyhow::{anyhow, Result};
+use std::path::Path;
+
+use super::FrameEncoder;
+
+const BYTES_PER_PIXEL: u32 = 4;
+
+/// Row pitch for texture-to-buffer copies: wgpu requires 256-byte multiples
+pub fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded = width * BYTES_PER_PIXEL;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    unpadded.div_ceil(align) * align
+}
+
+/// Drop the per-row copy padding and reorder BGRA texels to RGBA when needed
+pub fn unpad_rows(data: &[u8], width: u32, height: u32, padded_bytes_per_row: u32, bgra: bool) -> Vec<u8> {
+    let row_bytes = (width * BYTES_PER_PIXEL) as usize;
+    let mut rgba = Vec::with_capacity(row_bytes * height as usize);
+    for row in data.chunks(padded_bytes_per_row as usize).take(height as usize) {
+        rgba.extend_from_slice(&row[..row_bytes]);
+    }
+
+    if bgra {
+        for texel in rgba.chunks_exact_mut(BYTES_PER_PIXEL as usize) {
+            texel.swap(0, 2);
+        }
+    }
+    rgba
+}
+
+/// Whether texels of `format` are stored blue-first; `None` for formats screenshots can't encode
+fn bgra_order(format: wgpu::TextureFormat) -> Option<bool> {
+    match format {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => Some(false),
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => Some(true),
+        _ => None,
+    }
+}
+
+/// Check a surface can be read back into a PNG
+pub fn check_screenshot_support(config: &wgpu::SurfaceConfiguration) -> Result<()> {
+    if !config.usage.contains(wgpu::TextureUsages::COPY_SRC) {
+        return Err(anyhow!("The display surface does not allow reading frames back"));
+    }
+    if bgra_order(config.format).is_none() {
+        return Err(anyhow!("Screenshots are not supported for surface format {:?}", config.format));
+    }
+    Ok(())
+}
+
+/// Copy of a rendered frame on its way to mappable memory
+pub struct ScreenshotReadback {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: u32,
+    bgra: bool,
+}
+
+impl ScreenshotReadback {
+    /// Record a copy of `texture` into `frame`; read it once the frame is submitted
+    pub fn record(device: &wgpu::Device, frame: &mut FrameEncoder, texture: &wgpu::Texture) -> Result<Self> {
+        let bgra = bgra_order(texture.format())
+            .ok_or_else(|| anyhow!("Screenshots are not supported for texture format {:?}", texture.format()))?;
+        let width = texture.width();
+        let height = texture.height();
+        let padded_bytes_per_row = padded_bytes_per_row(width);
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("screenshot_readback"),
+            size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        frame.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+
+        Ok(Self { buffer, width, height, padded_bytes_per_row, bgra })
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Wait for the copy and return tightly packed RGBA8 rows (blocks until the frame's GPU work finishes)
+    pub fn read_rgba(&self, device: &wgpu::Device) -> Result<Vec<u8>> {
+        let slice = self.buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|_| anyhow!("Screenshot readback was dropped"))?
+            .map_err(|e| anyhow!("Failed to map screenshot buffer: {}", e))?;
+
+        let rgba = {
+            let data = slice.get_mapped_range();
+            unpad_rows(&data, self.width, self.height, self.padded_bytes_per_row, self.bgra)
+        };
+        self.buffer.unmap();
+        Ok(rgba)
+    }
+
+    /// Read the frame back and write it as a PNG
+    pub fn save_png(&self, device: &wgpu::Device, path: &Path) -> Result<()> {
+        let rgba = self.read_rgba(device)?;
+        image::save_buffer(path, &rgba, self.width, self.height, image::ExtendedColorType::Rgba8)
+            .map_err(|e| anyhow!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_padding_is_stripped() {
+        // 3 pixels wide = 12 bytes per row, padded to 256
+        let padded = padded_bytes_per_row(3);
+        assert_eq!(padded, 256);
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+
+        let mut data = vec![0xEE; (padded * 2) as usize];
+        for row in 0..2 {
+            for byte in 0..12 {
+                data[row * padded as usize + byte] = (row * 12 + byte) as u8;
+            }
+        }
+
+        let rgba = unpad_rows(&data, 3, 2, padded, false);
+        assert_eq!(rgba, (0..24).collect::<Vec<u8>>());
+
+        // BGRA texels come out as RGBA
+        let swapped = unpad_rows(&data, 3, 2, padded, true);
+        assert_eq!(&swapped[..4], &[2, 1, 0, 3]);
+    }
+
+    fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None).await.ok()
+        })
+    }
+
+    #[test]
+    fn test_offscreen_frame_saved_as_png() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping screenshot test: no GPU adapter available");
+            return;
+        };
+
+        // An odd width forces row padding
+        let (width, height) = (70, 30);
+        let target = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("screenshot_test_target"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Bgra8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut frame = FrameEncoder::new(&device);
+        {
+            let _pass = frame.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("screenshot_test_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::RED), store: wgpu::StoreOp::Store },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+        }
+        let readback = ScreenshotReadback::record(&device, &mut frame, &target).unwrap();
+        frame.submit(&queue);
+
+        let rgba = readback.read_rgba(&device).unwrap();
+        assert_eq!(rgba.len(), (width * height * BYTES_PER_PIXEL) as usize);
+        assert_eq!(&rgba[..4], &[255, 0, 0, 255]);
+
+        let path = std::env::temp_dir().join("aruu_test_screenshot.png");
+        readback.save_png(&device, &path).unwrap();
+        assert_eq!(image::image_dimensions(&path).unwrap(), (width, height));
+
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,310 @@
+use std::time::{Duration, Instant};
+
+use crate::audio::{AudioFeatures, RhythmFeatures};
+use super::ShaderType;
+
+/// Minimum time between automatic shader switches
+pub const DEFAULT_AUTO_SHADER_COOLDOWN: Duration = Duration::from_millis(2500);
+
+/// Longest a downbeat-quantized transition waits before switching anyway
+pub const DEFAULT_DOWNBEAT_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Holds a requested shader change until the next downbeat, so auto transitions land on phrase starts
+///
+/// Music without a detectable downbeat falls back to switching once `timeout` has passed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownbeatQuantizer {
+    pending: Option<(ShaderType, Instant)>, // Target shader and when it was first requested
+    timeout: Duration,
+}
+
+impl DownbeatQuantizer {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            pending: None,
+            timeout,
+        }
+    }
+
+    /// Queue `shader` for the next downbeat; a new target keeps the original request time
+    pub fn request(&mut self, shader: ShaderType, now: Instant) {
+        let requested_at = self.pending.map_or(now, |(_, requested_at)| requested_at);
+        self.pending = Some((shader, requested_at));
+    }
+
+    /// Shader waiting for a downbeat, if any
+    pub fn pending(&self) -> Option<ShaderType> {
+        self.pending.map(|(shader, _)| shader)
+    }
+
+    pub fn cancel(&mut self) {
+        self.pending = None;
+    }
+
+    /// The queued shader once a downbeat arrives or the timeout runs out; clears the queue
+    pub fn update(&mut self, now: Instant, downbeat_detected: bool) -> Option<ShaderType> {
+        let (shader, requested_at) = self.pending?;
+        if downbeat_detected || now.saturating_duration_since(requested_at) >= self.timeout {
+            self.pending = None;
+            Some(shader)
+        } else {
+            None
+        }
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl Default for DownbeatQuantizer {
+    fn default() -> Self {
+        Self::new(DEFAULT_DOWNBEAT_TIMEOUT)
+    }
+}
+
+/// Thresholds auto shader selection compares audio features against, checked in order:
+/// bass (Tunnel/Classic), treble with onsets (Particle), pitch with stable rhythm
+/// (Kaleidoscope), spectral flux (ParametricWave), dynamic range (Fractal)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShaderSelectionConfig {
+    pub bass_threshold: f32,             // bass + sub_bass above this picks Tunnel or Classic
+    pub tunnel_tempo_confidence: f32,    // Tempo confidence that turns bass into Tunnel
+    pub treble_threshold: f32,           // treble + presence needed for Particle
+    pub onset_threshold: f32,            // Onset strength needed alongside treble for Particle
+    pub pitch_confidence_threshold: f32, // Pitch confidence needed for Kaleidoscope
+    pub rhythm_stability_threshold: f32, // Rhythm stability needed alongside pitch for Kaleidoscope
+    pub spectral_flux_threshold: f32,    // Spectral flux above this picks ParametricWave
+    pub dynamic_range_threshold: f32,    // Dynamic range above this picks Fractal
+}
+
+impl ShaderSelectionConfig {
+    pub fn new() -> Self {
+        Self {
+            bass_threshold: 0.7,
+            tunnel_tempo_confidence: 0.8,
+            treble_threshold: 0.6,
+            onset_threshold: 0.5,
+            pitch_confidence_threshold: 0.7,
+            rhythm_stability_threshold: 0.6,
+            spectral_flux_threshold: 0.4,
+            dynamic_range_threshold: 0.6,
+        }
+    }
+
+    pub fn bass_threshold(mut self, threshold: f32) -> Self {
+        self.bass_threshold = threshold;
+        self
+    }
+
+    pub fn tunnel_tempo_confidence(mut self, confidence: f32) -> Self {
+        self.tunnel_tempo_confidence = confidence;
+        self
+    }
+
+    pub fn treble_threshold(mut self, threshold: f32) -> Self {
+        self.treble_threshold = threshold;
+        self
+    }
+
+    pub fn onset_threshold(mut self, threshold: f32) -> Self {
+        self.onset_threshold = threshold;
+        self
+    }
+
+    pub fn pitch_confidence_threshold(mut self, threshold: f32) -> Self {
+        self.pitch_confidence_threshold = threshold;
+        self
+    }
+
+    pub fn rhythm_stability_threshold(mut self, threshold: f32) -> Self {
+        self.rhythm_stability_threshold = threshold;
+        self
+    }
+
+    pub fn spectral_flux_threshold(mut self, threshold: f32) -> Self {
+        self.spectral_flux_threshold = threshold;
+        self
+    }
+
+    pub fn dynamic_range_threshold(mut self, threshold: f32) -> Self {
+        self.dynamic_range_threshold = threshold;
+        self
+    }
+
+    /// Recommend a shader for the current audio characteristics
+    pub fn analyze_audio_for_shader(&self, audio: &AudioFeatures, rhythm: &RhythmFeatures) -> ShaderType {
+        // High bass content -> Classic or Tunnel
+        if audio.bass + audio.sub_bass > self.bass_threshold {
+            return if rhythm.tempo_confidence > self.tunnel_tempo_confidence {
+                ShaderType::Tunnel // Strong rhythm + bass = tunnel effect
+            } else {
+                ShaderType::Classic // Just bass = classic waves
+            };
+        }
+
+        // High treble + onset activity -> Particle system
+        if audio.treble + audio.presence > self.treble_threshold && audio.onset_strength > self.onset_threshold {
+            return ShaderType::Particle;
+        }
+
+        // High pitch confidence + harmony -> Kaleidoscope
+        if audio.pitch_confidence > self.pitch_confidence_threshold && rhythm.rhythm_stability > self.rhythm_stability_threshold {
+            return ShaderType::Kaleidoscope;
+        }
+
+        // High spectral flux (dynamic changes) -> Parametric wave
+        if audio.spectral_flux > self.spectral_flux_threshold {
+            return ShaderType::ParametricWave;
+        }
+
+        // High dynamic range -> Fractal
+        if audio.dynamic_range > self.dynamic_range_threshold {
+            return ShaderType::Fractal;
+        }
+
+        // Default fallback
+        ShaderType::Classic
+    }
+}
+
+impl Default for ShaderSelectionConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_analysis_for_shader() {
+        let config = ShaderSelectionConfig::default();
+
+        // Test bass-heavy music
+        let bass_audio = AudioFeatures {
+            bass: 0.8,
+            sub_bass: 0.6,
+            ..AudioFeatures::new()
+        };
+        let high_tempo_rhythm = RhythmFeatures {
+            tempo_confidence: 0.9,
+            ..RhythmFeatures::new()
+        };
+        assert_eq!(config.analyze_audio_for_shader(&bass_audio, &high_tempo_rhythm), ShaderType::Tunnel);
+
+        let low_tempo_rhythm = RhythmFeatures {
+            tempo_confidence: 0.5,
+            ..RhythmFeatures::new()
+        };
+        assert_eq!(config.analyze_audio_for_shader(&bass_audio, &low_tempo_rhythm), ShaderType::Classic);
+
+        // Test treble-heavy with onsets
+        let treble_audio = AudioFeatures {
+            treble: 0.7,
+            presence: 0.5,
+            onset_strength: 0.6,
+            ..AudioFeatures::new()
+        };
+        assert_eq!(config.analyze_audio_for_shader(&treble_audio, &high_tempo_rhythm), ShaderType::Particle);
+
+        // Test harmonic content
+        let harmonic_audio = AudioFeatures {
+            pitch_confidence: 0.8,
+            ..AudioFeatures::new()
+        };
+        let stable_rhythm = RhythmFeatures {
+            rhythm_stability: 0.7,
+            ..RhythmFeatures::new()
+        };
+        assert_eq!(config.analyze_audio_for_shader(&harmonic_audio, &stable_rhythm), ShaderType::Kaleidoscope);
+
+        // Test high spectral flux
+        let dynamic_audio = AudioFeatures {
+            spectral_flux: 0.5,
+            ..AudioFeatures::new()
+        };
+        assert_eq!(config.analyze_audio_for_shader(&dynamic_audio, &high_tempo_rhythm), ShaderType::ParametricWave);
+
+        // Test high dynamic range
+        let range_audio = AudioFeatures {
+            dynamic_range: 0.7,
+            ..AudioFeatures::new()
+        };
+        assert_eq!(config.analyze_audio_for_shader(&range_audio, &high_tempo_rhythm), ShaderType::Fractal);
+
+        // Test default case
+        let default_audio = AudioFeatures::new();
+        let default_rhythm = RhythmFeatures::new();
+        assert_eq!(config.analyze_audio_for_shader(&default_audio, &default_rhythm), ShaderType::Classic);
+    }
+
+    #[test]
+    fn test_lower_bass_threshold_reacts_to_quieter_bass() {
+        let quiet_bass = AudioFeatures {
+            bass: 0.3,
+            sub_bass: 0.2,
+            ..AudioFeatures::new()
+        };
+        let steady = RhythmFeatures {
+            tempo_confidence: 0.9,
+            ..RhythmFeatures::new()
+        };
+        let loose = RhythmFeatures {
+            tempo_confidence: 0.4,
+            ..RhythmFeatures::new()
+        };
+
+        // The default bass threshold ignores it, so flux decides
+        let default_config = ShaderSelectionConfig::default();
+        let flux_bass = AudioFeatures { spectral_flux: 0.5, ..quiet_bass.clone() };
+        assert_eq!(default_config.analyze_audio_for_shader(&flux_bass, &steady), ShaderType::ParametricWave);
+
+        // An ambient-friendly bass threshold catches it first
+        let ambient = ShaderSelectionConfig::new().bass_threshold(0.4);
+        assert_eq!(ambient.analyze_audio_for_shader(&flux_bass, &steady), ShaderType::Tunnel);
+        assert_eq!(ambient.analyze_audio_for_shader(&flux_bass, &loose), ShaderType::Classic);
+    }
+
+    #[test]
+    fn test_queued_transition_waits_for_downbeat() {
+        let start = Instant::now();
+        let mut quantizer = DownbeatQuantizer::default();
+        quantizer.request(ShaderType::Tunnel, start);
+        assert_eq!(quantizer.pending(), Some(ShaderType::Tunnel));
+
+        // Frames without a downbeat keep it queued
+        for frame in 1..=30 {
+            let now = start + Duration::from_millis(frame * 16);
+            assert_eq!(quantizer.update(now, false), None);
+        }
+        assert_eq!(quantizer.pending(), Some(ShaderType::Tunnel));
+
+        // The downbeat releases it exactly once
+        let downbeat = start + Duration::from_millis(500);
+        assert_eq!(quantizer.update(downbeat, true), Some(ShaderType::Tunnel));
+        assert_eq!(quantizer.pending(), None);
+        assert_eq!(quantizer.update(downbeat, true), None);
+    }
+
+    #[test]
+    fn test_queued_transition_times_out_without_downbeat() {
+        let start = Instant::now();
+        let mut quantizer = DownbeatQuantizer::new(Duration::from_secs(2));
+        quantizer.request(ShaderType::Fractal, start);
+
+        // Retargeting doesn't restart the wait
+        quantizer.request(ShaderType::Particle, start + Duration::from_secs(1));
+        assert_eq!(quantizer.update(start + Duration::from_millis(1900), false), None);
+        assert_eq!(quantizer.update(start + Duration::from_secs(2), false), Some(ShaderType::Particle));
+
+        quantizer.request(ShaderType::Classic, start);
+        quantizer.cancel();
+        assert_eq!(quantizer.update(start + Duration::from_secs(10), true), None);
+    }
+}
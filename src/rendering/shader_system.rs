@@ -2,9 +2,13 @@ use wgpu::util::DeviceExt;
 use bytemuck::{Pod, Zeroable};
 use std::collections::HashMap;
 use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
 
 use crate::audio::{AudioFeatures, RhythmFeatures};
-use super::QualityLevel;
+use crate::clock::{system_clock, SharedClock};
+use crate::control::{kelvin_to_color_temperature, color_temperature_to_kelvin, white_balance_multiplier, ColorPalette, PaletteManager, Vector3, MAX_CUSTOM_PALETTE_STOPS, NEUTRAL_WHITE_BALANCE_KELVIN, WHITE_BALANCE_RANGE_KELVIN};
+use super::{pulse_transform, FrameEncoder, GpuTimer, PerformanceUniforms, PulseEnvelope, SpectrogramHistory, SpectrogramTexture, fold_bins, render_format, DEFAULT_SPECTROGRAM_COLUMNS, SPECTROGRAM_ROWS};
+use super::enhanced_composer::{create_quad_buffers, QuadBuffers};
 
 /// Unified uniform data structure that can support all shader types
 #[repr(C)]
@@ -50,6 +54,7 @@ pub struct UniversalUniforms {
     pub prev_palette_index: f32,
     pub prev_palette_base_hue: f32,
     pub prev_palette_hue_range: f32,
+    pub custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
 
     // Effect weights for multi-mode shaders
     pub plasma_weight: f32,
@@ -59,6 +64,12 @@ pub struct UniversalUniforms {
     pub fractal_weight: f32,
     pub spectralizer_weight: f32,
 
+    // Shader-specific parameters
+    pub kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    pub classic_wave_count: f32,    // Radial waves across the Classic shader (6 to 24)
+    pub classic_radial_speed: f32,  // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    pub spectrogram_head: f32,      // Ring column holding the newest spectrogram frame
+
     // System parameters
     pub projection_mode: f32,      // 0.0 = 2D, 1.0 = 3D perspective
     pub smoothing_factor: f32,     // Global smoothing control
@@ -84,12 +95,59 @@ pub struct UniversalUniforms {
     pub ui_safety_level: f32,             // Current safety level (0.0 to 4.0)
     pub ui_quality_level: f32,            // Current quality level (0.0 to 4.0)
     pub ui_auto_shader: f32,              // 1.0 = auto enabled, 0.0 = manual
-    pub ui_current_shader_index: f32,     // Index of current shader (0.0 to 7.0)
+    pub ui_current_shader_index: f32,     // Index of current shader (0.0 to 10.0)
     pub ui_fps: f32,                      // Current FPS for display
     pub ui_frame_time: f32,               // Current frame time in ms
+    pub ui_quality_reason: f32,           // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    pub ui_quality_change_age: f32,       // Seconds since last quality change
+    pub ui_software_renderer: f32,        // 1.0 when running on a CPU adapter
+    pub ui_playback_position: f32,        // Playback position as a fraction of the track (-1.0 = no seekable track)
+    pub ui_meter_level: f32,              // Level meter with attack/release ballistics (0.0 to 1.0)
+    pub ui_meter_peak: f32,               // Peak-hold level for the meter (0.0 to 1.0)
     pub screen_width: f32,                // Screen width in pixels
     pub screen_height: f32,               // Screen height in pixels
     pub text_scale: f32,                  // Text scaling factor
+
+    // Anti-aliasing
+    pub aa_width: f32,                    // One pixel in centered UV units, for analytic edge smoothing
+
+    // Screen transform
+    pub screen_shake: f32,                // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    pub white_balance_r: f32,             // White-balance multiplier (1.0 = neutral)
+    pub white_balance_g: f32,
+    pub white_balance_b: f32,
+
+    // Frequency axis
+    pub spectralizer_log_scale: f32,      // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pub pulse_scale: f32,                 // Whole-image zoom about the center (1.0 = none)
+    pub pulse_offset: f32,                // Upward kick of the image in UV units (0.0 = none)
+
+    // Source separation (both 0.0 unless HPSS is enabled)
+    pub harmonic_energy: f32,             // Sustained, tonal content; suited to color
+    pub percussive_energy: f32,           // Transient, broadband content; suited to motion
+
+    // Fractal shader
+    pub fractal_mode: f32,                // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    pub fractal_c_real: f32,              // Julia set constant, inside the main cardioid
+    pub fractal_c_imag: f32,
+    pub fractal_max_iterations: f32,      // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    pub projection_scale_x: f32,          // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    pub projection_scale_y: f32,          // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    pub spectralizer_stereo_split: f32,   // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    pub color_temperature: f32,           // White balance on a -1.0 (cool) to 1.0 (warm) scale, 0.0 = neutral
+
+    // Beat grid
+    pub beat_position: f32,               // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
 }
 
 impl Default for UniversalUniforms {
@@ -135,6 +193,7 @@ impl Default for UniversalUniforms {
             prev_palette_index: 0.0,
             prev_palette_base_hue: 0.0,
             prev_palette_hue_range: 1.0,
+            custom_palette_stops: 0.0,
 
             // Effect weights (all equal by default)
             plasma_weight: 0.2,
@@ -144,6 +203,12 @@ impl Default for UniversalUniforms {
             fractal_weight: 0.1,
             spectralizer_weight: 0.1,
 
+            // Shader-specific parameters
+            kaleidoscope_segments: 6.0,
+            classic_wave_count: 12.0,
+            classic_radial_speed: 1.0,
+            spectrogram_head: 0.0,
+
             // System parameters
             projection_mode: 0.0,
             smoothing_factor: 0.5,
@@ -172,15 +237,60 @@ impl Default for UniversalUniforms {
             ui_current_shader_index: 0.0,     // Classic shader by default
             ui_fps: 60.0,                     // Target FPS
             ui_frame_time: 16.67,             // Target frame time
+            ui_quality_reason: 0.0,           // No quality change yet
+            ui_quality_change_age: 0.0,
+            ui_software_renderer: 0.0,
+            ui_playback_position: -1.0,       // No track loaded
+            ui_meter_level: 0.0,
+            ui_meter_peak: 0.0,
             screen_width: 1200.0,             // Default screen width
             screen_height: 800.0,             // Default screen height
             text_scale: 1.0,                  // Normal text scale
+
+            // Anti-aliasing
+            aa_width: 2.0 / 800.0,            // One pixel at default resolution_y
+
+            // Screen transform
+            screen_shake: 0.0,
+
+            // Output color
+            white_balance_r: 1.0,
+            white_balance_g: 1.0,
+            white_balance_b: 1.0,
+
+            // Frequency axis (logarithmic matches how pitch is heard)
+            spectralizer_log_scale: 1.0,
+
+            // Onset pulse
+            pulse_scale: 1.0,
+            pulse_offset: 0.0,
+            harmonic_energy: 0.0,
+            percussive_energy: 0.0,
+
+            // Fractal shader (c at zero centroid and confidence; 64 iterations is Medium quality)
+            fractal_mode: 0.0,
+            fractal_c_real: 0.24,
+            fractal_c_imag: 0.0,
+            fractal_max_iterations: 64.0,
+
+            // Perspective for PERSPECTIVE_FOV_Y_DEGREES at the default 1200x800
+            projection_scale_x: 0.8660254,
+            projection_scale_y: 0.57735026,
+
+            // Stereo
+            spectralizer_stereo_split: 0.0,
+
+            // Output color
+            color_temperature: 0.0,
+
+            // Beat grid
+            beat_position: 0.0,
         }
     }
 }
 
 /// Represents different shader types/modes
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ShaderType {
     Classic,
     ParametricWave,
@@ -190,6 +300,9 @@ pub enum ShaderType {
     Particle,
     Fractal,
     Spectralizer,
+    Oscilloscope,
+    Spectrogram,
+    Mixed,
 }
 
 impl ShaderType {
@@ -203,6 +316,9 @@ impl ShaderType {
             ShaderType::Particle => "Particle",
             ShaderType::Fractal => "Fractal",
             ShaderType::Spectralizer => "Spectralizer",
+            ShaderType::Oscilloscope => "Oscilloscope",
+            ShaderType::Spectrogram => "Spectrogram",
+            ShaderType::Mixed => "Mixed",
         }
     }
 
@@ -216,6 +332,9 @@ impl ShaderType {
             ShaderType::Particle => "Dynamic particle systems responding to transients",
             ShaderType::Fractal => "Self-similar patterns scaled by spectral characteristics",
             ShaderType::Spectralizer => "Direct frequency visualization with artistic flair",
+            ShaderType::Oscilloscope => "Raw waveform trace of the most recent samples",
+            ShaderType::Spectrogram => "Scrolling frequency history with magnitude as color",
+            ShaderType::Mixed => "Six effects composited by their effect weights",
         }
     }
 
@@ -229,6 +348,9 @@ impl ShaderType {
             ShaderType::Particle,
             ShaderType::Fractal,
             ShaderType::Spectralizer,
+            ShaderType::Oscilloscope,
+            ShaderType::Spectrogram,
+            ShaderType::Mixed,
         ]
     }
 }
@@ -241,6 +363,9 @@ pub struct ShaderMetadata {
     pub fragment_source: &'static str,
     pub requires_3d: bool,
     pub performance_cost: u8, // 1-10 scale
+    pub recommended_palette: ColorPalette,     // Applied when the shader becomes active, unless the palette is locked
+    pub default_saturation: Option<f32>,       // None = global saturation (1.0)
+    pub recommended_weights: EffectWeights,    // Applied with the palette, unless the weights were set by hand
 }
 
 /// Registry of available shaders
@@ -269,6 +394,9 @@ impl ShaderRegistry {
             fragment_source: include_str!("shaders/classic.frag.wgsl"),
             requires_3d: false,
             performance_cost: 3,
+            recommended_palette: ColorPalette::Rainbow,
+            default_saturation: None,
+            recommended_weights: EffectWeights::default(),
         });
 
         // Parametric wave shader
@@ -278,6 +406,9 @@ impl ShaderRegistry {
             fragment_source: include_str!("shaders/parametric_wave.frag.wgsl"),
             requires_3d: false,
             performance_cost: 6,
+            recommended_palette: ColorPalette::Green,
+            default_saturation: None,
+            recommended_weights: EffectWeights::default(),
         });
 
         // Plasma shader - fluid organic patterns
@@ -287,6 +418,9 @@ impl ShaderRegistry {
             fragment_source: include_str!("shaders/plasma.frag.wgsl"),
             requires_3d: false,
             performance_cost: 7,
+            recommended_palette: ColorPalette::Orange,
+            default_saturation: None,
+            recommended_weights: EffectWeights::default(),
         });
 
         // Kaleidoscope shader - symmetric patterns
@@ -296,6 +430,9 @@ impl ShaderRegistry {
             fragment_source: include_str!("shaders/kaleidoscope.frag.wgsl"),
             requires_3d: false,
             performance_cost: 5,
+            recommended_palette: ColorPalette::Indigo,
+            default_saturation: None,
+            recommended_weights: EffectWeights::default(),
         });
 
         // Tunnel shader - 3D perspective effects
@@ -305,6 +442,9 @@ impl ShaderRegistry {
             fragment_source: include_str!("shaders/tunnel.frag.wgsl"),
             requires_3d: true,
             performance_cost: 6,
+            recommended_palette: ColorPalette::Blue,
+            default_saturation: None,
+            recommended_weights: EffectWeights::default(),
         });
 
         // Particle shader - dynamic particle systems
@@ -314,6 +454,9 @@ impl ShaderRegistry {
             fragment_source: include_str!("shaders/particle.frag.wgsl"),
             requires_3d: false,
             performance_cost: 8,
+            recommended_palette: ColorPalette::Yellow,
+            default_saturation: None,
+            recommended_weights: EffectWeights::default(),
         });
 
         // Fractal shader - mathematical fractal patterns
@@ -323,6 +466,9 @@ impl ShaderRegistry {
             fragment_source: include_str!("shaders/fractal.frag.wgsl"),
             requires_3d: false,
             performance_cost: 9,
+            recommended_palette: ColorPalette::Violet,
+            default_saturation: None,
+            recommended_weights: EffectWeights::default(),
         });
 
         // Spectralizer shader - direct frequency visualization
@@ -332,6 +478,45 @@ impl ShaderRegistry {
             fragment_source: include_str!("shaders/spectralizer.frag.wgsl"),
             requires_3d: false,
             performance_cost: 7,
+            recommended_palette: ColorPalette::Rainbow,
+            default_saturation: Some(1.0),
+            recommended_weights: EffectWeights::default(),
+        });
+
+        // Oscilloscope shader - raw time-domain waveform trace
+        self.register(ShaderMetadata {
+            shader_type: ShaderType::Oscilloscope,
+            vertex_source,
+            fragment_source: include_str!("shaders/oscilloscope.frag.wgsl"),
+            requires_3d: false,
+            performance_cost: 2,
+            recommended_palette: ColorPalette::Green,
+            default_saturation: None,
+            recommended_weights: EffectWeights::default(),
+        });
+
+        // Spectrogram shader - scrolling frequency history
+        self.register(ShaderMetadata {
+            shader_type: ShaderType::Spectrogram,
+            vertex_source,
+            fragment_source: include_str!("shaders/spectrogram.frag.wgsl"),
+            requires_3d: false,
+            performance_cost: 2,
+            recommended_palette: ColorPalette::Rainbow,
+            default_saturation: None,
+            recommended_weights: EffectWeights::default(),
+        });
+
+        // Mixed shader - effect layers blended by the effect weights
+        self.register(ShaderMetadata {
+            shader_type: ShaderType::Mixed,
+            vertex_source,
+            fragment_source: include_str!("shaders/mixed.frag.wgsl"),
+            requires_3d: false,
+            performance_cost: 8,
+            recommended_palette: ColorPalette::Rainbow,
+            default_saturation: None,
+            recommended_weights: EffectWeights::MIXED_RECOMMENDED,
         });
     }
 
@@ -350,25 +535,100 @@ impl ShaderRegistry {
     pub fn is_available(&self, shader_type: ShaderType) -> bool {
         self.shaders.contains_key(&shader_type)
     }
+
+    /// Compile every registered shader's modules without building pipelines, in
+    /// `ShaderType::all` order. Compile errors are captured in an error scope and returned
+    /// per shader instead of aborting on first use.
+    pub fn validate_all(&self, device: &wgpu::Device) -> Vec<(ShaderType, Result<()>)> {
+        ShaderType::all()
+            .iter()
+            .filter_map(|shader_type| self.get(*shader_type))
+            .map(|metadata| (metadata.shader_type, Self::validate(device, metadata)))
+            .collect()
+    }
+
+    fn validate(device: &wgpu::Device, metadata: &ShaderMetadata) -> Result<()> {
+        for (stage, source) in [("vertex", metadata.vertex_source), ("fragment", metadata.fragment_source)] {
+            device.push_error_scope(wgpu::ErrorFilter::Validation);
+            device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(&format!("{}_{}_validation", metadata.shader_type.name(), stage)),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+            if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+                return Err(anyhow!("{} {} shader failed to compile: {}", metadata.shader_type.name(), stage, error));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Shape applied to linear transition progress before it drives the crossfade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EasingCurve {
+    #[default]
+    Linear,
+    EaseInOut,
+    EaseIn,
+    EaseOut,
+    Smoothstep,
+}
+
+impl EasingCurve {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EasingCurve::Linear => "Linear",
+            EasingCurve::EaseInOut => "Ease In/Out",
+            EasingCurve::EaseIn => "Ease In",
+            EasingCurve::EaseOut => "Ease Out",
+            EasingCurve::Smoothstep => "Smoothstep",
+        }
+    }
+
+    /// Ease a 0-1 progress value; every curve maps 0 to 0 and 1 to exactly 1
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            EasingCurve::Linear => t,
+            EasingCurve::EaseIn => t * t,
+            EasingCurve::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            EasingCurve::EaseInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - 2.0 * (1.0 - t) * (1.0 - t)
+                }
+            }
+            EasingCurve::Smoothstep => t * t * (3.0 - 2.0 * t),
+        }
+    }
 }
 
 /// Manages shader transitions and blending
 pub struct ShaderTransitioner {
     current_shader: ShaderType,
     target_shader: Option<ShaderType>,
-    transition_progress: f32,
+    transition_progress: f32, // Linear in time; easing is applied when read
+    easing: EasingCurve,
     transition_duration: f32,
     last_update: std::time::Instant,
+    clock: SharedClock,
 }
 
 impl ShaderTransitioner {
     pub fn new(initial_shader: ShaderType) -> Self {
+        Self::with_clock(initial_shader, system_clock())
+    }
+
+    /// Create a transitioner driven by a custom time source (e.g. `MockClock` in tests)
+    pub fn with_clock(initial_shader: ShaderType, clock: SharedClock) -> Self {
         Self {
             current_shader: initial_shader,
             target_shader: None,
             transition_progress: 1.0, // Fully transitioned to current
             transition_duration: 2.0, // 2 second transitions
-            last_update: std::time::Instant::now(),
+            easing: EasingCurve::Linear,
+            last_update: clock.now(),
+            clock,
         }
     }
 
@@ -376,7 +636,7 @@ impl ShaderTransitioner {
         if target != self.current_shader {
             self.target_shader = Some(target);
             self.transition_progress = 0.0;
-            self.last_update = std::time::Instant::now();
+            self.last_update = self.clock.now();
         }
     }
 
@@ -386,13 +646,13 @@ impl ShaderTransitioner {
             self.current_shader = target;
             self.target_shader = None;
             self.transition_progress = 1.0;
-            self.last_update = std::time::Instant::now();
+            self.last_update = self.clock.now();
         }
     }
 
     pub fn update(&mut self) {
         if let Some(_target) = self.target_shader {
-            let now = std::time::Instant::now();
+            let now = self.clock.now();
             let elapsed = now.duration_since(self.last_update).as_secs_f32();
 
             self.transition_progress += elapsed / self.transition_duration;
@@ -416,30 +676,450 @@ impl ShaderTransitioner {
         self.target_shader.is_some()
     }
 
+    pub fn set_easing(&mut self, easing: EasingCurve) {
+        self.easing = easing;
+    }
+
+    pub fn easing(&self) -> EasingCurve {
+        self.easing
+    }
+
+    /// Eased crossfade amount (0.0 = previous shader, 1.0 = current)
     pub fn transition_progress(&self) -> f32 {
+        self.easing.apply(self.transition_progress)
+    }
+
+    /// Linear progress through the transition's duration, before easing
+    pub fn raw_progress(&self) -> f32 {
         self.transition_progress
     }
 }
 
+/// Fewest mirror segments the kaleidoscope folds into
+pub const MIN_KALEIDOSCOPE_SEGMENTS: u32 = 3;
+
+/// Most mirror segments before the fold turns to noise
+pub const MAX_KALEIDOSCOPE_SEGMENTS: u32 = 16;
+
+/// Map harmonic and tempo confidence to a whole kaleidoscope segment count
+///
+/// Whole numbers keep the mirror seams aligned; tonal, steady music gets more segments.
+pub fn kaleidoscope_segments(pitch_confidence: f32, tempo_confidence: f32) -> u32 {
+    let pitch = if pitch_confidence.is_finite() { pitch_confidence.clamp(0.0, 1.0) } else { 0.0 };
+    let tempo = if tempo_confidence.is_finite() { tempo_confidence.clamp(0.0, 1.0) } else { 0.0 };
+    let segments = 6.0 + pitch * 6.0 + tempo * 2.0;
+    (segments.round() as u32).clamp(MIN_KALEIDOSCOPE_SEGMENTS, MAX_KALEIDOSCOPE_SEGMENTS)
+}
+
+/// Range of radial wave counts for the Classic shader (min, max)
+pub const CLASSIC_WAVE_COUNT_RANGE: (f32, f32) = (6.0, 24.0);
+
+/// Range of the Classic shader's radial speed multiplier (min, max)
+pub const CLASSIC_RADIAL_SPEED_RANGE: (f32, f32) = (0.25, 3.0);
+
+/// Map mid and treble energy to the Classic shader's radial wave count
+///
+/// Brighter music packs more rings in; the shader fades rings out before they alias.
+pub fn classic_wave_count(mid: f32, treble: f32) -> f32 {
+    let mid = if mid.is_finite() { mid.clamp(0.0, 1.0) } else { 0.0 };
+    let treble = if treble.is_finite() { treble.clamp(0.0, 1.0) } else { 0.0 };
+    (12.0 + mid * 4.0 + treble * 8.0).clamp(CLASSIC_WAVE_COUNT_RANGE.0, CLASSIC_WAVE_COUNT_RANGE.1)
+}
+
+/// Map tempo to the Classic shader's radial speed, trusting the BPM only as far as its confidence
+pub fn classic_radial_speed(bpm: f32, tempo_confidence: f32) -> f32 {
+    let confidence = if tempo_confidence.is_finite() { tempo_confidence.clamp(0.0, 1.0) } else { 0.0 };
+    let tempo_speed = if bpm.is_finite() && bpm > 0.0 { bpm / 120.0 } else { 1.0 };
+    (1.0 + (tempo_speed - 1.0) * confidence).clamp(CLASSIC_RADIAL_SPEED_RANGE.0, CLASSIC_RADIAL_SPEED_RANGE.1)
+}
+
+/// How the Fractal shader iterates, uploaded as the `fractal_mode` uniform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FractalMode {
+    /// Mandelbrot blended with Julia and Burning Ship by band energy
+    #[default]
+    Mandelbrot,
+    /// A single Julia set whose `c` follows the spectral centroid and pitch confidence
+    Julia,
+}
+
+impl FractalMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FractalMode::Mandelbrot => "Mandelbrot",
+            FractalMode::Julia => "Julia",
+        }
+    }
+
+    pub fn as_uniform(&self) -> f32 {
+        match self {
+            FractalMode::Mandelbrot => 0.0,
+            FractalMode::Julia => 1.0,
+        }
+    }
+}
+
+/// Spectral centroids (Hz) swept once around the Julia path (low, high)
+pub const JULIA_CENTROID_RANGE_HZ: (f32, f32) = (100.0, 8000.0);
+
+/// Map the spectral centroid and pitch confidence to a Julia set constant `c`
+///
+/// `c = mu/2 - mu^2/4` maps the unit disk onto the Mandelbrot set's main cardioid, so
+/// every `c` gives a connected Julia set. The centroid (log-scaled) picks the angle of
+/// `mu` and pitch confidence its radius: tonal music sits near the cardioid's edge,
+/// where the sets are most detailed, while noise relaxes toward smoother shapes.
+pub fn julia_c(spectral_centroid: f32, pitch_confidence: f32) -> (f32, f32) {
+    let (low, high) = JULIA_CENTROID_RANGE_HZ;
+    let centroid = if spectral_centroid.is_finite() { spectral_centroid.clamp(low, high) } else { low };
+    let confidence = if pitch_confidence.is_finite() { pitch_confidence.clamp(0.0, 1.0) } else { 0.0 };
+
+    let angle = std::f32::consts::TAU * (centroid / low).ln() / (high / low).ln();
+    let radius = 0.8 + confidence * 0.18;
+    let (mu_re, mu_im) = (radius * angle.cos(), radius * angle.sin());
+
+    // mu/2 - mu^2/4
+    let c_re = mu_re / 2.0 - (mu_re * mu_re - mu_im * mu_im) / 4.0;
+    let c_im = mu_im / 2.0 - (2.0 * mu_re * mu_im) / 4.0;
+    (c_re, c_im)
+}
+
+/// Vertical field of view for shaders drawn with 3D perspective (`requires_3d`)
+pub const PERSPECTIVE_FOV_Y_DEGREES: f32 = 60.0;
+
+/// Diagonal of the inverse perspective projection, as uploaded in `projection_scale_x/y`
+///
+/// A fragment at centered screen position (x, y) in [-1, 1] looks along the view ray
+/// `(x * scale_x, y * scale_y, 1.0)`; folding the aspect ratio into `scale_x` keeps the
+/// field of view fixed vertically and square pixels square at any window shape.
+pub fn perspective_ray_scale(fov_y_degrees: f32, resolution: (u32, u32)) -> (f32, f32) {
+    let tan_half = (fov_y_degrees.to_radians() * 0.5).tan();
+    let aspect = resolution.0.max(1) as f32 / resolution.1.max(1) as f32;
+    (tan_half * aspect, tan_half)
+}
+
+/// Blend weights for the multi-mode effects, uploaded as the `*_weight` uniforms
+///
+/// The Mixed shader composites its layers by these weights, so they are
+/// normalized to sum to 1.0 before upload.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EffectWeights {
+    pub plasma: f32,
+    pub kaleidoscope: f32,
+    pub tunnel: f32,
+    pub particle: f32,
+    pub fractal: f32,
+    pub spectralizer: f32,
+}
+
+impl EffectWeights {
+    pub const COUNT: usize = 6;
+
+    /// Mixed shader starting blend: the fluid layers lead, the busier ones stay underneath
+    pub const MIXED_RECOMMENDED: Self = Self {
+        plasma: 0.3,
+        kaleidoscope: 0.25,
+        tunnel: 0.15,
+        particle: 0.15,
+        fractal: 0.05,
+        spectralizer: 0.1,
+    };
+
+    /// Effect names in uniform order
+    pub const NAMES: [&'static str; Self::COUNT] = ["Plasma", "Kaleidoscope", "Tunnel", "Particle", "Fractal", "Spectralizer"];
+
+    /// Weights in uniform order: plasma, kaleidoscope, tunnel, particle, fractal, spectralizer
+    pub fn to_array(&self) -> [f32; Self::COUNT] {
+        [self.plasma, self.kaleidoscope, self.tunnel, self.particle, self.fractal, self.spectralizer]
+    }
+
+    pub fn from_array(weights: [f32; Self::COUNT]) -> Self {
+        let [plasma, kaleidoscope, tunnel, particle, fractal, spectralizer] = weights;
+        Self { plasma, kaleidoscope, tunnel, particle, fractal, spectralizer }
+    }
+
+    /// Each weight clamped to 0.0-1.0 (non-finite values become 0.0)
+    pub fn clamped(&self) -> Self {
+        Self::from_array(self.to_array().map(|w| if w.is_finite() { w.clamp(0.0, 1.0) } else { 0.0 }))
+    }
+
+    pub fn sum(&self) -> f32 {
+        self.to_array().iter().sum()
+    }
+
+    /// Weights scaled to sum to 1.0 (negative and non-finite weights count as 0.0; all zero gives the defaults)
+    pub fn normalized(&self) -> Self {
+        let weights = self.to_array().map(|w| if w.is_finite() { w.max(0.0) } else { 0.0 });
+        let total: f32 = weights.iter().sum();
+        if total <= f32::EPSILON || !total.is_finite() {
+            return Self::default().scaled(1.0 / Self::default().sum());
+        }
+        Self::from_array(weights).scaled(1.0 / total)
+    }
+
+    /// Move one weight by `delta` (kept within 0.0-1.0) and rescale the others so the total stays 1.0
+    pub fn nudged(&self, index: usize, delta: f32) -> Self {
+        let mut weights = self.normalized().to_array();
+        let Some(target) = weights.get(index).map(|w| (w + delta).clamp(0.0, 1.0)) else {
+            return self.normalized();
+        };
+
+        let others: f32 = weights.iter().enumerate().filter(|&(i, _)| i != index).map(|(_, w)| w).sum();
+        let remaining = 1.0 - target;
+        for (i, weight) in weights.iter_mut().enumerate() {
+            *weight = if i == index {
+                target
+            } else if others > f32::EPSILON {
+                *weight * remaining / others
+            } else {
+                remaining / (Self::COUNT - 1) as f32
+            };
+        }
+        Self::from_array(weights)
+    }
+
+    fn scaled(&self, factor: f32) -> Self {
+        Self::from_array(self.to_array().map(|w| w * factor))
+    }
+}
+
+impl Default for EffectWeights {
+    fn default() -> Self {
+        let defaults = UniversalUniforms::default();
+        Self {
+            plasma: defaults.plasma_weight,
+            kaleidoscope: defaults.kaleidoscope_weight,
+            tunnel: defaults.tunnel_weight,
+            particle: defaults.particle_weight,
+            fractal: defaults.fractal_weight,
+            spectralizer: defaults.spectralizer_weight,
+        }
+    }
+}
+
 /// Maps audio analysis data to universal uniform structure
 pub struct UniformManager {
     start_time: std::time::Instant,
+    palette_manager: PaletteManager,
+    saturation: f32,
+    kaleidoscope_segments_override: Option<u32>, // None = driven by the music
+    classic_wave_count_override: Option<f32>,
+    classic_radial_speed_override: Option<f32>,
+    screen_shake: f32,
+    spectrogram_head: usize,
+    white_balance_kelvin: f32,
+    white_balance: Vector3<f32>, // Linear RGB multiplier for white_balance_kelvin
+    spectralizer_log_scale: bool,
+    spectralizer_stereo_split: bool,
+    fractal_mode: FractalMode,
+    projection_3d: bool, // Active shader declares `requires_3d`
+    effect_weights: EffectWeights,
+    effect_weights_overridden: bool, // Set by hand; shader recommendations leave them alone
+    shader_recommendations: bool,    // Apply each shader's recommended palette and weights on switch
+    pulse: PulseEnvelope,
+    last_pulse_time: Option<f32>, // `time` of the previous pulse update
 }
 
 impl UniformManager {
     pub fn new() -> Self {
         Self {
             start_time: std::time::Instant::now(),
+            palette_manager: PaletteManager::new(),
+            saturation: 1.0,
+            kaleidoscope_segments_override: None,
+            classic_wave_count_override: None,
+            classic_radial_speed_override: None,
+            screen_shake: 0.0,
+            spectrogram_head: 0,
+            white_balance_kelvin: NEUTRAL_WHITE_BALANCE_KELVIN,
+            white_balance: Vector3::new(1.0, 1.0, 1.0),
+            spectralizer_log_scale: true,
+            spectralizer_stereo_split: false,
+            fractal_mode: FractalMode::default(),
+            projection_3d: false,
+            effect_weights: EffectWeights::default(),
+            effect_weights_overridden: false,
+            shader_recommendations: true,
+            pulse: PulseEnvelope::new(),
+            last_pulse_time: None,
         }
     }
 
-    pub fn map_audio_data(&self,
+    /// Apply a shader's saturation default and, while recommendations are on, its recommended
+    /// palette (unless locked) and effect weights (unless set by hand) when it becomes active
+    pub fn apply_shader_defaults(&mut self, metadata: &ShaderMetadata) {
+        if self.shader_recommendations {
+            let time = self.start_time.elapsed().as_secs_f32();
+            self.palette_manager.apply_shader_default(metadata.recommended_palette, time);
+            if !self.effect_weights_overridden {
+                self.effect_weights = metadata.recommended_weights.normalized();
+            }
+        }
+        self.saturation = metadata.default_saturation.unwrap_or(1.0);
+    }
+
+    /// Turn off to keep the palette and effect weights when switching shaders
+    pub fn set_shader_recommendations(&mut self, enabled: bool) {
+        self.shader_recommendations = enabled;
+    }
+
+    pub fn shader_recommendations(&self) -> bool {
+        self.shader_recommendations
+    }
+
+    pub fn palette_manager(&self) -> &PaletteManager {
+        &self.palette_manager
+    }
+
+    pub fn palette_manager_mut(&mut self) -> &mut PaletteManager {
+        &mut self.palette_manager
+    }
+
+    pub fn saturation(&self) -> f32 {
+        self.saturation
+    }
+
+    /// Fix the kaleidoscope segment count (None = follow the music)
+    pub fn set_kaleidoscope_segments(&mut self, segments: Option<u32>) {
+        self.kaleidoscope_segments_override =
+            segments.map(|s| s.clamp(MIN_KALEIDOSCOPE_SEGMENTS, MAX_KALEIDOSCOPE_SEGMENTS));
+    }
+
+    pub fn kaleidoscope_segments_override(&self) -> Option<u32> {
+        self.kaleidoscope_segments_override
+    }
+
+    /// Fix the Classic shader's radial wave count (None = follow mid/treble)
+    pub fn set_classic_wave_count(&mut self, waves: Option<f32>) {
+        self.classic_wave_count_override = waves
+            .filter(|w| w.is_finite())
+            .map(|w| w.clamp(CLASSIC_WAVE_COUNT_RANGE.0, CLASSIC_WAVE_COUNT_RANGE.1));
+    }
+
+    /// Fix the Classic shader's radial speed multiplier (None = follow the BPM)
+    pub fn set_classic_radial_speed(&mut self, speed: Option<f32>) {
+        self.classic_radial_speed_override = speed
+            .filter(|s| s.is_finite())
+            .map(|s| s.clamp(CLASSIC_RADIAL_SPEED_RANGE.0, CLASSIC_RADIAL_SPEED_RANGE.1));
+    }
+
+    /// Set this frame's screen shake amplitude (already safety-scaled)
+    pub fn set_screen_shake(&mut self, amplitude: f32) {
+        self.screen_shake = amplitude;
+    }
+
+    pub fn set_spectrogram_head(&mut self, head: usize) {
+        self.spectrogram_head = head;
+    }
+
+    /// Tint the final image toward a color temperature, clamped to `WHITE_BALANCE_RANGE_KELVIN`
+    pub fn set_white_balance(&mut self, kelvin: f32) {
+        let (min, max) = WHITE_BALANCE_RANGE_KELVIN;
+        self.white_balance_kelvin = if kelvin.is_finite() { kelvin.clamp(min, max) } else { NEUTRAL_WHITE_BALANCE_KELVIN };
+        self.white_balance = white_balance_multiplier(self.white_balance_kelvin);
+    }
+
+    pub fn white_balance(&self) -> f32 {
+        self.white_balance_kelvin
+    }
+
+    /// Set the same tint on a -1.0 (cool) to 1.0 (warm) scale, clamped
+    pub fn set_color_temperature(&mut self, temperature: f32) {
+        self.set_white_balance(color_temperature_to_kelvin(temperature));
+    }
+
+    pub fn color_temperature(&self) -> f32 {
+        kelvin_to_color_temperature(self.white_balance_kelvin)
+    }
+
+    /// Spread the Spectralizer's frequency axis logarithmically (true) or linearly (false)
+    pub fn set_spectralizer_log_scale(&mut self, enabled: bool) {
+        self.spectralizer_log_scale = enabled;
+    }
+
+    pub fn spectralizer_log_scale(&self) -> bool {
+        self.spectralizer_log_scale
+    }
+
+    /// Split the Spectralizer into left (top) and right (bottom) spectra
+    pub fn set_spectralizer_stereo_split(&mut self, enabled: bool) {
+        self.spectralizer_stereo_split = enabled;
+    }
+
+    pub fn spectralizer_stereo_split(&self) -> bool {
+        self.spectralizer_stereo_split
+    }
+
+    /// Switch the projection uniforms to match a shader's `requires_3d` when its pipeline is built
+    pub fn apply_shader_projection(&mut self, metadata: &ShaderMetadata) {
+        self.projection_3d = metadata.requires_3d;
+    }
+
+    /// Whether the 3D perspective path is active
+    pub fn projection_3d(&self) -> bool {
+        self.projection_3d
+    }
+
+    /// Draw the Fractal shader as the Mandelbrot blend or the pitch-driven Julia set
+    pub fn set_fractal_mode(&mut self, mode: FractalMode) {
+        self.fractal_mode = mode;
+    }
+
+    pub fn fractal_mode(&self) -> FractalMode {
+        self.fractal_mode
+    }
+
+    /// Replace the multi-mode effect weights (normalized to sum to 1.0); shader recommendations
+    /// no longer change them afterwards
+    pub fn set_effect_weights(&mut self, weights: EffectWeights) {
+        self.effect_weights = weights.normalized();
+        self.effect_weights_overridden = true;
+    }
+
+    pub fn effect_weights(&self) -> EffectWeights {
+        self.effect_weights
+    }
+
+    /// Attack and decay of the onset zoom pulse (see `PulseEnvelope::set_times`)
+    pub fn set_pulse_envelope(&mut self, attack: std::time::Duration, decay: std::time::Duration) {
+        self.pulse.set_times(attack, decay);
+    }
+
+    pub fn pulse_envelope(&self) -> &PulseEnvelope {
+        &self.pulse
+    }
+
+    /// Advance the onset pulse to `time` and return the safety-gated pulse scale and offset
+    fn update_pulse(&mut self, onset_strength: f32, time: f32, pattern_complexity: f32) -> (f32, f32) {
+        let dt = self.last_pulse_time.map_or(0.0, |last| time - last);
+        self.last_pulse_time = Some(time);
+        let level = self.pulse.update(onset_strength, dt);
+        pulse_transform(level, pattern_complexity)
+    }
+
+    /// Width of one pixel in the centered UV space shaders use ([-1, 1] vertically)
+    pub fn aa_width(resolution: (u32, u32)) -> f32 {
+        2.0 / resolution.1.max(1) as f32
+    }
+
+    pub fn map_audio_data(&mut self,
                          audio_features: &AudioFeatures,
                          rhythm_features: &RhythmFeatures,
                          resolution: (u32, u32),
                          safety_multipliers: Option<crate::control::safety::SafetyMultipliers>,
                          transition_progress: f32) -> UniversalUniforms {
         let time = self.start_time.elapsed().as_secs_f32();
+        let color_change_rate = safety_multipliers.map_or(1.0, |s| s.color_change_rate);
+        self.palette_manager.update_key_sync(audio_features.detected_key.map(|key| key.root), audio_features.key_confidence, time, color_change_rate);
+        let palette = self.palette_manager.current_palette();
+        let prev_palette = self.palette_manager.previous_palette();
+        let (base_hue, hue_range) = self.palette_manager.hue_params(palette);
+        let (prev_base_hue, prev_hue_range) = self.palette_manager.hue_params(prev_palette);
+
+        // Strong onsets punch the image outward; the safety level caps how far
+        let pattern_complexity = safety_multipliers.map_or(1.0, |s| s.pattern_complexity);
+        let (pulse_scale, pulse_offset) = self.update_pulse(audio_features.onset_strength, time, pattern_complexity);
+        let (fractal_c_real, fractal_c_imag) = julia_c(audio_features.spectral_centroid, audio_features.pitch_confidence);
+        let (projection_scale_x, projection_scale_y) = perspective_ray_scale(PERSPECTIVE_FOV_Y_DEGREES, resolution);
 
         UniversalUniforms {
             // 5-band frequency analysis
@@ -461,6 +1141,7 @@ impl UniformManager {
             tempo_confidence: rhythm_features.tempo_confidence,
             onset_detected: if rhythm_features.onset_detected { 1.0 } else { 0.0 },
             downbeat_detected: if rhythm_features.downbeat_detected { 1.0 } else { 0.0 },
+            beat_position: rhythm_features.beat_position as f32,
 
             // Spectral characteristics
             spectral_centroid: audio_features.spectral_centroid,
@@ -473,9 +1154,69 @@ impl UniformManager {
             // Time
             time,
 
+            // Palette and saturation (per-shader defaults unless locked)
+            saturation: self.saturation,
+            palette_index: palette.as_index(),
+            palette_base_hue: base_hue,
+            palette_hue_range: hue_range,
+            prev_palette_index: prev_palette.as_index(),
+            prev_palette_base_hue: prev_base_hue,
+            prev_palette_hue_range: prev_hue_range,
+            custom_palette_stops: self.palette_manager.custom_stops().len() as f32,
+
+            // Effect weights
+            plasma_weight: self.effect_weights.plasma,
+            kaleidoscope_weight: self.effect_weights.kaleidoscope,
+            tunnel_weight: self.effect_weights.tunnel,
+            particle_weight: self.effect_weights.particle,
+            fractal_weight: self.effect_weights.fractal,
+            spectralizer_weight: self.effect_weights.spectralizer,
+
+            // Shader-specific parameters
+            kaleidoscope_segments: self.kaleidoscope_segments_override
+                .unwrap_or_else(|| kaleidoscope_segments(audio_features.pitch_confidence, rhythm_features.tempo_confidence)) as f32,
+            classic_wave_count: self.classic_wave_count_override
+                .unwrap_or_else(|| classic_wave_count(audio_features.mid, audio_features.treble)),
+            classic_radial_speed: self.classic_radial_speed_override
+                .unwrap_or_else(|| classic_radial_speed(rhythm_features.estimated_bpm, rhythm_features.tempo_confidence)),
+            spectrogram_head: self.spectrogram_head as f32,
+
             // Resolution
             resolution_x: resolution.0 as f32,
             resolution_y: resolution.1 as f32,
+            aa_width: Self::aa_width(resolution),
+
+            // Screen transform
+            screen_shake: self.screen_shake,
+
+            // Output color
+            white_balance_r: self.white_balance.x,
+            white_balance_g: self.white_balance.y,
+            white_balance_b: self.white_balance.z,
+
+            // Frequency axis
+            spectralizer_log_scale: if self.spectralizer_log_scale { 1.0 } else { 0.0 },
+
+            // Onset pulse
+            pulse_scale,
+            pulse_offset,
+
+            // Source separation
+            harmonic_energy: audio_features.harmonic_energy,
+            percussive_energy: audio_features.percussive_energy,
+
+            // Fractal shader (the iteration cap stays at its default unless rendering with quality)
+            fractal_mode: self.fractal_mode.as_uniform(),
+            fractal_c_real,
+            fractal_c_imag,
+
+            // Projection
+            projection_mode: if self.projection_3d { 1.0 } else { 0.0 },
+            projection_scale_x,
+            projection_scale_y,
+
+            spectralizer_stereo_split: if self.spectralizer_stereo_split { 1.0 } else { 0.0 },
+            color_temperature: kelvin_to_color_temperature(self.white_balance_kelvin),
 
             // Apply safety multipliers if provided
             safety_beat_intensity: safety_multipliers.map(|s| s.beat_intensity).unwrap_or(1.0),
@@ -485,8 +1226,8 @@ impl UniformManager {
             safety_pattern_complexity: safety_multipliers.map(|s| s.pattern_complexity).unwrap_or(1.0),
             safety_emergency_stop: safety_multipliers.map(|s| if s.beat_intensity == 0.0 { 0.0 } else { 1.0 }).unwrap_or(1.0),
 
-            // Shader transition blending
-            transition_blend: transition_progress,
+            // Shader transition blending, or the palette blend while on a continuous palette position
+            transition_blend: self.palette_manager.continuous_blend().unwrap_or(transition_progress),
 
             // Keep default values for other parameters
             ..UniversalUniforms::default()
@@ -494,6 +1235,28 @@ impl UniformManager {
     }
 }
 
+/// Time-domain samples uploaded each frame for the oscilloscope (binding 1)
+pub const WAVEFORM_SAMPLES: usize = 1024;
+
+/// Right-align the newest samples into a full waveform upload, zero-padding the oldest end
+pub fn fit_waveform(samples: &[f32]) -> [f32; WAVEFORM_SAMPLES] {
+    let mut waveform = [0.0; WAVEFORM_SAMPLES];
+    let newest = &samples[samples.len().saturating_sub(WAVEFORM_SAMPLES)..];
+    waveform[WAVEFORM_SAMPLES - newest.len()..].copy_from_slice(newest);
+    waveform
+}
+
+/// Log-spaced rows per channel in the stereo spectrum upload (binding 4)
+pub const STEREO_SPECTRUM_ROWS: usize = 64;
+
+/// Fold left and right FFT magnitudes into one upload: left rows, then right rows, each 0-1
+pub fn fit_stereo_spectrum(left: &[f32], right: &[f32]) -> [f32; STEREO_SPECTRUM_ROWS * 2] {
+    let mut spectrum = [0.0; STEREO_SPECTRUM_ROWS * 2];
+    spectrum[..STEREO_SPECTRUM_ROWS].copy_from_slice(&fold_bins(left, STEREO_SPECTRUM_ROWS));
+    spectrum[STEREO_SPECTRUM_ROWS..].copy_from_slice(&fold_bins(right, STEREO_SPECTRUM_ROWS));
+    spectrum
+}
+
 /// Main shader system that coordinates everything
 pub struct ShaderSystem {
     registry: ShaderRegistry,
@@ -503,6 +1266,14 @@ pub struct ShaderSystem {
     uniform_buffer: Option<wgpu::Buffer>,
     bind_group: Option<wgpu::BindGroup>,
     bind_group_layout: wgpu::BindGroupLayout,
+    waveform_buffer: wgpu::Buffer, // Read-only storage at binding 1; only the oscilloscope declares it
+    spectrogram: SpectrogramHistory,
+    spectrogram_texture: SpectrogramTexture, // Texture at binding 2; only the spectrogram declares it
+    palette_stops_buffer: wgpu::Buffer, // Read-only storage at binding 3: custom palette stops as (r, g, b, position)
+    stereo_spectrum_buffer: wgpu::Buffer, // Read-only storage at binding 4; only the spectralizer declares it
+    quad: QuadBuffers,
+    safety_multipliers: Option<crate::control::safety::SafetyMultipliers>, // None renders at full intensity
+    gpu_timer: Option<GpuTimer>, // Times the shader pass; resolved and collected by the owner of the frame
     resolution: (u32, u32),
 }
 
@@ -525,10 +1296,71 @@ impl ShaderSystem {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
             label: Some("universal_uniform_bind_group_layout"),
         });
 
+        let waveform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("waveform_storage_buffer"),
+            contents: bytemuck::cast_slice(&[0.0f32; WAVEFORM_SAMPLES]),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let spectrogram = SpectrogramHistory::new(DEFAULT_SPECTROGRAM_COLUMNS, SPECTROGRAM_ROWS);
+        let spectrogram_texture = SpectrogramTexture::new(device, &spectrogram);
+
+        let palette_stops_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("palette_stops_storage_buffer"),
+            contents: bytemuck::cast_slice(&uniform_manager.palette_manager().custom_stops_packed()),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let stereo_spectrum_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("stereo_spectrum_storage_buffer"),
+            contents: bytemuck::cast_slice(&[0.0f32; STEREO_SPECTRUM_ROWS * 2]),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+
         let mut system = Self {
             registry,
             transitioner,
@@ -537,10 +1369,19 @@ impl ShaderSystem {
             uniform_buffer: None,
             bind_group: None,
             bind_group_layout,
+            waveform_buffer,
+            spectrogram,
+            spectrogram_texture,
+            palette_stops_buffer,
+            stereo_spectrum_buffer,
+            quad: create_quad_buffers(device),
+            safety_multipliers: None,
+            gpu_timer: None,
             resolution: (config.width, config.height),
         };
 
         // Build initial shader pipeline
+        system.apply_active_shader_defaults();
         system.rebuild_pipeline(device, config)?;
 
         Ok(system)
@@ -563,6 +1404,7 @@ impl ShaderSystem {
         }
 
         self.transitioner.switch_immediately_to(shader_type);
+        self.apply_active_shader_defaults();
         self.rebuild_pipeline(device, config)?;
         Ok(())
     }
@@ -579,16 +1421,200 @@ impl ShaderSystem {
 
         // Rebuild pipeline if transition completed
         if was_transitioning && !self.transitioner.is_transitioning() {
+            self.apply_active_shader_defaults();
             self.rebuild_pipeline(device, config)?;
         }
 
         Ok(())
     }
 
+    /// Apply the active shader's saturation default and palette/weight recommendations
+    fn apply_active_shader_defaults(&mut self) {
+        if let Some(metadata) = self.registry.get(self.transitioner.current_shader()) {
+            self.uniform_manager.apply_shader_defaults(metadata);
+        }
+    }
+
+    /// Lock a palette so shader defaults and downbeats no longer change it
+    pub fn lock_palette(&mut self, palette: ColorPalette) {
+        let time = self.uniform_manager.start_time.elapsed().as_secs_f32();
+        self.uniform_manager.palette_manager_mut().lock_palette(palette, time);
+    }
+
+    /// Release a locked palette
+    pub fn unlock_palette(&mut self) {
+        self.uniform_manager.palette_manager_mut().unlock_palette();
+    }
+
+    pub fn current_palette(&self) -> ColorPalette {
+        self.uniform_manager.palette_manager().current_palette()
+    }
+
+    /// Blend continuously across the built-in palettes (0.0-8.0, wrapping Violet into Rainbow)
+    pub fn set_palette_position(&mut self, position: f32) {
+        self.uniform_manager.palette_manager_mut().set_continuous_position(position);
+    }
+
+    /// Continuous palette position, if one has been set
+    pub fn palette_position(&self) -> Option<f32> {
+        self.uniform_manager.palette_manager().continuous_position()
+    }
+
+    /// Upload user-defined palette stops (at most `MAX_CUSTOM_PALETTE_STOPS`) and lock the Custom palette
+    pub fn set_custom_palette(&mut self, queue: &wgpu::Queue, stops: Vec<(f32, [f32; 3])>) -> Result<()> {
+        self.uniform_manager.palette_manager_mut().set_custom_palette(stops)?;
+        let packed: [[f32; 4]; MAX_CUSTOM_PALETTE_STOPS] = self.uniform_manager.palette_manager().custom_stops_packed();
+        queue.write_buffer(&self.palette_stops_buffer, 0, bytemuck::cast_slice(&packed));
+        self.lock_palette(ColorPalette::Custom);
+        Ok(())
+    }
+
+    /// Manually fix the kaleidoscope mirror count (None = follow the music)
+    pub fn set_kaleidoscope_segments(&mut self, segments: Option<u32>) {
+        self.uniform_manager.set_kaleidoscope_segments(segments);
+    }
+
+    /// Manually fix the Classic shader's wave count and radial speed (None = follow the music)
+    pub fn set_classic_waves(&mut self, wave_count: Option<f32>, radial_speed: Option<f32>) {
+        self.uniform_manager.set_classic_wave_count(wave_count);
+        self.uniform_manager.set_classic_radial_speed(radial_speed);
+    }
+
+    /// Scale effects for a safety level on the following frames
+    pub fn set_safety_multipliers(&mut self, multipliers: Option<crate::control::safety::SafetyMultipliers>) {
+        self.safety_multipliers = multipliers;
+    }
+
+    /// Time the shader pass with timestamp queries (None stops timing)
+    pub fn set_gpu_timer(&mut self, gpu_timer: Option<GpuTimer>) {
+        self.gpu_timer = gpu_timer;
+    }
+
+    /// Timer for resolving and collecting the shader pass timings, if one is set
+    pub fn gpu_timer_mut(&mut self) -> Option<&mut GpuTimer> {
+        self.gpu_timer.as_mut()
+    }
+
+    /// Whole-screen shake amplitude applied by the shared vertex shader
+    pub fn set_screen_shake(&mut self, amplitude: f32) {
+        self.uniform_manager.set_screen_shake(amplitude);
+    }
+
+    /// Attack and decay of the onset zoom pulse applied by the shared vertex shader
+    pub fn set_pulse_envelope(&mut self, attack: std::time::Duration, decay: std::time::Duration) {
+        self.uniform_manager.set_pulse_envelope(attack, decay);
+    }
+
+    /// Global color temperature for installations (6500K = neutral)
+    pub fn set_white_balance(&mut self, kelvin: f32) {
+        self.uniform_manager.set_white_balance(kelvin);
+    }
+
+    pub fn white_balance(&self) -> f32 {
+        self.uniform_manager.white_balance()
+    }
+
+    pub fn set_color_temperature(&mut self, temperature: f32) {
+        self.uniform_manager.set_color_temperature(temperature);
+    }
+
+    pub fn color_temperature(&self) -> f32 {
+        self.uniform_manager.color_temperature()
+    }
+
+    /// Logarithmic (true, default) or linear (false) frequency axis for the Spectralizer
+    pub fn set_spectralizer_log_scale(&mut self, enabled: bool) {
+        self.uniform_manager.set_spectralizer_log_scale(enabled);
+    }
+
+    pub fn spectralizer_log_scale(&self) -> bool {
+        self.uniform_manager.spectralizer_log_scale()
+    }
+
+    /// Draw the left channel spectrum on the Spectralizer's top half and the right on the bottom
+    pub fn set_spectralizer_stereo_split(&mut self, enabled: bool) {
+        self.uniform_manager.set_spectralizer_stereo_split(enabled);
+    }
+
+    pub fn spectralizer_stereo_split(&self) -> bool {
+        self.uniform_manager.spectralizer_stereo_split()
+    }
+
+    /// Upload left/right FFT magnitudes for the split Spectralizer
+    pub fn set_stereo_spectrum(&self, queue: &wgpu::Queue, left: &[f32], right: &[f32]) {
+        queue.write_buffer(&self.stereo_spectrum_buffer, 0, bytemuck::cast_slice(&fit_stereo_spectrum(left, right)));
+    }
+
+    /// Mandelbrot blend (default) or pitch-driven Julia set for the Fractal shader
+    pub fn set_fractal_mode(&mut self, mode: FractalMode) {
+        self.uniform_manager.set_fractal_mode(mode);
+    }
+
+    pub fn fractal_mode(&self) -> FractalMode {
+        self.uniform_manager.fractal_mode()
+    }
+
+    /// Set the multi-mode effect weights (otherwise the active shader's recommended weights)
+    pub fn set_effect_weights(&mut self, weights: EffectWeights) {
+        self.uniform_manager.set_effect_weights(weights);
+    }
+
+    /// Apply each shader's recommended palette and effect weights when switching to it (on by default)
+    pub fn set_shader_recommendations(&mut self, enabled: bool) {
+        self.uniform_manager.set_shader_recommendations(enabled);
+    }
+
+    pub fn shader_recommendations(&self) -> bool {
+        self.uniform_manager.shader_recommendations()
+    }
+
+    pub fn effect_weights(&self) -> EffectWeights {
+        self.uniform_manager.effect_weights()
+    }
+
+    pub fn is_palette_locked(&self) -> bool {
+        self.uniform_manager.palette_manager().is_locked()
+    }
+
+    /// Follow the detected key with the palette hue (C is red, around the circle of fifths)
+    pub fn set_key_sync(&mut self, enabled: bool) {
+        self.uniform_manager.palette_manager_mut().set_key_sync(enabled);
+    }
+
+    pub fn is_key_synced(&self) -> bool {
+        self.uniform_manager.palette_manager().is_key_synced()
+    }
+
+    /// Upload the newest time-domain samples for the oscilloscope trace
+    pub fn set_waveform(&self, queue: &wgpu::Queue, samples: &[f32]) {
+        queue.write_buffer(&self.waveform_buffer, 0, bytemuck::cast_slice(&fit_waveform(samples)));
+    }
+
+    /// Scroll one frame of FFT magnitudes into the spectrogram, uploading only the new column
+    pub fn push_spectrum(&mut self, queue: &wgpu::Queue, bins: &[f32]) {
+        self.spectrogram.push(bins);
+        self.spectrogram_texture.upload(queue, &mut self.spectrogram);
+        self.uniform_manager.set_spectrogram_head(self.spectrogram.head());
+    }
+
+    /// Change how many frames of history the spectrogram spans (clears it)
+    pub fn set_spectrogram_columns(&mut self, columns: usize, device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> Result<()> {
+        self.spectrogram = SpectrogramHistory::new(columns, SPECTROGRAM_ROWS);
+        self.spectrogram_texture = SpectrogramTexture::new(device, &self.spectrogram);
+        self.uniform_manager.set_spectrogram_head(self.spectrogram.head());
+        // The bind group references the old texture view
+        self.rebuild_pipeline(device, config)
+    }
+
+    pub fn spectrogram_columns(&self) -> usize {
+        self.spectrogram.columns()
+    }
+
     fn rebuild_pipeline(&mut self, device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> Result<()> {
         let current_shader = self.transitioner.current_shader();
         let metadata = self.registry.get(current_shader)
             .ok_or_else(|| anyhow!("Shader metadata not found for {:?}", current_shader))?;
+        self.uniform_manager.apply_shader_projection(metadata);
 
         // Create shader modules
         let vertex_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -640,7 +1666,7 @@ impl ShaderSystem {
                 module: &fragment_shader,
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
+                    format: render_format(config),
                     blend: Some(wgpu::BlendState::REPLACE),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -675,10 +1701,28 @@ impl ShaderSystem {
         // Create bind group
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &self.bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            }],
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.waveform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(self.spectrogram_texture.view()),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: self.palette_stops_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: self.stereo_spectrum_buffer.as_entire_binding(),
+                },
+            ],
             label: Some("universal_uniform_bind_group"),
         });
 
@@ -686,103 +1730,94 @@ impl ShaderSystem {
         self.uniform_buffer = Some(uniform_buffer);
         self.bind_group = Some(bind_group);
 
-        println!("🎨 Switched to shader: {}", metadata.shader_type.name());
+        eprintln!("🎨 Switched to shader: {}", metadata.shader_type.name());
 
         Ok(())
     }
 
-    pub fn render(&self,
-                  device: &wgpu::Device,
+    /// Record the current shader into `frame` (submitted later with the rest of the frame)
+    pub fn render(&mut self,
                   queue: &wgpu::Queue,
+                  frame: &mut FrameEncoder,
                   view: &wgpu::TextureView,
-                  vertex_buffer: &wgpu::Buffer,
-                  index_buffer: &wgpu::Buffer,
-                  index_count: u32,
                   audio_features: &AudioFeatures,
                   rhythm_features: &RhythmFeatures) -> Result<()> {
 
         // Update uniforms
         if let Some(ref uniform_buffer) = self.uniform_buffer {
             let transition_progress = self.transitioner.transition_progress();
-            let uniforms = self.uniform_manager.map_audio_data(audio_features, rhythm_features, self.resolution, None, transition_progress);
+            let uniforms = self.uniform_manager.map_audio_data(audio_features, rhythm_features, self.resolution, self.safety_multipliers, transition_progress);
             queue.write_buffer(uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
         }
 
-        // Render
+        self.draw(frame, view);
+        Ok(())
+    }
+
+    /// Draw the fullscreen quad with whatever uniforms were last written, timing the pass
+    /// when a GPU timer is set
+    fn draw(&self, frame: &mut FrameEncoder, view: &wgpu::TextureView) {
         if let (Some(ref pipeline), Some(ref bind_group)) = (&self.current_pipeline, &self.bind_group) {
-            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("shader_system_render_encoder"),
+            let mut render_pass = frame.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("shader_system_render_pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: 0.0,
+                            g: 0.0,
+                            b: 0.0,
+                            a: 1.0,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: self.gpu_timer.as_ref().and_then(GpuTimer::timestamp_writes),
             });
 
-            {
-                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: Some("shader_system_render_pass"),
-                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(wgpu::Color {
-                                r: 0.0,
-                                g: 0.0,
-                                b: 0.0,
-                                a: 1.0,
-                            }),
-                            store: wgpu::StoreOp::Store,
-                        },
-                    })],
-                    depth_stencil_attachment: None,
-                    occlusion_query_set: None,
-                    timestamp_writes: None,
-                });
-
-                render_pass.set_pipeline(pipeline);
-                render_pass.set_bind_group(0, bind_group, &[]);
-                render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
-                render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-                render_pass.draw_indexed(0..index_count, 0, 0..1);
-            }
-
-            queue.submit(std::iter::once(encoder.finish()));
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.quad.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.quad.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+            render_pass.draw_indexed(0..self.quad.index_count, 0, 0..1);
         }
-
-        Ok(())
     }
 
-    /// Render with performance quality awareness
-    pub fn render_with_quality(&self,
-                               device: &wgpu::Device,
+    /// Render with performance quality awareness, recorded into `frame`
+    pub fn render_with_quality(&mut self,
                                queue: &wgpu::Queue,
+                               frame: &mut FrameEncoder,
                                view: &wgpu::TextureView,
-                               vertex_buffer: &wgpu::Buffer,
-                               index_buffer: &wgpu::Buffer,
-                               index_count: u32,
                                audio_features: &AudioFeatures,
                                rhythm_features: &RhythmFeatures,
-                               quality: QualityLevel,
-                               safety_multipliers: Option<crate::control::safety::SafetyMultipliers>) -> Result<()> {
+                               quality: &PerformanceUniforms) -> Result<()> {
 
         // Update uniforms with performance parameters
         if let Some(ref uniform_buffer) = self.uniform_buffer {
             let transition_progress = self.transitioner.transition_progress();
-            let mut uniforms = self.uniform_manager.map_audio_data(audio_features, rhythm_features, self.resolution, safety_multipliers, transition_progress);
+            let mut uniforms = self.uniform_manager.map_audio_data(audio_features, rhythm_features, self.resolution, self.safety_multipliers, transition_progress);
 
             // Apply quality scaling to audio parameters
-            let quality_scale = quality.effect_intensity();
+            let quality_scale = quality.effect_intensity;
             uniforms.overall_volume *= quality_scale;
             uniforms.color_intensity *= quality_scale;
             uniforms.beat_strength *= quality_scale;
 
             // Reduce complexity for lower quality levels
-            let complexity_scale = quality.complexity_multiplier();
+            let complexity_scale = quality.complexity_multiplier;
             uniforms.spectral_flux *= complexity_scale;
             uniforms.onset_strength *= complexity_scale;
+            uniforms.fractal_max_iterations = quality.max_iterations;
 
             queue.write_buffer(uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
         }
 
-        // Use regular render method for actual rendering
-        self.render(device, queue, view, vertex_buffer, index_buffer,
-                   index_count, audio_features, rhythm_features)
+        // Draw without rewriting uniforms so the quality and safety scaling above is kept
+        self.draw(frame, view);
+        Ok(())
     }
 
     pub fn current_shader(&self) -> ShaderType {
@@ -796,6 +1831,15 @@ impl ShaderSystem {
     pub fn is_transitioning(&self) -> bool {
         self.transitioner.is_transitioning()
     }
+
+    /// Easing curve for shader crossfades
+    pub fn set_transition_easing(&mut self, easing: EasingCurve) {
+        self.transitioner.set_easing(easing);
+    }
+
+    pub fn transition_easing(&self) -> EasingCurve {
+        self.transitioner.easing()
+    }
 }
 
 #[cfg(test)]
@@ -803,6 +1847,8 @@ mod tests {
     use super::*;
     use crate::audio::{AudioFeatures, RhythmFeatures};
     use crate::control::safety::SafetyMultipliers;
+    use crate::clock::MockClock;
+    use crate::rendering::request_headless_device;
 
     #[test]
     fn test_uniform_manager_creation() {
@@ -813,7 +1859,7 @@ mod tests {
 
     #[test]
     fn test_audio_data_mapping_basic() {
-        let manager = UniformManager::new();
+        let mut manager = UniformManager::new();
 
         let audio_features = AudioFeatures {
             sub_bass: 0.1,
@@ -828,9 +1874,16 @@ mod tests {
             spectral_centroid: 1000.0,
             spectral_rolloff: 2000.0,
             spectral_flux: 0.8,
+            spectral_crest: 1.0,
             pitch_confidence: 0.9,
             zero_crossing_rate: 0.1,
+            detected_key: None,
+            key_confidence: 0.0,
             onset_strength: 0.5,
+            stereo_balance: 0.0,
+            stereo_width: 0.0,
+            harmonic_energy: 0.35,
+            percussive_energy: 0.25,
         };
 
         let rhythm_features = RhythmFeatures {
@@ -841,7 +1894,8 @@ mod tests {
             onset_detected: true,
             downbeat_detected: false,
             rhythm_stability: 0.7,
-            beat_position: 0,
+            beat_position: 2,
+            tempo_lock_changed: false,
         };
 
         let resolution = (1920, 1080);
@@ -865,6 +1919,7 @@ mod tests {
         assert_eq!(uniforms.tempo_confidence, 0.9);
         assert_eq!(uniforms.onset_detected, 1.0); // true -> 1.0
         assert_eq!(uniforms.downbeat_detected, 0.0); // false -> 0.0
+        assert_eq!(uniforms.beat_position, 2.0);
 
         // Verify spectral characteristics
         assert_eq!(uniforms.spectral_centroid, 1000.0);
@@ -874,6 +1929,10 @@ mod tests {
         assert_eq!(uniforms.zero_crossing_rate, 0.1);
         assert_eq!(uniforms.onset_strength, 0.5);
 
+        // Verify source separation
+        assert_eq!(uniforms.harmonic_energy, 0.35);
+        assert_eq!(uniforms.percussive_energy, 0.25);
+
         // Verify resolution mapping
         assert_eq!(uniforms.resolution_x, 1920.0);
         assert_eq!(uniforms.resolution_y, 1080.0);
@@ -892,7 +1951,7 @@ mod tests {
 
     #[test]
     fn test_safety_multipliers_integration() {
-        let manager = UniformManager::new();
+        let mut manager = UniformManager::new();
         let audio_features = AudioFeatures::new();
         let rhythm_features = RhythmFeatures::new();
         let resolution = (800, 600);
@@ -903,6 +1962,7 @@ mod tests {
             color_change_rate: 0.4,
             brightness_range: 0.5,
             pattern_complexity: 0.6,
+            screen_shake: 0.6,
         };
 
         let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, resolution, Some(safety_multipliers), 1.0);
@@ -918,7 +1978,7 @@ mod tests {
 
     #[test]
     fn test_emergency_stop_detection() {
-        let manager = UniformManager::new();
+        let mut manager = UniformManager::new();
         let audio_features = AudioFeatures::new();
         let rhythm_features = RhythmFeatures::new();
         let resolution = (800, 600);
@@ -929,6 +1989,7 @@ mod tests {
             color_change_rate: 0.0,
             brightness_range: 0.1,
             pattern_complexity: 0.0,
+            screen_shake: 0.0,
         };
 
         let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, resolution, Some(emergency_safety), 1.0);
@@ -939,7 +2000,7 @@ mod tests {
 
     #[test]
     fn test_boolean_rhythm_conversion() {
-        let manager = UniformManager::new();
+        let mut manager = UniformManager::new();
         let audio_features = AudioFeatures::new();
         let resolution = (1920, 1080);
 
@@ -968,7 +2029,7 @@ mod tests {
 
     #[test]
     fn test_resolution_conversion() {
-        let manager = UniformManager::new();
+        let mut manager = UniformManager::new();
         let audio_features = AudioFeatures::new();
         let rhythm_features = RhythmFeatures::new();
 
@@ -987,9 +2048,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_aa_width_scales_inversely_with_resolution() {
+        let mut manager = UniformManager::new();
+        let audio_features = AudioFeatures::new();
+        let rhythm_features = RhythmFeatures::new();
+
+        let low_res = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        let high_res = manager.map_audio_data(&audio_features, &rhythm_features, (1600, 1200), None, 1.0);
+
+        assert!((low_res.aa_width - 2.0 / 600.0).abs() < 1e-6);
+        assert!((low_res.aa_width / high_res.aa_width - 2.0).abs() < 1e-5);
+    }
+
     #[test]
     fn test_transition_blend_progress_mapping() {
-        let manager = UniformManager::new();
+        let mut manager = UniformManager::new();
         let audio_features = AudioFeatures::new();
         let rhythm_features = RhythmFeatures::new();
         let resolution = (1920, 1080);
@@ -1003,8 +2077,428 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_kaleidoscope_segments_stay_in_range() {
+        for pitch_step in 0..=20 {
+            for tempo_step in 0..=20 {
+                let pitch = pitch_step as f32 * 0.1 - 0.5; // Includes out-of-range values
+                let tempo = tempo_step as f32 * 0.1 - 0.5;
+                let segments = kaleidoscope_segments(pitch, tempo);
+                assert!((MIN_KALEIDOSCOPE_SEGMENTS..=MAX_KALEIDOSCOPE_SEGMENTS).contains(&segments));
+            }
+        }
+        assert_eq!(kaleidoscope_segments(f32::NAN, f32::INFINITY), 6);
+        assert!(kaleidoscope_segments(1.0, 1.0) > kaleidoscope_segments(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_kaleidoscope_segments_override() {
+        let mut manager = UniformManager::new();
+        let mut audio_features = AudioFeatures::new();
+        audio_features.pitch_confidence = 1.0;
+        let rhythm_features = RhythmFeatures::new();
+
+        let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        assert_eq!(uniforms.kaleidoscope_segments, 12.0);
+        assert_eq!(uniforms.kaleidoscope_segments.fract(), 0.0);
+
+        manager.set_kaleidoscope_segments(Some(40));
+        let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        assert_eq!(uniforms.kaleidoscope_segments, MAX_KALEIDOSCOPE_SEGMENTS as f32);
+
+        manager.set_kaleidoscope_segments(None);
+        let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        assert_eq!(uniforms.kaleidoscope_segments, 12.0);
+    }
+
+    #[test]
+    fn test_classic_wave_count_tracks_treble() {
+        let quiet = classic_wave_count(0.3, 0.0);
+        let bright = classic_wave_count(0.3, 0.9);
+        assert!(bright > quiet);
+
+        for step in 0..=20 {
+            let level = step as f32 * 0.1 - 0.5; // Includes out-of-range values
+            let waves = classic_wave_count(level, level);
+            assert!((CLASSIC_WAVE_COUNT_RANGE.0..=CLASSIC_WAVE_COUNT_RANGE.1).contains(&waves));
+        }
+        assert_eq!(classic_wave_count(f32::NAN, f32::INFINITY), 12.0);
+
+        // Low tempo confidence keeps the default speed
+        assert_eq!(classic_radial_speed(180.0, 0.0), 1.0);
+        assert!((classic_radial_speed(180.0, 1.0) - 1.5).abs() < 1e-6);
+        assert_eq!(classic_radial_speed(1000.0, 1.0), CLASSIC_RADIAL_SPEED_RANGE.1);
+    }
+
+    #[test]
+    fn test_classic_wave_overrides() {
+        let mut manager = UniformManager::new();
+        let mut audio_features = AudioFeatures::new();
+        audio_features.treble = 1.0;
+        let rhythm_features = RhythmFeatures::new();
+
+        manager.set_classic_wave_count(Some(100.0));
+        manager.set_classic_radial_speed(Some(0.5));
+        let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        assert_eq!(uniforms.classic_wave_count, CLASSIC_WAVE_COUNT_RANGE.1);
+        assert_eq!(uniforms.classic_radial_speed, 0.5);
+
+        manager.set_classic_wave_count(None);
+        manager.set_classic_radial_speed(None);
+        let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        assert_eq!(uniforms.classic_wave_count, classic_wave_count(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_white_balance_uniforms() {
+        let mut manager = UniformManager::new();
+        let audio_features = AudioFeatures::new();
+        let rhythm_features = RhythmFeatures::new();
+
+        let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        assert!((uniforms.white_balance_r - 1.0).abs() < 1e-3);
+        assert!((uniforms.white_balance_b - 1.0).abs() < 1e-3);
+
+        manager.set_white_balance(3200.0);
+        let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        assert!(uniforms.white_balance_r > uniforms.white_balance_b);
+
+        manager.set_white_balance(100_000.0);
+        assert_eq!(manager.white_balance(), WHITE_BALANCE_RANGE_KELVIN.1);
+    }
+
+    #[test]
+    fn test_color_temperature_uniform() {
+        assert_eq!(UniversalUniforms::default().color_temperature, 0.0);
+
+        let mut manager = UniformManager::new();
+        let uniforms = manager.map_audio_data(&AudioFeatures::new(), &RhythmFeatures::new(), (800, 600), None, 1.0);
+        assert!(uniforms.color_temperature.abs() < 1e-4);
+
+        // Warm is the same tint as a low Kelvin white balance
+        manager.set_color_temperature(0.6);
+        let uniforms = manager.map_audio_data(&AudioFeatures::new(), &RhythmFeatures::new(), (800, 600), None, 1.0);
+        assert!((uniforms.color_temperature - 0.6).abs() < 1e-4);
+        assert!(manager.white_balance() < NEUTRAL_WHITE_BALANCE_KELVIN);
+        assert!(uniforms.white_balance_r > uniforms.white_balance_b);
+
+        // Out-of-range settings clamp to the ends of the scale
+        manager.set_color_temperature(3.0);
+        assert!((manager.color_temperature() - 1.0).abs() < 1e-4);
+        manager.set_color_temperature(-3.0);
+        assert!((manager.color_temperature() + 1.0).abs() < 1e-4);
+        assert!((manager.white_balance() - WHITE_BALANCE_RANGE_KELVIN.1).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_effect_weights_reach_uniforms() {
+        let mut manager = UniformManager::new();
+        let uniforms = manager.map_audio_data(&AudioFeatures::new(), &RhythmFeatures::new(), (800, 600), None, 1.0);
+        assert_eq!(uniforms.plasma_weight, UniversalUniforms::default().plasma_weight);
+        assert_eq!(manager.effect_weights(), EffectWeights::default());
+
+        manager.set_effect_weights(EffectWeights::from_array([0.5, 0.0, 0.25, 1.0, f32::NAN, 0.25]));
+        let uniforms = manager.map_audio_data(&AudioFeatures::new(), &RhythmFeatures::new(), (800, 600), None, 1.0);
+        assert!((uniforms.plasma_weight - 0.25).abs() < 1e-6);
+        assert_eq!(uniforms.kaleidoscope_weight, 0.0);
+        assert!((uniforms.tunnel_weight - 0.125).abs() < 1e-6);
+        assert!((uniforms.particle_weight - 0.5).abs() < 1e-6);
+        assert_eq!(uniforms.fractal_weight, 0.0);
+        assert!((uniforms.spectralizer_weight - 0.125).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_effect_weight_normalization() {
+        assert!((EffectWeights::default().sum() - 1.0).abs() < 1e-6);
+
+        let normalized = EffectWeights::from_array([2.0, 2.0, 0.0, -1.0, 0.0, 4.0]).normalized();
+        assert_eq!(normalized.to_array(), [0.25, 0.25, 0.0, 0.0, 0.0, 0.5]);
+
+        // Nothing to blend falls back to the defaults
+        let zero = EffectWeights::from_array([0.0; EffectWeights::COUNT]).normalized();
+        assert!((zero.plasma - EffectWeights::default().plasma).abs() < 1e-6);
+
+        // Nudging one weight keeps the total at 1.0 and the others in proportion
+        let nudged = normalized.nudged(0, 0.25);
+        assert!((nudged.plasma - 0.5).abs() < 1e-6);
+        assert!((nudged.sum() - 1.0).abs() < 1e-5);
+        assert!((nudged.spectralizer - 2.0 * nudged.kaleidoscope).abs() < 1e-6);
+
+        let solo = EffectWeights::from_array([0.0, 0.0, 0.0, 0.0, 0.0, 1.0]).nudged(5, -0.5);
+        assert!((solo.spectralizer - 0.5).abs() < 1e-6);
+        assert!((solo.plasma - 0.1).abs() < 1e-6);
+        assert_eq!(EffectWeights::default().nudged(0, -5.0).plasma, 0.0);
+    }
+
+    #[test]
+    fn test_validate_all_reports_broken_shader() {
+        let Ok((device, _queue)) = request_headless_device() else {
+            println!("Skipping shader validation test: no GPU adapter available");
+            return;
+        };
+
+        let mut registry = ShaderRegistry::new();
+        let results = registry.validate_all(&device);
+        assert_eq!(results.len(), ShaderType::all().len());
+        for (shader_type, result) in &results {
+            assert!(result.is_ok(), "{} should compile: {:?}", shader_type.name(), result);
+        }
+
+        let mut broken = registry.get(ShaderType::Plasma).unwrap().clone();
+        broken.fragment_source = "@fragment fn fs_main() -> @location(0) vec4<f32> { return undefined_color; }";
+        registry.register(broken);
+
+        let results = registry.validate_all(&device);
+        let (_, plasma) = results.iter().find(|(shader_type, _)| *shader_type == ShaderType::Plasma).unwrap();
+        let error = plasma.as_ref().expect_err("Broken shader should fail validation").to_string();
+        assert!(error.contains("Plasma fragment"), "Error should name the shader: {}", error);
+        assert_eq!(results.iter().filter(|(_, result)| result.is_err()).count(), 1);
+    }
+
+    #[test]
+    fn test_mixed_shader_reads_every_weight() {
+        let registry = ShaderRegistry::new();
+        let metadata = registry.get(ShaderType::Mixed).unwrap();
+        for field in ["plasma_weight", "kaleidoscope_weight", "tunnel_weight", "particle_weight", "fractal_weight", "spectralizer_weight"] {
+            assert!(metadata.fragment_source.contains(&format!("uniforms.{}", field)), "Mixed shader ignores {}", field);
+        }
+    }
+
+    #[test]
+    fn test_spectralizer_log_scale_uniform() {
+        let defaults = UniversalUniforms::default();
+        assert_eq!(defaults.spectralizer_log_scale, 1.0);
+
+        // Appended as plain f32s (followed by the pulse and HPSS pairs, the fractal block, the perspective scale, the stereo split, the color temperature and the beat position) so the Pod layout stays tightly packed
+        let words: &[f32] = bytemuck::cast_slice(std::slice::from_ref(&defaults));
+        assert_eq!(std::mem::size_of::<UniversalUniforms>(), std::mem::size_of_val(words));
+        assert_eq!(words[words.len() - 14], defaults.spectralizer_log_scale);
+        assert_eq!(words[words.len() - 13..words.len() - 11], [defaults.pulse_scale, defaults.pulse_offset]);
+        assert_eq!((defaults.pulse_scale, defaults.pulse_offset), (1.0, 0.0));
+        assert_eq!(words[words.len() - 11..words.len() - 9], [defaults.harmonic_energy, defaults.percussive_energy]);
+        assert_eq!(words[words.len() - 9..words.len() - 5], [defaults.fractal_mode, defaults.fractal_c_real, defaults.fractal_c_imag, defaults.fractal_max_iterations]);
+        assert_eq!(words[words.len() - 5..words.len() - 3], [defaults.projection_scale_x, defaults.projection_scale_y]);
+        assert_eq!(words[words.len() - 3], defaults.spectralizer_stereo_split);
+        assert_eq!(words[words.len() - 2], defaults.color_temperature);
+        assert_eq!(words[words.len() - 1], defaults.beat_position);
+
+        let mut manager = UniformManager::new();
+        assert!(manager.spectralizer_log_scale());
+        let uniforms = manager.map_audio_data(&AudioFeatures::new(), &RhythmFeatures::new(), (800, 600), None, 1.0);
+        assert_eq!(uniforms.spectralizer_log_scale, 1.0);
+
+        manager.set_spectralizer_log_scale(false);
+        let uniforms = manager.map_audio_data(&AudioFeatures::new(), &RhythmFeatures::new(), (800, 600), None, 1.0);
+        assert_eq!(uniforms.spectralizer_log_scale, 0.0);
+    }
+
+    #[test]
+    fn test_julia_c_stays_in_main_cardioid() {
+        // Inverting c = mu/2 - mu^2/4 gives |mu| = |1 - sqrt(1 - 4c)|, below 1 inside the cardioid
+        let mu_radius = |(re, im): (f32, f32)| {
+            let (w_re, w_im) = (1.0 - 4.0 * re, -4.0 * im);
+            let w_len = (w_re * w_re + w_im * w_im).sqrt();
+            let sqrt_re = ((w_len + w_re) / 2.0).sqrt();
+            let sqrt_im = ((w_len - w_re) / 2.0).sqrt().copysign(w_im);
+            ((1.0 - sqrt_re).powi(2) + sqrt_im.powi(2)).sqrt()
+        };
+
+        for centroid in [0.0, 50.0, 100.0, 440.0, 1000.0, 3000.0, 8000.0, 20000.0, f32::NAN] {
+            for confidence in [0.0, 0.5, 1.0, 2.0, -1.0, f32::INFINITY] {
+                let c = julia_c(centroid, confidence);
+                assert!(c.0.is_finite() && c.1.is_finite());
+                assert!((c.0 * c.0 + c.1 * c.1).sqrt() <= 0.75, "{:?} outside |c| <= 0.75", c);
+                let radius = mu_radius(c);
+                assert!((0.79..0.99).contains(&radius), "{:?} from ({}, {}) has |mu| = {}", c, centroid, confidence, radius);
+            }
+        }
+
+        // Confident pitch moves toward the detailed edge; the centroid moves around it
+        assert!(mu_radius(julia_c(1000.0, 1.0)) > mu_radius(julia_c(1000.0, 0.0)) + 0.1);
+        assert_ne!(julia_c(500.0, 0.5), julia_c(2000.0, 0.5));
+        assert_eq!(julia_c(0.0, 0.0), (UniversalUniforms::default().fractal_c_real, UniversalUniforms::default().fractal_c_imag));
+    }
+
+    #[test]
+    fn test_stereo_split_uniform_and_upload() {
+        let mut manager = UniformManager::new();
+        assert!(!manager.spectralizer_stereo_split());
+        let uniforms = manager.map_audio_data(&AudioFeatures::new(), &RhythmFeatures::new(), (800, 600), None, 1.0);
+        assert_eq!(uniforms.spectralizer_stereo_split, 0.0);
+
+        manager.set_spectralizer_stereo_split(true);
+        let uniforms = manager.map_audio_data(&AudioFeatures::new(), &RhythmFeatures::new(), (800, 600), None, 1.0);
+        assert_eq!(uniforms.spectralizer_stereo_split, 1.0);
+
+        // Left rows come first; a left-only spectrum leaves the right half dark
+        let mut left = vec![0.0; 512];
+        left[40] = 256.0;
+        let spectrum = fit_stereo_spectrum(&left, &[0.0; 512]);
+        assert!(spectrum[..STEREO_SPECTRUM_ROWS].iter().any(|&v| v > 0.99));
+        assert!(spectrum[STEREO_SPECTRUM_ROWS..].iter().all(|&v| v == 0.0));
+
+        let registry = ShaderRegistry::new();
+        let source = registry.get(ShaderType::Spectralizer).unwrap().fragment_source;
+        assert!(source.contains("uniforms.spectralizer_stereo_split"));
+        assert!(source.contains("@binding(4)"));
+    }
+
+    #[test]
+    fn test_fractal_mode_uniform() {
+        let mut manager = UniformManager::new();
+        let mut features = AudioFeatures::new();
+        features.spectral_centroid = 1500.0;
+        features.pitch_confidence = 0.9;
+
+        let uniforms = manager.map_audio_data(&features, &RhythmFeatures::new(), (800, 600), None, 1.0);
+        assert_eq!(uniforms.fractal_mode, 0.0);
+        assert_eq!((uniforms.fractal_c_real, uniforms.fractal_c_imag), julia_c(1500.0, 0.9));
+        assert_eq!(uniforms.fractal_max_iterations, 64.0);
+
+        manager.set_fractal_mode(FractalMode::Julia);
+        let uniforms = manager.map_audio_data(&features, &RhythmFeatures::new(), (800, 600), None, 1.0);
+        assert_eq!(uniforms.fractal_mode, 1.0);
+
+        let registry = ShaderRegistry::new();
+        let source = registry.get(ShaderType::Fractal).unwrap().fragment_source;
+        assert!(source.contains("uniforms.fractal_c_real") && source.contains("uniforms.fractal_max_iterations"));
+    }
+
+    #[test]
+    fn test_projection_follows_requires_3d() {
+        let registry = ShaderRegistry::new();
+        let mut manager = UniformManager::new();
+        let map = |manager: &mut UniformManager| manager.map_audio_data(&AudioFeatures::new(), &RhythmFeatures::new(), (1920, 1080), None, 1.0);
+        assert_eq!(map(&mut manager).projection_mode, 0.0);
+
+        manager.apply_shader_projection(registry.get(ShaderType::Tunnel).unwrap());
+        assert!(manager.projection_3d());
+        let uniforms = map(&mut manager);
+        assert_eq!(uniforms.projection_mode, 1.0);
+        let tan_half = (PERSPECTIVE_FOV_Y_DEGREES.to_radians() / 2.0).tan();
+        assert!((uniforms.projection_scale_y - tan_half).abs() < 1e-6);
+        assert!((uniforms.projection_scale_x / uniforms.projection_scale_y - 1920.0 / 1080.0).abs() < 1e-5);
+
+        manager.apply_shader_projection(registry.get(ShaderType::Classic).unwrap());
+        assert_eq!(map(&mut manager).projection_mode, 0.0);
+
+        // Only the Tunnel reads the perspective path
+        for shader in ShaderType::all() {
+            let metadata = registry.get(*shader).unwrap();
+            assert_eq!(metadata.fragment_source.contains("uniforms.projection_scale_x"), metadata.requires_3d, "{:?}", shader);
+        }
+
+        let defaults = UniversalUniforms::default();
+        let (scale_x, scale_y) = perspective_ray_scale(PERSPECTIVE_FOV_Y_DEGREES, (1200, 800));
+        assert!((defaults.projection_scale_x - scale_x).abs() < 1e-6 && (defaults.projection_scale_y - scale_y).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_shader_default_palette_applied_when_unlocked() {
+        let registry = ShaderRegistry::new();
+        let mut manager = UniformManager::new();
+        let audio_features = AudioFeatures::new();
+        let rhythm_features = RhythmFeatures::new();
+
+        manager.apply_shader_defaults(registry.get(ShaderType::Fractal).unwrap());
+        assert_eq!(manager.palette_manager().current_palette(), ColorPalette::Violet);
+
+        let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        assert_eq!(uniforms.palette_index, ColorPalette::Violet.as_index());
+        assert_eq!(uniforms.palette_base_hue, ColorPalette::Violet.base_hue());
+
+        manager.apply_shader_defaults(registry.get(ShaderType::Plasma).unwrap());
+        assert_eq!(manager.palette_manager().current_palette(), ColorPalette::Orange);
+
+        manager.apply_shader_defaults(registry.get(ShaderType::Spectralizer).unwrap());
+        assert_eq!(manager.palette_manager().current_palette(), ColorPalette::Rainbow);
+        assert_eq!(manager.saturation(), 1.0);
+    }
+
+    #[test]
+    fn test_shader_default_palette_ignored_when_locked() {
+        let registry = ShaderRegistry::new();
+        let mut manager = UniformManager::new();
+
+        manager.palette_manager_mut().lock_palette(ColorPalette::Green, 0.0);
+        manager.apply_shader_defaults(registry.get(ShaderType::Fractal).unwrap());
+
+        assert_eq!(manager.palette_manager().current_palette(), ColorPalette::Green);
+    }
+
+    #[test]
+    fn test_every_shader_has_recommendations() {
+        let registry = ShaderRegistry::new();
+        for &shader in ShaderType::all() {
+            let metadata = registry.get(shader).expect("Every shader type should be registered");
+            assert!(ColorPalette::all_palettes().contains(&metadata.recommended_palette), "{:?}", shader);
+            assert!((metadata.recommended_weights.sum() - 1.0).abs() < 1e-5, "{:?}", shader);
+        }
+    }
+
+    #[test]
+    fn test_shader_recommendations_apply_on_switch_when_enabled() {
+        let registry = ShaderRegistry::new();
+        let mut manager = UniformManager::new();
+        assert!(manager.shader_recommendations());
+
+        manager.apply_shader_defaults(registry.get(ShaderType::Mixed).unwrap());
+        assert_eq!(manager.palette_manager().current_palette(), ColorPalette::Rainbow);
+        assert_eq!(manager.effect_weights(), EffectWeights::MIXED_RECOMMENDED.normalized());
+        manager.apply_shader_defaults(registry.get(ShaderType::Tunnel).unwrap());
+        assert_eq!(manager.palette_manager().current_palette(), ColorPalette::Blue);
+        assert_eq!(manager.effect_weights(), EffectWeights::default().normalized());
+
+        // Hand-set weights stay put; the palette still follows the shader
+        let custom = EffectWeights::from_array([1.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        manager.set_effect_weights(custom);
+        manager.apply_shader_defaults(registry.get(ShaderType::Mixed).unwrap());
+        assert_eq!(manager.effect_weights(), custom);
+        assert_eq!(manager.palette_manager().current_palette(), ColorPalette::Rainbow);
+
+        // Turned off, switching keeps both
+        manager.set_shader_recommendations(false);
+        manager.apply_shader_defaults(registry.get(ShaderType::Fractal).unwrap());
+        assert_eq!(manager.palette_manager().current_palette(), ColorPalette::Rainbow);
+        assert_eq!(manager.effect_weights(), custom);
+    }
+
+    #[test]
+    fn test_continuous_palette_position_uniforms() {
+        let mut manager = UniformManager::new();
+        let audio_features = AudioFeatures::new();
+        let rhythm_features = RhythmFeatures::new();
+
+        manager.palette_manager_mut().set_continuous_position(1.5);
+        let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        assert_eq!(uniforms.palette_index, ColorPalette::Orange.as_index());
+        assert_eq!(uniforms.prev_palette_index, ColorPalette::Red.as_index());
+        assert!((uniforms.transition_blend - 0.5).abs() < 1e-6);
+    }
+
     // ===== SHADER SWITCHING VALIDATION TESTS =====
 
+    #[test]
+    fn test_custom_palette_uniforms() {
+        let mut manager = UniformManager::new();
+        let audio_features = AudioFeatures::new();
+        let rhythm_features = RhythmFeatures::new();
+
+        manager.palette_manager_mut().set_custom_palette(vec![
+            (0.0, [0.0, 0.0, 1.0]),
+            (0.5, [0.0, 1.0, 1.0]),
+            (1.0, [0.0, 1.0, 0.0]),
+        ]).unwrap();
+        manager.palette_manager_mut().lock_palette(ColorPalette::Custom, 0.0);
+
+        let uniforms = manager.map_audio_data(&audio_features, &rhythm_features, (800, 600), None, 1.0);
+        assert_eq!(uniforms.palette_index, 8.0);
+        assert_eq!(uniforms.custom_palette_stops, 3.0);
+        // Hue-only shaders center on cyan, spanning blue to green
+        assert!((uniforms.palette_base_hue - 0.5).abs() < 1e-3);
+        assert!((uniforms.palette_hue_range - 1.0 / 3.0).abs() < 1e-3);
+    }
+
     #[test]
     fn test_shader_registry_completeness() {
         let registry = ShaderRegistry::new();
@@ -1022,9 +2516,44 @@ mod tests {
         assert_eq!(available.len(), all_shader_types.len());
     }
 
+    #[test]
+    fn test_oscilloscope_registered() {
+        let registry = ShaderRegistry::new();
+        assert!(registry.is_available(ShaderType::Oscilloscope));
+        assert!(registry.available_shaders().contains(&ShaderType::Oscilloscope));
+
+        // Cheap to draw, and it reads the waveform storage buffer
+        let metadata = registry.get(ShaderType::Oscilloscope).unwrap();
+        assert!(metadata.performance_cost <= 3);
+        assert!(metadata.fragment_source.contains("@binding(1)"));
+    }
+
+    #[test]
+    fn test_spectrogram_registered() {
+        let registry = ShaderRegistry::new();
+        let metadata = registry.get(ShaderType::Spectrogram).unwrap();
+        assert!(metadata.fragment_source.contains("@binding(2)"));
+        assert!(metadata.fragment_source.contains("spectrogram_head"));
+    }
+
+    #[test]
+    fn test_fit_waveform_keeps_newest_samples() {
+        // Short history is right-aligned with silence before it
+        let short = fit_waveform(&[0.5, -0.5]);
+        assert_eq!(short[WAVEFORM_SAMPLES - 2..], [0.5, -0.5]);
+        assert!(short[..WAVEFORM_SAMPLES - 2].iter().all(|&s| s == 0.0));
+
+        // Long history drops the oldest samples
+        let long: Vec<f32> = (0..WAVEFORM_SAMPLES + 10).map(|i| i as f32).collect();
+        let fitted = fit_waveform(&long);
+        assert_eq!(fitted[0], 10.0);
+        assert_eq!(fitted[WAVEFORM_SAMPLES - 1], (WAVEFORM_SAMPLES + 9) as f32);
+    }
+
     #[test]
     fn test_shader_transitioner_basic_operations() {
-        let mut transitioner = ShaderTransitioner::new(ShaderType::Classic);
+        let clock = MockClock::new();
+        let mut transitioner = ShaderTransitioner::with_clock(ShaderType::Classic, clock.shared());
 
         // Initial state
         assert_eq!(transitioner.current_shader(), ShaderType::Classic);
@@ -1034,8 +2563,8 @@ mod tests {
         transitioner.transition_to(ShaderType::Plasma);
         assert!(transitioner.is_transitioning());
 
-        // Complete transition (wait for real time to pass)
-        std::thread::sleep(std::time::Duration::from_millis(2100));
+        // Complete transition
+        clock.advance_secs(2.1);
         transitioner.update();
         assert!(!transitioner.is_transitioning());
         assert_eq!(transitioner.current_shader(), ShaderType::Plasma);
@@ -1043,25 +2572,91 @@ mod tests {
 
     #[test]
     fn test_shader_transition_progress() {
-        let mut transitioner = ShaderTransitioner::new(ShaderType::Classic);
+        let clock = MockClock::new();
+        let mut transitioner = ShaderTransitioner::with_clock(ShaderType::Classic, clock.shared());
         transitioner.transition_to(ShaderType::Fractal);
 
         // Transition should start at 0.0 and progress to 1.0
         let initial_progress = transitioner.transition_progress();
         assert_eq!(initial_progress, 0.0);
 
-        // After some time, progress should increase
-        std::thread::sleep(std::time::Duration::from_millis(500));
+        // A quarter of the 2 second transition
+        clock.advance_secs(0.5);
         transitioner.update();
         let mid_progress = transitioner.transition_progress();
-        assert!(mid_progress > 0.0 && mid_progress < 1.0);
+        assert!((mid_progress - 0.25).abs() < 1e-4);
 
         // Complete transition
-        std::thread::sleep(std::time::Duration::from_millis(1700));
+        clock.advance_secs(1.7);
         transitioner.update();
         assert!(!transitioner.is_transitioning());
     }
 
+    #[test]
+    fn test_shader_transition_completes_with_mock_clock() {
+        let clock = MockClock::new();
+        let mut transitioner = ShaderTransitioner::with_clock(ShaderType::Classic, clock.shared());
+        transitioner.transition_to(ShaderType::Tunnel);
+
+        // Step in 60fps frames; no real time passes
+        let mut frames = 0;
+        while transitioner.is_transitioning() {
+            clock.advance_secs(1.0 / 60.0);
+            transitioner.update();
+            frames += 1;
+            assert!(frames <= 121, "transition should finish within ~2 seconds of frames");
+        }
+
+        assert!(frames >= 119);
+        assert_eq!(transitioner.current_shader(), ShaderType::Tunnel);
+        assert_eq!(transitioner.transition_progress(), 1.0);
+    }
+
+    #[test]
+    fn test_transition_easing_curves() {
+        let clock = MockClock::new();
+        let mut transitioner = ShaderTransitioner::with_clock(ShaderType::Classic, clock.shared());
+        transitioner.set_easing(EasingCurve::Smoothstep);
+        transitioner.transition_to(ShaderType::Plasma);
+
+        // Halfway through the 2 second transition
+        clock.advance_secs(1.0);
+        transitioner.update();
+        assert!((transitioner.raw_progress() - 0.5).abs() < 1e-4);
+        assert!((transitioner.transition_progress() - 0.5).abs() < 1e-4);
+
+        // Same timing, slower start
+        transitioner.set_easing(EasingCurve::EaseIn);
+        assert!((transitioner.raw_progress() - 0.5).abs() < 1e-4);
+        assert!(transitioner.transition_progress() < 0.5);
+
+        clock.advance_secs(1.1);
+        transitioner.update();
+        assert!(!transitioner.is_transitioning());
+        assert_eq!(transitioner.transition_progress(), 1.0);
+        transitioner.set_easing(EasingCurve::Smoothstep);
+        assert_eq!(transitioner.transition_progress(), 1.0);
+
+        for curve in [EasingCurve::Linear, EasingCurve::EaseInOut, EasingCurve::EaseIn, EasingCurve::EaseOut, EasingCurve::Smoothstep] {
+            assert_eq!(curve.apply(0.0), 0.0, "{}", curve.name());
+            assert_eq!(curve.apply(1.0), 1.0, "{}", curve.name());
+        }
+    }
+
+    #[test]
+    fn test_immediate_switch_snaps_eased_progress() {
+        let clock = MockClock::new();
+        let mut transitioner = ShaderTransitioner::with_clock(ShaderType::Classic, clock.shared());
+        transitioner.set_easing(EasingCurve::EaseIn);
+        transitioner.transition_to(ShaderType::Tunnel);
+        clock.advance_secs(0.5);
+        transitioner.update();
+
+        transitioner.switch_immediately_to(ShaderType::Fractal);
+        assert_eq!(transitioner.current_shader(), ShaderType::Fractal);
+        assert_eq!(transitioner.transition_progress(), 1.0);
+    }
+
     #[test]
     fn test_shader_type_properties() {
         // Test all shader types have names and descriptions
@@ -1096,7 +2691,8 @@ mod tests {
 
     #[test]
     fn test_shader_switching_sequence() {
-        let mut transitioner = ShaderTransitioner::new(ShaderType::Classic);
+        let clock = MockClock::new();
+        let mut transitioner = ShaderTransitioner::with_clock(ShaderType::Classic, clock.shared());
 
         let test_sequence = [
             ShaderType::Classic,
@@ -1110,7 +2706,7 @@ mod tests {
             transitioner.transition_to(target_shader);
 
             // Complete transition
-            std::thread::sleep(std::time::Duration::from_millis(2100));
+            clock.advance_secs(2.1);
             transitioner.update();
 
             assert_eq!(transitioner.current_shader(), target_shader);
@@ -1213,7 +2809,8 @@ mod tests {
 
     #[test]
     fn test_shader_transition_interruption() {
-        let mut transitioner = ShaderTransitioner::new(ShaderType::Classic);
+        let clock = MockClock::new();
+        let mut transitioner = ShaderTransitioner::with_clock(ShaderType::Classic, clock.shared());
 
         // Start first transition
         transitioner.transition_to(ShaderType::Plasma);
@@ -1224,7 +2821,7 @@ mod tests {
         assert!(transitioner.is_transitioning());
 
         // Complete the interrupted transition
-        std::thread::sleep(std::time::Duration::from_millis(2100));
+        clock.advance_secs(2.1);
         transitioner.update();
 
         // Should end up at the final target
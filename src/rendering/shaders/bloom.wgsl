@@ -0,0 +1,63 @@
+// Bloom shader - separable Gaussian blur of the bright parts of the scene, added back on top
+
+struct BloomUniforms {
+    texel_step: vec2<f32>, // One source texel along the blur direction (zero for the combine pass)
+    intensity: f32,        // Strength of the blurred glow in the combine pass
+    threshold: f32,        // Brightness kept by the first blur pass (0.0 = blur everything)
+}
+
+@group(0) @binding(0)
+var<uniform> bloom: BloomUniforms;
+@group(0) @binding(1)
+var scene_frame: texture_2d<f32>;
+@group(0) @binding(2)
+var bloom_frame: texture_2d<f32>;
+@group(0) @binding(3)
+var frame_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+}
+
+// Full-screen triangle generated from the vertex index (no vertex buffer needed)
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.tex_coords = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+fn bright_part(color: vec3<f32>) -> vec3<f32> {
+    if (bloom.threshold <= 0.0) {
+        return color;
+    }
+    let luma = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    let keep = smoothstep(bloom.threshold, bloom.threshold + 0.1, luma);
+    return color * keep;
+}
+
+// One direction of a 9-tap Gaussian (sigma ~2 texels), sampled from scene_frame
+@fragment
+fn fs_blur(in: VertexOutput) -> @location(0) vec4<f32> {
+    var weights = array<f32, 5>(0.2270270, 0.1945946, 0.1216216, 0.0540541, 0.0162162);
+
+    var sum = bright_part(textureSample(scene_frame, frame_sampler, in.tex_coords).rgb) * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = bloom.texel_step * f32(i);
+        sum += bright_part(textureSample(scene_frame, frame_sampler, in.tex_coords + offset).rgb) * weights[i];
+        sum += bright_part(textureSample(scene_frame, frame_sampler, in.tex_coords - offset).rgb) * weights[i];
+    }
+    return vec4<f32>(sum, 1.0);
+}
+
+// Additive combine of the scene and its blurred highlights
+@fragment
+fn fs_combine(in: VertexOutput) -> @location(0) vec4<f32> {
+    let scene = textureSample(scene_frame, frame_sampler, in.tex_coords).rgb;
+    let glow = textureSample(bloom_frame, frame_sampler, in.tex_coords).rgb;
+    return vec4<f32>(scene + glow * bloom.intensity, 1.0);
+}
@@ -44,6 +44,7 @@ struct UniversalUniforms {
     prev_palette_index: f32,
     prev_palette_base_hue: f32,
     prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
 
     // Effect weights
     plasma_weight: f32,
@@ -53,6 +54,12 @@ struct UniversalUniforms {
     fractal_weight: f32,
     spectralizer_weight: f32,
 
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
     // System parameters
     projection_mode: f32,
     smoothing_factor: f32,
@@ -83,9 +90,54 @@ struct UniversalUniforms {
     ui_current_shader_index: f32,
     ui_fps: f32,
     ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
     screen_width: f32,
     screen_height: f32,
     text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
 }
 
 @group(0) @binding(0)
@@ -103,15 +155,30 @@ fn hsv_to_rgb(hsv: vec3<f32>) -> vec3<f32> {
     return ((rgb - 1.0) * hsv.y + 1.0) * hsv.z;
 }
 
+// Polar coordinates of a centered UV: (radius, angle in radians)
+fn to_polar(uv: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(length(uv), atan2(uv.y, uv.x));
+}
+
+// Fade a radial wave of the given angular frequency (radians per UV unit) out before
+// it reaches two pixels per cycle, so high wave counts shimmer instead of aliasing
+fn radial_aa(radial_frequency: f32) -> f32 {
+    let cycles_per_pixel = radial_frequency * uniforms.aa_width / 6.28318;
+    return 1.0 - smoothstep(0.25, 0.5, cycles_per_pixel);
+}
+
 fn noise(p: vec2<f32>) -> f32 {
     return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
 }
 
 @fragment
 fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
-    let uv = in.tex_coords * 2.0 - 1.0;
-    let distance_from_center = length(uv);
-    let angle = atan2(uv.y, uv.x);
+    // Aspect-correct so rings stay circular on wide (e.g. fullscreen) surfaces
+    let resolution = vec2<f32>(uniforms.resolution_x, uniforms.resolution_y);
+    let uv = (in.tex_coords * 2.0 - 1.0) * vec2<f32>(resolution.x / resolution.y, 1.0);
+    let polar = to_polar(uv);
+    let distance_from_center = polar.x;
+    let angle = polar.y;
 
     let frequency_bands = vec3<f32>(
         uniforms.bass,
@@ -123,13 +190,15 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
     let safe_volume_factor = uniforms.overall_volume * uniforms.safety_pattern_complexity;
     let time_scaled = uniforms.time * (1.0 + safe_volume_factor * 0.3); // Reduced from 0.5 to 0.3
 
-    // Enhanced wave patterns with radial distortion
-    let radial_freq = distance_from_center * 12.0 * uniforms.frequency_scale;
+    // Enhanced wave patterns with radial distortion; ring count tracks mid/treble, speed tracks BPM
+    let wave_number = uniforms.classic_wave_count * uniforms.frequency_scale;
+    let radial_freq = distance_from_center * wave_number;
     let angular_freq = angle * 4.0 + time_scaled * 0.5;
+    let wave_time = time_scaled * uniforms.classic_radial_speed;
 
-    let bass_wave = sin(radial_freq + time_scaled) * frequency_bands.x * 0.8;
-    let mid_wave = sin(radial_freq * 2.0 + angular_freq + time_scaled * 1.3) * frequency_bands.y * 0.6;
-    let treble_wave = sin(radial_freq * 4.0 + angular_freq * 2.0 + time_scaled * 2.1) * frequency_bands.z * 0.4;
+    let bass_wave = sin(radial_freq + wave_time) * frequency_bands.x * 0.8 * radial_aa(wave_number);
+    let mid_wave = sin(radial_freq * 2.0 + angular_freq + wave_time * 1.3) * frequency_bands.y * 0.6 * radial_aa(wave_number * 2.0);
+    let treble_wave = sin(radial_freq * 4.0 + angular_freq * 2.0 + wave_time * 2.1) * frequency_bands.z * 0.4 * radial_aa(wave_number * 4.0);
 
     // Add noise texture for high-frequency detail
     let noise_coord = uv * 20.0 + vec2<f32>(time_scaled * 0.1);
@@ -200,5 +269,7 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
     // Ensure color values stay in valid range
     final_color = clamp(final_color, vec3<f32>(0.0), vec3<f32>(1.0));
 
-    return vec4<f32>(final_color, 1.0);
+    // Installation white balance, applied after all shading
+    let white_balance = vec3<f32>(uniforms.white_balance_r, uniforms.white_balance_g, uniforms.white_balance_b);
+    return vec4<f32>(final_color * white_balance, 1.0);
 }
\ No newline at end of file
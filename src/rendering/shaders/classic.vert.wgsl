@@ -3,6 +3,146 @@ struct VertexInput {
     @location(1) tex_coords: vec2<f32>,
 }
 
+struct UniversalUniforms {
+    // 5-band frequency analysis
+    sub_bass: f32,
+    bass: f32,
+    mid: f32,
+    treble: f32,
+    presence: f32,
+
+    // Volume and dynamics
+    overall_volume: f32,
+    signal_level_db: f32,
+    peak_level_db: f32,
+    dynamic_range: f32,
+
+    // Enhanced rhythm analysis
+    beat_strength: f32,
+    estimated_bpm: f32,
+    tempo_confidence: f32,
+    onset_detected: f32,
+    downbeat_detected: f32,
+
+    // Spectral characteristics
+    spectral_centroid: f32,
+    spectral_rolloff: f32,
+    spectral_flux: f32,
+    pitch_confidence: f32,
+    zero_crossing_rate: f32,
+    onset_strength: f32,
+
+    // Visual controls
+    time: f32,
+    color_intensity: f32,
+    frequency_scale: f32,
+    saturation: f32,
+    palette_index: f32,
+    palette_base_hue: f32,
+    palette_hue_range: f32,
+    transition_blend: f32,
+    prev_palette_index: f32,
+    prev_palette_base_hue: f32,
+    prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
+
+    // Effect weights
+    plasma_weight: f32,
+    kaleidoscope_weight: f32,
+    tunnel_weight: f32,
+    particle_weight: f32,
+    fractal_weight: f32,
+    spectralizer_weight: f32,
+
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
+    // System parameters
+    projection_mode: f32,
+    smoothing_factor: f32,
+
+    // Resolution
+    resolution_x: f32,
+    resolution_y: f32,
+
+    // Safety multipliers for epilepsy prevention
+    safety_beat_intensity: f32,
+    safety_onset_intensity: f32,
+    safety_color_change_rate: f32,
+    safety_brightness_range: f32,
+    safety_pattern_complexity: f32,
+    safety_emergency_stop: f32,
+
+    // Overlay system uniforms
+    mouse_x: f32,
+    mouse_y: f32,
+    mouse_pressed: f32,
+    show_debug_overlay: f32,
+    show_control_panel: f32,
+    ui_volume: f32,
+    ui_is_playing: f32,
+    ui_safety_level: f32,
+    ui_quality_level: f32,
+    ui_auto_shader: f32,
+    ui_current_shader_index: f32,
+    ui_fps: f32,
+    ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
+    screen_width: f32,
+    screen_height: f32,
+    text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: UniversalUniforms;
+
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) tex_coords: vec2<f32>,
@@ -12,7 +152,15 @@ struct VertexOutput {
 @vertex
 fn vs_main(model: VertexInput) -> VertexOutput {
     var out: VertexOutput;
-    out.tex_coords = model.tex_coords;
+
+    // Onset pulse: shrinking texture coordinates about the center enlarges the image,
+    // and the kick lifts it slightly (+v is down the screen)
+    let pulse_scale = max(uniforms.pulse_scale, 1.0);
+    let pulsed = (model.tex_coords - vec2<f32>(0.5)) / pulse_scale + vec2<f32>(0.5, 0.5 + uniforms.pulse_offset);
+
+    // Bass-driven screen shake: the same offset for every vertex, so the whole image moves
+    let shake_direction = vec2<f32>(sin(uniforms.time * 37.0), cos(uniforms.time * 29.0));
+    out.tex_coords = pulsed + shake_direction * uniforms.screen_shake;
     out.clip_position = vec4<f32>(model.position, 1.0);
     out.world_position = model.position;
     return out;
@@ -0,0 +1,56 @@
+// Radix-2 Stockham FFT: one butterfly per invocation, log2(n) passes ping-ponging between
+// two buffers; the autosort ordering leaves the result in natural order without a bit-reversal pass
+
+struct FftParams {
+    n: u32,      // Transform size (power of two)
+    stride: u32, // Length of the sub-transforms combined by this pass (1, 2, 4, ... n/2)
+    _padding0: u32,
+    _padding1: u32,
+}
+
+@group(0) @binding(0)
+var<uniform> params: FftParams;
+
+@group(0) @binding(1)
+var<storage, read> input_data: array<vec2<f32>>;
+
+@group(0) @binding(2)
+var<storage, read_write> output_data: array<vec2<f32>>;
+
+@group(0) @binding(3)
+var<storage, read_write> magnitudes: array<f32>;
+
+const PI: f32 = 3.14159265358979;
+
+fn complex_mul(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+}
+
+@compute @workgroup_size(64)
+fn butterfly(@builtin(global_invocation_id) id: vec3<u32>) {
+    let half_n = params.n / 2u;
+    let j = id.x;
+    if j >= half_n {
+        return;
+    }
+
+    // Twiddle for position k within the current sub-transform
+    let k = j % params.stride;
+    let angle = -PI * f32(k) / f32(params.stride);
+    let twiddle = vec2<f32>(cos(angle), sin(angle));
+
+    let a = input_data[j];
+    let b = complex_mul(input_data[j + half_n], twiddle);
+
+    let out_index = (j / params.stride) * params.stride * 2u + k;
+    output_data[out_index] = a + b;
+    output_data[out_index + params.stride] = a - b;
+}
+
+@compute @workgroup_size(64)
+fn magnitude(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.n / 2u {
+        return;
+    }
+    magnitudes[id.x] = length(input_data[id.x]);
+}
@@ -44,6 +44,7 @@ struct UniversalUniforms {
     prev_palette_index: f32,
     prev_palette_base_hue: f32,
     prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
 
     // Effect weights
     plasma_weight: f32,
@@ -53,6 +54,12 @@ struct UniversalUniforms {
     fractal_weight: f32,
     spectralizer_weight: f32,
 
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
     // System parameters
     projection_mode: f32,
     smoothing_factor: f32,
@@ -83,9 +90,54 @@ struct UniversalUniforms {
     ui_current_shader_index: f32,
     ui_fps: f32,
     ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
     screen_width: f32,
     screen_height: f32,
     text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
 }
 
 @group(0) @binding(0)
@@ -116,6 +168,11 @@ fn complex_magnitude_sq(z: vec2<f32>) -> f32 {
     return z.x * z.x + z.y * z.y;
 }
 
+// Iteration limit for the current render quality
+fn iteration_cap() -> i32 {
+    return max(i32(uniforms.fractal_max_iterations), 8);
+}
+
 // Audio-reactive Mandelbrot fractal
 fn mandelbrot_fractal(uv: vec2<f32>) -> f32 {
     // Audio-reactive zoom and translation
@@ -133,7 +190,7 @@ fn mandelbrot_fractal(uv: vec2<f32>) -> f32 {
     let c = (uv - spectral_offset) / bass_zoom + vec2<f32>(beat_offset, 0.0);
 
     var z = vec2<f32>(0.0, 0.0);
-    let max_iterations = 64;
+    let max_iterations = min(64, iteration_cap());
     var iteration = 0.0;
 
     // Dynamic iteration count based on dynamic range
@@ -192,7 +249,7 @@ fn julia_fractal(uv: vec2<f32>) -> f32 {
     let zoom = 1.5 + uniforms.mid * 1.0;
     var z = uv / zoom;
 
-    let max_iterations = 48;
+    let max_iterations = min(48, iteration_cap());
     var iteration = 0.0;
 
     for (var i = 0; i < max_iterations; i = i + 1) {
@@ -214,13 +271,42 @@ fn julia_fractal(uv: vec2<f32>) -> f32 {
     return iteration / f32(max_iterations);
 }
 
+// Julia set whose constant follows pitch (fractal_c_*, inside the main cardioid)
+fn pitch_julia_fractal(uv: vec2<f32>) -> f32 {
+    let c = vec2<f32>(uniforms.fractal_c_real, uniforms.fractal_c_imag);
+
+    // Slow rotation keeps the set moving through sustained notes
+    let angle = uniforms.time * 0.05;
+    let rotated = vec2<f32>(uv.x * cos(angle) - uv.y * sin(angle), uv.x * sin(angle) + uv.y * cos(angle));
+    let zoom = 1.2 + uniforms.bass * 0.3 * uniforms.safety_pattern_complexity;
+    var z = rotated / zoom;
+
+    let max_iterations = iteration_cap();
+    var iteration = 0.0;
+
+    for (var i = 0; i < max_iterations; i = i + 1) {
+        if (complex_magnitude_sq(z) > 4.0) {
+            break;
+        }
+        z = complex_square(z) + c;
+        iteration += 1.0;
+    }
+
+    // Smooth escape count; points that never escape sit at 1.0
+    if (complex_magnitude_sq(z) <= 4.0) {
+        return 1.0;
+    }
+    let log_zn = log(complex_magnitude_sq(z)) * 0.5;
+    return clamp((iteration + 1.0 - log(log_zn) / log(2.0)) / f32(max_iterations), 0.0, 1.0);
+}
+
 // Burning ship fractal for variety
 fn burning_ship_fractal(uv: vec2<f32>) -> f32 {
     let zoom = 0.8 + uniforms.presence * 1.5;
     let c = (uv + vec2<f32>(-1.8, -0.1)) / zoom;
 
     var z = vec2<f32>(0.0, 0.0);
-    let max_iterations = 32;
+    let max_iterations = min(32, iteration_cap());
     var iteration = 0.0;
 
     for (var i = 0; i < max_iterations; i = i + 1) {
@@ -240,6 +326,10 @@ fn burning_ship_fractal(uv: vec2<f32>) -> f32 {
 
 // Generate combined fractal pattern
 fn generate_fractal_pattern(uv: vec2<f32>) -> f32 {
+    if (uniforms.fractal_mode > 0.5) {
+        return pitch_julia_fractal(uv);
+    }
+
     // Frequency bands determine fractal type blending
     let mandelbrot_weight = uniforms.bass + uniforms.sub_bass;
     let julia_weight = uniforms.mid + uniforms.treble;
@@ -279,7 +369,12 @@ fn get_fractal_color(pattern: f32, uv: vec2<f32>) -> vec3<f32> {
     let treble_hue_shift = uniforms.treble * -0.15; // Cool shift
     let mid_saturation = uniforms.mid * 0.4;
 
-    let final_hue = pattern_hue + bass_hue_shift + treble_hue_shift;
+    var final_hue = fract(pattern_hue + bass_hue_shift + treble_hue_shift);
+
+    // Constrain hue to the active palette (Rainbow keeps the full spectrum)
+    if uniforms.palette_index >= 0.5 {
+        final_hue = fract(uniforms.palette_base_hue + (final_hue - 0.5) * uniforms.palette_hue_range);
+    }
 
     // Dynamic saturation based on audio characteristics
     let base_saturation = uniforms.saturation * (0.6 + uniforms.pitch_confidence * 0.4);
@@ -346,5 +441,7 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
     // Ensure color values stay in valid range
     color = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
 
-    return vec4<f32>(color, 1.0);
+    // Installation white balance, applied after all shading
+    let white_balance = vec3<f32>(uniforms.white_balance_r, uniforms.white_balance_g, uniforms.white_balance_b);
+    return vec4<f32>(color * white_balance, 1.0);
 }
\ No newline at end of file
@@ -0,0 +1,51 @@
+// Idle screen - slow self-animating plasma shown while the input is silent
+
+struct IdleUniforms {
+    time: f32,       // Seconds since the composer started
+    opacity: f32,    // Fade between the live scene (0.0) and the idle pattern (1.0)
+    brightness: f32, // Safety brightness allowance
+    aspect: f32,     // Output width / height
+}
+
+@group(0) @binding(0)
+var<uniform> idle: IdleUniforms;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+}
+
+// Full-screen triangle generated from the vertex index (no vertex buffer needed)
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.tex_coords = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+fn hue_to_rgb(h: f32) -> vec3<f32> {
+    let c = vec3<f32>(abs(h * 6.0 - 3.0) - 1.0,
+                      2.0 - abs(h * 6.0 - 2.0),
+                      2.0 - abs(h * 6.0 - 4.0));
+    return clamp(c, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Classic sine plasma: smooth, low contrast, no sudden changes
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let uv = (in.tex_coords * 2.0 - 1.0) * vec2<f32>(idle.aspect, 1.0);
+    let t = idle.time * 0.15;
+
+    var field = sin(uv.x * 2.0 + t);
+    field += sin((uv.y * 2.0 + t) * 0.7);
+    field += sin((uv.x + uv.y) * 1.5 + t * 1.3);
+    field += sin(length(uv + vec2<f32>(sin(t * 0.5), cos(t * 0.4))) * 3.0);
+    field = field * 0.25;
+
+    let hue = fract(0.55 + field * 0.15 + t * 0.02); // Stays around blues and violets
+    let color = mix(vec3<f32>(0.5), hue_to_rgb(hue), 0.6) * (0.25 + 0.1 * field) * idle.brightness;
+    return vec4<f32>(color, idle.opacity);
+}
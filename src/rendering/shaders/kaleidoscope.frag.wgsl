@@ -44,6 +44,7 @@ struct UniversalUniforms {
     prev_palette_index: f32,
     prev_palette_base_hue: f32,
     prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
 
     // Effect weights
     plasma_weight: f32,
@@ -53,6 +54,12 @@ struct UniversalUniforms {
     fractal_weight: f32,
     spectralizer_weight: f32,
 
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
     // System parameters
     projection_mode: f32,
     smoothing_factor: f32,
@@ -83,9 +90,54 @@ struct UniversalUniforms {
     ui_current_shader_index: f32,
     ui_fps: f32,
     ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
     screen_width: f32,
     screen_height: f32,
     text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
 }
 
 @group(0) @binding(0)
@@ -215,11 +267,8 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
     let resolution = vec2<f32>(uniforms.resolution_x, uniforms.resolution_y);
     let uv = (in.tex_coords * 2.0 - 1.0) * vec2<f32>(resolution.x / resolution.y, 1.0);
 
-    // Dynamic segment count based on pitch confidence and rhythm
-    let base_segments = 6.0;
-    let pitch_segments = uniforms.pitch_confidence * 6.0;
-    let rhythm_segments = uniforms.tempo_confidence * 2.0;
-    let segments = base_segments + pitch_segments + rhythm_segments;
+    // Whole segment count driven by pitch and tempo confidence (or a manual override)
+    let segments = max(uniforms.kaleidoscope_segments, 3.0);
 
     // Apply kaleidoscope folding
     let folded_uv = kaleidoscope_fold(uv, segments);
@@ -230,6 +279,15 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
     // Generate audio-reactive color
     var color = get_kaleidoscope_color(pattern, folded_uv);
 
+    // Analytic anti-aliasing across the atan2 seam (negative x-axis), where a
+    // non-integer segment count makes the fold discontinuous
+    if (uv.x < 0.0 && abs(uv.y) < uniforms.aa_width) {
+        let mirrored_uv = kaleidoscope_fold(vec2<f32>(uv.x, -uv.y), segments);
+        let mirrored_color = get_kaleidoscope_color(generate_segment_pattern(mirrored_uv), mirrored_uv);
+        let seam_blend = 0.5 + 0.5 * smoothstep(0.0, uniforms.aa_width, abs(uv.y));
+        color = mix(mirrored_color, color, seam_blend);
+    }
+
     // Radial gradient with bass extension
     let radius = length(uv);
     let bass_extension = 1.0 + uniforms.bass * 0.3;
@@ -260,5 +318,7 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
     // Ensure color values stay in valid range
     color = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
 
-    return vec4<f32>(color, 1.0);
+    // Installation white balance, applied after all shading
+    let white_balance = vec3<f32>(uniforms.white_balance_r, uniforms.white_balance_g, uniforms.white_balance_b);
+    return vec4<f32>(color * white_balance, 1.0);
 }
\ No newline at end of file
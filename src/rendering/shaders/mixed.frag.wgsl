@@ -0,0 +1,294 @@
+struct FragmentInput {
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) world_position: vec3<f32>,
+}
+
+struct UniversalUniforms {
+    // 5-band frequency analysis
+    sub_bass: f32,
+    bass: f32,
+    mid: f32,
+    treble: f32,
+    presence: f32,
+
+    // Volume and dynamics
+    overall_volume: f32,
+    signal_level_db: f32,
+    peak_level_db: f32,
+    dynamic_range: f32,
+
+    // Enhanced rhythm analysis
+    beat_strength: f32,
+    estimated_bpm: f32,
+    tempo_confidence: f32,
+    onset_detected: f32,
+    downbeat_detected: f32,
+
+    // Spectral characteristics
+    spectral_centroid: f32,
+    spectral_rolloff: f32,
+    spectral_flux: f32,
+    pitch_confidence: f32,
+    zero_crossing_rate: f32,
+    onset_strength: f32,
+
+    // Visual controls
+    time: f32,
+    color_intensity: f32,
+    frequency_scale: f32,
+    saturation: f32,
+    palette_index: f32,
+    palette_base_hue: f32,
+    palette_hue_range: f32,
+    transition_blend: f32,
+    prev_palette_index: f32,
+    prev_palette_base_hue: f32,
+    prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
+
+    // Effect weights
+    plasma_weight: f32,
+    kaleidoscope_weight: f32,
+    tunnel_weight: f32,
+    particle_weight: f32,
+    fractal_weight: f32,
+    spectralizer_weight: f32,
+
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
+    // System parameters
+    projection_mode: f32,
+    smoothing_factor: f32,
+
+    // Resolution
+    resolution_x: f32,
+    resolution_y: f32,
+
+    // Safety multipliers for epilepsy prevention
+    safety_beat_intensity: f32,
+    safety_onset_intensity: f32,
+    safety_color_change_rate: f32,
+    safety_brightness_range: f32,
+    safety_pattern_complexity: f32,
+    safety_emergency_stop: f32,
+
+    // Overlay system uniforms
+    mouse_x: f32,
+    mouse_y: f32,
+    mouse_pressed: f32,
+    show_debug_overlay: f32,
+    show_control_panel: f32,
+    ui_volume: f32,
+    ui_is_playing: f32,
+    ui_safety_level: f32,
+    ui_quality_level: f32,
+    ui_auto_shader: f32,
+    ui_current_shader_index: f32,
+    ui_fps: f32,
+    ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
+    screen_width: f32,
+    screen_height: f32,
+    text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: UniversalUniforms;
+
+const PI: f32 = 3.14159265359;
+
+fn hue_to_rgb(h: f32) -> vec3<f32> {
+    let c = vec3<f32>(abs(h * 6.0 - 3.0) - 1.0,
+                      2.0 - abs(h * 6.0 - 2.0),
+                      2.0 - abs(h * 6.0 - 4.0));
+    return clamp(c, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+fn hsv_to_rgb(hsv: vec3<f32>) -> vec3<f32> {
+    let rgb = hue_to_rgb(hsv.x);
+    return ((rgb - 1.0) * hsv.y + 1.0) * hsv.z;
+}
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+fn smooth_noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+
+    return mix(mix(hash(i), hash(i + vec2<f32>(1.0, 0.0)), u.x),
+               mix(hash(i + vec2<f32>(0.0, 1.0)), hash(i + vec2<f32>(1.0, 1.0)), u.x), u.y);
+}
+
+// Layer color from a hue offset and brightness, shared by every effect below
+fn layer_color(hue: f32, brightness: f32) -> vec3<f32> {
+    let base_hue = uniforms.spectral_centroid * 0.0001 + uniforms.time * 0.05;
+    return hsv_to_rgb(vec3<f32>(fract(base_hue + hue), uniforms.saturation * 0.85, clamp(brightness, 0.0, 1.0)));
+}
+
+// Each layer is a lighter take on the matching standalone shader
+
+fn plasma_layer(uv: vec2<f32>) -> vec3<f32> {
+    let t = uniforms.time * (1.0 + uniforms.estimated_bpm / 120.0);
+    let low = smooth_noise(uv * (2.0 + uniforms.bass * 4.0) + vec2<f32>(t * 0.3, t * 0.2));
+    let high = smooth_noise(uv * (4.0 + uniforms.treble * 8.0) + vec2<f32>(t * -0.4, t * 0.5));
+    let field = low * 0.65 + high * 0.35;
+    return layer_color(field * 0.3, uniforms.overall_volume * (0.4 + field * 0.6) + 0.15);
+}
+
+fn kaleidoscope_layer(uv: vec2<f32>) -> vec3<f32> {
+    let segments = max(uniforms.kaleidoscope_segments, 3.0);
+    let sector = 2.0 * PI / segments;
+    var angle = atan2(uv.y, uv.x) + uniforms.time * 0.1;
+    angle = abs(angle - sector * floor(angle / sector) - sector * 0.5);
+    let radius = length(uv);
+    let folded = vec2<f32>(cos(angle), sin(angle)) * radius;
+
+    let pattern = sin(folded.x * (6.0 + uniforms.mid * 8.0) - uniforms.time) * cos(folded.y * 10.0 + uniforms.time * 0.7);
+    return layer_color(radius * 0.4 + 0.33, abs(pattern) * (0.4 + uniforms.mid * 0.6));
+}
+
+fn tunnel_layer(uv: vec2<f32>) -> vec3<f32> {
+    let radius = max(length(uv), 0.05);
+    let depth = 1.0 / radius + uniforms.time * (1.0 + uniforms.bass * uniforms.safety_beat_intensity);
+    let angle = atan2(uv.y, uv.x) / PI;
+
+    let rings = 0.5 + 0.5 * sin(depth * 4.0);
+    let stripes = 0.5 + 0.5 * sin(angle * 8.0 * PI);
+    let fade = smoothstep(0.05, 0.4, radius);
+    return layer_color(depth * 0.02, (rings * 0.7 + stripes * 0.3) * fade * (0.4 + uniforms.bass * 0.6));
+}
+
+fn particle_layer(uv: vec2<f32>) -> vec3<f32> {
+    let grid = uv * 8.0 + vec2<f32>(0.0, uniforms.time * 0.5);
+    let cell = floor(grid);
+    let center = vec2<f32>(hash(cell), hash(cell + vec2<f32>(7.0, 3.0)));
+    let dist = length(fract(grid) - center);
+
+    let size = 0.05 + uniforms.treble * 0.1 + uniforms.onset_strength * uniforms.safety_onset_intensity * 0.05;
+    let glow = 1.0 - smoothstep(0.0, size, dist);
+    return layer_color(hash(cell) * 0.5 + 0.6, glow * (0.6 + uniforms.presence * 0.4));
+}
+
+fn fractal_layer(uv: vec2<f32>) -> vec3<f32> {
+    let c = vec2<f32>(-0.74 + uniforms.mid * 0.05, 0.16 + uniforms.treble * 0.05 * sin(uniforms.time * 0.2));
+    let max_iterations = i32(8.0 + 16.0 * uniforms.safety_pattern_complexity);
+    var z = uv * 1.2;
+    var iterations = 0;
+    for (var i = 0; i < max_iterations; i = i + 1) {
+        if (dot(z, z) > 4.0) {
+            break;
+        }
+        z = vec2<f32>(z.x * z.x - z.y * z.y, 2.0 * z.x * z.y) + c;
+        iterations = iterations + 1;
+    }
+
+    let escape = f32(iterations) / f32(max(max_iterations, 1));
+    return layer_color(escape * 0.6 + 0.75, escape * (0.5 + uniforms.overall_volume * 0.5));
+}
+
+fn spectralizer_layer(tex_coords: vec2<f32>) -> vec3<f32> {
+    var bands = array<f32, 5>(uniforms.sub_bass, uniforms.bass, uniforms.mid, uniforms.treble, uniforms.presence);
+    let index = min(i32(tex_coords.x * 5.0), 4);
+    let level = bands[index];
+    let height = 1.0 - tex_coords.y; // Bars grow up from the bottom
+    let within = fract(tex_coords.x * 5.0);
+    let bar = step(height, level) * smoothstep(0.0, 0.08, within) * (1.0 - smoothstep(0.92, 1.0, within));
+    return layer_color(f32(index) * 0.15, bar * (0.5 + height * 0.5));
+}
+
+@fragment
+fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
+    // Normalize coordinates to screen center
+    let resolution = vec2<f32>(uniforms.resolution_x, uniforms.resolution_y);
+    let uv = (in.tex_coords * 2.0 - 1.0) * vec2<f32>(resolution.x / resolution.y, 1.0);
+
+    // Weights arrive normalized, so the composite never exceeds the brightest layer; skip layers weighted out
+    var color = vec3<f32>(0.0);
+    if (uniforms.plasma_weight > 0.001) {
+        color += plasma_layer(uv) * uniforms.plasma_weight;
+    }
+    if (uniforms.kaleidoscope_weight > 0.001) {
+        color += kaleidoscope_layer(uv) * uniforms.kaleidoscope_weight;
+    }
+    if (uniforms.tunnel_weight > 0.001) {
+        color += tunnel_layer(uv) * uniforms.tunnel_weight;
+    }
+    if (uniforms.particle_weight > 0.001) {
+        color += particle_layer(uv) * uniforms.particle_weight;
+    }
+    if (uniforms.fractal_weight > 0.001) {
+        color += fractal_layer(uv) * uniforms.fractal_weight;
+    }
+    if (uniforms.spectralizer_weight > 0.001) {
+        color += spectralizer_layer(in.tex_coords) * uniforms.spectralizer_weight;
+    }
+
+    // Apply safe color intensity
+    color = color * uniforms.color_intensity * uniforms.safety_brightness_range;
+
+    // Apply emergency stop override
+    color = color * uniforms.safety_emergency_stop;
+
+    // Emergency stop fallback: show dim gray
+    if (uniforms.safety_emergency_stop < 0.1) {
+        color = vec3<f32>(0.1, 0.1, 0.1);
+    }
+
+    // Ensure color values stay in valid range
+    color = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+
+    // Installation white balance, applied after all shading
+    let white_balance = vec3<f32>(uniforms.white_balance_r, uniforms.white_balance_g, uniforms.white_balance_b);
+    return vec4<f32>(color * white_balance, 1.0);
+}
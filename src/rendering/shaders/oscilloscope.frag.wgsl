@@ -0,0 +1,259 @@
+struct FragmentInput {
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) world_position: vec3<f32>,
+}
+
+struct UniversalUniforms {
+    // 5-band frequency analysis
+    sub_bass: f32,
+    bass: f32,
+    mid: f32,
+    treble: f32,
+    presence: f32,
+
+    // Volume and dynamics
+    overall_volume: f32,
+    signal_level_db: f32,
+    peak_level_db: f32,
+    dynamic_range: f32,
+
+    // Enhanced rhythm analysis
+    beat_strength: f32,
+    estimated_bpm: f32,
+    tempo_confidence: f32,
+    onset_detected: f32,
+    downbeat_detected: f32,
+
+    // Spectral characteristics
+    spectral_centroid: f32,
+    spectral_rolloff: f32,
+    spectral_flux: f32,
+    pitch_confidence: f32,
+    zero_crossing_rate: f32,
+    onset_strength: f32,
+
+    // Visual controls
+    time: f32,
+    color_intensity: f32,
+    frequency_scale: f32,
+    saturation: f32,
+    palette_index: f32,
+    palette_base_hue: f32,
+    palette_hue_range: f32,
+    transition_blend: f32,
+    prev_palette_index: f32,
+    prev_palette_base_hue: f32,
+    prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
+
+    // Effect weights
+    plasma_weight: f32,
+    kaleidoscope_weight: f32,
+    tunnel_weight: f32,
+    particle_weight: f32,
+    fractal_weight: f32,
+    spectralizer_weight: f32,
+
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
+    // System parameters
+    projection_mode: f32,
+    smoothing_factor: f32,
+
+    // Resolution
+    resolution_x: f32,
+    resolution_y: f32,
+
+    // Safety multipliers for epilepsy prevention
+    safety_beat_intensity: f32,
+    safety_onset_intensity: f32,
+    safety_color_change_rate: f32,
+    safety_brightness_range: f32,
+    safety_pattern_complexity: f32,
+    safety_emergency_stop: f32,
+
+    // Overlay system uniforms
+    mouse_x: f32,
+    mouse_y: f32,
+    mouse_pressed: f32,
+    show_debug_overlay: f32,
+    show_control_panel: f32,
+    ui_volume: f32,
+    ui_is_playing: f32,
+    ui_safety_level: f32,
+    ui_quality_level: f32,
+    ui_auto_shader: f32,
+    ui_current_shader_index: f32,
+    ui_fps: f32,
+    ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
+    screen_width: f32,
+    screen_height: f32,
+    text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: UniversalUniforms;
+
+// Most recent time-domain samples, oldest first (WAVEFORM_SAMPLES in shader_system.rs)
+const WAVEFORM_SAMPLES: u32 = 1024u;
+
+@group(0) @binding(1)
+var<storage, read> waveform: array<f32, 1024>;
+
+// Custom palette stops as (r, g, b, position), sorted; MAX_CUSTOM_PALETTE_STOPS in palettes.rs
+@group(0) @binding(3)
+var<storage, read> palette_stops: array<vec4<f32>, 8>;
+
+fn hue_to_rgb(h: f32) -> vec3<f32> {
+    let c = vec3<f32>(abs(h * 6.0 - 3.0) - 1.0,
+                      2.0 - abs(h * 6.0 - 2.0),
+                      2.0 - abs(h * 6.0 - 4.0));
+    return clamp(c, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+fn hsv_to_rgb(hsv: vec3<f32>) -> vec3<f32> {
+    let rgb = hue_to_rgb(hsv.x);
+    return ((rgb - 1.0) * hsv.y + 1.0) * hsv.z;
+}
+
+// Linearly interpolated sample at a horizontal position (0 = oldest, 1 = newest)
+fn sample_at(x: f32) -> f32 {
+    let position = clamp(x, 0.0, 1.0) * f32(WAVEFORM_SAMPLES - 1u);
+    let index = u32(floor(position));
+    let next = min(index + 1u, WAVEFORM_SAMPLES - 1u);
+    return mix(waveform[index], waveform[next], fract(position));
+}
+
+// Custom palette (index 8) color at a position, interpolated between the uploaded stops
+fn custom_palette_color(position: f32) -> vec3<f32> {
+    let count = u32(uniforms.custom_palette_stops);
+    if count == 0u {
+        return vec3<f32>(clamp(position, 0.0, 1.0));
+    }
+
+    let t = clamp(position, 0.0, 1.0);
+    var color = palette_stops[0].rgb;
+    for (var i = 1u; i < min(count, 8u); i = i + 1u) {
+        let previous = palette_stops[i - 1u];
+        let stop = palette_stops[i];
+        if t >= stop.w {
+            color = stop.rgb;
+        } else if t > previous.w {
+            color = mix(previous.rgb, stop.rgb, (t - previous.w) / (stop.w - previous.w));
+        }
+    }
+    return color;
+}
+
+// Trace color for the active palette; rainbow sweeps hue across the screen, custom stops run left to right
+fn trace_color(x: f32, palette_index: f32, base_hue: f32, hue_range: f32) -> vec3<f32> {
+    if palette_index > 7.5 && palette_index < 8.5 {
+        return custom_palette_color(x);
+    }
+
+    var hue = fract(base_hue + (x - 0.5) * hue_range);
+    if palette_index < 0.5 {
+        hue = fract(x * 0.6 + uniforms.time * 0.05);
+    }
+    return hsv_to_rgb(vec3<f32>(hue, uniforms.saturation * 0.8, 1.0));
+}
+
+@fragment
+fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
+    let resolution = vec2<f32>(uniforms.resolution_x, uniforms.resolution_y);
+    let aspect = resolution.x / resolution.y;
+    let x = in.tex_coords.x;
+    let y = 1.0 - in.tex_coords.y * 2.0; // [-1, 1], up is positive
+
+    // Keep quiet material visible without clipping loud material
+    let gain = 0.8 / max(0.25, uniforms.overall_volume + 0.25);
+    let trace = sample_at(x) * gain;
+
+    // Distance to the trace measured perpendicular to its slope, so steep edges keep their width
+    let dx = 1.0 / f32(WAVEFORM_SAMPLES);
+    let slope = (sample_at(x + dx) - sample_at(x - dx)) * gain / (2.0 * dx * 2.0 * aspect);
+    let trace_distance = abs(y - trace) / sqrt(1.0 + slope * slope);
+
+    // Line a couple of pixels wide, thickened gently by safe beats, with a soft glow
+    let safe_beat_strength = uniforms.beat_strength * uniforms.safety_beat_intensity;
+    let half_width = uniforms.aa_width * (1.0 + safe_beat_strength * 0.5);
+    let line = 1.0 - smoothstep(half_width, half_width + uniforms.aa_width, trace_distance);
+    let glow = exp(-trace_distance / (0.03 + uniforms.onset_strength * uniforms.safety_onset_intensity * 0.02)) * 0.35;
+
+    // Faint center line and graticule for reference
+    let center = (1.0 - smoothstep(0.0, uniforms.aa_width, abs(y))) * 0.08;
+    let grid_x = abs(fract(x * 8.0 + 0.5) - 0.5) / 8.0 * 2.0 * aspect;
+    let grid = (1.0 - smoothstep(0.0, uniforms.aa_width, grid_x)) * 0.04;
+
+    let current_color = trace_color(x, uniforms.palette_index, uniforms.palette_base_hue, uniforms.palette_hue_range);
+    let prev_color = trace_color(x, uniforms.prev_palette_index, uniforms.prev_palette_base_hue, uniforms.prev_palette_hue_range);
+    let line_color = mix(prev_color, current_color, uniforms.transition_blend);
+
+    var color = line_color * (line + glow) + vec3<f32>(center + grid);
+
+    // Apply global intensity with safety limits
+    color = color * uniforms.color_intensity * uniforms.safety_brightness_range;
+
+    // Apply emergency stop override
+    color = color * uniforms.safety_emergency_stop;
+
+    // Emergency stop fallback: show dim gray
+    if (uniforms.safety_emergency_stop < 0.1) {
+        color = vec3<f32>(0.1, 0.1, 0.1);
+    }
+
+    color = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+
+    // Installation white balance, applied after all shading
+    let white_balance = vec3<f32>(uniforms.white_balance_r, uniforms.white_balance_g, uniforms.white_balance_b);
+    return vec4<f32>(color * white_balance, 1.0);
+}
@@ -0,0 +1,201 @@
+// Beat grid overlay fragment shader - four quarter-note ticks along the bottom edge for beatmatching
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) screen_pos: vec2<f32>,
+}
+
+// CRITICAL: This must match UniversalUniforms exactly in size and order
+struct Uniforms {
+    // 5-band frequency analysis
+    sub_bass: f32,
+    bass: f32,
+    mid: f32,
+    treble: f32,
+    presence: f32,
+
+    // Volume and dynamics
+    overall_volume: f32,
+    signal_level_db: f32,
+    peak_level_db: f32,
+    dynamic_range: f32,
+
+    // Enhanced rhythm analysis
+    beat_strength: f32,
+    estimated_bpm: f32,
+    tempo_confidence: f32,
+    onset_detected: f32,
+    downbeat_detected: f32,
+
+    // Spectral characteristics
+    spectral_centroid: f32,
+    spectral_rolloff: f32,
+    spectral_flux: f32,
+    pitch_confidence: f32,
+    zero_crossing_rate: f32,
+    onset_strength: f32,
+
+    // Visual controls
+    time: f32,
+    color_intensity: f32,
+    frequency_scale: f32,
+    saturation: f32,
+    palette_index: f32,
+    palette_base_hue: f32,
+    palette_hue_range: f32,
+    transition_blend: f32,
+    prev_palette_index: f32,
+    prev_palette_base_hue: f32,
+    prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
+
+    // Effect weights
+    plasma_weight: f32,
+    kaleidoscope_weight: f32,
+    tunnel_weight: f32,
+    particle_weight: f32,
+    fractal_weight: f32,
+    spectralizer_weight: f32,
+
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
+    // System parameters
+    projection_mode: f32,
+    smoothing_factor: f32,
+    resolution_x: f32,
+    resolution_y: f32,
+
+    // Safety multipliers
+    safety_beat_intensity: f32,
+    safety_onset_intensity: f32,
+    safety_color_change_rate: f32,
+    safety_brightness_range: f32,
+    safety_pattern_complexity: f32,
+    safety_emergency_stop: f32,
+
+    // Overlay system uniforms
+    mouse_x: f32,
+    mouse_y: f32,
+    mouse_pressed: f32,
+    show_debug_overlay: f32,
+    show_control_panel: f32,
+    ui_volume: f32,
+    ui_is_playing: f32,
+    ui_safety_level: f32,
+    ui_quality_level: f32,
+    ui_auto_shader: f32,
+    ui_current_shader_index: f32,
+    ui_fps: f32,
+    ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
+    screen_width: f32,
+    screen_height: f32,
+    text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+// Grid bounds in screen space; must match OverlayType::BeatGrid.screen_region in overlay_system.rs
+const GRID_MIN = vec2<f32>(0.35, 0.9);
+const GRID_MAX = vec2<f32>(0.65, 0.97);
+
+fn sdf_rounded_box(pos: vec2<f32>, size: vec2<f32>, radius: f32) -> f32 {
+    let q = abs(pos) - size + radius;
+    return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - radius;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let screen_pos = input.screen_pos;
+
+    // Only render inside the grid strip
+    if (any(screen_pos < GRID_MIN) || any(screen_pos > GRID_MAX)) {
+        discard;
+    }
+
+    // Local coordinates within the grid (0.0 to 1.0)
+    let grid_pos = (screen_pos - GRID_MIN) / (GRID_MAX - GRID_MIN);
+
+    // Faint backdrop so the ticks read over any visualization
+    var color = vec4<f32>(0.05, 0.05, 0.08, 0.25);
+
+    // One tick per quarter note, centered in its quarter of the strip
+    let slot = min(floor(grid_pos.x * 4.0), 3.0);
+    let slot_pos = vec2<f32>(fract(grid_pos.x * 4.0) - 0.5, grid_pos.y - 0.5);
+    let tick_sdf = sdf_rounded_box(slot_pos, vec2<f32>(0.1, 0.32), 0.05);
+    let coverage = 1.0 - smoothstep(-0.02, 0.02, tick_sdf);
+    if (coverage <= 0.0) {
+        return color;
+    }
+
+    // Resting ticks are dim grey, with beat one warmer so the bar start stays visible
+    var tick_color = vec4<f32>(0.6, 0.6, 0.65, 0.45);
+    if (slot == 0.0) {
+        tick_color = vec4<f32>(0.8, 0.7, 0.5, 0.55);
+    }
+
+    // Highlight the current beat; the emergency stop freezes the grid at rest
+    let running = uniforms.safety_emergency_stop;
+    if (abs(slot - uniforms.beat_position) < 0.5) {
+        tick_color = mix(tick_color, vec4<f32>(0.3, 0.8, 1.0, 0.85), running);
+    }
+
+    // Downbeat flash: at most once per bar (well under 3 Hz at any tempo), confined to the
+    // first tick and scaled by the safety multipliers so Ultra Safe leaves it nearly still
+    if (slot == 0.0) {
+        let flash = clamp(uniforms.downbeat_detected * uniforms.safety_beat_intensity * uniforms.safety_brightness_range, 0.0, 1.0) * running;
+        tick_color = mix(tick_color, vec4<f32>(1.0, 0.95, 0.85, 0.9), flash * 0.6);
+    }
+
+    return mix(color, tick_color, coverage);
+}
@@ -48,6 +48,7 @@ struct Uniforms {
     prev_palette_index: f32,
     prev_palette_base_hue: f32,
     prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
 
     // Effect weights
     plasma_weight: f32,
@@ -57,6 +58,12 @@ struct Uniforms {
     fractal_weight: f32,
     spectralizer_weight: f32,
 
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
     // System parameters
     projection_mode: f32,
     smoothing_factor: f32,
@@ -85,14 +92,77 @@ struct Uniforms {
     ui_current_shader_index: f32,
     ui_fps: f32,
     ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
     screen_width: f32,
     screen_height: f32,
     text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
 }
 
 @group(0) @binding(0)
 var<uniform> uniforms: Uniforms;
 
+// Interactive element rects in panel-local space as (min_x, min_y, max_x, max_y).
+// Must match ControlLayout in overlay_system.rs, which hit-tests clicks against the same rects.
+struct ControlLayout {
+    rects: array<vec4<f32>, 8>,
+}
+
+@group(0) @binding(1)
+var<uniform> controls: ControlLayout;
+
+const VOLUME_SLIDER: u32 = 0u;
+const PREVIOUS_TRACK: u32 = 1u;
+const OPEN_FILE: u32 = 2u;
+const NEXT_TRACK: u32 = 3u;
+const SAFETY_LEVEL: u32 = 4u;
+const EMERGENCY_STOP: u32 = 5u;
+const PLAY_PAUSE: u32 = 6u;
+const SEEK_BAR: u32 = 7u;
+
 // Enhanced SDF functions for professional UI elements
 fn sdf_box(pos: vec2<f32>, size: vec2<f32>) -> f32 {
     let d = abs(pos) - size;
@@ -184,13 +254,27 @@ fn draw_text_pattern(pos: vec2<f32>, scale: f32) -> f32 {
     return 0.0;
 }
 
-fn is_mouse_over_area(area_center: vec2<f32>, area_size: vec2<f32>) -> bool {
-    let mouse_local = vec2<f32>(uniforms.mouse_x, uniforms.mouse_y);
-    let area_min = area_center - area_size * 0.5;
-    let area_max = area_center + area_size * 0.5;
+fn in_rect(rect: vec4<f32>, pos: vec2<f32>) -> bool {
+    return pos.x >= rect.x && pos.x <= rect.z && pos.y >= rect.y && pos.y <= rect.w;
+}
+
+fn rect_center(rect: vec4<f32>) -> vec2<f32> {
+    return (rect.xy + rect.zw) * 0.5;
+}
+
+fn rect_half_size(rect: vec4<f32>) -> vec2<f32> {
+    return (rect.zw - rect.xy) * 0.5;
+}
+
+// Position within the rect, 0.0 to 1.0 on each axis
+fn rect_uv(rect: vec4<f32>, pos: vec2<f32>) -> vec2<f32> {
+    return (pos - rect.xy) / (rect.zw - rect.xy);
+}
 
-    return mouse_local.x >= area_min.x && mouse_local.x <= area_max.x &&
-           mouse_local.y >= area_min.y && mouse_local.y <= area_max.y;
+fn is_mouse_over(element: u32) -> bool {
+    // Mouse is in screen space; the panel covers x 0.0-0.4, y 0.0-0.3
+    let mouse_local = vec2<f32>(uniforms.mouse_x / 0.4, uniforms.mouse_y / 0.3);
+    return in_rect(controls.rects[element], mouse_local);
 }
 
 @fragment
@@ -205,6 +289,7 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     // Local coordinates within the control panel (0.0 to 1.0)
     let local_x = screen_pos.x / 0.4;
     let local_y = screen_pos.y / 0.3;
+    let local_pos = vec2<f32>(local_x, local_y);
 
     // Semi-transparent dark background with subtle border
     var color = vec4<f32>(0.06, 0.06, 0.13, 0.9);
@@ -246,34 +331,50 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
         }
 
         // Volume slider track
-        let slider_y = 0.31;
-        let slider_height = 0.04;
-        if (local_y >= slider_y - slider_height * 0.5 && local_y < slider_y + slider_height * 0.5 &&
-            local_x >= 0.1 && local_x < 0.9) {
+        let slider = controls.rects[VOLUME_SLIDER];
+        if (in_rect(slider, local_pos)) {
+            let slider_pos = rect_uv(slider, local_pos).x;
 
             // Track background
             color = vec4<f32>(0.2, 0.25, 0.3, 0.9);
 
             // Volume level fill with audio-reactive glow
-            let volume_width = uniforms.ui_volume * 0.8;
-            if (local_x < 0.1 + volume_width) {
-                let audio_pulse = uniforms.overall_volume * 0.3;
+            if (slider_pos < uniforms.ui_volume) {
+                let audio_pulse = uniforms.ui_meter_level * 0.3;
                 color = vec4<f32>(0.3 + audio_pulse, 0.7 + audio_pulse * 0.2, 0.4, 0.95);
             }
 
-            // Volume handle with hover effect
-            let handle_x = 0.1 + volume_width;
+            // Volume handle, highlighted while the slider is hovered
+            let handle_x = mix(slider.x, slider.z, uniforms.ui_volume);
             let handle_distance = abs(local_x - handle_x);
-            let handle_mouse_over = is_mouse_over_area(vec2<f32>(handle_x * 0.4, slider_y * 0.3), vec2<f32>(0.04, 0.03));
 
             if (handle_distance < 0.025) {
-                if (handle_mouse_over) {
+                if (is_mouse_over(VOLUME_SLIDER)) {
                     color = vec4<f32>(0.9, 0.95, 1.0, 1.0);
                 } else {
                     color = vec4<f32>(0.75, 0.8, 0.85, 0.95);
                 }
             }
         }
+
+        // Level meter with peak-hold tick below the slider
+        let meter_y = 0.355;
+        let meter_height = 0.012;
+        if (local_y >= meter_y - meter_height * 0.5 && local_y < meter_y + meter_height * 0.5 &&
+            local_x >= slider.x && local_x < slider.z) {
+            let meter_pos = rect_uv(slider, local_pos).x;
+            color = vec4<f32>(0.12, 0.14, 0.16, 0.9);
+
+            if (meter_pos < uniforms.ui_meter_level) {
+                // Green through amber to red toward the top of the scale
+                let hot = smoothstep(0.6, 0.95, meter_pos);
+                color = vec4<f32>(mix(vec3<f32>(0.3, 0.8, 0.4), vec3<f32>(0.95, 0.3, 0.2), hot), 0.95);
+            }
+
+            if (uniforms.ui_meter_peak > 0.01 && abs(meter_pos - uniforms.ui_meter_peak) < 0.006) {
+                color = vec4<f32>(0.95, 0.95, 0.9, 1.0);
+            }
+        }
     }
 
     // File control section (0.38 - 0.62)
@@ -286,14 +387,13 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
             }
         }
 
-        let button_y = 0.52;
-        let button_radius = 0.045;
-
-        // Previous button
-        let prev_center = vec2<f32>(0.2, button_y);
-        let prev_distance = distance(vec2<f32>(local_x, local_y), prev_center);
+        // Previous button (circle inscribed in its rect)
+        let prev_rect = controls.rects[PREVIOUS_TRACK];
+        let prev_center = rect_center(prev_rect);
+        let button_radius = rect_half_size(prev_rect).x;
+        let prev_distance = distance(local_pos, prev_center);
         if (prev_distance < button_radius) {
-            let prev_mouse_over = is_mouse_over_area(vec2<f32>(prev_center.x * 0.4, prev_center.y * 0.3), vec2<f32>(0.08, 0.08));
+            let prev_mouse_over = is_mouse_over(PREVIOUS_TRACK);
 
             if (prev_mouse_over && uniforms.mouse_pressed > 0.5) {
                 color = vec4<f32>(0.5, 0.6, 0.7, 0.95); // Pressed state
@@ -312,11 +412,12 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
         }
 
         // Open file button (rectangular)
-        let open_center = vec2<f32>(0.5, button_y);
-        let open_size = vec2<f32>(0.08, 0.05);
-        let open_sdf = sdf_rounded_box(vec2<f32>(local_x, local_y) - open_center, open_size, 0.01);
+        let open_rect = controls.rects[OPEN_FILE];
+        let open_center = rect_center(open_rect);
+        let open_size = rect_half_size(open_rect);
+        let open_sdf = sdf_rounded_box(local_pos - open_center, open_size, 0.01);
         if (open_sdf < 0.0) {
-            let open_mouse_over = is_mouse_over_area(vec2<f32>(open_center.x * 0.4, open_center.y * 0.3), vec2<f32>(0.12, 0.08));
+            let open_mouse_over = is_mouse_over(OPEN_FILE);
 
             if (open_mouse_over && uniforms.mouse_pressed > 0.5) {
                 color = vec4<f32>(0.4, 0.7, 0.5, 0.95); // Pressed state
@@ -335,10 +436,11 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
         }
 
         // Next button
-        let next_center = vec2<f32>(0.8, button_y);
-        let next_distance = distance(vec2<f32>(local_x, local_y), next_center);
-        if (next_distance < button_radius) {
-            let next_mouse_over = is_mouse_over_area(vec2<f32>(next_center.x * 0.4, next_center.y * 0.3), vec2<f32>(0.08, 0.08));
+        let next_rect = controls.rects[NEXT_TRACK];
+        let next_center = rect_center(next_rect);
+        let next_distance = distance(local_pos, next_center);
+        if (next_distance < rect_half_size(next_rect).x) {
+            let next_mouse_over = is_mouse_over(NEXT_TRACK);
 
             if (next_mouse_over && uniforms.mouse_pressed > 0.5) {
                 color = vec4<f32>(0.5, 0.6, 0.7, 0.95); // Pressed state
@@ -359,13 +461,37 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
 
     // Safety and emergency control section (0.62 - 0.82)
     if (local_y >= 0.62 && local_y < 0.82) {
-        // Emergency stop button (large, prominent)
-        let emergency_center = vec2<f32>(0.5, 0.72);
-        let emergency_radius = 0.08;
-        let emergency_distance = distance(vec2<f32>(local_x, local_y), emergency_center);
+        // Safety level indicator (horizontal bar)
+        let safety_rect = controls.rects[SAFETY_LEVEL];
+        if (in_rect(safety_rect, local_pos)) {
+            let safety_position = rect_uv(safety_rect, local_pos).x;
+            let safety_level_normalized = uniforms.ui_safety_level / 4.0;
+
+            if (safety_position <= safety_level_normalized) {
+                var safety_color: vec3<f32>;
+                if (uniforms.ui_safety_level < 1.5) {
+                    safety_color = vec3<f32>(0.3, 0.8, 0.3); // Ultra safe (green)
+                } else if (uniforms.ui_safety_level < 2.5) {
+                    safety_color = vec3<f32>(0.6, 0.8, 0.3); // Safe (yellow-green)
+                } else if (uniforms.ui_safety_level < 3.5) {
+                    safety_color = vec3<f32>(0.8, 0.6, 0.2); // Standard (orange)
+                } else {
+                    safety_color = vec3<f32>(0.8, 0.3, 0.2); // High performance (red)
+                }
+                color = vec4<f32>(safety_color, 0.9);
+            } else {
+                color = vec4<f32>(0.2, 0.25, 0.3, 0.7); // Inactive part
+            }
+        }
+
+        // Emergency stop button (large, prominent), drawn over the safety bar it overlaps
+        let emergency_rect = controls.rects[EMERGENCY_STOP];
+        let emergency_center = rect_center(emergency_rect);
+        let emergency_radius = rect_half_size(emergency_rect).x;
+        let emergency_distance = distance(local_pos, emergency_center);
 
         if (emergency_distance < emergency_radius) {
-            let emergency_mouse_over = is_mouse_over_area(vec2<f32>(emergency_center.x * 0.4, emergency_center.y * 0.3), vec2<f32>(0.12, 0.12));
+            let emergency_mouse_over = is_mouse_over(EMERGENCY_STOP);
 
             var emergency_color: vec4<f32>;
             if (uniforms.safety_emergency_stop < 0.5) {
@@ -398,28 +524,6 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
                 color = mix(color, vec4<f32>(1.0, 1.0, 1.0, 1.0), 0.8);
             }
         }
-
-        // Safety level indicator (horizontal bar)
-        if (local_y > 0.65 && local_y < 0.68 && local_x > 0.1 && local_x < 0.9) {
-            let safety_position = (local_x - 0.1) / 0.8;
-            let safety_level_normalized = uniforms.ui_safety_level / 4.0;
-
-            if (safety_position <= safety_level_normalized) {
-                var safety_color: vec3<f32>;
-                if (uniforms.ui_safety_level < 1.5) {
-                    safety_color = vec3<f32>(0.3, 0.8, 0.3); // Ultra safe (green)
-                } else if (uniforms.ui_safety_level < 2.5) {
-                    safety_color = vec3<f32>(0.6, 0.8, 0.3); // Safe (yellow-green)
-                } else if (uniforms.ui_safety_level < 3.5) {
-                    safety_color = vec3<f32>(0.8, 0.6, 0.2); // Standard (orange)
-                } else {
-                    safety_color = vec3<f32>(0.8, 0.3, 0.2); // High performance (red)
-                }
-                color = vec4<f32>(safety_color, 0.9);
-            } else {
-                color = vec4<f32>(0.2, 0.25, 0.3, 0.7); // Inactive part
-            }
-        }
     }
 
     // Status indicators section (0.82 - 1.0)
@@ -454,22 +558,32 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
             color = vec4<f32>(shader_color_intensity, 0.4, 0.8 - shader_color_intensity * 0.3, 0.9);
         }
 
-        // Playing status with waveform visualization
-        if (local_y > 0.92 && local_y < 0.98 && local_x > 0.1 && local_x < 0.9) {
-            if (uniforms.ui_is_playing > 0.5) {
-                // Audio-reactive visualization
-                let wave_x = local_x * 20.0;
-                let wave_amplitude = uniforms.overall_volume * 0.3 + 0.1;
-                let wave = sin(wave_x + uniforms.time * 5.0) * wave_amplitude;
-                let wave_normalized = (wave + wave_amplitude) / (wave_amplitude * 2.0);
-
-                if (abs((local_y - 0.95) * 10.0) < wave_normalized * 2.0) {
-                    color = vec4<f32>(0.3, 0.8, 0.6, 0.9);
-                } else {
-                    color = vec4<f32>(0.2, 0.4, 0.3, 0.7);
-                }
+        // Transport row: play/pause button and seek bar
+        let play_rect = controls.rects[PLAY_PAUSE];
+        if (in_rect(play_rect, local_pos)) {
+            color = select(vec4<f32>(0.2, 0.3, 0.4, 0.9), vec4<f32>(0.3, 0.4, 0.5, 0.95), is_mouse_over(PLAY_PAUSE));
+            if (draw_icon_pattern(rect_uv(play_rect, local_pos), 4) > 0.5) {
+                color = vec4<f32>(0.85, 0.9, 0.95, 0.95);
+            }
+        }
+
+        // Seek bar with audio-reactive fill up to the playback position
+        let seek_rect = controls.rects[SEEK_BAR];
+        if (in_rect(seek_rect, local_pos)) {
+            let seek_position = rect_uv(seek_rect, local_pos).x;
+            if (uniforms.ui_playback_position < 0.0) {
+                color = vec4<f32>(0.25, 0.25, 0.3, 0.7); // No seekable track
+            } else if (seek_position <= uniforms.ui_playback_position) {
+                let wave = sin(local_x * 20.0 + uniforms.time * 5.0) * (uniforms.overall_volume * 0.3 + 0.1);
+                let pulse = select(0.0, wave, uniforms.ui_is_playing > 0.5);
+                color = vec4<f32>(0.3, 0.7 + pulse * 0.3, 0.6, 0.9);
             } else {
-                color = vec4<f32>(0.4, 0.4, 0.4, 0.7); // Gray for paused
+                color = vec4<f32>(0.2, 0.3, 0.3, 0.7);
+            }
+
+            // Playhead
+            if (uniforms.ui_playback_position >= 0.0 && abs(seek_position - uniforms.ui_playback_position) < 0.01) {
+                color = vec4<f32>(0.9, 0.95, 1.0, 1.0);
             }
         }
     }
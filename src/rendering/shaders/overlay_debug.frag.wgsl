@@ -48,6 +48,7 @@ struct Uniforms {
     prev_palette_index: f32,
     prev_palette_base_hue: f32,
     prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
 
     // Effect weights
     plasma_weight: f32,
@@ -57,6 +58,12 @@ struct Uniforms {
     fractal_weight: f32,
     spectralizer_weight: f32,
 
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
     // System parameters
     projection_mode: f32,
     smoothing_factor: f32,
@@ -85,9 +92,54 @@ struct Uniforms {
     ui_current_shader_index: f32,
     ui_fps: f32,
     ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
     screen_width: f32,
     screen_height: f32,
     text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
 }
 
 @group(0) @binding(0)
@@ -185,12 +237,12 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
     if (local_y < 0.15) {
         color = vec4<f32>(0.9, 0.9, 0.95, 0.9);
 
-        // Header title pattern
-        if (local_y > 0.05 && local_y < 0.12 && local_x > 0.1 && local_x < 0.9) {
-            let text_intensity = draw_text_pattern(vec2<f32>(local_x * 8.0, (local_y - 0.05) * 20.0), 0.15);
-            if (text_intensity > 0.5) {
-                color = vec4<f32>(0.2, 0.3, 0.5, 0.9);
-            }
+        // Title and section labels are real text, drawn by OverlaySystem (DEBUG_TEXT_ROWS)
+
+        // Software renderer warning: pulsing red band across the header
+        if (uniforms.ui_software_renderer > 0.5 && local_y > 0.015 && local_y < 0.045 && local_x > 0.05 && local_x < 0.95) {
+            let pulse = 0.75 + 0.25 * sin(uniforms.time * 3.0);
+            color = vec4<f32>(0.85 * pulse, 0.1, 0.1, 0.95);
         }
 
         // Header underline
@@ -201,14 +253,6 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
 
     // Audio frequency section (0.15 - 0.45)
     if (local_y >= 0.15 && local_y < 0.45) {
-        // Section label area
-        if (local_y > 0.16 && local_y < 0.22 && local_x > 0.05 && local_x < 0.95) {
-            let text_intensity = draw_text_pattern(vec2<f32>(local_x * 10.0, (local_y - 0.16) * 25.0), 0.12);
-            if (text_intensity > 0.5) {
-                color = vec4<f32>(0.1, 0.2, 0.4, 0.9);
-            }
-        }
-
         // 5-band frequency visualization
         let bar_y_start = 0.25;
         let bar_height = 0.15;
@@ -269,14 +313,6 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
 
     // BPM section (0.45 - 0.65)
     if (local_y >= 0.45 && local_y < 0.65) {
-        // BPM label
-        if (local_y > 0.46 && local_y < 0.52 && local_x > 0.05 && local_x < 0.3) {
-            let text_intensity = draw_text_pattern(vec2<f32>(local_x * 12.0, (local_y - 0.46) * 25.0), 0.1);
-            if (text_intensity > 0.5) {
-                color = vec4<f32>(0.1, 0.2, 0.4, 0.9);
-            }
-        }
-
         // BPM pulsing indicator
         let bpm_center = vec2<f32>(0.5, 0.55);
         let bpm_distance = distance(vec2<f32>(local_x, local_y), bpm_center);
@@ -314,14 +350,6 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
 
     // Performance section (0.65 - 0.85)
     if (local_y >= 0.65 && local_y < 0.85) {
-        // FPS label
-        if (local_y > 0.66 && local_y < 0.72 && local_x > 0.05 && local_x < 0.25) {
-            let text_intensity = draw_text_pattern(vec2<f32>(local_x * 15.0, (local_y - 0.66) * 25.0), 0.08);
-            if (text_intensity > 0.5) {
-                color = vec4<f32>(0.1, 0.2, 0.4, 0.9);
-            }
-        }
-
         // FPS section with clear indicator
         if (local_y > 0.70 && local_y < 0.78) {
             // Simple "F" indicator - just a clear rectangular pattern
@@ -361,6 +389,31 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
             }
         }
 
+        // Quality indicator: one segment per level, tinted by why it last changed
+        if (local_y > 0.72 && local_y < 0.76 && local_x > 0.35 && local_x < 0.90) {
+            let segment = floor((local_x - 0.35) / 0.11);
+            let segment_x = fract((local_x - 0.35) / 0.11);
+
+            var reason_color = vec3<f32>(0.3, 0.5, 0.8); // Unchanged or manual
+            if (uniforms.ui_quality_reason > 0.5 && uniforms.ui_quality_reason < 1.5) {
+                reason_color = vec3<f32>(0.8, 0.3, 0.2); // Lowered for sustained low FPS
+            } else if (uniforms.ui_quality_reason > 1.5 && uniforms.ui_quality_reason < 2.5) {
+                reason_color = vec3<f32>(0.2, 0.7, 0.3); // Raised with FPS headroom
+            }
+
+            // Recent changes stand out, then settle to neutral over a few seconds
+            let recency = select(0.0, 1.0 - clamp(uniforms.ui_quality_change_age / 5.0, 0.0, 1.0), uniforms.ui_quality_reason > 0.5);
+            let segment_color = mix(vec3<f32>(0.3, 0.5, 0.8), reason_color, recency);
+
+            if (segment_x > 0.1 && segment_x < 0.9) {
+                if (segment <= uniforms.ui_quality_level) {
+                    color = vec4<f32>(segment_color, 0.95);
+                } else {
+                    color = vec4<f32>(0.75, 0.75, 0.8, 0.9);
+                }
+            }
+        }
+
         // Frame time section with clear indicator
         if (local_y > 0.78 && local_y < 0.86) {
             // Simple "T" indicator for Time
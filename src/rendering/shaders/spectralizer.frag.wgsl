@@ -44,6 +44,7 @@ struct UniversalUniforms {
     prev_palette_index: f32,
     prev_palette_base_hue: f32,
     prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
 
     // Effect weights
     plasma_weight: f32,
@@ -53,6 +54,12 @@ struct UniversalUniforms {
     fractal_weight: f32,
     spectralizer_weight: f32,
 
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
     // System parameters
     projection_mode: f32,
     smoothing_factor: f32,
@@ -83,14 +90,66 @@ struct UniversalUniforms {
     ui_current_shader_index: f32,
     ui_fps: f32,
     ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
     screen_width: f32,
     screen_height: f32,
     text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
 }
 
 @group(0) @binding(0)
 var<uniform> uniforms: UniversalUniforms;
 
+// Log-spaced rows per channel; STEREO_SPECTRUM_ROWS in shader_system.rs
+const STEREO_SPECTRUM_ROWS: u32 = 64u;
+
+// Left channel rows then right channel rows, each normalized to 0-1
+@group(0) @binding(4)
+var<storage, read> stereo_spectrum: array<f32, 128>;
+
 fn hue_to_rgb(h: f32) -> vec3<f32> {
     let c = vec3<f32>(abs(h * 6.0 - 3.0) - 1.0,
                       2.0 - abs(h * 6.0 - 2.0),
@@ -103,10 +162,37 @@ fn hsv_to_rgb(hsv: vec3<f32>) -> vec3<f32> {
     return ((rgb - 1.0) * hsv.y + 1.0) * hsv.z;
 }
 
+// Audible range spanned by the frequency axis
+const AXIS_MIN_HZ: f32 = 20.0;
+const AXIS_MAX_HZ: f32 = 20000.0;
+
+// Frequency under a screen position (0 = low edge, 1 = high edge), blending a linear
+// axis with a logarithmic one so each octave gets the same width
+fn axis_frequency(freq_position: f32) -> f32 {
+    let linear_hz = mix(AXIS_MIN_HZ, AXIS_MAX_HZ, freq_position);
+    let log_hz = AXIS_MIN_HZ * pow(AXIS_MAX_HZ / AXIS_MIN_HZ, freq_position);
+    return mix(linear_hz, log_hz, clamp(uniforms.spectralizer_log_scale, 0.0, 1.0));
+}
+
+// Fractional band index (0 to 5) for a frequency, using the analyzer's default band edges
+fn band_position_for(hz: f32) -> f32 {
+    let f = log2(clamp(hz, AXIS_MIN_HZ, AXIS_MAX_HZ));
+    if (f < log2(60.0)) {
+        return (f - log2(AXIS_MIN_HZ)) / (log2(60.0) - log2(AXIS_MIN_HZ));
+    } else if (f < log2(200.0)) {
+        return 1.0 + (f - log2(60.0)) / (log2(200.0) - log2(60.0));
+    } else if (f < log2(2000.0)) {
+        return 2.0 + (f - log2(200.0)) / (log2(2000.0) - log2(200.0));
+    } else if (f < log2(8000.0)) {
+        return 3.0 + (f - log2(2000.0)) / (log2(8000.0) - log2(2000.0));
+    }
+    return 4.0 + (f - log2(8000.0)) / (log2(AXIS_MAX_HZ) - log2(8000.0));
+}
+
 // Simulate frequency spectrum display
 fn get_frequency_bar_height(freq_position: f32) -> f32 {
     // Determine which frequency band we're in
-    let band_position = freq_position * 5.0;
+    let band_position = band_position_for(axis_frequency(freq_position));
     let band_index = clamp(floor(band_position), 0.0, 4.0);
     let band_fraction = fract(band_position);
 
@@ -154,6 +240,38 @@ fn get_frequency_bar_height(freq_position: f32) -> f32 {
     return (final_height + onset_enhancement) * emergency_multiplier;
 }
 
+// Mask for the bar column under a frequency position, with edges smoothed over one pixel
+fn bar_column_mask(freq_pos: f32, bar_count: f32, bar_width: f32) -> f32 {
+    let bar_x = fract(freq_pos * bar_count);
+    let bar_distance = abs(bar_x - 0.5); // Distance from bar center
+    let bar_aa = uniforms.aa_width * 0.5 * bar_count;
+    return 1.0 - smoothstep(bar_width * 0.5 - bar_aa, bar_width * 0.5 + bar_aa, bar_distance);
+}
+
+// Level of one channel (0 = left, 1 = right) at a frequency position, on the rows' log axis
+fn stereo_level(channel: u32, freq_pos: f32) -> f32 {
+    let row = clamp(freq_pos, 0.0, 1.0) * f32(STEREO_SPECTRUM_ROWS - 1u);
+    let lower = u32(floor(row));
+    let upper = min(lower + 1u, STEREO_SPECTRUM_ROWS - 1u);
+    let offset = channel * STEREO_SPECTRUM_ROWS;
+    return mix(stereo_spectrum[offset + lower], stereo_spectrum[offset + upper], fract(row));
+}
+
+// Left channel bars rising from the center line, right channel bars hanging below it
+fn generate_stereo_spectrum_bars(uv: vec2<f32>) -> f32 {
+    let freq_pos = (uv.x + 1.0) * 0.5;
+    let channel = select(1u, 0u, uv.y >= 0.0);
+    let target_height = stereo_level(channel, freq_pos) * uniforms.safety_emergency_stop;
+
+    let bar_mask = bar_column_mask(freq_pos, 32.0, 0.6);
+    let height_mask = 1.0 - smoothstep(target_height * 0.9, target_height, abs(uv.y));
+
+    // Faint divider so silent channels still show where they start
+    let divider = (1.0 - smoothstep(0.0, uniforms.aa_width * 2.0, abs(uv.y))) * 0.3;
+
+    return bar_mask * height_mask + divider;
+}
+
 // Generate spectrum analyzer bars
 fn generate_spectrum_bars(uv: vec2<f32>) -> f32 {
     // Horizontal position maps to frequency (0 = low, 1 = high)
@@ -171,14 +289,7 @@ fn generate_spectrum_bars(uv: vec2<f32>) -> f32 {
 
     // Calculate which bar we're in
     let bar_count = 20.0;
-    let bar_x = fract(freq_pos * bar_count);
-    let bar_center = 0.5;
-
-    // Distance from bar center
-    let bar_distance = abs(bar_x - bar_center);
-
-    // Create bar shape with soft edges
-    let bar_mask = 1.0 - smoothstep(bar_width * 0.4, bar_width * 0.6, bar_distance);
+    let bar_mask = bar_column_mask(freq_pos, bar_count, bar_width);
 
     // Height mask - create the bar visualization
     let height_mask = 1.0 - smoothstep(target_height * 0.9, target_height, amplitude_pos);
@@ -287,6 +398,11 @@ fn generate_spectral_particles(uv: vec2<f32>) -> f32 {
 
 // Combine all spectralizer elements
 fn generate_spectralizer_pattern(uv: vec2<f32>) -> f32 {
+    // Stereo split shows the two channel spectra alone, so left/right differences read clearly
+    if (uniforms.spectralizer_stereo_split > 0.5) {
+        return generate_stereo_spectrum_bars(uv);
+    }
+
     // Different visualization modes based on audio characteristics
     let spectrum_bars = generate_spectrum_bars(uv);
     let waveform = generate_waveform(uv * vec2<f32>(2.0, 1.0)); // Stretch waveform horizontally
@@ -390,5 +506,7 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
     // Ensure color values stay in valid range
     color = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
 
-    return vec4<f32>(color, 1.0);
+    // Installation white balance, applied after all shading
+    let white_balance = vec3<f32>(uniforms.white_balance_r, uniforms.white_balance_g, uniforms.white_balance_b);
+    return vec4<f32>(color * white_balance, 1.0);
 }
\ No newline at end of file
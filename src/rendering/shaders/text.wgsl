@@ -0,0 +1,50 @@
+// Bitmap text: one instanced quad per glyph, sampled from the single-channel glyph atlas
+
+struct GlyphInstance {
+    @location(0) position: vec2<f32>, // Top-left corner in screen coordinates (0-1, y down)
+    @location(1) size: vec2<f32>,
+    @location(2) uv_min: vec2<f32>,
+    @location(3) uv_max: vec2<f32>,
+    @location(4) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var atlas: texture_2d<f32>;
+@group(0) @binding(1)
+var atlas_sampler: sampler;
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32, glyph: GlyphInstance) -> VertexOutput {
+    // Two triangles covering the glyph cell, as offsets from its top-left corner
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+    );
+    let corner = corners[vertex_index];
+    let screen = glyph.position + corner * glyph.size;
+
+    var output: VertexOutput;
+    output.clip_position = vec4<f32>(screen.x * 2.0 - 1.0, 1.0 - screen.y * 2.0, 0.0, 1.0);
+    output.uv = mix(glyph.uv_min, glyph.uv_max, corner);
+    output.color = glyph.color;
+    return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+    let coverage = textureSample(atlas, atlas_sampler, input.uv).r;
+    if (coverage < 0.5) {
+        discard;
+    }
+    return vec4<f32>(input.color.rgb, input.color.a * coverage);
+}
@@ -0,0 +1,42 @@
+// Trail composite shader - blends the decayed previous frame into the current one
+
+struct TrailUniforms {
+    decay: f32,
+    _padding0: f32,
+    _padding1: f32,
+    _padding2: f32,
+}
+
+@group(0) @binding(0)
+var<uniform> trail: TrailUniforms;
+@group(0) @binding(1)
+var current_frame: texture_2d<f32>;
+@group(0) @binding(2)
+var previous_frame: texture_2d<f32>;
+@group(0) @binding(3)
+var frame_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+}
+
+// Full-screen triangle generated from the vertex index (no vertex buffer needed)
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((vertex_index << 1u) & 2u), f32(vertex_index & 2u));
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+    out.tex_coords = vec2<f32>(uv.x, 1.0 - uv.y);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let current = textureSample(current_frame, frame_sampler, in.tex_coords).rgb;
+    let previous = textureSample(previous_frame, frame_sampler, in.tex_coords).rgb * trail.decay;
+
+    // Keep whichever is brighter so trails fade out instead of accumulating
+    return vec4<f32>(max(current, previous), 1.0);
+}
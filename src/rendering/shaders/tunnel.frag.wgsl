@@ -44,6 +44,7 @@ struct UniversalUniforms {
     prev_palette_index: f32,
     prev_palette_base_hue: f32,
     prev_palette_hue_range: f32,
+    custom_palette_stops: f32, // Stops used from the palette_stops buffer when a palette index is 8 (Custom)
 
     // Effect weights
     plasma_weight: f32,
@@ -53,6 +54,12 @@ struct UniversalUniforms {
     fractal_weight: f32,
     spectralizer_weight: f32,
 
+    // Shader-specific parameters
+    kaleidoscope_segments: f32, // Mirror count for the kaleidoscope fold (integer, 3 to 16)
+    classic_wave_count: f32, // Radial waves across the Classic shader (6 to 24)
+    classic_radial_speed: f32, // Outward wave speed multiplier for Classic (0.25 to 3.0)
+    spectrogram_head: f32, // Ring column holding the newest spectrogram frame
+
     // System parameters
     projection_mode: f32,
     smoothing_factor: f32,
@@ -83,9 +90,54 @@ struct UniversalUniforms {
     ui_current_shader_index: f32,
     ui_fps: f32,
     ui_frame_time: f32,
+    ui_quality_reason: f32, // Last quality change reason (0 none, 1 low FPS, 2 headroom, 3 manual)
+    ui_quality_change_age: f32, // Seconds since last quality change
+    ui_software_renderer: f32, // 1.0 when running on a CPU adapter
+    ui_playback_position: f32, // Playback position as a fraction of the track (-1.0 = no seekable track)
+    ui_meter_level: f32, // Level meter with attack/release ballistics (0.0 to 1.0)
+    ui_meter_peak: f32, // Peak-hold level for the meter (0.0 to 1.0)
     screen_width: f32,
     screen_height: f32,
     text_scale: f32,
+
+    // Anti-aliasing
+    aa_width: f32,
+
+    // Screen transform
+    screen_shake: f32, // Whole-screen UV displacement amplitude from bass hits (0.0 = none)
+
+    // Output color
+    white_balance_r: f32, // White-balance multiplier (1.0 = neutral)
+    white_balance_g: f32,
+    white_balance_b: f32,
+
+    // Frequency axis
+    spectralizer_log_scale: f32, // Spectralizer bin mapping (0.0 = linear, 1.0 = logarithmic)
+
+    // Onset pulse
+    pulse_scale: f32, // Whole-image zoom about the center (1.0 = none)
+    pulse_offset: f32, // Upward kick of the image in UV units (0.0 = none)
+    harmonic_energy: f32, // Sustained, tonal content (0.0 unless HPSS is enabled)
+    percussive_energy: f32, // Transient, broadband content (0.0 unless HPSS is enabled)
+
+    // Fractal shader
+    fractal_mode: f32, // 0.0 = Mandelbrot blend, 1.0 = pitch-driven Julia set
+    fractal_c_real: f32, // Julia set constant, inside the main cardioid
+    fractal_c_imag: f32,
+    fractal_max_iterations: f32, // Iteration cap from the render quality level
+
+    // Perspective (used while projection_mode is 1.0)
+    projection_scale_x: f32, // View-ray x per unit of centered screen x: tan(fov_y / 2) * aspect
+    projection_scale_y: f32, // View-ray y per unit of centered screen y: tan(fov_y / 2)
+
+    // Stereo
+    spectralizer_stereo_split: f32, // 1.0 draws the left spectrum above the center line and the right below
+
+    // Output color
+    color_temperature: f32, // White balance on a -1.0 (cool) to 1.0 (warm) scale; white_balance_r/g/b carry the tint
+
+    // Beat grid
+    beat_position: f32, // Quarter note within the bar (0.0 to 3.0, 0.0 = downbeat)
 }
 
 @group(0) @binding(0)
@@ -103,24 +155,48 @@ fn hsv_to_rgb(hsv: vec3<f32>) -> vec3<f32> {
     return ((rgb - 1.0) * hsv.y + 1.0) * hsv.z;
 }
 
-// 3D tunnel perspective transformation
-fn tunnel_coordinates(uv: vec2<f32>) -> vec3<f32> {
+// Radius of the tunnel wall in view units
+const TUNNEL_RADIUS: f32 = 0.6;
+
+// Depth scaling shared by both projections: bass stretches the tunnel, beats pulse it
+fn tunnel_depth_scale() -> f32 {
+    let bass_depth_factor = 1.0 + uniforms.bass * 2.0 + uniforms.sub_bass * 1.5;
+    let safe_beat_strength = uniforms.beat_strength * uniforms.safety_beat_intensity;
+    let beat_pulse = 1.0 + safe_beat_strength * sin(uniforms.time * 4.0) * 0.15; // Reduced speed and intensity
+    return bass_depth_factor * beat_pulse;
+}
+
+// 2D approximation: depth falls off as 1 / radius from the screen center
+fn tunnel_coordinates_2d(uv: vec2<f32>) -> vec3<f32> {
     let radius = length(uv);
     let angle = atan2(uv.y, uv.x);
 
     // Prevent division by zero
     let safe_radius = max(radius, 0.01);
+    let depth = 1.0 / safe_radius * tunnel_depth_scale();
 
-    // Perspective depth calculation - bass extends the tunnel
-    let bass_depth_factor = 1.0 + uniforms.bass * 2.0 + uniforms.sub_bass * 1.5;
-    let depth = 1.0 / safe_radius * bass_depth_factor;
+    return vec3<f32>(angle, depth, radius);
+}
 
-    // Beat-driven depth pulsing
-    let safe_beat_strength = uniforms.beat_strength * uniforms.safety_beat_intensity;
-    let beat_pulse = 1.0 + safe_beat_strength * sin(uniforms.time * 4.0) * 0.15; // Reduced speed and intensity
-    let final_depth = depth * beat_pulse;
+// 3D perspective: cast a view ray from a camera drifting off the axis and hit the tunnel wall
+fn tunnel_coordinates_3d(screen: vec2<f32>, uv: vec2<f32>) -> vec3<f32> {
+    let ray = vec3<f32>(screen.x * uniforms.projection_scale_x, screen.y * uniforms.projection_scale_y, 1.0);
+
+    // Mids sway the camera; the parallax is what the 2D path can't show
+    let sway = (0.3 + uniforms.mid * 0.4) * uniforms.safety_pattern_complexity;
+    let camera = vec2<f32>(sin(uniforms.time * 0.5), cos(uniforms.time * 0.37)) * TUNNEL_RADIUS * 0.3 * sway;
 
-    return vec3<f32>(angle, final_depth, radius);
+    // Solve |camera + t * ray.xy| = TUNNEL_RADIUS for the far (only positive) root
+    let a = max(dot(ray.xy, ray.xy), 1e-6);
+    let b = 2.0 * dot(camera, ray.xy);
+    let c = dot(camera, camera) - TUNNEL_RADIUS * TUNNEL_RADIUS;
+    let t = (-b + sqrt(max(b * b - 4.0 * a * c, 0.0))) / (2.0 * a);
+
+    let hit = camera + ray.xy * t;
+    let angle = atan2(hit.y, hit.x);
+    let depth = min(t * ray.z, 100.0) * tunnel_depth_scale();
+
+    return vec3<f32>(angle, depth, length(uv));
 }
 
 // Generate tunnel rings and radial patterns
@@ -234,8 +310,13 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
     let resolution = vec2<f32>(uniforms.resolution_x, uniforms.resolution_y);
     let uv = (in.tex_coords * 2.0 - 1.0) * vec2<f32>(resolution.x / resolution.y, 1.0);
 
-    // Transform to tunnel coordinates
-    let tunnel_coord = tunnel_coordinates(uv);
+    // Transform to tunnel coordinates, with real depth when the perspective path is active
+    var tunnel_coord: vec3<f32>;
+    if (uniforms.projection_mode > 0.5) {
+        tunnel_coord = tunnel_coordinates_3d(in.tex_coords * 2.0 - 1.0, uv);
+    } else {
+        tunnel_coord = tunnel_coordinates_2d(uv);
+    }
 
     // Generate tunnel pattern
     let pattern = generate_tunnel_pattern(tunnel_coord);
@@ -267,5 +348,7 @@ fn fs_main(in: FragmentInput) -> @location(0) vec4<f32> {
     // Ensure color values stay in valid range
     color = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
 
-    return vec4<f32>(color, 1.0);
+    // Installation white balance, applied after all shading
+    let white_balance = vec3<f32>(uniforms.white_balance_r, uniforms.white_balance_g, uniforms.white_balance_b);
+    return vec4<f32>(color * white_balance, 1.0);
 }
\ No newline at end of file
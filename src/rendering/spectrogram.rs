@@ -0,0 +1,229 @@
+/// Columns of history shown across the spectrogram by default
+pub const DEFAULT_SPECTROGRAM_COLUMNS: usize = 256;
+
+/// Frequency rows per column; FFT bins are folded onto a log-frequency axis
+pub const SPECTROGRAM_ROWS: usize = 128;
+
+/// Magnitudes this far below full scale render as black
+const SPECTROGRAM_DB_RANGE: f32 = 80.0;
+
+/// Map an FFT magnitude to 0-1 on a decibel scale relative to a full-scale windowed sine
+fn normalize_magnitude(magnitude: f32, bin_count: usize) -> f32 {
+    let full_scale = (bin_count as f32 / 2.0).max(1.0);
+    let db = 20.0 * (magnitude / full_scale).max(1e-9).log10();
+    ((db + SPECTROGRAM_DB_RANGE) / SPECTROGRAM_DB_RANGE).clamp(0.0, 1.0)
+}
+
+/// Fold FFT magnitude bins into `rows` log-spaced rows (row 0 = lowest frequency), keeping each row's peak
+pub fn fold_bins(bins: &[f32], rows: usize) -> Vec<f32> {
+    let mut column = vec![0.0; rows];
+    if bins.len() < 2 {
+        return column;
+    }
+
+    // Skip the DC bin; row edges grow geometrically from bin 1 to the last bin
+    let span = bins.len() as f32;
+    for (row, value) in column.iter_mut().enumerate() {
+        let start = (span.powf(row as f32 / rows as f32) as usize).max(1);
+        let end = (span.powf((row + 1) as f32 / rows as f32) as usize).clamp(start + 1, bins.len());
+        let peak = bins[start.min(bins.len() - 1)..end].iter().fold(0.0f32, |acc, &m| acc.max(m));
+        *value = normalize_magnitude(peak, bins.len());
+    }
+    column
+}
+
+/// Ring buffer of the last N spectrum columns, tracking which ones still need uploading
+pub struct SpectrogramHistory {
+    columns: usize,
+    rows: usize,
+    data: Vec<f32>, // Column-major: `rows` values per column
+    head: usize,    // Column holding the newest frame
+    pending: usize, // Columns pushed since the last upload (capped at `columns`)
+}
+
+impl SpectrogramHistory {
+    pub fn new(columns: usize, rows: usize) -> Self {
+        let columns = columns.max(1);
+        let rows = rows.max(1);
+        Self {
+            columns,
+            rows,
+            data: vec![0.0; columns * rows],
+            head: columns - 1,
+            pending: columns, // A fresh texture needs every column
+        }
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Ring index of the newest column
+    pub fn head(&self) -> usize {
+        self.head
+    }
+
+    /// Append one frame of FFT magnitudes, overwriting the oldest column
+    pub fn push(&mut self, bins: &[f32]) {
+        let column = fold_bins(bins, self.rows);
+        self.push_column(&column);
+    }
+
+    /// Append an already folded column of `rows` normalized values
+    pub fn push_column(&mut self, column: &[f32]) {
+        self.head = (self.head + 1) % self.columns;
+        let start = self.head * self.rows;
+        let slot = &mut self.data[start..start + self.rows];
+        slot.fill(0.0);
+        let len = column.len().min(self.rows);
+        slot[..len].copy_from_slice(&column[..len]);
+        self.pending = (self.pending + 1).min(self.columns);
+    }
+
+    /// Column by age: 0 is the newest, `columns - 1` the oldest
+    pub fn column(&self, age: usize) -> &[f32] {
+        let index = (self.head + self.columns - age % self.columns) % self.columns;
+        &self.data[index * self.rows..(index + 1) * self.rows]
+    }
+
+    /// Ring indices of columns pushed since the last upload, oldest first; clears the pending count
+    pub fn take_pending(&mut self) -> Vec<usize> {
+        let pending = std::mem::take(&mut self.pending);
+        (0..pending)
+            .rev()
+            .map(|age| (self.head + self.columns - age) % self.columns)
+            .collect()
+    }
+
+    /// The whole ring laid out row by row, as a texture upload expects
+    fn row_major(&self) -> Vec<f32> {
+        let mut texels = Vec::with_capacity(self.data.len());
+        for row in 0..self.rows {
+            texels.extend((0..self.columns).map(|column| self.data[column * self.rows + row]));
+        }
+        texels
+    }
+}
+
+/// GPU copy of a `SpectrogramHistory`: one texel column per ring column, rows bottom-up in frequency
+pub struct SpectrogramTexture {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+impl SpectrogramTexture {
+    pub fn new(device: &wgpu::Device, history: &SpectrogramHistory) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("spectrogram_history_texture"),
+            size: wgpu::Extent3d {
+                width: history.columns() as u32,
+                height: history.rows() as u32,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R32Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self { texture, view }
+    }
+
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    /// Write the columns pushed since the last upload; only a full ring of changes re-uploads everything
+    pub fn upload(&self, queue: &wgpu::Queue, history: &mut SpectrogramHistory) {
+        let pending = history.take_pending();
+        if pending.is_empty() {
+            return;
+        }
+
+        let rows = history.rows() as u32;
+        if pending.len() == history.columns() {
+            let columns = history.columns() as u32;
+            queue.write_texture(
+                self.texture.as_image_copy(),
+                bytemuck::cast_slice(&history.row_major()),
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(columns * 4),
+                    rows_per_image: Some(rows),
+                },
+                wgpu::Extent3d { width: columns, height: rows, depth_or_array_layers: 1 },
+            );
+            return;
+        }
+
+        for index in pending {
+            let column = &history.data[index * history.rows..(index + 1) * history.rows];
+            queue.write_texture(
+                wgpu::ImageCopyTexture {
+                    texture: &self.texture,
+                    mip_level: 0,
+                    origin: wgpu::Origin3d { x: index as u32, y: 0, z: 0 },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                bytemuck::cast_slice(column),
+                wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(4), // One texel per row
+                    rows_per_image: Some(rows),
+                },
+                wgpu::Extent3d { width: 1, height: rows, depth_or_array_layers: 1 },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_wraps() {
+        let mut history = SpectrogramHistory::new(4, 2);
+        // A fresh history uploads every column once
+        assert_eq!(history.take_pending(), vec![0, 1, 2, 3]);
+
+        for frame in 1..=6 {
+            history.push_column(&[frame as f32, -(frame as f32)]);
+        }
+
+        // Six pushes into four columns: frames 3-6 remain, newest at ring index 1
+        assert_eq!(history.head(), 1);
+        assert_eq!(history.column(0), &[6.0, -6.0]);
+        assert_eq!(history.column(1), &[5.0, -5.0]);
+        assert_eq!(history.column(3), &[3.0, -3.0]);
+        assert_eq!(history.row_major(), vec![5.0, 6.0, 3.0, 4.0, -5.0, -6.0, -3.0, -4.0]);
+
+        // More pushes than columns collapse to one full upload
+        assert_eq!(history.take_pending().len(), 4);
+
+        // Afterwards only the newest column is pending
+        history.push_column(&[7.0, -7.0]);
+        assert_eq!(history.take_pending(), vec![2]);
+        assert!(history.take_pending().is_empty());
+        assert_eq!(history.column(0), &[7.0, -7.0]);
+    }
+
+    #[test]
+    fn test_fold_bins_log_rows() {
+        let mut bins = vec![0.0; 512];
+        bins[2] = 256.0; // Full scale, low frequency
+        bins[400] = 0.0256; // 80 dB down, near the top
+
+        let column = fold_bins(&bins, SPECTROGRAM_ROWS);
+        assert_eq!(column.len(), SPECTROGRAM_ROWS);
+        assert_eq!(column.iter().position(|&v| v > 0.99), Some(15)); // First row starting at bin 2 (512^(15/128) ≈ 2.08)
+        assert!(column[SPECTROGRAM_ROWS - 5..].iter().all(|&v| v < 0.01));
+        assert!(fold_bins(&[], 8).iter().all(|&v| v == 0.0));
+    }
+}
@@ -0,0 +1,433 @@
+/// Glyph size in atlas pixels
+pub const GLYPH_WIDTH: u32 = 5;
+pub const GLYPH_HEIGHT: u32 = 7;
+
+/// Atlas cell size: one pixel of padding right and below each glyph keeps neighbors from bleeding
+const CELL_WIDTH: u32 = GLYPH_WIDTH + 1;
+const CELL_HEIGHT: u32 = GLYPH_HEIGHT + 1;
+const ATLAS_COLUMNS: u32 = 16;
+
+/// Horizontal advance and line height relative to the glyph size
+const ADVANCE_RATIO: f32 = CELL_WIDTH as f32 / GLYPH_WIDTH as f32;
+const LINE_HEIGHT_RATIO: f32 = 1.5;
+
+/// Glyphs uploaded per frame; longer text is cut off
+pub const MAX_GLYPHS: usize = 1024;
+
+/// Embedded 5x7 bitmap font: one byte per row, bit 4 is the leftmost pixel.
+/// Lowercase letters render as uppercase; anything else missing renders as '?'.
+const FONT: [(char, [u8; 7]); 51] = [
+    ('0', [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110]),
+    ('1', [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('2', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111]),
+    ('3', [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110]),
+    ('4', [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010]),
+    ('5', [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110]),
+    ('6', [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110]),
+    ('7', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000]),
+    ('8', [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110]),
+    ('9', [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100]),
+    ('A', [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('B', [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110]),
+    ('C', [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110]),
+    ('D', [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100]),
+    ('E', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111]),
+    ('F', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('G', [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111]),
+    ('H', [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('I', [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('J', [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100]),
+    ('K', [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001]),
+    ('L', [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111]),
+    ('M', [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001]),
+    ('N', [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001]),
+    ('O', [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('P', [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('Q', [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101]),
+    ('R', [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001]),
+    ('S', [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110]),
+    ('T', [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100]),
+    ('U', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('V', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100]),
+    ('W', [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010]),
+    ('X', [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001]),
+    ('Y', [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100]),
+    ('Z', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111]),
+    ('.', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100]),
+    (',', [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000]),
+    (':', [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000]),
+    ('-', [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000]),
+    ('+', [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000]),
+    ('=', [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000]),
+    ('%', [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011]),
+    ('/', [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000]),
+    ('(', [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010]),
+    (')', [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000]),
+    ('#', [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010]),
+    ('_', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111]),
+    ('\'', [0b01100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('!', [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100]),
+    ('?', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100]),
+];
+
+/// The embedded font baked into a single-channel coverage bitmap
+pub struct GlyphAtlas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>, // Row-major, 255 = glyph pixel
+}
+
+impl GlyphAtlas {
+    pub fn new() -> Self {
+        let rows = (FONT.len() as u32).div_ceil(ATLAS_COLUMNS);
+        let width = ATLAS_COLUMNS * CELL_WIDTH;
+        let height = rows * CELL_HEIGHT;
+        let mut pixels = vec![0u8; (width * height) as usize];
+
+        for (index, (_, bitmap)) in FONT.iter().enumerate() {
+            let (cell_x, cell_y) = Self::cell_origin(index);
+            for (y, row_bits) in bitmap.iter().enumerate() {
+                for x in 0..GLYPH_WIDTH {
+                    if row_bits & (1 << (GLYPH_WIDTH - 1 - x)) != 0 {
+                        pixels[((cell_y + y as u32) * width + cell_x + x) as usize] = 255;
+                    }
+                }
+            }
+        }
+
+        Self { width, height, pixels }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Atlas index for a character, falling back to '?' for anything the font lacks
+    pub fn glyph_index(&self, ch: char) -> usize {
+        let ch = ch.to_ascii_uppercase();
+        FONT.iter()
+            .position(|&(glyph, _)| glyph == ch)
+            .unwrap_or(FONT.len() - 1)
+    }
+
+    /// Texture coordinates (min, max) of a glyph
+    pub fn uv_rect(&self, index: usize) -> ([f32; 2], [f32; 2]) {
+        let (x, y) = Self::cell_origin(index);
+        let (width, height) = (self.width as f32, self.height as f32);
+        (
+            [x as f32 / width, y as f32 / height],
+            [(x + GLYPH_WIDTH) as f32 / width, (y + GLYPH_HEIGHT) as f32 / height],
+        )
+    }
+
+    /// Quads for `text` with its top-left corner at `origin` (screen coordinates, 0-1, y down).
+    /// `glyph_size` is one glyph's width and height in the same units; spaces only advance
+    /// and newlines start a new line.
+    pub fn layout(&self, text: &str, origin: [f32; 2], glyph_size: [f32; 2], color: [f32; 4]) -> Vec<GlyphInstance> {
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut pen = origin;
+        for ch in text.chars() {
+            match ch {
+                '\n' => {
+                    pen = [origin[0], pen[1] + glyph_size[1] * LINE_HEIGHT_RATIO];
+                    continue;
+                }
+                ch if ch.is_whitespace() => {}
+                ch => {
+                    let (uv_min, uv_max) = self.uv_rect(self.glyph_index(ch));
+                    glyphs.push(GlyphInstance { position: pen, size: glyph_size, uv_min, uv_max, color });
+                }
+            }
+            pen[0] += glyph_size[0] * ADVANCE_RATIO;
+        }
+        glyphs
+    }
+
+    fn cell_origin(index: usize) -> (u32, u32) {
+        let index = index as u32;
+        ((index % ATLAS_COLUMNS) * CELL_WIDTH, (index / ATLAS_COLUMNS) * CELL_HEIGHT)
+    }
+}
+
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Glyph width for a given height that keeps the font's aspect ratio on a `resolution` screen
+pub fn glyph_size_for_height(height: f32, resolution: (u32, u32)) -> [f32; 2] {
+    let aspect = resolution.1.max(1) as f32 / resolution.0.max(1) as f32;
+    [height * GLYPH_WIDTH as f32 / GLYPH_HEIGHT as f32 * aspect, height]
+}
+
+/// One glyph quad, drawn as an instance of a six-vertex quad
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct GlyphInstance {
+    pub position: [f32; 2], // Top-left corner in screen coordinates (0-1, y down)
+    pub size: [f32; 2],
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl GlyphInstance {
+    const ATTRIBUTES: [wgpu::VertexAttribute; 5] = wgpu::vertex_attr_array![
+        0 => Float32x2,
+        1 => Float32x2,
+        2 => Float32x2,
+        3 => Float32x2,
+        4 => Float32x4,
+    ];
+
+    fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<GlyphInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBUTES,
+        }
+    }
+}
+
+/// Draws queued strings from the glyph atlas, one instanced quad per glyph
+pub struct TextRenderer {
+    atlas: GlyphAtlas,
+    pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+    instance_buffer: wgpu::Buffer,
+    glyphs: Vec<GlyphInstance>,
+}
+
+impl TextRenderer {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, format: wgpu::TextureFormat) -> Self {
+        let atlas = GlyphAtlas::new();
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("glyph_atlas_texture"),
+            size: wgpu::Extent3d { width: atlas.width(), height: atlas.height(), depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            texture.as_image_copy(),
+            atlas.pixels(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(atlas.width()),
+                rows_per_image: Some(atlas.height()),
+            },
+            wgpu::Extent3d { width: atlas.width(), height: atlas.height(), depth_or_array_layers: 1 },
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        // Nearest filtering keeps the bitmap font's pixels crisp at integer scales
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("glyph_atlas_sampler"),
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("text_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("text_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&sampler) },
+            ],
+        });
+
+        let instance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("text_instance_buffer"),
+            size: (MAX_GLYPHS * std::mem::size_of::<GlyphInstance>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("text_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/text.wgsl").into()),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("text_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("text_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[GlyphInstance::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self { atlas, pipeline, bind_group, instance_buffer, glyphs: Vec::new() }
+    }
+
+    pub fn atlas(&self) -> &GlyphAtlas {
+        &self.atlas
+    }
+
+    /// Drop everything queued for the previous frame
+    pub fn clear(&mut self) {
+        self.glyphs.clear();
+    }
+
+    /// Queue `text` for the next draw (see `GlyphAtlas::layout` for the coordinates)
+    pub fn queue_text(&mut self, text: &str, origin: [f32; 2], glyph_size: [f32; 2], color: [f32; 4]) {
+        let glyphs = self.atlas.layout(text, origin, glyph_size, color);
+        self.glyphs.extend(glyphs);
+    }
+
+    /// Glyphs that will be drawn (capped at `MAX_GLYPHS`)
+    pub fn glyph_count(&self) -> usize {
+        self.glyphs.len().min(MAX_GLYPHS)
+    }
+
+    /// Upload queued glyphs; call before the pass that draws them
+    pub fn prepare(&self, queue: &wgpu::Queue) {
+        if self.glyph_count() > 0 {
+            queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&self.glyphs[..self.glyph_count()]));
+        }
+    }
+
+    /// Draw the prepared glyphs into an open pass
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        let count = self.glyph_count() as u32;
+        if count == 0 {
+            return;
+        }
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+        render_pass.draw(0..6, 0..count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atlas_bakes_every_glyph() {
+        let atlas = GlyphAtlas::new();
+        assert_eq!(atlas.width(), ATLAS_COLUMNS * CELL_WIDTH);
+        assert_eq!(atlas.pixels().len(), (atlas.width() * atlas.height()) as usize);
+        assert!(atlas.height() * ATLAS_COLUMNS >= FONT.len() as u32 * CELL_HEIGHT);
+
+        // Every glyph has ink inside its cell and none in the padding
+        for (index, (ch, _)) in FONT.iter().enumerate() {
+            let (x0, y0) = GlyphAtlas::cell_origin(index);
+            let pixel = |x: u32, y: u32| atlas.pixels()[((y0 + y) * atlas.width() + x0 + x) as usize];
+            let ink = (0..GLYPH_HEIGHT).flat_map(|y| (0..GLYPH_WIDTH).map(move |x| (x, y))).filter(|&(x, y)| pixel(x, y) > 0).count();
+            assert!(ink > 0, "glyph {:?} is empty", ch);
+            assert!((0..CELL_HEIGHT).all(|y| pixel(GLYPH_WIDTH, y) == 0));
+            assert!((0..CELL_WIDTH).all(|x| pixel(x, GLYPH_HEIGHT) == 0));
+        }
+
+        // '1' has a single-pixel stem in its middle column
+        let one = atlas.glyph_index('1');
+        let (x0, y0) = GlyphAtlas::cell_origin(one);
+        assert_eq!(atlas.pixels()[((y0 + 3) * atlas.width() + x0 + 2) as usize], 255);
+        assert_eq!(atlas.pixels()[((y0 + 3) * atlas.width() + x0) as usize], 0);
+    }
+
+    #[test]
+    fn test_glyph_lookup() {
+        let atlas = GlyphAtlas::new();
+        assert_eq!(atlas.glyph_index('a'), atlas.glyph_index('A'));
+        assert_ne!(atlas.glyph_index('A'), atlas.glyph_index('B'));
+        assert_eq!(atlas.glyph_index('~'), atlas.glyph_index('?'));
+
+        let (uv_min, uv_max) = atlas.uv_rect(atlas.glyph_index('0'));
+        assert_eq!(uv_min, [0.0, 0.0]);
+        assert_eq!(uv_max, [GLYPH_WIDTH as f32 / atlas.width() as f32, GLYPH_HEIGHT as f32 / atlas.height() as f32]);
+    }
+
+    #[test]
+    fn test_string_maps_to_glyph_quads() {
+        let atlas = GlyphAtlas::new();
+        let color = [1.0; 4];
+
+        // Spaces advance without a quad
+        let glyphs = atlas.layout("FPS 60.0", [0.1, 0.2], [0.01, 0.02], color);
+        assert_eq!(glyphs.len(), 7);
+        assert_eq!(glyphs[0].position, [0.1, 0.2]);
+        assert!((glyphs[1].position[0] - (0.1 + 0.01 * ADVANCE_RATIO)).abs() < 1e-6);
+        assert!((glyphs[3].position[0] - (0.1 + 4.0 * 0.01 * ADVANCE_RATIO)).abs() < 1e-6);
+        assert!(glyphs.iter().all(|glyph| glyph.size == [0.01, 0.02] && glyph.color == color));
+
+        // Newlines return to the left edge one line lower
+        let glyphs = atlas.layout("BPM\n128", [0.5, 0.0], [0.01, 0.02], color);
+        assert_eq!(glyphs.len(), 6);
+        assert_eq!(glyphs[3].position[0], 0.5);
+        assert!((glyphs[3].position[1] - 0.02 * LINE_HEIGHT_RATIO).abs() < 1e-6);
+
+        assert!(atlas.layout("   ", [0.0, 0.0], [0.01, 0.02], color).is_empty());
+    }
+
+    #[test]
+    fn test_glyph_size_keeps_font_aspect() {
+        let [width, height] = glyph_size_for_height(0.07, (1000, 1000));
+        assert!((width - 0.05).abs() < 1e-6 && height == 0.07);
+
+        // Wide screens need narrower glyphs in normalized units
+        let [wide, _] = glyph_size_for_height(0.07, (2000, 1000));
+        assert!((wide - 0.025).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,466 @@
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+use super::FrameEncoder;
+
+/// Upper bound for trail decay; 1.0 would keep frames on screen forever
+pub const MAX_TRAIL_DECAY: f32 = 0.98;
+
+/// Decay used when trails are toggled on without an explicit value
+pub const DEFAULT_TRAIL_DECAY: f32 = 0.85;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+struct TrailUniforms {
+    decay: f32,
+    _padding: [f32; 3],
+}
+
+impl TrailUniforms {
+    fn new(decay: f32) -> Self {
+        Self { decay, _padding: [0.0; 3] }
+    }
+}
+
+/// Offscreen color target that later passes sample from
+pub(super) struct FrameTarget {
+    #[cfg_attr(not(test), allow(dead_code))] // Only read back by the GPU tests; the view keeps it alive
+    pub(super) texture: wgpu::Texture,
+    pub(super) view: wgpu::TextureView,
+}
+
+impl FrameTarget {
+    pub(super) fn new(device: &wgpu::Device, format: wgpu::TextureFormat, size: (u32, u32), label: &str) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width: size.0.max(1),
+                height: size.1.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self { texture, view }
+    }
+}
+
+/// Frame feedback for motion trails
+///
+/// When enabled, shaders render into an offscreen scene texture. The composite
+/// pass blends it with the decayed previous frame into one of two ping-ponged
+/// history textures, then copies the result to the output view.
+pub struct TrailSystem {
+    decay: f32,
+    format: wgpu::TextureFormat,
+    size: (u32, u32),
+    scene: FrameTarget,
+    history: [FrameTarget; 2],
+    current: usize, // History slot written this frame
+    history_stale: bool,
+    sampler: wgpu::Sampler,
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    composite_uniforms: wgpu::Buffer,
+    present_uniforms: wgpu::Buffer,
+    composite_bind_groups: [wgpu::BindGroup; 2],
+    present_bind_groups: [wgpu::BindGroup; 2],
+}
+
+impl TrailSystem {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, width: u32, height: u32) -> Self {
+        let size = (width.max(1), height.max(1));
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("trail_composite_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/trail_composite.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("trail_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                Self::texture_layout_entry(1),
+                Self::texture_layout_entry(2),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("trail_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("trail_composite_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("trail_sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let composite_uniforms = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("trail_composite_uniforms"),
+            contents: bytemuck::cast_slice(&[TrailUniforms::new(0.0)]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // The present pass reads the same history texture twice with zero decay (a plain copy)
+        let present_uniforms = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("trail_present_uniforms"),
+            contents: bytemuck::cast_slice(&[TrailUniforms::new(0.0)]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let scene = FrameTarget::new(device, format, size, "trail_scene_texture");
+        let history = [
+            FrameTarget::new(device, format, size, "trail_history_texture_0"),
+            FrameTarget::new(device, format, size, "trail_history_texture_1"),
+        ];
+
+        let (composite_bind_groups, present_bind_groups) = Self::create_bind_groups(
+            device, &bind_group_layout, &sampler, &composite_uniforms, &present_uniforms, &scene, &history,
+        );
+
+        Self {
+            decay: 0.0,
+            format,
+            size,
+            scene,
+            history,
+            current: 0,
+            history_stale: false,
+            sampler,
+            bind_group_layout,
+            pipeline,
+            composite_uniforms,
+            present_uniforms,
+            composite_bind_groups,
+            present_bind_groups,
+        }
+    }
+
+    fn texture_layout_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        }
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        uniforms: &wgpu::Buffer,
+        current: &wgpu::TextureView,
+        previous: &wgpu::TextureView,
+        label: &str,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(label),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniforms.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(current) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(previous) },
+                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::Sampler(sampler) },
+            ],
+        })
+    }
+
+    /// Bind groups indexed by the history slot being written this frame
+    fn create_bind_groups(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        composite_uniforms: &wgpu::Buffer,
+        present_uniforms: &wgpu::Buffer,
+        scene: &FrameTarget,
+        history: &[FrameTarget; 2],
+    ) -> ([wgpu::BindGroup; 2], [wgpu::BindGroup; 2]) {
+        let composite = [0usize, 1].map(|slot| {
+            Self::create_bind_group(device, layout, sampler, composite_uniforms,
+                                    &scene.view, &history[1 - slot].view, "trail_composite_bind_group")
+        });
+        let present = [0usize, 1].map(|slot| {
+            Self::create_bind_group(device, layout, sampler, present_uniforms,
+                                    &history[slot].view, &history[slot].view, "trail_present_bind_group")
+        });
+        (composite, present)
+    }
+
+    /// Clamp a requested decay to the supported range
+    pub fn clamp_decay(decay: f32) -> f32 {
+        decay.clamp(0.0, MAX_TRAIL_DECAY)
+    }
+
+    /// Set how much of the previous frame survives into the next (0.0 disables trails)
+    pub fn set_decay(&mut self, decay: f32) {
+        let decay = Self::clamp_decay(decay);
+        if self.decay == 0.0 && decay > 0.0 {
+            // History was not updated while disabled; don't resurrect an old frame
+            self.history_stale = true;
+        }
+        self.decay = decay;
+    }
+
+    pub fn decay(&self) -> f32 {
+        self.decay
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.decay > 0.0
+    }
+
+    /// Recreate offscreen targets if the output size changed
+    pub fn ensure_size(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        let size = (width.max(1), height.max(1));
+        if size == self.size {
+            return;
+        }
+
+        self.size = size;
+        self.scene = FrameTarget::new(device, self.format, size, "trail_scene_texture");
+        self.history = [
+            FrameTarget::new(device, self.format, size, "trail_history_texture_0"),
+            FrameTarget::new(device, self.format, size, "trail_history_texture_1"),
+        ];
+        let (composite, present) = Self::create_bind_groups(
+            device, &self.bind_group_layout, &self.sampler, &self.composite_uniforms,
+            &self.present_uniforms, &self.scene, &self.history,
+        );
+        self.composite_bind_groups = composite;
+        self.present_bind_groups = present;
+    }
+
+    /// View that shaders should render into while trails are enabled
+    pub fn scene_view(&self) -> &wgpu::TextureView {
+        &self.scene.view
+    }
+
+    /// Blend the scene with the decayed previous frame and record writing the result to `target`
+    pub fn composite(&mut self, queue: &wgpu::Queue, frame: &mut FrameEncoder, target: &wgpu::TextureView) {
+        queue.write_buffer(&self.composite_uniforms, 0, bytemuck::cast_slice(&[TrailUniforms::new(self.decay)]));
+
+        let write_slot = self.current;
+        if self.history_stale {
+            Self::clear_pass(frame, &self.history[1 - write_slot].view);
+            self.history_stale = false;
+        }
+
+        self.draw_pass(frame, &self.history[write_slot].view, &self.composite_bind_groups[write_slot], "trail_composite_pass");
+        self.draw_pass(frame, target, &self.present_bind_groups[write_slot], "trail_present_pass");
+
+        self.current = 1 - write_slot;
+    }
+
+    fn draw_pass(&self, frame: &mut FrameEncoder, view: &wgpu::TextureView, bind_group: &wgpu::BindGroup, label: &str) {
+        let mut render_pass = frame.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some(label),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    fn clear_pass(frame: &mut FrameEncoder, view: &wgpu::TextureView) {
+        let _render_pass = frame.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("trail_history_clear_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: u32 = 4;
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None).await.ok()
+        })
+    }
+
+    fn fill(device: &wgpu::Device, queue: &wgpu::Queue, view: &wgpu::TextureView, color: wgpu::Color) {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let _pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Clear(color), store: wgpu::StoreOp::Store },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    fn composite(device: &wgpu::Device, queue: &wgpu::Queue, trails: &mut TrailSystem, view: &wgpu::TextureView) {
+        let mut frame = FrameEncoder::new(device);
+        trails.composite(queue, &mut frame, view);
+        frame.submit(queue);
+    }
+
+    fn read_first_pixel(device: &wgpu::Device, queue: &wgpu::Queue, texture: &wgpu::Texture) -> [u8; 4] {
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (wgpu::COPY_BYTES_PER_ROW_ALIGNMENT * SIZE) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: Some(SIZE),
+                },
+            },
+            wgpu::Extent3d { width: SIZE, height: SIZE, depth_or_array_layers: 1 },
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        [data[0], data[1], data[2], data[3]]
+    }
+
+    #[test]
+    fn test_decay_is_clamped() {
+        assert_eq!(TrailSystem::clamp_decay(-0.5), 0.0);
+        assert_eq!(TrailSystem::clamp_decay(0.5), 0.5);
+        assert_eq!(TrailSystem::clamp_decay(1.5), MAX_TRAIL_DECAY);
+    }
+
+    #[test]
+    fn test_bright_pixel_persists_into_next_frame() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping trail test: no GPU adapter available");
+            return;
+        };
+
+        let mut trails = TrailSystem::new(&device, FORMAT, SIZE, SIZE);
+        trails.set_decay(0.5);
+        let output = FrameTarget::new(&device, FORMAT, (SIZE, SIZE), "trail_test_output");
+
+        // Frame N: bright scene
+        fill(&device, &queue, trails.scene_view(), wgpu::Color::WHITE);
+        composite(&device, &queue, &mut trails, &output.view);
+        let bright = read_first_pixel(&device, &queue, &output.texture);
+        assert_eq!(bright[0], 255);
+
+        // Frame N+1: black scene, the previous frame should still show dimmed
+        fill(&device, &queue, trails.scene_view(), wgpu::Color::BLACK);
+        composite(&device, &queue, &mut trails, &output.view);
+        let faded = read_first_pixel(&device, &queue, &output.texture);
+        assert!(faded[0] > 0, "trail should persist after a black frame");
+        assert!(faded[0] < bright[0], "trail should be dimmer than the original frame");
+    }
+
+    #[test]
+    fn test_no_trail_without_decay() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping trail test: no GPU adapter available");
+            return;
+        };
+
+        let mut trails = TrailSystem::new(&device, FORMAT, SIZE, SIZE);
+        let output = FrameTarget::new(&device, FORMAT, (SIZE, SIZE), "trail_test_output");
+
+        fill(&device, &queue, trails.scene_view(), wgpu::Color::WHITE);
+        composite(&device, &queue, &mut trails, &output.view);
+        fill(&device, &queue, trails.scene_view(), wgpu::Color::BLACK);
+        composite(&device, &queue, &mut trails, &output.view);
+
+        assert_eq!(read_first_pixel(&device, &queue, &output.texture)[0], 0);
+    }
+}
@@ -0,0 +1,161 @@
+use std::time::{Duration, Instant};
+
+use crate::clock::{system_clock, SharedClock};
+
+/// Time for the meter to cover ~63% of an upward step
+pub const VU_ATTACK_TIME: Duration = Duration::from_millis(10);
+
+/// Time for the meter to cover ~63% of a downward step (classic VU release feel)
+pub const VU_RELEASE_TIME: Duration = Duration::from_millis(300);
+
+/// How long the peak indicator holds before falling back
+pub const VU_PEAK_HOLD_TIME: Duration = Duration::from_millis(1500);
+
+/// Level meter with attack/release ballistics and a peak-hold indicator for the overlay
+pub struct VuMeter {
+    clock: SharedClock,
+    attack: Duration,
+    release: Duration,
+    peak_hold: Duration,
+    level: f32,
+    peak: f32,
+    peak_time: Instant,
+    last_update: Instant,
+}
+
+impl VuMeter {
+    pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a meter driven by a custom time source (tests use `MockClock`)
+    pub fn with_clock(clock: SharedClock) -> Self {
+        let now = clock.now();
+        Self {
+            clock,
+            attack: VU_ATTACK_TIME,
+            release: VU_RELEASE_TIME,
+            peak_hold: VU_PEAK_HOLD_TIME,
+            level: 0.0,
+            peak: 0.0,
+            peak_time: now,
+            last_update: now,
+        }
+    }
+
+    /// Configure attack and release time constants
+    pub fn set_time_constants(&mut self, attack: Duration, release: Duration) {
+        self.attack = attack;
+        self.release = release;
+    }
+
+    /// Configure how long peaks are held (zero disables the hold)
+    pub fn set_peak_hold(&mut self, hold: Duration) {
+        self.peak_hold = hold;
+    }
+
+    /// Feed the latest instantaneous level (0.0 to 1.0) and return the smoothed meter level
+    pub fn update(&mut self, input: f32) -> f32 {
+        let now = self.clock.now();
+        let dt = now.duration_since(self.last_update).as_secs_f32();
+        self.last_update = now;
+
+        let input = if input.is_finite() { input.clamp(0.0, 1.0) } else { 0.0 };
+
+        // Fast attack, slow release
+        let time_constant = if input > self.level { self.attack } else { self.release };
+        self.level += (input - self.level) * Self::coefficient(dt, time_constant);
+
+        // Peak hold tracks the raw input, then falls back with the release constant
+        if input >= self.peak {
+            self.peak = input;
+            self.peak_time = now;
+        } else if now.duration_since(self.peak_time) > self.peak_hold {
+            self.peak += (self.level - self.peak) * Self::coefficient(dt, self.release);
+        }
+        self.peak = self.peak.max(self.level);
+
+        self.level
+    }
+
+    /// Smoothed meter level (0.0 to 1.0)
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Held peak level (0.0 to 1.0)
+    pub fn peak(&self) -> f32 {
+        self.peak
+    }
+
+    /// One-pole smoothing coefficient for an elapsed time and time constant
+    fn coefficient(dt: f32, time_constant: Duration) -> f32 {
+        let tau = time_constant.as_secs_f32();
+        if tau <= 0.0 {
+            1.0
+        } else {
+            1.0 - (-dt / tau).exp()
+        }
+    }
+}
+
+impl Default for VuMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn test_fast_attack_slow_release() {
+        let clock = MockClock::new();
+        let mut meter = VuMeter::with_clock(clock.shared());
+
+        // One attack time constant covers ~63% of a step up
+        clock.advance(VU_ATTACK_TIME);
+        let risen = meter.update(1.0);
+        assert!((risen - 0.632).abs() < 0.01);
+
+        // After a few more attack constants the meter is essentially at the input
+        for _ in 0..5 {
+            clock.advance(VU_ATTACK_TIME);
+            meter.update(1.0);
+        }
+        assert!(meter.level() > 0.99);
+
+        // The same interval barely moves the meter on a step down
+        clock.advance(VU_ATTACK_TIME);
+        let after_short_release = meter.update(0.0);
+        assert!(after_short_release > 0.95);
+
+        // One release time constant covers ~63% of the fall
+        clock.advance(VU_RELEASE_TIME - VU_ATTACK_TIME);
+        let released = meter.update(0.0);
+        assert!((released - 0.368).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_peak_hold() {
+        let clock = MockClock::new();
+        let mut meter = VuMeter::with_clock(clock.shared());
+
+        clock.advance_secs(0.1);
+        meter.update(0.8);
+
+        // Peak stays put during the hold period while the level falls
+        clock.advance(VU_PEAK_HOLD_TIME / 2);
+        meter.update(0.0);
+        assert_eq!(meter.peak(), 0.8);
+        assert!(meter.level() < 0.8);
+
+        // After the hold it falls back toward the level
+        clock.advance(VU_PEAK_HOLD_TIME);
+        meter.update(0.0);
+        assert!(meter.peak() < 0.8);
+        assert!(meter.peak() >= meter.level());
+    }
+}
@@ -0,0 +1,450 @@
+//! Session recording and replay for reproducible bug reports
+//!
+//! A session captures the per-frame feature stream plus control events (shader
+//! switches, resizes, safety changes) with timestamps in one plain-text file.
+//! Replay feeds the recorded frames back in order, one per rendered frame, so
+//! the visualizer sees exactly what the reporter saw regardless of audio input.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::audio::{AudioFeatures, RhythmFeatures};
+use crate::clock::{system_clock, MockClock, SharedClock};
+use crate::control::SafetyLevel;
+use crate::rendering::ShaderType;
+
+/// First line of every session file
+const SESSION_HEADER: &str = "aruu-session 1";
+
+/// Something that happened during a recorded session
+#[derive(Debug, Clone)]
+pub enum SessionEvent {
+    Frame { audio: AudioFeatures, rhythm: RhythmFeatures },
+    ShaderSwitch(ShaderType),
+    Resize(u32, u32),
+    SafetyLevel(SafetyLevel),
+    EmergencyStop,
+    Resume,
+}
+
+/// Timestamped session event (stored as whole microseconds)
+#[derive(Debug, Clone)]
+pub struct SessionEntry {
+    pub timestamp: Duration, // Since recording started
+    pub event: SessionEvent,
+}
+
+/// A loaded or freshly recorded session
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    entries: Vec<SessionEntry>,
+}
+
+impl Session {
+    /// Load a session file written by `SessionRecorder::save`
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read session file {}", path.display()))?;
+        Self::parse(&text)
+    }
+
+    /// Parse the plain-text session format
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(SESSION_HEADER) {
+            return Err(anyhow!("Not an Aruu session file (missing '{}' header)", SESSION_HEADER));
+        }
+
+        let mut entries = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = parse_entry(line).with_context(|| format!("Session line {}", index + 2))?;
+            entries.push(entry);
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Serialize to the plain-text session format
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(SESSION_HEADER);
+        text.push('\n');
+        for entry in &self.entries {
+            text.push_str(&format_entry(entry));
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn entries(&self) -> &[SessionEntry] {
+        &self.entries
+    }
+
+    /// Number of recorded frames
+    pub fn frame_count(&self) -> usize {
+        self.entries.iter().filter(|e| matches!(e.event, SessionEvent::Frame { .. })).count()
+    }
+
+    /// Timestamp of the last entry
+    pub fn duration(&self) -> Duration {
+        self.entries.last().map_or(Duration::ZERO, |e| e.timestamp)
+    }
+}
+
+/// Collects timestamped events while a session is being recorded
+pub struct SessionRecorder {
+    clock: SharedClock,
+    started: Instant,
+    session: Session,
+    last_shader: Option<ShaderType>,
+    last_safety_level: Option<SafetyLevel>,
+    last_emergency_stopped: bool,
+}
+
+impl SessionRecorder {
+    pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Create a recorder stamping events from a custom time source
+    pub fn with_clock(clock: SharedClock) -> Self {
+        let started = clock.now();
+        Self {
+            clock,
+            started,
+            session: Session::default(),
+            last_shader: None,
+            last_safety_level: None,
+            last_emergency_stopped: false,
+        }
+    }
+
+    /// Record an event at the current time
+    pub fn record(&mut self, event: SessionEvent) {
+        let timestamp = self.clock.now().duration_since(self.started);
+        self.session.entries.push(SessionEntry { timestamp, event });
+    }
+
+    /// Record shader and safety changes since the last call (the first call records the starting state)
+    pub fn record_state(&mut self, shader: ShaderType, safety_level: SafetyLevel, emergency_stopped: bool) {
+        if self.last_shader != Some(shader) {
+            self.record(SessionEvent::ShaderSwitch(shader));
+            self.last_shader = Some(shader);
+        }
+        if self.last_safety_level != Some(safety_level) {
+            self.record(SessionEvent::SafetyLevel(safety_level));
+            self.last_safety_level = Some(safety_level);
+        }
+        if emergency_stopped != self.last_emergency_stopped {
+            self.record(if emergency_stopped { SessionEvent::EmergencyStop } else { SessionEvent::Resume });
+            self.last_emergency_stopped = emergency_stopped;
+        }
+    }
+
+    /// Record the analysis results that drove one rendered frame
+    pub fn record_frame(&mut self, audio: &AudioFeatures, rhythm: &RhythmFeatures) {
+        self.record(SessionEvent::Frame { audio: audio.clone(), rhythm: rhythm.clone() });
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// Write the recording to disk
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.session.to_text())
+            .with_context(|| format!("Failed to write session file {}", path.display()))
+    }
+}
+
+impl Default for SessionRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One replayed frame: the control events leading up to it and its features
+#[derive(Debug, Clone)]
+pub struct ReplayFrame {
+    pub events: Vec<SessionEvent>,
+    pub audio: AudioFeatures,
+    pub rhythm: RhythmFeatures,
+}
+
+/// Steps through a session frame by frame on a fixed clock
+pub struct SessionPlayer {
+    entries: VecDeque<SessionEntry>,
+    clock: MockClock,
+}
+
+impl SessionPlayer {
+    pub fn new(session: Session) -> Self {
+        Self {
+            entries: session.entries.into(),
+            clock: MockClock::new(),
+        }
+    }
+
+    /// Load a session file for replay
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::new(Session::load(path)?))
+    }
+
+    /// Advance to the next recorded frame; None once the session is exhausted
+    ///
+    /// Control events recorded after the final frame are still returned, with
+    /// default features, so the replay ends in the recorded state.
+    pub fn next_frame(&mut self) -> Option<ReplayFrame> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let mut events = Vec::new();
+        while let Some(entry) = self.entries.pop_front() {
+            self.advance_clock_to(entry.timestamp);
+            match entry.event {
+                SessionEvent::Frame { audio, rhythm } => return Some(ReplayFrame { events, audio, rhythm }),
+                event => events.push(event),
+            }
+        }
+
+        Some(ReplayFrame { events, audio: AudioFeatures::new(), rhythm: RhythmFeatures::new() })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Session time of the last replayed entry
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    /// Clock following recorded timestamps, for components that should replay on session time
+    pub fn clock(&self) -> SharedClock {
+        self.clock.shared()
+    }
+
+    fn advance_clock_to(&self, timestamp: Duration) {
+        let elapsed = self.clock.elapsed();
+        if timestamp > elapsed {
+            self.clock.advance(timestamp - elapsed);
+        }
+    }
+}
+
+pub(crate) fn safety_level_token(level: SafetyLevel) -> &'static str {
+    match level {
+        SafetyLevel::UltraSafe => "UltraSafe",
+        SafetyLevel::Safe => "Safe",
+        SafetyLevel::Moderate => "Moderate",
+        SafetyLevel::Standard => "Standard",
+        SafetyLevel::Disabled => "Disabled",
+    }
+}
+
+pub(crate) fn parse_safety_level(token: &str) -> Result<SafetyLevel> {
+    match token {
+        "UltraSafe" => Ok(SafetyLevel::UltraSafe),
+        "Safe" => Ok(SafetyLevel::Safe),
+        "Moderate" => Ok(SafetyLevel::Moderate),
+        "Standard" => Ok(SafetyLevel::Standard),
+        "Disabled" => Ok(SafetyLevel::Disabled),
+        _ => Err(anyhow!("Unknown safety level '{}'", token)),
+    }
+}
+
+pub(crate) fn parse_shader_type(token: &str) -> Result<ShaderType> {
+    ShaderType::all()
+        .iter()
+        .copied()
+        .find(|shader| format!("{:?}", shader) == token)
+        .ok_or_else(|| anyhow!("Unknown shader '{}'", token))
+}
+
+/// Number of values on a frame line
+const FRAME_VALUE_COUNT: usize = 24;
+
+/// Feature values in file order (f32 `Display` round-trips exactly)
+fn frame_values(audio: &AudioFeatures, rhythm: &RhythmFeatures) -> [f32; FRAME_VALUE_COUNT] {
+    [
+        audio.sub_bass,
+        audio.bass,
+        audio.mid,
+        audio.treble,
+        audio.presence,
+        audio.overall_volume,
+        audio.signal_level_db,
+        audio.peak_level_db,
+        audio.dynamic_range,
+        audio.spectral_centroid,
+        audio.spectral_rolloff,
+        audio.spectral_flux,
+        audio.spectral_crest,
+        audio.pitch_confidence,
+        audio.zero_crossing_rate,
+        audio.onset_strength,
+        rhythm.beat_strength,
+        rhythm.tempo_bpm,
+        rhythm.estimated_bpm,
+        rhythm.tempo_confidence,
+        if rhythm.onset_detected { 1.0 } else { 0.0 },
+        rhythm.rhythm_stability,
+        if rhythm.downbeat_detected { 1.0 } else { 0.0 },
+        rhythm.beat_position as f32,
+    ]
+}
+
+fn frame_from_values(v: &[f32]) -> SessionEvent {
+    let audio = AudioFeatures {
+        sub_bass: v[0],
+        bass: v[1],
+        mid: v[2],
+        treble: v[3],
+        presence: v[4],
+        overall_volume: v[5],
+        signal_level_db: v[6],
+        peak_level_db: v[7],
+        dynamic_range: v[8],
+        spectral_centroid: v[9],
+        spectral_rolloff: v[10],
+        spectral_flux: v[11],
+        spectral_crest: v[12],
+        pitch_confidence: v[13],
+        zero_crossing_rate: v[14],
+        detected_key: None, // Key is derived from the spectrum, which sessions don't record
+        key_confidence: 0.0,
+        onset_strength: v[15],
+        stereo_balance: 0.0, // Sessions record the mono feature set
+        stereo_width: 0.0,
+        harmonic_energy: 0.0,
+        percussive_energy: 0.0,
+    };
+    let rhythm = RhythmFeatures {
+        beat_strength: v[16],
+        tempo_bpm: v[17],
+        estimated_bpm: v[18],
+        tempo_confidence: v[19],
+        onset_detected: v[20] > 0.5,
+        rhythm_stability: v[21],
+        downbeat_detected: v[22] > 0.5,
+        beat_position: v[23] as u8,
+        tempo_lock_changed: false, // Not recorded; replays don't log lock changes
+    };
+    SessionEvent::Frame { audio, rhythm }
+}
+
+fn format_entry(entry: &SessionEntry) -> String {
+    let timestamp = entry.timestamp.as_micros();
+    match &entry.event {
+        SessionEvent::Frame { audio, rhythm } => {
+            let values: Vec<String> = frame_values(audio, rhythm).iter().map(|v| v.to_string()).collect();
+            format!("{} frame {}", timestamp, values.join(" "))
+        }
+        SessionEvent::ShaderSwitch(shader) => format!("{} shader {:?}", timestamp, shader),
+        SessionEvent::Resize(width, height) => format!("{} resize {} {}", timestamp, width, height),
+        SessionEvent::SafetyLevel(level) => format!("{} safety {}", timestamp, safety_level_token(*level)),
+        SessionEvent::EmergencyStop => format!("{} stop", timestamp),
+        SessionEvent::Resume => format!("{} resume", timestamp),
+    }
+}
+
+fn parse_entry(line: &str) -> Result<SessionEntry> {
+    let mut tokens = line.split_whitespace();
+    let timestamp: u64 = tokens.next().ok_or_else(|| anyhow!("Missing timestamp"))?.parse()?;
+    let kind = tokens.next().ok_or_else(|| anyhow!("Missing event kind"))?;
+    let args: Vec<&str> = tokens.collect();
+
+    let event = match (kind, args.as_slice()) {
+        ("frame", values) => {
+            let values = values.iter().map(|v| v.parse::<f32>()).collect::<Result<Vec<_>, _>>()?;
+            if values.len() != FRAME_VALUE_COUNT {
+                return Err(anyhow!("Frame has {} values, expected {}", values.len(), FRAME_VALUE_COUNT));
+            }
+            frame_from_values(&values)
+        }
+        ("shader", [name]) => SessionEvent::ShaderSwitch(parse_shader_type(name)?),
+        ("resize", [width, height]) => SessionEvent::Resize(width.parse()?, height.parse()?),
+        ("safety", [level]) => SessionEvent::SafetyLevel(parse_safety_level(level)?),
+        ("stop", []) => SessionEvent::EmergencyStop,
+        ("resume", []) => SessionEvent::Resume,
+        _ => return Err(anyhow!("Unrecognized session entry '{}'", line)),
+    };
+
+    Ok(SessionEntry { timestamp: Duration::from_micros(timestamp), event })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_replay_reaches_recorded_shader() {
+        let clock = MockClock::new();
+        let mut recorder = SessionRecorder::with_clock(clock.shared());
+
+        let mut audio = AudioFeatures::new();
+        let mut rhythm = RhythmFeatures::new();
+        for frame in 0..5 {
+            audio.bass = frame as f32 * 0.1;
+            rhythm.onset_detected = frame % 2 == 0;
+            let (shader, safety_level) = if frame < 3 {
+                (ShaderType::Classic, SafetyLevel::Safe)
+            } else {
+                (ShaderType::Fractal, SafetyLevel::UltraSafe)
+            };
+            recorder.record_state(shader, safety_level, false);
+            recorder.record_frame(&audio, &rhythm);
+            clock.advance(Duration::from_millis(16));
+        }
+
+        let path = std::env::temp_dir().join(format!("aruu_session_test_{}.txt", std::process::id()));
+        recorder.save(&path).unwrap();
+        let session = Session::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(session.frame_count(), 5);
+        assert_eq!(session.entries().len(), 9); // 5 frames + initial state + one switch of each
+
+        let mut player = SessionPlayer::new(session);
+        let mut shader = ShaderType::Spectralizer;
+        let mut safety_level = SafetyLevel::Disabled;
+        let mut last_bass = 0.0;
+        let mut frames = 0;
+        while let Some(frame) = player.next_frame() {
+            for event in frame.events {
+                match event {
+                    SessionEvent::ShaderSwitch(switched) => shader = switched,
+                    SessionEvent::SafetyLevel(level) => safety_level = level,
+                    _ => {}
+                }
+            }
+            last_bass = frame.audio.bass;
+            frames += 1;
+        }
+
+        assert_eq!(frames, 5);
+        assert_eq!(shader, ShaderType::Fractal);
+        assert_eq!(safety_level, SafetyLevel::UltraSafe);
+        assert_eq!(last_bass, 0.4);
+        assert_eq!(player.elapsed(), Duration::from_millis(64));
+    }
+
+    #[test]
+    fn test_rejects_unknown_entries() {
+        assert!(Session::parse("not a session").is_err());
+        assert!(Session::parse("aruu-session 1\n500000 shader Nonexistent").is_err());
+        assert!(Session::parse("aruu-session 1\n500000 frame 1 2 3").is_err());
+
+        let session = Session::parse("aruu-session 1\n# comment\n250000 resize 800 600\n500000 stop\n750000 resume\n").unwrap();
+        assert_eq!(session.entries().len(), 3);
+        assert_eq!(session.duration(), Duration::from_millis(750));
+    }
+}
@@ -0,0 +1,204 @@
+//! Periodic checkpoints of the visual setup for crash recovery
+//!
+//! A session state captures what a long-running installation has been tuned to:
+//! the active shader, effect weights, palette position, safety level, quality
+//! override and volume. It is serialized with serde as TOML, so the file stays
+//! hand-editable. Loading is forgiving: a field that is missing, truncated or out
+//! of range falls back to its default instead of failing the whole file.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::control::SafetyLevel;
+use crate::rendering::{EffectWeights, QualityLevel, ShaderType};
+
+/// Format version written as the first key of every session state file
+const SESSION_STATE_VERSION: i64 = 1;
+
+/// Volume of a fresh `AudioProcessor`
+const DEFAULT_SESSION_VOLUME: f32 = 0.1;
+
+/// Highest palette position; positions wrap at the built-in palette count
+const MAX_PALETTE_POSITION: f32 = crate::control::ColorPalette::COUNT as f32;
+
+/// Snapshot of the visual setup worth restoring after a restart
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionState {
+    pub shader: ShaderType,
+    pub effect_weights: EffectWeights,
+    pub palette_position: Option<f32>, // Continuous palette position (0-8); None follows the shader
+    pub safety_level: SafetyLevel,
+    pub quality_override: Option<QualityLevel>, // None is adaptive quality
+    pub volume: f32,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self {
+            shader: ShaderType::Classic,
+            effect_weights: EffectWeights::default(),
+            palette_position: None,
+            safety_level: SafetyLevel::default(),
+            quality_override: None,
+            volume: DEFAULT_SESSION_VOLUME,
+        }
+    }
+}
+
+impl SessionState {
+    /// Read a state file; only an unreadable file is an error, bad contents fall back per field
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read session state {}", path.display()))?;
+        Ok(Self::parse(&text))
+    }
+
+    /// Parse a state file, keeping the default for any field that doesn't deserialize.
+    /// Every line is written with its newline, so a last line without one was cut off
+    /// mid-write and is ignored rather than misread (e.g. `volume = 0.6` from `0.65`)
+    pub fn parse(text: &str) -> Self {
+        let complete = text.rfind('\n').map_or("", |end| &text[..=end]);
+        let mut state = Self::default();
+        let table: toml::Table = match toml::from_str(complete) {
+            Ok(table) => table,
+            Err(e) => {
+                println!("⚠️  Session state is not valid TOML ({}), using defaults", e.message());
+                return state;
+            }
+        };
+        if table.get("version").and_then(toml::Value::as_integer) != Some(SESSION_STATE_VERSION) {
+            println!("⚠️  Session state has no 'version = {}', using defaults", SESSION_STATE_VERSION);
+            return state;
+        }
+
+        for (key, value) in table {
+            if key == "version" {
+                continue;
+            }
+            if let Err(e) = state.apply_setting(&key, value) {
+                println!("⚠️  Session state '{}': {:#} - keeping the default", key, e);
+            }
+        }
+        state
+    }
+
+    /// Deserialize one field, rejecting values a live visualizer couldn't use
+    fn apply_setting(&mut self, key: &str, value: toml::Value) -> Result<()> {
+        match key {
+            "shader" => self.shader = value.try_into()?,
+            "effect_weights" => {
+                let weights: EffectWeights = value.try_into()?;
+                if weights.to_array().iter().any(|w| !w.is_finite()) {
+                    return Err(anyhow!("Effect weights must be finite"));
+                }
+                self.effect_weights = weights.clamped();
+            }
+            "palette_position" => {
+                let position: f32 = value.try_into()?;
+                if !(0.0..=MAX_PALETTE_POSITION).contains(&position) {
+                    return Err(anyhow!("Palette position {} is outside 0-{}", position, MAX_PALETTE_POSITION));
+                }
+                self.palette_position = Some(position);
+            }
+            "safety_level" => self.safety_level = value.try_into()?,
+            "quality_override" => self.quality_override = Some(value.try_into()?),
+            "volume" => {
+                let volume: f32 = value.try_into()?;
+                if !(0.0..=1.0).contains(&volume) {
+                    return Err(anyhow!("Volume {} is outside 0-1", volume));
+                }
+                self.volume = volume;
+            }
+            other => return Err(anyhow!("Unknown session state setting '{}'", other)),
+        }
+        Ok(())
+    }
+
+    /// Serialize to TOML, led by the format version
+    pub fn to_text(&self) -> Result<String> {
+        let fields = toml::to_string(self).context("Failed to serialize session state")?;
+        Ok(format!("version = {}\n{}", SESSION_STATE_VERSION, fields))
+    }
+
+    /// Write the state next to `path` and move it into place, so a crash mid-write
+    /// never leaves a half-written checkpoint behind
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let temporary = path.with_extension("tmp");
+        std::fs::write(&temporary, self.to_text()?)
+            .with_context(|| format!("Failed to write session state {}", temporary.display()))?;
+        std::fs::rename(&temporary, path)
+            .with_context(|| format!("Failed to replace session state {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tuned_state() -> SessionState {
+        SessionState {
+            shader: ShaderType::Kaleidoscope,
+            effect_weights: EffectWeights::from_array([0.1, 0.9, 0.25, 0.0, 0.5, 0.75]),
+            palette_position: Some(2.5),
+            safety_level: SafetyLevel::Moderate,
+            quality_override: Some(QualityLevel::Low),
+            volume: 0.65,
+        }
+    }
+
+    #[test]
+    fn test_modified_state_round_trips() {
+        let state = tuned_state();
+        assert_ne!(state, SessionState::default());
+        assert_eq!(SessionState::parse(&state.to_text().unwrap()), state);
+
+        let path = std::env::temp_dir().join(format!("aruu-state-test-{}.toml", std::process::id()));
+        state.save_to(&path).unwrap();
+        assert_eq!(SessionState::load_from(&path).unwrap(), state);
+        std::fs::remove_file(&path).unwrap();
+
+        let adaptive = SessionState { quality_override: None, palette_position: None, ..tuned_state() };
+        assert_eq!(SessionState::parse(&adaptive.to_text().unwrap()), adaptive);
+    }
+
+    #[test]
+    fn test_truncated_state_loads_defaults() {
+        let text = tuned_state().to_text().unwrap();
+
+        // Cut anywhere: nothing panics, and every field is either restored or default
+        for end in 0..=text.len() {
+            let state = SessionState::parse(&text[..end]);
+            assert!(state.shader == ShaderType::Kaleidoscope || state.shader == ShaderType::Classic);
+            assert!(state.volume == 0.65 || state.volume == DEFAULT_SESSION_VOLUME);
+        }
+        assert_eq!(SessionState::parse(&text[..10]), SessionState::default());
+
+        // A field cut mid-value falls back alone
+        let cut = text.find("Moder").unwrap() + 5;
+        let state = SessionState::parse(&text[..cut]);
+        assert_eq!(state.shader, ShaderType::Kaleidoscope);
+        assert_eq!(state.palette_position, Some(2.5));
+        assert_eq!(state.safety_level, SafetyLevel::default());
+        assert_eq!(state.volume, DEFAULT_SESSION_VOLUME);
+    }
+
+    #[test]
+    fn test_out_of_range_fields_fall_back() {
+        let text = format!(
+            "version = {}\nshader = \"Lava\"\npalette_position = 12\nsafety_level = \"Reckless\"\nquality_override = \"Insane\"\nvolume = 3\n\n[effect_weights]\nplasma = 1\n",
+            SESSION_STATE_VERSION
+        );
+        assert_eq!(SessionState::parse(&text), SessionState::default());
+        assert_eq!(SessionState::parse(&format!("version = {}\nvolume = nan\n", SESSION_STATE_VERSION)), SessionState::default());
+
+        // Unversioned or malformed files load as defaults rather than failing
+        assert_eq!(SessionState::parse("shader = \"Plasma\"\n"), SessionState::default());
+        assert_eq!(SessionState::parse("aruu-state 1\nshader = Plasma\n"), SessionState::default());
+        assert!(SessionState::load_from("/nonexistent/aruu-state.toml").is_err());
+    }
+}
@@ -1,28 +1,487 @@
-use crate::{AudioProcessor, RhythmDetector};
-use crate::rendering::{WgpuContext, EnhancedFrameComposer};
-use crate::control::UserInterface;
+use crate::{AudioProcessor, AudioFeatures, FeaturePlayback, FeatureRecorder, FeatureSource, FftBackend, Playlist, ReconnectBackoff, RhythmDetector, RhythmFeatures, Seeked, TrackChanged, DEFAULT_IDLE_TIMEOUT};
+use crate::args::LaunchOptions;
+use crate::session::{SessionEvent, SessionPlayer, SessionRecorder};
+use crate::session_state::SessionState;
+use crate::rendering::{backend_from_env, request_headless_device, AdaptiveThresholds, HeadlessRenderer, WgpuContext, EnhancedFrameComposer, FullscreenMode, ShaderType, QualityLevel, DEFAULT_EXPORT_RESOLUTION, DEFAULT_WINDOW_ICON_PNG, DEFAULT_WINDOW_TITLE, WAVEFORM_SAMPLES};
+use crate::control::{AttractMode, KeyBindings, LoopEdit, MidiSource, MidiSync, OscServer, UserInterface, SafetyLevel, DEFAULT_EMERGENCY_STOP_KEY, DEFAULT_EXIT_KEY, NEUTRAL_WHITE_BALANCE_KELVIN};
 use winit::{
     event::{Event, WindowEvent},
     event_loop::EventLoop,
+    keyboard::KeyCode,
 };
-use std::time::Instant;
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant};
+use anyhow::{Result, anyhow};
+
+const TITLE_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where `--checkpoint` writes state and `--resume` reads it back, unless the builder says otherwise
+pub const DEFAULT_SESSION_STATE_FILE: &str = "aruu-state.toml";
+
+/// Time between state checkpoints
+pub const DEFAULT_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
 
 pub struct AudioVisualizer {
     audio_processor: AudioProcessor,
     rhythm_detector: RhythmDetector,
+    track_changes: Receiver<TrackChanged>,
+    seeks: Receiver<Seeked>,
+    reconnect_backoff: ReconnectBackoff, // Paces attempts to reopen a lost input device
     wgpu_context: WgpuContext,
     frame_composer: EnhancedFrameComposer,
     user_interface: UserInterface,
+    window_title: String, // Leads the live title
+    last_title_update: Instant,
+    checkpoint_path: Option<PathBuf>, // None = no checkpoints
+    checkpoint_interval: Duration,
+    last_checkpoint: Instant,
+    target_fps: u32,
+    session_recorder: Option<SessionRecorder>,
+    session_player: Option<SessionPlayer>,
+    feature_recorder: FeatureRecorder,
+    feature_source: Option<Box<dyn FeatureSource>>, // Stands in for live analysis, e.g. a recorded timeline
+    attract_mode: AttractMode,
+    idle_pattern: bool, // Fade to the idle pattern on sustained silence (attract mode takes precedence)
+    midi_source: Option<MidiSource>,
+    midi_sync: MidiSync,
+    osc_server: Option<OscServer>,
+    shut_down: bool,
 }
 
-impl AudioVisualizer {
-    pub async fn new() -> Result<(Self, EventLoop<()>)> {
-        println!("🎵 Initializing Aruu Audio Visualizer...");
+/// Windowless visualizer from `AudioVisualizerBuilder::build_headless`: the same audio,
+/// rhythm and safety pipeline rendering offscreen into a `HeadlessRenderer`
+pub struct HeadlessVisualizer {
+    audio_processor: AudioProcessor,
+    rhythm_detector: RhythmDetector,
+    renderer: HeadlessRenderer,
+    user_interface: UserInterface,
+}
+
+/// Chainable configuration for `AudioVisualizer`
+#[derive(Debug, Clone)]
+pub struct AudioVisualizerBuilder {
+    target_fps: u32,
+    safety_level: SafetyLevel,
+    initial_shader: ShaderType,
+    auto_shader: bool,
+    quality_override: Option<QualityLevel>,
+    adaptive_quality: bool,
+    adaptive_thresholds: AdaptiveThresholds,
+    trail_decay: f32,
+    use_audio_input: bool,
+    emergency_stop_key: KeyCode,
+    exit_key: Option<KeyCode>,
+    key_bindings: KeyBindings,
+    preset_file: Option<PathBuf>,
+    auto_resume: Option<Duration>,
+    input_channel: Option<usize>,
+    input_device: Option<String>,
+    attract_idle_after: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    white_balance_kelvin: f32,
+    metronome: bool,
+    midi_port: Option<String>,
+    midi_tempo: bool,
+    midi_onsets: bool,
+    osc_port: Option<u16>,
+    latency_offset_ms: i32,
+    gpu_fft: bool,
+    fullscreen_mode: FullscreenMode,
+    start_fullscreen: bool,
+    present_mode: wgpu::PresentMode,
+    backend: Option<wgpu::Backends>,
+    headless_size: (u32, u32),
+    show_warning: bool,
+    window_title: String,
+    window_icon: Option<&'static [u8]>, // PNG bytes
+    checkpoint_path: Option<PathBuf>,
+    checkpoint_interval: Duration,
+    stereo_split: bool,
+    shader_recommendations: bool,
+}
+
+impl AudioVisualizerBuilder {
+    pub fn new() -> Self {
+        Self {
+            target_fps: 60,
+            safety_level: SafetyLevel::Safe,
+            initial_shader: ShaderType::Classic,
+            auto_shader: true,
+            quality_override: None, // Adaptive quality
+            adaptive_quality: true,
+            adaptive_thresholds: AdaptiveThresholds::default(),
+            trail_decay: 0.0,       // Trails off
+            use_audio_input: true,
+            emergency_stop_key: DEFAULT_EMERGENCY_STOP_KEY,
+            exit_key: Some(DEFAULT_EXIT_KEY),
+            key_bindings: KeyBindings::default(),
+            preset_file: None,      // Presets last for the session only
+            auto_resume: None,      // Manual resume only
+            input_channel: None,    // Analyze the input as delivered
+            input_device: None,     // System default input
+            attract_idle_after: None, // Go dark when idle
+            idle_timeout: Some(DEFAULT_IDLE_TIMEOUT),
+            white_balance_kelvin: NEUTRAL_WHITE_BALANCE_KELVIN,
+            metronome: false,
+            midi_port: None,        // Rhythm from onset detection only
+            midi_tempo: true,
+            midi_onsets: true,
+            osc_port: None,         // No remote control
+            latency_offset_ms: 0,
+            gpu_fft: false,
+            fullscreen_mode: FullscreenMode::Borderless,
+            start_fullscreen: false,
+            present_mode: wgpu::PresentMode::Fifo, // V-sync
+            backend: None,          // ARUU_BACKEND, else the first working of BACKEND_PRIORITY
+            headless_size: DEFAULT_EXPORT_RESOLUTION,
+            show_warning: true,
+            window_title: DEFAULT_WINDOW_TITLE.to_string(),
+            window_icon: Some(DEFAULT_WINDOW_ICON_PNG),
+            checkpoint_path: None,  // Nothing is written unless asked for
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            stereo_split: false,
+            shader_recommendations: true,
+        }
+    }
+
+    /// Frame rate cap for the render loop, also the adaptive quality target (e.g. 144 for a
+    /// 144Hz display, paired with a non-V-sync `present_mode`)
+    pub fn target_fps(mut self, fps: u32) -> Self {
+        self.target_fps = fps.max(1);
+        self
+    }
+
+    /// Starting epilepsy safety level
+    pub fn safety_level(mut self, level: SafetyLevel) -> Self {
+        self.safety_level = level;
+        self
+    }
+
+    /// Shader shown on startup (auto mode may still switch away unless disabled)
+    pub fn initial_shader(mut self, shader: ShaderType) -> Self {
+        self.initial_shader = shader;
+        self
+    }
+
+    /// Enable/disable audio-driven shader selection
+    pub fn auto_shader(mut self, enabled: bool) -> Self {
+        self.auto_shader = enabled;
+        self
+    }
+
+    /// Fixed quality level (None keeps adaptive quality)
+    pub fn quality(mut self, quality: Option<QualityLevel>) -> Self {
+        self.quality_override = quality;
+        self
+    }
+
+    /// Let the performance manager change quality on its own (on by default); off holds the
+    /// starting quality for the whole run
+    pub fn adaptive_quality(mut self, enabled: bool) -> Self {
+        self.adaptive_quality = enabled;
+        self
+    }
 
-        let audio_processor = match AudioProcessor::new() {
-            Ok(processor) => {
+    /// How eagerly adaptive quality steps down or up (invalid thresholds keep the defaults)
+    pub fn adaptive_thresholds(mut self, thresholds: AdaptiveThresholds) -> Self {
+        self.adaptive_thresholds = thresholds;
+        self
+    }
+
+    /// Motion trail decay (0.0 disables trails)
+    pub fn trail_decay(mut self, decay: f32) -> Self {
+        self.trail_decay = decay;
+        self
+    }
+
+    /// Open the default input device (false = file playback / silent analysis only)
+    pub fn audio_input(mut self, enabled: bool) -> Self {
+        self.use_audio_input = enabled;
+        self
+    }
+
+    /// Dedicated emergency-stop key for kiosks or accessibility devices (ESC always works)
+    pub fn emergency_stop_key(mut self, keycode: KeyCode) -> Self {
+        self.emergency_stop_key = keycode;
+        self
+    }
+
+    /// Keyboard exit key (None = only closing the window exits)
+    pub fn exit_key(mut self, keycode: Option<KeyCode>) -> Self {
+        self.exit_key = keycode;
+        self
+    }
+
+    /// Key-to-action layout, e.g. from `KeyBindings::load` (safety and exit keys are set separately)
+    pub fn key_bindings(mut self, bindings: KeyBindings) -> Self {
+        self.key_bindings = bindings;
+        self
+    }
+
+    /// File presets are loaded from and saved to (created on the first save)
+    pub fn preset_file(mut self, path: Option<PathBuf>) -> Self {
+        self.preset_file = path;
+        self
+    }
+
+    /// Automatically resume at UltraSafe this long after an emergency stop (unattended installations)
+    pub fn auto_resume(mut self, interval: Option<Duration>) -> Self {
+        self.auto_resume = interval;
+        self
+    }
+
+    /// Analyze a single channel of a multi-channel input (e.g. the master bus of an interface)
+    pub fn input_channel(mut self, channel: Option<usize>) -> Self {
+        self.input_channel = channel;
+        self
+    }
+
+    /// Listen to this input device (an index from `AudioProcessor::list_input_devices` or part
+    /// of its name) instead of the default; unknown devices fall back to the default
+    pub fn input_device(mut self, device: Option<String>) -> Self {
+        self.input_device = device;
+        self
+    }
+
+    /// Enter attract mode after this much silence (None disables it)
+    pub fn attract_mode(mut self, idle_after: Option<Duration>) -> Self {
+        self.attract_idle_after = idle_after;
+        self
+    }
+
+    /// Show the idle pattern after this much silence (None keeps the live scene)
+    pub fn idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Global color temperature tint for projectors (6500K = neutral)
+    pub fn white_balance(mut self, kelvin: f32) -> Self {
+        self.white_balance_kelvin = kelvin;
+        self
+    }
+
+    /// Click on every detected beat (accented on downbeats) to check rhythm detection by ear
+    pub fn metronome(mut self, enabled: bool) -> Self {
+        self.metronome = enabled;
+        self
+    }
+
+    /// Sync to a MIDI input whose port name contains this text ("" = first port); needs the `midi` feature
+    pub fn midi_input(mut self, port: Option<String>) -> Self {
+        self.midi_port = port;
+        self
+    }
+
+    /// Which detected rhythm features MIDI replaces: clock tempo for BPM, note-ons for onsets
+    pub fn midi_overrides(mut self, tempo: bool, onsets: bool) -> Self {
+        self.midi_tempo = tempo;
+        self.midi_onsets = onsets;
+        self
+    }
+
+    /// Accept OSC remote control on this UDP port (`DEFAULT_OSC_PORT` is 9000)
+    pub fn osc_port(mut self, port: Option<u16>) -> Self {
+        self.osc_port = port;
+        self
+    }
+
+    /// Nudge file-playback visuals later (positive) or earlier (negative) to line up with the speakers
+    pub fn latency_offset(mut self, ms: i32) -> Self {
+        self.latency_offset_ms = ms;
+        self
+    }
+
+    /// Run the spectrum FFT as a compute shader when the GPU supports it (CPU otherwise)
+    pub fn gpu_fft(mut self, enabled: bool) -> Self {
+        self.gpu_fft = enabled;
+        self
+    }
+
+    /// Borderless (default) or exclusive fullscreen, used at startup and by the F / Alt+Enter toggle
+    pub fn fullscreen_mode(mut self, mode: FullscreenMode) -> Self {
+        self.fullscreen_mode = mode;
+        self
+    }
+
+    /// Open fullscreen instead of in an 800x600 window (for projection)
+    pub fn start_fullscreen(mut self, enabled: bool) -> Self {
+        self.start_fullscreen = enabled;
+        self
+    }
+
+    /// Show the photosensitivity warning screen before visuals start (on by default)
+    pub fn show_warning(mut self, enabled: bool) -> Self {
+        self.show_warning = enabled;
+        self
+    }
+
+    /// Text leading the window title, ahead of the live shader, tempo and track info
+    pub fn window_title(mut self, title: impl Into<String>) -> Self {
+        self.window_title = title.into();
+        self
+    }
+
+    /// Window icon as PNG bytes (None keeps the platform default)
+    pub fn window_icon(mut self, png: Option<&'static [u8]>) -> Self {
+        self.window_icon = png;
+        self
+    }
+
+    /// Draw the Spectralizer as left (top) and right (bottom) channel spectra; mono sources
+    /// show the same spectrum in both halves
+    pub fn stereo_split(mut self, enabled: bool) -> Self {
+        self.stereo_split = enabled;
+        self
+    }
+
+    /// Switch to each shader's recommended palette and effect weights as it becomes active
+    /// (on by default; a locked palette or hand-set weights are never replaced)
+    pub fn shader_recommendations(mut self, enabled: bool) -> Self {
+        self.shader_recommendations = enabled;
+        self
+    }
+
+    /// Write a `SessionState` checkpoint to `path` every `interval` and on shutdown
+    /// (None turns checkpoints off)
+    pub fn checkpoint(mut self, path: Option<PathBuf>, interval: Duration) -> Self {
+        self.checkpoint_path = path;
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    /// Apply command-line options over the current settings; a starting shader also turns off
+    /// automatic selection, so the requested shader stays on screen
+    pub fn launch_options(mut self, options: &LaunchOptions) -> Self {
+        if let Some(shader) = options.shader {
+            self = self.initial_shader(shader).auto_shader(false);
+        }
+        if let Some(level) = options.safety {
+            self = self.safety_level(level);
+        }
+        if options.quality.is_some() {
+            self = self.quality(options.quality);
+        }
+        if let Some(fps) = options.fps {
+            self = self.target_fps(fps);
+        }
+        if options.fullscreen {
+            self = self.start_fullscreen(true);
+        }
+        if options.input_device.is_some() {
+            self = self.input_device(options.input_device.clone());
+        }
+        if options.backend.is_some() {
+            self = self.backend(options.backend);
+        }
+        if options.skip_warning {
+            self = self.show_warning(false);
+        }
+        if let Some(title) = &options.title {
+            self = self.window_title(title.clone());
+        }
+        if let Some(secs) = options.checkpoint_secs {
+            let path = (secs > 0).then(|| self.checkpoint_path.clone().unwrap_or_else(|| DEFAULT_SESSION_STATE_FILE.into()));
+            self = self.checkpoint(path, Duration::from_secs(secs));
+        }
+        self
+    }
+
+    /// Surface present mode: `Fifo` (V-sync), `Mailbox` or `Immediate` (uncapped by the display)
+    pub fn present_mode(mut self, mode: wgpu::PresentMode) -> Self {
+        self.present_mode = mode;
+        self
+    }
+
+    /// Only try this GPU backend (None falls back through `BACKEND_PRIORITY`, unless
+    /// `ARUU_BACKEND` names one)
+    pub fn backend(mut self, backend: Option<wgpu::Backends>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Output resolution of `build_headless`
+    pub fn headless_size(mut self, width: u32, height: u32) -> Self {
+        self.headless_size = (width, height);
+        self
+    }
+
+    pub fn get_target_fps(&self) -> u32 {
+        self.target_fps
+    }
+
+    pub fn get_initial_shader(&self) -> ShaderType {
+        self.initial_shader
+    }
+
+    /// Create the user interface with the configured safety, auto-shader and quality settings
+    pub fn build_user_interface(&self) -> UserInterface {
+        let mut user_interface = UserInterface::new();
+        user_interface.set_safety_level(self.safety_level);
+        user_interface.auto_shader_enabled = self.auto_shader;
+        user_interface.quality_override = self.quality_override;
+        user_interface.sync_shader_index(self.initial_shader);
+        user_interface.set_emergency_stop_key(self.emergency_stop_key);
+        user_interface.set_exit_key(self.exit_key);
+        user_interface.set_key_bindings(self.key_bindings.clone());
+        if let Some(path) = &self.preset_file {
+            // An unreadable file is left alone rather than overwritten by the next save
+            if let Err(e) = user_interface.set_preset_file(path.clone()) {
+                println!("⚠️  {:#} - presets will not be saved", e);
+            }
+        }
+        user_interface.set_auto_resume(self.auto_resume);
+        if !self.show_warning {
+            user_interface.epilepsy_warning.dismiss();
+        }
+        user_interface
+    }
+
+    /// Open the configured MIDI input; without one (or without a device) rhythm comes from analysis
+    fn build_midi_source(&self) -> Option<MidiSource> {
+        let port = self.midi_port.as_deref()?;
+        match MidiSource::open(port) {
+            Ok(source) => {
+                println!("🎹 MIDI input connected: {}", source.port_name());
+                Some(source)
+            }
+            Err(e) => {
+                println!("⚠️  {} - using detected rhythm", e);
+                None
+            }
+        }
+    }
+
+    fn build_osc_server(&self) -> Option<OscServer> {
+        let port = self.osc_port?;
+        match OscServer::bind(("0.0.0.0", port)) {
+            Ok(server) => {
+                println!("📡 OSC control listening on UDP port {}", port);
+                Some(server)
+            }
+            Err(e) => {
+                println!("⚠️  Failed to open OSC port {}: {}", port, e);
+                None
+            }
+        }
+    }
+
+    fn build_audio_processor(&self) -> AudioProcessor {
+        if !self.use_audio_input {
+            return AudioProcessor::new_default();
+        }
+
+        let processor = match &self.input_device {
+            Some(device) => AudioProcessor::new_with_device(device),
+            None => AudioProcessor::new(),
+        };
+        match processor {
+            Ok(mut processor) => {
                 println!("✅ Audio input initialized successfully");
+                if let Err(e) = processor.set_input_channel(self.input_channel) {
+                    println!("⚠️  {} - analyzing all channels", e);
+                }
                 processor
             }
             Err(e) => {
@@ -30,33 +489,245 @@ impl AudioVisualizer {
                 println!("💡 Falling back to default processor for testing");
                 AudioProcessor::new_default()
             }
+        }
+    }
+
+    /// Audio input and rhythm detection, analyzing at the target frame rate
+    fn build_analysis(&self) -> (AudioProcessor, RhythmDetector) {
+        let mut audio_processor = self.build_audio_processor();
+        audio_processor.set_analysis_frame_rate(self.target_fps as f32);
+        audio_processor.set_latency_offset(self.latency_offset_ms);
+        if let Some(timeout) = self.idle_timeout {
+            audio_processor.set_idle_timeout(timeout);
+        }
+        if self.gpu_fft {
+            audio_processor.set_fft_backend(FftBackend::Gpu);
+        }
+        let mut rhythm_detector = RhythmDetector::new(audio_processor.sample_rate());
+        rhythm_detector.set_frame_rate(self.target_fps as f32);
+        rhythm_detector.set_click_enabled(self.metronome);
+        (audio_processor, rhythm_detector)
+    }
+
+    /// Build a windowed visualizer and its event loop
+    pub async fn build(self) -> Result<(AudioVisualizer, EventLoop<()>)> {
+        println!("🎵 Initializing Aruu Audio Visualizer...");
+
+        let (mut audio_processor, rhythm_detector) = self.build_analysis();
+        let track_changes = audio_processor.track_changes();
+        let seeks = audio_processor.seeks();
+
+        let backend = match self.backend {
+            Some(backend) => Some(backend),
+            None => backend_from_env()?,
         };
+        let (mut wgpu_context, event_loop) = WgpuContext::with_backend(self.present_mode, backend).await?;
+        wgpu_context.fullscreen_mode = self.fullscreen_mode;
+        wgpu_context.set_title(&self.window_title);
+        if let Some(png) = self.window_icon {
+            if let Err(e) = wgpu_context.set_icon_png(png) {
+                println!("⚠️  {}", e);
+            }
+        }
+        if self.start_fullscreen {
+            wgpu_context.set_fullscreen(true);
+        }
+        let mut frame_composer = EnhancedFrameComposer::new(&wgpu_context)?;
+        if frame_composer.current_shader() != self.initial_shader {
+            frame_composer.set_shader_immediately(self.initial_shader, &wgpu_context)?;
+        }
+        frame_composer.set_target_fps(self.target_fps as f32);
+        frame_composer.set_quality_override(self.quality_override);
+        frame_composer.set_adaptive_quality(self.adaptive_quality);
+        if let Err(e) = frame_composer.set_adaptive_thresholds(self.adaptive_thresholds) {
+            println!("⚠️  {}", e);
+        }
+        frame_composer.set_trail_decay(self.trail_decay);
+        frame_composer.set_white_balance(self.white_balance_kelvin);
+        frame_composer.set_spectralizer_stereo_split(self.stereo_split);
+        frame_composer.set_shader_recommendations(self.shader_recommendations);
 
-        let rhythm_detector = RhythmDetector::new(44100.0);
+        let user_interface = self.build_user_interface();
 
-        let (wgpu_context, event_loop) = WgpuContext::new().await?;
-        let frame_composer = EnhancedFrameComposer::new(&wgpu_context)?;
-        let user_interface = UserInterface::new();
+        let midi_source = self.build_midi_source();
+        let mut midi_sync = MidiSync::new();
+        midi_sync.set_override_tempo(self.midi_tempo);
+        midi_sync.set_override_onsets(self.midi_onsets);
+        let osc_server = self.build_osc_server();
+
+        let mut attract_mode = AttractMode::new();
+        if let Some(idle_after) = self.attract_idle_after {
+            attract_mode.set_enabled(true, idle_after);
+        }
 
         println!("✅ WGPU context and rendering pipeline initialized");
         println!("🚀 Audio Visualizer ready!");
 
         Ok((
-            Self {
+            AudioVisualizer {
                 audio_processor,
                 rhythm_detector,
+                track_changes,
+                seeks,
+                reconnect_backoff: ReconnectBackoff::new(),
                 wgpu_context,
                 frame_composer,
                 user_interface,
+                window_title: self.window_title.clone(),
+                last_title_update: Instant::now(),
+                checkpoint_path: self.checkpoint_path.clone(),
+                checkpoint_interval: self.checkpoint_interval,
+                last_checkpoint: Instant::now(),
+                target_fps: self.target_fps,
+                session_recorder: None,
+                session_player: None,
+                feature_recorder: FeatureRecorder::new(),
+                feature_source: None,
+                attract_mode,
+                idle_pattern: self.idle_timeout.is_some(),
+                midi_source,
+                midi_sync,
+                osc_server,
+                shut_down: false,
             },
             event_loop,
         ))
     }
 
+    /// Build a visualizer that renders offscreen at `headless_size`, on the default adapter
+    pub fn build_headless(self) -> Result<HeadlessVisualizer> {
+        let (device, queue) = request_headless_device()?;
+        self.build_headless_with_device(device, queue)
+    }
+
+    /// Build a visualizer that renders offscreen at `headless_size`, on an existing device.
+    /// Window, key, MIDI, OSC and checkpoint settings have nothing to act on and are ignored.
+    pub fn build_headless_with_device(self, device: wgpu::Device, queue: wgpu::Queue) -> Result<HeadlessVisualizer> {
+        let (width, height) = self.headless_size;
+        let mut renderer = HeadlessRenderer::with_device(device, queue, width, height)?;
+        let shader_system = renderer.shader_system_mut();
+        shader_system.set_white_balance(self.white_balance_kelvin);
+        shader_system.set_spectralizer_stereo_split(self.stereo_split);
+        shader_system.set_shader_recommendations(self.shader_recommendations);
+        if renderer.current_shader() != self.initial_shader {
+            renderer.set_shader(self.initial_shader)?;
+        }
+
+        let (audio_processor, rhythm_detector) = self.build_analysis();
+        let user_interface = self.build_user_interface();
+        renderer.set_safety_multipliers(Some(user_interface.get_safety_multipliers()));
+
+        Ok(HeadlessVisualizer {
+            audio_processor,
+            rhythm_detector,
+            renderer,
+            user_interface,
+        })
+    }
+}
+
+impl Default for AudioVisualizerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioVisualizer {
+    /// Build a visualizer from command-line options, loading the requested file or session
+    ///
+    /// A file or session that fails to load is reported and the visualizer falls back to live input.
+    pub async fn new(options: &LaunchOptions) -> Result<(Self, EventLoop<()>)> {
+        let (mut visualizer, event_loop) = AudioVisualizerBuilder::new().launch_options(options).build().await?;
+
+        if options.resume {
+            if let Err(e) = visualizer.resume_session_state() {
+                println!("❌ Failed to resume: {:#}", e);
+            }
+        }
+
+        if let Some(path) = &options.replay {
+            if let Err(e) = visualizer.replay_session(path) {
+                println!("❌ Failed to load session: {}", e);
+            }
+        } else if let Some(path) = &options.file {
+            println!("🎶 Loading audio file: {}", path.display());
+            match visualizer.load_audio_file(&path.to_string_lossy()) {
+                Ok(_) => println!("✅ Successfully loaded audio file"),
+                Err(e) => println!("❌ Failed to load audio file: {}", e),
+            }
+        }
+
+        Ok((visualizer, event_loop))
+    }
+
+    /// Start configuring a visualizer
+    pub fn builder() -> AudioVisualizerBuilder {
+        AudioVisualizerBuilder::new()
+    }
+
+    pub fn current_shader(&self) -> ShaderType {
+        self.frame_composer.current_shader()
+    }
+
+    pub fn safety_level(&self) -> SafetyLevel {
+        self.user_interface.get_safety_level()
+    }
+
+    /// Show a slow shader cycle on a low-intensity demo signal after `idle_after` of silence,
+    /// returning to live audio as soon as it's heard again
+    pub fn set_attract_mode(&mut self, enabled: bool, idle_after: Duration) {
+        self.attract_mode.set_enabled(enabled, idle_after);
+    }
+
+    pub fn is_attracting(&self) -> bool {
+        self.attract_mode.is_active()
+    }
+
+    /// Tint the visualization toward a color temperature; clamped to `WHITE_BALANCE_RANGE_KELVIN`
+    pub fn set_white_balance(&mut self, kelvin: f32) {
+        self.frame_composer.set_white_balance(kelvin);
+    }
+
+    /// The same tint on a -1.0 (cool) to 1.0 (warm) scale
+    pub fn set_color_temperature(&mut self, temperature: f32) {
+        self.frame_composer.set_color_temperature(temperature);
+    }
+
+    /// Click on every detected beat through the audio output, louder on downbeats
+    pub fn set_metronome(&mut self, enabled: bool) {
+        self.rhythm_detector.set_click_enabled(enabled);
+        println!("🥁 Metronome click: {}", if enabled { "on" } else { "off" });
+    }
+
+    pub fn is_metronome_enabled(&self) -> bool {
+        self.rhythm_detector.is_click_enabled()
+    }
+
+    /// Scale beat detection for soft acoustic onsets (above 1.0) or busy, distorted mixes (below 1.0)
+    pub fn set_onset_sensitivity(&mut self, sensitivity: f32) {
+        self.rhythm_detector.set_onset_sensitivity(sensitivity);
+        println!("🥁 Onset sensitivity: {:.2}", self.rhythm_detector.onset_sensitivity());
+    }
+
+    pub fn onset_sensitivity(&self) -> f32 {
+        self.rhythm_detector.onset_sensitivity()
+    }
+
+    /// Hold automatic shader switches for the next downbeat so they land on phrase starts
+    pub fn set_transition_on_downbeat(&mut self, enabled: bool) {
+        self.frame_composer.set_transition_on_downbeat(enabled);
+        println!("🥁 Downbeat-quantized transitions: {}", if enabled { "on" } else { "off" });
+    }
+
+    /// How long emergency stop takes to fade to black (and back on resume)
+    pub fn set_emergency_fade_duration(&mut self, duration: Duration) {
+        self.frame_composer.set_emergency_fade_duration(duration);
+        println!("🛡️ Emergency fade: {}ms", self.frame_composer.emergency_fade_duration().as_millis());
+    }
+
     pub fn run(mut self, event_loop: EventLoop<()>) -> Result<()> {
         let mut last_render_time = Instant::now();
-        let target_fps = 60;
-        let frame_duration = std::time::Duration::from_millis(1000 / target_fps);
+        let frame_duration = Duration::from_secs_f64(1.0 / self.target_fps as f64);
 
         event_loop.run(move |event, elwt| { // ASSUMPTION: Keeping deprecated API for simplicity - requires major refactoring to fix
             match event {
@@ -67,14 +738,27 @@ impl AudioVisualizer {
                     match event {
                             WindowEvent::CloseRequested => {
                                 println!("👋 Closing Aruu Audio Visualizer");
+                                self.shutdown();
                                 elwt.exit();
                             }
                             WindowEvent::Resized(physical_size) => {
                                 self.wgpu_context.resize(*physical_size);
+                                if let Some(recorder) = &mut self.session_recorder {
+                                    recorder.record(SessionEvent::Resize(physical_size.width, physical_size.height));
+                                }
+                            }
+                            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                                // Moving to a display with another DPI changes the physical size
+                                println!("🖥️  Display scale factor: {:.2}", scale_factor);
+                                self.wgpu_context.resize(self.wgpu_context.window.inner_size());
+                            }
+                            WindowEvent::ModifiersChanged(modifiers) => {
+                                self.user_interface.set_modifiers(modifiers.state());
                             }
                             WindowEvent::RedrawRequested => {
                                 let now = Instant::now();
                                 if now.duration_since(last_render_time) >= frame_duration {
+                                    self.handle_remote_commands();
                                     match self.render_frame() {
                                         Ok(_) => last_render_time = now,
                                         Err(e) => eprintln!("Render error: {}", e),
@@ -89,9 +773,36 @@ impl AudioVisualizer {
                                             println!("{}", self.user_interface.get_status_text(&self.frame_composer));
                                         }
 
-                                        // Check for exit condition (double ESC press)
+                                        if self.user_interface.take_session_toggle() {
+                                            self.toggle_recording();
+                                        }
+
+                                        if let Some(offset) = self.user_interface.take_seek_request() {
+                                            if let Err(e) = self.audio_processor.seek_by(offset) {
+                                                println!("💡 Seek unavailable: {}", e);
+                                            }
+                                        }
+
+                                        if let Some(edit) = self.user_interface.take_loop_edit() {
+                                            let result = match edit {
+                                                LoopEdit::In => self.audio_processor.mark_loop_in().map(|_| ()),
+                                                LoopEdit::Out => self.audio_processor.mark_loop_out(),
+                                                LoopEdit::Clear => self.audio_processor.set_loop_region(None),
+                                            };
+                                            if let Err(e) = result {
+                                                println!("💡 Loop unavailable: {}", e);
+                                            }
+                                        }
+
+                                        if self.user_interface.take_fullscreen_toggle() {
+                                            let fullscreen = self.wgpu_context.toggle_fullscreen();
+                                            println!("🖥️  Fullscreen ({}): {}", self.wgpu_context.fullscreen_mode.name(), if fullscreen { "ON" } else { "OFF" });
+                                        }
+
+                                        // Check for exit condition (exit key pressed)
                                         if self.user_interface.should_exit() {
                                             println!("👋 Closing Aruu Audio Visualizer");
+                                            self.shutdown();
                                             elwt.exit();
                                         }
                                     }
@@ -140,39 +851,162 @@ impl AudioVisualizer {
         Ok(())
     }
 
+    /// Apply OSC commands received since the last frame (never blocks)
+    fn handle_remote_commands(&mut self) {
+        let Some(server) = &mut self.osc_server else {
+            return;
+        };
+        for command in server.poll() {
+            if let Err(e) = self.user_interface.apply_remote_command(command, &mut self.frame_composer, &self.wgpu_context) {
+                eprintln!("OSC command error: {}", e);
+            }
+        }
+    }
+
+    /// Reopen the live input device after a disconnect, backing off between failed attempts
+    fn recover_audio_device(&mut self) {
+        if self.audio_processor.is_device_healthy() {
+            return;
+        }
+        let now = Instant::now();
+        if !self.reconnect_backoff.ready(now) {
+            return;
+        }
+        match self.audio_processor.reconnect() {
+            Ok(()) => self.reconnect_backoff.reset(),
+            Err(e) => {
+                let wait = self.reconnect_backoff.failed(now);
+                eprintln!("⚠️  Audio input reconnect failed, retrying in {:.1}s: {}", wait.as_secs_f32(), e);
+            }
+        }
+    }
+
     fn render_frame(&mut self) -> Result<()> {
         let frame_start = Instant::now();
+        self.recover_audio_device();
 
-        // Process audio with enhanced features (includes AdvancedAudioAnalyzer internally)
-        let audio_features = self.audio_processor.process_frame()?;
+        // A replayed session supplies recorded features (and the switches around them) instead of live audio
+        let replaying = self.session_player.is_some() || self.feature_source.is_some();
+        let (audio_features, rhythm_features) = match self.next_replay_frame()?.or_else(|| self.next_source_features()) {
+            Some(features) => features,
+            None => {
+                // Process audio with enhanced features (includes AdvancedAudioAnalyzer internally)
+                let mut audio_features = self.audio_processor.process_frame()?;
 
-        let frequency_bins = vec![
-            audio_features.bass,
-            audio_features.mid,
-            audio_features.treble,
-            audio_features.overall_volume,
-        ];
+                // A new track starts tempo tracking from scratch
+                if self.track_changes.try_iter().count() > 0 {
+                    self.rhythm_detector.reset_tempo_lock();
+                }
+                // Audio on either side of a seek is unrelated, so onsets are measured afresh
+                if self.seeks.try_iter().count() > 0 {
+                    self.rhythm_detector.reset_onset_history();
+                }
 
-        // Enhanced rhythm analysis
-        let rhythm_features = self.rhythm_detector.process_frame(&frequency_bins);
+                // Files and reconnected devices can change the sample rate under us
+                if self.rhythm_detector.sample_rate() != self.audio_processor.sample_rate() {
+                    self.rhythm_detector.set_sample_rate(self.audio_processor.sample_rate());
+                }
 
+                let frequency_bins = vec![
+                    audio_features.bass,
+                    audio_features.mid,
+                    audio_features.treble,
+                    audio_features.overall_volume,
+                ];
 
-        // Auto-select shader based on audio characteristics if enabled
-        if self.user_interface.is_auto_shader_enabled() {
+                // Enhanced rhythm analysis
+                let mut rhythm_features = self.rhythm_detector.process_frame(&frequency_bins);
+                if let Some(bpm) = self.rhythm_detector.tempo_lock().filter(|_| rhythm_features.tempo_lock_changed) {
+                    println!("🥁 Tempo locked at {:.0} BPM", bpm);
+                }
+                if let Some(click) = self.rhythm_detector.take_click() {
+                    self.audio_processor.play_click(&click);
+                }
+
+                // MIDI clock and notes, when connected, stand in for detected tempo and onsets
+                let midi_events = self.midi_source.as_mut().map(|source| source.poll()).unwrap_or_default();
+                self.midi_sync.update(&midi_events);
+                self.midi_sync.apply(&mut audio_features, &mut rhythm_features);
+
+                // When idle, the attract demo signal stands in for silence
+                if self.attract_mode.update(&audio_features) {
+                    self.attract_mode.demo_frame()
+                } else {
+                    (audio_features, rhythm_features)
+                }
+            }
+        };
+
+        // Sustained silence fades to the idle pattern unless attract mode is filling the gap
+        let silent = !replaying && self.idle_pattern && !self.attract_mode.is_enabled() && self.audio_processor.is_silent();
+        self.frame_composer.set_idle(silent);
+
+        if !replaying && self.attract_mode.is_active() {
+            if self.attract_mode.take_shader_switch() {
+                self.frame_composer.next_shader(&self.wgpu_context)?;
+                self.user_interface.sync_shader_index(self.frame_composer.current_shader());
+            }
+        } else if !replaying && self.user_interface.is_auto_shader_enabled() {
+            // Auto-select shader based on audio characteristics if enabled (replays use recorded switches)
             self.frame_composer.auto_select_shader(&self.wgpu_context, &audio_features, &rhythm_features)?;
         }
 
+        if let Some(recorder) = &mut self.session_recorder {
+            recorder.record_state(
+                self.frame_composer.current_shader(),
+                self.user_interface.get_safety_level(),
+                self.user_interface.is_emergency_stopped(),
+            );
+            recorder.record_frame(&audio_features, &rhythm_features);
+        }
+        if self.feature_recorder.is_recording() {
+            let frame_index = self.frame_composer.frames_rendered();
+            if let Err(e) = self.feature_recorder.record(&audio_features, &rhythm_features, frame_index) {
+                eprintln!("Feature recording failed, stopping: {}", e);
+                let _ = self.feature_recorder.stop();
+            }
+        }
+
         // Render with enhanced composer and safety multipliers
+        self.user_interface.update_safety();
         let safety_multipliers = self.user_interface.get_safety_multipliers();
         let volume = self.audio_processor.get_volume();
+        self.frame_composer.set_transport_state(
+            !self.audio_processor.is_paused(),
+            self.audio_processor.playback_fraction(),
+        );
+        self.frame_composer.set_waveform(&self.wgpu_context, &self.audio_processor.recent_samples(WAVEFORM_SAMPLES));
+        self.frame_composer.push_spectrum(&self.wgpu_context, self.audio_processor.spectrum());
+        // Two extra FFTs, so only while the split Spectralizer can show them
+        if self.frame_composer.spectralizer_stereo_split() && self.frame_composer.current_shader() == ShaderType::Spectralizer {
+            let (left, right) = self.audio_processor.stereo_spectra();
+            self.frame_composer.set_stereo_spectrum(&self.wgpu_context, &left, &right);
+        }
         self.frame_composer.render(&self.wgpu_context, &audio_features, &rhythm_features, Some(safety_multipliers), volume)?;
 
+        // Live info in the window title, throttled to avoid per-frame window calls
+        if self.last_title_update.elapsed() >= TITLE_UPDATE_INTERVAL {
+            let title = Self::window_title(
+                &self.window_title,
+                self.frame_composer.current_shader().name(),
+                rhythm_features.estimated_bpm,
+                self.frame_composer.average_fps(),
+                self.audio_processor.current_track(),
+            );
+            self.wgpu_context.set_title(&title);
+            self.last_title_update = Instant::now();
+        }
+
+        if self.checkpoint_path.is_some() && self.last_checkpoint.elapsed() >= self.checkpoint_interval {
+            self.write_checkpoint();
+        }
+
         // Display performance overlay if enabled (console output)
         if let Some(performance_text) = self.user_interface.get_performance_overlay(&self.frame_composer) {
             static mut FRAME_COUNTER: u32 = 0;
             unsafe {
                 FRAME_COUNTER += 1;
-                if FRAME_COUNTER % 60 == 0 { // Display every 60 frames (once per second at 60fps)
+                if FRAME_COUNTER % self.target_fps == 0 { // About once per second at the target rate
                     println!("{}", performance_text);
                 }
             }
@@ -182,10 +1016,212 @@ impl AudioVisualizer {
     }
 
 
+    /// Compose the window title from live shader, tempo and frame-rate info, plus the
+    /// playing file's name during file playback
+    pub fn window_title(base: &str, shader_name: &str, bpm: f32, fps: f32, track: Option<&Path>) -> String {
+        let mut title = format!("{} - {} | {:.0} BPM | {:.0} FPS", base, shader_name, bpm, fps);
+        if let Some(name) = track.and_then(Path::file_name) {
+            title.push_str(&format!(" | {}", name.to_string_lossy()));
+        }
+        title
+    }
+
     pub fn load_audio_file(&mut self, file_path: &str) -> Result<()> {
         self.audio_processor.play_from_file(file_path)
     }
 
+    /// Play a list of files back to back; track changes reset tempo tracking
+    pub fn load_playlist(&mut self, playlist: Playlist) -> Result<()> {
+        self.audio_processor.play_playlist(playlist)
+    }
+
+    /// Start recording features and control events for a reproducible bug report
+    pub fn start_recording(&mut self) {
+        self.session_recorder = Some(SessionRecorder::new());
+        println!("⏺️  Session recording started");
+    }
+
+    /// Stop recording and write the session file
+    pub fn stop_recording<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<()> {
+        let recorder = self.session_recorder.take().ok_or_else(|| anyhow!("No session is being recorded"))?;
+        recorder.save(&path)?;
+        println!("💾 Session saved to {} ({} frames)", path.as_ref().display(), recorder.session().frame_count());
+        Ok(())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.session_recorder.is_some()
+    }
+
+    /// Re-drive the visualizer from a recorded session file, one recorded frame per rendered frame
+    pub fn replay_session<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<()> {
+        let player = SessionPlayer::load(&path)?;
+        println!("⏯️  Replaying session {}", path.as_ref().display());
+        self.session_player = Some(player);
+        Ok(())
+    }
+
+    pub fn is_replaying(&self) -> bool {
+        self.session_player.is_some() || self.feature_source.is_some()
+    }
+
+    /// Write each frame's analyzed features to an NDJSON timeline at `path`
+    pub fn start_feature_recording<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<()> {
+        self.feature_recorder.start(path)
+    }
+
+    /// Close the feature timeline; returns the number of frames written
+    pub fn stop_feature_recording(&mut self) -> Result<u64> {
+        self.feature_recorder.stop()
+    }
+
+    /// Drive the visualizer from a recorded feature timeline instead of live audio
+    pub fn replay_features<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<()> {
+        let playback = FeaturePlayback::load(&path)?;
+        println!("⏯️  Replaying {} feature frames from {}", playback.remaining(), path.as_ref().display());
+        self.set_feature_source(Some(Box::new(playback)));
+        Ok(())
+    }
+
+    /// Replace live analysis with another feature source (None = back to live audio)
+    pub fn set_feature_source(&mut self, source: Option<Box<dyn FeatureSource>>) {
+        self.feature_source = source;
+    }
+
+    fn toggle_recording(&mut self) {
+        if !self.is_recording() {
+            self.start_recording();
+            return;
+        }
+
+        if let Err(e) = self.stop_recording(Self::session_file_name()) {
+            eprintln!("Failed to save session: {}", e);
+        }
+    }
+
+    /// Timestamped file name for a session saved without an explicit path
+    fn session_file_name() -> String {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        format!("aruu-session-{}.txt", timestamp)
+    }
+
+    /// Snapshot of the tuned visual setup, as written to checkpoints
+    pub fn session_state(&self) -> SessionState {
+        SessionState {
+            volume: self.audio_processor.get_volume(),
+            ..self.user_interface.session_state(&self.frame_composer)
+        }
+    }
+
+    /// Restore the setup from the checkpoint file, e.g. after a crash
+    pub fn resume_session_state(&mut self) -> Result<()> {
+        let path = self.checkpoint_path.clone().unwrap_or_else(|| DEFAULT_SESSION_STATE_FILE.into());
+        let state = SessionState::load_from(&path)?;
+        self.user_interface.restore_session_state(&state, &mut self.frame_composer, &self.wgpu_context)?;
+        self.audio_processor.set_volume(state.volume);
+        println!("♻️  Resumed {} from {}", state.shader.name(), path.display());
+        Ok(())
+    }
+
+    /// Save a checkpoint now; failures are logged, never fatal
+    fn write_checkpoint(&mut self) {
+        self.last_checkpoint = Instant::now();
+        if let Some(path) = &self.checkpoint_path {
+            if let Err(e) = self.session_state().save_to(path) {
+                eprintln!("Failed to write checkpoint: {:#}", e);
+            }
+        }
+    }
+
+    /// Stop audio, save any open session recording, wait for queued GPU work and log a summary
+    ///
+    /// Call before the event loop ends for a deterministic teardown; later calls (including
+    /// the one from `Drop`) do nothing.
+    pub fn shutdown(&mut self) {
+        if self.shut_down {
+            return;
+        }
+        self.shut_down = true;
+
+        self.write_checkpoint(); // Before audio stops, while the volume is still live
+        let audio_released = self.audio_processor.shutdown();
+
+        let recording = if self.is_recording() {
+            let path = Self::session_file_name();
+            match self.stop_recording(&path) {
+                Ok(()) => Some(path),
+                Err(e) => {
+                    eprintln!("Failed to save session: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        self.session_player = None;
+        self.feature_source = None;
+        if self.feature_recorder.is_recording() {
+            if let Err(e) = self.feature_recorder.stop() {
+                eprintln!("Failed to save feature timeline: {}", e);
+            }
+        }
+
+        self.wgpu_context.device.poll(wgpu::Maintain::Wait);
+
+        println!(
+            "🛑 Audio Visualizer shut down: {} frames rendered, audio {}, {}",
+            self.frame_composer.frames_rendered(),
+            if audio_released { "stopped" } else { "already idle" },
+            recording.map_or_else(|| "no recording open".to_string(), |path| format!("recording saved to {}", path)),
+        );
+    }
+
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down
+    }
+
+    /// Next frame from the feature source, dropping back to live audio once it runs out
+    fn next_source_features(&mut self) -> Option<(AudioFeatures, RhythmFeatures)> {
+        let features = self.feature_source.as_mut()?.next_features();
+        if features.is_none() {
+            println!("⏹️  Feature replay finished - back to live audio");
+            self.feature_source = None;
+        }
+        features
+    }
+
+    /// Apply the next replayed frame's control events and return its features
+    fn next_replay_frame(&mut self) -> Result<Option<(AudioFeatures, RhythmFeatures)>> {
+        let Some(player) = &mut self.session_player else {
+            return Ok(None);
+        };
+        let Some(frame) = player.next_frame() else {
+            println!("⏹️  Session replay finished - back to live audio");
+            self.session_player = None;
+            return Ok(None);
+        };
+
+        for event in frame.events {
+            match event {
+                SessionEvent::ShaderSwitch(shader) => {
+                    self.frame_composer.set_shader_immediately(shader, &self.wgpu_context)?;
+                    self.user_interface.sync_shader_index(shader);
+                }
+                SessionEvent::Resize(width, height) => {
+                    let _ = self.wgpu_context.window.request_inner_size(winit::dpi::PhysicalSize::new(width, height));
+                }
+                SessionEvent::SafetyLevel(level) => self.user_interface.set_safety_level(level),
+                SessionEvent::EmergencyStop => self.user_interface.emergency_stop(),
+                SessionEvent::Resume => self.user_interface.resume_from_emergency(),
+                SessionEvent::Frame { .. } => {}
+            }
+        }
+
+        Ok(Some((frame.audio, frame.rhythm)))
+    }
+
     /// Handle overlay events from the GUI system
     fn handle_overlay_event(&mut self, event: crate::rendering::OverlayEvent) -> Result<()> {
         use crate::rendering::OverlayEvent;
@@ -208,18 +1244,16 @@ impl AudioVisualizer {
                 Ok(())
             }
             OverlayEvent::PreviousTrack => {
-                println!("⏮️ Previous shader (simulating previous track)");
-                // Use shader cycling as track navigation simulation
-                if let Err(e) = self.frame_composer.next_shader(&self.wgpu_context) {
-                    eprintln!("Error switching shader: {}", e);
+                println!("⏮️ Previous track");
+                if let Err(e) = self.audio_processor.previous_track() {
+                    println!("💡 Previous track unavailable: {}", e);
                 }
                 Ok(())
             }
             OverlayEvent::NextTrack => {
-                println!("⏭️ Next shader (simulating next track)");
-                // Use shader cycling as track navigation simulation
-                if let Err(e) = self.frame_composer.next_shader(&self.wgpu_context) {
-                    eprintln!("Error switching shader: {}", e);
+                println!("⏭️ Next track");
+                if let Err(e) = self.audio_processor.next_track() {
+                    println!("💡 Next track unavailable: {}", e);
                 }
                 Ok(())
             }
@@ -229,6 +1263,22 @@ impl AudioVisualizer {
                 println!("{}", self.user_interface.get_status_text(&self.frame_composer));
                 Ok(())
             }
+            OverlayEvent::PlayPause => {
+                if self.audio_processor.is_paused() {
+                    println!("▶️ Resume playback");
+                    self.audio_processor.resume();
+                } else {
+                    println!("⏸️ Pause playback");
+                    self.audio_processor.pause();
+                }
+                Ok(())
+            }
+            OverlayEvent::Seek(fraction) => {
+                if let Err(e) = self.audio_processor.seek_fraction(fraction) {
+                    println!("💡 Seek unavailable: {}", e);
+                }
+                Ok(())
+            }
             OverlayEvent::EmergencyStop => {
                 println!("🚨 Emergency stop activated!");
                 self.user_interface.emergency_stop();
@@ -239,9 +1289,49 @@ impl AudioVisualizer {
     }
 }
 
+impl HeadlessVisualizer {
+    pub fn current_shader(&self) -> ShaderType {
+        self.renderer.current_shader()
+    }
+
+    pub fn safety_level(&self) -> SafetyLevel {
+        self.user_interface.get_safety_level()
+    }
+
+    /// Renderer for the output size, frame count and look settings
+    pub fn renderer_mut(&mut self) -> &mut HeadlessRenderer {
+        &mut self.renderer
+    }
+
+    /// Play a file and visualize it instead of live input
+    pub fn load_audio_file(&mut self, file_path: &str) -> Result<()> {
+        self.audio_processor.play_from_file(file_path)
+    }
+
+    /// Analyze the next audio frame and render it at the configured safety level,
+    /// returning its pixels as tightly packed RGBA8 rows, top row first
+    pub fn render_frame(&mut self) -> Result<Vec<u8>> {
+        let audio_features = self.audio_processor.process_frame()?;
+        if self.rhythm_detector.sample_rate() != self.audio_processor.sample_rate() {
+            self.rhythm_detector.set_sample_rate(self.audio_processor.sample_rate());
+        }
+        let frequency_bins = vec![
+            audio_features.bass,
+            audio_features.mid,
+            audio_features.treble,
+            audio_features.overall_volume,
+        ];
+        let rhythm_features = self.rhythm_detector.process_frame(&frequency_bins);
+
+        self.user_interface.update_safety();
+        self.renderer.set_safety_multipliers(Some(self.user_interface.get_safety_multipliers()));
+        self.renderer.render_to_buffer(&audio_features, &rhythm_features)
+    }
+}
+
 impl Drop for AudioVisualizer {
     fn drop(&mut self) {
-        println!("🛑 Audio Visualizer shutting down");
+        self.shutdown();
     }
 }
 
@@ -254,7 +1344,7 @@ mod tests {
     fn test_audio_processing_pipeline() {
         // Use default processor for testing (no audio device required)
         let mut audio_processor = AudioProcessor::new_default();
-        let mut rhythm_detector = RhythmDetector::new(44100.0);
+        let mut rhythm_detector = RhythmDetector::new(audio_processor.sample_rate());
 
         // Process a frame to ensure pipeline works
         let audio_features = audio_processor.process_frame().expect("Audio processing should work");
@@ -288,7 +1378,7 @@ mod tests {
     #[test]
     fn test_multiple_frame_processing() {
         let mut audio_processor = AudioProcessor::new_default();
-        let mut rhythm_detector = RhythmDetector::new(44100.0);
+        let mut rhythm_detector = RhythmDetector::new(audio_processor.sample_rate());
 
         // Process multiple frames to ensure stability
         for _ in 0..10 {
@@ -364,6 +1454,173 @@ mod tests {
         assert!(audio_features.overall_volume >= 0.0 && audio_features.overall_volume <= 1.0);
     }
 
+    fn headless_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+        pollster::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+            let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await?;
+            adapter.request_device(&wgpu::DeviceDescriptor::default(), None).await.ok()
+        })
+    }
+
+    #[test]
+    fn test_builder_applies_safety_level_and_shader() {
+        let builder = AudioVisualizer::builder()
+            .safety_level(SafetyLevel::UltraSafe)
+            .initial_shader(ShaderType::Fractal)
+            .auto_shader(false)
+            .target_fps(30)
+            .audio_input(false)
+            .headless_size(64, 36);
+
+        assert_eq!(builder.get_initial_shader(), ShaderType::Fractal);
+        assert_eq!(builder.get_target_fps(), 30);
+
+        let user_interface = builder.build_user_interface();
+        assert_eq!(user_interface.get_safety_level(), SafetyLevel::UltraSafe);
+        assert_eq!(user_interface.get_safety_engine().get_safety_level(), SafetyLevel::UltraSafe);
+        assert!(!user_interface.is_auto_shader_enabled());
+        assert_eq!(user_interface.current_shader_index(), 6); // Fractal
+
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping headless visualizer test: no GPU adapter available");
+            return;
+        };
+        let mut visualizer = builder.build_headless_with_device(device, queue).expect("Headless visualizer should build");
+        assert_eq!(visualizer.safety_level(), SafetyLevel::UltraSafe);
+        assert_eq!(visualizer.current_shader(), ShaderType::Fractal);
+
+        let pixels = visualizer.render_frame().unwrap();
+        assert_eq!(pixels.len(), 64 * 36 * 4);
+        assert_eq!(visualizer.renderer_mut().frames_rendered(), 1);
+    }
+
+    #[test]
+    fn test_builder_sets_emergency_stop_and_exit_keys() {
+        let user_interface = AudioVisualizer::builder()
+            .emergency_stop_key(KeyCode::KeyB)
+            .exit_key(None)
+            .build_user_interface();
+        assert_eq!(user_interface.emergency_stop_key(), KeyCode::KeyB);
+        assert_eq!(user_interface.exit_key(), None);
+    }
+
+    #[test]
+    fn test_builder_sets_auto_resume() {
+        let user_interface = AudioVisualizer::builder()
+            .auto_resume(Some(Duration::from_secs(30)))
+            .build_user_interface();
+        assert_eq!(user_interface.get_safety_engine().auto_resume(), Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_builder_enables_metronome_clicks() {
+        let (_, rhythm_detector) = AudioVisualizer::builder().audio_input(false).build_analysis();
+        assert!(!rhythm_detector.is_click_enabled());
+
+        let (_, rhythm_detector) = AudioVisualizer::builder().audio_input(false).metronome(true).build_analysis();
+        assert!(rhythm_detector.is_click_enabled());
+    }
+
+    #[test]
+    fn test_builder_applies_latency_offset() {
+        let (audio_processor, _) = AudioVisualizer::builder().audio_input(false).latency_offset(-40).build_analysis();
+        assert_eq!(audio_processor.latency_offset_ms(), -40);
+    }
+
+    #[test]
+    fn test_builder_selects_gpu_fft() {
+        let (audio_processor, _) = AudioVisualizer::builder().audio_input(false).build_analysis();
+        assert_eq!(audio_processor.fft_backend(), FftBackend::Cpu);
+
+        let (audio_processor, _) = AudioVisualizer::builder().audio_input(false).gpu_fft(true).build_analysis();
+        if headless_device().is_none() {
+            println!("Skipping GPU FFT builder check: no GPU adapter available");
+            return;
+        }
+        assert_eq!(audio_processor.fft_backend(), FftBackend::Gpu);
+    }
+
+    #[test]
+    fn test_builder_sets_key_bindings() {
+        let user_interface = AudioVisualizer::builder()
+            .key_bindings(KeyBindings::parse("KeyJ = CycleNext").unwrap())
+            .build_user_interface();
+        assert_eq!(user_interface.key_bindings().action_for(KeyCode::KeyJ), Some(crate::control::Action::CycleNext));
+    }
+
+    #[test]
+    fn test_builder_loads_preset_file() {
+        // A missing file just means no presets have been saved yet
+        let missing = std::env::temp_dir().join("aruu-missing-presets.txt");
+        let user_interface = AudioVisualizer::builder().preset_file(Some(missing)).build_user_interface();
+        assert!(user_interface.preset_bank().is_empty());
+
+        let path = std::env::temp_dir().join("aruu-builder-presets.txt");
+        let mut bank = crate::control::PresetBank::new();
+        bank.insert("Slot 1", crate::control::Preset { shader: ShaderType::Tunnel, ..Default::default() });
+        bank.save(&path).unwrap();
+
+        let user_interface = AudioVisualizer::builder().preset_file(Some(path.clone())).build_user_interface();
+        assert_eq!(user_interface.preset_bank().get("Slot 1").map(|preset| preset.shader), Some(ShaderType::Tunnel));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_builder_applies_stereo_split() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping headless visualizer test: no GPU adapter available");
+            return;
+        };
+        let mut visualizer = AudioVisualizer::builder()
+            .audio_input(false)
+            .headless_size(16, 16)
+            .stereo_split(true)
+            .build_headless_with_device(device, queue)
+            .expect("Headless visualizer should build");
+        assert!(visualizer.renderer_mut().shader_system_mut().spectralizer_stereo_split());
+    }
+
+    #[test]
+    fn test_builder_applies_shader_recommendations() {
+        let Some((device, queue)) = headless_device() else {
+            println!("Skipping headless visualizer test: no GPU adapter available");
+            return;
+        };
+        let mut visualizer = AudioVisualizer::builder()
+            .audio_input(false)
+            .headless_size(16, 16)
+            .shader_recommendations(false)
+            .build_headless_with_device(device, queue)
+            .expect("Headless visualizer should build");
+        assert!(!visualizer.renderer_mut().shader_system_mut().shader_recommendations());
+    }
+
+    #[test]
+    fn test_checkpoint_launch_options() {
+        // Off by default, so running from any directory leaves no state files behind
+        let builder = AudioVisualizer::builder().launch_options(&LaunchOptions::default());
+        assert_eq!(builder.checkpoint_path, None);
+
+        let options = LaunchOptions::parse(["--checkpoint", "5"]).unwrap();
+        let builder = AudioVisualizer::builder().launch_options(&options);
+        assert_eq!(builder.checkpoint_interval, Duration::from_secs(5));
+        assert_eq!(builder.checkpoint_path.as_deref(), Some(Path::new(DEFAULT_SESSION_STATE_FILE)));
+
+        let options = LaunchOptions::parse(["--checkpoint", "0"]).unwrap();
+        assert_eq!(AudioVisualizer::builder().launch_options(&options).checkpoint_path, None);
+    }
+
+    #[test]
+    fn test_window_title_builder() {
+        let title = AudioVisualizer::window_title(DEFAULT_WINDOW_TITLE, "Plasma", 127.6, 59.94, None);
+        assert_eq!(title, "Aruu Audio Visualizer - Plasma | 128 BPM | 60 FPS");
+
+        // File playback names the track, without its directory
+        let title = AudioVisualizer::window_title("Stream", "Tunnel", 90.2, 144.0, Some(Path::new("music/Night Drive.flac")));
+        assert_eq!(title, "Stream - Tunnel | 90 BPM | 144 FPS | Night Drive.flac");
+    }
+
     #[test]
     fn test_rhythm_features_validity() {
         let mut rhythm_detector = RhythmDetector::new(44100.0);